@@ -0,0 +1,20 @@
+use kal_core::infrastructure::api::ApiServer;
+use sqlx::SqlitePool;
+use tokio::net::TcpListener;
+
+use super::{local_actor, publisher, CliResult};
+
+pub async fn run(bind: String, trust_user_header: bool, pool: SqlitePool) -> CliResult {
+    let listener = TcpListener::bind(&bind).await?;
+    println!("Serving the JSON API on http://{bind}/");
+
+    // Requests that do not name a trusted user are attributed to this one
+    let publisher = publisher(&pool, local_actor())?;
+    let mut server = ApiServer::new(pool).with_publisher(publisher);
+    if trust_user_header {
+        server = server.trust_actor_header();
+    }
+    server.serve(listener).await?;
+
+    Ok(())
+}
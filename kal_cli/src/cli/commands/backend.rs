@@ -0,0 +1,55 @@
+use std::path::PathBuf;
+
+use kal_core::infrastructure::rpc::{RpcClient, RpcDispatcher};
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+
+use super::{local_actor, publisher, CliResult};
+
+/// Where commands are executed: in a running daemon, or in-process
+/// against the database when no daemon is listening.
+pub enum Backend {
+    Remote(RpcClient),
+    Local(RpcDispatcher),
+}
+
+impl Backend {
+    pub async fn open() -> CliResult<Self> {
+        if let Ok(client) = RpcClient::connect(socket_path()?).await {
+            return Ok(Backend::Remote(client));
+        }
+
+        let pool = crate::connect().await?;
+        let publisher = publisher(&pool, local_actor())?;
+        Ok(Backend::Local(RpcDispatcher::new(pool).with_publisher(publisher)))
+    }
+
+    pub async fn call(&mut self, method: &str, params: Value) -> CliResult<Value> {
+        let result = match self {
+            Backend::Remote(client) => client.call(method, params).await?,
+            Backend::Local(dispatcher) => dispatcher.call(method, params).await?,
+        };
+
+        Ok(result)
+    }
+
+    pub async fn call_as<T: DeserializeOwned>(&mut self, method: &str, params: Value) -> CliResult<T> {
+        Ok(serde_json::from_value(self.call(method, params).await?)?)
+    }
+}
+
+/// `$KAL_SOCKET`, or `kal.sock` in the runtime directory (falling back
+/// to the data directory).
+pub fn socket_path() -> CliResult<PathBuf> {
+    if let Some(path) = std::env::var_os("KAL_SOCKET") {
+        return Ok(PathBuf::from(path));
+    }
+
+    let dir = dirs::runtime_dir()
+        .or_else(dirs::data_dir)
+        .ok_or("no runtime directory for this platform")?
+        .join("kal");
+    std::fs::create_dir_all(&dir)?;
+
+    Ok(dir.join("kal.sock"))
+}
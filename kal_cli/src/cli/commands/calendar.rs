@@ -0,0 +1,217 @@
+use chrono::Utc;
+use clap::Subcommand;
+use kal_core::{
+    domain::value_objects::CalendarId,
+    infrastructure::{
+        dto::{AuditEntryDto, BusyPeriodDto, CalendarDto, CreatedDto},
+        persistence::{SqliteCalendarRepository, SqliteSubscriptionRepository},
+        webcal::{RefreshOutcome, SubscriptionRefresher, WebcalClient, WebcalError},
+    },
+};
+use serde_json::json;
+use sqlx::SqlitePool;
+
+use super::{backend::Backend, parse_datetime, CliResult};
+use crate::cli::output;
+
+#[derive(Subcommand)]
+pub enum CalendarCommands {
+    /// Create a new calendar
+    Create {
+        #[arg(short, long)]
+        name: String,
+
+        #[arg(short, long)]
+        description: Option<String>,
+    },
+
+    /// Subscribe to a remote ICS feed
+    Subscribe {
+        #[arg(short, long)]
+        name: String,
+
+        #[arg(short, long)]
+        url: String,
+
+        /// Refresh interval in minutes
+        #[arg(short, long, default_value_t = 60)]
+        refresh: i64,
+    },
+
+    /// Fetch a subscribed calendar's feed now, or every feed that is due
+    Refresh {
+        #[arg(short, long)]
+        calendar_id: Option<String>,
+    },
+
+    /// List active calendars
+    List,
+
+    /// Rename a calendar
+    Rename {
+        #[arg(short, long)]
+        calendar_id: String,
+
+        #[arg(short, long)]
+        name: String,
+    },
+
+    /// Update a calendar's description
+    Describe {
+        #[arg(short, long)]
+        calendar_id: String,
+
+        #[arg(short, long)]
+        description: Option<String>,
+    },
+
+    /// Archive a calendar
+    Archive {
+        #[arg(short, long)]
+        calendar_id: String,
+    },
+
+    /// Unarchive a calendar
+    Unarchive {
+        #[arg(short, long)]
+        calendar_id: String,
+    },
+
+    /// Delete a calendar
+    Delete {
+        #[arg(short, long)]
+        calendar_id: String,
+    },
+
+    /// Show when a calendar is busy; transparent events are left out
+    FreeBusy {
+        #[arg(short, long)]
+        calendar_id: String,
+
+        #[arg(long)]
+        from: String,
+
+        #[arg(long)]
+        to: String,
+    },
+
+    /// Show who changed a calendar or anything in it, most recent first
+    Audit {
+        #[arg(short, long)]
+        calendar_id: String,
+
+        #[arg(short, long, default_value_t = 20)]
+        limit: u32,
+    },
+}
+
+pub async fn run(action: CalendarCommands, mut backend: Backend) -> CliResult {
+    match action {
+        CalendarCommands::Create { name, description } => {
+            let created: CreatedDto = backend
+                .call_as("calendar.create", json!({ "name": name, "description": description }))
+                .await?;
+            output::success(&format!("Created calendar {}", created.id));
+        }
+        CalendarCommands::Subscribe { name, url, refresh } => {
+            let params = json!({ "name": name, "url": url, "refresh_minutes": refresh });
+            let created: CreatedDto = backend.call_as("calendar.subscribe", params).await?;
+            output::success(&format!("Subscribed calendar {}", created.id));
+        }
+        CalendarCommands::Refresh { calendar_id } => {
+            // Feeds are fetched here rather than in a daemon call, which
+            // would hold the database for as long as the download takes
+            let pool = crate::connect().await?;
+            let results = match calendar_id {
+                Some(id) => {
+                    let id = id.parse::<CalendarId>()?;
+                    vec![(id, refresher(&pool).refresh(&id, Utc::now()).await)]
+                }
+                None => refresh_due(&pool).await?,
+            };
+
+            for (id, outcome) in results {
+                match outcome {
+                    Ok(RefreshOutcome::NotModified) => {
+                        output::success(&format!("Calendar {id} is up to date"));
+                    }
+                    Ok(RefreshOutcome::Replaced { objects, skipped }) => {
+                        output::success(&format!("Refreshed calendar {id}: {objects} item(s)"));
+                        for (uid, reason) in skipped {
+                            output::warning(&format!("skipped {uid}: {reason}"));
+                        }
+                    }
+                    Err(e) => output::warning(&format!("could not refresh calendar {id}: {e}")),
+                }
+            }
+        }
+        CalendarCommands::List => {
+            let calendars: Vec<CalendarDto> = backend.call_as("calendar.list", json!({})).await?;
+            output::calendars(&calendars);
+        }
+        CalendarCommands::FreeBusy { calendar_id, from, to } => {
+            let params = json!({
+                "id": calendar_id.parse::<CalendarId>()?.to_string(),
+                "from": parse_datetime(&from)?,
+                "to": parse_datetime(&to)?,
+            });
+            let periods: Vec<BusyPeriodDto> = backend.call_as("calendar.free_busy", params).await?;
+            output::busy_periods(&periods);
+        }
+        CalendarCommands::Rename { calendar_id, name } => {
+            let id = calendar_id.parse::<CalendarId>()?;
+            backend
+                .call("calendar.update", json!({ "id": id.to_string(), "name": name }))
+                .await?;
+            output::success("Calendar renamed");
+        }
+        CalendarCommands::Describe { calendar_id, description } => {
+            let id = calendar_id.parse::<CalendarId>()?;
+            backend
+                .call("calendar.update", json!({ "id": id.to_string(), "description": description }))
+                .await?;
+            output::success("Calendar description updated");
+        }
+        CalendarCommands::Archive { calendar_id } => {
+            let id = calendar_id.parse::<CalendarId>()?;
+            backend.call("calendar.archive", json!({ "id": id.to_string() })).await?;
+            output::success("Calendar archived");
+        }
+        CalendarCommands::Unarchive { calendar_id } => {
+            let id = calendar_id.parse::<CalendarId>()?;
+            backend.call("calendar.unarchive", json!({ "id": id.to_string() })).await?;
+            output::success("Calendar unarchived");
+        }
+        CalendarCommands::Delete { calendar_id } => {
+            let id = calendar_id.parse::<CalendarId>()?;
+            backend.call("calendar.delete", json!({ "id": id.to_string() })).await?;
+            output::success("Calendar deleted");
+        }
+        CalendarCommands::Audit { calendar_id, limit } => {
+            let id = calendar_id.parse::<CalendarId>()?;
+            let entries: Vec<AuditEntryDto> = backend
+                .call_as("calendar.audit", json!({ "id": id.to_string(), "limit": limit }))
+                .await?;
+            output::audit(&entries);
+        }
+    }
+
+    Ok(())
+}
+
+fn refresher(
+    pool: &SqlitePool,
+) -> SubscriptionRefresher<SqliteCalendarRepository, SqliteSubscriptionRepository> {
+    SubscriptionRefresher::new(
+        WebcalClient::new(),
+        SqliteCalendarRepository::new(pool.clone()),
+        SqliteSubscriptionRepository::new(pool.clone()),
+    )
+}
+
+/// Fetches every subscribed calendar whose refresh interval has elapsed.
+pub async fn refresh_due(
+    pool: &SqlitePool,
+) -> CliResult<Vec<(CalendarId, Result<RefreshOutcome, WebcalError>)>> {
+    Ok(refresher(pool).refresh_due(Utc::now()).await?)
+}
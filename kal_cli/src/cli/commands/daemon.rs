@@ -0,0 +1,99 @@
+use std::time::Duration;
+
+use kal_core::{
+    application::scope::CommandScope,
+    infrastructure::rpc::{RpcClient, RpcServer},
+};
+use sqlx::SqlitePool;
+use tokio::{
+    net::UnixListener,
+    signal::unix::{signal, SignalKind},
+};
+
+use super::{
+    backend::socket_path, calendar::refresh_due, local_actor, publisher, trash::purge_expired,
+    CliResult,
+};
+use crate::cli::output;
+
+/// How often a running daemon purges expired trash.
+const TRASH_PURGE_INTERVAL: Duration = Duration::from_secs(60 * 60);
+
+/// How often a running daemon looks for subscriptions due a refresh.
+const SUBSCRIPTION_CHECK_INTERVAL: Duration = Duration::from_secs(5 * 60);
+
+pub async fn run(pool: SqlitePool) -> CliResult {
+    let path = socket_path()?;
+
+    if path.exists() {
+        if RpcClient::connect(&path).await.is_ok() {
+            return Err(format!("a daemon is already listening on {}", path.display()).into());
+        }
+        // Left behind by a daemon that did not shut down cleanly
+        std::fs::remove_file(&path)?;
+    }
+
+    let listener = UnixListener::bind(&path)?;
+    println!("Listening on {}", path.display());
+
+    // Calls are attributed to the user who connected; the daemon's own
+    // purges, and calls from a peer it cannot identify, to its user
+    let publisher = publisher(&pool, local_actor())?;
+    let server = RpcServer::new(pool.clone()).with_publisher(publisher.clone());
+    let scope = CommandScope::new().with_publisher(publisher);
+
+    let result = tokio::select! {
+        result = server.serve(listener) => result,
+        result = purge_trash(&pool, &scope) => result,
+        result = refresh_subscriptions(&pool) => result,
+        result = shutdown() => result,
+    };
+
+    std::fs::remove_file(&path)?;
+    result?;
+
+    Ok(())
+}
+
+/// Purges expired trash on startup and every hour after; a failed purge
+/// is retried on the next tick rather than stopping the daemon.
+async fn purge_trash(pool: &SqlitePool, scope: &CommandScope) -> std::io::Result<()> {
+    let mut ticks = tokio::time::interval(TRASH_PURGE_INTERVAL);
+
+    loop {
+        ticks.tick().await;
+        if let Err(e) = purge_expired(pool, scope).await {
+            output::warning(&format!("could not purge the trash: {e}"));
+        }
+    }
+}
+
+/// Refreshes subscribed calendars as their refresh intervals elapse; a
+/// feed that cannot be fetched is tried again on the next tick.
+async fn refresh_subscriptions(pool: &SqlitePool) -> std::io::Result<()> {
+    let mut ticks = tokio::time::interval(SUBSCRIPTION_CHECK_INTERVAL);
+
+    loop {
+        ticks.tick().await;
+        match refresh_due(pool).await {
+            Ok(results) => {
+                for (id, outcome) in results {
+                    if let Err(e) = outcome {
+                        output::warning(&format!("could not refresh calendar {id}: {e}"));
+                    }
+                }
+            }
+            Err(e) => output::warning(&format!("could not refresh subscriptions: {e}")),
+        }
+    }
+}
+
+/// Resolves on Ctrl-C or SIGTERM so the socket file gets cleaned up.
+async fn shutdown() -> std::io::Result<()> {
+    let mut terminate = signal(SignalKind::terminate())?;
+
+    tokio::select! {
+        result = tokio::signal::ctrl_c() => result,
+        _ = terminate.recv() => Ok(()),
+    }
+}
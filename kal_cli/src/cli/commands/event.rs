@@ -0,0 +1,161 @@
+use kal_core::{
+    domain::value_objects::{CalendarId, EventId, ReminderId},
+    infrastructure::dto::{AuditEntryDto, CreatedDto, ReminderStateDto},
+};
+use serde_json::json;
+
+use super::{
+    add_place, add_status, attendee_params, backend::Backend, is_date_only, parse_datetime,
+    reminder_params, snoozed_message, CliResult,
+};
+use crate::cli::{output, EventCommands};
+
+pub async fn run(action: EventCommands, mut backend: Backend) -> CliResult {
+    match action {
+        EventCommands::Create { calendar_id, title, description, start, end, color, place, availability, tags } => {
+            let color = match color {
+                Some(color) => color.parse::<u8>()?,
+                None => 0,
+            };
+
+            let mut params = json!({
+                "calendar_id": calendar_id.parse::<CalendarId>()?.to_string(),
+                "title": title,
+                "description": description,
+                "starts_at": parse_datetime(&start)?,
+                "ends_at": parse_datetime(&end)?,
+                "color": color,
+                "is_all_day": is_date_only(&start) && is_date_only(&end),
+                "tags": tags,
+            });
+            add_place(&mut params, place)?;
+            add_status(&mut params, availability);
+            let created: CreatedDto = backend.call_as("event.create", params).await?;
+            output::success(&format!("Created event {}", created.id));
+        }
+        EventCommands::UpdateTitle { event_id, title } => {
+            let id = event_id.parse::<EventId>()?;
+            backend
+                .call("event.update", json!({ "id": id.to_string(), "title": title }))
+                .await?;
+            output::success("Event title updated");
+        }
+        EventCommands::SetLocation { event_id, place } => {
+            if place.is_empty() {
+                return Err("give --location, --url or --geo".into());
+            }
+
+            let mut params = json!({ "id": event_id.parse::<EventId>()?.to_string() });
+            add_place(&mut params, place)?;
+            backend.call("event.update", params).await?;
+            output::success("Event location updated");
+        }
+        EventCommands::SetStatus { event_id, availability } => {
+            if availability.is_empty() {
+                return Err("give --status or --transparency".into());
+            }
+
+            let mut params = json!({ "id": event_id.parse::<EventId>()?.to_string() });
+            add_status(&mut params, availability);
+            backend.call("event.update", params).await?;
+            output::success("Event status updated");
+        }
+        EventCommands::Tag { event_id, tag } => {
+            let id = event_id.parse::<EventId>()?;
+            backend
+                .call("event.add_tag", json!({ "id": id.to_string(), "tag": tag }))
+                .await?;
+            output::success("Tag added");
+        }
+        EventCommands::Untag { event_id, tag } => {
+            let id = event_id.parse::<EventId>()?;
+            backend
+                .call("event.remove_tag", json!({ "id": id.to_string(), "tag": tag }))
+                .await?;
+            output::success("Tag removed");
+        }
+        EventCommands::Cancel { event_id } => {
+            let id = event_id.parse::<EventId>()?;
+            backend.call("event.cancel", json!({ "id": id.to_string() })).await?;
+            output::success("Event cancelled");
+        }
+        EventCommands::Delete { event_id } => {
+            let id = event_id.parse::<EventId>()?;
+            backend.call("event.delete", json!({ "id": id.to_string() })).await?;
+            output::success("Event deleted");
+        }
+        EventCommands::Restore { event_id } => {
+            let id = event_id.parse::<EventId>()?;
+            backend.call("event.restore", json!({ "id": id.to_string() })).await?;
+            output::success("Event restored");
+        }
+        EventCommands::AddReminder { event_id, reminder } => {
+            let id = event_id.parse::<EventId>()?;
+            let params = reminder_params(id.to_string(), reminder)?;
+            let created: CreatedDto = backend.call_as("event.add_reminder", params).await?;
+            output::success(&format!("Added reminder {}", created.id));
+        }
+        EventCommands::RemoveReminder { event_id, reminder_id } => {
+            let params = json!({
+                "id": event_id.parse::<EventId>()?.to_string(),
+                "reminder_id": reminder_id.parse::<ReminderId>()?.to_string(),
+            });
+            backend.call("event.remove_reminder", params).await?;
+            output::success("Reminder removed");
+        }
+        EventCommands::SnoozeReminder { event_id, reminder_id, minutes } => {
+            let params = json!({
+                "id": event_id.parse::<EventId>()?.to_string(),
+                "reminder_id": reminder_id.parse::<ReminderId>()?.to_string(),
+                "minutes": minutes,
+            });
+            let state: ReminderStateDto = backend.call_as("reminder.snooze", params).await?;
+            output::success(&snoozed_message(&state));
+        }
+        EventCommands::DismissReminder { event_id, reminder_id } => {
+            let params = json!({
+                "id": event_id.parse::<EventId>()?.to_string(),
+                "reminder_id": reminder_id.parse::<ReminderId>()?.to_string(),
+            });
+            backend.call("reminder.dismiss", params).await?;
+            output::success("Reminder dismissed");
+        }
+        EventCommands::SetOrganizer { event_id, email, name, clear: _ } => {
+            let params = json!({
+                "id": event_id.parse::<EventId>()?.to_string(),
+                "email": email,
+                "name": name,
+            });
+            backend.call("event.set_organizer", params).await?;
+            output::success("Organizer updated");
+        }
+        EventCommands::AddAttendee { event_id, attendee } => {
+            let id = event_id.parse::<EventId>()?;
+            backend.call("event.add_attendee", attendee_params(id.to_string(), attendee)).await?;
+            output::success("Attendee added");
+        }
+        EventCommands::RemoveAttendee { event_id, email } => {
+            let params = json!({ "id": event_id.parse::<EventId>()?.to_string(), "email": email });
+            backend.call("event.remove_attendee", params).await?;
+            output::success("Attendee removed");
+        }
+        EventCommands::AttendeeStatus { event_id, email, status } => {
+            let params = json!({
+                "id": event_id.parse::<EventId>()?.to_string(),
+                "email": email,
+                "status": status,
+            });
+            backend.call("event.update_attendee_status", params).await?;
+            output::success("Attendee status updated");
+        }
+        EventCommands::Audit { event_id, limit } => {
+            let id = event_id.parse::<EventId>()?;
+            let entries: Vec<AuditEntryDto> = backend
+                .call_as("event.audit", json!({ "id": id.to_string(), "limit": limit }))
+                .await?;
+            output::audit(&entries);
+        }
+    }
+
+    Ok(())
+}
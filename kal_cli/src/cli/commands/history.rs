@@ -0,0 +1,33 @@
+use kal_core::infrastructure::dto::HistoryEntryDto;
+use serde_json::json;
+
+use super::{backend::Backend, CliResult};
+use crate::cli::output;
+
+pub async fn undo(mut backend: Backend) -> CliResult {
+    let entry: HistoryEntryDto = backend.call_as("history.undo", json!({})).await?;
+    output::success(&format!("Undid {}", describe(&entry)));
+    Ok(())
+}
+
+pub async fn redo(mut backend: Backend) -> CliResult {
+    let entry: HistoryEntryDto = backend.call_as("history.redo", json!({})).await?;
+    output::success(&format!("Redid {}", describe(&entry)));
+    Ok(())
+}
+
+pub async fn list(limit: u32, mut backend: Backend) -> CliResult {
+    let entries: Vec<HistoryEntryDto> = backend
+        .call_as("history.list", json!({ "limit": limit }))
+        .await?;
+    output::history(&entries);
+    Ok(())
+}
+
+/// The command with what it changed, when that was a single object.
+fn describe(entry: &HistoryEntryDto) -> String {
+    match entry.changes.as_slice() {
+        [change] => format!("{} ({})", entry.command, output::change_summary(change)),
+        changes => format!("{} ({} changes)", entry.command, changes.len()),
+    }
+}
@@ -0,0 +1,55 @@
+use kal_core::{
+    domain::value_objects::EventId,
+    infrastructure::{
+        dto::{ItipMessageDto, ReplyOutcomeDto},
+        itip::{DirectoryTransport, ItipMessage, StdoutTransport, Transport},
+    },
+};
+use serde_json::json;
+
+use super::{backend::Backend, CliResult};
+use crate::cli::{output, ItipCommands};
+
+pub async fn run(action: ItipCommands, mut backend: Backend) -> CliResult {
+    match action {
+        ItipCommands::Send { event_id, out } => {
+            let id = event_id.parse::<EventId>()?;
+            let dto: ItipMessageDto = backend
+                .call_as("itip.message", json!({ "id": id.to_string() }))
+                .await?;
+            let message = ItipMessage::parse(&dto.ics)?;
+
+            match out {
+                Some(dir) => {
+                    let transport = DirectoryTransport::new(dir);
+                    transport.send(&message).await?;
+                    output::success(&format!(
+                        "Wrote {} for {} to {}",
+                        dto.method,
+                        dto.recipients.join(", "),
+                        transport.path_for(&message).display(),
+                    ));
+                }
+                None => StdoutTransport.send(&message).await?,
+            }
+        }
+        ItipCommands::Receive { files } => {
+            for file in files {
+                let ics = tokio::fs::read_to_string(&file).await?;
+                let outcome: ReplyOutcomeDto = backend
+                    .call_as("itip.reply", json!({ "ics": ics }))
+                    .await?;
+
+                match outcome.current {
+                    None => output::success(&format!("Applied {}", file.display())),
+                    Some(current) => output::warning(&format!(
+                        "Ignored {}: it answers an older revision (now {current})",
+                        file.display()
+                    )),
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,136 @@
+use chrono::{NaiveDate, Utc};
+use kal_core::{
+    domain::value_objects::{CalendarId, EventId, JournalId},
+    infrastructure::dto::{CreatedDto, ImportReportDto, JournalEntryDto},
+};
+use serde_json::{json, Value};
+
+use super::{backend::Backend, parse_datetime, CliResult};
+use crate::cli::{output, JournalCommands, JournalLinkArgs};
+
+pub async fn run(action: JournalCommands, mut backend: Backend) -> CliResult {
+    match action {
+        JournalCommands::Create { calendar_id, date, title, body, link } => {
+            let date = match date {
+                Some(date) => parse_date(&date)?,
+                None => Utc::now().date_naive(),
+            };
+            let params = json!({
+                "calendar_id": calendar_id.parse::<CalendarId>()?.to_string(),
+                "date": date,
+                "title": title,
+                "body": body,
+                "link": link_value(link)?,
+            });
+            let created: CreatedDto = backend.call_as("journal.create", params).await?;
+            output::success(&format!("Created journal entry {}", created.id));
+        }
+        JournalCommands::List { calendar_id, from, to } => {
+            let params = list_params(&calendar_id, from, to)?;
+            let entries: Vec<JournalEntryDto> = backend.call_as("journal.list", params).await?;
+            output::journal(&entries);
+        }
+        JournalCommands::Show { journal_id } => {
+            let id = journal_id.parse::<JournalId>()?;
+            let entry: JournalEntryDto = backend.call_as("journal.get", json!({ "id": id.to_string() })).await?;
+            output::journal(&[entry]);
+        }
+        JournalCommands::ForEvent { event_id } => {
+            let id = event_id.parse::<EventId>()?;
+            let entries: Vec<JournalEntryDto> =
+                backend.call_as("journal.for_event", json!({ "id": id.to_string() })).await?;
+            output::journal(&entries);
+        }
+        JournalCommands::Update { journal_id, date, title, body, link } => {
+            let mut params = json!({ "id": journal_id.parse::<JournalId>()?.to_string() });
+            let fields = params.as_object_mut().expect("params are an object");
+
+            if let Some(date) = date {
+                fields.insert("date".into(), json!(parse_date(&date)?));
+            }
+            if let Some(title) = title {
+                fields.insert("title".into(), Value::from(title));
+            }
+            if let Some(body) = body {
+                fields.insert("body".into(), Value::from(body));
+            }
+            if link.event.is_some() {
+                fields.insert("link".into(), link_value(link)?);
+            }
+
+            if fields.len() == 1 {
+                return Err("nothing to update".into());
+            }
+
+            backend.call("journal.update", params).await?;
+            output::success("Journal entry updated");
+        }
+        JournalCommands::Delete { journal_id } => {
+            let id = journal_id.parse::<JournalId>()?;
+            backend.call("journal.delete", json!({ "id": id.to_string() })).await?;
+            output::success("Journal entry deleted");
+        }
+        JournalCommands::Search { query, calendar_id } => {
+            let calendar_id = calendar_id
+                .map(|id| id.parse::<CalendarId>().map(|id| id.to_string()))
+                .transpose()?;
+            let params = json!({ "query": query, "calendar_id": calendar_id });
+            let entries: Vec<JournalEntryDto> = backend.call_as("journal.search", params).await?;
+            output::journal(&entries);
+        }
+        JournalCommands::Import { calendar_id, files } => {
+            let calendar_id = calendar_id.parse::<CalendarId>()?;
+
+            for file in files {
+                let ics = tokio::fs::read_to_string(&file).await?;
+                let outcome: ImportReportDto = backend
+                    .call_as("journal.import", json!({ "calendar_id": calendar_id.to_string(), "ics": ics }))
+                    .await?;
+
+                output::success(&format!("Imported {} journal entries from {}", outcome.imported, file.display()));
+                for skipped in outcome.skipped {
+                    output::warning(&format!("Skipped {}: {}", skipped.uid, skipped.reason));
+                }
+            }
+        }
+        JournalCommands::Export { calendar_id, from, to, out } => {
+            let params = list_params(&calendar_id, from, to)?;
+            let ics: String = backend.call_as("journal.export", params).await?;
+
+            match out {
+                Some(path) => {
+                    tokio::fs::write(&path, ics).await?;
+                    output::success(&format!("Wrote {}", path.display()));
+                }
+                None => print!("{ics}"),
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn list_params(calendar_id: &str, from: Option<String>, to: Option<String>) -> CliResult<Value> {
+    Ok(json!({
+        "calendar_id": calendar_id.parse::<CalendarId>()?.to_string(),
+        "from": from.as_deref().map(parse_date).transpose()?,
+        "to": to.as_deref().map(parse_date).transpose()?,
+    }))
+}
+
+/// The `link` object of the journal DTOs; `null` when no event is given
+/// or the event is empty.
+fn link_value(args: JournalLinkArgs) -> CliResult<Value> {
+    let Some(event) = args.event.filter(|event| !event.is_empty()) else {
+        return Ok(Value::Null);
+    };
+
+    Ok(json!({
+        "event_id": event.parse::<EventId>()?.to_string(),
+        "original_starts_at": args.occurrence.as_deref().map(parse_datetime).transpose()?,
+    }))
+}
+
+fn parse_date(value: &str) -> CliResult<NaiveDate> {
+    NaiveDate::parse_from_str(value, "%Y-%m-%d").map_err(|_| format!("invalid date: {value}").into())
+}
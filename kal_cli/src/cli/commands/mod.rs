@@ -0,0 +1,158 @@
+use std::sync::Arc;
+
+use chrono::{DateTime, NaiveDate, NaiveDateTime, Utc};
+use kal_core::{
+    application::publisher::{Publisher, DEFAULT_ACTOR},
+    infrastructure::{
+        dto::ReminderStateDto,
+        itip::{DirectoryTransport, ItipSubscriber},
+        persistence::{SqliteEventRepository, SqliteRecurringEventRepository},
+    },
+};
+use serde_json::{json, Value};
+use sqlx::SqlitePool;
+
+use super::{output, AttendeeArgs, PlaceArgs, ReminderArgs, StatusArgs};
+
+pub mod api;
+pub mod backend;
+pub mod calendar;
+pub mod daemon;
+pub mod event;
+pub mod history;
+pub mod itip;
+pub mod journal;
+pub mod recurring;
+pub mod remind;
+pub mod search;
+pub mod server;
+pub mod tag;
+pub mod task;
+pub mod trash;
+
+pub type CliResult<T = ()> = Result<T, Box<dyn std::error::Error>>;
+
+/// Names `actor` as the one who made every change, in the audit log and
+/// to subscribers, and writes the organizer's invitations and
+/// cancellations to the outbox as scheduled events change.
+pub fn publisher(pool: &SqlitePool, actor: impl Into<String>) -> CliResult<Publisher> {
+    let transport = Arc::new(DirectoryTransport::new(crate::outbox()?));
+    let itip = ItipSubscriber::new(
+        SqliteEventRepository::new(pool.clone()),
+        SqliteRecurringEventRepository::new(pool.clone()),
+        transport,
+    )
+    .on_error(|e| output::warning(&format!("Could not write a scheduling message: {e}")));
+
+    Ok(Publisher::new().with_actor(actor).subscribe(Arc::new(itip)))
+}
+
+/// `$USER`, for changes made on this machine.
+pub fn local_actor() -> String {
+    std::env::var("USER").unwrap_or_else(|_| DEFAULT_ACTOR.to_string())
+}
+
+/// Parses `YYYY-MM-DD HH:MM`, `YYYY-MM-DD` (midnight) or RFC 3339, all
+/// read as UTC.
+pub fn parse_datetime(value: &str) -> CliResult<DateTime<Utc>> {
+    if let Ok(dt) = DateTime::parse_from_rfc3339(value) {
+        return Ok(dt.with_timezone(&Utc));
+    }
+
+    if let Ok(dt) = NaiveDateTime::parse_from_str(value, "%Y-%m-%d %H:%M") {
+        return Ok(dt.and_utc());
+    }
+
+    if let Ok(date) = NaiveDate::parse_from_str(value, "%Y-%m-%d") {
+        return Ok(date.and_hms_opt(0, 0, 0).expect("midnight is valid").and_utc());
+    }
+
+    Err(format!("invalid date/time: {value}").into())
+}
+
+pub fn is_date_only(value: &str) -> bool {
+    NaiveDate::parse_from_str(value, "%Y-%m-%d").is_ok()
+}
+
+/// Params for `*.add_reminder`.
+pub fn reminder_params(id: String, args: ReminderArgs) -> CliResult<Value> {
+    let at = args.at.as_deref().map(parse_datetime).transpose()?;
+
+    Ok(json!({
+        "id": id,
+        "offset_seconds": args.minutes_before.map(|m| -m * 60),
+        "at": at,
+        "action": args.action,
+        "target": args.target,
+        "description": args.description,
+        "repeat": args.repeat,
+        "repeat_interval_seconds": args.repeat_every.map(|m| m * 60),
+    }))
+}
+
+/// Params for `*.add_attendee`.
+pub fn attendee_params(id: String, args: AttendeeArgs) -> Value {
+    json!({
+        "id": id,
+        "email": args.email,
+        "name": args.name,
+        "role": args.role,
+        "rsvp": args.rsvp,
+    })
+}
+
+/// Adds the place fields that were given to `params`. An empty value is
+/// sent as `null`, which clears the field on update.
+pub fn add_place(params: &mut Value, args: PlaceArgs) -> CliResult {
+    let fields = params.as_object_mut().expect("params are an object");
+
+    if let Some(location) = args.location {
+        fields.insert("location".into(), non_empty(location));
+    }
+
+    if let Some(url) = args.url {
+        fields.insert("url".into(), non_empty(url));
+    }
+
+    if let Some(geo) = args.geo {
+        let value = if geo.is_empty() { Value::Null } else { parse_geo(&geo)? };
+        fields.insert("geo".into(), value);
+    }
+
+    Ok(())
+}
+
+/// Adds the status and transparency that were given to `params`.
+pub fn add_status(params: &mut Value, args: StatusArgs) {
+    let fields = params.as_object_mut().expect("params are an object");
+
+    if let Some(status) = args.status {
+        fields.insert("status".into(), Value::from(status));
+    }
+
+    if let Some(transparency) = args.transparency {
+        fields.insert("transparency".into(), Value::from(transparency));
+    }
+}
+
+/// Parses `LAT,LON` into the `geo` object of the DTOs.
+fn parse_geo(value: &str) -> CliResult<Value> {
+    let invalid = || format!("invalid coordinates: {value} (expected LAT,LON)");
+    let (latitude, longitude) = value.split_once(',').ok_or_else(invalid)?;
+
+    Ok(json!({
+        "latitude": latitude.trim().parse::<f64>().map_err(|_| invalid())?,
+        "longitude": longitude.trim().parse::<f64>().map_err(|_| invalid())?,
+    }))
+}
+
+fn non_empty(value: String) -> Value {
+    if value.is_empty() { Value::Null } else { Value::from(value) }
+}
+
+pub fn snoozed_message(state: &ReminderStateDto) -> String {
+    match state.snoozed_until {
+        Some(until) => format!("Reminder snoozed until {}", until.format("%Y-%m-%d %H:%M")),
+        None => "Reminder snoozed".to_string(),
+    }
+}
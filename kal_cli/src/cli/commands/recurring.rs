@@ -0,0 +1,150 @@
+use kal_core::{
+    domain::value_objects::{CalendarId, EventId, ReminderId, Frequency},
+    infrastructure::dto::{CreatedDto, ReminderStateDto},
+};
+use serde_json::json;
+
+use super::{
+    add_place, add_status, attendee_params, backend::Backend, is_date_only, parse_datetime,
+    reminder_params, snoozed_message, CliResult,
+};
+use crate::cli::{output, RecurringCommands};
+
+pub async fn run(action: RecurringCommands, mut backend: Backend) -> CliResult {
+    match action {
+        RecurringCommands::Create { calendar_id, title, pattern, start, end, place, availability, tags } => {
+            let mut params = json!({
+                "calendar_id": calendar_id.parse::<CalendarId>()?.to_string(),
+                "title": title,
+                "starts_at": parse_datetime(&start)?,
+                "ends_at": parse_datetime(&end)?,
+                "frequency": pattern.parse::<Frequency>()?.to_string(),
+                "is_all_day": is_date_only(&start) && is_date_only(&end),
+                "tags": tags,
+            });
+            add_place(&mut params, place)?;
+            add_status(&mut params, availability);
+            let created: CreatedDto = backend.call_as("recurring.create", params).await?;
+            output::success(&format!("Created recurring event {}", created.id));
+        }
+        RecurringCommands::SetLocation { event_id, place } => {
+            if place.is_empty() {
+                return Err("give --location, --url or --geo".into());
+            }
+
+            let mut params = json!({ "id": event_id.parse::<EventId>()?.to_string() });
+            add_place(&mut params, place)?;
+            backend.call("recurring.update", params).await?;
+            output::success("Recurring event location updated");
+        }
+        RecurringCommands::SetStatus { event_id, availability } => {
+            if availability.is_empty() {
+                return Err("give --status or --transparency".into());
+            }
+
+            let mut params = json!({ "id": event_id.parse::<EventId>()?.to_string() });
+            add_status(&mut params, availability);
+            backend.call("recurring.update", params).await?;
+            output::success("Recurring event status updated");
+        }
+        RecurringCommands::Tag { event_id, tag } => {
+            let id = event_id.parse::<EventId>()?;
+            backend
+                .call("recurring.add_tag", json!({ "id": id.to_string(), "tag": tag }))
+                .await?;
+            output::success("Tag added");
+        }
+        RecurringCommands::Untag { event_id, tag } => {
+            let id = event_id.parse::<EventId>()?;
+            backend
+                .call("recurring.remove_tag", json!({ "id": id.to_string(), "tag": tag }))
+                .await?;
+            output::success("Tag removed");
+        }
+        RecurringCommands::Cancel { event_id } => {
+            let id = event_id.parse::<EventId>()?;
+            backend.call("recurring.cancel", json!({ "id": id.to_string() })).await?;
+            output::success("Recurring event cancelled");
+        }
+        RecurringCommands::CancelOccurrence { event_id, date } => {
+            let params = json!({
+                "id": event_id.parse::<EventId>()?.to_string(),
+                "original_starts_at": parse_datetime(&date)?,
+            });
+            backend.call("recurring.cancel_occurrence", params).await?;
+            output::success("Occurrence cancelled");
+        }
+        RecurringCommands::OccurrenceLocation { event_id, date, location } => {
+            let params = json!({
+                "id": event_id.parse::<EventId>()?.to_string(),
+                "original_starts_at": parse_datetime(&date)?,
+                "location": location.filter(|l| !l.is_empty()),
+            });
+            backend.call("recurring.set_occurrence_location", params).await?;
+            output::success("Occurrence location updated");
+        }
+        RecurringCommands::AddReminder { event_id, reminder } => {
+            let id = event_id.parse::<EventId>()?;
+            let params = reminder_params(id.to_string(), reminder)?;
+            let created: CreatedDto = backend.call_as("recurring.add_reminder", params).await?;
+            output::success(&format!("Added reminder {}", created.id));
+        }
+        RecurringCommands::RemoveReminder { event_id, reminder_id } => {
+            let params = json!({
+                "id": event_id.parse::<EventId>()?.to_string(),
+                "reminder_id": reminder_id.parse::<ReminderId>()?.to_string(),
+            });
+            backend.call("recurring.remove_reminder", params).await?;
+            output::success("Reminder removed");
+        }
+        RecurringCommands::SnoozeReminder { event_id, reminder_id, date, minutes } => {
+            let params = json!({
+                "id": event_id.parse::<EventId>()?.to_string(),
+                "reminder_id": reminder_id.parse::<ReminderId>()?.to_string(),
+                "original_starts_at": parse_datetime(&date)?,
+                "minutes": minutes,
+            });
+            let state: ReminderStateDto = backend.call_as("reminder.snooze", params).await?;
+            output::success(&snoozed_message(&state));
+        }
+        RecurringCommands::DismissReminder { event_id, reminder_id, date } => {
+            let params = json!({
+                "id": event_id.parse::<EventId>()?.to_string(),
+                "reminder_id": reminder_id.parse::<ReminderId>()?.to_string(),
+                "original_starts_at": parse_datetime(&date)?,
+            });
+            backend.call("reminder.dismiss", params).await?;
+            output::success("Reminder dismissed");
+        }
+        RecurringCommands::SetOrganizer { event_id, email, name, clear: _ } => {
+            let params = json!({
+                "id": event_id.parse::<EventId>()?.to_string(),
+                "email": email,
+                "name": name,
+            });
+            backend.call("recurring.set_organizer", params).await?;
+            output::success("Organizer updated");
+        }
+        RecurringCommands::AddAttendee { event_id, attendee } => {
+            let id = event_id.parse::<EventId>()?;
+            backend.call("recurring.add_attendee", attendee_params(id.to_string(), attendee)).await?;
+            output::success("Attendee added");
+        }
+        RecurringCommands::RemoveAttendee { event_id, email } => {
+            let params = json!({ "id": event_id.parse::<EventId>()?.to_string(), "email": email });
+            backend.call("recurring.remove_attendee", params).await?;
+            output::success("Attendee removed");
+        }
+        RecurringCommands::AttendeeStatus { event_id, email, status } => {
+            let params = json!({
+                "id": event_id.parse::<EventId>()?.to_string(),
+                "email": email,
+                "status": status,
+            });
+            backend.call("recurring.update_attendee_status", params).await?;
+            output::success("Attendee status updated");
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,53 @@
+use std::{path::PathBuf, sync::Arc};
+
+use kal_core::infrastructure::{
+    persistence::{
+        SqliteCalendarRepository,
+        SqliteEventRepository,
+        SqliteRecurringEventRepository,
+        SqliteReminderStateRepository,
+    },
+    reminders::{
+        CommandNotifier,
+        FileNotifier,
+        Notifier,
+        ReminderScheduler,
+        StdoutNotifier,
+        SystemClock,
+    },
+};
+use sqlx::SqlitePool;
+
+use crate::cli::output;
+
+use super::CliResult;
+
+pub async fn run(file: Option<PathBuf>, command: Option<String>, pool: SqlitePool) -> CliResult {
+    let notifier: Box<dyn Notifier> = match (file, command) {
+        (Some(path), _) => Box::new(FileNotifier::new(path)),
+        (None, Some(command)) => Box::new(CommandNotifier::new(command)),
+        (None, None) => Box::new(StdoutNotifier),
+    };
+
+    let scheduler = ReminderScheduler::new(
+        SqliteCalendarRepository::new(pool.clone()),
+        SqliteEventRepository::new(pool.clone()),
+        SqliteRecurringEventRepository::new(pool.clone()),
+        SqliteReminderStateRepository::new(pool),
+        Arc::new(SystemClock),
+        notifier,
+    );
+
+    let deliveries = scheduler.run(|delivery| {
+        if let Err(e) = delivery.result() {
+            output::error(&format!("{}: {e}", delivery.notification().message()));
+        }
+    });
+
+    tokio::select! {
+        result = deliveries => result?,
+        result = tokio::signal::ctrl_c() => result?,
+    }
+
+    Ok(())
+}
@@ -0,0 +1,33 @@
+use kal_core::{domain::value_objects::CalendarId, infrastructure::dto::SearchHitDto};
+use serde_json::json;
+
+use super::{backend::Backend, parse_datetime, CliResult};
+use crate::cli::output;
+
+pub async fn run(
+    query: String,
+    calendar_id: Option<String>,
+    window: Option<(String, String)>,
+    limit: usize,
+    mut backend: Backend,
+) -> CliResult {
+    let calendar_id = calendar_id
+        .map(|id| id.parse::<CalendarId>().map(|id| id.to_string()))
+        .transpose()?;
+    let (from, to) = match window {
+        Some((from, to)) => (Some(parse_datetime(&from)?), Some(parse_datetime(&to)?)),
+        None => (None, None),
+    };
+
+    let params = json!({
+        "query": query,
+        "calendar_id": calendar_id,
+        "from": from,
+        "to": to,
+        "limit": limit,
+    });
+    let hits: Vec<SearchHitDto> = backend.call_as("event.search", params).await?;
+    output::search_hits(&hits);
+
+    Ok(())
+}
@@ -0,0 +1,30 @@
+use kal_core::infrastructure::caldav_server::CalDavServer;
+use sqlx::SqlitePool;
+use tokio::net::TcpListener;
+
+use super::{local_actor, publisher, CliResult};
+
+pub async fn run(
+    bind: String,
+    credentials: Option<(String, String)>,
+    pool: SqlitePool,
+) -> CliResult {
+    // Changes are made by the one account the server accepts
+    let actor = match &credentials {
+        Some((username, _)) => username.clone(),
+        None => local_actor(),
+    };
+    let publisher = publisher(&pool, actor)?;
+    let mut server = CalDavServer::new(pool).with_publisher(publisher);
+
+    if let Some((username, password)) = credentials {
+        server = server.with_credentials(&username, &password);
+    }
+
+    let listener = TcpListener::bind(&bind).await?;
+    println!("Serving CalDAV on http://{bind}/");
+
+    server.serve(listener).await?;
+
+    Ok(())
+}
@@ -0,0 +1,39 @@
+use kal_core::infrastructure::dto::{RetagOutcomeDto, TagDto};
+use serde_json::json;
+
+use super::{backend::Backend, CliResult};
+use crate::cli::{output, TagCommands};
+
+pub async fn run(action: TagCommands, mut backend: Backend) -> CliResult {
+    match action {
+        TagCommands::List => {
+            let tags: Vec<TagDto> = backend.call_as("tag.list", json!({})).await?;
+            output::tags(&tags);
+        }
+        TagCommands::Rename { from, to } => {
+            let outcome: RetagOutcomeDto = backend
+                .call_as("tag.rename", json!({ "from": from, "to": to }))
+                .await?;
+            retagged(&format!("Renamed {from} to {to}"), &outcome);
+        }
+        TagCommands::Merge { from, into } => {
+            let outcome: RetagOutcomeDto = backend
+                .call_as("tag.merge", json!({ "from": from, "to": into }))
+                .await?;
+            retagged(&format!("Merged {from} into {into}"), &outcome);
+        }
+    }
+
+    Ok(())
+}
+
+fn retagged(message: &str, outcome: &RetagOutcomeDto) {
+    output::success(&format!("{message} on {} item(s)", outcome.updated));
+
+    if outcome.skipped > 0 {
+        output::warning(&format!(
+            "{} item(s) in read-only calendars kept the old tag",
+            outcome.skipped
+        ));
+    }
+}
@@ -0,0 +1,139 @@
+use kal_core::{
+    domain::value_objects::{CalendarId, Frequency, TaskId},
+    infrastructure::dto::{CreatedDto, TaskDto, ImportReportDto},
+};
+use serde_json::{json, Value};
+
+use super::{backend::Backend, non_empty, parse_datetime, CliResult};
+use crate::cli::{output, RepeatArgs, TaskCommands};
+
+pub async fn run(action: TaskCommands, mut backend: Backend) -> CliResult {
+    match action {
+        TaskCommands::Create { calendar_id, title, description, start, due, priority, repeat } => {
+            let params = json!({
+                "calendar_id": calendar_id.parse::<CalendarId>()?.to_string(),
+                "title": title,
+                "description": description,
+                "starts_at": start.as_deref().map(parse_datetime).transpose()?,
+                "due_at": due.as_deref().map(parse_datetime).transpose()?,
+                "priority": priority,
+                "recurrence": recurrence(repeat)?,
+            });
+            let created: CreatedDto = backend.call_as("task.create", params).await?;
+            output::success(&format!("Created task {}", created.id));
+        }
+        TaskCommands::List { calendar_id, open } => {
+            let params = json!({
+                "calendar_id": calendar_id.parse::<CalendarId>()?.to_string(),
+                "open": open,
+            });
+            let tasks: Vec<TaskDto> = backend.call_as("task.list", params).await?;
+            output::tasks(&tasks);
+        }
+        TaskCommands::Update { task_id, title, description, start, due, priority, progress } => {
+            let mut params = json!({ "id": task_id.parse::<TaskId>()?.to_string() });
+            let fields = params.as_object_mut().expect("params are an object");
+
+            if let Some(title) = title {
+                fields.insert("title".into(), Value::from(title));
+            }
+            if let Some(description) = description {
+                fields.insert("description".into(), non_empty(description));
+            }
+            if let Some(start) = start {
+                fields.insert("starts_at".into(), optional_datetime(&start)?);
+            }
+            if let Some(due) = due {
+                fields.insert("due_at".into(), optional_datetime(&due)?);
+            }
+            if let Some(priority) = priority {
+                let value = if priority == 0 { Value::Null } else { Value::from(priority) };
+                fields.insert("priority".into(), value);
+            }
+            if let Some(progress) = progress {
+                fields.insert("percent_complete".into(), Value::from(progress));
+            }
+
+            if fields.len() == 1 {
+                return Err("nothing to update".into());
+            }
+
+            backend.call("task.update", params).await?;
+            output::success("Task updated");
+        }
+        TaskCommands::Complete { task_id } => {
+            let id = task_id.parse::<TaskId>()?;
+            let task: TaskDto = backend.call_as("task.complete", json!({ "id": id.to_string() })).await?;
+
+            match task.due_at.filter(|_| task.status != "COMPLETED") {
+                Some(due_at) => output::success(&format!(
+                    "Task done; next due {}",
+                    due_at.format("%Y-%m-%d %H:%M")
+                )),
+                None => output::success("Task completed"),
+            }
+        }
+        TaskCommands::Reopen { task_id } => {
+            let id = task_id.parse::<TaskId>()?;
+            backend.call("task.reopen", json!({ "id": id.to_string() })).await?;
+            output::success("Task reopened");
+        }
+        TaskCommands::Delete { task_id } => {
+            let id = task_id.parse::<TaskId>()?;
+            backend.call("task.delete", json!({ "id": id.to_string() })).await?;
+            output::success("Task deleted");
+        }
+        TaskCommands::Import { calendar_id, files } => {
+            let calendar_id = calendar_id.parse::<CalendarId>()?;
+
+            for file in files {
+                let ics = tokio::fs::read_to_string(&file).await?;
+                let outcome: ImportReportDto = backend
+                    .call_as("task.import", json!({ "calendar_id": calendar_id.to_string(), "ics": ics }))
+                    .await?;
+
+                output::success(&format!("Imported {} task(s) from {}", outcome.imported, file.display()));
+                for skipped in outcome.skipped {
+                    output::warning(&format!("Skipped {}: {}", skipped.uid, skipped.reason));
+                }
+            }
+        }
+        TaskCommands::Export { calendar_id, open, out } => {
+            let params = json!({
+                "calendar_id": calendar_id.parse::<CalendarId>()?.to_string(),
+                "open": open,
+            });
+            let ics: String = backend.call_as("task.export", params).await?;
+
+            match out {
+                Some(path) => {
+                    tokio::fs::write(&path, ics).await?;
+                    output::success(&format!("Wrote {}", path.display()));
+                }
+                None => print!("{ics}"),
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// The `recurrence` object of the task DTOs, or `null`.
+fn recurrence(args: RepeatArgs) -> CliResult<Value> {
+    let Some(repeat) = args.repeat else {
+        return Ok(Value::Null);
+    };
+
+    Ok(json!({
+        "frequency": repeat.parse::<Frequency>()?.to_string(),
+        "interval": args.interval,
+        "until": args.until.as_deref().map(parse_datetime).transpose()?,
+    }))
+}
+
+fn optional_datetime(value: &str) -> CliResult<Value> {
+    if value.is_empty() {
+        return Ok(Value::Null);
+    }
+    Ok(json!(parse_datetime(value)?))
+}
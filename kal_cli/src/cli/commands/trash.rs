@@ -0,0 +1,83 @@
+use chrono::{DateTime, Duration, Utc};
+use kal_core::{
+    application::{
+        commands::trash::{PurgeTrashCommand, PurgeTrashHandler, DEFAULT_RETENTION_DAYS},
+        scope::CommandScope,
+    },
+    infrastructure::{
+        dto::{PurgeOutcomeDto, TrashedItemDto},
+        persistence::SqliteUnitOfWork,
+    },
+};
+use serde_json::json;
+use sqlx::SqlitePool;
+
+use super::{backend::Backend, CliResult};
+use crate::cli::{output, TrashCommands};
+
+pub async fn run(action: TrashCommands, mut backend: Backend) -> CliResult {
+    match action {
+        TrashCommands::List => {
+            let items: Vec<TrashedItemDto> = backend.call_as("trash.list", json!({})).await?;
+            output::trash(&items);
+        }
+        TrashCommands::Restore { id } => {
+            backend.call("trash.restore", json!({ "id": id })).await?;
+            output::success("Restored from the trash");
+        }
+        TrashCommands::Empty { older_than } => {
+            let before = older_than.map(|days| Utc::now() - Duration::days(days));
+            purge(&mut backend, before).await?;
+        }
+        TrashCommands::Purge => {
+            let before = Utc::now() - retention()?;
+            purge(&mut backend, Some(before)).await?;
+        }
+    }
+
+    Ok(())
+}
+
+async fn purge(backend: &mut Backend, before: Option<DateTime<Utc>>) -> CliResult {
+    let outcome: PurgeOutcomeDto = backend
+        .call_as("trash.purge", json!({ "before": before }))
+        .await?;
+    output::success(&format!(
+        "Purged {} calendar(s), {} event(s) and {} series",
+        outcome.calendars, outcome.events, outcome.series
+    ));
+
+    Ok(())
+}
+
+/// How long the trash keeps things: `$KAL_TRASH_RETENTION_DAYS`, 30 days
+/// by default.
+fn retention() -> CliResult<Duration> {
+    let days = match std::env::var("KAL_TRASH_RETENTION_DAYS") {
+        Ok(days) => days
+            .parse()
+            .map_err(|_| format!("invalid KAL_TRASH_RETENTION_DAYS: {days}"))?,
+        Err(_) => DEFAULT_RETENTION_DAYS,
+    };
+
+    Ok(Duration::days(days))
+}
+
+/// Purges what has been in the trash for longer than the retention
+/// period, auditing and publishing what went through `scope`.
+pub async fn purge_expired(pool: &SqlitePool, scope: &CommandScope) -> CliResult {
+    let scope = scope.scoped();
+    let uow = SqliteUnitOfWork::begin(pool).await?;
+    PurgeTrashHandler::new(
+        scope.unrecorded(uow.calendars()),
+        scope.unrecorded(uow.events()),
+        scope.unrecorded(uow.recurring()),
+    )
+    .handle(PurgeTrashCommand::expired(Utc::now(), retention()?))
+    .await?;
+    scope.audit(&uow.audit(), "trash.purge").await?;
+    uow.commit().await?;
+    scope.publish("trash.purge").await;
+
+    Ok(())
+}
@@ -1,4 +1,6 @@
-use clap::{Parser, Subcommand};
+use std::path::PathBuf;
+
+use clap::{Args, Parser, Subcommand};
 
 pub mod commands;
 pub mod output;
@@ -30,6 +32,105 @@ pub enum Commands {
         #[command(subcommand)]
         action: RecurringCommands,
     },
+
+    /// Scheduling messages (iTIP) for events with attendees
+    Itip {
+        #[command(subcommand)]
+        action: ItipCommands,
+    },
+
+    /// Tags across all calendars
+    Tag {
+        #[command(subcommand)]
+        action: TagCommands,
+    },
+
+    /// Deleted calendars, events and series, kept for a while before
+    /// they are purged
+    Trash {
+        #[command(subcommand)]
+        action: TrashCommands,
+    },
+
+    /// To-do items, imported and exported as VTODOs
+    Task {
+        #[command(subcommand)]
+        action: TaskCommands,
+    },
+
+    /// Notes and logs kept by date, imported and exported as VJOURNALs
+    Journal {
+        #[command(subcommand)]
+        action: JournalCommands,
+    },
+
+    /// Find events and series by their text, best match first
+    Search {
+        /// Every word must appear in the title, description, location or
+        /// attendees
+        query: String,
+
+        #[arg(short, long)]
+        calendar_id: Option<String>,
+
+        #[arg(long, requires = "to")]
+        from: Option<String>,
+
+        #[arg(long, requires = "from")]
+        to: Option<String>,
+
+        #[arg(short, long, default_value_t = 20)]
+        limit: usize,
+    },
+
+    /// Undo the last command, including one run in an earlier session
+    Undo,
+
+    /// Redo the command undone last
+    Redo,
+
+    /// Commands that can be undone, most recent first
+    History {
+        #[arg(short, long, default_value_t = 20)]
+        limit: u32,
+    },
+
+    /// Serve the calendars over CalDAV
+    Server {
+        #[arg(short, long, default_value = "127.0.0.1:5232")]
+        bind: String,
+
+        #[arg(short, long, requires = "password")]
+        username: Option<String>,
+
+        #[arg(short, long, requires = "username")]
+        password: Option<String>,
+    },
+
+    /// Serve the HTTP JSON API
+    Api {
+        #[arg(short, long, default_value = "127.0.0.1:8080")]
+        bind: String,
+
+        /// Attribute requests to the user their x-kal-user header names;
+        /// only behind a proxy that authenticates them
+        #[arg(long)]
+        trust_user_header: bool,
+    },
+
+    /// Run the JSON-RPC daemon; other commands use it while it runs
+    Daemon,
+
+    /// Deliver reminders as they come due (to stdout by default)
+    Remind {
+        /// Append notifications to this file
+        #[arg(long, conflicts_with = "command")]
+        file: Option<PathBuf>,
+
+        /// Run this shell command per notification, with KAL_* variables set
+        #[arg(long)]
+        command: Option<String>,
+    },
 }
 
 #[derive(Subcommand)]
@@ -53,6 +154,16 @@ pub enum EventCommands {
 
         #[arg(long)]
         color: Option<String>,
+
+        #[command(flatten)]
+        place: PlaceArgs,
+
+        #[command(flatten)]
+        availability: StatusArgs,
+
+        /// May be given more than once
+        #[arg(long = "tag")]
+        tags: Vec<String>,
     },
 
     /// Update event title
@@ -64,6 +175,42 @@ pub enum EventCommands {
         title: String,
     },
 
+    /// Change where an event takes place; an empty value clears a field
+    SetLocation {
+        #[arg(short, long)]
+        event_id: String,
+
+        #[command(flatten)]
+        place: PlaceArgs,
+    },
+
+    /// Mark an event tentative or confirmed, or show it as free
+    SetStatus {
+        #[arg(short, long)]
+        event_id: String,
+
+        #[command(flatten)]
+        availability: StatusArgs,
+    },
+
+    /// Add a tag to an event
+    Tag {
+        #[arg(short, long)]
+        event_id: String,
+
+        #[arg(short, long)]
+        tag: String,
+    },
+
+    /// Remove a tag from an event
+    Untag {
+        #[arg(short, long)]
+        event_id: String,
+
+        #[arg(short, long)]
+        tag: String,
+    },
+
     /// Cancel an event
     Cancel {
         #[arg(short, long)]
@@ -81,6 +228,100 @@ pub enum EventCommands {
         #[arg(short, long)]
         event_id: String,
     },
+
+    /// Add a reminder to an event
+    AddReminder {
+        #[arg(short, long)]
+        event_id: String,
+
+        #[command(flatten)]
+        reminder: ReminderArgs,
+    },
+
+    /// Remove a reminder from an event
+    RemoveReminder {
+        #[arg(short, long)]
+        event_id: String,
+
+        #[arg(short, long)]
+        reminder_id: String,
+    },
+    /// Fire a reminder again in a few minutes
+    SnoozeReminder {
+        #[arg(short, long)]
+        event_id: String,
+
+        #[arg(short, long)]
+        reminder_id: String,
+
+        #[arg(short, long, default_value_t = 10)]
+        minutes: i64,
+    },
+
+    /// Stop a reminder from firing again
+    DismissReminder {
+        #[arg(short, long)]
+        event_id: String,
+
+        #[arg(short, long)]
+        reminder_id: String,
+    },
+
+    /// Set or clear the organizer of an event
+    SetOrganizer {
+        #[arg(short, long)]
+        event_id: String,
+
+        #[arg(long, conflicts_with = "clear", required_unless_present = "clear")]
+        email: Option<String>,
+
+        #[arg(short, long)]
+        name: Option<String>,
+
+        /// Remove the organizer
+        #[arg(long)]
+        clear: bool,
+    },
+
+    /// Invite an attendee to an event
+    AddAttendee {
+        #[arg(short, long)]
+        event_id: String,
+
+        #[command(flatten)]
+        attendee: AttendeeArgs,
+    },
+
+    /// Remove an attendee from an event
+    RemoveAttendee {
+        #[arg(short, long)]
+        event_id: String,
+
+        #[arg(long)]
+        email: String,
+    },
+
+    /// Record an attendee's reply
+    AttendeeStatus {
+        #[arg(short, long)]
+        event_id: String,
+
+        #[arg(long)]
+        email: String,
+
+        /// needs-action, accepted, declined, tentative or delegated
+        #[arg(short, long)]
+        status: String,
+    },
+
+    /// Show who changed an event or series and how, most recent first
+    Audit {
+        #[arg(short, long)]
+        event_id: String,
+
+        #[arg(short, long, default_value_t = 20)]
+        limit: u32,
+    },
 }
 
 #[derive(Subcommand)]
@@ -95,6 +336,58 @@ pub enum RecurringCommands {
 
         #[arg(short, long)]
         pattern: String, // "daily", "weekly", "monthly", yearly
+
+        #[arg(long)]
+        start: String,
+
+        #[arg(long)]
+        end: String,
+
+        #[command(flatten)]
+        place: PlaceArgs,
+
+        #[command(flatten)]
+        availability: StatusArgs,
+
+        /// May be given more than once
+        #[arg(long = "tag")]
+        tags: Vec<String>,
+    },
+
+    /// Change where a series takes place; an empty value clears a field
+    SetLocation {
+        #[arg(short, long)]
+        event_id: String,
+
+        #[command(flatten)]
+        place: PlaceArgs,
+    },
+
+    /// Mark a series tentative or confirmed, or show it as free
+    SetStatus {
+        #[arg(short, long)]
+        event_id: String,
+
+        #[command(flatten)]
+        availability: StatusArgs,
+    },
+
+    /// Add a tag to a recurring event
+    Tag {
+        #[arg(short, long)]
+        event_id: String,
+
+        #[arg(short, long)]
+        tag: String,
+    },
+
+    /// Remove a tag from a recurring event
+    Untag {
+        #[arg(short, long)]
+        event_id: String,
+
+        #[arg(short, long)]
+        tag: String,
     },
 
     /// Cancel a recurring event
@@ -111,4 +404,486 @@ pub enum RecurringCommands {
         #[arg(short, long)]
         date: String,
     },
+
+    /// Hold a single occurrence somewhere else
+    OccurrenceLocation {
+        #[arg(short, long)]
+        event_id: String,
+
+        /// Original start of the occurrence
+        #[arg(short, long)]
+        date: String,
+
+        /// Omit to go back to the series' location
+        #[arg(short, long)]
+        location: Option<String>,
+    },
+
+    /// Add a reminder to every occurrence of a series
+    AddReminder {
+        #[arg(short, long)]
+        event_id: String,
+
+        #[command(flatten)]
+        reminder: ReminderArgs,
+    },
+
+    /// Remove a reminder from a series
+    RemoveReminder {
+        #[arg(short, long)]
+        event_id: String,
+
+        #[arg(short, long)]
+        reminder_id: String,
+    },
+    /// Fire a reminder of one occurrence again in a few minutes
+    SnoozeReminder {
+        #[arg(short, long)]
+        event_id: String,
+
+        #[arg(short, long)]
+        reminder_id: String,
+
+        /// Original start of the occurrence
+        #[arg(short, long)]
+        date: String,
+
+        #[arg(short, long, default_value_t = 10)]
+        minutes: i64,
+    },
+
+    /// Stop a reminder of one occurrence from firing again
+    DismissReminder {
+        #[arg(short, long)]
+        event_id: String,
+
+        #[arg(short, long)]
+        reminder_id: String,
+
+        /// Original start of the occurrence
+        #[arg(short, long)]
+        date: String,
+    },
+
+    /// Set or clear the organizer of a series
+    SetOrganizer {
+        #[arg(short, long)]
+        event_id: String,
+
+        #[arg(long, conflicts_with = "clear", required_unless_present = "clear")]
+        email: Option<String>,
+
+        #[arg(short, long)]
+        name: Option<String>,
+
+        /// Remove the organizer
+        #[arg(long)]
+        clear: bool,
+    },
+
+    /// Invite an attendee to a series
+    AddAttendee {
+        #[arg(short, long)]
+        event_id: String,
+
+        #[command(flatten)]
+        attendee: AttendeeArgs,
+    },
+
+    /// Remove an attendee from a series
+    RemoveAttendee {
+        #[arg(short, long)]
+        event_id: String,
+
+        #[arg(long)]
+        email: String,
+    },
+
+    /// Record an attendee's reply
+    AttendeeStatus {
+        #[arg(short, long)]
+        event_id: String,
+
+        #[arg(long)]
+        email: String,
+
+        /// needs-action, accepted, declined, tentative or delegated
+        #[arg(short, long)]
+        status: String,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum ItipCommands {
+    /// Send the organizer's invitation, or its cancellation once the event
+    /// is cancelled, to every attendee
+    Send {
+        #[arg(short, long)]
+        event_id: String,
+
+        /// Write the message into this directory instead of printing it
+        #[arg(short, long)]
+        out: Option<PathBuf>,
+    },
+
+    /// Record attendees' answers from METHOD:REPLY files
+    Receive {
+        #[arg(required = true)]
+        files: Vec<PathBuf>,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum TrashCommands {
+    /// List what is in the trash, most recently deleted first
+    List,
+
+    /// Bring a calendar, event or series back out of the trash
+    Restore {
+        id: String,
+    },
+
+    /// Delete what is in the trash for good
+    Empty {
+        /// Only what was deleted more than this many days ago
+        #[arg(long)]
+        older_than: Option<i64>,
+    },
+
+    /// Delete what has been in the trash for longer than
+    /// $KAL_TRASH_RETENTION_DAYS, 30 days by default
+    Purge,
+}
+
+#[derive(Subcommand)]
+pub enum TagCommands {
+    /// List tags in use and how often
+    List,
+
+    /// Rename a tag everywhere
+    Rename {
+        from: String,
+        to: String,
+    },
+
+    /// Fold one tag into another that already exists
+    Merge {
+        from: String,
+        into: String,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum TaskCommands {
+    /// Create a task
+    Create {
+        #[arg(short, long)]
+        calendar_id: String,
+
+        #[arg(short, long)]
+        title: String,
+
+        #[arg(short, long)]
+        description: Option<String>,
+
+        #[arg(long)]
+        start: Option<String>,
+
+        #[arg(long)]
+        due: Option<String>,
+
+        /// 1 (highest) to 9 (lowest)
+        #[arg(short, long)]
+        priority: Option<u8>,
+
+        #[command(flatten)]
+        repeat: RepeatArgs,
+    },
+
+    /// List a calendar's tasks, soonest due first
+    List {
+        #[arg(short, long)]
+        calendar_id: String,
+
+        /// Leave out completed and cancelled tasks
+        #[arg(long)]
+        open: bool,
+    },
+
+    /// Change a task; an empty value clears a field
+    Update {
+        #[arg(short, long)]
+        task_id: String,
+
+        #[arg(long)]
+        title: Option<String>,
+
+        #[arg(short, long)]
+        description: Option<String>,
+
+        #[arg(long)]
+        start: Option<String>,
+
+        #[arg(long)]
+        due: Option<String>,
+
+        /// 1 (highest) to 9 (lowest), 0 to clear
+        #[arg(short, long)]
+        priority: Option<u8>,
+
+        /// Percent complete; 100 completes the task
+        #[arg(long)]
+        progress: Option<u8>,
+    },
+
+    /// Complete a task; a recurring task moves on to its next dates
+    Complete {
+        #[arg(short, long)]
+        task_id: String,
+    },
+
+    /// Open a completed or cancelled task again
+    Reopen {
+        #[arg(short, long)]
+        task_id: String,
+    },
+
+    /// Delete a task
+    Delete {
+        #[arg(short, long)]
+        task_id: String,
+    },
+
+    /// Import the VTODOs of .ics files into a calendar
+    Import {
+        #[arg(short, long)]
+        calendar_id: String,
+
+        #[arg(required = true)]
+        files: Vec<PathBuf>,
+    },
+
+    /// Export a calendar's tasks as VTODOs
+    Export {
+        #[arg(short, long)]
+        calendar_id: String,
+
+        /// Leave out completed and cancelled tasks
+        #[arg(long)]
+        open: bool,
+
+        /// Write to this file instead of printing
+        #[arg(short, long)]
+        out: Option<PathBuf>,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum JournalCommands {
+    /// Write a journal entry
+    Create {
+        #[arg(short, long)]
+        calendar_id: String,
+
+        /// Defaults to today
+        #[arg(short, long)]
+        date: Option<String>,
+
+        #[arg(short, long)]
+        title: String,
+
+        #[arg(short, long, default_value = "")]
+        body: String,
+
+        #[command(flatten)]
+        link: JournalLinkArgs,
+    },
+
+    /// List a calendar's journal entries by date
+    List {
+        #[arg(short, long)]
+        calendar_id: String,
+
+        #[arg(long)]
+        from: Option<String>,
+
+        #[arg(long)]
+        to: Option<String>,
+    },
+
+    /// Show a journal entry
+    Show {
+        #[arg(short, long)]
+        journal_id: String,
+    },
+
+    /// List the entries about an event or series
+    ForEvent {
+        #[arg(short, long)]
+        event_id: String,
+    },
+
+    /// Change a journal entry; an empty --event unlinks it
+    Update {
+        #[arg(short, long)]
+        journal_id: String,
+
+        #[arg(short, long)]
+        date: Option<String>,
+
+        #[arg(short, long)]
+        title: Option<String>,
+
+        #[arg(short, long)]
+        body: Option<String>,
+
+        #[command(flatten)]
+        link: JournalLinkArgs,
+    },
+
+    /// Delete a journal entry
+    Delete {
+        #[arg(short, long)]
+        journal_id: String,
+    },
+
+    /// Find entries containing every word of a query, best match first
+    Search {
+        query: String,
+
+        #[arg(short, long)]
+        calendar_id: Option<String>,
+    },
+
+    /// Import the VJOURNALs of .ics files into a calendar
+    Import {
+        #[arg(short, long)]
+        calendar_id: String,
+
+        #[arg(required = true)]
+        files: Vec<PathBuf>,
+    },
+
+    /// Export a calendar's journal as VJOURNALs
+    Export {
+        #[arg(short, long)]
+        calendar_id: String,
+
+        #[arg(long)]
+        from: Option<String>,
+
+        #[arg(long)]
+        to: Option<String>,
+
+        /// Write to this file instead of printing
+        #[arg(short, long)]
+        out: Option<PathBuf>,
+    },
+}
+
+#[derive(Args)]
+pub struct JournalLinkArgs {
+    /// The event or series the entry is about
+    #[arg(short, long)]
+    pub event: Option<String>,
+
+    /// Narrows the link to the occurrence of a series starting here
+    #[arg(long, requires = "event")]
+    pub occurrence: Option<String>,
+}
+
+#[derive(Args)]
+pub struct RepeatArgs {
+    /// daily, weekly, monthly or yearly
+    #[arg(long)]
+    pub repeat: Option<String>,
+
+    #[arg(long, default_value_t = 1, requires = "repeat")]
+    pub interval: u32,
+
+    #[arg(long, requires = "repeat")]
+    pub until: Option<String>,
+}
+
+#[derive(Args)]
+pub struct ReminderArgs {
+    /// Minutes before the start
+    #[arg(short, long, conflicts_with = "at", required_unless_present = "at")]
+    pub minutes_before: Option<i64>,
+
+    /// Fire at a fixed time instead
+    #[arg(long)]
+    pub at: Option<String>,
+
+    /// display, email or command
+    #[arg(long, default_value = "display")]
+    pub action: String,
+
+    /// Email recipient or command line
+    #[arg(long)]
+    pub target: Option<String>,
+
+    #[arg(short, long)]
+    pub description: Option<String>,
+
+    /// Additional firings after the first
+    #[arg(long, default_value_t = 0, requires = "repeat_every")]
+    pub repeat: u32,
+
+    /// Minutes between repeats
+    #[arg(long)]
+    pub repeat_every: Option<i64>,
+}
+
+#[derive(Args)]
+pub struct AttendeeArgs {
+    #[arg(long)]
+    pub email: String,
+
+    #[arg(short, long)]
+    pub name: Option<String>,
+
+    /// chair, req-participant, opt-participant or non-participant
+    #[arg(short, long, default_value = "req-participant")]
+    pub role: String,
+
+    /// Ask the attendee to reply
+    #[arg(long)]
+    pub rsvp: bool,
+}
+
+#[derive(Args)]
+pub struct PlaceArgs {
+    #[arg(short, long)]
+    pub location: Option<String>,
+
+    #[arg(long)]
+    pub url: Option<String>,
+
+    /// Coordinates as LAT,LON
+    #[arg(long, allow_hyphen_values = true)]
+    pub geo: Option<String>,
+}
+
+impl PlaceArgs {
+    pub fn is_empty(&self) -> bool {
+        self.location.is_none() && self.url.is_none() && self.geo.is_none()
+    }
+}
+
+#[derive(Args)]
+pub struct StatusArgs {
+    /// tentative, confirmed or cancelled
+    #[arg(long)]
+    pub status: Option<String>,
+
+    /// opaque, or transparent for time that stays free
+    #[arg(long)]
+    pub transparency: Option<String>,
+}
+
+impl StatusArgs {
+    pub fn is_empty(&self) -> bool {
+        self.status.is_none() && self.transparency.is_none()
+    }
 }
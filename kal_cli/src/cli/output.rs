@@ -0,0 +1,240 @@
+use colored::Colorize;
+use kal_core::{
+    domain::search::{HIGHLIGHT_END, HIGHLIGHT_START},
+    infrastructure::dto::{
+        AuditEntryDto, BusyPeriodDto, CalendarDto, FieldChangeDto, HistoryChangeDto,
+        HistoryEntryDto, JournalEntryDto, SearchHitDto, TagDto, TaskDto, TrashedItemDto,
+    },
+};
+
+pub fn success(message: &str) {
+    println!("{} {}", "✓".green(), message);
+}
+
+pub fn warning(message: &str) {
+    eprintln!("{} {}", "warning:".yellow().bold(), message);
+}
+
+pub fn error(message: &str) {
+    eprintln!("{} {}", "error:".red().bold(), message);
+}
+
+pub fn calendars(calendars: &[CalendarDto]) {
+    if calendars.is_empty() {
+        println!("No calendars");
+        return;
+    }
+
+    for calendar in calendars {
+        let kind = if calendar.subscription_url.is_some() { " (subscribed)" } else { "" };
+        println!(
+            "{}  {}{}",
+            calendar.id.to_string().dimmed(),
+            calendar.name.bold(),
+            kind.dimmed(),
+        );
+        if let Some(description) = &calendar.description {
+            println!("    {description}");
+        }
+    }
+}
+
+pub fn tags(tags: &[TagDto]) {
+    if tags.is_empty() {
+        println!("No tags");
+        return;
+    }
+
+    for tag in tags {
+        println!("{}  {}", tag.name.bold(), tag.count.to_string().dimmed());
+    }
+}
+
+pub fn trash(items: &[TrashedItemDto]) {
+    if items.is_empty() {
+        println!("Trash is empty");
+        return;
+    }
+
+    for item in items {
+        println!(
+            "{}  {}  {}  {}",
+            item.id.to_string().dimmed(),
+            item.deleted_at.format("%Y-%m-%d %H:%M"),
+            item.kind.to_lowercase(),
+            item.title.bold(),
+        );
+    }
+}
+
+pub fn history(entries: &[HistoryEntryDto]) {
+    if entries.is_empty() {
+        println!("Nothing to undo");
+        return;
+    }
+
+    for entry in entries {
+        println!(
+            "{}  {}",
+            entry.recorded_at.format("%Y-%m-%d %H:%M"),
+            entry.command.bold(),
+        );
+        for change in &entry.changes {
+            println!("    {}", change_summary(change));
+        }
+    }
+}
+
+/// `deleted event "Standup"`, for history listings and undo messages.
+pub fn change_summary(change: &HistoryChangeDto) -> String {
+    match &change.title {
+        Some(title) => format!("{} {} \"{}\"", change.action, change.kind.to_lowercase(), title),
+        None => format!("{} {}", change.action, change.kind.to_lowercase()),
+    }
+}
+
+pub fn audit(entries: &[AuditEntryDto]) {
+    if entries.is_empty() {
+        println!("No changes recorded");
+        return;
+    }
+
+    for entry in entries {
+        let occurrence = entry
+            .occurrence
+            .map(|occurrence| format!(" (occurrence {})", occurrence.format("%Y-%m-%d %H:%M")))
+            .unwrap_or_default();
+        println!(
+            "{}  {}  {}  {}{}  {}",
+            entry.recorded_at.format("%Y-%m-%d %H:%M"),
+            entry.actor.bold(),
+            entry.command,
+            entry.event,
+            occurrence,
+            entry.object_id.to_string().dimmed(),
+        );
+        for change in &entry.changes {
+            println!("    {}", field_change(change));
+        }
+    }
+}
+
+/// `title: "Standup" → "Sync"`, or `attendees: added "bob@example.com"`.
+fn field_change(change: &FieldChangeDto) -> String {
+    match (&change.from, &change.to) {
+        (Some(from), Some(to)) => format!("{}: \"{}\" → \"{}\"", change.field, from, to),
+        (None, Some(to)) => format!("{}: added \"{}\"", change.field, to),
+        (Some(from), None) => format!("{}: removed \"{}\"", change.field, from),
+        (None, None) => change.field.clone(),
+    }
+}
+
+pub fn busy_periods(periods: &[BusyPeriodDto]) {
+    if periods.is_empty() {
+        println!("Free");
+        return;
+    }
+
+    for period in periods {
+        let kind = if period.kind == "BUSY" { period.kind.red() } else { period.kind.yellow() };
+        println!(
+            "{} - {}  {}  {}",
+            period.starts_at.format("%Y-%m-%d %H:%M"),
+            period.ends_at.format("%Y-%m-%d %H:%M"),
+            kind,
+            period.event_id.to_string().dimmed(),
+        );
+    }
+}
+
+pub fn tasks(tasks: &[TaskDto]) {
+    if tasks.is_empty() {
+        println!("No tasks");
+        return;
+    }
+
+    for task in tasks {
+        let done = task.status == "COMPLETED" || task.status == "CANCELLED";
+        let mark = if done { "x".green() } else { " ".normal() };
+        let title = if done { task.title.dimmed() } else { task.title.bold() };
+        let due = match task.due_at {
+            Some(due_at) if task.is_overdue => format!("  due {}", due_at.format("%Y-%m-%d %H:%M")).red(),
+            Some(due_at) => format!("  due {}", due_at.format("%Y-%m-%d %H:%M")).normal(),
+            None => "".normal(),
+        };
+        let priority = task.priority.map(|p| format!("  !{p}")).unwrap_or_default();
+        let progress = match task.percent_complete {
+            0 | 100 => String::new(),
+            percent => format!("  {percent}%"),
+        };
+        let repeat = if task.recurrence.is_some() { "  ↻" } else { "" };
+
+        println!(
+            "{}  [{mark}] {title}{due}{}{progress}{repeat}",
+            task.id.to_string().dimmed(),
+            priority.yellow(),
+        );
+    }
+}
+
+pub fn journal(entries: &[JournalEntryDto]) {
+    if entries.is_empty() {
+        println!("No journal entries");
+        return;
+    }
+
+    for entry in entries {
+        let link = match &entry.link {
+            Some(link) => match link.original_starts_at {
+                Some(original) => format!("  ↗ {} @ {}", link.event_id, original.format("%Y-%m-%d %H:%M")),
+                None => format!("  ↗ {}", link.event_id),
+            },
+            None => String::new(),
+        };
+
+        println!(
+            "{}  {}  {}{}",
+            entry.id.to_string().dimmed(),
+            entry.date,
+            entry.title.bold(),
+            link.dimmed(),
+        );
+        for line in entry.body.lines() {
+            println!("    {line}");
+        }
+    }
+}
+
+pub fn search_hits(hits: &[SearchHitDto]) {
+    if hits.is_empty() {
+        println!("No matches");
+        return;
+    }
+
+    for hit in hits {
+        let repeat = if hit.kind == "SERIES" { "  ↻" } else { "" };
+        println!(
+            "{}  {}  {}{repeat}",
+            hit.event_id.to_string().dimmed(),
+            hit.starts_at.format("%Y-%m-%d %H:%M"),
+            hit.title.bold(),
+        );
+        println!("    {}", highlighted(&hit.snippet));
+    }
+}
+
+/// Renders the snippet's marked words in yellow.
+fn highlighted(snippet: &str) -> String {
+    let mut out = String::new();
+    let mut rest = snippet;
+
+    while let Some((before, after)) = rest.split_once(HIGHLIGHT_START) {
+        let (marked, after) = after.split_once(HIGHLIGHT_END).unwrap_or((after, ""));
+        out.push_str(before);
+        out.push_str(&marked.yellow().bold().to_string());
+        rest = after;
+    }
+
+    out.push_str(rest);
+    out.replace('\n', " ")
+}
@@ -1,5 +1,88 @@
+use std::path::PathBuf;
+
+use clap::Parser;
+use kal_core::infrastructure::persistence::Database;
+use sqlx::SqlitePool;
+
+use cli::{commands::{self, backend::Backend}, output, Cli, Commands};
+
 mod cli;
 
-fn main() {
-    todo!();
+#[tokio::main]
+async fn main() {
+    let cli = Cli::parse();
+
+    if let Err(e) = run(cli).await {
+        output::error(&e.to_string());
+        std::process::exit(1);
+    }
+}
+
+async fn run(cli: Cli) -> commands::CliResult {
+    match cli.command {
+        Commands::Calendar { action } => {
+            commands::calendar::run(action, Backend::open().await?).await
+        }
+        Commands::Event { action } => {
+            commands::event::run(action, Backend::open().await?).await
+        }
+        Commands::Recurring { action } => {
+            commands::recurring::run(action, Backend::open().await?).await
+        }
+        Commands::Itip { action } => {
+            commands::itip::run(action, Backend::open().await?).await
+        }
+        Commands::Tag { action } => {
+            commands::tag::run(action, Backend::open().await?).await
+        }
+        Commands::Trash { action } => {
+            commands::trash::run(action, Backend::open().await?).await
+        }
+        Commands::Task { action } => {
+            commands::task::run(action, Backend::open().await?).await
+        }
+        Commands::Journal { action } => {
+            commands::journal::run(action, Backend::open().await?).await
+        }
+        Commands::Search { query, calendar_id, from, to, limit } => {
+            commands::search::run(query, calendar_id, from.zip(to), limit, Backend::open().await?).await
+        }
+        Commands::Undo => commands::history::undo(Backend::open().await?).await,
+        Commands::Redo => commands::history::redo(Backend::open().await?).await,
+        Commands::History { limit } => commands::history::list(limit, Backend::open().await?).await,
+        Commands::Server { bind, username, password } => {
+            commands::server::run(bind, username.zip(password), connect().await?).await
+        }
+        Commands::Api { bind, trust_user_header } => {
+            commands::api::run(bind, trust_user_header, connect().await?).await
+        }
+        Commands::Daemon => commands::daemon::run(connect().await?).await,
+        Commands::Remind { file, command } => {
+            commands::remind::run(file, command, connect().await?).await
+        }
+    }
+}
+
+/// Opens `$KAL_DATABASE`, or `kal.db` in the user's data directory,
+/// migrating it to the current schema.
+async fn connect() -> commands::CliResult<SqlitePool> {
+    let path = match std::env::var_os("KAL_DATABASE") {
+        Some(path) => PathBuf::from(path),
+        None => data_dir()?.join("kal.db"),
+    };
+
+    Ok(Database::open(path).await?.into_pool())
+}
+
+/// `$KAL_OUTBOX`, or `outbox` in the user's data directory: where
+/// scheduling messages are written for something else to send.
+fn outbox() -> commands::CliResult<PathBuf> {
+    match std::env::var_os("KAL_OUTBOX") {
+        Some(path) => Ok(PathBuf::from(path)),
+        None => Ok(data_dir()?.join("outbox")),
+    }
+}
+
+fn data_dir() -> commands::CliResult<PathBuf> {
+    Ok(dirs::data_dir().ok_or("no data directory for this platform")?.join("kal"))
 }
@@ -7,6 +7,12 @@ pub struct ArchiveCalendarCommand {
     id: CalendarId,
 }
 
+impl ArchiveCalendarCommand {
+    pub fn new(id: CalendarId) -> Self {
+        Self { id }
+    }
+}
+
 pub struct ArchiveCalendarHandler<R: CalendarRepository> {
     repository: R,
 }
@@ -12,6 +12,12 @@ pub struct CreateCalendarCommand {
     description: Option<String>,
 }
 
+impl CreateCalendarCommand {
+    pub fn new(name: String, description: Option<String>) -> Self {
+        Self { name, description }
+    }
+}
+
 
 pub struct CreateCalendarHandler<R: CalendarRepository> {
     repository: R,
@@ -1,3 +1,5 @@
+use chrono::Utc;
+
 use crate::{
     application::error::ApplicationError,
     domain::{repository::CalendarRepository, value_objects::CalendarId}
@@ -6,6 +8,13 @@ use crate::{
 pub struct DeleteCalendarCommand {
     calendar_id: CalendarId,
 }
+
+impl DeleteCalendarCommand {
+    pub fn new(calendar_id: CalendarId) -> Self {
+        Self { calendar_id }
+    }
+}
+
 pub struct DeleteCalendarHandler<R: CalendarRepository> {
     repository: R,
 }
@@ -19,12 +28,14 @@ impl<R: CalendarRepository> DeleteCalendarHandler<R> {
         &self,
         command: DeleteCalendarCommand,
     ) -> Result<(), ApplicationError> {
-        let _calendar = self
+        self
             .repository
             .find_by_id(&command.calendar_id)
-            .await?;
+            .await?
+            .ok_or(ApplicationError::CalendarNotFound)?;
 
-        self.repository.delete(&command.calendar_id).await?;
+        // Its events and series go to the trash with it
+        self.repository.trash(&command.calendar_id, Utc::now()).await?;
 
         Ok(())
     }
@@ -1,14 +1,18 @@
 pub mod create_calendar;
 pub mod delete_calendar;
+pub mod restore_trashed_calendar;
 pub mod archive_calendar;
 pub mod unarchive_calendar;
 pub mod rename_calendar;
 pub mod update_calendar_description;
+pub mod subscribe_calendar;
 
 // Re-exports for convenience
 pub use create_calendar::{CreateCalendarCommand, CreateCalendarHandler};
 pub use delete_calendar::{DeleteCalendarCommand, DeleteCalendarHandler};
+pub use restore_trashed_calendar::{RestoreTrashedCalendarCommand, RestoreTrashedCalendarHandler};
 pub use archive_calendar::{ArchiveCalendarCommand, ArchiveCalendarHandler};
 pub use unarchive_calendar::{UnarchiveCalendarCommand, UnarchiveCalendarHandler};
 pub use rename_calendar::{RenameCalendarCommand, RenameCalendarHandler};
 pub use update_calendar_description::{UpdateCalendarDescriptionCommand, UpdateCalendarDescriptionHandler};
+pub use subscribe_calendar::{SubscribeCalendarCommand, SubscribeCalendarHandler};
@@ -8,6 +8,12 @@ pub struct RenameCalendarCommand {
     new_name: String,
 }
 
+impl RenameCalendarCommand {
+    pub fn new(calendar_id: CalendarId, new_name: String) -> Self {
+        Self { calendar_id, new_name }
+    }
+}
+
 pub struct RenameCalendarHandler<R: CalendarRepository> {
     repository: R,
 }
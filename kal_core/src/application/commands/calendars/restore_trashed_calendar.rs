@@ -0,0 +1,36 @@
+use crate::{
+    application::error::ApplicationError,
+    domain::{repository::CalendarRepository, value_objects::CalendarId}
+};
+
+pub struct RestoreTrashedCalendarCommand {
+    id: CalendarId,
+}
+
+impl RestoreTrashedCalendarCommand {
+    pub fn new(id: CalendarId) -> Self {
+        Self { id }
+    }
+}
+
+/// Takes a calendar out of the trash, along with the events and series
+/// that were trashed with it.
+pub struct RestoreTrashedCalendarHandler<R: CalendarRepository> {
+    repository: R,
+}
+
+impl<R: CalendarRepository> RestoreTrashedCalendarHandler<R> {
+    pub fn new(repository: R) -> Self {
+        Self { repository }
+    }
+
+    pub async fn handle(
+        &self,
+        command: RestoreTrashedCalendarCommand,
+    ) -> Result<(), ApplicationError> {
+        self.repository
+            .restore(&command.id)
+            .await
+            .map_err(ApplicationError::from_trash_lookup)
+    }
+}
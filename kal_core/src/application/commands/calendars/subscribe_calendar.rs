@@ -0,0 +1,53 @@
+use crate::{
+    application::error::ApplicationError,
+    domain::{
+        calendar::Calendar,
+        repository::CalendarRepository,
+        value_objects::{CalendarId, Subscription}
+    }
+};
+
+pub struct SubscribeCalendarCommand {
+    name: String,
+    description: Option<String>,
+    source_url: String,
+    refresh_interval: chrono::Duration,
+}
+
+impl SubscribeCalendarCommand {
+    pub fn new(
+        name: String,
+        description: Option<String>,
+        source_url: String,
+        refresh_interval: chrono::Duration,
+    ) -> Self {
+        Self { name, description, source_url, refresh_interval }
+    }
+}
+
+
+pub struct SubscribeCalendarHandler<R: CalendarRepository> {
+    repository: R,
+}
+
+impl<R: CalendarRepository> SubscribeCalendarHandler<R> {
+    pub fn new(repository: R) -> Self {
+        Self { repository }
+    }
+
+    /// Creates a read-only calendar backed by a remote feed. Its content is
+    /// filled on the first refresh.
+    pub async fn handle(
+        &self,
+        command: SubscribeCalendarCommand,
+    ) -> Result<CalendarId, ApplicationError> {
+        let subscription = Subscription::new(command.source_url, command.refresh_interval)?;
+        let calendar = Calendar::subscribed(command.name, command.description, subscription)?;
+
+        let calendar_id = *calendar.calendar_id();
+
+        self.repository.save(&calendar).await?;
+
+        Ok(calendar_id)
+    }
+}
@@ -7,6 +7,12 @@ pub struct UnarchiveCalendarCommand {
     id: CalendarId,
 }
 
+impl UnarchiveCalendarCommand {
+    pub fn new(id: CalendarId) -> Self {
+        Self { id }
+    }
+}
+
 pub struct UnarchiveCalendarHandler<R: CalendarRepository> {
     repository: R,
 }
@@ -8,6 +8,12 @@ pub struct UpdateCalendarDescriptionCommand {
     new_description: Option<String>,
 }
 
+impl UpdateCalendarDescriptionCommand {
+    pub fn new(id: CalendarId, new_description: Option<String>) -> Self {
+        Self { id, new_description }
+    }
+}
+
 pub struct UpdateCalendarDescriptionHandler<R: CalendarRepository> {
     repository: R,
 }
@@ -0,0 +1,49 @@
+use crate::{
+    application::{commands::guards::ensure_writable, error::ApplicationError},
+    domain::{
+        attendee::Attendee,
+        repository::{CalendarRepository, EventRepository},
+        value_objects::EventId
+    }
+};
+
+pub struct AddEventAttendeeCommand {
+    id: EventId,
+    attendee: Attendee,
+}
+
+impl AddEventAttendeeCommand {
+    pub fn new(id: EventId, attendee: Attendee) -> Self {
+        Self { id, attendee }
+    }
+}
+
+pub struct AddEventAttendeeHandler<R: EventRepository, C: CalendarRepository> {
+    repository: R,
+    calendars: C,
+}
+
+impl<R: EventRepository, C: CalendarRepository> AddEventAttendeeHandler<R, C> {
+    pub fn new(repository: R, calendars: C) -> Self {
+        Self { repository, calendars }
+    }
+
+    pub async fn handle(
+        &self,
+        command: AddEventAttendeeCommand,
+    ) -> Result<(), ApplicationError> {
+        let mut event = self
+            .repository
+            .find_by_id(&command.id)
+            .await?
+            .ok_or(ApplicationError::EventNotFound)?;
+
+        ensure_writable(&self.calendars, event.calendar_id()).await?;
+
+        event.add_attendee(command.attendee)?;
+
+        self.repository.save(&event).await?;
+
+        Ok(())
+    }
+}
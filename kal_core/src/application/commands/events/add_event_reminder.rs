@@ -0,0 +1,88 @@
+use chrono::Duration;
+
+use crate::{
+    application::{commands::guards::ensure_writable, error::ApplicationError},
+    domain::{
+        reminder::{Reminder, ReminderAction, ReminderTrigger},
+        repository::{CalendarRepository, EventRepository},
+        value_objects::{EventId, ReminderId}
+    }
+};
+
+pub struct AddEventReminderCommand {
+    id: EventId,
+    trigger: ReminderTrigger,
+    action: ReminderAction,
+    description: Option<String>,
+    repeat: u32,
+    repeat_interval: Option<Duration>,
+    reminder_id: Option<ReminderId>,
+}
+
+impl AddEventReminderCommand {
+    pub fn new(
+        id: EventId,
+        trigger: ReminderTrigger,
+        action: ReminderAction,
+        description: Option<String>,
+        repeat: u32,
+        repeat_interval: Option<Duration>,
+    ) -> Self {
+        Self {
+            id,
+            trigger,
+            action,
+            description,
+            repeat,
+            repeat_interval,
+            reminder_id: None,
+        }
+    }
+
+    /// Adds the reminder under a caller-chosen id, e.g. the UID of an
+    /// imported VALARM, instead of a fresh one.
+    pub fn with_reminder_id(mut self, reminder_id: ReminderId) -> Self {
+        self.reminder_id = Some(reminder_id);
+        self
+    }
+}
+
+pub struct AddEventReminderHandler<R: EventRepository, C: CalendarRepository> {
+    repository: R,
+    calendars: C,
+}
+
+impl<R: EventRepository, C: CalendarRepository> AddEventReminderHandler<R, C> {
+    pub fn new(repository: R, calendars: C) -> Self {
+        Self { repository, calendars }
+    }
+
+    pub async fn handle(
+        &self,
+        command: AddEventReminderCommand,
+    ) -> Result<ReminderId, ApplicationError> {
+        let mut event = self
+            .repository
+            .find_by_id(&command.id)
+            .await?
+            .ok_or(ApplicationError::EventNotFound)?;
+
+        ensure_writable(&self.calendars, event.calendar_id()).await?;
+
+        let reminder = Reminder::with_id(
+            command.reminder_id.unwrap_or_default(),
+            command.trigger,
+            command.action,
+            command.description,
+            command.repeat,
+            command.repeat_interval,
+        )?;
+        let reminder_id = *reminder.reminder_id();
+
+        event.add_reminder(reminder);
+
+        self.repository.save(&event).await?;
+
+        Ok(reminder_id)
+    }
+}
@@ -0,0 +1,49 @@
+use crate::{
+    application::{commands::guards::ensure_writable, error::ApplicationError},
+    domain::{
+        repository::{CalendarRepository, EventRepository},
+        tag::Tag,
+        value_objects::EventId
+    }
+};
+
+pub struct AddEventTagCommand {
+    id: EventId,
+    tag: Tag,
+}
+
+impl AddEventTagCommand {
+    pub fn new(id: EventId, tag: Tag) -> Self {
+        Self { id, tag }
+    }
+}
+
+pub struct AddEventTagHandler<R: EventRepository, C: CalendarRepository> {
+    repository: R,
+    calendars: C,
+}
+
+impl<R: EventRepository, C: CalendarRepository> AddEventTagHandler<R, C> {
+    pub fn new(repository: R, calendars: C) -> Self {
+        Self { repository, calendars }
+    }
+
+    pub async fn handle(
+        &self,
+        command: AddEventTagCommand,
+    ) -> Result<(), ApplicationError> {
+        let mut event = self
+            .repository
+            .find_by_id(&command.id)
+            .await?
+            .ok_or(ApplicationError::EventNotFound)?;
+
+        ensure_writable(&self.calendars, event.calendar_id()).await?;
+
+        event.add_tag(command.tag);
+
+        self.repository.save(&event).await?;
+
+        Ok(())
+    }
+}
@@ -1,9 +1,15 @@
+use chrono::Utc;
+
 use crate::{
-    application::error::ApplicationError,
+    application::{commands::guards::ensure_writable, error::ApplicationError},
     domain::{
         event::Event,
-        repository::EventRepository,
-        value_objects::{CalendarId, EventColor, EventId, TimeRange}
+        repository::{CalendarRepository, EventRepository},
+        tag::Tag,
+        value_objects::{
+            validate_url, CalendarId, EventColor, EventId, EventStatus, GeoPoint, TimeRange,
+            Transparency,
+        }
     }
 };
 
@@ -14,29 +20,108 @@ pub struct CreateEventCommand {
     time_range: TimeRange,
     color: EventColor,
     is_all_day: bool,
+    event_id: Option<EventId>,
+    location: Option<String>,
+    url: Option<String>,
+    geo: Option<GeoPoint>,
+    tags: Vec<Tag>,
+    status: EventStatus,
+    transparency: Transparency,
+}
+
+impl CreateEventCommand {
+    pub fn new(
+        calendar_id: CalendarId,
+        title: String,
+        description: Option<String>,
+        time_range: TimeRange,
+        color: EventColor,
+        is_all_day: bool,
+    ) -> Self {
+        Self {
+            calendar_id,
+            title,
+            description,
+            time_range,
+            color,
+            is_all_day,
+            event_id: None,
+            location: None,
+            url: None,
+            geo: None,
+            tags: Vec::new(),
+            status: EventStatus::default(),
+            transparency: Transparency::default(),
+        }
+    }
+
+    /// Creates the event under a caller-chosen id, e.g. one derived from
+    /// an iCalendar UID, instead of a fresh one.
+    pub fn with_event_id(mut self, event_id: EventId) -> Self {
+        self.event_id = Some(event_id);
+        self
+    }
+
+    pub fn with_place(
+        mut self,
+        location: Option<String>,
+        url: Option<String>,
+        geo: Option<GeoPoint>,
+    ) -> Self {
+        self.location = location.filter(|l| !l.trim().is_empty());
+        self.url = url;
+        self.geo = geo;
+        self
+    }
+
+    pub fn with_tags(mut self, tags: Vec<Tag>) -> Self {
+        self.tags = tags;
+        self
+    }
+
+    pub fn with_status(mut self, status: EventStatus, transparency: Transparency) -> Self {
+        self.status = status;
+        self.transparency = transparency;
+        self
+    }
 }
 
-pub struct CreateEventHandler<R: EventRepository> {
+pub struct CreateEventHandler<R: EventRepository, C: CalendarRepository> {
     repository: R,
+    calendars: C,
 }
 
-impl<R: EventRepository> CreateEventHandler<R> {
-    pub fn new(repository: R) -> Self {
-        Self { repository }
+impl<R: EventRepository, C: CalendarRepository> CreateEventHandler<R, C> {
+    pub fn new(repository: R, calendars: C) -> Self {
+        Self { repository, calendars }
     }
 
     pub async fn handle(
         &self,
         command: CreateEventCommand,
     ) -> Result<EventId, ApplicationError> {
-        let event = Event::new(
+        ensure_writable(&self.calendars, &command.calendar_id).await?;
+
+        if let Some(url) = &command.url {
+            validate_url(url)?;
+        }
+
+        let now = Utc::now();
+        let event = Event::with_id(
+            command.event_id.unwrap_or_else(EventId::new),
             command.calendar_id,
             command.title,
             command.description,
             command.time_range,
             command.color,
-            command.is_all_day
-        )?;
+            command.is_all_day,
+            command.status,
+            now,
+            now,
+        )?
+        .with_transparency(command.transparency)
+        .with_place(command.location, command.url, command.geo)
+        .with_tags(command.tags);
 
         let event_id = event.event_id().clone();
 
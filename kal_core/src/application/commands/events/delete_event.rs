@@ -1,31 +1,43 @@
+use chrono::Utc;
+
 use crate::{
-    application::error::ApplicationError,
-    domain::{repository::EventRepository, value_objects::EventId}
+    application::{commands::guards::ensure_writable, error::ApplicationError},
+    domain::{repository::{CalendarRepository, EventRepository}, value_objects::EventId}
 };
 
 pub struct DeleteEventCommand {
     id: EventId,
 }
 
-pub struct DeleteEventHandler<R: EventRepository> {
+impl DeleteEventCommand {
+    pub fn new(id: EventId) -> Self {
+        Self { id }
+    }
+}
+
+pub struct DeleteEventHandler<R: EventRepository, C: CalendarRepository> {
     repository: R,
+    calendars: C,
 }
 
-impl<R: EventRepository> DeleteEventHandler<R> {
-    pub fn new(repository: R) -> Self {
-        Self { repository }
+impl<R: EventRepository, C: CalendarRepository> DeleteEventHandler<R, C> {
+    pub fn new(repository: R, calendars: C) -> Self {
+        Self { repository, calendars }
     }
 
     pub async fn handle(
         &self,
         command: DeleteEventCommand,
     ) -> Result<(), ApplicationError> {
-        let _event = self
+        let event = self
             .repository
             .find_by_id(&command.id)
-            .await?;
+            .await?
+            .ok_or(ApplicationError::EventNotFound)?;
+
+        ensure_writable(&self.calendars, event.calendar_id()).await?;
 
-        self.repository.delete(&command.id).await?;
+        self.repository.trash(&command.id, Utc::now()).await?;
 
         Ok(())
     }
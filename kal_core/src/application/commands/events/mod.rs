@@ -2,17 +2,45 @@ pub mod create_event;
 pub mod delete_event;
 pub mod cancel_event;
 pub mod restore_event;
+pub mod restore_trashed_event;
 pub mod update_event_title;
 pub mod update_event_description;
 pub mod update_event_color;
 pub mod update_event_time_range;
+pub mod update_event_location;
+pub mod update_event_url;
+pub mod update_event_geo;
+pub mod update_event_status;
+pub mod update_event_transparency;
+pub mod add_event_reminder;
+pub mod remove_event_reminder;
+pub mod set_event_organizer;
+pub mod add_event_attendee;
+pub mod remove_event_attendee;
+pub mod update_event_attendee_status;
+pub mod add_event_tag;
+pub mod remove_event_tag;
 
 // Re-exports for convenience
 pub use create_event::{CreateEventCommand, CreateEventHandler};
 pub use delete_event::{DeleteEventCommand, DeleteEventHandler};
 pub use cancel_event::{CancelEventCommand, CancelEventHandler};
 pub use restore_event::{RestoreEventCommand, RestoreEventHandler};
+pub use restore_trashed_event::{RestoreTrashedEventCommand, RestoreTrashedEventHandler};
 pub use update_event_title::{UpdateEventTitleCommand, UpdateEventTitleHandler};
 pub use update_event_description::{UpdateEventDescriptionCommand, UpdateEventDescriptionHandler};
 pub use update_event_color::{UpdateEventColorCommand, UpdateEventColorHandler};
 pub use update_event_time_range::{UpdateEventTimeRangeCommand, UpdateEventTimeRangeHandler};
+pub use update_event_location::{UpdateEventLocationCommand, UpdateEventLocationHandler};
+pub use update_event_url::{UpdateEventUrlCommand, UpdateEventUrlHandler};
+pub use update_event_geo::{UpdateEventGeoCommand, UpdateEventGeoHandler};
+pub use update_event_status::{UpdateEventStatusCommand, UpdateEventStatusHandler};
+pub use update_event_transparency::{UpdateEventTransparencyCommand, UpdateEventTransparencyHandler};
+pub use add_event_reminder::{AddEventReminderCommand, AddEventReminderHandler};
+pub use remove_event_reminder::{RemoveEventReminderCommand, RemoveEventReminderHandler};
+pub use set_event_organizer::{SetEventOrganizerCommand, SetEventOrganizerHandler};
+pub use add_event_attendee::{AddEventAttendeeCommand, AddEventAttendeeHandler};
+pub use remove_event_attendee::{RemoveEventAttendeeCommand, RemoveEventAttendeeHandler};
+pub use update_event_attendee_status::{UpdateEventAttendeeStatusCommand, UpdateEventAttendeeStatusHandler};
+pub use add_event_tag::{AddEventTagCommand, AddEventTagHandler};
+pub use remove_event_tag::{RemoveEventTagCommand, RemoveEventTagHandler};
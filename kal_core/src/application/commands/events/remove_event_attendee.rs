@@ -0,0 +1,48 @@
+use crate::{
+    application::{commands::guards::ensure_writable, error::ApplicationError},
+    domain::{
+        repository::{CalendarRepository, EventRepository},
+        value_objects::EventId
+    }
+};
+
+pub struct RemoveEventAttendeeCommand {
+    id: EventId,
+    email: String,
+}
+
+impl RemoveEventAttendeeCommand {
+    pub fn new(id: EventId, email: String) -> Self {
+        Self { id, email }
+    }
+}
+
+pub struct RemoveEventAttendeeHandler<R: EventRepository, C: CalendarRepository> {
+    repository: R,
+    calendars: C,
+}
+
+impl<R: EventRepository, C: CalendarRepository> RemoveEventAttendeeHandler<R, C> {
+    pub fn new(repository: R, calendars: C) -> Self {
+        Self { repository, calendars }
+    }
+
+    pub async fn handle(
+        &self,
+        command: RemoveEventAttendeeCommand,
+    ) -> Result<(), ApplicationError> {
+        let mut event = self
+            .repository
+            .find_by_id(&command.id)
+            .await?
+            .ok_or(ApplicationError::EventNotFound)?;
+
+        ensure_writable(&self.calendars, event.calendar_id()).await?;
+
+        event.remove_attendee(&command.email)?;
+
+        self.repository.save(&event).await?;
+
+        Ok(())
+    }
+}
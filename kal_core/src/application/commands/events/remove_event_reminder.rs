@@ -0,0 +1,48 @@
+use crate::{
+    application::{commands::guards::ensure_writable, error::ApplicationError},
+    domain::{
+        repository::{CalendarRepository, EventRepository},
+        value_objects::{EventId, ReminderId}
+    }
+};
+
+pub struct RemoveEventReminderCommand {
+    id: EventId,
+    reminder_id: ReminderId,
+}
+
+impl RemoveEventReminderCommand {
+    pub fn new(id: EventId, reminder_id: ReminderId) -> Self {
+        Self { id, reminder_id }
+    }
+}
+
+pub struct RemoveEventReminderHandler<R: EventRepository, C: CalendarRepository> {
+    repository: R,
+    calendars: C,
+}
+
+impl<R: EventRepository, C: CalendarRepository> RemoveEventReminderHandler<R, C> {
+    pub fn new(repository: R, calendars: C) -> Self {
+        Self { repository, calendars }
+    }
+
+    pub async fn handle(
+        &self,
+        command: RemoveEventReminderCommand,
+    ) -> Result<(), ApplicationError> {
+        let mut event = self
+            .repository
+            .find_by_id(&command.id)
+            .await?
+            .ok_or(ApplicationError::EventNotFound)?;
+
+        ensure_writable(&self.calendars, event.calendar_id()).await?;
+
+        event.remove_reminder(&command.reminder_id)?;
+
+        self.repository.save(&event).await?;
+
+        Ok(())
+    }
+}
@@ -1,19 +1,26 @@
 use crate::{
-    application::error::ApplicationError,
-    domain::{repository::EventRepository, value_objects::EventId}
+    application::{commands::guards::ensure_writable, error::ApplicationError},
+    domain::{repository::{CalendarRepository, EventRepository}, value_objects::EventId}
 };
 
 pub struct RestoreEventCommand {
     id: EventId,
 }
 
-pub struct RestoreEventHandler<R: EventRepository> {
+impl RestoreEventCommand {
+    pub fn new(id: EventId) -> Self {
+        Self { id }
+    }
+}
+
+pub struct RestoreEventHandler<R: EventRepository, C: CalendarRepository> {
     repository: R,
+    calendars: C,
 }
 
-impl<R: EventRepository> RestoreEventHandler<R> {
-    pub fn new(repository: R) -> Self {
-        Self { repository }
+impl<R: EventRepository, C: CalendarRepository> RestoreEventHandler<R, C> {
+    pub fn new(repository: R, calendars: C) -> Self {
+        Self { repository, calendars }
     }
 
     pub async fn handle(
@@ -26,6 +33,8 @@ impl<R: EventRepository> RestoreEventHandler<R> {
             .await?
             .ok_or(ApplicationError::EventNotFound)?;
 
+        ensure_writable(&self.calendars, event.calendar_id()).await?;
+
         event.restore();
 
         self.repository.save(&event).await?;
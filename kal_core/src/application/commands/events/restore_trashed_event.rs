@@ -0,0 +1,45 @@
+use crate::{
+    application::{commands::guards::ensure_restorable, error::ApplicationError},
+    domain::{repository::{CalendarRepository, EventRepository}, value_objects::EventId}
+};
+
+pub struct RestoreTrashedEventCommand {
+    id: EventId,
+}
+
+impl RestoreTrashedEventCommand {
+    pub fn new(id: EventId) -> Self {
+        Self { id }
+    }
+}
+
+pub struct RestoreTrashedEventHandler<R: EventRepository, C: CalendarRepository> {
+    repository: R,
+    calendars: C,
+}
+
+impl<R: EventRepository, C: CalendarRepository> RestoreTrashedEventHandler<R, C> {
+    pub fn new(repository: R, calendars: C) -> Self {
+        Self { repository, calendars }
+    }
+
+    pub async fn handle(
+        &self,
+        command: RestoreTrashedEventCommand,
+    ) -> Result<(), ApplicationError> {
+        let event = self
+            .repository
+            .find_trashed()
+            .await?
+            .into_iter()
+            .find(|event| *event.event_id() == command.id)
+            .ok_or(ApplicationError::NotInTrash)?;
+
+        ensure_restorable(&self.calendars, event.calendar_id()).await?;
+
+        self.repository
+            .restore(&command.id)
+            .await
+            .map_err(ApplicationError::from_trash_lookup)
+    }
+}
@@ -0,0 +1,49 @@
+use crate::{
+    application::{commands::guards::ensure_writable, error::ApplicationError},
+    domain::{
+        attendee::Organizer,
+        repository::{CalendarRepository, EventRepository},
+        value_objects::EventId
+    }
+};
+
+pub struct SetEventOrganizerCommand {
+    id: EventId,
+    organizer: Option<Organizer>,
+}
+
+impl SetEventOrganizerCommand {
+    pub fn new(id: EventId, organizer: Option<Organizer>) -> Self {
+        Self { id, organizer }
+    }
+}
+
+pub struct SetEventOrganizerHandler<R: EventRepository, C: CalendarRepository> {
+    repository: R,
+    calendars: C,
+}
+
+impl<R: EventRepository, C: CalendarRepository> SetEventOrganizerHandler<R, C> {
+    pub fn new(repository: R, calendars: C) -> Self {
+        Self { repository, calendars }
+    }
+
+    pub async fn handle(
+        &self,
+        command: SetEventOrganizerCommand,
+    ) -> Result<(), ApplicationError> {
+        let mut event = self
+            .repository
+            .find_by_id(&command.id)
+            .await?
+            .ok_or(ApplicationError::EventNotFound)?;
+
+        ensure_writable(&self.calendars, event.calendar_id()).await?;
+
+        event.set_organizer(command.organizer);
+
+        self.repository.save(&event).await?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,50 @@
+use crate::{
+    application::{commands::guards::ensure_writable, error::ApplicationError},
+    domain::{
+        attendee::ParticipationStatus,
+        repository::{CalendarRepository, EventRepository},
+        value_objects::EventId
+    }
+};
+
+pub struct UpdateEventAttendeeStatusCommand {
+    id: EventId,
+    email: String,
+    status: ParticipationStatus,
+}
+
+impl UpdateEventAttendeeStatusCommand {
+    pub fn new(id: EventId, email: String, status: ParticipationStatus) -> Self {
+        Self { id, email, status }
+    }
+}
+
+pub struct UpdateEventAttendeeStatusHandler<R: EventRepository, C: CalendarRepository> {
+    repository: R,
+    calendars: C,
+}
+
+impl<R: EventRepository, C: CalendarRepository> UpdateEventAttendeeStatusHandler<R, C> {
+    pub fn new(repository: R, calendars: C) -> Self {
+        Self { repository, calendars }
+    }
+
+    pub async fn handle(
+        &self,
+        command: UpdateEventAttendeeStatusCommand,
+    ) -> Result<(), ApplicationError> {
+        let mut event = self
+            .repository
+            .find_by_id(&command.id)
+            .await?
+            .ok_or(ApplicationError::EventNotFound)?;
+
+        ensure_writable(&self.calendars, event.calendar_id()).await?;
+
+        event.update_participation(&command.email, command.status)?;
+
+        self.repository.save(&event).await?;
+
+        Ok(())
+    }
+}
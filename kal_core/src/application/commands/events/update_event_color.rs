@@ -1,7 +1,7 @@
 use crate::{
-    application::error::ApplicationError,
+    application::{commands::guards::ensure_writable, error::ApplicationError},
     domain::{
-        repository::EventRepository,
+        repository::{CalendarRepository, EventRepository},
         value_objects::{EventColor, EventId}
     }
 };
@@ -11,13 +11,20 @@ pub struct UpdateEventColorCommand {
     new_color: EventColor,
 }
 
-pub struct UpdateEventColorHandler<R: EventRepository> {
+impl UpdateEventColorCommand {
+    pub fn new(id: EventId, new_color: EventColor) -> Self {
+        Self { id, new_color }
+    }
+}
+
+pub struct UpdateEventColorHandler<R: EventRepository, C: CalendarRepository> {
     repository: R,
+    calendars: C,
 }
 
-impl<R: EventRepository> UpdateEventColorHandler<R> {
-    pub fn new(repository: R) -> Self {
-        Self { repository }
+impl<R: EventRepository, C: CalendarRepository> UpdateEventColorHandler<R, C> {
+    pub fn new(repository: R, calendars: C) -> Self {
+        Self { repository, calendars }
     }
 
     pub async fn handle(
@@ -30,6 +37,8 @@ impl<R: EventRepository> UpdateEventColorHandler<R> {
             .await?
             .ok_or(ApplicationError::EventNotFound)?;
 
+        ensure_writable(&self.calendars, event.calendar_id()).await?;
+
         event.update_color(command.new_color);
 
         self.repository.save(&event).await?;
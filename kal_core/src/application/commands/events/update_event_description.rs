@@ -1,6 +1,6 @@
 use crate::{
-    application::error::ApplicationError,
-    domain::{repository::EventRepository, value_objects::EventId}
+    application::{commands::guards::ensure_writable, error::ApplicationError},
+    domain::{repository::{CalendarRepository, EventRepository}, value_objects::EventId}
 };
 
 pub struct UpdateEventDescriptionCommand {
@@ -8,13 +8,20 @@ pub struct UpdateEventDescriptionCommand {
     new_description: Option<String>,
 }
 
-pub struct UpdateEventDescriptionHandler<R: EventRepository> {
+impl UpdateEventDescriptionCommand {
+    pub fn new(id: EventId, new_description: Option<String>) -> Self {
+        Self { id, new_description }
+    }
+}
+
+pub struct UpdateEventDescriptionHandler<R: EventRepository, C: CalendarRepository> {
     repository: R,
+    calendars: C,
 }
 
-impl<R: EventRepository> UpdateEventDescriptionHandler<R> {
-    pub fn new(repository: R) ->  Self {
-        Self { repository }
+impl<R: EventRepository, C: CalendarRepository> UpdateEventDescriptionHandler<R, C> {
+    pub fn new(repository: R, calendars: C) -> Self {
+        Self { repository, calendars }
     }
     
     pub async fn handle(
@@ -27,6 +34,8 @@ impl<R: EventRepository> UpdateEventDescriptionHandler<R> {
             .await?
             .ok_or(ApplicationError::EventNotFound)?;
 
+        ensure_writable(&self.calendars, event.calendar_id()).await?;
+
         event.update_description(command.new_description);
 
         self.repository.save(&event).await?;
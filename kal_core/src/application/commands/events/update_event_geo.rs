@@ -0,0 +1,45 @@
+use crate::{
+    application::{commands::guards::ensure_writable, error::ApplicationError},
+    domain::{repository::{CalendarRepository, EventRepository}, value_objects::{EventId, GeoPoint}}
+};
+
+pub struct UpdateEventGeoCommand {
+    id: EventId,
+    geo: Option<GeoPoint>,
+}
+
+impl UpdateEventGeoCommand {
+    pub fn new(id: EventId, geo: Option<GeoPoint>) -> Self {
+        Self { id, geo }
+    }
+}
+
+pub struct UpdateEventGeoHandler<R: EventRepository, C: CalendarRepository> {
+    repository: R,
+    calendars: C,
+}
+
+impl<R: EventRepository, C: CalendarRepository> UpdateEventGeoHandler<R, C> {
+    pub fn new(repository: R, calendars: C) -> Self {
+        Self { repository, calendars }
+    }
+
+    pub async fn handle(
+        &self,
+        command: UpdateEventGeoCommand,
+    ) -> Result<(), ApplicationError> {
+        let mut event = self
+            .repository
+            .find_by_id(&command.id)
+            .await?
+            .ok_or(ApplicationError::EventNotFound)?;
+
+        ensure_writable(&self.calendars, event.calendar_id()).await?;
+
+        event.update_geo(command.geo);
+
+        self.repository.save(&event).await?;
+
+        Ok(())
+    }
+}
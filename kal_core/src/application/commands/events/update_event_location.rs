@@ -0,0 +1,45 @@
+use crate::{
+    application::{commands::guards::ensure_writable, error::ApplicationError},
+    domain::{repository::{CalendarRepository, EventRepository}, value_objects::{EventId}}
+};
+
+pub struct UpdateEventLocationCommand {
+    id: EventId,
+    location: Option<String>,
+}
+
+impl UpdateEventLocationCommand {
+    pub fn new(id: EventId, location: Option<String>) -> Self {
+        Self { id, location }
+    }
+}
+
+pub struct UpdateEventLocationHandler<R: EventRepository, C: CalendarRepository> {
+    repository: R,
+    calendars: C,
+}
+
+impl<R: EventRepository, C: CalendarRepository> UpdateEventLocationHandler<R, C> {
+    pub fn new(repository: R, calendars: C) -> Self {
+        Self { repository, calendars }
+    }
+
+    pub async fn handle(
+        &self,
+        command: UpdateEventLocationCommand,
+    ) -> Result<(), ApplicationError> {
+        let mut event = self
+            .repository
+            .find_by_id(&command.id)
+            .await?
+            .ok_or(ApplicationError::EventNotFound)?;
+
+        ensure_writable(&self.calendars, event.calendar_id()).await?;
+
+        event.update_location(command.location);
+
+        self.repository.save(&event).await?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,45 @@
+use crate::{
+    application::{commands::guards::ensure_writable, error::ApplicationError},
+    domain::{repository::{CalendarRepository, EventRepository}, value_objects::{EventId, EventStatus}}
+};
+
+pub struct UpdateEventStatusCommand {
+    id: EventId,
+    status: EventStatus,
+}
+
+impl UpdateEventStatusCommand {
+    pub fn new(id: EventId, status: EventStatus) -> Self {
+        Self { id, status }
+    }
+}
+
+pub struct UpdateEventStatusHandler<R: EventRepository, C: CalendarRepository> {
+    repository: R,
+    calendars: C,
+}
+
+impl<R: EventRepository, C: CalendarRepository> UpdateEventStatusHandler<R, C> {
+    pub fn new(repository: R, calendars: C) -> Self {
+        Self { repository, calendars }
+    }
+
+    pub async fn handle(
+        &self,
+        command: UpdateEventStatusCommand,
+    ) -> Result<(), ApplicationError> {
+        let mut event = self
+            .repository
+            .find_by_id(&command.id)
+            .await?
+            .ok_or(ApplicationError::EventNotFound)?;
+
+        ensure_writable(&self.calendars, event.calendar_id()).await?;
+
+        event.set_status(command.status);
+
+        self.repository.save(&event).await?;
+
+        Ok(())
+    }
+}
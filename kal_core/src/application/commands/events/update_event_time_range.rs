@@ -1,17 +1,24 @@
-use crate::{application::error::ApplicationError, domain::{repository::EventRepository, value_objects::{EventId, TimeRange}}};
+use crate::{application::{commands::guards::ensure_writable, error::ApplicationError}, domain::{repository::{CalendarRepository, EventRepository}, value_objects::{EventId, TimeRange}}};
 
 pub struct UpdateEventTimeRangeCommand {
     id: EventId,
     new_event_time_range: TimeRange,
 }
 
-pub struct UpdateEventTimeRangeHandler<R: EventRepository> {
+impl UpdateEventTimeRangeCommand {
+    pub fn new(id: EventId, new_event_time_range: TimeRange) -> Self {
+        Self { id, new_event_time_range }
+    }
+}
+
+pub struct UpdateEventTimeRangeHandler<R: EventRepository, C: CalendarRepository> {
     repository: R,
+    calendars: C,
 }
 
-impl<R: EventRepository> UpdateEventTimeRangeHandler<R> {
-    pub fn new(repository: R) -> Self {
-        Self { repository }
+impl<R: EventRepository, C: CalendarRepository> UpdateEventTimeRangeHandler<R, C> {
+    pub fn new(repository: R, calendars: C) -> Self {
+        Self { repository, calendars }
     }
 
     pub async fn handle(
@@ -24,6 +31,8 @@ impl<R: EventRepository> UpdateEventTimeRangeHandler<R> {
             .await?
             .ok_or(ApplicationError::EventNotFound)?;
 
+        ensure_writable(&self.calendars, event.calendar_id()).await?;
+
         event.update_time_range(command.new_event_time_range);
 
         self.repository.save(&event).await?;
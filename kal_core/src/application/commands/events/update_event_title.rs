@@ -1,6 +1,6 @@
 use crate::{
-    application::error::ApplicationError,
-    domain::{repository::EventRepository, value_objects::EventId}
+    application::{commands::guards::ensure_writable, error::ApplicationError},
+    domain::{repository::{CalendarRepository, EventRepository}, value_objects::EventId}
 };
 
 pub struct UpdateEventTitleCommand {
@@ -8,13 +8,20 @@ pub struct UpdateEventTitleCommand {
     new_title: String,
 }
 
-pub struct UpdateEventTitleHandler<R: EventRepository> {
+impl UpdateEventTitleCommand {
+    pub fn new(id: EventId, new_title: String) -> Self {
+        Self { id, new_title }
+    }
+}
+
+pub struct UpdateEventTitleHandler<R: EventRepository, C: CalendarRepository> {
     repository: R,
+    calendars: C,
 }
 
-impl<R: EventRepository> UpdateEventTitleHandler<R> {
-    pub fn new(repository: R) -> Self {
-        Self { repository }
+impl<R: EventRepository, C: CalendarRepository> UpdateEventTitleHandler<R, C> {
+    pub fn new(repository: R, calendars: C) -> Self {
+        Self { repository, calendars }
     }
 
     pub async fn handle(
@@ -27,6 +34,8 @@ impl<R: EventRepository> UpdateEventTitleHandler<R> {
             .await?
             .ok_or(ApplicationError::EventNotFound)?;
 
+        ensure_writable(&self.calendars, event.calendar_id()).await?;
+
         event.update_title(command.new_title);
 
         self.repository.save(&event).await?;
@@ -0,0 +1,45 @@
+use crate::{
+    application::{commands::guards::ensure_writable, error::ApplicationError},
+    domain::{repository::{CalendarRepository, EventRepository}, value_objects::{EventId, Transparency}}
+};
+
+pub struct UpdateEventTransparencyCommand {
+    id: EventId,
+    transparency: Transparency,
+}
+
+impl UpdateEventTransparencyCommand {
+    pub fn new(id: EventId, transparency: Transparency) -> Self {
+        Self { id, transparency }
+    }
+}
+
+pub struct UpdateEventTransparencyHandler<R: EventRepository, C: CalendarRepository> {
+    repository: R,
+    calendars: C,
+}
+
+impl<R: EventRepository, C: CalendarRepository> UpdateEventTransparencyHandler<R, C> {
+    pub fn new(repository: R, calendars: C) -> Self {
+        Self { repository, calendars }
+    }
+
+    pub async fn handle(
+        &self,
+        command: UpdateEventTransparencyCommand,
+    ) -> Result<(), ApplicationError> {
+        let mut event = self
+            .repository
+            .find_by_id(&command.id)
+            .await?
+            .ok_or(ApplicationError::EventNotFound)?;
+
+        ensure_writable(&self.calendars, event.calendar_id()).await?;
+
+        event.set_transparency(command.transparency);
+
+        self.repository.save(&event).await?;
+
+        Ok(())
+    }
+}
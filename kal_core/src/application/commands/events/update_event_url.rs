@@ -0,0 +1,45 @@
+use crate::{
+    application::{commands::guards::ensure_writable, error::ApplicationError},
+    domain::{repository::{CalendarRepository, EventRepository}, value_objects::{EventId}}
+};
+
+pub struct UpdateEventUrlCommand {
+    id: EventId,
+    url: Option<String>,
+}
+
+impl UpdateEventUrlCommand {
+    pub fn new(id: EventId, url: Option<String>) -> Self {
+        Self { id, url }
+    }
+}
+
+pub struct UpdateEventUrlHandler<R: EventRepository, C: CalendarRepository> {
+    repository: R,
+    calendars: C,
+}
+
+impl<R: EventRepository, C: CalendarRepository> UpdateEventUrlHandler<R, C> {
+    pub fn new(repository: R, calendars: C) -> Self {
+        Self { repository, calendars }
+    }
+
+    pub async fn handle(
+        &self,
+        command: UpdateEventUrlCommand,
+    ) -> Result<(), ApplicationError> {
+        let mut event = self
+            .repository
+            .find_by_id(&command.id)
+            .await?
+            .ok_or(ApplicationError::EventNotFound)?;
+
+        ensure_writable(&self.calendars, event.calendar_id()).await?;
+
+        event.update_url(command.url)?;
+
+        self.repository.save(&event).await?;
+
+        Ok(())
+    }
+}
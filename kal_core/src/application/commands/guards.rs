@@ -0,0 +1,35 @@
+use crate::{
+    application::error::ApplicationError,
+    domain::{repository::CalendarRepository, value_objects::CalendarId},
+};
+
+/// Loads the calendar an edit targets and refuses the edit if the
+/// calendar is a read-only subscription.
+pub(crate) async fn ensure_writable<C: CalendarRepository>(
+    calendars: &C,
+    calendar_id: &CalendarId,
+) -> Result<(), ApplicationError> {
+    let calendar = calendars
+        .find_by_id(calendar_id)
+        .await?
+        .ok_or(ApplicationError::CalendarNotFound)?;
+
+    calendar.ensure_writable()?;
+
+    Ok(())
+}
+
+/// Refuses to bring an event or series back into a calendar that is
+/// itself in the trash; restoring the calendar brings both back.
+pub(crate) async fn ensure_restorable<C: CalendarRepository>(
+    calendars: &C,
+    calendar_id: &CalendarId,
+) -> Result<(), ApplicationError> {
+    if calendars.find_by_id(calendar_id).await?.is_none() {
+        return Err(ApplicationError::Validation(
+            "its calendar is in the trash; restore the calendar first".into(),
+        ));
+    }
+
+    Ok(())
+}
@@ -0,0 +1,8 @@
+// Undoing and redoing recorded commands
+pub mod redo;
+pub mod undo;
+
+mod replay;
+
+pub use redo::{RedoCommand, RedoHandler};
+pub use undo::{UndoCommand, UndoHandler};
@@ -0,0 +1,76 @@
+use crate::{
+    application::error::ApplicationError,
+    domain::{
+        history::HistoryEntry,
+        repository::{
+            CalendarRepository, EventRepository, HistoryRepository, JournalRepository,
+            RecurringEventRepository, TaskRepository,
+        },
+    },
+};
+use super::replay::{spans, Replayer};
+
+/// Redoes the command undone last. Running another command after an
+/// undo starts a new branch of history, leaving nothing to redo.
+#[derive(Debug, Default)]
+pub struct RedoCommand;
+
+pub struct RedoHandler<C, E, R, T, J, H>
+where
+    C: CalendarRepository,
+    E: EventRepository,
+    R: RecurringEventRepository,
+    T: TaskRepository,
+    J: JournalRepository,
+    H: HistoryRepository,
+{
+    replayer: Replayer<C, E, R, T, J>,
+    history: H,
+}
+
+impl<C, E, R, T, J, H> RedoHandler<C, E, R, T, J, H>
+where
+    C: CalendarRepository,
+    E: EventRepository,
+    R: RecurringEventRepository,
+    T: TaskRepository,
+    J: JournalRepository,
+    H: HistoryRepository,
+{
+    pub fn new(calendars: C, events: E, recurring: R, tasks: T, journal: J, history: H) -> Self {
+        Self {
+            replayer: Replayer::new(calendars, events, recurring, tasks, journal),
+            history,
+        }
+    }
+
+    /// Returns the entry that was redone.
+    pub async fn handle(&self, _command: RedoCommand) -> Result<HistoryEntry, ApplicationError> {
+        let entry = self
+            .history
+            .find_next()
+            .await?
+            .ok_or(ApplicationError::NothingToRedo)?;
+
+        let targets: Vec<_> = entry.changes().iter().filter_map(|c| c.after().as_ref()).collect();
+        if let Some(calendar_id) = self.replayer.purged_calendar(&targets).await? {
+            self.history.set_current(Some(*entry.id())).await?;
+            return Err(ApplicationError::CalendarPurged(*entry.id(), calendar_id));
+        }
+
+        // Nothing may have moved on since the undo, which left each object
+        // as its first change found it
+        for (first, last) in spans(entry.changes()) {
+            let (expected, target) = (first.before().as_ref(), last.after().as_ref());
+            self.replayer.check(first, expected, target).await?;
+        }
+
+        for change in entry.changes() {
+            self.replayer.apply(change, change.after().as_ref()).await?;
+        }
+
+        self.history.set_current(Some(*entry.id())).await?;
+
+        Ok(entry)
+    }
+}
@@ -0,0 +1,236 @@
+use crate::{
+    application::{
+        error::ApplicationError,
+        history::{find_calendar, find_event, find_series},
+    },
+    domain::{
+        calendar::Calendar,
+        event::Event,
+        history::{Change, ObjectKind, Snapshot},
+        recurrence::RecurringEvent,
+        repository::{
+            CalendarRepository, EventRepository, JournalRepository, RecurringEventRepository,
+            TaskRepository,
+        },
+        value_objects::{CalendarId, EventId, JournalId, TaskId},
+    },
+};
+
+/// Puts the objects a recorded command touched back into one of the
+/// states it recorded, whatever they look like now.
+pub(crate) struct Replayer<C, E, R, T, J> {
+    calendars: C,
+    events: E,
+    recurring: R,
+    tasks: T,
+    journal: J,
+}
+
+impl<C, E, R, T, J> Replayer<C, E, R, T, J>
+where
+    C: CalendarRepository,
+    E: EventRepository,
+    R: RecurringEventRepository,
+    T: TaskRepository,
+    J: JournalRepository,
+{
+    pub(crate) fn new(calendars: C, events: E, recurring: R, tasks: T, journal: J) -> Self {
+        Self { calendars, events, recurring, tasks, journal }
+    }
+
+    /// Fails with a conflict unless the changed object is as the history
+    /// left it: `expected`, or gone when that is `None`. Replaying saves
+    /// over what is stored and moves the version on, so an object at
+    /// another version passes when nothing else about it differs; one
+    /// already gone passes when `target` would remove it anyway. Tasks and
+    /// journal entries carry no version and are not checked.
+    pub(crate) async fn check(
+        &self,
+        change: &Change,
+        expected: Option<&Snapshot>,
+        target: Option<&Snapshot>,
+    ) -> Result<(), ApplicationError> {
+        let id = *change.object_id();
+
+        let stored = match change.kind() {
+            ObjectKind::Calendar => find_calendar(&self.calendars, &CalendarId::from_uuid(id))
+                .await?
+                .map(Snapshot::Calendar),
+            ObjectKind::Event => find_event(&self.events, &EventId::from_uuid(id))
+                .await?
+                .map(Snapshot::Event),
+            ObjectKind::Series => find_series(&self.recurring, &EventId::from_uuid(id))
+                .await?
+                .map(Snapshot::Recurring),
+            ObjectKind::Task | ObjectKind::Journal => return Ok(()),
+        };
+
+        match (stored, expected) {
+            (None, None) => Ok(()),
+            (None, Some(_)) if target.is_none() => Ok(()),
+            (Some(stored), Some(expected))
+                if stored.version() == expected.version()
+                    || unversioned(&stored)? == unversioned(expected)? =>
+            {
+                Ok(())
+            }
+            _ => Err(ApplicationError::Conflict),
+        }
+    }
+
+    /// Makes the changed object match `target`, removing it when `target`
+    /// is `None`.
+    pub(crate) async fn apply(
+        &self,
+        change: &Change,
+        target: Option<&Snapshot>,
+    ) -> Result<(), ApplicationError> {
+        let id = *change.object_id();
+
+        match (change.kind(), target) {
+            (_, Some(Snapshot::Calendar(calendar))) => self.put_calendar(calendar).await,
+            (_, Some(Snapshot::Event(event))) => self.put_event(event).await,
+            (_, Some(Snapshot::Recurring(event))) => self.put_series(event).await,
+            (_, Some(Snapshot::Task(task))) => Ok(self.tasks.save(task).await?),
+            (_, Some(Snapshot::Journal(entry))) => Ok(self.journal.save(entry).await?),
+            (ObjectKind::Calendar, None) => {
+                let id = CalendarId::from_uuid(id);
+                if find_calendar(&self.calendars, &id).await?.is_some() {
+                    self.calendars.delete(&id).await?;
+                }
+                Ok(())
+            }
+            (ObjectKind::Event, None) => {
+                let id = EventId::from_uuid(id);
+                if find_event(&self.events, &id).await?.is_some() {
+                    self.events.delete(&id).await?;
+                }
+                Ok(())
+            }
+            (ObjectKind::Series, None) => {
+                let id = EventId::from_uuid(id);
+                if find_series(&self.recurring, &id).await?.is_some() {
+                    self.recurring.delete(&id).await?;
+                }
+                Ok(())
+            }
+            (ObjectKind::Task, None) => {
+                let id = TaskId::from_uuid(id);
+                if self.tasks.find_by_id(&id).await?.is_some() {
+                    self.tasks.delete(&id).await?;
+                }
+                Ok(())
+            }
+            (ObjectKind::Journal, None) => {
+                let id = JournalId::from_uuid(id);
+                if self.journal.find_by_id(&id).await?.is_some() {
+                    self.journal.delete(&id).await?;
+                }
+                Ok(())
+            }
+        }
+    }
+
+    /// A calendar that some of `targets` live in but that is gone for
+    /// good, neither stored nor in the trash nor among the targets.
+    pub(crate) async fn purged_calendar(
+        &self,
+        targets: &[&Snapshot],
+    ) -> Result<Option<CalendarId>, ApplicationError> {
+        for target in targets {
+            let calendar_id = target.calendar_id();
+            let put_back = targets.iter().any(|other| {
+                matches!(other, Snapshot::Calendar(c) if *c.calendar_id() == calendar_id)
+            });
+            if !put_back && find_calendar(&self.calendars, &calendar_id).await?.is_none() {
+                return Ok(Some(calendar_id));
+            }
+        }
+
+        Ok(None)
+    }
+
+    // Saves go against whatever version is stored now, which `check` has
+    // vouched for; trash state is then moved across separately since saves
+    // leave it alone. Trashing and restoring a calendar carries its events
+    // and series along, as the original command did.
+
+    async fn put_calendar(&self, target: &Calendar) -> Result<(), ApplicationError> {
+        let id = target.calendar_id();
+        let current = find_calendar(&self.calendars, id).await?;
+        let version = current.as_ref().map_or(0, |c| *c.version());
+        self.calendars.save(&target.clone().with_version(version)).await?;
+
+        let trashed = current.as_ref().is_some_and(Calendar::is_trashed);
+        match (*target.deleted_at(), trashed) {
+            (Some(deleted_at), false) => self.calendars.trash(id, deleted_at).await?,
+            (None, true) => self.calendars.restore(id).await?,
+            _ => {}
+        }
+
+        Ok(())
+    }
+
+    async fn put_event(&self, target: &Event) -> Result<(), ApplicationError> {
+        let id = target.event_id();
+        let current = find_event(&self.events, id).await?;
+        let version = current.as_ref().map_or(0, |e| *e.version());
+        self.events.save(&target.clone().with_version(version)).await?;
+
+        let trashed = current.as_ref().is_some_and(Event::is_trashed);
+        match (*target.deleted_at(), trashed) {
+            (Some(deleted_at), false) => self.events.trash(id, deleted_at).await?,
+            (None, true) => self.events.restore(id).await?,
+            _ => {}
+        }
+
+        Ok(())
+    }
+
+    async fn put_series(&self, target: &RecurringEvent) -> Result<(), ApplicationError> {
+        let id = target.event_id();
+        let current = find_series(&self.recurring, id).await?;
+        let version = current.as_ref().map_or(0, |e| *e.version());
+        self.recurring.save(&target.clone().with_version(version)).await?;
+
+        let trashed = current.as_ref().is_some_and(RecurringEvent::is_trashed);
+        match (*target.deleted_at(), trashed) {
+            (Some(deleted_at), false) => self.recurring.trash(id, deleted_at).await?,
+            (None, true) => self.recurring.restore(id).await?,
+            _ => {}
+        }
+
+        Ok(())
+    }
+}
+
+/// The first and last of `changes` to each object they touch.
+pub(crate) fn spans(changes: &[Change]) -> Vec<(&Change, &Change)> {
+    let mut spans: Vec<(&Change, &Change)> = Vec::new();
+    for change in changes {
+        match spans.iter_mut().find(|(first, _)| first.object_id() == change.object_id()) {
+            Some((_, last)) => *last = change,
+            None => spans.push((change, change)),
+        }
+    }
+
+    spans
+}
+
+/// Everything stored about an object apart from its version.
+fn unversioned(snapshot: &Snapshot) -> Result<serde_json::Value, ApplicationError> {
+    let value = match snapshot {
+        Snapshot::Calendar(calendar) => serde_json::to_value(calendar),
+        Snapshot::Event(event) => serde_json::to_value(event),
+        Snapshot::Recurring(event) => serde_json::to_value(event),
+        Snapshot::Task(task) => serde_json::to_value(task),
+        Snapshot::Journal(entry) => serde_json::to_value(entry),
+    };
+
+    let mut value = value.map_err(|e| ApplicationError::Repository(e.to_string()))?;
+    if let Some(fields) = value.as_object_mut() {
+        fields.remove("version");
+    }
+
+    Ok(value)
+}
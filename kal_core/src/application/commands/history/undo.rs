@@ -0,0 +1,77 @@
+use crate::{
+    application::error::ApplicationError,
+    domain::{
+        history::HistoryEntry,
+        repository::{
+            CalendarRepository, EventRepository, HistoryRepository, JournalRepository,
+            RecurringEventRepository, TaskRepository,
+        },
+    },
+};
+use super::replay::{spans, Replayer};
+
+/// Undoes the most recent command that has not been undone.
+#[derive(Debug, Default)]
+pub struct UndoCommand;
+
+pub struct UndoHandler<C, E, R, T, J, H>
+where
+    C: CalendarRepository,
+    E: EventRepository,
+    R: RecurringEventRepository,
+    T: TaskRepository,
+    J: JournalRepository,
+    H: HistoryRepository,
+{
+    replayer: Replayer<C, E, R, T, J>,
+    history: H,
+}
+
+impl<C, E, R, T, J, H> UndoHandler<C, E, R, T, J, H>
+where
+    C: CalendarRepository,
+    E: EventRepository,
+    R: RecurringEventRepository,
+    T: TaskRepository,
+    J: JournalRepository,
+    H: HistoryRepository,
+{
+    pub fn new(calendars: C, events: E, recurring: R, tasks: T, journal: J, history: H) -> Self {
+        Self {
+            replayer: Replayer::new(calendars, events, recurring, tasks, journal),
+            history,
+        }
+    }
+
+    /// Returns the entry that was undone.
+    pub async fn handle(&self, _command: UndoCommand) -> Result<HistoryEntry, ApplicationError> {
+        let entry = self
+            .history
+            .find_current()
+            .await?
+            .ok_or(ApplicationError::NothingToUndo)?;
+
+        // Nothing can be put back into a purged calendar; stepping past the
+        // entry keeps it from blocking every undo behind it
+        let targets: Vec<_> = entry.changes().iter().filter_map(|c| c.before().as_ref()).collect();
+        if let Some(calendar_id) = self.replayer.purged_calendar(&targets).await? {
+            self.history.set_current(*entry.parent_id()).await?;
+            return Err(ApplicationError::CalendarPurged(*entry.id(), calendar_id));
+        }
+
+        // Nothing may have moved on since the command
+        for (first, last) in spans(entry.changes()) {
+            let (expected, target) = (last.after().as_ref(), first.before().as_ref());
+            self.replayer.check(last, expected, target).await?;
+        }
+
+        // Last change first, so objects come back in the order they went
+        for change in entry.changes().iter().rev() {
+            self.replayer.apply(change, change.before().as_ref()).await?;
+        }
+
+        self.history.set_current(*entry.parent_id()).await?;
+
+        Ok(entry)
+    }
+}
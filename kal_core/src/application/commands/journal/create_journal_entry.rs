@@ -0,0 +1,85 @@
+use chrono::NaiveDate;
+
+use crate::{
+    application::{commands::guards::ensure_writable, error::ApplicationError},
+    domain::{
+        journal::{JournalEntry, JournalLink},
+        repository::{CalendarRepository, EventRepository, JournalRepository, RecurringEventRepository},
+        value_objects::{CalendarId, JournalId},
+    }
+};
+
+use super::ensure_link_target;
+
+pub struct CreateJournalEntryCommand {
+    calendar_id: CalendarId,
+    date: NaiveDate,
+    title: String,
+    body: String,
+    link: Option<JournalLink>,
+}
+
+impl CreateJournalEntryCommand {
+    pub fn new(calendar_id: CalendarId, date: NaiveDate, title: String, body: String) -> Self {
+        Self {
+            calendar_id,
+            date,
+            title,
+            body,
+            link: None,
+        }
+    }
+
+    pub fn with_link(mut self, link: Option<JournalLink>) -> Self {
+        self.link = link;
+        self
+    }
+}
+
+pub struct CreateJournalEntryHandler<J, E, R, C>
+where
+    J: JournalRepository,
+    E: EventRepository,
+    R: RecurringEventRepository,
+    C: CalendarRepository,
+{
+    repository: J,
+    events: E,
+    recurring: R,
+    calendars: C,
+}
+
+impl<J, E, R, C> CreateJournalEntryHandler<J, E, R, C>
+where
+    J: JournalRepository,
+    E: EventRepository,
+    R: RecurringEventRepository,
+    C: CalendarRepository,
+{
+    pub fn new(repository: J, events: E, recurring: R, calendars: C) -> Self {
+        Self { repository, events, recurring, calendars }
+    }
+
+    pub async fn handle(
+        &self,
+        command: CreateJournalEntryCommand,
+    ) -> Result<JournalId, ApplicationError> {
+        ensure_writable(&self.calendars, &command.calendar_id).await?;
+
+        if let Some(link) = &command.link {
+            ensure_link_target(&self.events, &self.recurring, link).await?;
+        }
+
+        let entry = JournalEntry::new(
+            command.calendar_id,
+            command.date,
+            command.title,
+            command.body,
+        )?
+        .with_link(command.link);
+
+        self.repository.save(&entry).await?;
+
+        Ok(*entry.journal_id())
+    }
+}
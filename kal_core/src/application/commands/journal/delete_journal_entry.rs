@@ -0,0 +1,42 @@
+use crate::{
+    application::{commands::guards::ensure_writable, error::ApplicationError},
+    domain::{repository::{CalendarRepository, JournalRepository}, value_objects::JournalId}
+};
+
+pub struct DeleteJournalEntryCommand {
+    id: JournalId,
+}
+
+impl DeleteJournalEntryCommand {
+    pub fn new(id: JournalId) -> Self {
+        Self { id }
+    }
+}
+
+pub struct DeleteJournalEntryHandler<J: JournalRepository, C: CalendarRepository> {
+    repository: J,
+    calendars: C,
+}
+
+impl<J: JournalRepository, C: CalendarRepository> DeleteJournalEntryHandler<J, C> {
+    pub fn new(repository: J, calendars: C) -> Self {
+        Self { repository, calendars }
+    }
+
+    pub async fn handle(
+        &self,
+        command: DeleteJournalEntryCommand,
+    ) -> Result<(), ApplicationError> {
+        let entry = self
+            .repository
+            .find_by_id(&command.id)
+            .await?
+            .ok_or(ApplicationError::JournalEntryNotFound)?;
+
+        ensure_writable(&self.calendars, entry.calendar_id()).await?;
+
+        self.repository.delete(&command.id).await?;
+
+        Ok(())
+    }
+}
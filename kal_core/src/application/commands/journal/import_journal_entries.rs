@@ -0,0 +1,80 @@
+use crate::{
+    application::{commands::guards::ensure_writable, error::ApplicationError},
+    domain::{
+        journal::JournalEntry,
+        repository::{CalendarRepository, EventRepository, JournalRepository, RecurringEventRepository},
+        value_objects::CalendarId,
+    }
+};
+
+use super::ensure_link_target;
+
+/// Saves entries mapped from VJOURNALs. Ids derive from UIDs, so importing
+/// the same file again updates the entries instead of duplicating them.
+/// Links to events that are not in the store are dropped.
+pub struct ImportJournalEntriesCommand {
+    calendar_id: CalendarId,
+    entries: Vec<JournalEntry>,
+}
+
+impl ImportJournalEntriesCommand {
+    pub fn new(calendar_id: CalendarId, entries: Vec<JournalEntry>) -> Self {
+        Self { calendar_id, entries }
+    }
+}
+
+pub struct ImportJournalEntriesHandler<J, E, R, C>
+where
+    J: JournalRepository,
+    E: EventRepository,
+    R: RecurringEventRepository,
+    C: CalendarRepository,
+{
+    repository: J,
+    events: E,
+    recurring: R,
+    calendars: C,
+}
+
+impl<J, E, R, C> ImportJournalEntriesHandler<J, E, R, C>
+where
+    J: JournalRepository,
+    E: EventRepository,
+    R: RecurringEventRepository,
+    C: CalendarRepository,
+{
+    pub fn new(repository: J, events: E, recurring: R, calendars: C) -> Self {
+        Self { repository, events, recurring, calendars }
+    }
+
+    /// Returns how many entries were saved.
+    pub async fn handle(
+        &self,
+        command: ImportJournalEntriesCommand,
+    ) -> Result<usize, ApplicationError> {
+        ensure_writable(&self.calendars, &command.calendar_id).await?;
+
+        let count = command.entries.len();
+        for entry in command.entries {
+            if entry.calendar_id() != &command.calendar_id {
+                return Err(ApplicationError::Validation(format!(
+                    "journal entry {} belongs to another calendar",
+                    entry.journal_id()
+                )));
+            }
+
+            let link = match entry.link() {
+                Some(link) => match ensure_link_target(&self.events, &self.recurring, link).await {
+                    Ok(()) => Some(*link),
+                    Err(ApplicationError::EventNotFound | ApplicationError::RecurringEventNotFound) => None,
+                    Err(error) => return Err(error),
+                },
+                None => None,
+            };
+
+            self.repository.save(&entry.with_link(link)).await?;
+        }
+
+        Ok(count)
+    }
+}
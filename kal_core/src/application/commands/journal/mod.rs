@@ -0,0 +1,45 @@
+pub mod create_journal_entry;
+pub mod update_journal_entry;
+pub mod delete_journal_entry;
+pub mod import_journal_entries;
+
+// Re-exports for convenience
+pub use create_journal_entry::{CreateJournalEntryCommand, CreateJournalEntryHandler};
+pub use update_journal_entry::{UpdateJournalEntryCommand, UpdateJournalEntryHandler};
+pub use delete_journal_entry::{DeleteJournalEntryCommand, DeleteJournalEntryHandler};
+pub use import_journal_entries::{ImportJournalEntriesCommand, ImportJournalEntriesHandler};
+
+use crate::{
+    application::error::ApplicationError,
+    domain::{
+        journal::JournalLink,
+        repository::{EventRepository, RecurringEventRepository, RepositoryError},
+    },
+};
+
+/// Checks that a link points at something: an event or series, or for
+/// an occurrence, a series.
+pub(super) async fn ensure_link_target<E, R>(
+    events: &E,
+    recurring: &R,
+    link: &JournalLink,
+) -> Result<(), ApplicationError>
+where
+    E: EventRepository,
+    R: RecurringEventRepository,
+{
+    let event_id = link.event_id();
+
+    if matches!(link, JournalLink::Event(_)) && events.find_by_id(&event_id).await?.is_some() {
+        return Ok(());
+    }
+
+    match recurring.find_by_id(&event_id).await {
+        Ok(_) => Ok(()),
+        Err(RepositoryError::NotFound) => Err(match link {
+            JournalLink::Event(_) => ApplicationError::EventNotFound,
+            JournalLink::Occurrence(..) => ApplicationError::RecurringEventNotFound,
+        }),
+        Err(error) => Err(error.into()),
+    }
+}
@@ -0,0 +1,111 @@
+use chrono::NaiveDate;
+
+use crate::{
+    application::{commands::guards::ensure_writable, error::ApplicationError},
+    domain::{
+        journal::JournalLink,
+        repository::{CalendarRepository, EventRepository, JournalRepository, RecurringEventRepository},
+        value_objects::JournalId,
+    }
+};
+
+use super::ensure_link_target;
+
+/// Changes only the fields that were set. `Some(None)` clears the link.
+pub struct UpdateJournalEntryCommand {
+    id: JournalId,
+    date: Option<NaiveDate>,
+    title: Option<String>,
+    body: Option<String>,
+    link: Option<Option<JournalLink>>,
+}
+
+impl UpdateJournalEntryCommand {
+    pub fn new(id: JournalId) -> Self {
+        Self {
+            id,
+            date: None,
+            title: None,
+            body: None,
+            link: None,
+        }
+    }
+
+    pub fn with_date(mut self, date: NaiveDate) -> Self {
+        self.date = Some(date);
+        self
+    }
+
+    pub fn with_title(mut self, title: String) -> Self {
+        self.title = Some(title);
+        self
+    }
+
+    pub fn with_body(mut self, body: String) -> Self {
+        self.body = Some(body);
+        self
+    }
+
+    pub fn with_link(mut self, link: Option<JournalLink>) -> Self {
+        self.link = Some(link);
+        self
+    }
+}
+
+pub struct UpdateJournalEntryHandler<J, E, R, C>
+where
+    J: JournalRepository,
+    E: EventRepository,
+    R: RecurringEventRepository,
+    C: CalendarRepository,
+{
+    repository: J,
+    events: E,
+    recurring: R,
+    calendars: C,
+}
+
+impl<J, E, R, C> UpdateJournalEntryHandler<J, E, R, C>
+where
+    J: JournalRepository,
+    E: EventRepository,
+    R: RecurringEventRepository,
+    C: CalendarRepository,
+{
+    pub fn new(repository: J, events: E, recurring: R, calendars: C) -> Self {
+        Self { repository, events, recurring, calendars }
+    }
+
+    pub async fn handle(
+        &self,
+        command: UpdateJournalEntryCommand,
+    ) -> Result<(), ApplicationError> {
+        let mut entry = self
+            .repository
+            .find_by_id(&command.id)
+            .await?
+            .ok_or(ApplicationError::JournalEntryNotFound)?;
+
+        ensure_writable(&self.calendars, entry.calendar_id()).await?;
+
+        if let Some(date) = command.date {
+            entry.move_to(date);
+        }
+        if let Some(title) = command.title {
+            entry.update_title(title)?;
+        }
+        if let Some(body) = command.body {
+            entry.update_body(body);
+        }
+        if let Some(link) = command.link {
+            if let Some(link) = &link {
+                ensure_link_target(&self.events, &self.recurring, link).await?;
+            }
+            entry.set_link(link);
+        }
+
+        self.repository.save(&entry).await?;
+
+        Ok(())
+    }
+}
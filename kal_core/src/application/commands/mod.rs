@@ -1,3 +1,12 @@
 pub mod calendars;
 pub mod events;
 pub mod recurring;
+pub mod reminders;
+pub mod scheduling;
+pub mod tags;
+pub mod tasks;
+pub mod trash;
+pub mod journal;
+pub mod history;
+
+mod guards;
@@ -0,0 +1,49 @@
+use crate::{
+    application::{commands::guards::ensure_writable, error::ApplicationError},
+    domain::{
+        attendee::Attendee,
+        repository::{CalendarRepository, RecurringEventRepository},
+        value_objects::EventId
+    }
+};
+
+pub struct AddRecurringAttendeeCommand {
+    id: EventId,
+    attendee: Attendee,
+}
+
+impl AddRecurringAttendeeCommand {
+    pub fn new(id: EventId, attendee: Attendee) -> Self {
+        Self { id, attendee }
+    }
+}
+
+pub struct AddRecurringAttendeeHandler<R: RecurringEventRepository, C: CalendarRepository> {
+    repository: R,
+    calendars: C,
+}
+
+impl<R: RecurringEventRepository, C: CalendarRepository> AddRecurringAttendeeHandler<R, C> {
+    pub fn new(repository: R, calendars: C) -> Self {
+        Self { repository, calendars }
+    }
+
+    pub async fn handle(
+        &self,
+        command: AddRecurringAttendeeCommand,
+    ) -> Result<(), ApplicationError> {
+        let mut event = self
+            .repository
+            .find_by_id(&command.id)
+            .await
+            .map_err(ApplicationError::from_recurring_lookup)?;
+
+        ensure_writable(&self.calendars, event.calendar_id()).await?;
+
+        event.add_attendee(command.attendee)?;
+
+        self.repository.save(&event).await?;
+
+        Ok(())
+    }
+}
@@ -1,19 +1,28 @@
+use chrono::Utc;
+
 use crate::{
-    application::error::ApplicationError,
-    domain::{repository::RecurringEventRepository, value_objects::EventId}
+    application::{commands::guards::ensure_writable, error::ApplicationError},
+    domain::{repository::{CalendarRepository, RecurringEventRepository}, value_objects::EventId}
 };
 
 pub struct DeleteRecurringEventCommand {
     pub id: EventId,
 }
 
-pub struct DeleteRecurringEventHandler<R: RecurringEventRepository> {
+impl DeleteRecurringEventCommand {
+    pub fn new(id: EventId) -> Self {
+        Self { id }
+    }
+}
+
+pub struct DeleteRecurringEventHandler<R: RecurringEventRepository, C: CalendarRepository> {
     repository: R,
+    calendars: C,
 }
 
-impl<R: RecurringEventRepository> DeleteRecurringEventHandler<R> {
-    pub fn new(repository: R) -> Self {
-        Self { repository }
+impl<R: RecurringEventRepository, C: CalendarRepository> DeleteRecurringEventHandler<R, C> {
+    pub fn new(repository: R, calendars: C) -> Self {
+        Self { repository, calendars }
     }
 
     pub async fn handle(
@@ -21,12 +30,15 @@ impl<R: RecurringEventRepository> DeleteRecurringEventHandler<R> {
         command: DeleteRecurringEventCommand
     ) -> Result<(), ApplicationError> {
 
-        let _event = self
+        let event = self
             .repository
             .find_by_id(&command.id)
-            .await?;
+            .await
+            .map_err(ApplicationError::from_recurring_lookup)?;
+
+        ensure_writable(&self.calendars, event.calendar_id()).await?;
 
-        self.repository.delete(&command.id).await?;
+        self.repository.trash(&command.id, Utc::now()).await?;
 
         Ok(())
     }
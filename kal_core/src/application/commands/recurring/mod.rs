@@ -2,18 +2,48 @@
 pub mod create_recurring_event;
 pub mod cancel_recurring_event;
 pub mod restore_recurring_event;
+pub mod restore_trashed_recurring_event;
 pub mod delete_recurring_event;
+pub mod add_recurring_reminder;
+pub mod remove_recurring_reminder;
+pub mod set_recurring_organizer;
+pub mod add_recurring_attendee;
+pub mod remove_recurring_attendee;
+pub mod update_recurring_attendee_status;
+pub mod update_recurring_location;
+pub mod update_recurring_url;
+pub mod update_recurring_geo;
+pub mod update_recurring_status;
+pub mod update_recurring_transparency;
+pub mod add_recurring_tag;
+pub mod remove_recurring_tag;
 
 // Occurrence-level commands (affect single instances)
 pub mod cancel_recurring_occurrence;
 pub mod restore_recurring_occurrence;
 pub mod reschedule_recurring_occurrence;
+pub mod set_recurring_occurrence_location;
 
 // Re-exports for convenience
 pub use create_recurring_event::{CreateRecurringEventCommand, CreateRecurringEventHandler};
 pub use cancel_recurring_event::{CancelRecurringEventCommand, CancelRecurringEventHandler};
 pub use restore_recurring_event::{RestoreRecurringEventCommand, RestoreRecurringEventHandler};
+pub use restore_trashed_recurring_event::{RestoreTrashedRecurringEventCommand, RestoreTrashedRecurringEventHandler};
 pub use delete_recurring_event::{DeleteRecurringEventCommand, DeleteRecurringEventHandler};
+pub use add_recurring_reminder::{AddRecurringReminderCommand, AddRecurringReminderHandler};
+pub use remove_recurring_reminder::{RemoveRecurringReminderCommand, RemoveRecurringReminderHandler};
+pub use set_recurring_organizer::{SetRecurringOrganizerCommand, SetRecurringOrganizerHandler};
+pub use add_recurring_attendee::{AddRecurringAttendeeCommand, AddRecurringAttendeeHandler};
+pub use remove_recurring_attendee::{RemoveRecurringAttendeeCommand, RemoveRecurringAttendeeHandler};
+pub use update_recurring_attendee_status::{UpdateRecurringAttendeeStatusCommand, UpdateRecurringAttendeeStatusHandler};
+pub use update_recurring_location::{UpdateRecurringLocationCommand, UpdateRecurringLocationHandler};
+pub use update_recurring_url::{UpdateRecurringUrlCommand, UpdateRecurringUrlHandler};
+pub use update_recurring_geo::{UpdateRecurringGeoCommand, UpdateRecurringGeoHandler};
+pub use update_recurring_status::{UpdateRecurringStatusCommand, UpdateRecurringStatusHandler};
+pub use update_recurring_transparency::{UpdateRecurringTransparencyCommand, UpdateRecurringTransparencyHandler};
+pub use add_recurring_tag::{AddRecurringTagCommand, AddRecurringTagHandler};
+pub use remove_recurring_tag::{RemoveRecurringTagCommand, RemoveRecurringTagHandler};
 pub use cancel_recurring_occurrence::{CancelRecurringOccurrenceCommand, CancelRecurringOccurrenceHandler};
 pub use restore_recurring_occurrence::{RestoreRecurringOccurrenceCommand, RestoreRecurringOccurrenceHandler};
 pub use reschedule_recurring_occurrence::{RescheduleRecurringOccurrenceCommand, RescheduleRecurringOccurrenceHandler};
+pub use set_recurring_occurrence_location::{SetRecurringOccurrenceLocationCommand, SetRecurringOccurrenceLocationHandler};
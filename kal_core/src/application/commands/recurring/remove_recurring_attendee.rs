@@ -0,0 +1,48 @@
+use crate::{
+    application::{commands::guards::ensure_writable, error::ApplicationError},
+    domain::{
+        repository::{CalendarRepository, RecurringEventRepository},
+        value_objects::EventId
+    }
+};
+
+pub struct RemoveRecurringAttendeeCommand {
+    id: EventId,
+    email: String,
+}
+
+impl RemoveRecurringAttendeeCommand {
+    pub fn new(id: EventId, email: String) -> Self {
+        Self { id, email }
+    }
+}
+
+pub struct RemoveRecurringAttendeeHandler<R: RecurringEventRepository, C: CalendarRepository> {
+    repository: R,
+    calendars: C,
+}
+
+impl<R: RecurringEventRepository, C: CalendarRepository> RemoveRecurringAttendeeHandler<R, C> {
+    pub fn new(repository: R, calendars: C) -> Self {
+        Self { repository, calendars }
+    }
+
+    pub async fn handle(
+        &self,
+        command: RemoveRecurringAttendeeCommand,
+    ) -> Result<(), ApplicationError> {
+        let mut event = self
+            .repository
+            .find_by_id(&command.id)
+            .await
+            .map_err(ApplicationError::from_recurring_lookup)?;
+
+        ensure_writable(&self.calendars, event.calendar_id()).await?;
+
+        event.remove_attendee(&command.email)?;
+
+        self.repository.save(&event).await?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,48 @@
+use crate::{
+    application::{commands::guards::ensure_writable, error::ApplicationError},
+    domain::{
+        repository::{CalendarRepository, RecurringEventRepository},
+        value_objects::{EventId, ReminderId}
+    }
+};
+
+pub struct RemoveRecurringReminderCommand {
+    id: EventId,
+    reminder_id: ReminderId,
+}
+
+impl RemoveRecurringReminderCommand {
+    pub fn new(id: EventId, reminder_id: ReminderId) -> Self {
+        Self { id, reminder_id }
+    }
+}
+
+pub struct RemoveRecurringReminderHandler<R: RecurringEventRepository, C: CalendarRepository> {
+    repository: R,
+    calendars: C,
+}
+
+impl<R: RecurringEventRepository, C: CalendarRepository> RemoveRecurringReminderHandler<R, C> {
+    pub fn new(repository: R, calendars: C) -> Self {
+        Self { repository, calendars }
+    }
+
+    pub async fn handle(
+        &self,
+        command: RemoveRecurringReminderCommand,
+    ) -> Result<(), ApplicationError> {
+        let mut event = self
+            .repository
+            .find_by_id(&command.id)
+            .await
+            .map_err(ApplicationError::from_recurring_lookup)?;
+
+        ensure_writable(&self.calendars, event.calendar_id()).await?;
+
+        event.remove_reminder(&command.reminder_id)?;
+
+        self.repository.save(&event).await?;
+
+        Ok(())
+    }
+}
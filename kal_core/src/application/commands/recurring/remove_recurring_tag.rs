@@ -0,0 +1,49 @@
+use crate::{
+    application::{commands::guards::ensure_writable, error::ApplicationError},
+    domain::{
+        repository::{CalendarRepository, RecurringEventRepository},
+        tag::Tag,
+        value_objects::EventId
+    }
+};
+
+pub struct RemoveRecurringTagCommand {
+    id: EventId,
+    tag: Tag,
+}
+
+impl RemoveRecurringTagCommand {
+    pub fn new(id: EventId, tag: Tag) -> Self {
+        Self { id, tag }
+    }
+}
+
+pub struct RemoveRecurringTagHandler<R: RecurringEventRepository, C: CalendarRepository> {
+    repository: R,
+    calendars: C,
+}
+
+impl<R: RecurringEventRepository, C: CalendarRepository> RemoveRecurringTagHandler<R, C> {
+    pub fn new(repository: R, calendars: C) -> Self {
+        Self { repository, calendars }
+    }
+
+    pub async fn handle(
+        &self,
+        command: RemoveRecurringTagCommand,
+    ) -> Result<(), ApplicationError> {
+        let mut event = self
+            .repository
+            .find_by_id(&command.id)
+            .await
+            .map_err(ApplicationError::from_recurring_lookup)?;
+
+        ensure_writable(&self.calendars, event.calendar_id()).await?;
+
+        event.remove_tag(&command.tag)?;
+
+        self.repository.save(&event).await?;
+
+        Ok(())
+    }
+}
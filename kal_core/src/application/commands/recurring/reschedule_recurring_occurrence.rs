@@ -1,22 +1,33 @@
 use chrono::{DateTime, Utc};
 
-use crate::{application::error::ApplicationError, domain::{
-    repository::RecurringEventRepository,
+use crate::{application::{commands::guards::ensure_writable, error::ApplicationError}, domain::{
+    repository::{CalendarRepository, RecurringEventRepository},
     value_objects::{EventId, TimeRange}}};
 
 pub struct RescheduleRecurringOccurrenceCommand {
     id: EventId,
-    orignal_starts_at: DateTime<Utc>,
+    original_starts_at: DateTime<Utc>,
     new_time_range: TimeRange,
 }
 
-pub struct RescheduleRecurringOccurrenceHandler<R: RecurringEventRepository> {
+impl RescheduleRecurringOccurrenceCommand {
+    pub fn new(
+        id: EventId,
+        original_starts_at: DateTime<Utc>,
+        new_time_range: TimeRange,
+    ) -> Self {
+        Self { id, original_starts_at, new_time_range }
+    }
+}
+
+pub struct RescheduleRecurringOccurrenceHandler<R: RecurringEventRepository, C: CalendarRepository> {
     repository: R,
+    calendars: C,
 }
 
-impl<R: RecurringEventRepository> RescheduleRecurringOccurrenceHandler<R> {
-    pub fn new(repository: R) -> Self {
-        Self { repository }
+impl<R: RecurringEventRepository, C: CalendarRepository> RescheduleRecurringOccurrenceHandler<R, C> {
+    pub fn new(repository: R, calendars: C) -> Self {
+        Self { repository, calendars }
     }
 
     pub async fn handle(
@@ -26,10 +37,13 @@ impl<R: RecurringEventRepository> RescheduleRecurringOccurrenceHandler<R> {
         let mut event = self
             .repository
             .find_by_id(&command.id)
-            .await?;
+            .await
+            .map_err(ApplicationError::from_recurring_lookup)?;
+
+        ensure_writable(&self.calendars, event.calendar_id()).await?;
 
         event.reschedule_occurrence(
-            command.orignal_starts_at,
+            command.original_starts_at,
             command.new_time_range,
         );
 
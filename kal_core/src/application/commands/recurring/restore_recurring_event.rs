@@ -1,19 +1,26 @@
 use crate::{
-    application::error::ApplicationError,
-    domain::{ repository::RecurringEventRepository, value_objects::EventId },
+    application::{commands::guards::ensure_writable, error::ApplicationError},
+    domain::{ repository::{CalendarRepository, RecurringEventRepository}, value_objects::EventId },
 };
 
 pub struct RestoreRecurringEventCommand {
     id: EventId,
 }
 
-pub struct RestoreRecurringEventHandler<R: RecurringEventRepository> {
+impl RestoreRecurringEventCommand {
+    pub fn new(id: EventId) -> Self {
+        Self { id }
+    }
+}
+
+pub struct RestoreRecurringEventHandler<R: RecurringEventRepository, C: CalendarRepository> {
     repository: R,
+    calendars: C,
 }
 
-impl<R: RecurringEventRepository> RestoreRecurringEventHandler<R> {
-    pub fn new(repository: R) -> Self {
-        Self { repository }
+impl<R: RecurringEventRepository, C: CalendarRepository> RestoreRecurringEventHandler<R, C> {
+    pub fn new(repository: R, calendars: C) -> Self {
+        Self { repository, calendars }
     }
 
     pub async fn handle(
@@ -23,7 +30,10 @@ impl<R: RecurringEventRepository> RestoreRecurringEventHandler<R> {
         let mut event = self
             .repository
             .find_by_id(&command.id)
-            .await?;
+            .await
+            .map_err(ApplicationError::from_recurring_lookup)?;
+
+        ensure_writable(&self.calendars, event.calendar_id()).await?;
 
         event.restore();
 
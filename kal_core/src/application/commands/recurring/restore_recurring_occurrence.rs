@@ -1,8 +1,8 @@
 use chrono::{DateTime, Utc};
 
 use crate::{
-    application::error::ApplicationError,
-    domain::{repository::RecurringEventRepository, value_objects::EventId}
+    application::{commands::guards::ensure_writable, error::ApplicationError},
+    domain::{repository::{CalendarRepository, RecurringEventRepository}, value_objects::EventId}
 };
 
 pub struct RestoreRecurringOccurrenceCommand {
@@ -10,13 +10,20 @@ pub struct RestoreRecurringOccurrenceCommand {
     starts_at: DateTime<Utc>,
 }
 
-pub struct RestoreRecurringOccurrenceHandler<R: RecurringEventRepository> {
+impl RestoreRecurringOccurrenceCommand {
+    pub fn new(id: EventId, starts_at: DateTime<Utc>) -> Self {
+        Self { id, starts_at }
+    }
+}
+
+pub struct RestoreRecurringOccurrenceHandler<R: RecurringEventRepository, C: CalendarRepository> {
     repository: R,
+    calendars: C,
 }
 
-impl<R: RecurringEventRepository> RestoreRecurringOccurrenceHandler<R> {
-    pub fn new(repository: R) -> Self {
-        Self { repository }
+impl<R: RecurringEventRepository, C: CalendarRepository> RestoreRecurringOccurrenceHandler<R, C> {
+    pub fn new(repository: R, calendars: C) -> Self {
+        Self { repository, calendars }
     }
 
     pub async fn handle(
@@ -26,7 +33,10 @@ impl<R: RecurringEventRepository> RestoreRecurringOccurrenceHandler<R> {
         let mut event = self
             .repository
             .find_by_id(&command.id)
-            .await?;
+            .await
+            .map_err(ApplicationError::from_recurring_lookup)?;
+
+        ensure_writable(&self.calendars, event.calendar_id()).await?;
 
         event.restore_occurrence(command.starts_at);
 
@@ -0,0 +1,57 @@
+use chrono::{DateTime, Utc};
+
+use crate::{
+    application::{commands::guards::ensure_writable, error::ApplicationError},
+    domain::{
+        repository::{CalendarRepository, RecurringEventRepository},
+        value_objects::EventId,
+    },
+};
+
+/// Moves a single occurrence to another location; `None` clears the
+/// override so it follows the series again.
+pub struct SetRecurringOccurrenceLocationCommand {
+    id: EventId,
+    original_starts_at: DateTime<Utc>,
+    location: Option<String>,
+}
+
+impl SetRecurringOccurrenceLocationCommand {
+    pub fn new(
+        id: EventId,
+        original_starts_at: DateTime<Utc>,
+        location: Option<String>,
+    ) -> Self {
+        Self { id, original_starts_at, location }
+    }
+}
+
+pub struct SetRecurringOccurrenceLocationHandler<R: RecurringEventRepository, C: CalendarRepository> {
+    repository: R,
+    calendars: C,
+}
+
+impl<R: RecurringEventRepository, C: CalendarRepository> SetRecurringOccurrenceLocationHandler<R, C> {
+    pub fn new(repository: R, calendars: C) -> Self {
+        Self { repository, calendars }
+    }
+
+    pub async fn handle(
+        &self,
+        command: SetRecurringOccurrenceLocationCommand,
+    ) -> Result<(), ApplicationError> {
+        let mut event = self
+            .repository
+            .find_by_id(&command.id)
+            .await
+            .map_err(ApplicationError::from_recurring_lookup)?;
+
+        ensure_writable(&self.calendars, event.calendar_id()).await?;
+
+        event.set_occurrence_location(command.original_starts_at, command.location)?;
+
+        self.repository.save(&event).await?;
+
+        Ok(())
+    }
+}
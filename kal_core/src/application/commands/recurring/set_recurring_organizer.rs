@@ -0,0 +1,49 @@
+use crate::{
+    application::{commands::guards::ensure_writable, error::ApplicationError},
+    domain::{
+        attendee::Organizer,
+        repository::{CalendarRepository, RecurringEventRepository},
+        value_objects::EventId
+    }
+};
+
+pub struct SetRecurringOrganizerCommand {
+    id: EventId,
+    organizer: Option<Organizer>,
+}
+
+impl SetRecurringOrganizerCommand {
+    pub fn new(id: EventId, organizer: Option<Organizer>) -> Self {
+        Self { id, organizer }
+    }
+}
+
+pub struct SetRecurringOrganizerHandler<R: RecurringEventRepository, C: CalendarRepository> {
+    repository: R,
+    calendars: C,
+}
+
+impl<R: RecurringEventRepository, C: CalendarRepository> SetRecurringOrganizerHandler<R, C> {
+    pub fn new(repository: R, calendars: C) -> Self {
+        Self { repository, calendars }
+    }
+
+    pub async fn handle(
+        &self,
+        command: SetRecurringOrganizerCommand,
+    ) -> Result<(), ApplicationError> {
+        let mut event = self
+            .repository
+            .find_by_id(&command.id)
+            .await
+            .map_err(ApplicationError::from_recurring_lookup)?;
+
+        ensure_writable(&self.calendars, event.calendar_id()).await?;
+
+        event.set_organizer(command.organizer);
+
+        self.repository.save(&event).await?;
+
+        Ok(())
+    }
+}
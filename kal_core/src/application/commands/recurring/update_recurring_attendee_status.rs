@@ -0,0 +1,50 @@
+use crate::{
+    application::{commands::guards::ensure_writable, error::ApplicationError},
+    domain::{
+        attendee::ParticipationStatus,
+        repository::{CalendarRepository, RecurringEventRepository},
+        value_objects::EventId
+    }
+};
+
+pub struct UpdateRecurringAttendeeStatusCommand {
+    id: EventId,
+    email: String,
+    status: ParticipationStatus,
+}
+
+impl UpdateRecurringAttendeeStatusCommand {
+    pub fn new(id: EventId, email: String, status: ParticipationStatus) -> Self {
+        Self { id, email, status }
+    }
+}
+
+pub struct UpdateRecurringAttendeeStatusHandler<R: RecurringEventRepository, C: CalendarRepository> {
+    repository: R,
+    calendars: C,
+}
+
+impl<R: RecurringEventRepository, C: CalendarRepository> UpdateRecurringAttendeeStatusHandler<R, C> {
+    pub fn new(repository: R, calendars: C) -> Self {
+        Self { repository, calendars }
+    }
+
+    pub async fn handle(
+        &self,
+        command: UpdateRecurringAttendeeStatusCommand,
+    ) -> Result<(), ApplicationError> {
+        let mut event = self
+            .repository
+            .find_by_id(&command.id)
+            .await
+            .map_err(ApplicationError::from_recurring_lookup)?;
+
+        ensure_writable(&self.calendars, event.calendar_id()).await?;
+
+        event.update_participation(&command.email, command.status)?;
+
+        self.repository.save(&event).await?;
+
+        Ok(())
+    }
+}
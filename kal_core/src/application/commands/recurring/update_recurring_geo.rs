@@ -0,0 +1,48 @@
+use crate::{
+    application::{commands::guards::ensure_writable, error::ApplicationError},
+    domain::{
+        repository::{CalendarRepository, RecurringEventRepository},
+        value_objects::{EventId, GeoPoint},
+    },
+};
+
+pub struct UpdateRecurringGeoCommand {
+    id: EventId,
+    geo: Option<GeoPoint>,
+}
+
+impl UpdateRecurringGeoCommand {
+    pub fn new(id: EventId, geo: Option<GeoPoint>) -> Self {
+        Self { id, geo }
+    }
+}
+
+pub struct UpdateRecurringGeoHandler<R: RecurringEventRepository, C: CalendarRepository> {
+    repository: R,
+    calendars: C,
+}
+
+impl<R: RecurringEventRepository, C: CalendarRepository> UpdateRecurringGeoHandler<R, C> {
+    pub fn new(repository: R, calendars: C) -> Self {
+        Self { repository, calendars }
+    }
+
+    pub async fn handle(
+        &self,
+        command: UpdateRecurringGeoCommand,
+    ) -> Result<(), ApplicationError> {
+        let mut event = self
+            .repository
+            .find_by_id(&command.id)
+            .await
+            .map_err(ApplicationError::from_recurring_lookup)?;
+
+        ensure_writable(&self.calendars, event.calendar_id()).await?;
+
+        event.update_geo(command.geo);
+
+        self.repository.save(&event).await?;
+
+        Ok(())
+    }
+}
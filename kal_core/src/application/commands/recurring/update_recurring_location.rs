@@ -0,0 +1,48 @@
+use crate::{
+    application::{commands::guards::ensure_writable, error::ApplicationError},
+    domain::{
+        repository::{CalendarRepository, RecurringEventRepository},
+        value_objects::{EventId},
+    },
+};
+
+pub struct UpdateRecurringLocationCommand {
+    id: EventId,
+    location: Option<String>,
+}
+
+impl UpdateRecurringLocationCommand {
+    pub fn new(id: EventId, location: Option<String>) -> Self {
+        Self { id, location }
+    }
+}
+
+pub struct UpdateRecurringLocationHandler<R: RecurringEventRepository, C: CalendarRepository> {
+    repository: R,
+    calendars: C,
+}
+
+impl<R: RecurringEventRepository, C: CalendarRepository> UpdateRecurringLocationHandler<R, C> {
+    pub fn new(repository: R, calendars: C) -> Self {
+        Self { repository, calendars }
+    }
+
+    pub async fn handle(
+        &self,
+        command: UpdateRecurringLocationCommand,
+    ) -> Result<(), ApplicationError> {
+        let mut event = self
+            .repository
+            .find_by_id(&command.id)
+            .await
+            .map_err(ApplicationError::from_recurring_lookup)?;
+
+        ensure_writable(&self.calendars, event.calendar_id()).await?;
+
+        event.update_location(command.location);
+
+        self.repository.save(&event).await?;
+
+        Ok(())
+    }
+}
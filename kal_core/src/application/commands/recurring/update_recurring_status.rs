@@ -0,0 +1,48 @@
+use crate::{
+    application::{commands::guards::ensure_writable, error::ApplicationError},
+    domain::{
+        repository::{CalendarRepository, RecurringEventRepository},
+        value_objects::{EventId, EventStatus},
+    },
+};
+
+pub struct UpdateRecurringStatusCommand {
+    id: EventId,
+    status: EventStatus,
+}
+
+impl UpdateRecurringStatusCommand {
+    pub fn new(id: EventId, status: EventStatus) -> Self {
+        Self { id, status }
+    }
+}
+
+pub struct UpdateRecurringStatusHandler<R: RecurringEventRepository, C: CalendarRepository> {
+    repository: R,
+    calendars: C,
+}
+
+impl<R: RecurringEventRepository, C: CalendarRepository> UpdateRecurringStatusHandler<R, C> {
+    pub fn new(repository: R, calendars: C) -> Self {
+        Self { repository, calendars }
+    }
+
+    pub async fn handle(
+        &self,
+        command: UpdateRecurringStatusCommand,
+    ) -> Result<(), ApplicationError> {
+        let mut event = self
+            .repository
+            .find_by_id(&command.id)
+            .await
+            .map_err(ApplicationError::from_recurring_lookup)?;
+
+        ensure_writable(&self.calendars, event.calendar_id()).await?;
+
+        event.set_status(command.status);
+
+        self.repository.save(&event).await?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,48 @@
+use crate::{
+    application::{commands::guards::ensure_writable, error::ApplicationError},
+    domain::{
+        repository::{CalendarRepository, RecurringEventRepository},
+        value_objects::{EventId, Transparency},
+    },
+};
+
+pub struct UpdateRecurringTransparencyCommand {
+    id: EventId,
+    transparency: Transparency,
+}
+
+impl UpdateRecurringTransparencyCommand {
+    pub fn new(id: EventId, transparency: Transparency) -> Self {
+        Self { id, transparency }
+    }
+}
+
+pub struct UpdateRecurringTransparencyHandler<R: RecurringEventRepository, C: CalendarRepository> {
+    repository: R,
+    calendars: C,
+}
+
+impl<R: RecurringEventRepository, C: CalendarRepository> UpdateRecurringTransparencyHandler<R, C> {
+    pub fn new(repository: R, calendars: C) -> Self {
+        Self { repository, calendars }
+    }
+
+    pub async fn handle(
+        &self,
+        command: UpdateRecurringTransparencyCommand,
+    ) -> Result<(), ApplicationError> {
+        let mut event = self
+            .repository
+            .find_by_id(&command.id)
+            .await
+            .map_err(ApplicationError::from_recurring_lookup)?;
+
+        ensure_writable(&self.calendars, event.calendar_id()).await?;
+
+        event.set_transparency(command.transparency);
+
+        self.repository.save(&event).await?;
+
+        Ok(())
+    }
+}
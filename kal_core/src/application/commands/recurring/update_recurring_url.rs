@@ -0,0 +1,48 @@
+use crate::{
+    application::{commands::guards::ensure_writable, error::ApplicationError},
+    domain::{
+        repository::{CalendarRepository, RecurringEventRepository},
+        value_objects::{EventId},
+    },
+};
+
+pub struct UpdateRecurringUrlCommand {
+    id: EventId,
+    url: Option<String>,
+}
+
+impl UpdateRecurringUrlCommand {
+    pub fn new(id: EventId, url: Option<String>) -> Self {
+        Self { id, url }
+    }
+}
+
+pub struct UpdateRecurringUrlHandler<R: RecurringEventRepository, C: CalendarRepository> {
+    repository: R,
+    calendars: C,
+}
+
+impl<R: RecurringEventRepository, C: CalendarRepository> UpdateRecurringUrlHandler<R, C> {
+    pub fn new(repository: R, calendars: C) -> Self {
+        Self { repository, calendars }
+    }
+
+    pub async fn handle(
+        &self,
+        command: UpdateRecurringUrlCommand,
+    ) -> Result<(), ApplicationError> {
+        let mut event = self
+            .repository
+            .find_by_id(&command.id)
+            .await
+            .map_err(ApplicationError::from_recurring_lookup)?;
+
+        ensure_writable(&self.calendars, event.calendar_id()).await?;
+
+        event.update_url(command.url)?;
+
+        self.repository.save(&event).await?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,82 @@
+use chrono::{DateTime, Utc};
+
+use crate::{
+    application::error::ApplicationError,
+    domain::{
+        reminder::ReminderState,
+        repository::{EventRepository, RecurringEventRepository, ReminderStateRepository},
+        value_objects::{EventId, ReminderId},
+    },
+};
+
+use super::instance_key;
+
+pub struct DismissReminderCommand {
+    id: EventId,
+    reminder_id: ReminderId,
+    original_starts_at: Option<DateTime<Utc>>,
+}
+
+impl DismissReminderCommand {
+    pub fn new(id: EventId, reminder_id: ReminderId) -> Self {
+        Self {
+            id,
+            reminder_id,
+            original_starts_at: None,
+        }
+    }
+
+    /// Picks the occurrence of a recurring series.
+    pub fn with_occurrence(mut self, original_starts_at: Option<DateTime<Utc>>) -> Self {
+        self.original_starts_at = original_starts_at;
+        self
+    }
+}
+
+pub struct DismissReminderHandler<E, R, S>
+where
+    E: EventRepository,
+    R: RecurringEventRepository,
+    S: ReminderStateRepository,
+{
+    events: E,
+    recurring: R,
+    states: S,
+}
+
+impl<E, R, S> DismissReminderHandler<E, R, S>
+where
+    E: EventRepository,
+    R: RecurringEventRepository,
+    S: ReminderStateRepository,
+{
+    pub fn new(events: E, recurring: R, states: S) -> Self {
+        Self { events, recurring, states }
+    }
+
+    pub async fn handle(
+        &self,
+        command: DismissReminderCommand,
+    ) -> Result<ReminderState, ApplicationError> {
+        let key = instance_key(
+            &self.events,
+            &self.recurring,
+            command.id,
+            command.reminder_id,
+            command.original_starts_at,
+        )
+        .await?;
+
+        let mut state = self
+            .states
+            .find(&key)
+            .await?
+            .unwrap_or_else(|| ReminderState::new(key));
+
+        state.dismiss(Utc::now());
+
+        self.states.save(&state).await?;
+
+        Ok(state)
+    }
+}
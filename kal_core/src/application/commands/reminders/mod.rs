@@ -0,0 +1,64 @@
+// Per-instance commands; the state they write is read by the scheduler
+pub mod snooze_reminder;
+pub mod dismiss_reminder;
+
+pub use snooze_reminder::{SnoozeReminderCommand, SnoozeReminderHandler};
+pub use dismiss_reminder::{DismissReminderCommand, DismissReminderHandler};
+
+use chrono::{DateTime, Utc};
+
+use crate::{
+    application::error::ApplicationError,
+    domain::{
+        error::DomainError,
+        reminder::ReminderKey,
+        repository::{EventRepository, RecurringEventRepository, RepositoryError},
+        value_objects::{EventId, ReminderId},
+    },
+};
+
+/// Resolves a reminder on an event or series to the key of one instance.
+/// Events have a single instance at their start; series need the original
+/// start of the occurrence.
+async fn instance_key<E, R>(
+    events: &E,
+    recurring: &R,
+    id: EventId,
+    reminder_id: ReminderId,
+    original_starts_at: Option<DateTime<Utc>>,
+) -> Result<ReminderKey, ApplicationError>
+where
+    E: EventRepository,
+    R: RecurringEventRepository,
+{
+    let (reminders, starts_at) = match events.find_by_id(&id).await? {
+        Some(event) => {
+            let starts_at = *event.time_range().starts_at();
+            if original_starts_at.is_some_and(|at| at != starts_at) {
+                return Err(ApplicationError::Validation(
+                    "the occurrence does not match the event start".into(),
+                ));
+            }
+            (event.reminders().clone(), starts_at)
+        }
+        None => {
+            let series = match recurring.find_by_id(&id).await {
+                Ok(series) => series,
+                Err(RepositoryError::NotFound) => return Err(ApplicationError::EventNotFound),
+                Err(e) => return Err(e.into()),
+            };
+            let starts_at = original_starts_at.ok_or_else(|| {
+                ApplicationError::Validation(
+                    "the occurrence start is required for recurring events".into(),
+                )
+            })?;
+            (series.reminders().clone(), starts_at)
+        }
+    };
+
+    if !reminders.iter().any(|r| r.reminder_id() == &reminder_id) {
+        return Err(DomainError::ReminderNotFound(reminder_id.to_string()).into());
+    }
+
+    Ok(ReminderKey::new(id, starts_at, reminder_id))
+}
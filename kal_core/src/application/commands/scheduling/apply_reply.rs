@@ -0,0 +1,87 @@
+use crate::{
+    application::{commands::guards::ensure_writable, error::ApplicationError},
+    domain::{
+        attendee::ParticipationStatus,
+        repository::{
+            CalendarRepository, EventRepository, RecurringEventRepository, RepositoryError,
+        },
+        value_objects::EventId,
+    },
+};
+
+/// An attendee's answer to an invitation, for an event or a series.
+pub struct ApplyReplyCommand {
+    id: EventId,
+    email: String,
+    status: ParticipationStatus,
+    sequence: u32,
+}
+
+impl ApplyReplyCommand {
+    /// `sequence` is the revision of the invitation being answered.
+    pub fn new(id: EventId, email: String, status: ParticipationStatus, sequence: u32) -> Self {
+        Self { id, email, status, sequence }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReplyOutcome {
+    Applied,
+    /// The reply answers an earlier revision than the current one and
+    /// was ignored; the attendee has to answer the update.
+    Outdated { current: u32 },
+}
+
+pub struct ApplyReplyHandler<E, R, C>
+where
+    E: EventRepository,
+    R: RecurringEventRepository,
+    C: CalendarRepository,
+{
+    events: E,
+    recurring: R,
+    calendars: C,
+}
+
+impl<E, R, C> ApplyReplyHandler<E, R, C>
+where
+    E: EventRepository,
+    R: RecurringEventRepository,
+    C: CalendarRepository,
+{
+    pub fn new(events: E, recurring: R, calendars: C) -> Self {
+        Self { events, recurring, calendars }
+    }
+
+    pub async fn handle(&self, command: ApplyReplyCommand) -> Result<ReplyOutcome, ApplicationError> {
+        if let Some(mut event) = self.events.find_by_id(&command.id).await? {
+            if command.sequence < *event.sequence() {
+                return Ok(ReplyOutcome::Outdated { current: *event.sequence() });
+            }
+
+            ensure_writable(&self.calendars, event.calendar_id()).await?;
+
+            event.update_participation(&command.email, command.status)?;
+            self.events.save(&event).await?;
+
+            return Ok(ReplyOutcome::Applied);
+        }
+
+        let mut series = match self.recurring.find_by_id(&command.id).await {
+            Ok(series) => series,
+            Err(RepositoryError::NotFound) => return Err(ApplicationError::EventNotFound),
+            Err(e) => return Err(e.into()),
+        };
+
+        if command.sequence < *series.sequence() {
+            return Ok(ReplyOutcome::Outdated { current: *series.sequence() });
+        }
+
+        ensure_writable(&self.calendars, series.calendar_id()).await?;
+
+        series.update_participation(&command.email, command.status)?;
+        self.recurring.save(&series).await?;
+
+        Ok(ReplyOutcome::Applied)
+    }
+}
@@ -0,0 +1,4 @@
+// Incoming iTIP messages; building outgoing ones needs no state change
+pub mod apply_reply;
+
+pub use apply_reply::{ApplyReplyCommand, ApplyReplyHandler, ReplyOutcome};
@@ -0,0 +1,57 @@
+use crate::{
+    application::error::ApplicationError,
+    domain::{
+        error::DomainError,
+        repository::{CalendarRepository, EventRepository, RecurringEventRepository, TagRepository},
+        tag::Tag,
+    },
+};
+
+use super::retag::{replace_everywhere, RetagOutcome};
+
+/// Folds one tag into another: everything tagged `from` is tagged
+/// `into` instead, and `from` disappears.
+pub struct MergeTagCommand {
+    from: Tag,
+    into: Tag,
+}
+
+impl MergeTagCommand {
+    pub fn new(from: Tag, into: Tag) -> Self {
+        Self { from, into }
+    }
+}
+
+pub struct MergeTagHandler<E, R, T, C>
+where
+    E: EventRepository,
+    R: RecurringEventRepository,
+    T: TagRepository,
+    C: CalendarRepository,
+{
+    events: E,
+    recurring: R,
+    tags: T,
+    calendars: C,
+}
+
+impl<E, R, T, C> MergeTagHandler<E, R, T, C>
+where
+    E: EventRepository,
+    R: RecurringEventRepository,
+    T: TagRepository,
+    C: CalendarRepository,
+{
+    pub fn new(events: E, recurring: R, tags: T, calendars: C) -> Self {
+        Self { events, recurring, tags, calendars }
+    }
+
+    pub async fn handle(&self, command: MergeTagCommand) -> Result<RetagOutcome, ApplicationError> {
+        if self.tags.find(&command.from).await?.is_none() {
+            return Err(DomainError::TagNotFound(command.from.to_string()).into());
+        }
+
+        replace_everywhere(&self.events, &self.recurring, &self.calendars, &command.from, &command.into)
+            .await
+    }
+}
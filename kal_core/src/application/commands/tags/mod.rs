@@ -0,0 +1,8 @@
+// Operations over a tag wherever it is used
+pub mod rename_tag;
+pub mod merge_tag;
+mod retag;
+
+pub use rename_tag::{RenameTagCommand, RenameTagHandler};
+pub use merge_tag::{MergeTagCommand, MergeTagHandler};
+pub use retag::RetagOutcome;
@@ -0,0 +1,64 @@
+use crate::{
+    application::error::ApplicationError,
+    domain::{
+        error::DomainError,
+        repository::{CalendarRepository, EventRepository, RecurringEventRepository, TagRepository},
+        tag::Tag,
+    },
+};
+
+use super::retag::{replace_everywhere, RetagOutcome};
+
+/// Gives a tag a new name everywhere. Renaming onto a tag that is
+/// already in use is refused; that is a merge.
+pub struct RenameTagCommand {
+    from: Tag,
+    to: Tag,
+}
+
+impl RenameTagCommand {
+    pub fn new(from: Tag, to: Tag) -> Self {
+        Self { from, to }
+    }
+}
+
+pub struct RenameTagHandler<E, R, T, C>
+where
+    E: EventRepository,
+    R: RecurringEventRepository,
+    T: TagRepository,
+    C: CalendarRepository,
+{
+    events: E,
+    recurring: R,
+    tags: T,
+    calendars: C,
+}
+
+impl<E, R, T, C> RenameTagHandler<E, R, T, C>
+where
+    E: EventRepository,
+    R: RecurringEventRepository,
+    T: TagRepository,
+    C: CalendarRepository,
+{
+    pub fn new(events: E, recurring: R, tags: T, calendars: C) -> Self {
+        Self { events, recurring, tags, calendars }
+    }
+
+    pub async fn handle(&self, command: RenameTagCommand) -> Result<RetagOutcome, ApplicationError> {
+        if self.tags.find(&command.from).await?.is_none() {
+            return Err(DomainError::TagNotFound(command.from.to_string()).into());
+        }
+
+        if command.from != command.to && self.tags.find(&command.to).await?.is_some() {
+            return Err(ApplicationError::Validation(format!(
+                "tag {} already exists; merge into it instead",
+                command.to
+            )));
+        }
+
+        replace_everywhere(&self.events, &self.recurring, &self.calendars, &command.from, &command.to)
+            .await
+    }
+}
@@ -0,0 +1,79 @@
+use std::collections::HashMap;
+
+use crate::{
+    application::error::ApplicationError,
+    domain::{
+        repository::{CalendarRepository, EventRepository, RecurringEventRepository},
+        tag::Tag,
+        value_objects::CalendarId,
+    },
+};
+
+/// How many objects a rename or merge changed. Objects in read-only
+/// subscriptions keep their tags until the feed changes them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct RetagOutcome {
+    pub updated: u32,
+    pub skipped: u32,
+}
+
+/// Replaces `from` with `to` on every event and series carrying it.
+pub(super) async fn replace_everywhere<E, R, C>(
+    events: &E,
+    recurring: &R,
+    calendars: &C,
+    from: &Tag,
+    to: &Tag,
+) -> Result<RetagOutcome, ApplicationError>
+where
+    E: EventRepository,
+    R: RecurringEventRepository,
+    C: CalendarRepository,
+{
+    let mut outcome = RetagOutcome::default();
+    let mut writable = HashMap::new();
+
+    for mut event in events.find_by_tag(from).await? {
+        if !is_writable(calendars, &mut writable, event.calendar_id()).await? {
+            outcome.skipped += 1;
+            continue;
+        }
+
+        if event.replace_tag(from, to.clone()) {
+            events.save(&event).await?;
+            outcome.updated += 1;
+        }
+    }
+
+    for mut event in recurring.find_by_tag(from).await? {
+        if !is_writable(calendars, &mut writable, event.calendar_id()).await? {
+            outcome.skipped += 1;
+            continue;
+        }
+
+        if event.replace_tag(from, to.clone()) {
+            recurring.save(&event).await?;
+            outcome.updated += 1;
+        }
+    }
+
+    Ok(outcome)
+}
+
+async fn is_writable<C: CalendarRepository>(
+    calendars: &C,
+    known: &mut HashMap<CalendarId, bool>,
+    calendar_id: &CalendarId,
+) -> Result<bool, ApplicationError> {
+    if let Some(writable) = known.get(calendar_id) {
+        return Ok(*writable);
+    }
+
+    let writable = calendars
+        .find_by_id(calendar_id)
+        .await?
+        .is_some_and(|calendar| calendar.ensure_writable().is_ok());
+    known.insert(*calendar_id, writable);
+
+    Ok(writable)
+}
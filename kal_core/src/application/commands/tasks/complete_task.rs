@@ -0,0 +1,48 @@
+use chrono::Utc;
+
+use crate::{
+    application::{commands::guards::ensure_writable, error::ApplicationError},
+    domain::{repository::{CalendarRepository, TaskRepository}, task::Task, value_objects::TaskId}
+};
+
+pub struct CompleteTaskCommand {
+    id: TaskId,
+}
+
+impl CompleteTaskCommand {
+    pub fn new(id: TaskId) -> Self {
+        Self { id }
+    }
+}
+
+pub struct CompleteTaskHandler<R: TaskRepository, C: CalendarRepository> {
+    repository: R,
+    calendars: C,
+}
+
+impl<R: TaskRepository, C: CalendarRepository> CompleteTaskHandler<R, C> {
+    pub fn new(repository: R, calendars: C) -> Self {
+        Self { repository, calendars }
+    }
+
+    /// Returns the task as saved: completed, or moved on to its next
+    /// dates if it recurs.
+    pub async fn handle(
+        &self,
+        command: CompleteTaskCommand,
+    ) -> Result<Task, ApplicationError> {
+        let mut task = self
+            .repository
+            .find_by_id(&command.id)
+            .await?
+            .ok_or(ApplicationError::TaskNotFound)?;
+
+        ensure_writable(&self.calendars, task.calendar_id()).await?;
+
+        task.complete(Utc::now());
+
+        self.repository.save(&task).await?;
+
+        Ok(task)
+    }
+}
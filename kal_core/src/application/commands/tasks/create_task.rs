@@ -0,0 +1,89 @@
+use chrono::{DateTime, Utc};
+
+use crate::{
+    application::{commands::guards::ensure_writable, error::ApplicationError},
+    domain::{
+        recurrence::RecurrenceRule,
+        repository::{CalendarRepository, TaskRepository},
+        task::Task,
+        value_objects::{CalendarId, TaskId, TaskStatus},
+    }
+};
+
+pub struct CreateTaskCommand {
+    calendar_id: CalendarId,
+    title: String,
+    description: Option<String>,
+    starts_at: Option<DateTime<Utc>>,
+    due_at: Option<DateTime<Utc>>,
+    priority: Option<u8>,
+    rule: Option<RecurrenceRule>,
+}
+
+impl CreateTaskCommand {
+    pub fn new(
+        calendar_id: CalendarId,
+        title: String,
+        description: Option<String>,
+        starts_at: Option<DateTime<Utc>>,
+        due_at: Option<DateTime<Utc>>,
+    ) -> Self {
+        Self {
+            calendar_id,
+            title,
+            description,
+            starts_at,
+            due_at,
+            priority: None,
+            rule: None,
+        }
+    }
+
+    pub fn with_priority(mut self, priority: Option<u8>) -> Self {
+        self.priority = priority;
+        self
+    }
+
+    pub fn with_rule(mut self, rule: Option<RecurrenceRule>) -> Self {
+        self.rule = rule;
+        self
+    }
+}
+
+pub struct CreateTaskHandler<R: TaskRepository, C: CalendarRepository> {
+    repository: R,
+    calendars: C,
+}
+
+impl<R: TaskRepository, C: CalendarRepository> CreateTaskHandler<R, C> {
+    pub fn new(repository: R, calendars: C) -> Self {
+        Self { repository, calendars }
+    }
+
+    pub async fn handle(
+        &self,
+        command: CreateTaskCommand,
+    ) -> Result<TaskId, ApplicationError> {
+        ensure_writable(&self.calendars, &command.calendar_id).await?;
+
+        let now = Utc::now();
+        let mut task = Task::with_id(
+            TaskId::new(),
+            command.calendar_id,
+            command.title,
+            command.description,
+            command.starts_at,
+            command.due_at,
+            TaskStatus::default(),
+            now,
+            now,
+        )?;
+
+        task.set_priority(command.priority)?;
+        task.set_rule(command.rule)?;
+
+        self.repository.save(&task).await?;
+
+        Ok(*task.task_id())
+    }
+}
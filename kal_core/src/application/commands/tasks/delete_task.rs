@@ -0,0 +1,42 @@
+use crate::{
+    application::{commands::guards::ensure_writable, error::ApplicationError},
+    domain::{repository::{CalendarRepository, TaskRepository}, value_objects::TaskId}
+};
+
+pub struct DeleteTaskCommand {
+    id: TaskId,
+}
+
+impl DeleteTaskCommand {
+    pub fn new(id: TaskId) -> Self {
+        Self { id }
+    }
+}
+
+pub struct DeleteTaskHandler<R: TaskRepository, C: CalendarRepository> {
+    repository: R,
+    calendars: C,
+}
+
+impl<R: TaskRepository, C: CalendarRepository> DeleteTaskHandler<R, C> {
+    pub fn new(repository: R, calendars: C) -> Self {
+        Self { repository, calendars }
+    }
+
+    pub async fn handle(
+        &self,
+        command: DeleteTaskCommand,
+    ) -> Result<(), ApplicationError> {
+        let task = self
+            .repository
+            .find_by_id(&command.id)
+            .await?
+            .ok_or(ApplicationError::TaskNotFound)?;
+
+        ensure_writable(&self.calendars, task.calendar_id()).await?;
+
+        self.repository.delete(&command.id).await?;
+
+        Ok(())
+    }
+}
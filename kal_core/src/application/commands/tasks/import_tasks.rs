@@ -0,0 +1,52 @@
+use crate::{
+    application::{commands::guards::ensure_writable, error::ApplicationError},
+    domain::{
+        repository::{CalendarRepository, TaskRepository},
+        task::Task,
+        value_objects::CalendarId,
+    }
+};
+
+/// Saves tasks mapped from VTODOs. Ids derive from UIDs, so importing the
+/// same file again updates the tasks instead of duplicating them.
+pub struct ImportTasksCommand {
+    calendar_id: CalendarId,
+    tasks: Vec<Task>,
+}
+
+impl ImportTasksCommand {
+    pub fn new(calendar_id: CalendarId, tasks: Vec<Task>) -> Self {
+        Self { calendar_id, tasks }
+    }
+}
+
+pub struct ImportTasksHandler<R: TaskRepository, C: CalendarRepository> {
+    repository: R,
+    calendars: C,
+}
+
+impl<R: TaskRepository, C: CalendarRepository> ImportTasksHandler<R, C> {
+    pub fn new(repository: R, calendars: C) -> Self {
+        Self { repository, calendars }
+    }
+
+    /// Returns how many tasks were saved.
+    pub async fn handle(
+        &self,
+        command: ImportTasksCommand,
+    ) -> Result<usize, ApplicationError> {
+        ensure_writable(&self.calendars, &command.calendar_id).await?;
+
+        for task in &command.tasks {
+            if task.calendar_id() != &command.calendar_id {
+                return Err(ApplicationError::Validation(format!(
+                    "task {} belongs to another calendar",
+                    task.task_id()
+                )));
+            }
+            self.repository.save(task).await?;
+        }
+
+        Ok(command.tasks.len())
+    }
+}
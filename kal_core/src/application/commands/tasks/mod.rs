@@ -0,0 +1,14 @@
+pub mod create_task;
+pub mod update_task;
+pub mod complete_task;
+pub mod reopen_task;
+pub mod delete_task;
+pub mod import_tasks;
+
+// Re-exports for convenience
+pub use create_task::{CreateTaskCommand, CreateTaskHandler};
+pub use update_task::{UpdateTaskCommand, UpdateTaskHandler};
+pub use complete_task::{CompleteTaskCommand, CompleteTaskHandler};
+pub use reopen_task::{ReopenTaskCommand, ReopenTaskHandler};
+pub use delete_task::{DeleteTaskCommand, DeleteTaskHandler};
+pub use import_tasks::{ImportTasksCommand, ImportTasksHandler};
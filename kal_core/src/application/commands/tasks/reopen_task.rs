@@ -0,0 +1,44 @@
+use crate::{
+    application::{commands::guards::ensure_writable, error::ApplicationError},
+    domain::{repository::{CalendarRepository, TaskRepository}, value_objects::TaskId}
+};
+
+pub struct ReopenTaskCommand {
+    id: TaskId,
+}
+
+impl ReopenTaskCommand {
+    pub fn new(id: TaskId) -> Self {
+        Self { id }
+    }
+}
+
+pub struct ReopenTaskHandler<R: TaskRepository, C: CalendarRepository> {
+    repository: R,
+    calendars: C,
+}
+
+impl<R: TaskRepository, C: CalendarRepository> ReopenTaskHandler<R, C> {
+    pub fn new(repository: R, calendars: C) -> Self {
+        Self { repository, calendars }
+    }
+
+    pub async fn handle(
+        &self,
+        command: ReopenTaskCommand,
+    ) -> Result<(), ApplicationError> {
+        let mut task = self
+            .repository
+            .find_by_id(&command.id)
+            .await?
+            .ok_or(ApplicationError::TaskNotFound)?;
+
+        ensure_writable(&self.calendars, task.calendar_id()).await?;
+
+        task.reopen();
+
+        self.repository.save(&task).await?;
+
+        Ok(())
+    }
+}
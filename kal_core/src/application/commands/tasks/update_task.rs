@@ -0,0 +1,132 @@
+use chrono::{DateTime, Utc};
+
+use crate::{
+    application::{commands::guards::ensure_writable, error::ApplicationError},
+    domain::{
+        recurrence::RecurrenceRule,
+        repository::{CalendarRepository, TaskRepository},
+        value_objects::TaskId,
+    }
+};
+
+/// Changes only the fields that were set. `Some(None)` clears an
+/// optional field.
+pub struct UpdateTaskCommand {
+    id: TaskId,
+    title: Option<String>,
+    description: Option<Option<String>>,
+    starts_at: Option<Option<DateTime<Utc>>>,
+    due_at: Option<Option<DateTime<Utc>>>,
+    priority: Option<Option<u8>>,
+    percent_complete: Option<u8>,
+    rule: Option<Option<RecurrenceRule>>,
+}
+
+impl UpdateTaskCommand {
+    pub fn new(id: TaskId) -> Self {
+        Self {
+            id,
+            title: None,
+            description: None,
+            starts_at: None,
+            due_at: None,
+            priority: None,
+            percent_complete: None,
+            rule: None,
+        }
+    }
+
+    pub fn with_title(mut self, title: String) -> Self {
+        self.title = Some(title);
+        self
+    }
+
+    pub fn with_description(mut self, description: Option<String>) -> Self {
+        self.description = Some(description);
+        self
+    }
+
+    pub fn with_starts_at(mut self, starts_at: Option<DateTime<Utc>>) -> Self {
+        self.starts_at = Some(starts_at);
+        self
+    }
+
+    pub fn with_due_at(mut self, due_at: Option<DateTime<Utc>>) -> Self {
+        self.due_at = Some(due_at);
+        self
+    }
+
+    pub fn with_priority(mut self, priority: Option<u8>) -> Self {
+        self.priority = Some(priority);
+        self
+    }
+
+    pub fn with_percent_complete(mut self, percent_complete: u8) -> Self {
+        self.percent_complete = Some(percent_complete);
+        self
+    }
+
+    pub fn with_rule(mut self, rule: Option<RecurrenceRule>) -> Self {
+        self.rule = Some(rule);
+        self
+    }
+}
+
+pub struct UpdateTaskHandler<R: TaskRepository, C: CalendarRepository> {
+    repository: R,
+    calendars: C,
+}
+
+impl<R: TaskRepository, C: CalendarRepository> UpdateTaskHandler<R, C> {
+    pub fn new(repository: R, calendars: C) -> Self {
+        Self { repository, calendars }
+    }
+
+    pub async fn handle(
+        &self,
+        command: UpdateTaskCommand,
+    ) -> Result<(), ApplicationError> {
+        let mut task = self
+            .repository
+            .find_by_id(&command.id)
+            .await?
+            .ok_or(ApplicationError::TaskNotFound)?;
+
+        ensure_writable(&self.calendars, task.calendar_id()).await?;
+
+        if let Some(title) = command.title {
+            task.update_title(title)?;
+        }
+        if let Some(description) = command.description {
+            task.update_description(description);
+        }
+        // A rule needs a date: drop it before clearing the dates, and add
+        // it after setting them
+        let rule = match command.rule {
+            Some(None) => {
+                task.set_rule(None)?;
+                None
+            }
+            rule => rule,
+        };
+        if command.starts_at.is_some() || command.due_at.is_some() {
+            task.reschedule(
+                command.starts_at.unwrap_or(*task.starts_at()),
+                command.due_at.unwrap_or(*task.due_at()),
+            )?;
+        }
+        if let Some(priority) = command.priority {
+            task.set_priority(priority)?;
+        }
+        if let Some(percent_complete) = command.percent_complete {
+            task.set_progress(percent_complete)?;
+        }
+        if let Some(rule) = rule {
+            task.set_rule(rule)?;
+        }
+
+        self.repository.save(&task).await?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,4 @@
+// Operations over everything in the trash
+pub mod purge_trash;
+
+pub use purge_trash::{PurgeOutcome, PurgeTrashCommand, PurgeTrashHandler, DEFAULT_RETENTION_DAYS};
@@ -0,0 +1,64 @@
+use chrono::{DateTime, Duration, Utc};
+
+use crate::{
+    application::error::ApplicationError,
+    domain::repository::{CalendarRepository, EventRepository, RecurringEventRepository},
+};
+
+/// How long deleted items stay in the trash unless configured otherwise.
+pub const DEFAULT_RETENTION_DAYS: i64 = 30;
+
+pub struct PurgeTrashCommand {
+    cutoff: DateTime<Utc>,
+}
+
+impl PurgeTrashCommand {
+    /// Purges everything trashed before `cutoff`.
+    pub fn new(cutoff: DateTime<Utc>) -> Self {
+        Self { cutoff }
+    }
+
+    /// Purges what has been in the trash for longer than `retention`.
+    pub fn expired(now: DateTime<Utc>, retention: Duration) -> Self {
+        Self::new(now - retention)
+    }
+}
+
+/// How many items a purge removed for good. Events and series that went
+/// with a purged calendar are not counted separately.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct PurgeOutcome {
+    pub calendars: u64,
+    pub events: u64,
+    pub series: u64,
+}
+
+pub struct PurgeTrashHandler<C, E, R>
+where
+    C: CalendarRepository,
+    E: EventRepository,
+    R: RecurringEventRepository,
+{
+    calendars: C,
+    events: E,
+    recurring: R,
+}
+
+impl<C, E, R> PurgeTrashHandler<C, E, R>
+where
+    C: CalendarRepository,
+    E: EventRepository,
+    R: RecurringEventRepository,
+{
+    pub fn new(calendars: C, events: E, recurring: R) -> Self {
+        Self { calendars, events, recurring }
+    }
+
+    pub async fn handle(&self, command: PurgeTrashCommand) -> Result<PurgeOutcome, ApplicationError> {
+        Ok(PurgeOutcome {
+            calendars: self.calendars.purge_trashed(command.cutoff).await?,
+            events: self.events.purge_trashed(command.cutoff).await?,
+            series: self.recurring.purge_trashed(command.cutoff).await?,
+        })
+    }
+}
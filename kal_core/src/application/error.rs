@@ -1,6 +1,8 @@
 use thiserror::Error;
 
-use crate::domain::{error::DomainError, repository::RepositoryError};
+use crate::domain::{
+    error::DomainError, repository::RepositoryError, value_objects::CalendarId,
+};
 
 #[derive(Debug, Error)]
 pub enum ApplicationError {
@@ -13,6 +15,12 @@ pub enum ApplicationError {
     #[error("Event not found")]
     EventNotFound,
 
+    #[error("Task not found")]
+    TaskNotFound,
+
+    #[error("Journal entry not found")]
+    JournalEntryNotFound,
+
     #[error("Domain error: {0}")]
     Domain(#[from] DomainError),
 
@@ -21,10 +29,62 @@ pub enum ApplicationError {
 
     #[error("Validation error: {0}")]
     Validation(String),
+
+    #[error("Modified concurrently; reload and try again")]
+    Conflict,
+
+    #[error("Not in the trash")]
+    NotInTrash,
+
+    #[error("Nothing to undo")]
+    NothingToUndo,
+
+    #[error("Nothing to redo")]
+    NothingToRedo,
+
+    /// Undo or redo met an entry whose calendar was purged from the
+    /// trash; the history cursor has moved past it.
+    #[error("Skipped history entry {0}: calendar {1} was purged")]
+    CalendarPurged(i64, CalendarId),
 }
 
 impl From<RepositoryError> for ApplicationError {
     fn from(error: RepositoryError) -> Self {
-        ApplicationError::Repository(error.to_string())
+        match error {
+            RepositoryError::Conflict => ApplicationError::Conflict,
+            error => ApplicationError::Repository(error.to_string()),
+        }
+    }
+}
+
+impl ApplicationError {
+    /// Whether repeating the command against freshly loaded state may
+    /// succeed.
+    pub fn is_retryable(&self) -> bool {
+        matches!(self, ApplicationError::Conflict)
+    }
+
+    /// Whether the command moved the history cursor before failing, so
+    /// the move should be committed.
+    pub fn skipped_history_entry(&self) -> bool {
+        matches!(self, ApplicationError::CalendarPurged(..))
+    }
+
+    /// `RecurringEventRepository::find_by_id` reports a missing series as
+    /// `RepositoryError::NotFound` rather than `None`.
+    pub(crate) fn from_recurring_lookup(error: RepositoryError) -> Self {
+        match error {
+            RepositoryError::NotFound => ApplicationError::RecurringEventNotFound,
+            error => error.into(),
+        }
+    }
+
+    /// Restoring reports anything not in the trash as
+    /// `RepositoryError::NotFound`.
+    pub(crate) fn from_trash_lookup(error: RepositoryError) -> Self {
+        match error {
+            RepositoryError::NotFound => ApplicationError::NotInTrash,
+            error => error.into(),
+        }
     }
 }
@@ -0,0 +1,402 @@
+use std::sync::{Arc, Mutex};
+
+use async_trait::async_trait;
+use chrono::{DateTime, NaiveDate, Utc};
+
+use crate::domain::{
+    calendar::Calendar,
+    event::Event,
+    history::{Change, ObjectKind, Snapshot},
+    journal::JournalEntry,
+    recurrence::RecurringEvent,
+    repository::{
+        CalendarRepository, EventRepository, JournalRepository, RecurringEventRepository,
+        RepositoryError, TaskRepository,
+    },
+    tag::Tag,
+    task::Task,
+    value_objects::{CalendarId, EventId, JournalId, TaskId, TimeRange},
+};
+
+/// Collects the changes made through the repositories it wraps, so a
+/// command can be recorded for undo once it has succeeded. Clones share
+/// what has been collected.
+#[derive(Debug, Clone, Default)]
+pub struct Recorder {
+    changes: Arc<Mutex<Vec<Change>>>,
+}
+
+impl Recorder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Wraps a repository so that its writes are recorded here.
+    pub fn record<R>(&self, repository: R) -> Recorded<R> {
+        Recorded { inner: repository, recorder: self.clone() }
+    }
+
+    /// Hands over everything recorded so far and starts afresh.
+    pub fn take(&self) -> Vec<Change> {
+        match self.changes.lock() {
+            Ok(mut changes) => std::mem::take(&mut *changes),
+            Err(poisoned) => std::mem::take(&mut *poisoned.into_inner()),
+        }
+    }
+
+    fn push(&self, kind: ObjectKind, object_id: uuid::Uuid, before: Option<Snapshot>, after: Option<Snapshot>) {
+        let change = Change::new(kind, object_id, before, after);
+        match self.changes.lock() {
+            Ok(mut changes) => changes.push(change),
+            Err(poisoned) => poisoned.into_inner().push(change),
+        }
+    }
+}
+
+/// A repository whose saves, trashing, restores and deletes are recorded
+/// with the state of the object before and after. Purging the trash is
+/// permanent and passes through unrecorded.
+pub struct Recorded<R> {
+    inner: R,
+    recorder: Recorder,
+}
+
+// ============================================================================
+// Lookups
+// ============================================================================
+
+/// A calendar whether it is live or in the trash.
+pub(crate) async fn find_calendar<C: CalendarRepository + ?Sized>(
+    calendars: &C,
+    id: &CalendarId,
+) -> Result<Option<Calendar>, RepositoryError> {
+    if let Some(calendar) = calendars.find_by_id(id).await? {
+        return Ok(Some(calendar));
+    }
+
+    Ok(calendars
+        .find_trashed()
+        .await?
+        .into_iter()
+        .find(|calendar| calendar.calendar_id() == id))
+}
+
+/// An event whether it is live or in the trash.
+pub(crate) async fn find_event<E: EventRepository + ?Sized>(
+    events: &E,
+    id: &EventId,
+) -> Result<Option<Event>, RepositoryError> {
+    if let Some(event) = events.find_by_id(id).await? {
+        return Ok(Some(event));
+    }
+
+    Ok(events
+        .find_trashed()
+        .await?
+        .into_iter()
+        .find(|event| event.event_id() == id))
+}
+
+/// A series whether it is live or in the trash.
+pub(crate) async fn find_series<R: RecurringEventRepository + ?Sized>(
+    recurring: &R,
+    id: &EventId,
+) -> Result<Option<RecurringEvent>, RepositoryError> {
+    match recurring.find_by_id(id).await {
+        Ok(event) => return Ok(Some(event)),
+        Err(RepositoryError::NotFound) => {}
+        Err(error) => return Err(error),
+    }
+
+    Ok(recurring
+        .find_trashed()
+        .await?
+        .into_iter()
+        .find(|event| event.event_id() == id))
+}
+
+// ============================================================================
+// Calendars
+// ============================================================================
+
+impl<C: CalendarRepository> Recorded<C> {
+    async fn calendar_snapshot(&self, id: &CalendarId) -> Result<Option<Snapshot>, RepositoryError> {
+        Ok(find_calendar(&self.inner, id).await?.map(Snapshot::Calendar))
+    }
+}
+
+#[async_trait]
+impl<C: CalendarRepository> CalendarRepository for Recorded<C> {
+    async fn save(&self, calendar: &Calendar) -> Result<u32, RepositoryError> {
+        let id = calendar.calendar_id();
+        let before = self.calendar_snapshot(id).await?;
+        let version = self.inner.save(calendar).await?;
+        let after = self.calendar_snapshot(id).await?;
+        self.recorder.push(ObjectKind::Calendar, id.as_uuid(), before, after);
+        Ok(version)
+    }
+
+    async fn find_by_id(&self, id: &CalendarId) -> Result<Option<Calendar>, RepositoryError> {
+        self.inner.find_by_id(id).await
+    }
+
+    async fn find_all_active(&self) -> Result<Vec<Calendar>, RepositoryError> {
+        self.inner.find_all_active().await
+    }
+
+    async fn trash(&self, id: &CalendarId, deleted_at: DateTime<Utc>) -> Result<(), RepositoryError> {
+        let before = self.calendar_snapshot(id).await?;
+        self.inner.trash(id, deleted_at).await?;
+        let after = self.calendar_snapshot(id).await?;
+        self.recorder.push(ObjectKind::Calendar, id.as_uuid(), before, after);
+        Ok(())
+    }
+
+    async fn restore(&self, id: &CalendarId) -> Result<(), RepositoryError> {
+        let before = self.calendar_snapshot(id).await?;
+        self.inner.restore(id).await?;
+        let after = self.calendar_snapshot(id).await?;
+        self.recorder.push(ObjectKind::Calendar, id.as_uuid(), before, after);
+        Ok(())
+    }
+
+    async fn find_trashed(&self) -> Result<Vec<Calendar>, RepositoryError> {
+        self.inner.find_trashed().await
+    }
+
+    async fn purge_trashed(&self, cutoff: DateTime<Utc>) -> Result<u64, RepositoryError> {
+        self.inner.purge_trashed(cutoff).await
+    }
+
+    async fn delete(&self, id: &CalendarId) -> Result<(), RepositoryError> {
+        let before = self.calendar_snapshot(id).await?;
+        self.inner.delete(id).await?;
+        self.recorder.push(ObjectKind::Calendar, id.as_uuid(), before, None);
+        Ok(())
+    }
+}
+
+// ============================================================================
+// Events
+// ============================================================================
+
+impl<E: EventRepository> Recorded<E> {
+    async fn event_snapshot(&self, id: &EventId) -> Result<Option<Snapshot>, RepositoryError> {
+        Ok(find_event(&self.inner, id).await?.map(Snapshot::Event))
+    }
+}
+
+#[async_trait]
+impl<E: EventRepository> EventRepository for Recorded<E> {
+    async fn save(&self, event: &Event) -> Result<u32, RepositoryError> {
+        let id = event.event_id();
+        let before = self.event_snapshot(id).await?;
+        let version = self.inner.save(event).await?;
+        let after = self.event_snapshot(id).await?;
+        self.recorder.push(ObjectKind::Event, id.as_uuid(), before, after);
+        Ok(version)
+    }
+
+    async fn find_by_id(&self, id: &EventId) -> Result<Option<Event>, RepositoryError> {
+        self.inner.find_by_id(id).await
+    }
+
+    async fn find_by_calendar(&self, calendar_id: &CalendarId) -> Result<Vec<Event>, RepositoryError> {
+        self.inner.find_by_calendar(calendar_id).await
+    }
+
+    async fn find_in_range(&self, calendar_id: &CalendarId, range: &TimeRange) -> Result<Vec<Event>, RepositoryError> {
+        self.inner.find_in_range(calendar_id, range).await
+    }
+
+    async fn find_by_location(&self, calendar_id: &CalendarId, needle: &str) -> Result<Vec<Event>, RepositoryError> {
+        self.inner.find_by_location(calendar_id, needle).await
+    }
+
+    async fn find_by_tag(&self, tag: &Tag) -> Result<Vec<Event>, RepositoryError> {
+        self.inner.find_by_tag(tag).await
+    }
+
+    async fn find_with_reminders_in(&self, window: &TimeRange) -> Result<Vec<Event>, RepositoryError> {
+        self.inner.find_with_reminders_in(window).await
+    }
+
+    async fn trash(&self, id: &EventId, deleted_at: DateTime<Utc>) -> Result<(), RepositoryError> {
+        let before = self.event_snapshot(id).await?;
+        self.inner.trash(id, deleted_at).await?;
+        let after = self.event_snapshot(id).await?;
+        self.recorder.push(ObjectKind::Event, id.as_uuid(), before, after);
+        Ok(())
+    }
+
+    async fn restore(&self, id: &EventId) -> Result<(), RepositoryError> {
+        let before = self.event_snapshot(id).await?;
+        self.inner.restore(id).await?;
+        let after = self.event_snapshot(id).await?;
+        self.recorder.push(ObjectKind::Event, id.as_uuid(), before, after);
+        Ok(())
+    }
+
+    async fn find_trashed(&self) -> Result<Vec<Event>, RepositoryError> {
+        self.inner.find_trashed().await
+    }
+
+    async fn purge_trashed(&self, cutoff: DateTime<Utc>) -> Result<u64, RepositoryError> {
+        self.inner.purge_trashed(cutoff).await
+    }
+
+    async fn delete(&self, id: &EventId) -> Result<(), RepositoryError> {
+        let before = self.event_snapshot(id).await?;
+        self.inner.delete(id).await?;
+        self.recorder.push(ObjectKind::Event, id.as_uuid(), before, None);
+        Ok(())
+    }
+}
+
+// ============================================================================
+// Series
+// ============================================================================
+
+impl<R: RecurringEventRepository> Recorded<R> {
+    async fn series_snapshot(&self, id: &EventId) -> Result<Option<Snapshot>, RepositoryError> {
+        Ok(find_series(&self.inner, id).await?.map(Snapshot::Recurring))
+    }
+}
+
+#[async_trait]
+impl<R: RecurringEventRepository> RecurringEventRepository for Recorded<R> {
+    async fn save(&self, event: &RecurringEvent) -> Result<u32, RepositoryError> {
+        let id = event.event_id();
+        let before = self.series_snapshot(id).await?;
+        let version = self.inner.save(event).await?;
+        let after = self.series_snapshot(id).await?;
+        self.recorder.push(ObjectKind::Series, id.as_uuid(), before, after);
+        Ok(version)
+    }
+
+    async fn find_by_calendar(&self, calendar_id: &CalendarId) -> Result<Vec<RecurringEvent>, RepositoryError> {
+        self.inner.find_by_calendar(calendar_id).await
+    }
+
+    async fn find_by_id(&self, event_id: &EventId) -> Result<RecurringEvent, RepositoryError> {
+        self.inner.find_by_id(event_id).await
+    }
+
+    async fn find_by_location(&self, calendar_id: &CalendarId, needle: &str) -> Result<Vec<RecurringEvent>, RepositoryError> {
+        self.inner.find_by_location(calendar_id, needle).await
+    }
+
+    async fn find_by_tag(&self, tag: &Tag) -> Result<Vec<RecurringEvent>, RepositoryError> {
+        self.inner.find_by_tag(tag).await
+    }
+
+    async fn find_with_reminders_in(&self, window: &TimeRange) -> Result<Vec<RecurringEvent>, RepositoryError> {
+        self.inner.find_with_reminders_in(window).await
+    }
+
+    async fn trash(&self, id: &EventId, deleted_at: DateTime<Utc>) -> Result<(), RepositoryError> {
+        let before = self.series_snapshot(id).await?;
+        self.inner.trash(id, deleted_at).await?;
+        let after = self.series_snapshot(id).await?;
+        self.recorder.push(ObjectKind::Series, id.as_uuid(), before, after);
+        Ok(())
+    }
+
+    async fn restore(&self, id: &EventId) -> Result<(), RepositoryError> {
+        let before = self.series_snapshot(id).await?;
+        self.inner.restore(id).await?;
+        let after = self.series_snapshot(id).await?;
+        self.recorder.push(ObjectKind::Series, id.as_uuid(), before, after);
+        Ok(())
+    }
+
+    async fn find_trashed(&self) -> Result<Vec<RecurringEvent>, RepositoryError> {
+        self.inner.find_trashed().await
+    }
+
+    async fn purge_trashed(&self, cutoff: DateTime<Utc>) -> Result<u64, RepositoryError> {
+        self.inner.purge_trashed(cutoff).await
+    }
+
+    async fn delete(&self, id: &EventId) -> Result<(), RepositoryError> {
+        let before = self.series_snapshot(id).await?;
+        self.inner.delete(id).await?;
+        self.recorder.push(ObjectKind::Series, id.as_uuid(), before, None);
+        Ok(())
+    }
+}
+
+// ============================================================================
+// Tasks and journal entries
+// ============================================================================
+
+#[async_trait]
+impl<T: TaskRepository> TaskRepository for Recorded<T> {
+    async fn save(&self, task: &Task) -> Result<(), RepositoryError> {
+        let id = task.task_id();
+        let before = self.inner.find_by_id(id).await?.map(Snapshot::Task);
+        self.inner.save(task).await?;
+        let after = self.inner.find_by_id(id).await?.map(Snapshot::Task);
+        self.recorder.push(ObjectKind::Task, id.as_uuid(), before, after);
+        Ok(())
+    }
+
+    async fn find_by_id(&self, id: &TaskId) -> Result<Option<Task>, RepositoryError> {
+        self.inner.find_by_id(id).await
+    }
+
+    async fn find_by_calendar(&self, calendar_id: &CalendarId) -> Result<Vec<Task>, RepositoryError> {
+        self.inner.find_by_calendar(calendar_id).await
+    }
+
+    async fn delete(&self, id: &TaskId) -> Result<(), RepositoryError> {
+        let before = self.inner.find_by_id(id).await?.map(Snapshot::Task);
+        self.inner.delete(id).await?;
+        self.recorder.push(ObjectKind::Task, id.as_uuid(), before, None);
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl<J: JournalRepository> JournalRepository for Recorded<J> {
+    async fn save(&self, entry: &JournalEntry) -> Result<(), RepositoryError> {
+        let id = entry.journal_id();
+        let before = self.inner.find_by_id(id).await?.map(Snapshot::Journal);
+        self.inner.save(entry).await?;
+        let after = self.inner.find_by_id(id).await?.map(Snapshot::Journal);
+        self.recorder.push(ObjectKind::Journal, id.as_uuid(), before, after);
+        Ok(())
+    }
+
+    async fn find_by_id(&self, id: &JournalId) -> Result<Option<JournalEntry>, RepositoryError> {
+        self.inner.find_by_id(id).await
+    }
+
+    async fn find_by_calendar(
+        &self,
+        calendar_id: &CalendarId,
+        from: Option<NaiveDate>,
+        to: Option<NaiveDate>,
+    ) -> Result<Vec<JournalEntry>, RepositoryError> {
+        self.inner.find_by_calendar(calendar_id, from, to).await
+    }
+
+    async fn find_by_event(&self, event_id: &EventId) -> Result<Vec<JournalEntry>, RepositoryError> {
+        self.inner.find_by_event(event_id).await
+    }
+
+    async fn search(
+        &self,
+        query: &str,
+        calendar_id: Option<&CalendarId>,
+    ) -> Result<Vec<JournalEntry>, RepositoryError> {
+        self.inner.search(query, calendar_id).await
+    }
+
+    async fn delete(&self, id: &JournalId) -> Result<(), RepositoryError> {
+        let before = self.inner.find_by_id(id).await?.map(Snapshot::Journal);
+        self.inner.delete(id).await?;
+        self.recorder.push(ObjectKind::Journal, id.as_uuid(), before, None);
+        Ok(())
+    }
+}
@@ -1,2 +1,5 @@
 pub mod commands;
 pub mod error;
+pub mod history;
+pub mod publisher;
+pub mod scope;
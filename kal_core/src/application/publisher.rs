@@ -0,0 +1,406 @@
+use std::{
+    fmt,
+    sync::{Arc, Mutex},
+};
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+
+use crate::{
+    application::history::{find_calendar, find_event, find_series},
+    domain::{
+        calendar::Calendar,
+        domain_event::{DomainEvent, EventContext, EventSubscriber},
+        event::Event,
+        recurrence::RecurringEvent,
+        repository::{
+            CalendarRepository, EventRepository, RecurringEventRepository, RepositoryError,
+        },
+        tag::Tag,
+        value_objects::{CalendarId, EventId, TimeRange},
+    },
+};
+
+/// Who events are attributed to unless a publisher is told otherwise.
+pub const DEFAULT_ACTOR: &str = "local";
+
+/// Collects the domain events raised by what is saved through the
+/// repositories it wraps, and hands them to its subscribers once the
+/// command has succeeded. Clones share the subscribers and the queue.
+#[derive(Clone)]
+pub struct Publisher {
+    subscribers: Arc<Vec<Arc<dyn EventSubscriber>>>,
+    pending: Arc<Mutex<Vec<DomainEvent>>>,
+    actor: Arc<str>,
+}
+
+impl Default for Publisher {
+    fn default() -> Self {
+        Self {
+            subscribers: Arc::default(),
+            pending: Arc::default(),
+            actor: Arc::from(DEFAULT_ACTOR),
+        }
+    }
+}
+
+impl fmt::Debug for Publisher {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Publisher")
+            .field("subscribers", &self.subscribers.len())
+            .field("pending", &self.pending.lock().map(|p| p.len()).unwrap_or_default())
+            .field("actor", &self.actor)
+            .finish()
+    }
+}
+
+impl Publisher {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a subscriber; subscribers are notified in the order added.
+    pub fn subscribe(mut self, subscriber: Arc<dyn EventSubscriber>) -> Self {
+        let mut subscribers = self.subscribers.as_ref().clone();
+        subscribers.push(subscriber);
+        self.subscribers = Arc::new(subscribers);
+        self
+    }
+
+    /// Attributes published events to `actor`, such as the user name.
+    pub fn with_actor(mut self, actor: impl Into<String>) -> Self {
+        self.actor = Arc::from(actor.into());
+        self
+    }
+
+    /// A publisher with the same subscribers and actor and a queue of its
+    /// own, so concurrent commands' events stay apart.
+    pub fn scoped(&self) -> Self {
+        Self {
+            subscribers: self.subscribers.clone(),
+            pending: Arc::default(),
+            actor: self.actor.clone(),
+        }
+    }
+
+    /// Wraps a repository so that what it stores raises events here.
+    pub fn track<R>(&self, repository: R) -> Published<R> {
+        Published { inner: repository, publisher: self.clone() }
+    }
+
+    /// Hands the queued events to every subscriber as the doing of
+    /// `command`, and empties the queue. Only call this once the changes
+    /// have been stored for good.
+    pub async fn publish(&self, command: &str) {
+        let events = self.take();
+        if events.is_empty() {
+            return;
+        }
+
+        let context = self.context(command);
+        for event in &events {
+            for subscriber in self.subscribers.iter() {
+                subscriber.notify(event, &context).await;
+            }
+        }
+    }
+
+    /// The queued events, which stay queued.
+    pub fn pending(&self) -> Vec<DomainEvent> {
+        match self.pending.lock() {
+            Ok(pending) => pending.clone(),
+            Err(poisoned) => poisoned.into_inner().clone(),
+        }
+    }
+
+    /// The context events published now as the doing of `command` carry.
+    pub fn context(&self, command: &str) -> EventContext {
+        EventContext::new(command, self.actor.as_ref(), Utc::now())
+    }
+
+    /// Empties the queue without telling anyone, as when a command fails.
+    pub fn take(&self) -> Vec<DomainEvent> {
+        match self.pending.lock() {
+            Ok(mut pending) => std::mem::take(&mut *pending),
+            Err(poisoned) => std::mem::take(&mut *poisoned.into_inner()),
+        }
+    }
+
+    fn push(&self, events: impl IntoIterator<Item = DomainEvent>) {
+        match self.pending.lock() {
+            Ok(mut pending) => pending.extend(events),
+            Err(poisoned) => poisoned.into_inner().extend(events),
+        }
+    }
+}
+
+/// A repository that queues the events raised by the aggregates it saves,
+/// along with their creation, trashing, restoring and deletion, purges
+/// included. A save with nothing raised, as when undo replaces an object
+/// wholesale, queues a change with no fields.
+///
+/// Those lifecycle events are raised here rather than by the handlers on
+/// purpose: they are repository operations with no mutator to raise them,
+/// and undo, sync and purges reach them without going through a handler.
+/// Raising them next to the write means every path reports them alike,
+/// and only once the write went through.
+pub struct Published<R> {
+    inner: R,
+    publisher: Publisher,
+}
+
+/// What of `trashed` a purge of everything deleted before `cutoff` takes.
+fn expired<T>(
+    trashed: Vec<T>,
+    cutoff: DateTime<Utc>,
+    deleted_at: impl Fn(&T) -> &Option<DateTime<Utc>>,
+) -> Vec<T> {
+    trashed
+        .into_iter()
+        .filter(|item| deleted_at(item).is_some_and(|at| at < cutoff))
+        .collect()
+}
+
+// ============================================================================
+// Calendars
+// ============================================================================
+
+#[async_trait]
+impl<C: CalendarRepository> CalendarRepository for Published<C> {
+    async fn save(&self, calendar: &Calendar) -> Result<u32, RepositoryError> {
+        let version = self.inner.save(calendar).await?;
+
+        let calendar_id = *calendar.calendar_id();
+        if *calendar.version() == 0 {
+            self.publisher.push([DomainEvent::CalendarCreated { calendar_id }]);
+        } else if calendar.pending_events().is_empty() {
+            self.publisher.push([DomainEvent::CalendarChanged { calendar_id, changes: Vec::new() }]);
+        }
+        self.publisher.push(calendar.pending_events().iter().cloned());
+        Ok(version)
+    }
+
+    async fn find_by_id(&self, id: &CalendarId) -> Result<Option<Calendar>, RepositoryError> {
+        self.inner.find_by_id(id).await
+    }
+
+    async fn find_all_active(&self) -> Result<Vec<Calendar>, RepositoryError> {
+        self.inner.find_all_active().await
+    }
+
+    async fn trash(&self, id: &CalendarId, deleted_at: DateTime<Utc>) -> Result<(), RepositoryError> {
+        self.inner.trash(id, deleted_at).await?;
+        self.publisher.push([DomainEvent::CalendarTrashed { calendar_id: *id }]);
+        Ok(())
+    }
+
+    async fn restore(&self, id: &CalendarId) -> Result<(), RepositoryError> {
+        self.inner.restore(id).await?;
+        self.publisher.push([DomainEvent::CalendarRestoredFromTrash { calendar_id: *id }]);
+        Ok(())
+    }
+
+    async fn find_trashed(&self) -> Result<Vec<Calendar>, RepositoryError> {
+        self.inner.find_trashed().await
+    }
+
+    async fn purge_trashed(&self, cutoff: DateTime<Utc>) -> Result<u64, RepositoryError> {
+        let purged = expired(self.inner.find_trashed().await?, cutoff, Calendar::deleted_at);
+        let count = self.inner.purge_trashed(cutoff).await?;
+        self.publisher.push(purged.iter().map(|calendar| DomainEvent::CalendarDeleted {
+            calendar_id: *calendar.calendar_id(),
+        }));
+        Ok(count)
+    }
+
+    async fn delete(&self, id: &CalendarId) -> Result<(), RepositoryError> {
+        let existed = find_calendar(&self.inner, id).await?.is_some();
+        self.inner.delete(id).await?;
+        if existed {
+            self.publisher.push([DomainEvent::CalendarDeleted { calendar_id: *id }]);
+        }
+        Ok(())
+    }
+}
+
+// ============================================================================
+// Events
+// ============================================================================
+
+impl<E: EventRepository> Published<E> {
+    async fn event_calendar(&self, id: &EventId) -> Result<Option<CalendarId>, RepositoryError> {
+        Ok(find_event(&self.inner, id).await?.map(|event| *event.calendar_id()))
+    }
+}
+
+#[async_trait]
+impl<E: EventRepository> EventRepository for Published<E> {
+    async fn save(&self, event: &Event) -> Result<u32, RepositoryError> {
+        let version = self.inner.save(event).await?;
+
+        let (calendar_id, event_id) = (*event.calendar_id(), *event.event_id());
+        if *event.version() == 0 {
+            self.publisher.push([DomainEvent::EventCreated { calendar_id, event_id }]);
+        } else if event.pending_events().is_empty() {
+            self.publisher.push([DomainEvent::EventChanged {
+                calendar_id,
+                event_id,
+                changes: Vec::new(),
+            }]);
+        }
+        self.publisher.push(event.pending_events().iter().cloned());
+        Ok(version)
+    }
+
+    async fn find_by_id(&self, id: &EventId) -> Result<Option<Event>, RepositoryError> {
+        self.inner.find_by_id(id).await
+    }
+
+    async fn find_by_calendar(&self, calendar_id: &CalendarId) -> Result<Vec<Event>, RepositoryError> {
+        self.inner.find_by_calendar(calendar_id).await
+    }
+
+    async fn find_in_range(&self, calendar_id: &CalendarId, range: &TimeRange) -> Result<Vec<Event>, RepositoryError> {
+        self.inner.find_in_range(calendar_id, range).await
+    }
+
+    async fn find_by_location(&self, calendar_id: &CalendarId, needle: &str) -> Result<Vec<Event>, RepositoryError> {
+        self.inner.find_by_location(calendar_id, needle).await
+    }
+
+    async fn find_by_tag(&self, tag: &Tag) -> Result<Vec<Event>, RepositoryError> {
+        self.inner.find_by_tag(tag).await
+    }
+
+    async fn find_with_reminders_in(&self, window: &TimeRange) -> Result<Vec<Event>, RepositoryError> {
+        self.inner.find_with_reminders_in(window).await
+    }
+
+    async fn trash(&self, id: &EventId, deleted_at: DateTime<Utc>) -> Result<(), RepositoryError> {
+        self.inner.trash(id, deleted_at).await?;
+        if let Some(calendar_id) = self.event_calendar(id).await? {
+            self.publisher.push([DomainEvent::EventTrashed { calendar_id, event_id: *id }]);
+        }
+        Ok(())
+    }
+
+    async fn restore(&self, id: &EventId) -> Result<(), RepositoryError> {
+        self.inner.restore(id).await?;
+        if let Some(calendar_id) = self.event_calendar(id).await? {
+            self.publisher.push([DomainEvent::EventRestoredFromTrash { calendar_id, event_id: *id }]);
+        }
+        Ok(())
+    }
+
+    async fn find_trashed(&self) -> Result<Vec<Event>, RepositoryError> {
+        self.inner.find_trashed().await
+    }
+
+    async fn purge_trashed(&self, cutoff: DateTime<Utc>) -> Result<u64, RepositoryError> {
+        let purged = expired(self.inner.find_trashed().await?, cutoff, Event::deleted_at);
+        let count = self.inner.purge_trashed(cutoff).await?;
+        self.publisher.push(purged.iter().map(|event| DomainEvent::EventDeleted {
+            calendar_id: *event.calendar_id(),
+            event_id: *event.event_id(),
+        }));
+        Ok(count)
+    }
+
+    async fn delete(&self, id: &EventId) -> Result<(), RepositoryError> {
+        let calendar_id = self.event_calendar(id).await?;
+        self.inner.delete(id).await?;
+        if let Some(calendar_id) = calendar_id {
+            self.publisher.push([DomainEvent::EventDeleted { calendar_id, event_id: *id }]);
+        }
+        Ok(())
+    }
+}
+
+// ============================================================================
+// Series
+// ============================================================================
+
+impl<R: RecurringEventRepository> Published<R> {
+    async fn series_calendar(&self, id: &EventId) -> Result<Option<CalendarId>, RepositoryError> {
+        Ok(find_series(&self.inner, id).await?.map(|event| *event.calendar_id()))
+    }
+}
+
+#[async_trait]
+impl<R: RecurringEventRepository> RecurringEventRepository for Published<R> {
+    async fn save(&self, event: &RecurringEvent) -> Result<u32, RepositoryError> {
+        let version = self.inner.save(event).await?;
+
+        let (calendar_id, event_id) = (*event.calendar_id(), *event.event_id());
+        if *event.version() == 0 {
+            self.publisher.push([DomainEvent::SeriesCreated { calendar_id, event_id }]);
+        } else if event.pending_events().is_empty() {
+            self.publisher.push([DomainEvent::SeriesChanged {
+                calendar_id,
+                event_id,
+                changes: Vec::new(),
+            }]);
+        }
+        self.publisher.push(event.pending_events().iter().cloned());
+        Ok(version)
+    }
+
+    async fn find_by_calendar(&self, calendar_id: &CalendarId) -> Result<Vec<RecurringEvent>, RepositoryError> {
+        self.inner.find_by_calendar(calendar_id).await
+    }
+
+    async fn find_by_id(&self, event_id: &EventId) -> Result<RecurringEvent, RepositoryError> {
+        self.inner.find_by_id(event_id).await
+    }
+
+    async fn find_by_location(&self, calendar_id: &CalendarId, needle: &str) -> Result<Vec<RecurringEvent>, RepositoryError> {
+        self.inner.find_by_location(calendar_id, needle).await
+    }
+
+    async fn find_by_tag(&self, tag: &Tag) -> Result<Vec<RecurringEvent>, RepositoryError> {
+        self.inner.find_by_tag(tag).await
+    }
+
+    async fn find_with_reminders_in(&self, window: &TimeRange) -> Result<Vec<RecurringEvent>, RepositoryError> {
+        self.inner.find_with_reminders_in(window).await
+    }
+
+    async fn trash(&self, id: &EventId, deleted_at: DateTime<Utc>) -> Result<(), RepositoryError> {
+        self.inner.trash(id, deleted_at).await?;
+        if let Some(calendar_id) = self.series_calendar(id).await? {
+            self.publisher.push([DomainEvent::SeriesTrashed { calendar_id, event_id: *id }]);
+        }
+        Ok(())
+    }
+
+    async fn restore(&self, id: &EventId) -> Result<(), RepositoryError> {
+        self.inner.restore(id).await?;
+        if let Some(calendar_id) = self.series_calendar(id).await? {
+            self.publisher.push([DomainEvent::SeriesRestoredFromTrash { calendar_id, event_id: *id }]);
+        }
+        Ok(())
+    }
+
+    async fn find_trashed(&self) -> Result<Vec<RecurringEvent>, RepositoryError> {
+        self.inner.find_trashed().await
+    }
+
+    async fn purge_trashed(&self, cutoff: DateTime<Utc>) -> Result<u64, RepositoryError> {
+        let purged = expired(self.inner.find_trashed().await?, cutoff, RecurringEvent::deleted_at);
+        let count = self.inner.purge_trashed(cutoff).await?;
+        self.publisher.push(purged.iter().map(|event| DomainEvent::SeriesDeleted {
+            calendar_id: *event.calendar_id(),
+            event_id: *event.event_id(),
+        }));
+        Ok(count)
+    }
+
+    async fn delete(&self, id: &EventId) -> Result<(), RepositoryError> {
+        let calendar_id = self.series_calendar(id).await?;
+        self.inner.delete(id).await?;
+        if let Some(calendar_id) = calendar_id {
+            self.publisher.push([DomainEvent::SeriesDeleted { calendar_id, event_id: *id }]);
+        }
+        Ok(())
+    }
+}
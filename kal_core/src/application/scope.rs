@@ -0,0 +1,109 @@
+use chrono::Utc;
+
+use crate::{
+    application::{
+        history::{Recorded, Recorder},
+        publisher::{Published, Publisher},
+    },
+    domain::repository::{AuditRepository, HistoryRepository, RepositoryError},
+};
+
+/// Wires the repositories one command writes through, so every front end
+/// records and publishes the same things: calendars, events and series
+/// are recorded for undo and raise domain events, tasks and journal
+/// entries are recorded only. Run a command against a `scoped` copy,
+/// `record` and `audit` it with the rest of its writes, and `publish`
+/// once they are stored.
+#[derive(Debug, Clone, Default)]
+pub struct CommandScope {
+    recorder: Recorder,
+    publisher: Publisher,
+}
+
+impl CommandScope {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Publishes domain events to `publisher`'s subscribers.
+    pub fn with_publisher(mut self, publisher: Publisher) -> Self {
+        self.publisher = publisher;
+        self
+    }
+
+    /// Attributes the command's changes to `actor`, such as the user who
+    /// asked for it.
+    pub fn with_actor(mut self, actor: impl Into<String>) -> Self {
+        self.publisher = self.publisher.with_actor(actor);
+        self
+    }
+
+    /// A scope with the same subscribers and a recorder and event queue
+    /// of its own, so concurrent commands' changes stay apart.
+    pub fn scoped(&self) -> Self {
+        Self { recorder: Recorder::new(), publisher: self.publisher.scoped() }
+    }
+
+    pub fn calendars<C>(&self, calendars: C) -> Recorded<Published<C>> {
+        self.recorder.record(self.publisher.track(calendars))
+    }
+
+    pub fn events<E>(&self, events: E) -> Recorded<Published<E>> {
+        self.recorder.record(self.publisher.track(events))
+    }
+
+    pub fn recurring<R>(&self, recurring: R) -> Recorded<Published<R>> {
+        self.recorder.record(self.publisher.track(recurring))
+    }
+
+    pub fn tasks<T>(&self, tasks: T) -> Recorded<T> {
+        self.recorder.record(tasks)
+    }
+
+    pub fn journal<J>(&self, journal: J) -> Recorded<J> {
+        self.recorder.record(journal)
+    }
+
+    /// Publishes without recording, for undo and redo, which move the
+    /// history cursor instead, and for purges, which cannot be undone.
+    pub fn unrecorded<R>(&self, repository: R) -> Published<R> {
+        self.publisher.track(repository)
+    }
+
+    /// Adds what the command changed to `history` as `command`, if it
+    /// changed anything. Call it once the command has succeeded, in the
+    /// same transaction where there is one.
+    pub async fn record<H: HistoryRepository>(
+        &self,
+        history: &H,
+        command: &str,
+    ) -> Result<(), RepositoryError> {
+        let changes = self.recorder.take();
+        if !changes.is_empty() {
+            history.record(command, Utc::now(), &changes).await?;
+        }
+        Ok(())
+    }
+
+    /// Appends the command's events to `audit` as `command`'s doing. Call
+    /// it in the command's transaction: an entry that cannot be written
+    /// then fails the command instead of leaving a change nobody can
+    /// account for.
+    pub async fn audit<A: AuditRepository>(
+        &self,
+        audit: &A,
+        command: &str,
+    ) -> Result<(), RepositoryError> {
+        let context = self.publisher.context(command);
+        for event in self.publisher.pending() {
+            audit.append(&event, &context).await?;
+        }
+        Ok(())
+    }
+
+    /// Hands the command's events to the subscribers. Only call this once
+    /// its changes have been stored for good.
+    pub async fn publish(&self, command: &str) {
+        self.publisher.publish(command).await;
+    }
+}
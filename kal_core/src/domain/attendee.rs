@@ -0,0 +1,153 @@
+use std::fmt;
+
+use getset::Getters;
+use serde::{Deserialize, Serialize};
+
+use crate::domain::error::DomainError;
+
+/// What is expected of an attendee (iCalendar ROLE).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum AttendeeRole {
+    Chair,
+    #[default]
+    Required,
+    Optional,
+    NonParticipant,
+}
+
+impl fmt::Display for AttendeeRole {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AttendeeRole::Chair => write!(f, "CHAIR"),
+            AttendeeRole::Required => write!(f, "REQ-PARTICIPANT"),
+            AttendeeRole::Optional => write!(f, "OPT-PARTICIPANT"),
+            AttendeeRole::NonParticipant => write!(f, "NON-PARTICIPANT"),
+        }
+    }
+}
+
+impl std::str::FromStr for AttendeeRole {
+    type Err = DomainError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_uppercase().as_str() {
+            "CHAIR" => Ok(AttendeeRole::Chair),
+            "REQ-PARTICIPANT" => Ok(AttendeeRole::Required),
+            "OPT-PARTICIPANT" => Ok(AttendeeRole::Optional),
+            "NON-PARTICIPANT" => Ok(AttendeeRole::NonParticipant),
+            other => Err(DomainError::InvalidAttendee(format!("unknown role {other}"))),
+        }
+    }
+}
+
+/// An attendee's answer to the invitation (iCalendar PARTSTAT).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum ParticipationStatus {
+    #[default]
+    NeedsAction,
+    Accepted,
+    Declined,
+    Tentative,
+    Delegated,
+}
+
+impl fmt::Display for ParticipationStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParticipationStatus::NeedsAction => write!(f, "NEEDS-ACTION"),
+            ParticipationStatus::Accepted => write!(f, "ACCEPTED"),
+            ParticipationStatus::Declined => write!(f, "DECLINED"),
+            ParticipationStatus::Tentative => write!(f, "TENTATIVE"),
+            ParticipationStatus::Delegated => write!(f, "DELEGATED"),
+        }
+    }
+}
+
+impl std::str::FromStr for ParticipationStatus {
+    type Err = DomainError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_uppercase().as_str() {
+            "NEEDS-ACTION" => Ok(ParticipationStatus::NeedsAction),
+            "ACCEPTED" => Ok(ParticipationStatus::Accepted),
+            "DECLINED" => Ok(ParticipationStatus::Declined),
+            "TENTATIVE" => Ok(ParticipationStatus::Tentative),
+            "DELEGATED" => Ok(ParticipationStatus::Delegated),
+            other => Err(DomainError::InvalidAttendee(format!("unknown status {other}"))),
+        }
+    }
+}
+
+/// The person who owns an event or series.
+#[derive(Debug, Clone, PartialEq, Eq, Getters, Serialize, Deserialize)]
+pub struct Organizer {
+    #[getset(get = "pub")]
+    email: String,
+    #[getset(get = "pub")]
+    name: Option<String>,
+}
+
+impl Organizer {
+    pub fn new(email: String, name: Option<String>) -> Result<Self, DomainError> {
+        Ok(Self {
+            email: validate_email(email)?,
+            name: name.filter(|n| !n.trim().is_empty()),
+        })
+    }
+}
+
+/// Someone invited to an event or series, identified by email address.
+#[derive(Debug, Clone, PartialEq, Eq, Getters, Serialize, Deserialize)]
+pub struct Attendee {
+    #[getset(get = "pub")]
+    email: String,
+    #[getset(get = "pub")]
+    name: Option<String>,
+    #[getset(get = "pub")]
+    role: AttendeeRole,
+    #[getset(get = "pub")]
+    status: ParticipationStatus,
+    /// Whether the organizer asked for a reply.
+    #[getset(get = "pub")]
+    rsvp: bool,
+}
+
+impl Attendee {
+    pub fn new(
+        email: String,
+        name: Option<String>,
+        role: AttendeeRole,
+        status: ParticipationStatus,
+        rsvp: bool,
+    ) -> Result<Self, DomainError> {
+        Ok(Self {
+            email: validate_email(email)?,
+            name: name.filter(|n| !n.trim().is_empty()),
+            role,
+            status,
+            rsvp,
+        })
+    }
+
+    /// Email addresses are compared case-insensitively.
+    pub fn has_email(&self, email: &str) -> bool {
+        self.email.eq_ignore_ascii_case(email.trim())
+    }
+
+    pub fn set_status(&mut self, status: ParticipationStatus) {
+        self.status = status;
+    }
+}
+
+fn validate_email(email: String) -> Result<String, DomainError> {
+    let email = email.trim();
+
+    match email.split_once('@') {
+        Some((local, domain))
+            if !local.is_empty() && !domain.is_empty() && !email.contains(char::is_whitespace) =>
+        {
+            Ok(email.to_string())
+        }
+        _ => Err(DomainError::InvalidAttendee(format!("invalid email address {email}"))),
+    }
+}
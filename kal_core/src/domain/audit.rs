@@ -0,0 +1,51 @@
+use chrono::{DateTime, Utc};
+use getset::Getters;
+use uuid::Uuid;
+
+use crate::domain::{domain_event::FieldChange, value_objects::CalendarId};
+
+/// One domain event as the audit log keeps it: who set it off, through
+/// which command, and the fields it changed. Entries outlive the objects
+/// they describe.
+#[derive(Debug, Clone, Getters)]
+pub struct AuditEntry {
+    #[getset(get = "pub")]
+    id: i64,
+    #[getset(get = "pub")]
+    recorded_at: DateTime<Utc>,
+    #[getset(get = "pub")]
+    actor: String,
+    /// The command that ran, such as `event.update`.
+    #[getset(get = "pub")]
+    command: String,
+    /// The domain event's name, such as `EventRescheduled`.
+    #[getset(get = "pub")]
+    event: String,
+    #[getset(get = "pub")]
+    calendar_id: CalendarId,
+    /// The event or series, or the calendar for calendar events.
+    #[getset(get = "pub")]
+    object_id: Uuid,
+    /// The original start of the occurrence, for occurrence events.
+    #[getset(get = "pub")]
+    occurrence: Option<DateTime<Utc>>,
+    #[getset(get = "pub")]
+    changes: Vec<FieldChange>,
+}
+
+impl AuditEntry {
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        id: i64,
+        recorded_at: DateTime<Utc>,
+        actor: String,
+        command: String,
+        event: String,
+        calendar_id: CalendarId,
+        object_id: Uuid,
+        occurrence: Option<DateTime<Utc>>,
+        changes: Vec<FieldChange>,
+    ) -> Self {
+        Self { id, recorded_at, actor, command, event, calendar_id, object_id, occurrence, changes }
+    }
+}
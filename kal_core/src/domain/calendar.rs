@@ -1,9 +1,14 @@
 use chrono::{DateTime, Utc};
 use getset::Getters;
+use serde::{Deserialize, Serialize};
 
-use crate::domain::{error::DomainError, value_objects::CalendarId};
+use crate::domain::{
+    domain_event::{DomainEvent, FieldChange},
+    error::DomainError,
+    value_objects::{CalendarId, Subscription},
+};
 
-#[derive(Debug, Clone, Getters)]
+#[derive(Debug, Clone, Getters, Serialize, Deserialize)]
 pub struct Calendar {
     #[getset(get = "pub")]
     calendar_id: CalendarId,
@@ -14,9 +19,20 @@ pub struct Calendar {
     #[getset(get = "pub")]
     is_archived: bool,
     #[getset(get = "pub")]
+    subscription: Option<Subscription>,
+    /// Stored revision used to detect concurrent updates; 0 until saved.
+    #[getset(get = "pub")]
+    version: u32,
+    /// When the object was moved to the trash; `None` while it is live.
+    #[getset(get = "pub")]
+    deleted_at: Option<DateTime<Utc>>,
+    #[getset(get = "pub")]
     created_at: DateTime<Utc>,
     #[getset(get = "pub")]
     updated_at: DateTime<Utc>,
+    /// Raised by mutators since the calendar was loaded; not stored.
+    #[serde(skip)]
+    pending_events: Vec<DomainEvent>,
 }
 
 impl Calendar {
@@ -33,8 +49,12 @@ impl Calendar {
                 name,
                 description,
                 is_archived: false,
+                subscription: None,
+                version: 0,
+                deleted_at: None,
                 created_at: now,
                 updated_at: now,
+                pending_events: Vec::new(),
             })
         }
     }
@@ -44,6 +64,7 @@ impl Calendar {
         name: String,
         description: Option<String>,
         is_archived: bool,
+        subscription: Option<Subscription>,
         created_at: DateTime<Utc>,
         updated_at: DateTime<Utc>,
     ) -> Result<Self, DomainError> {
@@ -55,28 +76,111 @@ impl Calendar {
                 name,
                 description,
                 is_archived,
+                subscription,
+                version: 0,
+                deleted_at: None,
                 created_at,
                 updated_at,
+                pending_events: Vec::new(),
             })
         }
     }
 
+    pub fn subscribed(
+        name: String,
+        description: Option<String>,
+        subscription: Subscription,
+    ) -> Result<Self, DomainError> {
+        let mut calendar = Self::new(name, description)?;
+        calendar.subscription = Some(subscription);
+        Ok(calendar)
+    }
+
+    /// Restores the stored version when rebuilding a calendar.
+    pub fn with_version(mut self, version: u32) -> Self {
+        self.version = version;
+        self
+    }
+
+    /// Restores the stored deletion time when rebuilding a calendar.
+    pub fn with_deleted_at(mut self, deleted_at: Option<DateTime<Utc>>) -> Self {
+        self.deleted_at = deleted_at;
+        self
+    }
+
+    pub fn is_trashed(&self) -> bool {
+        self.deleted_at.is_some()
+    }
+
+    /// Events raised since the calendar was loaded or last drained.
+    pub fn pending_events(&self) -> &[DomainEvent] {
+        &self.pending_events
+    }
+
+    pub fn take_pending_events(&mut self) -> Vec<DomainEvent> {
+        std::mem::take(&mut self.pending_events)
+    }
+
+    /// Drops raised events, as storing and reloading the calendar would.
+    pub fn without_pending_events(mut self) -> Self {
+        self.pending_events.clear();
+        self
+    }
+
+    pub fn is_subscription(&self) -> bool {
+        self.subscription.is_some()
+    }
+
+    /// Subscribed calendars mirror a remote feed and are only changed by
+    /// a refresh.
+    pub fn ensure_writable(&self) -> Result<(), DomainError> {
+        if self.is_subscription() {
+            Err(DomainError::SubscriptionReadOnly)
+        } else {
+            Ok(())
+        }
+    }
+
+    pub fn record_fetch(
+        &mut self,
+        fetched_at: DateTime<Utc>,
+        etag: Option<String>,
+        last_modified: Option<String>,
+    ) {
+        if let Some(subscription) = self.subscription.as_mut() {
+            subscription.record_fetch(fetched_at, etag, last_modified);
+            self.touch();
+        }
+    }
+
     pub fn archive(&mut self) {
+        if !self.is_archived {
+            self.raise(DomainEvent::CalendarArchived { calendar_id: self.calendar_id });
+        }
         self.is_archived = true;
         self.touch();
     }
 
     pub fn unarchive(&mut self) {
+        if self.is_archived {
+            self.raise(DomainEvent::CalendarUnarchived { calendar_id: self.calendar_id });
+        }
         self.is_archived = false;
         self.touch();
     }
 
     pub fn update_name(&mut self, name: String) {
+        self.changed(FieldChange::set("name", &self.name, &name));
         self.name = name;
         self.touch();
     }
 
     pub fn update_description(&mut self, description: Option<String>) {
+        self.changed(FieldChange::between(
+            "description",
+            self.description.as_ref(),
+            description.as_ref(),
+        ));
         self.description = description;
         self.touch();
     }
@@ -84,4 +188,15 @@ impl Calendar {
     pub fn touch(&mut self) {
         self.updated_at = Utc::now();
     }
+
+    fn raise(&mut self, event: DomainEvent) {
+        self.pending_events.push(event);
+    }
+
+    fn changed(&mut self, change: FieldChange) {
+        if change.from() != change.to() {
+            let calendar_id = self.calendar_id;
+            self.raise(DomainEvent::CalendarChanged { calendar_id, changes: vec![change] });
+        }
+    }
 }
@@ -0,0 +1,140 @@
+use std::collections::BTreeSet;
+
+use chrono::{DateTime, Utc};
+
+use crate::domain::{
+    attendee::{Attendee, Organizer},
+    error::DomainError,
+    event::Event,
+    recurrence::RecurringEvent,
+    tag::Tag,
+    value_objects::{CalendarId, EventId},
+};
+
+/// A single calendar object resource: one UID, possibly with overrides.
+#[derive(Debug, Clone)]
+pub enum CalendarObject {
+    Event(Event),
+    Recurring(RecurringEvent),
+}
+
+impl CalendarObject {
+    pub fn event_id(&self) -> &EventId {
+        match self {
+            CalendarObject::Event(event) => event.event_id(),
+            CalendarObject::Recurring(event) => event.event_id(),
+        }
+    }
+
+    pub fn calendar_id(&self) -> &CalendarId {
+        match self {
+            CalendarObject::Event(event) => event.calendar_id(),
+            CalendarObject::Recurring(event) => event.calendar_id(),
+        }
+    }
+
+    pub fn updated_at(&self) -> &DateTime<Utc> {
+        match self {
+            CalendarObject::Event(event) => event.updated_at(),
+            CalendarObject::Recurring(event) => event.updated_at(),
+        }
+    }
+
+    pub fn organizer(&self) -> Option<&Organizer> {
+        match self {
+            CalendarObject::Event(event) => event.organizer().as_ref(),
+            CalendarObject::Recurring(event) => event.organizer().as_ref(),
+        }
+    }
+
+    pub fn attendees(&self) -> &[Attendee] {
+        match self {
+            CalendarObject::Event(event) => event.attendees(),
+            CalendarObject::Recurring(event) => event.attendees(),
+        }
+    }
+
+    pub fn tags(&self) -> &BTreeSet<Tag> {
+        match self {
+            CalendarObject::Event(event) => event.tags(),
+            CalendarObject::Recurring(event) => event.tags(),
+        }
+    }
+
+    pub fn sequence(&self) -> u32 {
+        match self {
+            CalendarObject::Event(event) => *event.sequence(),
+            CalendarObject::Recurring(event) => *event.sequence(),
+        }
+    }
+
+    pub fn version(&self) -> u32 {
+        match self {
+            CalendarObject::Event(event) => *event.version(),
+            CalendarObject::Recurring(event) => *event.version(),
+        }
+    }
+
+    /// Restores the stored version on whichever object this wraps.
+    pub fn with_version(self, version: u32) -> Self {
+        match self {
+            CalendarObject::Event(event) => CalendarObject::Event(event.with_version(version)),
+            CalendarObject::Recurring(event) => {
+                CalendarObject::Recurring(event.with_version(version))
+            }
+        }
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        match self {
+            CalendarObject::Event(event) => event.is_cancelled(),
+            CalendarObject::Recurring(event) => event.is_cancelled(),
+        }
+    }
+
+    pub fn is_recurring(&self) -> bool {
+        matches!(self, CalendarObject::Recurring(_))
+    }
+
+    /// Copies the object under another `EventId`.
+    pub fn duplicate(&self, event_id: EventId) -> Result<Self, DomainError> {
+        Ok(match self {
+            CalendarObject::Event(e) => CalendarObject::Event(Event::with_id(
+                event_id,
+                *e.calendar_id(),
+                e.title().clone(),
+                e.description().clone(),
+                *e.time_range(),
+                *e.color(),
+                *e.is_all_day(),
+                *e.status(),
+                *e.created_at(),
+                Utc::now(),
+            )?
+            .with_reminders(e.reminders().clone())
+            .with_participants(e.organizer().clone(), e.attendees().clone())
+            .with_place(e.location().clone(), e.url().clone(), *e.geo())
+            .with_transparency(*e.transparency())
+            .with_tags(e.tags().iter().cloned())),
+            CalendarObject::Recurring(e) => CalendarObject::Recurring(RecurringEvent::with_id(
+                event_id,
+                *e.calendar_id(),
+                e.title().clone(),
+                e.description().clone(),
+                *e.time_range(),
+                e.rule().clone(),
+                e.exceptions().clone(),
+                *e.color(),
+                *e.is_all_day(),
+                *e.status(),
+                *e.created_at(),
+                Utc::now(),
+            )?
+            .with_reminders(e.reminders().clone())
+            .with_participants(e.organizer().clone(), e.attendees().clone())
+            .with_place(e.location().clone(), e.url().clone(), *e.geo())
+            .with_transparency(*e.transparency())
+            .with_tags(e.tags().iter().cloned())),
+        })
+    }
+}
@@ -0,0 +1,262 @@
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use getset::Getters;
+use uuid::Uuid;
+
+use crate::domain::value_objects::{CalendarId, EventId, TimeRange};
+
+/// One field an edit changed, as text. `from` is `None` when the field
+/// was empty or the value was added; `to` when it was cleared or removed.
+#[derive(Debug, Clone, PartialEq, Eq, Getters)]
+pub struct FieldChange {
+    /// The field, such as `title`; one attendee or reminder is named
+    /// after the collection, such as `attendees`.
+    #[getset(get = "pub")]
+    field: String,
+    #[getset(get = "pub")]
+    from: Option<String>,
+    #[getset(get = "pub")]
+    to: Option<String>,
+}
+
+impl FieldChange {
+    pub fn new(field: impl Into<String>, from: Option<String>, to: Option<String>) -> Self {
+        Self { field: field.into(), from, to }
+    }
+
+    /// A change between two values that may be empty.
+    pub(crate) fn between<T: ToString>(field: &str, from: Option<&T>, to: Option<&T>) -> Self {
+        Self::new(field, from.map(T::to_string), to.map(T::to_string))
+    }
+
+    pub(crate) fn set<T: ToString>(field: &str, from: &T, to: &T) -> Self {
+        Self::between(field, Some(from), Some(to))
+    }
+
+    pub(crate) fn added<T: ToString>(field: &str, value: &T) -> Self {
+        Self::between(field, None, Some(value))
+    }
+
+    pub(crate) fn removed<T: ToString>(field: &str, value: &T) -> Self {
+        Self::between(field, Some(value), None)
+    }
+}
+
+/// Something that happened to a calendar, event or series. Mutators raise
+/// these on the aggregate; creating, trashing, restoring and deleting are
+/// raised where the aggregate is saved. Subscribers hear of them once the
+/// change is stored.
+#[derive(Debug, Clone, PartialEq)]
+pub enum DomainEvent {
+    CalendarCreated { calendar_id: CalendarId },
+    CalendarChanged { calendar_id: CalendarId, changes: Vec<FieldChange> },
+    CalendarArchived { calendar_id: CalendarId },
+    CalendarUnarchived { calendar_id: CalendarId },
+    /// Its events and series went to the trash with it.
+    CalendarTrashed { calendar_id: CalendarId },
+    CalendarRestoredFromTrash { calendar_id: CalendarId },
+    /// Deleted for good, or purged from the trash; its events and series
+    /// went with it.
+    CalendarDeleted { calendar_id: CalendarId },
+
+    EventCreated { calendar_id: CalendarId, event_id: EventId },
+    /// `changes` is empty when a save replaced the event wholesale, as
+    /// undo does.
+    EventChanged { calendar_id: CalendarId, event_id: EventId, changes: Vec<FieldChange> },
+    EventRescheduled { calendar_id: CalendarId, event_id: EventId, from: TimeRange, to: TimeRange },
+    EventCancelled { calendar_id: CalendarId, event_id: EventId },
+    /// Brought back from cancelled.
+    EventRestored { calendar_id: CalendarId, event_id: EventId },
+    EventTrashed { calendar_id: CalendarId, event_id: EventId },
+    EventRestoredFromTrash { calendar_id: CalendarId, event_id: EventId },
+    EventDeleted { calendar_id: CalendarId, event_id: EventId },
+
+    SeriesCreated { calendar_id: CalendarId, event_id: EventId },
+    /// `changes` is empty when a save replaced the series wholesale.
+    SeriesChanged { calendar_id: CalendarId, event_id: EventId, changes: Vec<FieldChange> },
+    SeriesCancelled { calendar_id: CalendarId, event_id: EventId },
+    SeriesRestored { calendar_id: CalendarId, event_id: EventId },
+    SeriesTrashed { calendar_id: CalendarId, event_id: EventId },
+    SeriesRestoredFromTrash { calendar_id: CalendarId, event_id: EventId },
+    SeriesDeleted { calendar_id: CalendarId, event_id: EventId },
+
+    /// Occurrences are named by their original start.
+    OccurrenceCancelled { calendar_id: CalendarId, event_id: EventId, occurrence: DateTime<Utc> },
+    OccurrenceRescheduled {
+        calendar_id: CalendarId,
+        event_id: EventId,
+        occurrence: DateTime<Utc>,
+        to: TimeRange,
+    },
+    /// Any cancellation or override was dropped.
+    OccurrenceRestored { calendar_id: CalendarId, event_id: EventId, occurrence: DateTime<Utc> },
+    OccurrenceChanged {
+        calendar_id: CalendarId,
+        event_id: EventId,
+        occurrence: DateTime<Utc>,
+        changes: Vec<FieldChange>,
+    },
+}
+
+impl DomainEvent {
+    /// The variant's name, such as `EventRescheduled`.
+    pub fn name(&self) -> &'static str {
+        match self {
+            DomainEvent::CalendarCreated { .. } => "CalendarCreated",
+            DomainEvent::CalendarChanged { .. } => "CalendarChanged",
+            DomainEvent::CalendarArchived { .. } => "CalendarArchived",
+            DomainEvent::CalendarUnarchived { .. } => "CalendarUnarchived",
+            DomainEvent::CalendarTrashed { .. } => "CalendarTrashed",
+            DomainEvent::CalendarRestoredFromTrash { .. } => "CalendarRestoredFromTrash",
+            DomainEvent::CalendarDeleted { .. } => "CalendarDeleted",
+            DomainEvent::EventCreated { .. } => "EventCreated",
+            DomainEvent::EventChanged { .. } => "EventChanged",
+            DomainEvent::EventRescheduled { .. } => "EventRescheduled",
+            DomainEvent::EventCancelled { .. } => "EventCancelled",
+            DomainEvent::EventRestored { .. } => "EventRestored",
+            DomainEvent::EventTrashed { .. } => "EventTrashed",
+            DomainEvent::EventRestoredFromTrash { .. } => "EventRestoredFromTrash",
+            DomainEvent::EventDeleted { .. } => "EventDeleted",
+            DomainEvent::SeriesCreated { .. } => "SeriesCreated",
+            DomainEvent::SeriesChanged { .. } => "SeriesChanged",
+            DomainEvent::SeriesCancelled { .. } => "SeriesCancelled",
+            DomainEvent::SeriesRestored { .. } => "SeriesRestored",
+            DomainEvent::SeriesTrashed { .. } => "SeriesTrashed",
+            DomainEvent::SeriesRestoredFromTrash { .. } => "SeriesRestoredFromTrash",
+            DomainEvent::SeriesDeleted { .. } => "SeriesDeleted",
+            DomainEvent::OccurrenceCancelled { .. } => "OccurrenceCancelled",
+            DomainEvent::OccurrenceRescheduled { .. } => "OccurrenceRescheduled",
+            DomainEvent::OccurrenceRestored { .. } => "OccurrenceRestored",
+            DomainEvent::OccurrenceChanged { .. } => "OccurrenceChanged",
+        }
+    }
+
+    /// The calendar affected; a calendar's own id for calendar events.
+    pub fn calendar_id(&self) -> CalendarId {
+        match self {
+            DomainEvent::CalendarCreated { calendar_id }
+            | DomainEvent::CalendarChanged { calendar_id, .. }
+            | DomainEvent::CalendarArchived { calendar_id }
+            | DomainEvent::CalendarUnarchived { calendar_id }
+            | DomainEvent::CalendarTrashed { calendar_id }
+            | DomainEvent::CalendarRestoredFromTrash { calendar_id }
+            | DomainEvent::CalendarDeleted { calendar_id }
+            | DomainEvent::EventCreated { calendar_id, .. }
+            | DomainEvent::EventChanged { calendar_id, .. }
+            | DomainEvent::EventRescheduled { calendar_id, .. }
+            | DomainEvent::EventCancelled { calendar_id, .. }
+            | DomainEvent::EventRestored { calendar_id, .. }
+            | DomainEvent::EventTrashed { calendar_id, .. }
+            | DomainEvent::EventRestoredFromTrash { calendar_id, .. }
+            | DomainEvent::EventDeleted { calendar_id, .. }
+            | DomainEvent::SeriesCreated { calendar_id, .. }
+            | DomainEvent::SeriesChanged { calendar_id, .. }
+            | DomainEvent::SeriesCancelled { calendar_id, .. }
+            | DomainEvent::SeriesRestored { calendar_id, .. }
+            | DomainEvent::SeriesTrashed { calendar_id, .. }
+            | DomainEvent::SeriesRestoredFromTrash { calendar_id, .. }
+            | DomainEvent::SeriesDeleted { calendar_id, .. }
+            | DomainEvent::OccurrenceCancelled { calendar_id, .. }
+            | DomainEvent::OccurrenceRescheduled { calendar_id, .. }
+            | DomainEvent::OccurrenceRestored { calendar_id, .. }
+            | DomainEvent::OccurrenceChanged { calendar_id, .. } => *calendar_id,
+        }
+    }
+
+    /// The event or series affected, if any.
+    pub fn event_id(&self) -> Option<EventId> {
+        match self {
+            DomainEvent::CalendarCreated { .. }
+            | DomainEvent::CalendarChanged { .. }
+            | DomainEvent::CalendarArchived { .. }
+            | DomainEvent::CalendarUnarchived { .. }
+            | DomainEvent::CalendarTrashed { .. }
+            | DomainEvent::CalendarRestoredFromTrash { .. }
+            | DomainEvent::CalendarDeleted { .. } => None,
+            DomainEvent::EventCreated { event_id, .. }
+            | DomainEvent::EventChanged { event_id, .. }
+            | DomainEvent::EventRescheduled { event_id, .. }
+            | DomainEvent::EventCancelled { event_id, .. }
+            | DomainEvent::EventRestored { event_id, .. }
+            | DomainEvent::EventTrashed { event_id, .. }
+            | DomainEvent::EventRestoredFromTrash { event_id, .. }
+            | DomainEvent::EventDeleted { event_id, .. }
+            | DomainEvent::SeriesCreated { event_id, .. }
+            | DomainEvent::SeriesChanged { event_id, .. }
+            | DomainEvent::SeriesCancelled { event_id, .. }
+            | DomainEvent::SeriesRestored { event_id, .. }
+            | DomainEvent::SeriesTrashed { event_id, .. }
+            | DomainEvent::SeriesRestoredFromTrash { event_id, .. }
+            | DomainEvent::SeriesDeleted { event_id, .. }
+            | DomainEvent::OccurrenceCancelled { event_id, .. }
+            | DomainEvent::OccurrenceRescheduled { event_id, .. }
+            | DomainEvent::OccurrenceRestored { event_id, .. }
+            | DomainEvent::OccurrenceChanged { event_id, .. } => Some(*event_id),
+        }
+    }
+
+    /// The id of the object the event is about: the event or series, or
+    /// else the calendar.
+    pub fn object_id(&self) -> Uuid {
+        self.event_id()
+            .map(|id| id.as_uuid())
+            .unwrap_or_else(|| self.calendar_id().as_uuid())
+    }
+
+    /// The original start of the occurrence affected, for occurrence events.
+    pub fn occurrence(&self) -> Option<DateTime<Utc>> {
+        match self {
+            DomainEvent::OccurrenceCancelled { occurrence, .. }
+            | DomainEvent::OccurrenceRescheduled { occurrence, .. }
+            | DomainEvent::OccurrenceRestored { occurrence, .. }
+            | DomainEvent::OccurrenceChanged { occurrence, .. } => Some(*occurrence),
+            _ => None,
+        }
+    }
+
+    /// The fields the event changed, where it names them. A moved event
+    /// or occurrence changes its `time_range`; an occurrence's original
+    /// range is left out as the series implies it.
+    pub fn changes(&self) -> Vec<FieldChange> {
+        match self {
+            DomainEvent::CalendarChanged { changes, .. }
+            | DomainEvent::EventChanged { changes, .. }
+            | DomainEvent::SeriesChanged { changes, .. }
+            | DomainEvent::OccurrenceChanged { changes, .. } => changes.clone(),
+            DomainEvent::EventRescheduled { from, to, .. } => {
+                vec![FieldChange::set("time_range", from, to)]
+            }
+            DomainEvent::OccurrenceRescheduled { to, .. } => {
+                vec![FieldChange::added("time_range", to)]
+            }
+            _ => Vec::new(),
+        }
+    }
+}
+
+/// Who set off the domain events published together, and how.
+#[derive(Debug, Clone, PartialEq, Eq, Getters)]
+pub struct EventContext {
+    /// The command that ran, such as `event.update`.
+    #[getset(get = "pub")]
+    command: String,
+    /// Who ran it: a user name, or `local` when nobody was named.
+    #[getset(get = "pub")]
+    actor: String,
+    #[getset(get = "pub")]
+    occurred_at: DateTime<Utc>,
+}
+
+impl EventContext {
+    pub fn new(command: impl Into<String>, actor: impl Into<String>, occurred_at: DateTime<Utc>) -> Self {
+        Self { command: command.into(), actor: actor.into(), occurred_at }
+    }
+}
+
+/// Reacts to domain events, e.g. to reschedule reminders or invalidate a
+/// cache. Called after the change that raised an event has been stored;
+/// a subscriber that fails handles it itself, as the change stands.
+#[async_trait]
+pub trait EventSubscriber: Send + Sync {
+    async fn notify(&self, event: &DomainEvent, context: &EventContext);
+}
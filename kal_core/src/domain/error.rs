@@ -31,4 +31,55 @@ pub enum DomainError {
 
     #[error("Cannot modify archived calendar")]
     CalendarArchived,
+
+    #[error("Calendar is a read-only subscription")]
+    SubscriptionReadOnly,
+
+    #[error("Invalid subscription URL: {0}")]
+    InvalidSubscriptionUrl(String),
+
+    #[error("Invalid refresh interval: must be greater than 0 and at most a year")]
+    InvalidRefreshInterval,
+
+    #[error("Invalid sync item kind")]
+    InvalidSyncItemKind,
+
+    #[error("Invalid history object kind")]
+    InvalidObjectKind,
+
+    #[error("Invalid conflict strategy")]
+    InvalidConflictStrategy,
+
+    #[error("Invalid reminder: {0}")]
+    InvalidReminder(String),
+
+    #[error("Reminder not found: {0}")]
+    ReminderNotFound(String),
+
+    #[error("Invalid attendee: {0}")]
+    InvalidAttendee(String),
+
+    #[error("Attendee not found: {0}")]
+    AttendeeNotFound(String),
+
+    #[error("Invalid coordinates: {0}")]
+    InvalidGeo(String),
+
+    #[error("Invalid URL: {0}")]
+    InvalidUrl(String),
+
+    #[error("Occurrence is cancelled: {0}")]
+    OccurrenceCancelled(String),
+
+    #[error("Invalid tag: {0}")]
+    InvalidTag(String),
+
+    #[error("Tag not found: {0}")]
+    TagNotFound(String),
+
+    #[error("Invalid status: {0}")]
+    InvalidStatus(String),
+
+    #[error("Invalid task: {0}")]
+    InvalidTask(String),
 }
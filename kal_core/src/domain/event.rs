@@ -1,12 +1,22 @@
+use std::collections::BTreeSet;
+
 use chrono::{DateTime, Utc};
 use getset::Getters;
+use serde::{Deserialize, Serialize};
 
 use crate::domain::{
+    attendee::{Attendee, Organizer, ParticipationStatus},
+    domain_event::{DomainEvent, FieldChange},
     error::DomainError,
-    value_objects::{CalendarId, EventColor, EventId, TimeRange}
+    reminder::{Reminder, ReminderInstance},
+    tag::Tag,
+    value_objects::{
+        validate_url, CalendarId, EventColor, EventId, EventStatus, GeoPoint, ReminderId,
+        TimeRange, Transparency,
+    }
 };
 
-#[derive(Debug, Clone, Getters)]
+#[derive(Debug, Clone, Getters, Serialize, Deserialize)]
 pub struct Event {
     #[getset(get = "pub")]
     event_id: EventId,
@@ -17,17 +27,45 @@ pub struct Event {
     #[getset(get = "pub")]
     description: Option<String>,
     #[getset(get = "pub")]
+    location: Option<String>,
+    #[getset(get = "pub")]
+    url: Option<String>,
+    #[getset(get = "pub")]
+    geo: Option<GeoPoint>,
+    #[getset(get = "pub")]
     time_range: TimeRange,
     #[getset(get = "pub")]
     color: EventColor,
     #[getset(get = "pub")]
     is_all_day: bool,
     #[getset(get = "pub")]
-    is_cancelled: bool,
+    status: EventStatus,
+    #[getset(get = "pub")]
+    transparency: Transparency,
+    #[getset(get = "pub")]
+    reminders: Vec<Reminder>,
+    #[getset(get = "pub")]
+    organizer: Option<Organizer>,
+    #[getset(get = "pub")]
+    attendees: Vec<Attendee>,
+    #[getset(get = "pub")]
+    tags: BTreeSet<Tag>,
+    /// iTIP SEQUENCE; bumped by changes attendees must be told about.
+    #[getset(get = "pub")]
+    sequence: u32,
+    /// Stored revision used to detect concurrent updates; 0 until saved.
+    #[getset(get = "pub")]
+    version: u32,
+    /// When the object was moved to the trash; `None` while it is live.
+    #[getset(get = "pub")]
+    deleted_at: Option<DateTime<Utc>>,
     #[getset(get = "pub")]
     created_at: DateTime<Utc>,
     #[getset(get = "pub")]
     updated_at: DateTime<Utc>,
+    /// Raised by mutators since the event was loaded; not stored.
+    #[serde(skip)]
+    pending_events: Vec<DomainEvent>,
 }
 
 impl Event {
@@ -48,12 +86,24 @@ impl Event {
                 calendar_id,
                 title,
                 description,
+                location: None,
+                url: None,
+                geo: None,
                 time_range,
                 color,
                 is_all_day,
-                is_cancelled: false,
+                status: EventStatus::default(),
+                transparency: Transparency::default(),
+                reminders: Vec::new(),
+                organizer: None,
+                attendees: Vec::new(),
+                tags: BTreeSet::new(),
+                sequence: 0,
+                version: 0,
+                deleted_at: None,
                 created_at: now,
                 updated_at: now,
+                pending_events: Vec::new(),
             })
         }
     }
@@ -66,7 +116,7 @@ impl Event {
         time_range: TimeRange,
         color: EventColor,
         is_all_day: bool,
-        is_cancelled: bool,
+        status: EventStatus,
         created_at: DateTime<Utc>,
         updated_at: DateTime<Utc>,
     ) -> Result<Self, DomainError> {
@@ -78,54 +128,365 @@ impl Event {
                 calendar_id,
                 title,
                 description,
+                location: None,
+                url: None,
+                geo: None,
                 time_range,
                 color,
                 is_all_day,
-                is_cancelled,
+                status,
+                transparency: Transparency::default(),
+                reminders: Vec::new(),
+                organizer: None,
+                attendees: Vec::new(),
+                tags: BTreeSet::new(),
+                sequence: 0,
+                version: 0,
+                deleted_at: None,
                 created_at,
                 updated_at,
+                pending_events: Vec::new(),
             })
         }
     }
+
+    /// Attaches stored reminders when rebuilding an event.
+    pub fn with_reminders(mut self, reminders: Vec<Reminder>) -> Self {
+        self.reminders = reminders;
+        self
+    }
+
+    /// Attaches stored tags when rebuilding an event.
+    pub fn with_tags(mut self, tags: impl IntoIterator<Item = Tag>) -> Self {
+        self.tags = tags.into_iter().collect();
+        self
+    }
+
+    /// Attaches the stored location, URL and coordinates when rebuilding
+    /// an event.
+    pub fn with_place(
+        mut self,
+        location: Option<String>,
+        url: Option<String>,
+        geo: Option<GeoPoint>,
+    ) -> Self {
+        self.location = location;
+        self.url = url;
+        self.geo = geo;
+        self
+    }
+
+    /// Restores the stored transparency when rebuilding an event.
+    pub fn with_transparency(mut self, transparency: Transparency) -> Self {
+        self.transparency = transparency;
+        self
+    }
+
+    /// Restores the stored sequence number when rebuilding an event.
+    pub fn with_sequence(mut self, sequence: u32) -> Self {
+        self.sequence = sequence;
+        self
+    }
+
+    /// Restores the stored version when rebuilding an event.
+    pub fn with_version(mut self, version: u32) -> Self {
+        self.version = version;
+        self
+    }
+
+    /// Restores the stored deletion time when rebuilding an event.
+    pub fn with_deleted_at(mut self, deleted_at: Option<DateTime<Utc>>) -> Self {
+        self.deleted_at = deleted_at;
+        self
+    }
+
+    pub fn is_trashed(&self) -> bool {
+        self.deleted_at.is_some()
+    }
+
+    /// Events raised since the event was loaded or last drained.
+    pub fn pending_events(&self) -> &[DomainEvent] {
+        &self.pending_events
+    }
+
+    pub fn take_pending_events(&mut self) -> Vec<DomainEvent> {
+        std::mem::take(&mut self.pending_events)
+    }
+
+    /// Drops raised events, as storing and reloading the event would.
+    pub fn without_pending_events(mut self) -> Self {
+        self.pending_events.clear();
+        self
+    }
+
+    /// Attaches stored participants when rebuilding an event.
+    pub fn with_participants(
+        mut self,
+        organizer: Option<Organizer>,
+        attendees: Vec<Attendee>,
+    ) -> Self {
+        self.organizer = organizer;
+        self.attendees = attendees;
+        self
+    }
     
     pub fn cancel(&mut self) {
-        self.is_cancelled = true;
-        self.touch();
+        self.set_status(EventStatus::Cancelled);
     }
 
+    /// Restoring confirms an event, whatever it was before cancelling.
     pub fn restore(&mut self) {
-        self.is_cancelled = false;
+        self.set_status(EventStatus::Confirmed);
+    }
+
+    pub fn set_status(&mut self, status: EventStatus) {
+        let (calendar_id, event_id) = (self.calendar_id, self.event_id);
+        match (self.status, status) {
+            (from, to) if from == to => {}
+            (_, EventStatus::Cancelled) => {
+                self.raise(DomainEvent::EventCancelled { calendar_id, event_id })
+            }
+            (EventStatus::Cancelled, _) => {
+                self.raise(DomainEvent::EventRestored { calendar_id, event_id })
+            }
+            (from, to) => self.changed(FieldChange::set("status", &from, &to)),
+        }
+
+        self.status = status;
+        self.revise();
+    }
+
+    pub fn set_transparency(&mut self, transparency: Transparency) {
+        self.changed(FieldChange::set("transparency", &self.transparency, &transparency));
+        self.transparency = transparency;
         self.touch();
     }
 
+    pub fn is_cancelled(&self) -> bool {
+        self.status == EventStatus::Cancelled
+    }
+
+    /// Whether an event takes up time: it is not cancelled and not
+    /// transparent. Tentative holds count as busy.
+    pub fn is_busy(&self) -> bool {
+        !self.is_cancelled() && self.transparency == Transparency::Opaque
+    }
+
     pub fn update_title(&mut self, title: String) {
+        self.changed(FieldChange::set("title", &self.title, &title));
         self.title = title;
         self.touch();
     }
 
     pub fn update_description(&mut self, description: Option<String>) {
+        self.changed(FieldChange::between(
+            "description",
+            self.description.as_ref(),
+            description.as_ref(),
+        ));
         self.description = description;
         self.touch();
     }
 
     pub fn update_time_range(&mut self, time_range: TimeRange) {
+        if self.time_range != time_range {
+            self.raise(DomainEvent::EventRescheduled {
+                calendar_id: self.calendar_id,
+                event_id: self.event_id,
+                from: self.time_range,
+                to: time_range,
+            });
+        }
+
         self.time_range = time_range;
-        self.touch();
+        self.revise();
     }
 
     pub fn update_color(&mut self, color: EventColor) {
+        self.changed(FieldChange::set("color", &u8::from(self.color), &u8::from(color)));
         self.color = color;
         self.touch();
     }
 
+    pub fn add_reminder(&mut self, reminder: Reminder) {
+        self.changed(FieldChange::added("reminders", reminder.reminder_id()));
+        self.reminders.push(reminder);
+        self.touch();
+    }
+
+    pub fn remove_reminder(&mut self, reminder_id: &ReminderId) -> Result<(), DomainError> {
+        let index = self
+            .reminders
+            .iter()
+            .position(|r| r.reminder_id() == reminder_id)
+            .ok_or_else(|| DomainError::ReminderNotFound(reminder_id.to_string()))?;
+
+        self.reminders.remove(index);
+        self.changed(FieldChange::removed("reminders", reminder_id));
+        self.touch();
+        Ok(())
+    }
+
+    /// Blank locations are stored as none.
+    pub fn update_location(&mut self, location: Option<String>) {
+        let location = location.filter(|l| !l.trim().is_empty());
+        self.changed(FieldChange::between("location", self.location.as_ref(), location.as_ref()));
+        self.location = location;
+        self.revise();
+    }
+
+    pub fn update_url(&mut self, url: Option<String>) -> Result<(), DomainError> {
+        if let Some(url) = &url {
+            validate_url(url)?;
+        }
+
+        self.changed(FieldChange::between("url", self.url.as_ref(), url.as_ref()));
+        self.url = url;
+        self.touch();
+        Ok(())
+    }
+
+    pub fn update_geo(&mut self, geo: Option<GeoPoint>) {
+        self.changed(FieldChange::between("geo", self.geo.as_ref(), geo.as_ref()));
+        self.geo = geo;
+        self.touch();
+    }
+
+    pub fn set_organizer(&mut self, organizer: Option<Organizer>) {
+        self.changed(FieldChange::between(
+            "organizer",
+            self.organizer.as_ref().map(Organizer::email),
+            organizer.as_ref().map(Organizer::email),
+        ));
+        self.organizer = organizer;
+        self.touch();
+    }
+
+    pub fn add_attendee(&mut self, attendee: Attendee) -> Result<(), DomainError> {
+        if self.attendees.iter().any(|a| a.has_email(attendee.email())) {
+            return Err(DomainError::InvalidAttendee(format!(
+                "{} is already invited",
+                attendee.email()
+            )));
+        }
+
+        self.changed(FieldChange::added("attendees", attendee.email()));
+        self.attendees.push(attendee);
+        self.touch();
+        Ok(())
+    }
+
+    pub fn remove_attendee(&mut self, email: &str) -> Result<(), DomainError> {
+        let index = self
+            .attendees
+            .iter()
+            .position(|a| a.has_email(email))
+            .ok_or_else(|| DomainError::AttendeeNotFound(email.to_string()))?;
+
+        let attendee = self.attendees.remove(index);
+        self.changed(FieldChange::removed("attendees", attendee.email()));
+        self.touch();
+        Ok(())
+    }
+
+    pub fn update_participation(
+        &mut self,
+        email: &str,
+        status: ParticipationStatus,
+    ) -> Result<(), DomainError> {
+        let attendee = self
+            .attendees
+            .iter_mut()
+            .find(|a| a.has_email(email))
+            .ok_or_else(|| DomainError::AttendeeNotFound(email.to_string()))?;
+        let change =
+            FieldChange::set(&format!("attendee {}", attendee.email()), attendee.status(), &status);
+        attendee.set_status(status);
+
+        self.changed(change);
+        self.touch();
+        Ok(())
+    }
+
+    /// Reminders resolved against this event's start. Cancelled events
+    /// have none.
+    pub fn reminder_instances(&self) -> Vec<ReminderInstance> {
+        if self.is_cancelled() {
+            return Vec::new();
+        }
+
+        let starts_at = *self.time_range.starts_at();
+
+        self.reminders
+            .iter()
+            .map(|reminder| {
+                ReminderInstance::new(self.event_id, starts_at, starts_at, reminder.clone())
+            })
+            .collect()
+    }
+
+    /// Whether both events are busy at the same time in one calendar.
     pub fn overlaps_with(&self, other: &Event) -> bool {
-        !self.is_cancelled 
-            && !other.is_cancelled 
+        self.is_busy()
+            && other.is_busy()
             && self.calendar_id == other.calendar_id
             && self.time_range.overlaps(other.time_range())
     }
 
+    /// Adding a tag that is already present changes nothing.
+    pub fn add_tag(&mut self, tag: Tag) {
+        let change = FieldChange::added("tags", &tag);
+        if self.tags.insert(tag) {
+            self.changed(change);
+            self.touch();
+        }
+    }
+
+    pub fn remove_tag(&mut self, tag: &Tag) -> Result<(), DomainError> {
+        if !self.tags.remove(tag) {
+            return Err(DomainError::TagNotFound(tag.to_string()));
+        }
+
+        self.changed(FieldChange::removed("tags", tag));
+        self.touch();
+        Ok(())
+    }
+
+    /// Replaces `from` with `to`, merging the two if both are present.
+    /// Returns whether the tags changed.
+    pub fn replace_tag(&mut self, from: &Tag, to: Tag) -> bool {
+        if from == &to || !self.tags.remove(from) {
+            return false;
+        }
+
+        self.changed(FieldChange::set("tags", from, &to));
+        self.tags.insert(to);
+        self.touch();
+        true
+    }
+
     pub fn touch(&mut self) {
         self.updated_at = Utc::now();
     }
+
+    /// A change that invalidates invitations already sent.
+    fn revise(&mut self) {
+        self.sequence += 1;
+        self.touch();
+    }
+
+    fn raise(&mut self, event: DomainEvent) {
+        self.pending_events.push(event);
+    }
+
+    /// Raises `EventChanged` unless the field kept its value.
+    fn changed(&mut self, change: FieldChange) {
+        if change.from() != change.to() {
+            self.raise(DomainEvent::EventChanged {
+                calendar_id: self.calendar_id,
+                event_id: self.event_id,
+                changes: vec![change],
+            });
+        }
+    }
 }
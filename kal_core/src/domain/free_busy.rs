@@ -0,0 +1,74 @@
+use std::fmt;
+
+use getset::Getters;
+
+use crate::domain::{
+    event::Event,
+    recurrence::RecurringEvent,
+    value_objects::{EventId, EventStatus, TimeRange},
+};
+
+/// How firmly a period is taken (iCalendar FBTYPE).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BusyKind {
+    Busy,
+    Tentative,
+}
+
+impl BusyKind {
+    fn of(status: &EventStatus) -> Self {
+        match status {
+            EventStatus::Tentative => BusyKind::Tentative,
+            _ => BusyKind::Busy,
+        }
+    }
+}
+
+impl fmt::Display for BusyKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BusyKind::Busy => write!(f, "BUSY"),
+            BusyKind::Tentative => write!(f, "BUSY-TENTATIVE"),
+        }
+    }
+}
+
+/// Time taken up by one event or one occurrence of a series.
+#[derive(Debug, Clone, PartialEq, Eq, Getters)]
+pub struct BusyPeriod {
+    #[getset(get = "pub")]
+    event_id: EventId,
+    #[getset(get = "pub")]
+    time_range: TimeRange,
+    #[getset(get = "pub")]
+    kind: BusyKind,
+}
+
+/// The busy periods overlapping `window`, earliest first. Cancelled and
+/// transparent events and series take up no time.
+pub fn busy_periods(
+    events: &[Event],
+    series: &[RecurringEvent],
+    window: &TimeRange,
+) -> Vec<BusyPeriod> {
+    let mut periods: Vec<BusyPeriod> = events
+        .iter()
+        .filter(|event| event.is_busy() && event.time_range().overlaps(window))
+        .map(|event| BusyPeriod {
+            event_id: *event.event_id(),
+            time_range: *event.time_range(),
+            kind: BusyKind::of(event.status()),
+        })
+        .collect();
+
+    for event in series.iter().filter(|event| event.is_busy()) {
+        periods.extend(event.occurrences_in(window).into_iter().map(|occurrence| BusyPeriod {
+            event_id: *event.event_id(),
+            time_range: *occurrence.time_range(),
+            kind: BusyKind::of(event.status()),
+        }));
+    }
+
+    periods.sort_by_key(|period| *period.time_range.starts_at());
+    periods
+}
@@ -0,0 +1,190 @@
+use core::fmt;
+
+use chrono::{DateTime, Utc};
+use getset::Getters;
+use uuid::Uuid;
+
+use crate::domain::{
+    calendar::Calendar,
+    error::DomainError,
+    event::Event,
+    journal::JournalEntry,
+    recurrence::RecurringEvent,
+    task::Task,
+    value_objects::CalendarId,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ObjectKind {
+    Calendar,
+    Event,
+    Series,
+    Task,
+    Journal,
+}
+
+impl fmt::Display for ObjectKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ObjectKind::Calendar => write!(f, "CALENDAR"),
+            ObjectKind::Event => write!(f, "EVENT"),
+            ObjectKind::Series => write!(f, "SERIES"),
+            ObjectKind::Task => write!(f, "TASK"),
+            ObjectKind::Journal => write!(f, "JOURNAL"),
+        }
+    }
+}
+
+impl std::str::FromStr for ObjectKind {
+    type Err = DomainError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_uppercase().as_str() {
+            "CALENDAR" => Ok(ObjectKind::Calendar),
+            "EVENT" => Ok(ObjectKind::Event),
+            "SERIES" => Ok(ObjectKind::Series),
+            "TASK" => Ok(ObjectKind::Task),
+            "JOURNAL" => Ok(ObjectKind::Journal),
+            _ => Err(DomainError::InvalidObjectKind),
+        }
+    }
+}
+
+/// An object as a command found or left it, including whether it was in
+/// the trash.
+#[derive(Debug, Clone)]
+pub enum Snapshot {
+    Calendar(Calendar),
+    Event(Event),
+    Recurring(RecurringEvent),
+    Task(Task),
+    Journal(JournalEntry),
+}
+
+impl Snapshot {
+    pub fn kind(&self) -> ObjectKind {
+        match self {
+            Snapshot::Calendar(_) => ObjectKind::Calendar,
+            Snapshot::Event(_) => ObjectKind::Event,
+            Snapshot::Recurring(_) => ObjectKind::Series,
+            Snapshot::Task(_) => ObjectKind::Task,
+            Snapshot::Journal(_) => ObjectKind::Journal,
+        }
+    }
+
+    pub fn object_id(&self) -> Uuid {
+        match self {
+            Snapshot::Calendar(calendar) => calendar.calendar_id().as_uuid(),
+            Snapshot::Event(event) => event.event_id().as_uuid(),
+            Snapshot::Recurring(event) => event.event_id().as_uuid(),
+            Snapshot::Task(task) => task.task_id().as_uuid(),
+            Snapshot::Journal(entry) => entry.journal_id().as_uuid(),
+        }
+    }
+
+    /// The calendar the object lives in; a calendar's own id for calendars.
+    pub fn calendar_id(&self) -> CalendarId {
+        match self {
+            Snapshot::Calendar(calendar) => *calendar.calendar_id(),
+            Snapshot::Event(event) => *event.calendar_id(),
+            Snapshot::Recurring(event) => *event.calendar_id(),
+            Snapshot::Task(task) => *task.calendar_id(),
+            Snapshot::Journal(entry) => *entry.calendar_id(),
+        }
+    }
+
+    /// Whether the object was in the trash. Tasks and journal entries
+    /// never are.
+    pub fn is_trashed(&self) -> bool {
+        match self {
+            Snapshot::Calendar(calendar) => calendar.is_trashed(),
+            Snapshot::Event(event) => event.is_trashed(),
+            Snapshot::Recurring(event) => event.is_trashed(),
+            Snapshot::Task(_) | Snapshot::Journal(_) => false,
+        }
+    }
+
+    /// The version the object was stored at. Tasks and journal entries
+    /// carry none.
+    pub fn version(&self) -> Option<u32> {
+        match self {
+            Snapshot::Calendar(calendar) => Some(*calendar.version()),
+            Snapshot::Event(event) => Some(*event.version()),
+            Snapshot::Recurring(event) => Some(*event.version()),
+            Snapshot::Task(_) | Snapshot::Journal(_) => None,
+        }
+    }
+
+    /// The name shown for the object in listings.
+    pub fn title(&self) -> &str {
+        match self {
+            Snapshot::Calendar(calendar) => calendar.name(),
+            Snapshot::Event(event) => event.title(),
+            Snapshot::Recurring(event) => event.title(),
+            Snapshot::Task(task) => task.title(),
+            Snapshot::Journal(entry) => entry.title(),
+        }
+    }
+}
+
+/// What one command did to one object. `before` is `None` when the
+/// command created the object, `after` when it deleted it for good.
+#[derive(Debug, Clone, Getters)]
+pub struct Change {
+    #[getset(get = "pub")]
+    kind: ObjectKind,
+    #[getset(get = "pub")]
+    object_id: Uuid,
+    #[getset(get = "pub")]
+    before: Option<Snapshot>,
+    #[getset(get = "pub")]
+    after: Option<Snapshot>,
+}
+
+impl Change {
+    pub fn new(
+        kind: ObjectKind,
+        object_id: Uuid,
+        before: Option<Snapshot>,
+        after: Option<Snapshot>,
+    ) -> Self {
+        Self { kind, object_id, before, after }
+    }
+
+    /// The object's title after the change, or before it when it was
+    /// deleted.
+    pub fn title(&self) -> Option<&str> {
+        self.after.as_ref().or(self.before.as_ref()).map(Snapshot::title)
+    }
+}
+
+/// A command recorded for undo and redo. Entries form a tree: each one
+/// is recorded as a child of the entry that was current at the time, so
+/// a command run after an undo starts a new branch.
+#[derive(Debug, Clone, Getters)]
+pub struct HistoryEntry {
+    #[getset(get = "pub")]
+    id: i64,
+    #[getset(get = "pub")]
+    parent_id: Option<i64>,
+    /// The method that ran, such as `event.delete`.
+    #[getset(get = "pub")]
+    command: String,
+    #[getset(get = "pub")]
+    recorded_at: DateTime<Utc>,
+    /// In the order the command made them.
+    #[getset(get = "pub")]
+    changes: Vec<Change>,
+}
+
+impl HistoryEntry {
+    pub fn new(
+        id: i64,
+        parent_id: Option<i64>,
+        command: String,
+        recorded_at: DateTime<Utc>,
+        changes: Vec<Change>,
+    ) -> Self {
+        Self { id, parent_id, command, recorded_at, changes }
+    }
+}
@@ -0,0 +1,124 @@
+use chrono::{DateTime, NaiveDate, Utc};
+use getset::Getters;
+use serde::{Deserialize, Serialize};
+
+use crate::domain::{
+    error::DomainError,
+    value_objects::{CalendarId, EventId, JournalId},
+};
+
+/// A note or log that belongs to a date rather than a time slot
+/// (iCalendar VJOURNAL), such as meeting notes or a daily log.
+#[derive(Debug, Clone, Getters, Serialize, Deserialize)]
+pub struct JournalEntry {
+    #[getset(get = "pub")]
+    journal_id: JournalId,
+    #[getset(get = "pub")]
+    calendar_id: CalendarId,
+    #[getset(get = "pub")]
+    date: NaiveDate,
+    #[getset(get = "pub")]
+    title: String,
+    #[getset(get = "pub")]
+    body: String,
+    #[getset(get = "pub")]
+    link: Option<JournalLink>,
+    #[getset(get = "pub")]
+    created_at: DateTime<Utc>,
+    #[getset(get = "pub")]
+    updated_at: DateTime<Utc>,
+}
+
+/// What a journal entry is about: a one-off event or a whole series, or
+/// one occurrence of a series identified by its original start.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum JournalLink {
+    Event(EventId),
+    Occurrence(EventId, DateTime<Utc>),
+}
+
+impl JournalLink {
+    pub fn event_id(&self) -> EventId {
+        match self {
+            Self::Event(event_id) | Self::Occurrence(event_id, _) => *event_id,
+        }
+    }
+
+    pub fn original_starts_at(&self) -> Option<DateTime<Utc>> {
+        match self {
+            Self::Event(_) => None,
+            Self::Occurrence(_, original_starts_at) => Some(*original_starts_at),
+        }
+    }
+}
+
+impl JournalEntry {
+    pub fn new(
+        calendar_id: CalendarId,
+        date: NaiveDate,
+        title: String,
+        body: String,
+    ) -> Result<Self, DomainError> {
+        let now = Utc::now();
+        Self::with_id(JournalId::new(), calendar_id, date, title, body, now, now)
+    }
+
+    pub fn with_id(
+        journal_id: JournalId,
+        calendar_id: CalendarId,
+        date: NaiveDate,
+        title: String,
+        body: String,
+        created_at: DateTime<Utc>,
+        updated_at: DateTime<Utc>,
+    ) -> Result<Self, DomainError> {
+        if title.is_empty() {
+            return Err(DomainError::EmptyTitle);
+        }
+
+        Ok(Self {
+            journal_id,
+            calendar_id,
+            date,
+            title,
+            body,
+            link: None,
+            created_at,
+            updated_at,
+        })
+    }
+
+    /// Restores the stored event link when rebuilding an entry.
+    pub fn with_link(mut self, link: Option<JournalLink>) -> Self {
+        self.link = link;
+        self
+    }
+
+    pub fn update_title(&mut self, title: String) -> Result<(), DomainError> {
+        if title.is_empty() {
+            return Err(DomainError::EmptyTitle);
+        }
+        self.title = title;
+        self.touch();
+        Ok(())
+    }
+
+    pub fn update_body(&mut self, body: String) {
+        self.body = body;
+        self.touch();
+    }
+
+    pub fn move_to(&mut self, date: NaiveDate) {
+        self.date = date;
+        self.touch();
+    }
+
+    pub fn set_link(&mut self, link: Option<JournalLink>) {
+        self.link = link;
+        self.touch();
+    }
+
+    pub fn touch(&mut self) {
+        self.updated_at = Utc::now();
+    }
+}
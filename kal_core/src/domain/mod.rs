@@ -1,6 +1,18 @@
 pub mod calendar;
 pub mod event;
 pub mod recurrence;
+pub mod reminder;
+pub mod task;
+pub mod journal;
+pub mod search;
+pub mod attendee;
+pub mod tag;
+pub mod calendar_object;
+pub mod free_busy;
+pub mod sync;
+pub mod history;
+pub mod domain_event;
+pub mod audit;
 pub mod value_objects;
 pub mod repository;
 pub mod error;
@@ -8,5 +20,17 @@ pub mod error;
 // Re-export
 pub use calendar::Calendar;
 pub use event::Event;
-pub use recurrence::{RecurringEvent, RecurrenceRule, RecurrenceException, ExceptionModification};
-pub use value_objects::{CalendarId, EventId, TimeRange, Frequency, EventColor};
+pub use recurrence::{RecurringEvent, RecurrenceRule, RecurrenceException, ExceptionModification, Occurrence};
+pub use reminder::{Reminder, ReminderTrigger, ReminderAction, ReminderInstance, ReminderKey, ReminderState};
+pub use attendee::{Attendee, AttendeeRole, Organizer, ParticipationStatus};
+pub use task::Task;
+pub use journal::{JournalEntry, JournalLink};
+pub use search::{SearchHit, SearchHitKind};
+pub use tag::{Tag, TagFilter, TagUsage};
+pub use calendar_object::CalendarObject;
+pub use free_busy::{BusyKind, BusyPeriod};
+pub use history::{Change, HistoryEntry, ObjectKind, Snapshot};
+pub use domain_event::{DomainEvent, EventContext, EventSubscriber, FieldChange};
+pub use audit::AuditEntry;
+pub use sync::{SyncCollection, SyncItem, SyncItemKind, Tombstone, ConflictStrategy, ConflictResolver};
+pub use value_objects::{CalendarId, EventId, TimeRange, Frequency, EventColor, EventStatus, Transparency, GeoPoint, ReminderId, Subscription, TaskId, TaskStatus, JournalId};
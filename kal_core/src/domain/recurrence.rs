@@ -1,14 +1,22 @@
-use std::collections::HashMap;
+use std::collections::{BTreeSet, HashMap};
 
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, Duration, Months, Utc};
 use getset::Getters;
+use serde::{Deserialize, Serialize};
 
 use crate::domain::{
+    attendee::{Attendee, Organizer, ParticipationStatus},
+    domain_event::{DomainEvent, FieldChange},
     error::DomainError,
-    value_objects::{CalendarId, EventColor, EventId, Frequency, TimeRange}
+    reminder::{Reminder, ReminderInstance, ReminderTrigger},
+    tag::Tag,
+    value_objects::{
+        validate_url, CalendarId, EventColor, EventId, EventStatus, Frequency, GeoPoint,
+        ReminderId, TimeRange, Transparency,
+    }
 };
 
-#[derive(Debug, Clone, Getters)]
+#[derive(Debug, Clone, Getters, Serialize, Deserialize)]
 pub struct RecurrenceRule {
     #[getset(get = "pub")]
     frequency: Frequency,
@@ -33,9 +41,22 @@ impl RecurrenceRule {
             until,
         })
     }
+
+    /// The date `n` repetitions after `from`, or `None` when that is past
+    /// the last representable date.
+    pub fn advance(&self, from: DateTime<Utc>, n: u32) -> Option<DateTime<Utc>> {
+        let steps = n.checked_mul(self.interval)?;
+
+        match self.frequency {
+            Frequency::Daily => from.checked_add_signed(Duration::try_days(steps as i64)?),
+            Frequency::Weekly => from.checked_add_signed(Duration::try_weeks(steps as i64)?),
+            Frequency::Monthly => from.checked_add_months(Months::new(steps)),
+            Frequency::Yearly => from.checked_add_months(Months::new(steps.checked_mul(12)?)),
+        }
+    }
 }
 
-#[derive(Debug, Clone, Getters)]
+#[derive(Debug, Clone, Getters, Serialize, Deserialize)]
 pub struct RecurringEvent {
     #[getset(get = "pub")]
     event_id: EventId,
@@ -46,6 +67,12 @@ pub struct RecurringEvent {
     #[getset(get = "pub")]
     description: Option<String>,
     #[getset(get = "pub")]
+    location: Option<String>,
+    #[getset(get = "pub")]
+    url: Option<String>,
+    #[getset(get = "pub")]
+    geo: Option<GeoPoint>,
+    #[getset(get = "pub")]
     time_range: TimeRange,
     #[getset(get = "pub")]
     rule: RecurrenceRule,
@@ -56,11 +83,33 @@ pub struct RecurringEvent {
     #[getset(get = "pub")]
     is_all_day: bool,
     #[getset(get = "pub")]
-    is_cancelled: bool,
+    status: EventStatus,
+    #[getset(get = "pub")]
+    transparency: Transparency,
+    #[getset(get = "pub")]
+    reminders: Vec<Reminder>,
+    #[getset(get = "pub")]
+    organizer: Option<Organizer>,
+    #[getset(get = "pub")]
+    attendees: Vec<Attendee>,
+    #[getset(get = "pub")]
+    tags: BTreeSet<Tag>,
+    /// iTIP SEQUENCE; bumped by changes attendees must be told about.
+    #[getset(get = "pub")]
+    sequence: u32,
+    /// Stored revision used to detect concurrent updates; 0 until saved.
+    #[getset(get = "pub")]
+    version: u32,
+    /// When the object was moved to the trash; `None` while it is live.
+    #[getset(get = "pub")]
+    deleted_at: Option<DateTime<Utc>>,
     #[getset(get = "pub")]
     created_at: DateTime<Utc>,
     #[getset(get = "pub")]
     updated_at: DateTime<Utc>,
+    /// Raised by mutators since the series was loaded; not stored.
+    #[serde(skip)]
+    pending_events: Vec<DomainEvent>,
 }
 
 impl RecurringEvent {
@@ -82,14 +131,26 @@ impl RecurringEvent {
                 calendar_id,
                 title,
                 description,
+                location: None,
+                url: None,
+                geo: None,
                 time_range,
                 rule,
                 exceptions: HashMap::new(),
                 color,
                 is_all_day,
-                is_cancelled: false,
+                status: EventStatus::default(),
+                transparency: Transparency::default(),
+                reminders: Vec::new(),
+                organizer: None,
+                attendees: Vec::new(),
+                tags: BTreeSet::new(),
+                sequence: 0,
+                version: 0,
+                deleted_at: None,
                 created_at: now,
                 updated_at: now,
+                pending_events: Vec::new(),
             })
         }
     }
@@ -104,7 +165,7 @@ impl RecurringEvent {
         exceptions: HashMap<DateTime<Utc>, RecurrenceException>,
         color: EventColor,
         is_all_day: bool,
-        is_cancelled: bool,
+        status: EventStatus,
         created_at: DateTime<Utc>,
         updated_at: DateTime<Utc>,
     ) -> Result<Self, DomainError> {
@@ -116,74 +177,551 @@ impl RecurringEvent {
                 calendar_id,
                 title,
                 description,
+                location: None,
+                url: None,
+                geo: None,
                 time_range,
                 rule,
                 exceptions,
                 color,
                 is_all_day,
-                is_cancelled,
+                status,
+                transparency: Transparency::default(),
+                reminders: Vec::new(),
+                organizer: None,
+                attendees: Vec::new(),
+                tags: BTreeSet::new(),
+                sequence: 0,
+                version: 0,
+                deleted_at: None,
                 created_at,
                 updated_at,
+                pending_events: Vec::new(),
             })
         }
     }
 
+    /// Attaches stored reminders when rebuilding a series.
+    pub fn with_reminders(mut self, reminders: Vec<Reminder>) -> Self {
+        self.reminders = reminders;
+        self
+    }
+
+    /// Attaches stored tags when rebuilding a series.
+    pub fn with_tags(mut self, tags: impl IntoIterator<Item = Tag>) -> Self {
+        self.tags = tags.into_iter().collect();
+        self
+    }
+
+    /// Attaches the stored location, URL and coordinates when rebuilding
+    /// a series.
+    pub fn with_place(
+        mut self,
+        location: Option<String>,
+        url: Option<String>,
+        geo: Option<GeoPoint>,
+    ) -> Self {
+        self.location = location;
+        self.url = url;
+        self.geo = geo;
+        self
+    }
+
+    /// Restores the stored transparency when rebuilding a series.
+    pub fn with_transparency(mut self, transparency: Transparency) -> Self {
+        self.transparency = transparency;
+        self
+    }
+
+    /// Restores the stored sequence number when rebuilding a series.
+    pub fn with_sequence(mut self, sequence: u32) -> Self {
+        self.sequence = sequence;
+        self
+    }
+
+    /// Restores the stored version when rebuilding a series.
+    pub fn with_version(mut self, version: u32) -> Self {
+        self.version = version;
+        self
+    }
+
+    /// Restores the stored deletion time when rebuilding a series.
+    pub fn with_deleted_at(mut self, deleted_at: Option<DateTime<Utc>>) -> Self {
+        self.deleted_at = deleted_at;
+        self
+    }
+
+    pub fn is_trashed(&self) -> bool {
+        self.deleted_at.is_some()
+    }
+
+    /// Events raised since the series was loaded or last drained.
+    pub fn pending_events(&self) -> &[DomainEvent] {
+        &self.pending_events
+    }
+
+    pub fn take_pending_events(&mut self) -> Vec<DomainEvent> {
+        std::mem::take(&mut self.pending_events)
+    }
+
+    /// Drops raised events, as storing and reloading the series would.
+    pub fn without_pending_events(mut self) -> Self {
+        self.pending_events.clear();
+        self
+    }
+
+    /// Attaches stored participants when rebuilding a series.
+    pub fn with_participants(
+        mut self,
+        organizer: Option<Organizer>,
+        attendees: Vec<Attendee>,
+    ) -> Self {
+        self.organizer = organizer;
+        self.attendees = attendees;
+        self
+    }
+
     pub fn add_exception(&mut self, exception: RecurrenceException) {
         self.exceptions.insert(exception.original_starts_at, exception);
-        self.touch();
+        self.revise();
     }
 
     pub fn remove_exception(&mut self, original_starts_at: DateTime<Utc>) {
         self.exceptions.remove(&original_starts_at);
-        self.touch();
+        self.revise();
     }
 
     pub fn restore_occurrence(&mut self, original_starts_at: DateTime<Utc>) {
+        self.raise(DomainEvent::OccurrenceRestored {
+            calendar_id: self.calendar_id,
+            event_id: self.event_id,
+            occurrence: original_starts_at,
+        });
         self.remove_exception(original_starts_at);
-        self.touch();
     }
 
     pub fn cancel_occurrence(&mut self, original_starts_at: DateTime<Utc>) {
+        self.raise(DomainEvent::OccurrenceCancelled {
+            calendar_id: self.calendar_id,
+            event_id: self.event_id,
+            occurrence: original_starts_at,
+        });
         let exception = RecurrenceException::cancelled(original_starts_at);
         self.add_exception(exception);
     }
 
+    /// Moves one occurrence, keeping any location it was given.
     pub fn reschedule_occurrence(
         &mut self,
         original_starts_at: DateTime<Utc>,
         new_time_range: TimeRange,
     ) {
+        let location = self
+            .exceptions
+            .get(&original_starts_at)
+            .and_then(|ex| ex.location.clone());
         let exception = RecurrenceException::rescheduled(
             original_starts_at,
             new_time_range
-        );
+        )
+        .with_location(location);
+        self.raise(DomainEvent::OccurrenceRescheduled {
+            calendar_id: self.calendar_id,
+            event_id: self.event_id,
+            occurrence: original_starts_at,
+            to: new_time_range,
+        });
         self.add_exception(exception);
     }
 
-    pub fn cancel(&mut self) {
-        self.is_cancelled = true;
+    /// Holds one occurrence somewhere else; `None` goes back to the
+    /// series' location.
+    pub fn set_occurrence_location(
+        &mut self,
+        original_starts_at: DateTime<Utc>,
+        location: Option<String>,
+    ) -> Result<(), DomainError> {
+        let location = location.filter(|l| !l.trim().is_empty());
+        let original_range = TimeRange::new(
+            original_starts_at,
+            original_starts_at + self.time_range.duration(),
+        )?;
+
+        let current = self.exceptions.get(&original_starts_at);
+        let change = FieldChange::between(
+            "location",
+            current.and_then(|ex| ex.location.as_ref()),
+            location.as_ref(),
+        );
+        let exception = match current {
+            Some(ex) if matches!(ex.modification, ExceptionModification::Cancelled) => {
+                return Err(DomainError::OccurrenceCancelled(original_starts_at.to_rfc3339()));
+            }
+            Some(ex) => ex.clone().with_location(location),
+            None => RecurrenceException::rescheduled(original_starts_at, original_range)
+                .with_location(location),
+        };
+
+        if change.from() != change.to() {
+            self.raise(DomainEvent::OccurrenceChanged {
+                calendar_id: self.calendar_id,
+                event_id: self.event_id,
+                occurrence: original_starts_at,
+                changes: vec![change],
+            });
+        }
+
+        // An override left with nothing to change is dropped
+        if exception.location.is_none() && exception.new_time_range() == Some(&original_range) {
+            self.remove_exception(original_starts_at);
+        } else {
+            self.add_exception(exception);
+        }
+
+        Ok(())
+    }
+
+    pub fn add_reminder(&mut self, reminder: Reminder) {
+        self.changed(FieldChange::added("reminders", reminder.reminder_id()));
+        self.reminders.push(reminder);
+        self.touch();
+    }
+
+    pub fn remove_reminder(&mut self, reminder_id: &ReminderId) -> Result<(), DomainError> {
+        let index = self
+            .reminders
+            .iter()
+            .position(|r| r.reminder_id() == reminder_id)
+            .ok_or_else(|| DomainError::ReminderNotFound(reminder_id.to_string()))?;
+
+        self.reminders.remove(index);
+        self.changed(FieldChange::removed("reminders", reminder_id));
+        self.touch();
+        Ok(())
+    }
+
+    /// Blank locations are stored as none.
+    pub fn update_location(&mut self, location: Option<String>) {
+        let location = location.filter(|l| !l.trim().is_empty());
+        self.changed(FieldChange::between("location", self.location.as_ref(), location.as_ref()));
+        self.location = location;
+        self.revise();
+    }
+
+    pub fn update_url(&mut self, url: Option<String>) -> Result<(), DomainError> {
+        if let Some(url) = &url {
+            validate_url(url)?;
+        }
+
+        self.changed(FieldChange::between("url", self.url.as_ref(), url.as_ref()));
+        self.url = url;
+        self.touch();
+        Ok(())
+    }
+
+    pub fn update_geo(&mut self, geo: Option<GeoPoint>) {
+        self.changed(FieldChange::between("geo", self.geo.as_ref(), geo.as_ref()));
+        self.geo = geo;
+        self.touch();
+    }
+
+    pub fn set_organizer(&mut self, organizer: Option<Organizer>) {
+        self.changed(FieldChange::between(
+            "organizer",
+            self.organizer.as_ref().map(Organizer::email),
+            organizer.as_ref().map(Organizer::email),
+        ));
+        self.organizer = organizer;
+        self.touch();
+    }
+
+    pub fn add_attendee(&mut self, attendee: Attendee) -> Result<(), DomainError> {
+        if self.attendees.iter().any(|a| a.has_email(attendee.email())) {
+            return Err(DomainError::InvalidAttendee(format!(
+                "{} is already invited",
+                attendee.email()
+            )));
+        }
+
+        self.changed(FieldChange::added("attendees", attendee.email()));
+        self.attendees.push(attendee);
+        self.touch();
+        Ok(())
+    }
+
+    pub fn remove_attendee(&mut self, email: &str) -> Result<(), DomainError> {
+        let index = self
+            .attendees
+            .iter()
+            .position(|a| a.has_email(email))
+            .ok_or_else(|| DomainError::AttendeeNotFound(email.to_string()))?;
+
+        let attendee = self.attendees.remove(index);
+        self.changed(FieldChange::removed("attendees", attendee.email()));
+        self.touch();
+        Ok(())
+    }
+
+    pub fn update_participation(
+        &mut self,
+        email: &str,
+        status: ParticipationStatus,
+    ) -> Result<(), DomainError> {
+        let attendee = self
+            .attendees
+            .iter_mut()
+            .find(|a| a.has_email(email))
+            .ok_or_else(|| DomainError::AttendeeNotFound(email.to_string()))?;
+        let change =
+            FieldChange::set(&format!("attendee {}", attendee.email()), attendee.status(), &status);
+        attendee.set_status(status);
+
+        self.changed(change);
         self.touch();
+        Ok(())
     }
 
+    pub fn cancel(&mut self) {
+        self.set_status(EventStatus::Cancelled);
+    }
+
+    /// Restoring confirms a series, whatever it was before cancelling.
     pub fn restore(&mut self) {
-        self.is_cancelled = false;
+        self.set_status(EventStatus::Confirmed);
+    }
+
+    pub fn set_status(&mut self, status: EventStatus) {
+        let (calendar_id, event_id) = (self.calendar_id, self.event_id);
+        match (self.status, status) {
+            (from, to) if from == to => {}
+            (_, EventStatus::Cancelled) => {
+                self.raise(DomainEvent::SeriesCancelled { calendar_id, event_id })
+            }
+            (EventStatus::Cancelled, _) => {
+                self.raise(DomainEvent::SeriesRestored { calendar_id, event_id })
+            }
+            (from, to) => self.changed(FieldChange::set("status", &from, &to)),
+        }
+
+        self.status = status;
+        self.revise();
+    }
+
+    pub fn set_transparency(&mut self, transparency: Transparency) {
+        self.changed(FieldChange::set("transparency", &self.transparency, &transparency));
+        self.transparency = transparency;
         self.touch();
     }
 
+    pub fn is_cancelled(&self) -> bool {
+        self.status == EventStatus::Cancelled
+    }
+
+    /// Whether a series takes up time: it is not cancelled and not
+    /// transparent. Tentative holds count as busy.
+    pub fn is_busy(&self) -> bool {
+        !self.is_cancelled() && self.transparency == Transparency::Opaque
+    }
+
+    /// Adding a tag that is already present changes nothing.
+    pub fn add_tag(&mut self, tag: Tag) {
+        let change = FieldChange::added("tags", &tag);
+        if self.tags.insert(tag) {
+            self.changed(change);
+            self.touch();
+        }
+    }
+
+    pub fn remove_tag(&mut self, tag: &Tag) -> Result<(), DomainError> {
+        if !self.tags.remove(tag) {
+            return Err(DomainError::TagNotFound(tag.to_string()));
+        }
+
+        self.changed(FieldChange::removed("tags", tag));
+        self.touch();
+        Ok(())
+    }
+
+    /// Replaces `from` with `to`, merging the two if both are present.
+    /// Returns whether the tags changed.
+    pub fn replace_tag(&mut self, from: &Tag, to: Tag) -> bool {
+        if from == &to || !self.tags.remove(from) {
+            return false;
+        }
+
+        self.changed(FieldChange::set("tags", from, &to));
+        self.tags.insert(to);
+        self.touch();
+        true
+    }
+
     pub fn touch(&mut self) {
         self.updated_at = Utc::now();
     }
+
+    /// A change that invalidates invitations already sent.
+    fn revise(&mut self) {
+        self.sequence += 1;
+        self.touch();
+    }
+
+    fn raise(&mut self, event: DomainEvent) {
+        self.pending_events.push(event);
+    }
+
+    /// Raises `SeriesChanged` unless the field kept its value.
+    fn changed(&mut self, change: FieldChange) {
+        if change.from() != change.to() {
+            self.raise(DomainEvent::SeriesChanged {
+                calendar_id: self.calendar_id,
+                event_id: self.event_id,
+                changes: vec![change],
+            });
+        }
+    }
+
+    /// Expands the series into the occurrences overlapping `window`, with
+    /// cancelled occurrences left out and rescheduled ones moved.
+    pub fn occurrences_in(&self, window: &TimeRange) -> Vec<Occurrence> {
+        let duration = self.time_range.duration();
+        let mut occurrences = Vec::new();
+
+        for n in 0.. {
+            // A rule with no end runs out where dates do
+            let Some(starts_at) = self.nth_start(n) else {
+                break;
+            };
+
+            if starts_at >= *window.ends_at()
+                || self.rule.until.is_some_and(|until| starts_at > until)
+            {
+                break;
+            }
+
+            if self.exceptions.contains_key(&starts_at) {
+                continue;
+            }
+
+            let Some(ends_at) = starts_at.checked_add_signed(duration) else {
+                break;
+            };
+            let time_range = TimeRange::new(starts_at, ends_at)
+                .expect("occurrences keep the series' positive duration");
+
+            if time_range.overlaps(window) {
+                occurrences.push(Occurrence {
+                    original_starts_at: starts_at,
+                    time_range,
+                    location: self.location.clone(),
+                });
+            }
+        }
+
+        // Reschedules can move an occurrence into the window from outside it
+        for exception in self.exceptions.values() {
+            if let Some(time_range) = exception.new_time_range()
+                && time_range.overlaps(window)
+            {
+                occurrences.push(Occurrence {
+                    original_starts_at: exception.original_starts_at,
+                    time_range: *time_range,
+                    location: exception.location.clone().or_else(|| self.location.clone()),
+                });
+            }
+        }
+
+        occurrences.sort_by_key(|o| *o.time_range.starts_at());
+        occurrences
+    }
+
+    /// Reminder instances whose first firing falls inside `window`.
+    ///
+    /// Every occurrence inherits the series' relative reminders, measured
+    /// from its own (possibly rescheduled) start; cancelled occurrences
+    /// get none. Absolute reminders fire once for the whole series and
+    /// are keyed to its first start.
+    pub fn reminder_instances(&self, window: &TimeRange) -> Vec<ReminderInstance> {
+        if self.is_cancelled() {
+            return Vec::new();
+        }
+
+        let series_start = *self.time_range.starts_at();
+        let fires_in_window =
+            |at: DateTime<Utc>| at >= *window.starts_at() && at < *window.ends_at();
+
+        let mut instances: Vec<ReminderInstance> = self
+            .reminders
+            .iter()
+            .filter(|r| !r.is_relative() && fires_in_window(r.trigger_at(series_start)))
+            .map(|r| ReminderInstance::new(self.event_id, series_start, series_start, r.clone()))
+            .collect();
+
+        let offsets: Vec<Duration> = self
+            .reminders
+            .iter()
+            .filter_map(|r| match r.trigger() {
+                ReminderTrigger::Relative(offset) => Some(*offset),
+                ReminderTrigger::Absolute(_) => None,
+            })
+            .collect();
+
+        if let (Some(earliest), Some(latest)) = (offsets.iter().min(), offsets.iter().max()) {
+            // Occurrences whose reminders can fire inside the window
+            let search = TimeRange::new(
+                *window.starts_at() - *latest,
+                *window.ends_at() - *earliest,
+            )
+            .expect("shifting both ends keeps the window positive");
+
+            for occurrence in self.occurrences_in(&search) {
+                let starts_at = *occurrence.time_range.starts_at();
+
+                for reminder in self.reminders.iter().filter(|r| r.is_relative()) {
+                    if fires_in_window(reminder.trigger_at(starts_at)) {
+                        instances.push(ReminderInstance::new(
+                            self.event_id,
+                            occurrence.original_starts_at,
+                            starts_at,
+                            reminder.clone(),
+                        ));
+                    }
+                }
+            }
+        }
+
+        instances.sort_by_key(|i| i.fires_at());
+        instances
+    }
+
+    fn nth_start(&self, n: u32) -> Option<DateTime<Utc>> {
+        self.rule.advance(*self.time_range.starts_at(), n)
+    }
 }
 
-#[derive(Debug, Clone, Getters)]
+/// A single instance of a recurring event.
+#[derive(Debug, Clone, PartialEq, Eq, Getters)]
+pub struct Occurrence {
+    #[getset(get = "pub")]
+    original_starts_at: DateTime<Utc>,
+    #[getset(get = "pub")]
+    time_range: TimeRange,
+    /// The occurrence's own location if it has one, else the series'.
+    #[getset(get = "pub")]
+    location: Option<String>,
+}
+
+#[derive(Debug, Clone, Getters, Serialize, Deserialize)]
 pub struct RecurrenceException {
     #[getset(get = "pub")]
     original_starts_at: DateTime<Utc>,
     #[getset(get = "pub")]
     modification: ExceptionModification,
+    /// Overrides the series' location; unused for cancelled occurrences.
+    #[getset(get = "pub")]
+    location: Option<String>,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum ExceptionModification {
     Cancelled,
     Rescheduled { new_time_range: TimeRange },
@@ -194,6 +732,7 @@ impl RecurrenceException {
         Self {
             original_starts_at,
             modification: ExceptionModification::Cancelled,
+            location: None,
         }
     }
 
@@ -204,9 +743,15 @@ impl RecurrenceException {
         Self {
             original_starts_at,
             modification: ExceptionModification::Rescheduled { new_time_range },
+            location: None,
         }
     }
 
+    pub fn with_location(mut self, location: Option<String>) -> Self {
+        self.location = location;
+        self
+    }
+
     pub fn new_time_range(&self) -> Option<&TimeRange> {
         match &self.modification {
             ExceptionModification::Rescheduled {
@@ -0,0 +1,353 @@
+use std::iter;
+
+use chrono::{DateTime, Duration, Utc};
+use getset::Getters;
+use serde::{Deserialize, Serialize};
+
+use crate::domain::{
+    error::DomainError,
+    value_objects::{EventId, ReminderId},
+};
+
+/// How many times a reminder may repeat after its first firing.
+pub const MAX_REMINDER_REPEAT: u32 = 1000;
+
+/// How far from its event's start a reminder may fire, and over how long
+/// its repeats may run.
+pub const MAX_REMINDER_SPAN: Duration = Duration::days(10 * 366);
+
+/// When a reminder fires.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ReminderTrigger {
+    /// Offset from the start of the event or occurrence; negative offsets
+    /// fire before it.
+    Relative(Duration),
+    Absolute(DateTime<Utc>),
+}
+
+/// What a reminder does when it fires.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ReminderAction {
+    Display,
+    Email { recipient: String },
+    Command { command: String },
+}
+
+impl ReminderAction {
+    pub fn kind(&self) -> &'static str {
+        match self {
+            ReminderAction::Display => "DISPLAY",
+            ReminderAction::Email { .. } => "EMAIL",
+            ReminderAction::Command { .. } => "COMMAND",
+        }
+    }
+
+    /// The recipient or command line, for actions that have one.
+    pub fn target(&self) -> Option<&str> {
+        match self {
+            ReminderAction::Display => None,
+            ReminderAction::Email { recipient } => Some(recipient),
+            ReminderAction::Command { command } => Some(command),
+        }
+    }
+
+    /// Inverse of `kind` and `target`.
+    pub fn from_parts(kind: &str, target: Option<String>) -> Result<Self, DomainError> {
+        let target = target.filter(|t| !t.trim().is_empty());
+
+        match (kind.to_uppercase().as_str(), target) {
+            ("DISPLAY", _) => Ok(ReminderAction::Display),
+            ("EMAIL", Some(recipient)) => Ok(ReminderAction::Email { recipient }),
+            ("COMMAND", Some(command)) => Ok(ReminderAction::Command { command }),
+            ("EMAIL", None) => Err(DomainError::InvalidReminder("email needs a recipient".into())),
+            ("COMMAND", None) => Err(DomainError::InvalidReminder("command cannot be empty".into())),
+            (other, _) => Err(DomainError::InvalidReminder(format!("unknown action {other}"))),
+        }
+    }
+}
+
+/// An alarm attached to an event or recurring series (a VALARM).
+#[derive(Debug, Clone, PartialEq, Eq, Getters, Serialize, Deserialize)]
+pub struct Reminder {
+    #[getset(get = "pub")]
+    reminder_id: ReminderId,
+    #[getset(get = "pub")]
+    trigger: ReminderTrigger,
+    #[getset(get = "pub")]
+    action: ReminderAction,
+    #[getset(get = "pub")]
+    description: Option<String>,
+    /// Additional firings after the first one.
+    #[getset(get = "pub")]
+    repeat: u32,
+    #[getset(get = "pub")]
+    repeat_interval: Option<Duration>,
+}
+
+impl Reminder {
+    pub fn new(
+        trigger: ReminderTrigger,
+        action: ReminderAction,
+        description: Option<String>,
+        repeat: u32,
+        repeat_interval: Option<Duration>,
+    ) -> Result<Self, DomainError> {
+        Self::with_id(ReminderId::new(), trigger, action, description, repeat, repeat_interval)
+    }
+
+    pub fn with_id(
+        reminder_id: ReminderId,
+        trigger: ReminderTrigger,
+        action: ReminderAction,
+        description: Option<String>,
+        repeat: u32,
+        repeat_interval: Option<Duration>,
+    ) -> Result<Self, DomainError> {
+        match (repeat, repeat_interval) {
+            (0, Some(_)) => {
+                return Err(DomainError::InvalidReminder(
+                    "a repeat interval needs a repeat count".into(),
+                ))
+            }
+            (1.., None) => {
+                return Err(DomainError::InvalidReminder(
+                    "repeating reminders need an interval".into(),
+                ))
+            }
+            (_, Some(interval)) if interval <= Duration::zero() => {
+                return Err(DomainError::InvalidReminder(
+                    "repeat interval must be positive".into(),
+                ))
+            }
+            (repeat, _) if repeat > MAX_REMINDER_REPEAT => {
+                return Err(DomainError::InvalidReminder(format!(
+                    "a reminder repeats at most {MAX_REMINDER_REPEAT} times"
+                )))
+            }
+            (_, Some(interval))
+                if interval.checked_mul(repeat as i32).is_none_or(|span| span > MAX_REMINDER_SPAN) =>
+            {
+                return Err(DomainError::InvalidReminder(
+                    "repeats must end within ten years".into(),
+                ))
+            }
+            _ => {}
+        }
+
+        if matches!(trigger, ReminderTrigger::Relative(offset) if offset.abs() > MAX_REMINDER_SPAN) {
+            return Err(DomainError::InvalidReminder(
+                "a reminder must fire within ten years of its event".into(),
+            ));
+        }
+
+        Ok(Self {
+            reminder_id,
+            trigger,
+            action,
+            description,
+            repeat,
+            repeat_interval,
+        })
+    }
+
+    /// The first firing for an event or occurrence starting at `starts_at`.
+    /// Offsets that would run off either end of the calendar stop there.
+    pub fn trigger_at(&self, starts_at: DateTime<Utc>) -> DateTime<Utc> {
+        match self.trigger {
+            ReminderTrigger::Relative(offset) => {
+                starts_at.checked_add_signed(offset).unwrap_or(if offset < Duration::zero() {
+                    DateTime::<Utc>::MIN_UTC
+                } else {
+                    DateTime::<Utc>::MAX_UTC
+                })
+            }
+            ReminderTrigger::Absolute(at) => at,
+        }
+    }
+
+    /// Every firing, the first one included, up to the end of the calendar.
+    pub fn fire_times(&self, starts_at: DateTime<Utc>) -> impl Iterator<Item = DateTime<Utc>> {
+        let interval = self.repeat_interval.unwrap_or_else(Duration::zero);
+
+        iter::successors(Some(self.trigger_at(starts_at)), move |at| {
+            at.checked_add_signed(interval)
+        })
+        .take(self.repeat as usize + 1)
+    }
+
+    pub fn is_relative(&self) -> bool {
+        matches!(self.trigger, ReminderTrigger::Relative(_))
+    }
+}
+
+/// A reminder resolved against one event or occurrence. Instances of a
+/// recurring series are told apart by the occurrence's original start.
+#[derive(Debug, Clone, PartialEq, Eq, Getters)]
+pub struct ReminderInstance {
+    #[getset(get = "pub")]
+    event_id: EventId,
+    #[getset(get = "pub")]
+    original_starts_at: DateTime<Utc>,
+    #[getset(get = "pub")]
+    starts_at: DateTime<Utc>,
+    #[getset(get = "pub")]
+    reminder: Reminder,
+}
+
+impl ReminderInstance {
+    pub fn new(
+        event_id: EventId,
+        original_starts_at: DateTime<Utc>,
+        starts_at: DateTime<Utc>,
+        reminder: Reminder,
+    ) -> Self {
+        Self {
+            event_id,
+            original_starts_at,
+            starts_at,
+            reminder,
+        }
+    }
+
+    pub fn fires_at(&self) -> DateTime<Utc> {
+        self.reminder.trigger_at(self.starts_at)
+    }
+
+    pub fn fire_times(&self) -> impl Iterator<Item = DateTime<Utc>> {
+        self.reminder.fire_times(self.starts_at)
+    }
+
+    pub fn key(&self) -> ReminderKey {
+        ReminderKey::new(
+            self.event_id,
+            self.original_starts_at,
+            *self.reminder.reminder_id(),
+        )
+    }
+}
+
+/// Identifies one reminder instance: the reminder, and for series the
+/// occurrence it belongs to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Getters)]
+pub struct ReminderKey {
+    #[getset(get = "pub")]
+    event_id: EventId,
+    #[getset(get = "pub")]
+    original_starts_at: DateTime<Utc>,
+    #[getset(get = "pub")]
+    reminder_id: ReminderId,
+}
+
+impl ReminderKey {
+    pub fn new(
+        event_id: EventId,
+        original_starts_at: DateTime<Utc>,
+        reminder_id: ReminderId,
+    ) -> Self {
+        Self {
+            event_id,
+            original_starts_at,
+            reminder_id,
+        }
+    }
+}
+
+/// Delivery state of one reminder instance, persisted so firings are
+/// not repeated after a restart.
+#[derive(Debug, Clone, PartialEq, Eq, Getters)]
+pub struct ReminderState {
+    #[getset(get = "pub")]
+    key: ReminderKey,
+    /// How many of the instance's fire times have been delivered.
+    #[getset(get = "pub")]
+    fired_count: u32,
+    #[getset(get = "pub")]
+    last_fired_at: Option<DateTime<Utc>>,
+    #[getset(get = "pub")]
+    snoozed_until: Option<DateTime<Utc>>,
+    #[getset(get = "pub")]
+    dismissed_at: Option<DateTime<Utc>>,
+}
+
+impl ReminderState {
+    pub fn new(key: ReminderKey) -> Self {
+        Self {
+            key,
+            fired_count: 0,
+            last_fired_at: None,
+            snoozed_until: None,
+            dismissed_at: None,
+        }
+    }
+
+    pub fn with_state(
+        key: ReminderKey,
+        fired_count: u32,
+        last_fired_at: Option<DateTime<Utc>>,
+        snoozed_until: Option<DateTime<Utc>>,
+        dismissed_at: Option<DateTime<Utc>>,
+    ) -> Self {
+        Self {
+            key,
+            fired_count,
+            last_fired_at,
+            snoozed_until,
+            dismissed_at,
+        }
+    }
+
+    pub fn is_dismissed(&self) -> bool {
+        self.dismissed_at.is_some()
+    }
+
+    /// When the instance should fire next, if ever. A snooze takes the
+    /// place of the remaining scheduled firings.
+    pub fn next_fire_at(&self, instance: &ReminderInstance) -> Option<DateTime<Utc>> {
+        if self.is_dismissed() {
+            return None;
+        }
+
+        if self.snoozed_until.is_some() {
+            return self.snoozed_until;
+        }
+
+        instance.fire_times().nth(self.fired_count as usize)
+    }
+
+    /// Records a delivery at `now`. Firings that were missed while nothing
+    /// was running are skipped rather than delivered in a burst.
+    pub fn record_fired(&mut self, instance: &ReminderInstance, now: DateTime<Utc>) {
+        let (elapsed, total) = instance
+            .fire_times()
+            .fold((0, 0), |(elapsed, total), at| (elapsed + u32::from(at <= now), total + 1));
+
+        self.fired_count = if self.snoozed_until.is_some() {
+            self.fired_count.max(elapsed)
+        } else {
+            (self.fired_count + 1).max(elapsed)
+        }
+        .min(total);
+        self.snoozed_until = None;
+        self.last_fired_at = Some(now);
+    }
+
+    /// Fires the instance again at `until`; scheduled repeats that pass in
+    /// the meantime are skipped. Snoozing a dismissed instance brings it back.
+    pub fn snooze(&mut self, until: DateTime<Utc>, now: DateTime<Utc>) -> Result<(), DomainError> {
+        if until <= now {
+            return Err(DomainError::InvalidReminder(
+                "a snooze must end in the future".into(),
+            ));
+        }
+
+        self.snoozed_until = Some(until);
+        self.dismissed_at = None;
+        Ok(())
+    }
+
+    /// Stops the instance from firing again, repeats and snoozes included.
+    pub fn dismiss(&mut self, now: DateTime<Utc>) {
+        self.snoozed_until = None;
+        self.dismissed_at = Some(now);
+    }
+}
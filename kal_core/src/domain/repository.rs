@@ -1,9 +1,20 @@
 use async_trait::async_trait;
+use chrono::{DateTime, NaiveDate, Utc};
 use super::{
+    audit::AuditEntry,
     calendar::Calendar,
+    calendar_object::CalendarObject,
+    domain_event::{DomainEvent, EventContext},
     event::Event,
+    history::{Change, HistoryEntry},
+    journal::JournalEntry,
     recurrence::RecurringEvent,
-    value_objects::{CalendarId, EventId, TimeRange},
+    reminder::{ReminderKey, ReminderState},
+    search::SearchHit,
+    sync::{SyncCollection, SyncItem, Tombstone},
+    tag::{Tag, TagUsage},
+    task::Task,
+    value_objects::{CalendarId, EventId, JournalId, TaskId, TimeRange},
 };
 
 #[derive(Debug, thiserror::Error)]
@@ -16,29 +27,200 @@ pub enum RepositoryError {
     
     #[error("Constraint violation: {0}")]
     ConstraintViolation(String),
+
+    /// The stored row changed after it was loaded.
+    #[error("Stale write: the entity was modified concurrently")]
+    Conflict,
 }
 
 #[async_trait]
 pub trait CalendarRepository: Send + Sync {
-    async fn save(&self, calendar: &Calendar) -> Result<(), RepositoryError>;
+    /// Writes the calendar and returns its new version; saving it again
+    /// needs that version, not the one it was loaded with.
+    async fn save(&self, calendar: &Calendar) -> Result<u32, RepositoryError>;
     async fn find_by_id(&self, id: &CalendarId) -> Result<Option<Calendar>, RepositoryError>;
     async fn find_all_active(&self) -> Result<Vec<Calendar>, RepositoryError>;
+    /// Moves a live calendar to the trash together with its live events
+    /// and series, which share its deletion time.
+    async fn trash(&self, id: &CalendarId, deleted_at: DateTime<Utc>) -> Result<(), RepositoryError>;
+    /// Brings a trashed calendar back with the events and series that
+    /// were trashed along with it.
+    async fn restore(&self, id: &CalendarId) -> Result<(), RepositoryError>;
+    /// Trashed calendars, most recently deleted first.
+    async fn find_trashed(&self) -> Result<Vec<Calendar>, RepositoryError>;
+    /// Permanently deletes calendars trashed before `cutoff`, with
+    /// everything in them. Returns how many calendars went.
+    async fn purge_trashed(&self, cutoff: DateTime<Utc>) -> Result<u64, RepositoryError>;
+    /// Permanently deletes the calendar, whether trashed or not.
     async fn delete(&self, id: &CalendarId) -> Result<(), RepositoryError>;
 }
 
 #[async_trait]
 pub trait EventRepository: Send + Sync {
-    async fn save(&self, event: &Event) -> Result<(), RepositoryError>;
+    /// Writes the event and returns its new version.
+    async fn save(&self, event: &Event) -> Result<u32, RepositoryError>;
     async fn find_by_id(&self, id: &EventId) -> Result<Option<Event>, RepositoryError>;
     async fn find_by_calendar(&self, calendar_id: &CalendarId) -> Result<Vec<Event>, RepositoryError>;
     async fn find_in_range(&self, calendar_id: &CalendarId, range: &TimeRange) -> Result<Vec<Event>, RepositoryError>;
+    /// Events whose location contains `needle`, ignoring ASCII case.
+    async fn find_by_location(&self, calendar_id: &CalendarId, needle: &str) -> Result<Vec<Event>, RepositoryError>;
+    /// Events carrying `tag`, across all calendars.
+    async fn find_by_tag(&self, tag: &Tag) -> Result<Vec<Event>, RepositoryError>;
+    /// Events, across all calendars, with a reminder whose first firing
+    /// falls inside `window`. Cancelled events have none.
+    async fn find_with_reminders_in(&self, window: &TimeRange) -> Result<Vec<Event>, RepositoryError>;
+    async fn trash(&self, id: &EventId, deleted_at: DateTime<Utc>) -> Result<(), RepositoryError>;
+    async fn restore(&self, id: &EventId) -> Result<(), RepositoryError>;
+    /// Trashed events, most recently deleted first, including those
+    /// trashed along with their calendar.
+    async fn find_trashed(&self) -> Result<Vec<Event>, RepositoryError>;
+    /// Permanently deletes events trashed before `cutoff`.
+    async fn purge_trashed(&self, cutoff: DateTime<Utc>) -> Result<u64, RepositoryError>;
+    /// Permanently deletes the event, whether trashed or not.
     async fn delete(&self, id: &EventId) -> Result<(), RepositoryError>;
 }
 
 #[async_trait]
 pub trait RecurringEventRepository: Send + Sync {
-    async fn save(&self, event: &RecurringEvent) -> Result<(), RepositoryError>;
+    /// Writes the series and returns its new version.
+    async fn save(&self, event: &RecurringEvent) -> Result<u32, RepositoryError>;
     async fn find_by_calendar(&self, calendar_id: &CalendarId) -> Result<Vec<RecurringEvent>, RepositoryError>;
     async fn find_by_id(&self, event_id: &EventId) -> Result<RecurringEvent, RepositoryError>;
+    /// Series whose own location, or any occurrence override, contains
+    /// `needle`, ignoring ASCII case.
+    async fn find_by_location(&self, calendar_id: &CalendarId, needle: &str) -> Result<Vec<RecurringEvent>, RepositoryError>;
+    /// Series carrying `tag`, across all calendars.
+    async fn find_by_tag(&self, tag: &Tag) -> Result<Vec<RecurringEvent>, RepositoryError>;
+    /// Series, across all calendars, with a reminder instance whose
+    /// first firing falls inside `window`.
+    async fn find_with_reminders_in(&self, window: &TimeRange) -> Result<Vec<RecurringEvent>, RepositoryError>;
+    async fn trash(&self, id: &EventId, deleted_at: DateTime<Utc>) -> Result<(), RepositoryError>;
+    async fn restore(&self, id: &EventId) -> Result<(), RepositoryError>;
+    /// Trashed series, most recently deleted first, including those
+    /// trashed along with their calendar.
+    async fn find_trashed(&self) -> Result<Vec<RecurringEvent>, RepositoryError>;
+    /// Permanently deletes series trashed before `cutoff`.
+    async fn purge_trashed(&self, cutoff: DateTime<Utc>) -> Result<u64, RepositoryError>;
+    /// Permanently deletes the series, whether trashed or not.
     async fn delete(&self, id: &EventId) -> Result<(), RepositoryError>;
 }
+
+#[async_trait]
+pub trait TaskRepository: Send + Sync {
+    async fn save(&self, task: &Task) -> Result<(), RepositoryError>;
+    async fn find_by_id(&self, id: &TaskId) -> Result<Option<Task>, RepositoryError>;
+    /// Tasks in a calendar, soonest due first; undated tasks come last.
+    async fn find_by_calendar(&self, calendar_id: &CalendarId) -> Result<Vec<Task>, RepositoryError>;
+    async fn delete(&self, id: &TaskId) -> Result<(), RepositoryError>;
+}
+
+#[async_trait]
+pub trait JournalRepository: Send + Sync {
+    async fn save(&self, entry: &JournalEntry) -> Result<(), RepositoryError>;
+    async fn find_by_id(&self, id: &JournalId) -> Result<Option<JournalEntry>, RepositoryError>;
+    /// Entries in a calendar dated within `from..=to` (either end open),
+    /// oldest first.
+    async fn find_by_calendar(
+        &self,
+        calendar_id: &CalendarId,
+        from: Option<NaiveDate>,
+        to: Option<NaiveDate>,
+    ) -> Result<Vec<JournalEntry>, RepositoryError>;
+    /// Entries linked to an event or series, including those linked to one
+    /// of its occurrences, oldest first.
+    async fn find_by_event(&self, event_id: &EventId) -> Result<Vec<JournalEntry>, RepositoryError>;
+    /// Entries whose title or body contain every word of `query`, best
+    /// match first. Only searches one calendar when `calendar_id` is set.
+    async fn search(
+        &self,
+        query: &str,
+        calendar_id: Option<&CalendarId>,
+    ) -> Result<Vec<JournalEntry>, RepositoryError>;
+    async fn delete(&self, id: &JournalId) -> Result<(), RepositoryError>;
+}
+
+#[async_trait]
+pub trait EventSearchRepository: Send + Sync {
+    /// Events and series whose title, description, location or attendees
+    /// contain every word of `text`, best match first. Events must overlap
+    /// `window`; series are returned whatever their dates, for the caller
+    /// to expand.
+    async fn search(
+        &self,
+        text: &str,
+        calendar_id: Option<&CalendarId>,
+        window: Option<&TimeRange>,
+    ) -> Result<Vec<SearchHit>, RepositoryError>;
+}
+
+#[async_trait]
+pub trait TagRepository: Send + Sync {
+    /// Every tag in use, by name.
+    async fn find_all(&self) -> Result<Vec<TagUsage>, RepositoryError>;
+    /// `None` when nothing carries the tag.
+    async fn find(&self, tag: &Tag) -> Result<Option<TagUsage>, RepositoryError>;
+}
+
+#[async_trait]
+pub trait SubscriptionRepository: Send + Sync {
+    async fn find_all(&self) -> Result<Vec<Calendar>, RepositoryError>;
+    async fn replace_contents(&self, calendar: &Calendar, objects: &[CalendarObject]) -> Result<(), RepositoryError>;
+}
+
+#[async_trait]
+pub trait SyncStateRepository: Send + Sync {
+    async fn save_collection(&self, collection: &SyncCollection) -> Result<(), RepositoryError>;
+    async fn find_collection(&self, calendar_id: &CalendarId) -> Result<Option<SyncCollection>, RepositoryError>;
+    async fn find_all_collections(&self) -> Result<Vec<SyncCollection>, RepositoryError>;
+    async fn delete_collection(&self, calendar_id: &CalendarId) -> Result<(), RepositoryError>;
+    async fn save_item(&self, item: &SyncItem) -> Result<(), RepositoryError>;
+    async fn find_item(&self, item_id: &EventId) -> Result<Option<SyncItem>, RepositoryError>;
+    async fn find_item_by_href(&self, calendar_id: &CalendarId, href: &str) -> Result<Option<SyncItem>, RepositoryError>;
+    async fn find_items(&self, calendar_id: &CalendarId) -> Result<Vec<SyncItem>, RepositoryError>;
+    async fn delete_item(&self, item_id: &EventId) -> Result<(), RepositoryError>;
+    async fn find_tombstones(&self, calendar_id: &CalendarId) -> Result<Vec<Tombstone>, RepositoryError>;
+    async fn delete_tombstone(&self, item_id: &EventId) -> Result<(), RepositoryError>;
+}
+
+/// The undo history. Entries and their changes are only ever appended;
+/// undo and redo move a cursor between them.
+#[async_trait]
+pub trait HistoryRepository: Send + Sync {
+    /// Appends an entry as a child of the current one and makes it
+    /// current. Returns its id.
+    async fn record(
+        &self,
+        command: &str,
+        recorded_at: DateTime<Utc>,
+        changes: &[Change],
+    ) -> Result<i64, RepositoryError>;
+    /// The entry an undo reverts: the last one applied.
+    async fn find_current(&self) -> Result<Option<HistoryEntry>, RepositoryError>;
+    /// The entry a redo applies again: the newest child of the current
+    /// entry.
+    async fn find_next(&self) -> Result<Option<HistoryEntry>, RepositoryError>;
+    /// Up to `limit` applied entries, from the current one back.
+    async fn find_applied(&self, limit: u32) -> Result<Vec<HistoryEntry>, RepositoryError>;
+    /// Makes `entry_id` current; `None` is before the first entry.
+    async fn set_current(&self, entry_id: Option<i64>) -> Result<(), RepositoryError>;
+}
+
+/// The audit log: one entry per domain event, only ever appended.
+#[async_trait]
+pub trait AuditRepository: Send + Sync {
+    /// Appends an entry for `event`. Returns its id.
+    async fn append(&self, event: &DomainEvent, context: &EventContext) -> Result<i64, RepositoryError>;
+    /// Up to `limit` entries about the event or series, newest first.
+    async fn find_by_event(&self, event_id: &EventId, limit: u32) -> Result<Vec<AuditEntry>, RepositoryError>;
+    /// Up to `limit` entries about the calendar and everything in it,
+    /// newest first.
+    async fn find_by_calendar(&self, calendar_id: &CalendarId, limit: u32) -> Result<Vec<AuditEntry>, RepositoryError>;
+}
+
+#[async_trait]
+pub trait ReminderStateRepository: Send + Sync {
+    async fn save(&self, state: &ReminderState) -> Result<(), RepositoryError>;
+    async fn find(&self, key: &ReminderKey) -> Result<Option<ReminderState>, RepositoryError>;
+    /// The states of those of `keys` that have one.
+    async fn find_all(&self, keys: &[ReminderKey]) -> Result<Vec<ReminderState>, RepositoryError>;
+}
@@ -0,0 +1,96 @@
+use std::fmt;
+
+use getset::Getters;
+
+use crate::domain::{
+    recurrence::RecurringEvent,
+    value_objects::{CalendarId, EventId, TimeRange},
+};
+
+/// Marks the matched words in a search snippet.
+pub const HIGHLIGHT_START: &str = "<mark>";
+pub const HIGHLIGHT_END: &str = "</mark>";
+
+/// Whether a search hit is a one-off event or a recurring series.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SearchHitKind {
+    Event,
+    Series,
+}
+
+impl fmt::Display for SearchHitKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SearchHitKind::Event => write!(f, "EVENT"),
+            SearchHitKind::Series => write!(f, "SERIES"),
+        }
+    }
+}
+
+/// An event or series whose title, description, location or attendees
+/// match a full-text search.
+#[derive(Debug, Clone, Getters)]
+pub struct SearchHit {
+    #[getset(get = "pub")]
+    event_id: EventId,
+    #[getset(get = "pub")]
+    calendar_id: CalendarId,
+    #[getset(get = "pub")]
+    kind: SearchHitKind,
+    #[getset(get = "pub")]
+    title: String,
+    /// The event's time, or for a series its first occurrence (in the
+    /// search window, once narrowed).
+    #[getset(get = "pub")]
+    time_range: TimeRange,
+    /// The best matching passage, matched words between
+    /// `HIGHLIGHT_START` and `HIGHLIGHT_END`.
+    #[getset(get = "pub")]
+    snippet: String,
+    /// Lower is a better match.
+    #[getset(get = "pub")]
+    score: f64,
+}
+
+impl SearchHit {
+    pub fn new(
+        event_id: EventId,
+        calendar_id: CalendarId,
+        kind: SearchHitKind,
+        title: String,
+        time_range: TimeRange,
+        snippet: String,
+        score: f64,
+    ) -> Self {
+        Self {
+            event_id,
+            calendar_id,
+            kind,
+            title,
+            time_range,
+            snippet,
+            score,
+        }
+    }
+}
+
+/// Keeps the hits that take place in `window`, in their order. Event
+/// hits are expected to be filtered already; series hits are kept when
+/// one of their occurrences falls in the window and moved to the first
+/// such occurrence. `series` holds the series behind the hits.
+pub fn within_window(
+    hits: Vec<SearchHit>,
+    series: &[RecurringEvent],
+    window: &TimeRange,
+) -> Vec<SearchHit> {
+    hits.into_iter()
+        .filter_map(|mut hit| {
+            if hit.kind == SearchHitKind::Series {
+                let event = series.iter().find(|event| event.event_id() == &hit.event_id)?;
+                let occurrence = event.occurrences_in(window).into_iter().next()?;
+                hit.time_range = *occurrence.time_range();
+            }
+            Some(hit)
+        })
+        .collect()
+}
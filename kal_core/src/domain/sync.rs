@@ -0,0 +1,363 @@
+use core::fmt;
+
+use chrono::{DateTime, Utc};
+use getset::Getters;
+
+use crate::domain::{
+    calendar_object::CalendarObject,
+    error::DomainError,
+    value_objects::{CalendarId, EventId},
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SyncItemKind {
+    Event,
+    Recurrence,
+}
+
+impl SyncItemKind {
+    pub fn of(object: &CalendarObject) -> Self {
+        match object {
+            CalendarObject::Event(_) => SyncItemKind::Event,
+            CalendarObject::Recurring(_) => SyncItemKind::Recurrence,
+        }
+    }
+}
+
+impl fmt::Display for SyncItemKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SyncItemKind::Event => write!(f, "EVENT"),
+            SyncItemKind::Recurrence => write!(f, "RECURRENCE"),
+        }
+    }
+}
+
+impl std::str::FromStr for SyncItemKind {
+    type Err = DomainError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_uppercase().as_str() {
+            "EVENT" => Ok(SyncItemKind::Event),
+            "RECURRENCE" => Ok(SyncItemKind::Recurrence),
+            _ => Err(DomainError::InvalidSyncItemKind),
+        }
+    }
+}
+
+/// Links a local calendar to a remote collection.
+#[derive(Debug, Clone, Getters)]
+pub struct SyncCollection {
+    #[getset(get = "pub")]
+    calendar_id: CalendarId,
+    #[getset(get = "pub")]
+    remote_href: String,
+    #[getset(get = "pub")]
+    sync_token: Option<String>,
+    #[getset(get = "pub")]
+    last_synced_at: Option<DateTime<Utc>>,
+}
+
+impl SyncCollection {
+    pub fn new(calendar_id: CalendarId, remote_href: String) -> Self {
+        Self {
+            calendar_id,
+            remote_href,
+            sync_token: None,
+            last_synced_at: None,
+        }
+    }
+
+    pub fn with_state(
+        calendar_id: CalendarId,
+        remote_href: String,
+        sync_token: Option<String>,
+        last_synced_at: Option<DateTime<Utc>>,
+    ) -> Self {
+        Self {
+            calendar_id,
+            remote_href,
+            sync_token,
+            last_synced_at,
+        }
+    }
+
+    pub fn mark_synced(&mut self, sync_token: Option<String>) {
+        self.sync_token = sync_token;
+        self.last_synced_at = Some(Utc::now());
+    }
+
+    pub fn reset_token(&mut self) {
+        self.sync_token = None;
+    }
+}
+
+/// Sync metadata for one local event or recurring series.
+#[derive(Debug, Clone, Getters)]
+pub struct SyncItem {
+    #[getset(get = "pub")]
+    item_id: EventId,
+    #[getset(get = "pub")]
+    calendar_id: CalendarId,
+    #[getset(get = "pub")]
+    kind: SyncItemKind,
+    #[getset(get = "pub")]
+    remote_href: Option<String>,
+    #[getset(get = "pub")]
+    remote_uid: Option<String>,
+    #[getset(get = "pub")]
+    etag: Option<String>,
+    #[getset(get = "pub")]
+    last_synced_hash: Option<String>,
+    #[getset(get = "pub")]
+    is_dirty: bool,
+    #[getset(get = "pub")]
+    last_synced_at: Option<DateTime<Utc>>,
+}
+
+impl SyncItem {
+    pub fn new(item_id: EventId, calendar_id: CalendarId, kind: SyncItemKind) -> Self {
+        Self {
+            item_id,
+            calendar_id,
+            kind,
+            remote_href: None,
+            remote_uid: None,
+            etag: None,
+            last_synced_hash: None,
+            is_dirty: true,
+            last_synced_at: None,
+        }
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    pub fn with_state(
+        item_id: EventId,
+        calendar_id: CalendarId,
+        kind: SyncItemKind,
+        remote_href: Option<String>,
+        remote_uid: Option<String>,
+        etag: Option<String>,
+        last_synced_hash: Option<String>,
+        is_dirty: bool,
+        last_synced_at: Option<DateTime<Utc>>,
+    ) -> Self {
+        Self {
+            item_id,
+            calendar_id,
+            kind,
+            remote_href,
+            remote_uid,
+            etag,
+            last_synced_hash,
+            is_dirty,
+            last_synced_at,
+        }
+    }
+
+    /// Whether the local copy differs from what was last exchanged.
+    pub fn has_local_changes(&self, current_hash: &str) -> bool {
+        self.is_dirty && self.last_synced_hash.as_deref() != Some(current_hash)
+    }
+
+    pub fn link(&mut self, remote_href: String, remote_uid: Option<String>) {
+        self.remote_href = Some(remote_href);
+        if remote_uid.is_some() {
+            self.remote_uid = remote_uid;
+        }
+    }
+
+    pub fn set_etag(&mut self, etag: Option<String>) {
+        self.etag = etag;
+    }
+
+    pub fn mark_synced(&mut self, kind: SyncItemKind, etag: Option<String>, hash: String) {
+        self.kind = kind;
+        self.etag = etag;
+        self.last_synced_hash = Some(hash);
+        self.is_dirty = false;
+        self.last_synced_at = Some(Utc::now());
+    }
+
+    pub fn mark_dirty(&mut self) {
+        self.is_dirty = true;
+    }
+}
+
+/// Remembers a locally deleted item until the deletion is pushed.
+#[derive(Debug, Clone, Getters)]
+pub struct Tombstone {
+    #[getset(get = "pub")]
+    item_id: EventId,
+    #[getset(get = "pub")]
+    calendar_id: CalendarId,
+    #[getset(get = "pub")]
+    kind: SyncItemKind,
+    #[getset(get = "pub")]
+    remote_href: String,
+    #[getset(get = "pub")]
+    etag: Option<String>,
+    #[getset(get = "pub")]
+    deleted_at: DateTime<Utc>,
+}
+
+impl Tombstone {
+    pub fn new(
+        item_id: EventId,
+        calendar_id: CalendarId,
+        kind: SyncItemKind,
+        remote_href: String,
+        etag: Option<String>,
+        deleted_at: DateTime<Utc>,
+    ) -> Self {
+        Self {
+            item_id,
+            calendar_id,
+            kind,
+            remote_href,
+            etag,
+            deleted_at,
+        }
+    }
+}
+
+
+// ======================================================
+// Conflict resolution
+// ======================================================
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConflictStrategy {
+    LocalWins,
+    RemoteWins,
+    NewestWins,
+    KeepBoth,
+}
+
+impl fmt::Display for ConflictStrategy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConflictStrategy::LocalWins => write!(f, "local-wins"),
+            ConflictStrategy::RemoteWins => write!(f, "remote-wins"),
+            ConflictStrategy::NewestWins => write!(f, "newest-wins"),
+            ConflictStrategy::KeepBoth => write!(f, "keep-both"),
+        }
+    }
+}
+
+impl std::str::FromStr for ConflictStrategy {
+    type Err = DomainError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "local-wins" | "local" => Ok(ConflictStrategy::LocalWins),
+            "remote-wins" | "remote" => Ok(ConflictStrategy::RemoteWins),
+            "newest-wins" | "newest" => Ok(ConflictStrategy::NewestWins),
+            "keep-both" | "both" => Ok(ConflictStrategy::KeepBoth),
+            _ => Err(DomainError::InvalidConflictStrategy),
+        }
+    }
+}
+
+/// One side of a conflict. `None` on either side means that side deleted
+/// the item.
+#[derive(Debug, Clone)]
+pub struct Conflict {
+    pub item_id: EventId,
+    pub local: Option<CalendarObject>,
+    pub local_deleted_at: Option<DateTime<Utc>>,
+    pub remote: Option<CalendarObject>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Resolution {
+    /// Push the local copy over the remote one.
+    KeepLocal,
+    /// Overwrite the local copy with the remote one.
+    KeepRemote,
+    /// Take the remote copy and re-create the local one under `copy_id`.
+    KeepBoth { copy_id: EventId },
+    /// The remote deletion wins; drop the local copy.
+    DeleteLocal,
+    /// The local deletion wins; drop the remote copy.
+    DeleteRemote,
+}
+
+#[derive(Debug, Clone)]
+pub struct ResolvedConflict {
+    pub item_id: EventId,
+    pub strategy: ConflictStrategy,
+    pub resolution: Resolution,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct ConflictReport {
+    pub resolved: Vec<ResolvedConflict>,
+}
+
+impl ConflictReport {
+    pub fn push(&mut self, resolved: ResolvedConflict) {
+        self.resolved.push(resolved);
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.resolved.is_empty()
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct ConflictResolver {
+    strategy: ConflictStrategy,
+}
+
+impl ConflictResolver {
+    pub fn new(strategy: ConflictStrategy) -> Self {
+        Self { strategy }
+    }
+
+    pub fn strategy(&self) -> ConflictStrategy {
+        self.strategy
+    }
+
+    /// Decides a conflict. When one side deleted the item and the other
+    /// modified it, `NewestWins` compares the deletion time with the
+    /// modification; a remote deletion has no timestamp, so the surviving
+    /// modification is kept. `KeepBoth` never loses a modification.
+    pub fn resolve(&self, conflict: &Conflict) -> ResolvedConflict {
+        let resolution = match (&conflict.local, &conflict.remote) {
+            (Some(local), Some(remote)) => match self.strategy {
+                ConflictStrategy::LocalWins => Resolution::KeepLocal,
+                ConflictStrategy::RemoteWins => Resolution::KeepRemote,
+                ConflictStrategy::NewestWins => {
+                    if local.updated_at() >= remote.updated_at() {
+                        Resolution::KeepLocal
+                    } else {
+                        Resolution::KeepRemote
+                    }
+                }
+                ConflictStrategy::KeepBoth => Resolution::KeepBoth {
+                    copy_id: EventId::new(),
+                },
+            },
+            (None, Some(remote)) => match self.strategy {
+                ConflictStrategy::LocalWins => Resolution::DeleteRemote,
+                ConflictStrategy::RemoteWins | ConflictStrategy::KeepBoth => Resolution::KeepRemote,
+                ConflictStrategy::NewestWins => match conflict.local_deleted_at {
+                    Some(deleted_at) if deleted_at >= *remote.updated_at() => Resolution::DeleteRemote,
+                    _ => Resolution::KeepRemote,
+                },
+            },
+            (Some(_), None) => match self.strategy {
+                ConflictStrategy::RemoteWins => Resolution::DeleteLocal,
+                _ => Resolution::KeepLocal,
+            },
+            (None, None) => Resolution::DeleteLocal,
+        };
+
+        ResolvedConflict {
+            item_id: conflict.item_id,
+            strategy: self.strategy,
+            resolution,
+        }
+    }
+}
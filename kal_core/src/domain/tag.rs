@@ -0,0 +1,89 @@
+use std::collections::BTreeSet;
+use std::fmt;
+
+use getset::Getters;
+use serde::{Deserialize, Serialize};
+
+use crate::domain::error::DomainError;
+
+/// A label such as `billable` or `deep-work`. Tags are trimmed and
+/// lowercased, so `Billable` and `billable` are the same tag.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub struct Tag(String);
+
+impl Tag {
+    pub fn new(name: impl AsRef<str>) -> Result<Self, DomainError> {
+        let name = name.as_ref().trim().to_lowercase();
+
+        if name.is_empty() {
+            return Err(DomainError::InvalidTag("tag cannot be empty".into()));
+        }
+
+        // Commas separate values in iCalendar CATEGORIES
+        if name.contains(',') || name.chars().any(char::is_control) {
+            return Err(DomainError::InvalidTag(format!(
+                "{name} may not contain commas or control characters"
+            )));
+        }
+
+        Ok(Self(name))
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl fmt::Display for Tag {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl std::str::FromStr for Tag {
+    type Err = DomainError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::new(s)
+    }
+}
+
+/// A tag in use and how many events and series carry it.
+#[derive(Debug, Clone, PartialEq, Eq, Getters)]
+pub struct TagUsage {
+    #[getset(get = "pub")]
+    tag: Tag,
+    #[getset(get = "pub")]
+    count: u32,
+}
+
+impl TagUsage {
+    pub fn new(tag: Tag, count: u32) -> Self {
+        Self { tag, count }
+    }
+}
+
+/// Selects objects by their tags. Empty lists place no constraint, so
+/// the default filter matches everything.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TagFilter {
+    all_of: Vec<Tag>,
+    any_of: Vec<Tag>,
+    none_of: Vec<Tag>,
+}
+
+impl TagFilter {
+    pub fn new(all_of: Vec<Tag>, any_of: Vec<Tag>, none_of: Vec<Tag>) -> Self {
+        Self { all_of, any_of, none_of }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.all_of.is_empty() && self.any_of.is_empty() && self.none_of.is_empty()
+    }
+
+    pub fn matches(&self, tags: &BTreeSet<Tag>) -> bool {
+        self.all_of.iter().all(|tag| tags.contains(tag))
+            && (self.any_of.is_empty() || self.any_of.iter().any(|tag| tags.contains(tag)))
+            && !self.none_of.iter().any(|tag| tags.contains(tag))
+    }
+}
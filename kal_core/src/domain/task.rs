@@ -0,0 +1,282 @@
+use chrono::{DateTime, Duration, Utc};
+use getset::Getters;
+use serde::{Deserialize, Serialize};
+
+use crate::domain::{
+    error::DomainError,
+    recurrence::RecurrenceRule,
+    value_objects::{CalendarId, TaskId, TaskStatus},
+};
+
+/// A to-do item (iCalendar VTODO). Unlike events, tasks take up no time:
+/// they have an optional start, an optional due date and track progress
+/// towards completion.
+#[derive(Debug, Clone, Getters, Serialize, Deserialize)]
+pub struct Task {
+    #[getset(get = "pub")]
+    task_id: TaskId,
+    #[getset(get = "pub")]
+    calendar_id: CalendarId,
+    #[getset(get = "pub")]
+    title: String,
+    #[getset(get = "pub")]
+    description: Option<String>,
+    #[getset(get = "pub")]
+    starts_at: Option<DateTime<Utc>>,
+    #[getset(get = "pub")]
+    due_at: Option<DateTime<Utc>>,
+    /// 1 is the highest priority and 9 the lowest; none means undefined.
+    #[getset(get = "pub")]
+    priority: Option<u8>,
+    #[getset(get = "pub")]
+    percent_complete: u8,
+    #[getset(get = "pub")]
+    status: TaskStatus,
+    #[getset(get = "pub")]
+    completed_at: Option<DateTime<Utc>>,
+    /// Repeats the task's start and due date. Completing a recurring task
+    /// moves it on to its next dates instead of closing it.
+    #[getset(get = "pub")]
+    rule: Option<RecurrenceRule>,
+    #[getset(get = "pub")]
+    created_at: DateTime<Utc>,
+    #[getset(get = "pub")]
+    updated_at: DateTime<Utc>,
+}
+
+impl Task {
+    pub fn new(
+        calendar_id: CalendarId,
+        title: String,
+        description: Option<String>,
+        starts_at: Option<DateTime<Utc>>,
+        due_at: Option<DateTime<Utc>>,
+    ) -> Result<Self, DomainError> {
+        let now = Utc::now();
+        Self::with_id(
+            TaskId::new(),
+            calendar_id,
+            title,
+            description,
+            starts_at,
+            due_at,
+            TaskStatus::default(),
+            now,
+            now,
+        )
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    pub fn with_id(
+        task_id: TaskId,
+        calendar_id: CalendarId,
+        title: String,
+        description: Option<String>,
+        starts_at: Option<DateTime<Utc>>,
+        due_at: Option<DateTime<Utc>>,
+        status: TaskStatus,
+        created_at: DateTime<Utc>,
+        updated_at: DateTime<Utc>,
+    ) -> Result<Self, DomainError> {
+        if title.is_empty() {
+            return Err(DomainError::EmptyTitle);
+        }
+        validate_dates(starts_at, due_at)?;
+
+        Ok(Self {
+            task_id,
+            calendar_id,
+            title,
+            description,
+            starts_at,
+            due_at,
+            priority: None,
+            percent_complete: 0,
+            status,
+            completed_at: None,
+            rule: None,
+            created_at,
+            updated_at,
+        })
+    }
+
+    /// Restores the stored priority when rebuilding a task.
+    pub fn with_priority(mut self, priority: Option<u8>) -> Self {
+        self.priority = priority;
+        self
+    }
+
+    /// Restores the stored progress when rebuilding a task.
+    pub fn with_progress(
+        mut self,
+        percent_complete: u8,
+        completed_at: Option<DateTime<Utc>>,
+    ) -> Self {
+        self.percent_complete = percent_complete;
+        self.completed_at = completed_at;
+        self
+    }
+
+    /// Restores the stored recurrence rule when rebuilding a task.
+    pub fn with_rule(mut self, rule: Option<RecurrenceRule>) -> Self {
+        self.rule = rule;
+        self
+    }
+
+    pub fn update_title(&mut self, title: String) -> Result<(), DomainError> {
+        if title.is_empty() {
+            return Err(DomainError::EmptyTitle);
+        }
+        self.title = title;
+        self.touch();
+        Ok(())
+    }
+
+    pub fn update_description(&mut self, description: Option<String>) {
+        self.description = description;
+        self.touch();
+    }
+
+    pub fn reschedule(
+        &mut self,
+        starts_at: Option<DateTime<Utc>>,
+        due_at: Option<DateTime<Utc>>,
+    ) -> Result<(), DomainError> {
+        validate_dates(starts_at, due_at)?;
+        if self.rule.is_some() && starts_at.is_none() && due_at.is_none() {
+            return Err(DomainError::InvalidTask(
+                "a recurring task needs a start or due date".into(),
+            ));
+        }
+
+        self.starts_at = starts_at;
+        self.due_at = due_at;
+        self.touch();
+        Ok(())
+    }
+
+    pub fn set_priority(&mut self, priority: Option<u8>) -> Result<(), DomainError> {
+        if let Some(priority) = priority
+            && !(1..=9).contains(&priority)
+        {
+            return Err(DomainError::InvalidTask(format!(
+                "priority must be between 1 and 9, got {priority}"
+            )));
+        }
+
+        self.priority = priority;
+        self.touch();
+        Ok(())
+    }
+
+    pub fn set_rule(&mut self, rule: Option<RecurrenceRule>) -> Result<(), DomainError> {
+        if rule.is_some() && self.starts_at.is_none() && self.due_at.is_none() {
+            return Err(DomainError::InvalidTask(
+                "a recurring task needs a start or due date".into(),
+            ));
+        }
+
+        self.rule = rule;
+        self.touch();
+        Ok(())
+    }
+
+    /// Records progress. Reaching 100% completes the task; anything less
+    /// puts it (back) in process.
+    pub fn set_progress(&mut self, percent_complete: u8) -> Result<(), DomainError> {
+        if percent_complete > 100 {
+            return Err(DomainError::InvalidTask(format!(
+                "percent complete must be at most 100, got {percent_complete}"
+            )));
+        }
+
+        if percent_complete == 100 {
+            self.complete(Utc::now());
+            return Ok(());
+        }
+
+        self.percent_complete = percent_complete;
+        self.status = if percent_complete > 0 {
+            TaskStatus::InProcess
+        } else {
+            TaskStatus::NeedsAction
+        };
+        self.completed_at = None;
+        self.touch();
+        Ok(())
+    }
+
+    /// Completes the task at `at`. A recurring task whose rule has not
+    /// run out moves on to its next start and due date and stays open.
+    pub fn complete(&mut self, at: DateTime<Utc>) {
+        match self.next_shift() {
+            Some(shift) => {
+                self.starts_at = self.starts_at.map(|starts_at| starts_at + shift);
+                self.due_at = self.due_at.map(|due_at| due_at + shift);
+                self.percent_complete = 0;
+                self.status = TaskStatus::NeedsAction;
+                self.completed_at = None;
+            }
+            None => {
+                self.percent_complete = 100;
+                self.status = TaskStatus::Completed;
+                self.completed_at = Some(at);
+            }
+        }
+        self.touch();
+    }
+
+    /// Opens a completed or cancelled task again, keeping partial progress.
+    pub fn reopen(&mut self) {
+        if self.percent_complete == 100 {
+            self.percent_complete = 0;
+        }
+        self.status = if self.percent_complete > 0 {
+            TaskStatus::InProcess
+        } else {
+            TaskStatus::NeedsAction
+        };
+        self.completed_at = None;
+        self.touch();
+    }
+
+    pub fn is_open(&self) -> bool {
+        matches!(self.status, TaskStatus::NeedsAction | TaskStatus::InProcess)
+    }
+
+    /// Whether the task is still open after its due date.
+    pub fn is_overdue(&self, now: DateTime<Utc>) -> bool {
+        self.is_open() && self.due_at.is_some_and(|due_at| due_at < now)
+    }
+
+    pub fn touch(&mut self) {
+        self.updated_at = Utc::now();
+    }
+
+    /// How far the start and due date move to the next repetition, or
+    /// `None` when the task does not recur or its rule has run out. The
+    /// step is measured from the due date if there is one.
+    fn next_shift(&self) -> Option<Duration> {
+        let rule = self.rule.as_ref()?;
+        let anchor = self.due_at.or(self.starts_at)?;
+        let next = rule.advance(anchor, 1)?;
+
+        if rule.until().is_some_and(|until| next > until) {
+            return None;
+        }
+
+        Some(next - anchor)
+    }
+}
+
+fn validate_dates(
+    starts_at: Option<DateTime<Utc>>,
+    due_at: Option<DateTime<Utc>>,
+) -> Result<(), DomainError> {
+    match (starts_at, due_at) {
+        (Some(starts_at), Some(due_at)) if starts_at > due_at => {
+            Err(DomainError::InvalidTimeRange)
+        }
+        _ => Ok(()),
+    }
+}
@@ -3,10 +3,11 @@ use core::fmt;
 use chrono::{DateTime, Utc};
 use getset::{Getters};
 use uuid::Uuid;
+use serde::{Deserialize, Serialize};
 
 use crate::domain::error::DomainError;
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub struct EventColor(u8);
 
 impl From<u8> for EventColor {
@@ -21,7 +22,7 @@ impl From<EventColor> for u8 {
     }
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Getters)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Getters, Serialize, Deserialize)]
 pub struct TimeRange {
     #[getset(get = "pub")]
     starts_at: DateTime<Utc>,
@@ -49,9 +50,159 @@ impl TimeRange {
     pub fn duration(&self) -> chrono::Duration {
         self.ends_at - self.starts_at
     }
+
+    /// Whether `at` falls inside the range; the end is exclusive.
+    pub fn contains(&self, at: DateTime<Utc>) -> bool {
+        self.starts_at <= at && at < self.ends_at
+    }
+}
+
+/// `start/end` in RFC 3339, like an iCalendar PERIOD.
+impl fmt::Display for TimeRange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}/{}", self.starts_at.to_rfc3339(), self.ends_at.to_rfc3339())
+    }
+}
+
+/// A position in decimal degrees (WGS 84), as carried by GEO.
+#[derive(Debug, Clone, Copy, PartialEq, Getters, Serialize, Deserialize)]
+pub struct GeoPoint {
+    #[getset(get = "pub")]
+    latitude: f64,
+    #[getset(get = "pub")]
+    longitude: f64,
+}
+
+impl GeoPoint {
+    pub fn new(latitude: f64, longitude: f64) -> Result<Self, DomainError> {
+        if !(-90.0..=90.0).contains(&latitude) {
+            return Err(DomainError::InvalidGeo(format!("latitude {latitude} is out of range")));
+        }
+        if !(-180.0..=180.0).contains(&longitude) {
+            return Err(DomainError::InvalidGeo(format!("longitude {longitude} is out of range")));
+        }
+
+        Ok(Self { latitude, longitude })
+    }
+}
+
+/// `LAT,LON`, as GEO writes it with `;` swapped for a comma.
+impl fmt::Display for GeoPoint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{},{}", self.latitude, self.longitude)
+    }
+}
+
+/// Checks that an event URL is absolute, i.e. starts with a scheme as in
+/// `https://…` or `tel:…`.
+pub(crate) fn validate_url(url: &str) -> Result<(), DomainError> {
+    let valid = match url.split_once(':') {
+        Some((scheme, rest)) => {
+            scheme.starts_with(|c: char| c.is_ascii_alphabetic())
+                && scheme
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || matches!(c, '+' | '-' | '.'))
+                && !rest.is_empty()
+                && !url.contains(char::is_whitespace)
+        }
+        None => false,
+    };
+
+    if valid {
+        Ok(())
+    } else {
+        Err(DomainError::InvalidUrl(url.to_string()))
+    }
+}
+
+/// The longest a subscription may go between refreshes.
+pub const MAX_REFRESH_INTERVAL: chrono::Duration = chrono::Duration::days(366);
+
+/// Source and fetch state of a read-only subscribed calendar.
+#[derive(Debug, Clone, PartialEq, Eq, Getters, Serialize, Deserialize)]
+pub struct Subscription {
+    #[getset(get = "pub")]
+    source_url: String,
+    #[getset(get = "pub")]
+    refresh_interval: chrono::Duration,
+    #[getset(get = "pub")]
+    last_fetched_at: Option<DateTime<Utc>>,
+    #[getset(get = "pub")]
+    etag: Option<String>,
+    /// The feed's `Last-Modified` header, kept verbatim as an HTTP date.
+    #[getset(get = "pub")]
+    last_modified: Option<String>,
+}
+
+impl Subscription {
+    pub fn new(
+        source_url: String,
+        refresh_interval: chrono::Duration,
+    ) -> Result<Self, DomainError> {
+        Self::with_state(source_url, refresh_interval, None, None)
+    }
+
+    pub fn with_state(
+        source_url: String,
+        refresh_interval: chrono::Duration,
+        last_fetched_at: Option<DateTime<Utc>>,
+        etag: Option<String>,
+    ) -> Result<Self, DomainError> {
+        let scheme = source_url
+            .split_once("://")
+            .map(|(scheme, _)| scheme.to_lowercase());
+
+        if !matches!(scheme.as_deref(), Some("http" | "https" | "webcal" | "webcals")) {
+            return Err(DomainError::InvalidSubscriptionUrl(source_url));
+        }
+
+        if refresh_interval <= chrono::Duration::zero() || refresh_interval > MAX_REFRESH_INTERVAL {
+            return Err(DomainError::InvalidRefreshInterval);
+        }
+
+        Ok(Self {
+            source_url,
+            refresh_interval,
+            last_fetched_at,
+            etag,
+            last_modified: None,
+        })
+    }
+
+    pub fn with_last_modified(mut self, last_modified: Option<String>) -> Self {
+        self.last_modified = last_modified;
+        self
+    }
+
+    /// The URL to fetch, with `webcal://` mapped onto HTTPS.
+    pub fn fetch_url(&self) -> String {
+        match self.source_url.split_once("://") {
+            Some((scheme, rest)) if scheme.eq_ignore_ascii_case("webcal")
+                || scheme.eq_ignore_ascii_case("webcals") => format!("https://{rest}"),
+            _ => self.source_url.clone(),
+        }
+    }
+
+    pub fn is_due(&self, now: DateTime<Utc>) -> bool {
+        match self.last_fetched_at {
+            Some(last) => now - last >= self.refresh_interval,
+            None => true,
+        }
+    }
+
+    pub fn record_fetch(
+        &mut self,
+        fetched_at: DateTime<Utc>,
+        etag: Option<String>,
+        last_modified: Option<String>,
+    ) {
+        self.last_fetched_at = Some(fetched_at);
+        self.etag = etag;
+        self.last_modified = last_modified;
+    }
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum Frequency {
     Daily,
     Weekly,
@@ -84,7 +235,105 @@ impl std::str::FromStr for Frequency {
     }
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+/// Whether an event is definite (iCalendar STATUS).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum EventStatus {
+    Tentative,
+    #[default]
+    Confirmed,
+    Cancelled,
+}
+
+impl fmt::Display for EventStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            EventStatus::Tentative => write!(f, "TENTATIVE"),
+            EventStatus::Confirmed => write!(f, "CONFIRMED"),
+            EventStatus::Cancelled => write!(f, "CANCELLED"),
+        }
+    }
+}
+
+impl std::str::FromStr for EventStatus {
+    type Err = DomainError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_uppercase().as_str() {
+            "TENTATIVE" => Ok(EventStatus::Tentative),
+            "CONFIRMED" => Ok(EventStatus::Confirmed),
+            "CANCELLED" => Ok(EventStatus::Cancelled),
+            other => Err(DomainError::InvalidStatus(format!("unknown status {other}"))),
+        }
+    }
+}
+
+/// Where a task stands (iCalendar VTODO STATUS).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum TaskStatus {
+    #[default]
+    NeedsAction,
+    InProcess,
+    Completed,
+    Cancelled,
+}
+
+impl fmt::Display for TaskStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TaskStatus::NeedsAction => write!(f, "NEEDS-ACTION"),
+            TaskStatus::InProcess => write!(f, "IN-PROCESS"),
+            TaskStatus::Completed => write!(f, "COMPLETED"),
+            TaskStatus::Cancelled => write!(f, "CANCELLED"),
+        }
+    }
+}
+
+impl std::str::FromStr for TaskStatus {
+    type Err = DomainError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_uppercase().as_str() {
+            "NEEDS-ACTION" => Ok(TaskStatus::NeedsAction),
+            "IN-PROCESS" => Ok(TaskStatus::InProcess),
+            "COMPLETED" => Ok(TaskStatus::Completed),
+            "CANCELLED" => Ok(TaskStatus::Cancelled),
+            other => Err(DomainError::InvalidStatus(format!("unknown task status {other}"))),
+        }
+    }
+}
+
+/// Whether an event takes up time in free/busy lookups (iCalendar
+/// TRANSP). Transparent events, such as "working from home" markers,
+/// never make anyone busy.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum Transparency {
+    #[default]
+    Opaque,
+    Transparent,
+}
+
+impl fmt::Display for Transparency {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Transparency::Opaque => write!(f, "OPAQUE"),
+            Transparency::Transparent => write!(f, "TRANSPARENT"),
+        }
+    }
+}
+
+impl std::str::FromStr for Transparency {
+    type Err = DomainError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_uppercase().as_str() {
+            "OPAQUE" => Ok(Transparency::Opaque),
+            "TRANSPARENT" => Ok(Transparency::Transparent),
+            other => Err(DomainError::InvalidStatus(format!("unknown transparency {other}"))),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub struct CalendarId(Uuid);
 
 impl CalendarId {
@@ -115,7 +364,7 @@ impl std::str::FromStr for CalendarId {
     }
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub struct EventId(Uuid);
 
 impl EventId {
@@ -145,3 +394,114 @@ impl std::str::FromStr for EventId {
         Ok(Self(Uuid::parse_str(s)?))
     }
 }
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct TaskId(Uuid);
+
+impl TaskId {
+    pub fn new() -> Self {
+        Self(Uuid::new_v4())
+    }
+
+    pub fn from_uuid(uuid: Uuid) -> Self {
+        Self(uuid)
+    }
+
+    pub fn as_uuid(&self) -> Uuid {
+        self.0
+    }
+}
+
+impl Default for TaskId {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl std::fmt::Display for TaskId {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl std::str::FromStr for TaskId {
+    type Err = uuid::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(Self(Uuid::parse_str(s)?))
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct JournalId(Uuid);
+
+impl JournalId {
+    pub fn new() -> Self {
+        Self(Uuid::new_v4())
+    }
+
+    pub fn from_uuid(uuid: Uuid) -> Self {
+        Self(uuid)
+    }
+
+    pub fn as_uuid(&self) -> Uuid {
+        self.0
+    }
+}
+
+impl Default for JournalId {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl std::fmt::Display for JournalId {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl std::str::FromStr for JournalId {
+    type Err = uuid::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(Self(Uuid::parse_str(s)?))
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct ReminderId(Uuid);
+
+impl ReminderId {
+    pub fn new() -> Self {
+        Self(Uuid::new_v4())
+    }
+    
+    pub fn from_uuid(uuid: Uuid) -> Self {
+        Self(uuid)
+    }
+    
+    pub fn as_uuid(&self) -> Uuid {
+        self.0
+    }
+}
+
+impl Default for ReminderId {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl std::fmt::Display for ReminderId {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl std::str::FromStr for ReminderId {
+    type Err = uuid::Error;
+    
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(Self(Uuid::parse_str(s)?))
+    }
+}
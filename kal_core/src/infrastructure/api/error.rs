@@ -0,0 +1,102 @@
+use thiserror::Error;
+
+use crate::{
+    application::error::ApplicationError,
+    domain::{error::DomainError, repository::RepositoryError},
+    infrastructure::{
+        dto::{error_code, ErrorDto},
+        ical::IcalError,
+        itip::ItipError,
+    },
+};
+
+#[derive(Debug, Error)]
+pub enum ApiError {
+    #[error("No such route")]
+    RouteNotFound,
+
+    #[error("Method not allowed; use {allow}")]
+    MethodNotAllowed { allow: &'static str },
+
+    #[error("Request body is larger than {limit} bytes")]
+    PayloadTooLarge { limit: usize },
+
+    #[error("Bad request: {0}")]
+    BadRequest(String),
+
+    #[error(transparent)]
+    Application(#[from] ApplicationError),
+}
+
+impl ApiError {
+    pub fn status(&self) -> u16 {
+        match self {
+            ApiError::RouteNotFound => 404,
+            ApiError::MethodNotAllowed { .. } => 405,
+            ApiError::PayloadTooLarge { .. } => 413,
+            ApiError::BadRequest(_) => 400,
+            ApiError::Application(e) => match e {
+                ApplicationError::CalendarNotFound
+                | ApplicationError::EventNotFound
+                | ApplicationError::TaskNotFound
+                | ApplicationError::JournalEntryNotFound
+                | ApplicationError::RecurringEventNotFound
+                | ApplicationError::NotInTrash
+                | ApplicationError::Domain(DomainError::ReminderNotFound(_))
+                | ApplicationError::Domain(DomainError::AttendeeNotFound(_))
+                | ApplicationError::Domain(DomainError::TagNotFound(_)) => 404,
+                ApplicationError::Domain(
+                    DomainError::SubscriptionReadOnly | DomainError::CalendarArchived,
+                ) => 403,
+                ApplicationError::Domain(_) => 422,
+                ApplicationError::Validation(_) => 400,
+                ApplicationError::Conflict
+                | ApplicationError::NothingToUndo
+                | ApplicationError::NothingToRedo
+                | ApplicationError::CalendarPurged(..) => 409,
+                ApplicationError::Repository(_) => 500,
+            },
+        }
+    }
+
+    pub fn code(&self) -> &'static str {
+        match self {
+            ApiError::RouteNotFound => "route_not_found",
+            ApiError::MethodNotAllowed { .. } => "method_not_allowed",
+            ApiError::PayloadTooLarge { .. } => "payload_too_large",
+            ApiError::BadRequest(_) => "bad_request",
+            ApiError::Application(e) => error_code(e),
+        }
+    }
+
+    pub fn to_dto(&self) -> ErrorDto {
+        ErrorDto {
+            code: self.code(),
+            message: self.to_string(),
+        }
+    }
+}
+
+impl From<DomainError> for ApiError {
+    fn from(error: DomainError) -> Self {
+        ApiError::Application(ApplicationError::Domain(error))
+    }
+}
+
+impl From<RepositoryError> for ApiError {
+    fn from(error: RepositoryError) -> Self {
+        ApiError::Application(error.into())
+    }
+}
+
+impl From<ItipError> for ApiError {
+    fn from(error: ItipError) -> Self {
+        ApiError::Application(ApplicationError::Validation(error.to_string()))
+    }
+}
+
+impl From<IcalError> for ApiError {
+    fn from(error: IcalError) -> Self {
+        ApiError::Application(ApplicationError::Validation(error.to_string()))
+    }
+}
@@ -0,0 +1,7 @@
+pub mod server;
+pub mod routes;
+pub mod error;
+
+pub use server::{ApiServer, ACTOR_HEADER};
+pub use routes::{ApiResponse, ApiRoutes};
+pub use error::ApiError;
@@ -0,0 +1,1689 @@
+use std::collections::HashMap;
+
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::{de::DeserializeOwned, Serialize};
+use sqlx::SqlitePool;
+use uuid::Uuid;
+
+use crate::{
+    application::{
+        commands::{
+            calendars::{
+                ArchiveCalendarCommand, ArchiveCalendarHandler,
+                CreateCalendarCommand, CreateCalendarHandler,
+                DeleteCalendarCommand, DeleteCalendarHandler,
+                RenameCalendarCommand, RenameCalendarHandler,
+                RestoreTrashedCalendarCommand, RestoreTrashedCalendarHandler,
+                SubscribeCalendarCommand, SubscribeCalendarHandler,
+                UnarchiveCalendarCommand, UnarchiveCalendarHandler,
+                UpdateCalendarDescriptionCommand, UpdateCalendarDescriptionHandler,
+            },
+            events::{
+                AddEventAttendeeCommand, AddEventAttendeeHandler,
+                AddEventReminderCommand, AddEventReminderHandler,
+                AddEventTagCommand, AddEventTagHandler,
+                CancelEventCommand, CancelEventHandler,
+                CreateEventCommand, CreateEventHandler,
+                DeleteEventCommand, DeleteEventHandler,
+                RemoveEventAttendeeCommand, RemoveEventAttendeeHandler,
+                RemoveEventReminderCommand, RemoveEventReminderHandler,
+                RemoveEventTagCommand, RemoveEventTagHandler,
+                RestoreEventCommand, RestoreEventHandler,
+                RestoreTrashedEventCommand, RestoreTrashedEventHandler,
+                SetEventOrganizerCommand, SetEventOrganizerHandler,
+                UpdateEventAttendeeStatusCommand, UpdateEventAttendeeStatusHandler,
+                UpdateEventColorCommand, UpdateEventColorHandler,
+                UpdateEventDescriptionCommand, UpdateEventDescriptionHandler,
+                UpdateEventGeoCommand, UpdateEventGeoHandler,
+                UpdateEventLocationCommand, UpdateEventLocationHandler,
+                UpdateEventStatusCommand, UpdateEventStatusHandler,
+                UpdateEventTimeRangeCommand, UpdateEventTimeRangeHandler,
+                UpdateEventTitleCommand, UpdateEventTitleHandler,
+                UpdateEventTransparencyCommand, UpdateEventTransparencyHandler,
+                UpdateEventUrlCommand, UpdateEventUrlHandler,
+            },
+            recurring::{
+                AddRecurringAttendeeCommand, AddRecurringAttendeeHandler,
+                AddRecurringReminderCommand, AddRecurringReminderHandler,
+                AddRecurringTagCommand, AddRecurringTagHandler,
+                CancelRecurringEventCommand, CancelRecurringEventHandler,
+                CancelRecurringOccurrenceCommand, CancelRecurringOccurrenceHandler,
+                CreateRecurringEventCommand, CreateRecurringEventHandler,
+                DeleteRecurringEventCommand, DeleteRecurringEventHandler,
+                RemoveRecurringAttendeeCommand, RemoveRecurringAttendeeHandler,
+                RemoveRecurringReminderCommand, RemoveRecurringReminderHandler,
+                RemoveRecurringTagCommand, RemoveRecurringTagHandler,
+                RescheduleRecurringOccurrenceCommand, RescheduleRecurringOccurrenceHandler,
+                RestoreRecurringEventCommand, RestoreRecurringEventHandler,
+                RestoreRecurringOccurrenceCommand, RestoreRecurringOccurrenceHandler,
+                RestoreTrashedRecurringEventCommand, RestoreTrashedRecurringEventHandler,
+                SetRecurringOccurrenceLocationCommand, SetRecurringOccurrenceLocationHandler,
+                SetRecurringOrganizerCommand, SetRecurringOrganizerHandler,
+                UpdateRecurringAttendeeStatusCommand, UpdateRecurringAttendeeStatusHandler,
+                UpdateRecurringGeoCommand, UpdateRecurringGeoHandler,
+                UpdateRecurringLocationCommand, UpdateRecurringLocationHandler,
+                UpdateRecurringStatusCommand, UpdateRecurringStatusHandler,
+                UpdateRecurringTransparencyCommand, UpdateRecurringTransparencyHandler,
+                UpdateRecurringUrlCommand, UpdateRecurringUrlHandler,
+            },
+            reminders::{
+                DismissReminderCommand, DismissReminderHandler,
+                SnoozeReminderCommand, SnoozeReminderHandler,
+            },
+            journal::{
+                CreateJournalEntryCommand, CreateJournalEntryHandler,
+                DeleteJournalEntryCommand, DeleteJournalEntryHandler,
+                ImportJournalEntriesCommand, ImportJournalEntriesHandler,
+                UpdateJournalEntryCommand, UpdateJournalEntryHandler,
+            },
+            scheduling::{ApplyReplyCommand, ApplyReplyHandler},
+            tags::{MergeTagCommand, MergeTagHandler, RenameTagCommand, RenameTagHandler},
+            tasks::{
+                CompleteTaskCommand, CompleteTaskHandler,
+                CreateTaskCommand, CreateTaskHandler,
+                DeleteTaskCommand, DeleteTaskHandler,
+                ImportTasksCommand, ImportTasksHandler,
+                ReopenTaskCommand, ReopenTaskHandler,
+                UpdateTaskCommand, UpdateTaskHandler,
+            },
+            history::{RedoCommand, RedoHandler, UndoCommand, UndoHandler},
+            trash::{PurgeTrashCommand, PurgeTrashHandler},
+        },
+        error::ApplicationError,
+        history::Recorded,
+        publisher::{Published, Publisher},
+        scope::CommandScope,
+    },
+    domain::{
+        calendar_object::CalendarObject,
+        free_busy::busy_periods,
+        journal::JournalEntry,
+        recurrence::RecurrenceRule,
+        search::{within_window, SearchHit, SearchHitKind},
+        repository::{
+            AuditRepository, CalendarRepository, EventRepository, EventSearchRepository,
+            HistoryRepository,
+            JournalRepository, RecurringEventRepository, RepositoryError, TagRepository,
+            TaskRepository,
+        },
+        tag::{Tag, TagFilter},
+        task::Task,
+        value_objects::{
+            CalendarId, EventColor, EventId, EventStatus, Frequency, JournalId, ReminderId,
+            TaskId, TimeRange, Transparency,
+        },
+    },
+    infrastructure::{
+        dto::*,
+        ical::{Component, IcalMapper},
+        itip::{ItipMessage, ItipReply},
+        persistence::{
+            SqliteAuditRepository,
+            SqliteCalendarRepository,
+            SqliteEventRepository,
+            SqliteEventSearchRepository,
+            SqliteHistoryRepository,
+            SqliteJournalRepository,
+            SqliteRecurringEventRepository,
+            SqliteReminderStateRepository,
+            SqliteTagRepository,
+            SqliteTaskRepository,
+            SqliteUnitOfWork,
+        },
+    },
+};
+
+use super::error::ApiError;
+
+type ApiResult = Result<ApiResponse, ApiError>;
+
+/// A transport-independent JSON response.
+#[derive(Debug, Clone)]
+pub struct ApiResponse {
+    pub status: u16,
+    pub body: Option<String>,
+}
+
+impl ApiResponse {
+    fn json(status: u16, value: &impl Serialize) -> Self {
+        Self {
+            status,
+            body: Some(serde_json::to_string(value).expect("DTOs always serialize")),
+        }
+    }
+
+    fn ok(value: &impl Serialize) -> Self {
+        Self::json(200, value)
+    }
+
+    fn created(id: Uuid) -> Self {
+        Self::json(201, &CreatedDto { id })
+    }
+
+    fn no_content() -> Self {
+        Self { status: 204, body: None }
+    }
+}
+
+/// How many entries `GET /history` returns unless asked otherwise.
+const DEFAULT_HISTORY_LIMIT: u32 = 20;
+
+/// How many entries the `audit` routes return unless asked otherwise.
+const DEFAULT_AUDIT_LIMIT: u32 = 20;
+
+/// REST routes over the application layer. Writes go through the
+/// command handlers; reads go straight to the repositories.
+///
+/// Each request runs in one transaction. Requests that change something
+/// are added to the undo history and the audit log in that same
+/// transaction, and the domain events they raised are published once it
+/// has committed.
+#[derive(Clone)]
+pub struct ApiRoutes {
+    pool: SqlitePool,
+    scope: CommandScope,
+}
+
+impl ApiRoutes {
+    pub fn new(pool: SqlitePool) -> Self {
+        Self { pool, scope: CommandScope::new() }
+    }
+
+    /// Publishes domain events to `publisher`'s subscribers.
+    pub fn with_publisher(mut self, publisher: Publisher) -> Self {
+        self.scope = self.scope.with_publisher(publisher);
+        self
+    }
+
+    /// Attributes requests to `actor` rather than the publisher's, such as
+    /// the user a request names.
+    pub fn with_actor(mut self, actor: impl Into<String>) -> Self {
+        self.scope = self.scope.with_actor(actor);
+        self
+    }
+
+    pub async fn handle(&self, method: &str, path: &str, query: &str, body: &str) -> ApiResult {
+        // Only requests that may write take the write lock
+        let uow = match method {
+            "GET" => SqliteUnitOfWork::begin_read(&self.pool).await?,
+            _ => SqliteUnitOfWork::begin(&self.pool).await?,
+        };
+
+        // A scope of its own per request keeps concurrent requests apart
+        let request = Request { uow, scope: self.scope.scoped() };
+
+        // A failed request drops its unit of work, which rolls it back,
+        // except that undo and redo keep stepping past an entry they cannot
+        // replay
+        let response = match request.route(method, path, query, body).await {
+            Err(ApiError::Application(e)) if e.skipped_history_entry() => {
+                request.uow.commit().await?;
+                return Err(e.into());
+            }
+            response => response?,
+        };
+
+        let command = format!("{method} {path}");
+        request.scope.record(&request.uow.history(), &command).await?;
+        request.scope.audit(&request.uow.audit(), &command).await?;
+        request.uow.commit().await?;
+        request.scope.publish(&command).await;
+
+        Ok(response)
+    }
+}
+
+/// One request in flight: the transaction it runs in, and what it
+/// changed and raised along the way.
+struct Request {
+    uow: SqliteUnitOfWork,
+    scope: CommandScope,
+}
+
+impl Request {
+    fn calendars(&self) -> Recorded<Published<SqliteCalendarRepository>> {
+        self.scope.calendars(self.uow.calendars())
+    }
+
+    fn events(&self) -> Recorded<Published<SqliteEventRepository>> {
+        self.scope.events(self.uow.events())
+    }
+
+    fn recurring(&self) -> Recorded<Published<SqliteRecurringEventRepository>> {
+        self.scope.recurring(self.uow.recurring())
+    }
+
+    fn search(&self) -> SqliteEventSearchRepository {
+        self.uow.search()
+    }
+
+    fn reminder_states(&self) -> SqliteReminderStateRepository {
+        self.uow.reminder_states()
+    }
+
+    fn tags(&self) -> SqliteTagRepository {
+        self.uow.tags()
+    }
+
+    fn tasks(&self) -> Recorded<SqliteTaskRepository> {
+        self.scope.tasks(self.uow.tasks())
+    }
+
+    fn journal(&self) -> Recorded<SqliteJournalRepository> {
+        self.scope.journal(self.uow.journal())
+    }
+
+    fn history(&self) -> SqliteHistoryRepository {
+        self.uow.history()
+    }
+
+    fn audit(&self) -> SqliteAuditRepository {
+        self.uow.audit()
+    }
+
+    async fn route(&self, method: &str, path: &str, query: &str, body: &str) -> ApiResult {
+        let segments: Vec<&str> = path.split('/').filter(|s| !s.is_empty()).collect();
+        let query = parse_query(query);
+
+        match (method, segments.as_slice()) {
+            ("GET", ["calendars"]) => self.list_calendars().await,
+            ("POST", ["calendars"]) => self.create_calendar(parse_body(body)?).await,
+            ("GET", ["calendars", id]) => self.get_calendar(parse_id(id)?).await,
+            ("PATCH", ["calendars", id]) => self.update_calendar(parse_id(id)?, parse_body(body)?).await,
+            ("DELETE", ["calendars", id]) => self.delete_calendar(parse_id(id)?).await,
+            ("POST", ["calendars", id, "archive"]) => self.archive_calendar(parse_id(id)?, true).await,
+            ("POST", ["calendars", id, "unarchive"]) => self.archive_calendar(parse_id(id)?, false).await,
+            ("GET", ["calendars", id, "freebusy"]) => self.free_busy(parse_id(id)?, &query).await,
+            ("POST", ["subscriptions"]) => self.subscribe_calendar(parse_body(body)?).await,
+
+            ("GET", ["calendars", id, "events"]) => self.list_events(parse_id(id)?, &query).await,
+            ("POST", ["calendars", id, "events"]) => self.create_event(parse_id(id)?, parse_body(body)?).await,
+            ("GET", ["events", id]) => self.get_event(parse_id(id)?).await,
+            ("PATCH", ["events", id]) => self.update_event(parse_id(id)?, parse_body(body)?).await,
+            ("DELETE", ["events", id]) => self.delete_event(parse_id(id)?).await,
+            ("POST", ["events", id, "cancel"]) => self.cancel_event(parse_id(id)?, true).await,
+            ("POST", ["events", id, "restore"]) => self.cancel_event(parse_id(id)?, false).await,
+            ("POST", ["events", id, "reminders"]) => self.add_event_reminder(parse_id(id)?, parse_body(body)?).await,
+            ("DELETE", ["events", id, "reminders", reminder]) => {
+                self.remove_event_reminder(parse_id(id)?, parse_id(reminder)?).await
+            }
+            ("PUT", ["events", id, "organizer"]) => {
+                self.set_event_organizer(parse_id(id)?, parse_body(body)?).await
+            }
+            ("POST", ["events", id, "attendees"]) => {
+                self.add_event_attendee(parse_id(id)?, parse_body(body)?).await
+            }
+            ("PATCH", ["events", id, "attendees", email]) => {
+                let email = percent_decode(email);
+                self.update_event_attendee_status(parse_id(id)?, email, parse_body(body)?).await
+            }
+            ("DELETE", ["events", id, "attendees", email]) => {
+                self.remove_event_attendee(parse_id(id)?, percent_decode(email)).await
+            }
+            ("POST", ["events", id, "tags"]) => self.add_event_tag(parse_id(id)?, parse_body(body)?).await,
+            ("DELETE", ["events", id, "tags", tag]) => self.remove_event_tag(parse_id(id)?, parse_tag(tag)?).await,
+
+            ("GET", ["calendars", id, "recurring"]) => self.list_recurring(parse_id(id)?, &query).await,
+            ("POST", ["calendars", id, "recurring"]) => self.create_recurring(parse_id(id)?, parse_body(body)?).await,
+            ("GET", ["recurring", id]) => self.get_recurring(parse_id(id)?).await,
+            ("PATCH", ["recurring", id]) => self.update_recurring(parse_id(id)?, parse_body(body)?).await,
+            ("DELETE", ["recurring", id]) => self.delete_recurring(parse_id(id)?).await,
+            ("POST", ["recurring", id, "cancel"]) => self.cancel_recurring(parse_id(id)?, true).await,
+            ("POST", ["recurring", id, "restore"]) => self.cancel_recurring(parse_id(id)?, false).await,
+            ("GET", ["recurring", id, "occurrences"]) => self.list_occurrences(parse_id(id)?, &query).await,
+            ("POST", ["recurring", id, "exceptions"]) => self.add_exception(parse_id(id)?, parse_body(body)?).await,
+            ("DELETE", ["recurring", id, "exceptions", original]) => {
+                self.remove_exception(parse_id(id)?, parse_datetime(original)?).await
+            }
+            ("POST", ["recurring", id, "locations"]) => {
+                self.set_occurrence_location(parse_id(id)?, parse_body(body)?).await
+            }
+            ("POST", ["recurring", id, "reminders"]) => {
+                self.add_recurring_reminder(parse_id(id)?, parse_body(body)?).await
+            }
+            ("DELETE", ["recurring", id, "reminders", reminder]) => {
+                self.remove_recurring_reminder(parse_id(id)?, parse_id(reminder)?).await
+            }
+            ("PUT", ["recurring", id, "organizer"]) => {
+                self.set_recurring_organizer(parse_id(id)?, parse_body(body)?).await
+            }
+            ("POST", ["recurring", id, "attendees"]) => {
+                self.add_recurring_attendee(parse_id(id)?, parse_body(body)?).await
+            }
+            ("PATCH", ["recurring", id, "attendees", email]) => {
+                let email = percent_decode(email);
+                self.update_recurring_attendee_status(parse_id(id)?, email, parse_body(body)?).await
+            }
+            ("DELETE", ["recurring", id, "attendees", email]) => {
+                self.remove_recurring_attendee(parse_id(id)?, percent_decode(email)).await
+            }
+            ("POST", ["recurring", id, "tags"]) => self.add_recurring_tag(parse_id(id)?, parse_body(body)?).await,
+            ("DELETE", ["recurring", id, "tags", tag]) => {
+                self.remove_recurring_tag(parse_id(id)?, parse_tag(tag)?).await
+            }
+
+            ("GET", ["calendars", id, "tasks"]) => self.list_tasks(parse_id(id)?, &query).await,
+            ("POST", ["calendars", id, "tasks"]) => self.create_task(parse_id(id)?, parse_body(body)?).await,
+            ("POST", ["calendars", id, "tasks", "import"]) => {
+                self.import_tasks(parse_id(id)?, parse_body(body)?).await
+            }
+            ("GET", ["calendars", id, "tasks", "export"]) => self.export_tasks(parse_id(id)?, &query).await,
+            ("GET", ["tasks", id]) => self.get_task(parse_id(id)?).await,
+            ("PATCH", ["tasks", id]) => self.update_task(parse_id(id)?, parse_body(body)?).await,
+            ("DELETE", ["tasks", id]) => self.delete_task(parse_id(id)?).await,
+            ("POST", ["tasks", id, "complete"]) => self.complete_task(parse_id(id)?).await,
+            ("POST", ["tasks", id, "reopen"]) => self.reopen_task(parse_id(id)?).await,
+
+            ("GET", ["calendars", id, "journal"]) => self.list_journal(parse_id(id)?, &query).await,
+            ("POST", ["calendars", id, "journal"]) => {
+                self.create_journal_entry(parse_id(id)?, parse_body(body)?).await
+            }
+            ("POST", ["calendars", id, "journal", "import"]) => {
+                self.import_journal(parse_id(id)?, parse_body(body)?).await
+            }
+            ("GET", ["calendars", id, "journal", "export"]) => self.export_journal(parse_id(id)?, &query).await,
+            ("GET", ["journal", "search"]) => self.search_journal(&query).await,
+            ("GET", ["journal", id]) => self.get_journal_entry(parse_id(id)?).await,
+            ("PATCH", ["journal", id]) => self.update_journal_entry(parse_id(id)?, parse_body(body)?).await,
+            ("DELETE", ["journal", id]) => self.delete_journal_entry(parse_id(id)?).await,
+            ("GET", ["events" | "recurring", id, "journal"]) => self.journal_for_event(parse_id(id)?).await,
+
+            ("GET", ["search"]) => self.search_events(&query).await,
+
+            ("GET", ["tags"]) => self.list_tags().await,
+            ("POST", ["tags", "rename"]) => self.rename_tag(parse_body(body)?).await,
+            ("POST", ["tags", "merge"]) => self.merge_tag(parse_body(body)?).await,
+
+            ("GET", ["trash"]) => self.list_trash().await,
+            ("DELETE", ["trash"]) => self.purge_trash(&query).await,
+            ("POST", ["trash", id, "restore"]) => self.restore_trashed(parse_id(id)?).await,
+
+            ("GET", ["history"]) => self.list_history(&query).await,
+            ("POST", ["history", "undo"]) => self.undo().await,
+            ("POST", ["history", "redo"]) => self.redo().await,
+
+            ("GET", ["calendars", id, "audit"]) => self.calendar_audit(parse_id(id)?, &query).await,
+            ("GET", ["events" | "recurring", id, "audit"]) => self.event_audit(parse_id(id)?, &query).await,
+
+            // Instance state is keyed by the event or series alike
+            ("POST", ["events" | "recurring", id, "reminders", reminder, "snooze"]) => {
+                self.snooze_reminder(parse_id(id)?, parse_id(reminder)?, parse_body(body)?).await
+            }
+            ("POST", ["events" | "recurring", id, "reminders", reminder, "dismiss"]) => {
+                self.dismiss_reminder(parse_id(id)?, parse_id(reminder)?, parse_body(body)?).await
+            }
+
+            ("GET", ["events" | "recurring", id, "itip"]) => self.itip_message(parse_id(id)?).await,
+            ("POST", ["itip", "replies"]) => self.apply_reply(parse_body(body)?).await,
+
+            _ => match allowed_methods(&segments) {
+                Some(allow) => Err(ApiError::MethodNotAllowed { allow }),
+                None => Err(ApiError::RouteNotFound),
+            },
+        }
+    }
+
+
+    // ==================================================
+    // Calendars
+    // ==================================================
+
+    async fn list_calendars(&self) -> ApiResult {
+        let calendars = self.calendars().find_all_active().await?;
+        let dtos: Vec<CalendarDto> = calendars.iter().map(CalendarDto::from).collect();
+        Ok(ApiResponse::ok(&dtos))
+    }
+
+    async fn get_calendar(&self, id: Uuid) -> ApiResult {
+        let calendar = self
+            .calendars()
+            .find_by_id(&CalendarId::from_uuid(id))
+            .await?
+            .ok_or(ApplicationError::CalendarNotFound)?;
+        Ok(ApiResponse::ok(&CalendarDto::from(&calendar)))
+    }
+
+    async fn create_calendar(&self, dto: CreateCalendarDto) -> ApiResult {
+        let id = CreateCalendarHandler::new(self.calendars())
+            .handle(CreateCalendarCommand::new(dto.name, dto.description))
+            .await?;
+        Ok(ApiResponse::created(id.as_uuid()))
+    }
+
+    async fn subscribe_calendar(&self, dto: SubscribeCalendarDto) -> ApiResult {
+        let refresh_interval = dto.refresh_interval()?;
+        let command =
+            SubscribeCalendarCommand::new(dto.name, dto.description, dto.url, refresh_interval);
+
+        let id = SubscribeCalendarHandler::new(self.calendars()).handle(command).await?;
+        Ok(ApiResponse::created(id.as_uuid()))
+    }
+
+    async fn update_calendar(&self, id: Uuid, dto: UpdateCalendarDto) -> ApiResult {
+        let id = CalendarId::from_uuid(id);
+
+        if let Some(name) = dto.name {
+            RenameCalendarHandler::new(self.calendars())
+                .handle(RenameCalendarCommand::new(id, name))
+                .await?;
+        }
+
+        if let Some(description) = dto.description {
+            UpdateCalendarDescriptionHandler::new(self.calendars())
+                .handle(UpdateCalendarDescriptionCommand::new(id, description))
+                .await?;
+        }
+
+        self.get_calendar(id.as_uuid()).await
+    }
+
+    async fn archive_calendar(&self, id: Uuid, archive: bool) -> ApiResult {
+        let id = CalendarId::from_uuid(id);
+
+        if archive {
+            ArchiveCalendarHandler::new(self.calendars())
+                .handle(ArchiveCalendarCommand::new(id))
+                .await?;
+        } else {
+            UnarchiveCalendarHandler::new(self.calendars())
+                .handle(UnarchiveCalendarCommand::new(id))
+                .await?;
+        }
+
+        Ok(ApiResponse::no_content())
+    }
+
+    async fn delete_calendar(&self, id: Uuid) -> ApiResult {
+        DeleteCalendarHandler::new(self.calendars())
+            .handle(DeleteCalendarCommand::new(CalendarId::from_uuid(id)))
+            .await?;
+        Ok(ApiResponse::no_content())
+    }
+
+    async fn free_busy(&self, id: Uuid, query: &HashMap<String, String>) -> ApiResult {
+        let calendar_id = CalendarId::from_uuid(id);
+        self.ensure_calendar(&calendar_id).await?;
+        let window = query_range(query)?
+            .ok_or_else(|| ApiError::BadRequest("`from` and `to` are required".to_string()))?;
+
+        let events = self.events().find_in_range(&calendar_id, &window).await?;
+        let series = self.recurring().find_by_calendar(&calendar_id).await?;
+        let dtos: Vec<BusyPeriodDto> = busy_periods(&events, &series, &window)
+            .iter()
+            .map(BusyPeriodDto::from)
+            .collect();
+        Ok(ApiResponse::ok(&dtos))
+    }
+
+
+    // ==================================================
+    // Events
+    // ==================================================
+
+    async fn list_events(&self, id: Uuid, query: &HashMap<String, String>) -> ApiResult {
+        let calendar_id = CalendarId::from_uuid(id);
+        self.ensure_calendar(&calendar_id).await?;
+        let filter = query_tag_filter(query)?;
+
+        let mut events = match (query_text(query, "location"), query_range(query)?) {
+            (None, Some(range)) => self.events().find_in_range(&calendar_id, &range).await?,
+            (None, None) => self.events().find_by_calendar(&calendar_id).await?,
+            (Some(location), range) => {
+                let mut events = self.events().find_by_location(&calendar_id, &location).await?;
+
+                if let Some(range) = range {
+                    events.retain(|event| !event.is_cancelled() && event.time_range().overlaps(&range));
+                }
+
+                events
+            }
+        };
+        events.retain(|event| filter.matches(event.tags()));
+
+        let dtos: Vec<EventDto> = events.iter().map(EventDto::from).collect();
+        Ok(ApiResponse::ok(&dtos))
+    }
+
+    /// `?q=` is required. `?calendar_id=`, `?from=`/`?to=` and `?limit=`
+    /// (default 50) narrow the results.
+    async fn search_events(&self, query: &HashMap<String, String>) -> ApiResult {
+        let text = query_text(query, "q")
+            .ok_or_else(|| ApiError::BadRequest("`q` is required".to_string()))?;
+        let calendar_id = query
+            .get("calendar_id")
+            .map(|id| parse_id(id).map(CalendarId::from_uuid))
+            .transpose()?;
+        if let Some(calendar_id) = &calendar_id {
+            self.ensure_calendar(calendar_id).await?;
+        }
+        let window = query_range(query)?;
+        let limit = match query.get("limit") {
+            Some(limit) => limit
+                .parse::<usize>()
+                .map_err(|_| ApiError::BadRequest(format!("invalid limit: {limit}")))?,
+            None => 50,
+        };
+
+        let mut hits = self
+            .search()
+            .search(&text, calendar_id.as_ref(), window.as_ref())
+            .await?;
+        if let Some(window) = &window {
+            hits = self.hits_within(hits, window).await?;
+        }
+        hits.truncate(limit);
+
+        let dtos: Vec<SearchHitDto> = hits.iter().map(SearchHitDto::from).collect();
+        Ok(ApiResponse::ok(&dtos))
+    }
+
+    /// Keeps the series hits with an occurrence in `window`.
+    async fn hits_within(&self, hits: Vec<SearchHit>, window: &TimeRange) -> Result<Vec<SearchHit>, ApiError> {
+        let mut series = Vec::new();
+        for hit in hits.iter().filter(|hit| *hit.kind() == SearchHitKind::Series) {
+            series.push(self.recurring().find_by_id(hit.event_id()).await?);
+        }
+
+        Ok(within_window(hits, &series, window))
+    }
+
+    async fn get_event(&self, id: Uuid) -> ApiResult {
+        let event = self
+            .events()
+            .find_by_id(&EventId::from_uuid(id))
+            .await?
+            .ok_or(ApplicationError::EventNotFound)?;
+        Ok(ApiResponse::ok(&EventDto::from(&event)))
+    }
+
+    async fn create_event(&self, calendar_id: Uuid, dto: CreateEventDto) -> ApiResult {
+        let (status, transparency) =
+            parse_availability(dto.status.as_deref(), dto.transparency.as_deref())?;
+        let command = CreateEventCommand::new(
+            CalendarId::from_uuid(calendar_id),
+            dto.title,
+            dto.description,
+            TimeRange::new(dto.starts_at, dto.ends_at)?,
+            EventColor::from(dto.color),
+            dto.is_all_day,
+        )
+        .with_place(dto.location, dto.url, dto.geo.map(|g| g.point()).transpose()?)
+        .with_tags(parse_tags(&dto.tags)?)
+        .with_status(status, transparency);
+
+        let id = CreateEventHandler::new(self.events(), self.calendars())
+            .handle(command)
+            .await?;
+        Ok(ApiResponse::created(id.as_uuid()))
+    }
+
+    async fn update_event(&self, id: Uuid, dto: UpdateEventDto) -> ApiResult {
+        let id = EventId::from_uuid(id);
+
+        // Validate the whole patch before applying any part of it
+        let time_range = match (dto.starts_at, dto.ends_at) {
+            (None, None) => None,
+            (starts_at, ends_at) => {
+                let current = self
+                    .events()
+                    .find_by_id(&id)
+                    .await?
+                    .ok_or(ApplicationError::EventNotFound)?;
+                let starts_at = starts_at.unwrap_or(*current.time_range().starts_at());
+                let ends_at = ends_at.unwrap_or(*current.time_range().ends_at());
+                Some(TimeRange::new(starts_at, ends_at)?)
+            }
+        };
+        let geo = dto.geo.map(|geo| geo.map(|g| g.point()).transpose()).transpose()?;
+        let status = dto.status.as_deref().map(str::parse::<EventStatus>).transpose()?;
+        let transparency = dto.transparency.as_deref().map(str::parse::<Transparency>).transpose()?;
+
+        if let Some(title) = dto.title {
+            UpdateEventTitleHandler::new(self.events(), self.calendars())
+                .handle(UpdateEventTitleCommand::new(id, title))
+                .await?;
+        }
+
+        if let Some(description) = dto.description {
+            UpdateEventDescriptionHandler::new(self.events(), self.calendars())
+                .handle(UpdateEventDescriptionCommand::new(id, description))
+                .await?;
+        }
+
+        if let Some(time_range) = time_range {
+            UpdateEventTimeRangeHandler::new(self.events(), self.calendars())
+                .handle(UpdateEventTimeRangeCommand::new(id, time_range))
+                .await?;
+        }
+
+        if let Some(color) = dto.color {
+            UpdateEventColorHandler::new(self.events(), self.calendars())
+                .handle(UpdateEventColorCommand::new(id, EventColor::from(color)))
+                .await?;
+        }
+
+        if let Some(location) = dto.location {
+            UpdateEventLocationHandler::new(self.events(), self.calendars())
+                .handle(UpdateEventLocationCommand::new(id, location))
+                .await?;
+        }
+
+        if let Some(url) = dto.url {
+            UpdateEventUrlHandler::new(self.events(), self.calendars())
+                .handle(UpdateEventUrlCommand::new(id, url))
+                .await?;
+        }
+
+        if let Some(geo) = geo {
+            UpdateEventGeoHandler::new(self.events(), self.calendars())
+                .handle(UpdateEventGeoCommand::new(id, geo))
+                .await?;
+        }
+
+        if let Some(status) = status {
+            UpdateEventStatusHandler::new(self.events(), self.calendars())
+                .handle(UpdateEventStatusCommand::new(id, status))
+                .await?;
+        }
+
+        if let Some(transparency) = transparency {
+            UpdateEventTransparencyHandler::new(self.events(), self.calendars())
+                .handle(UpdateEventTransparencyCommand::new(id, transparency))
+                .await?;
+        }
+
+        self.get_event(id.as_uuid()).await
+    }
+
+    async fn cancel_event(&self, id: Uuid, cancel: bool) -> ApiResult {
+        let id = EventId::from_uuid(id);
+
+        if cancel {
+            CancelEventHandler::new(self.events(), self.calendars())
+                .handle(CancelEventCommand::new(id))
+                .await?;
+        } else {
+            RestoreEventHandler::new(self.events(), self.calendars())
+                .handle(RestoreEventCommand::new(id))
+                .await?;
+        }
+
+        Ok(ApiResponse::no_content())
+    }
+
+    async fn add_event_reminder(&self, id: Uuid, dto: CreateReminderDto) -> ApiResult {
+        let command = AddEventReminderCommand::new(
+            EventId::from_uuid(id),
+            dto.trigger()?,
+            dto.action()?,
+            dto.description.clone(),
+            dto.repeat,
+            dto.repeat_interval()?,
+        );
+
+        let reminder_id = AddEventReminderHandler::new(self.events(), self.calendars())
+            .handle(command)
+            .await?;
+        Ok(ApiResponse::created(reminder_id.as_uuid()))
+    }
+
+    async fn remove_event_reminder(&self, id: Uuid, reminder_id: Uuid) -> ApiResult {
+        RemoveEventReminderHandler::new(self.events(), self.calendars())
+            .handle(RemoveEventReminderCommand::new(
+                EventId::from_uuid(id),
+                ReminderId::from_uuid(reminder_id),
+            ))
+            .await?;
+        Ok(ApiResponse::no_content())
+    }
+
+    async fn set_event_organizer(&self, id: Uuid, dto: SetOrganizerDto) -> ApiResult {
+        SetEventOrganizerHandler::new(self.events(), self.calendars())
+            .handle(SetEventOrganizerCommand::new(EventId::from_uuid(id), dto.organizer()?))
+            .await?;
+        Ok(ApiResponse::no_content())
+    }
+
+    async fn add_event_attendee(&self, id: Uuid, dto: CreateAttendeeDto) -> ApiResult {
+        AddEventAttendeeHandler::new(self.events(), self.calendars())
+            .handle(AddEventAttendeeCommand::new(EventId::from_uuid(id), dto.attendee()?))
+            .await?;
+        Ok(ApiResponse::no_content())
+    }
+
+    async fn remove_event_attendee(&self, id: Uuid, email: String) -> ApiResult {
+        RemoveEventAttendeeHandler::new(self.events(), self.calendars())
+            .handle(RemoveEventAttendeeCommand::new(EventId::from_uuid(id), email))
+            .await?;
+        Ok(ApiResponse::no_content())
+    }
+
+    async fn update_event_attendee_status(&self, id: Uuid, email: String, dto: AttendeeStatusDto) -> ApiResult {
+        let command = UpdateEventAttendeeStatusCommand::new(
+            EventId::from_uuid(id),
+            email,
+            dto.status.parse()?,
+        );
+
+        UpdateEventAttendeeStatusHandler::new(self.events(), self.calendars())
+            .handle(command)
+            .await?;
+        Ok(ApiResponse::no_content())
+    }
+
+    async fn add_event_tag(&self, id: Uuid, dto: TagNameDto) -> ApiResult {
+        AddEventTagHandler::new(self.events(), self.calendars())
+            .handle(AddEventTagCommand::new(EventId::from_uuid(id), dto.tag()?))
+            .await?;
+        Ok(ApiResponse::no_content())
+    }
+
+    async fn remove_event_tag(&self, id: Uuid, tag: Tag) -> ApiResult {
+        RemoveEventTagHandler::new(self.events(), self.calendars())
+            .handle(RemoveEventTagCommand::new(EventId::from_uuid(id), tag))
+            .await?;
+        Ok(ApiResponse::no_content())
+    }
+
+    async fn delete_event(&self, id: Uuid) -> ApiResult {
+        DeleteEventHandler::new(self.events(), self.calendars())
+            .handle(DeleteEventCommand::new(EventId::from_uuid(id)))
+            .await?;
+        Ok(ApiResponse::no_content())
+    }
+
+
+    // ==================================================
+    // Recurring series
+    // ==================================================
+
+    async fn list_recurring(&self, id: Uuid, query: &HashMap<String, String>) -> ApiResult {
+        let calendar_id = CalendarId::from_uuid(id);
+        self.ensure_calendar(&calendar_id).await?;
+
+        let filter = query_tag_filter(query)?;
+
+        let mut events = match query_text(query, "location") {
+            Some(location) => self.recurring().find_by_location(&calendar_id, &location).await?,
+            None => self.recurring().find_by_calendar(&calendar_id).await?,
+        };
+        events.retain(|event| filter.matches(event.tags()));
+        let dtos: Vec<RecurringEventDto> = events.iter().map(RecurringEventDto::from).collect();
+        Ok(ApiResponse::ok(&dtos))
+    }
+
+    async fn get_recurring(&self, id: Uuid) -> ApiResult {
+        let event = self
+            .recurring()
+            .find_by_id(&EventId::from_uuid(id))
+            .await
+            .map_err(ApplicationError::from_recurring_lookup)?;
+        Ok(ApiResponse::ok(&RecurringEventDto::from(&event)))
+    }
+
+    async fn create_recurring(&self, calendar_id: Uuid, dto: CreateRecurringEventDto) -> ApiResult {
+        let (status, transparency) =
+            parse_availability(dto.status.as_deref(), dto.transparency.as_deref())?;
+        let frequency = dto.frequency.parse::<Frequency>()?;
+
+        let command = CreateRecurringEventCommand::new(
+            CalendarId::from_uuid(calendar_id),
+            dto.title,
+            dto.description,
+            TimeRange::new(dto.starts_at, dto.ends_at)?,
+            RecurrenceRule::new(frequency, dto.interval, dto.until)?,
+            EventColor::from(dto.color),
+            dto.is_all_day,
+        )
+        .with_place(dto.location, dto.url, dto.geo.map(|g| g.point()).transpose()?)
+        .with_tags(parse_tags(&dto.tags)?)
+        .with_status(status, transparency);
+
+        let id = CreateRecurringEventHandler::new(self.recurring(), self.calendars())
+            .handle(command)
+            .await?;
+        Ok(ApiResponse::created(id.as_uuid()))
+    }
+
+    async fn update_recurring(&self, id: Uuid, dto: UpdateRecurringDto) -> ApiResult {
+        let id = EventId::from_uuid(id);
+        let geo = dto.geo.map(|geo| geo.map(|g| g.point()).transpose()).transpose()?;
+        let status = dto.status.as_deref().map(str::parse::<EventStatus>).transpose()?;
+        let transparency = dto.transparency.as_deref().map(str::parse::<Transparency>).transpose()?;
+
+        if let Some(location) = dto.location {
+            UpdateRecurringLocationHandler::new(self.recurring(), self.calendars())
+                .handle(UpdateRecurringLocationCommand::new(id, location))
+                .await?;
+        }
+
+        if let Some(url) = dto.url {
+            UpdateRecurringUrlHandler::new(self.recurring(), self.calendars())
+                .handle(UpdateRecurringUrlCommand::new(id, url))
+                .await?;
+        }
+
+        if let Some(geo) = geo {
+            UpdateRecurringGeoHandler::new(self.recurring(), self.calendars())
+                .handle(UpdateRecurringGeoCommand::new(id, geo))
+                .await?;
+        }
+
+        if let Some(status) = status {
+            UpdateRecurringStatusHandler::new(self.recurring(), self.calendars())
+                .handle(UpdateRecurringStatusCommand::new(id, status))
+                .await?;
+        }
+
+        if let Some(transparency) = transparency {
+            UpdateRecurringTransparencyHandler::new(self.recurring(), self.calendars())
+                .handle(UpdateRecurringTransparencyCommand::new(id, transparency))
+                .await?;
+        }
+
+        self.get_recurring(id.as_uuid()).await
+    }
+
+    async fn cancel_recurring(&self, id: Uuid, cancel: bool) -> ApiResult {
+        let id = EventId::from_uuid(id);
+
+        if cancel {
+            CancelRecurringEventHandler::new(self.recurring(), self.calendars())
+                .handle(CancelRecurringEventCommand::new(id))
+                .await?;
+        } else {
+            RestoreRecurringEventHandler::new(self.recurring(), self.calendars())
+                .handle(RestoreRecurringEventCommand::new(id))
+                .await?;
+        }
+
+        Ok(ApiResponse::no_content())
+    }
+
+    async fn add_recurring_tag(&self, id: Uuid, dto: TagNameDto) -> ApiResult {
+        AddRecurringTagHandler::new(self.recurring(), self.calendars())
+            .handle(AddRecurringTagCommand::new(EventId::from_uuid(id), dto.tag()?))
+            .await?;
+        Ok(ApiResponse::no_content())
+    }
+
+    async fn remove_recurring_tag(&self, id: Uuid, tag: Tag) -> ApiResult {
+        RemoveRecurringTagHandler::new(self.recurring(), self.calendars())
+            .handle(RemoveRecurringTagCommand::new(EventId::from_uuid(id), tag))
+            .await?;
+        Ok(ApiResponse::no_content())
+    }
+
+    async fn delete_recurring(&self, id: Uuid) -> ApiResult {
+        DeleteRecurringEventHandler::new(self.recurring(), self.calendars())
+            .handle(DeleteRecurringEventCommand::new(EventId::from_uuid(id)))
+            .await?;
+        Ok(ApiResponse::no_content())
+    }
+
+    async fn list_occurrences(&self, id: Uuid, query: &HashMap<String, String>) -> ApiResult {
+        let range = query_range(query)?
+            .ok_or_else(|| ApiError::BadRequest("`from` and `to` are required".to_string()))?;
+
+        let event = self
+            .recurring()
+            .find_by_id(&EventId::from_uuid(id))
+            .await
+            .map_err(ApplicationError::from_recurring_lookup)?;
+
+        let dtos: Vec<OccurrenceDto> = event
+            .occurrences_in(&range)
+            .iter()
+            .map(OccurrenceDto::from)
+            .collect();
+        Ok(ApiResponse::ok(&dtos))
+    }
+
+    async fn add_exception(&self, id: Uuid, dto: OccurrenceExceptionDto) -> ApiResult {
+        let id = EventId::from_uuid(id);
+
+        match (dto.starts_at, dto.ends_at) {
+            (None, None) => {
+                CancelRecurringOccurrenceHandler::new(self.recurring(), self.calendars())
+                    .handle(CancelRecurringOccurrenceCommand::new(id, dto.original_starts_at))
+                    .await?;
+            }
+            (Some(starts_at), Some(ends_at)) => {
+                let command = RescheduleRecurringOccurrenceCommand::new(
+                    id,
+                    dto.original_starts_at,
+                    TimeRange::new(starts_at, ends_at)?,
+                );
+                RescheduleRecurringOccurrenceHandler::new(self.recurring(), self.calendars())
+                    .handle(command)
+                    .await?;
+            }
+            _ => {
+                return Err(ApiError::BadRequest(
+                    "`starts_at` and `ends_at` must be given together".to_string(),
+                ));
+            }
+        }
+
+        Ok(ApiResponse::no_content())
+    }
+
+    async fn remove_exception(&self, id: Uuid, original_starts_at: DateTime<Utc>) -> ApiResult {
+        RestoreRecurringOccurrenceHandler::new(self.recurring(), self.calendars())
+            .handle(RestoreRecurringOccurrenceCommand::new(
+                EventId::from_uuid(id),
+                original_starts_at,
+            ))
+            .await?;
+        Ok(ApiResponse::no_content())
+    }
+
+    async fn set_occurrence_location(&self, id: Uuid, dto: OccurrenceLocationDto) -> ApiResult {
+        SetRecurringOccurrenceLocationHandler::new(self.recurring(), self.calendars())
+            .handle(SetRecurringOccurrenceLocationCommand::new(
+                EventId::from_uuid(id),
+                dto.original_starts_at,
+                dto.location,
+            ))
+            .await?;
+        Ok(ApiResponse::no_content())
+    }
+
+    async fn add_recurring_reminder(&self, id: Uuid, dto: CreateReminderDto) -> ApiResult {
+        let command = AddRecurringReminderCommand::new(
+            EventId::from_uuid(id),
+            dto.trigger()?,
+            dto.action()?,
+            dto.description.clone(),
+            dto.repeat,
+            dto.repeat_interval()?,
+        );
+
+        let reminder_id = AddRecurringReminderHandler::new(self.recurring(), self.calendars())
+            .handle(command)
+            .await?;
+        Ok(ApiResponse::created(reminder_id.as_uuid()))
+    }
+
+    async fn remove_recurring_reminder(&self, id: Uuid, reminder_id: Uuid) -> ApiResult {
+        RemoveRecurringReminderHandler::new(self.recurring(), self.calendars())
+            .handle(RemoveRecurringReminderCommand::new(
+                EventId::from_uuid(id),
+                ReminderId::from_uuid(reminder_id),
+            ))
+            .await?;
+        Ok(ApiResponse::no_content())
+    }
+
+    async fn set_recurring_organizer(&self, id: Uuid, dto: SetOrganizerDto) -> ApiResult {
+        SetRecurringOrganizerHandler::new(self.recurring(), self.calendars())
+            .handle(SetRecurringOrganizerCommand::new(EventId::from_uuid(id), dto.organizer()?))
+            .await?;
+        Ok(ApiResponse::no_content())
+    }
+
+    async fn add_recurring_attendee(&self, id: Uuid, dto: CreateAttendeeDto) -> ApiResult {
+        AddRecurringAttendeeHandler::new(self.recurring(), self.calendars())
+            .handle(AddRecurringAttendeeCommand::new(EventId::from_uuid(id), dto.attendee()?))
+            .await?;
+        Ok(ApiResponse::no_content())
+    }
+
+    async fn remove_recurring_attendee(&self, id: Uuid, email: String) -> ApiResult {
+        RemoveRecurringAttendeeHandler::new(self.recurring(), self.calendars())
+            .handle(RemoveRecurringAttendeeCommand::new(EventId::from_uuid(id), email))
+            .await?;
+        Ok(ApiResponse::no_content())
+    }
+
+    async fn update_recurring_attendee_status(&self, id: Uuid, email: String, dto: AttendeeStatusDto) -> ApiResult {
+        let command = UpdateRecurringAttendeeStatusCommand::new(
+            EventId::from_uuid(id),
+            email,
+            dto.status.parse()?,
+        );
+
+        UpdateRecurringAttendeeStatusHandler::new(self.recurring(), self.calendars())
+            .handle(command)
+            .await?;
+        Ok(ApiResponse::no_content())
+    }
+
+    async fn ensure_calendar(&self, calendar_id: &CalendarId) -> Result<(), ApiError> {
+        self.calendars()
+            .find_by_id(calendar_id)
+            .await?
+            .ok_or(ApplicationError::CalendarNotFound)?;
+        Ok(())
+    }
+
+    // ==================================================
+    // Reminder instances
+    // ==================================================
+
+    async fn snooze_reminder(&self, id: Uuid, reminder_id: Uuid, dto: SnoozeReminderDto) -> ApiResult {
+        let command = SnoozeReminderCommand::new(
+            EventId::from_uuid(id),
+            ReminderId::from_uuid(reminder_id),
+            dto.until()?,
+        )
+        .with_occurrence(dto.original_starts_at);
+
+        let state = SnoozeReminderHandler::new(self.events(), self.recurring(), self.reminder_states())
+            .handle(command)
+            .await?;
+        Ok(ApiResponse::ok(&ReminderStateDto::from(&state)))
+    }
+
+    async fn dismiss_reminder(&self, id: Uuid, reminder_id: Uuid, dto: DismissReminderDto) -> ApiResult {
+        let command = DismissReminderCommand::new(
+            EventId::from_uuid(id),
+            ReminderId::from_uuid(reminder_id),
+        )
+        .with_occurrence(dto.original_starts_at);
+
+        let state = DismissReminderHandler::new(self.events(), self.recurring(), self.reminder_states())
+            .handle(command)
+            .await?;
+        Ok(ApiResponse::ok(&ReminderStateDto::from(&state)))
+    }
+
+    // ==================================================
+    // Tasks
+    // ==================================================
+
+    async fn list_tasks(&self, id: Uuid, query: &HashMap<String, String>) -> ApiResult {
+        let tasks = self.find_tasks(id, query).await?;
+        let dtos: Vec<TaskDto> = tasks.iter().map(TaskDto::from).collect();
+        Ok(ApiResponse::ok(&dtos))
+    }
+
+    async fn get_task(&self, id: Uuid) -> ApiResult {
+        let task = self
+            .tasks()
+            .find_by_id(&TaskId::from_uuid(id))
+            .await?
+            .ok_or(ApplicationError::TaskNotFound)?;
+        Ok(ApiResponse::ok(&TaskDto::from(&task)))
+    }
+
+    async fn create_task(&self, id: Uuid, dto: CreateTaskDto) -> ApiResult {
+        let rule = dto.recurrence.as_ref().map(RecurrenceDto::rule).transpose()?;
+        let command = CreateTaskCommand::new(
+            CalendarId::from_uuid(id),
+            dto.title,
+            dto.description,
+            dto.starts_at,
+            dto.due_at,
+        )
+        .with_priority(dto.priority)
+        .with_rule(rule);
+
+        let task_id = CreateTaskHandler::new(self.tasks(), self.calendars())
+            .handle(command)
+            .await?;
+        Ok(ApiResponse::created(task_id.as_uuid()))
+    }
+
+    async fn update_task(&self, id: Uuid, dto: UpdateTaskDto) -> ApiResult {
+        let mut command = UpdateTaskCommand::new(TaskId::from_uuid(id));
+
+        if let Some(title) = dto.title {
+            command = command.with_title(title);
+        }
+        if let Some(description) = dto.description {
+            command = command.with_description(description);
+        }
+        if let Some(starts_at) = dto.starts_at {
+            command = command.with_starts_at(starts_at);
+        }
+        if let Some(due_at) = dto.due_at {
+            command = command.with_due_at(due_at);
+        }
+        if let Some(priority) = dto.priority {
+            command = command.with_priority(priority);
+        }
+        if let Some(percent_complete) = dto.percent_complete {
+            command = command.with_percent_complete(percent_complete);
+        }
+        if let Some(recurrence) = dto.recurrence {
+            command = command.with_rule(recurrence.as_ref().map(RecurrenceDto::rule).transpose()?);
+        }
+
+        UpdateTaskHandler::new(self.tasks(), self.calendars())
+            .handle(command)
+            .await?;
+
+        self.get_task(id).await
+    }
+
+    async fn delete_task(&self, id: Uuid) -> ApiResult {
+        DeleteTaskHandler::new(self.tasks(), self.calendars())
+            .handle(DeleteTaskCommand::new(TaskId::from_uuid(id)))
+            .await?;
+        Ok(ApiResponse::no_content())
+    }
+
+    async fn complete_task(&self, id: Uuid) -> ApiResult {
+        let task = CompleteTaskHandler::new(self.tasks(), self.calendars())
+            .handle(CompleteTaskCommand::new(TaskId::from_uuid(id)))
+            .await?;
+        Ok(ApiResponse::ok(&TaskDto::from(&task)))
+    }
+
+    async fn reopen_task(&self, id: Uuid) -> ApiResult {
+        ReopenTaskHandler::new(self.tasks(), self.calendars())
+            .handle(ReopenTaskCommand::new(TaskId::from_uuid(id)))
+            .await?;
+        self.get_task(id).await
+    }
+
+    async fn import_tasks(&self, id: Uuid, dto: ImportTasksDto) -> ApiResult {
+        let calendar_id = CalendarId::from_uuid(id);
+        let calendar = Component::parse(&dto.ics)?;
+        let (tasks, skipped) = IcalMapper::tasks_to_domain(&calendar, calendar_id);
+
+        let imported = ImportTasksHandler::new(self.tasks(), self.calendars())
+            .handle(ImportTasksCommand::new(calendar_id, tasks))
+            .await?;
+
+        Ok(ApiResponse::ok(&ImportReportDto {
+            imported,
+            skipped: skipped
+                .into_iter()
+                .map(|(uid, e)| SkippedDto { uid, reason: e.to_string() })
+                .collect(),
+        }))
+    }
+
+    /// The calendar's tasks as a `.ics` document, in a JSON string.
+    async fn export_tasks(&self, id: Uuid, query: &HashMap<String, String>) -> ApiResult {
+        let tasks = self.find_tasks(id, query).await?;
+        Ok(ApiResponse::ok(&IcalMapper::tasks_to_ics(&tasks)))
+    }
+
+    /// `?open=true` keeps only tasks that still need doing.
+    async fn find_tasks(&self, id: Uuid, query: &HashMap<String, String>) -> Result<Vec<Task>, ApiError> {
+        let calendar_id = CalendarId::from_uuid(id);
+        self.ensure_calendar(&calendar_id).await?;
+
+        let mut tasks = self.tasks().find_by_calendar(&calendar_id).await?;
+        if query.get("open").is_some_and(|value| value == "true") {
+            tasks.retain(Task::is_open);
+        }
+
+        Ok(tasks)
+    }
+
+
+    // ==================================================
+    // Journal
+    // ==================================================
+
+    async fn list_journal(&self, id: Uuid, query: &HashMap<String, String>) -> ApiResult {
+        let entries = self.find_journal(id, query).await?;
+        let dtos: Vec<JournalEntryDto> = entries.iter().map(JournalEntryDto::from).collect();
+        Ok(ApiResponse::ok(&dtos))
+    }
+
+    async fn get_journal_entry(&self, id: Uuid) -> ApiResult {
+        let entry = self
+            .journal()
+            .find_by_id(&JournalId::from_uuid(id))
+            .await?
+            .ok_or(ApplicationError::JournalEntryNotFound)?;
+        Ok(ApiResponse::ok(&JournalEntryDto::from(&entry)))
+    }
+
+    async fn create_journal_entry(&self, id: Uuid, dto: CreateJournalEntryDto) -> ApiResult {
+        let command = CreateJournalEntryCommand::new(
+            CalendarId::from_uuid(id),
+            dto.date,
+            dto.title,
+            dto.body,
+        )
+        .with_link(dto.link.as_ref().map(JournalLinkDto::link));
+
+        let journal_id = CreateJournalEntryHandler::new(self.journal(), self.events(), self.recurring(), self.calendars())
+            .handle(command)
+            .await?;
+        Ok(ApiResponse::created(journal_id.as_uuid()))
+    }
+
+    async fn update_journal_entry(&self, id: Uuid, dto: UpdateJournalEntryDto) -> ApiResult {
+        let mut command = UpdateJournalEntryCommand::new(JournalId::from_uuid(id));
+
+        if let Some(date) = dto.date {
+            command = command.with_date(date);
+        }
+        if let Some(title) = dto.title {
+            command = command.with_title(title);
+        }
+        if let Some(body) = dto.body {
+            command = command.with_body(body);
+        }
+        if let Some(link) = dto.link {
+            command = command.with_link(link.as_ref().map(JournalLinkDto::link));
+        }
+
+        UpdateJournalEntryHandler::new(self.journal(), self.events(), self.recurring(), self.calendars())
+            .handle(command)
+            .await?;
+        self.get_journal_entry(id).await
+    }
+
+    async fn delete_journal_entry(&self, id: Uuid) -> ApiResult {
+        DeleteJournalEntryHandler::new(self.journal(), self.calendars())
+            .handle(DeleteJournalEntryCommand::new(JournalId::from_uuid(id)))
+            .await?;
+        Ok(ApiResponse::no_content())
+    }
+
+    async fn journal_for_event(&self, id: Uuid) -> ApiResult {
+        let entries = self.journal().find_by_event(&EventId::from_uuid(id)).await?;
+        let dtos: Vec<JournalEntryDto> = entries.iter().map(JournalEntryDto::from).collect();
+        Ok(ApiResponse::ok(&dtos))
+    }
+
+    /// `?q=` is required; `?calendar_id=` narrows the search to one calendar.
+    async fn search_journal(&self, query: &HashMap<String, String>) -> ApiResult {
+        let text = query_text(query, "q")
+            .ok_or_else(|| ApiError::BadRequest("`q` is required".to_string()))?;
+        let calendar_id = query
+            .get("calendar_id")
+            .map(|id| parse_id(id).map(CalendarId::from_uuid))
+            .transpose()?;
+        if let Some(calendar_id) = &calendar_id {
+            self.ensure_calendar(calendar_id).await?;
+        }
+
+        let entries = self.journal().search(&text, calendar_id.as_ref()).await?;
+        let dtos: Vec<JournalEntryDto> = entries.iter().map(JournalEntryDto::from).collect();
+        Ok(ApiResponse::ok(&dtos))
+    }
+
+    async fn import_journal(&self, id: Uuid, dto: ImportJournalDto) -> ApiResult {
+        let calendar_id = CalendarId::from_uuid(id);
+        let calendar = Component::parse(&dto.ics)?;
+        let (entries, skipped) = IcalMapper::journal_to_domain(&calendar, calendar_id);
+
+        let imported = ImportJournalEntriesHandler::new(
+            self.journal(),
+            self.events(),
+            self.recurring(),
+            self.calendars(),
+        )
+        .handle(ImportJournalEntriesCommand::new(calendar_id, entries))
+        .await?;
+
+        Ok(ApiResponse::ok(&ImportReportDto {
+            imported,
+            skipped: skipped
+                .into_iter()
+                .map(|(uid, e)| SkippedDto { uid, reason: e.to_string() })
+                .collect(),
+        }))
+    }
+
+    /// The calendar's journal as a `.ics` document, in a JSON string.
+    async fn export_journal(&self, id: Uuid, query: &HashMap<String, String>) -> ApiResult {
+        let entries = self.find_journal(id, query).await?;
+        Ok(ApiResponse::ok(&IcalMapper::journal_to_ics(&entries)))
+    }
+
+    /// `?from=` and `?to=` (dates, either optional) bound the entries' dates.
+    async fn find_journal(&self, id: Uuid, query: &HashMap<String, String>) -> Result<Vec<JournalEntry>, ApiError> {
+        let calendar_id = CalendarId::from_uuid(id);
+        self.ensure_calendar(&calendar_id).await?;
+
+        let from = query_date(query, "from")?;
+        let to = query_date(query, "to")?;
+        Ok(self.journal().find_by_calendar(&calendar_id, from, to).await?)
+    }
+
+    // ==================================================
+    // Tags
+    // ==================================================
+
+    async fn list_tags(&self) -> ApiResult {
+        let tags = self.tags().find_all().await?;
+        let dtos: Vec<TagDto> = tags.iter().map(TagDto::from).collect();
+        Ok(ApiResponse::ok(&dtos))
+    }
+
+    async fn rename_tag(&self, dto: RetagDto) -> ApiResult {
+        let (from, to) = dto.tags()?;
+        let outcome = RenameTagHandler::new(
+            self.events(),
+            self.recurring(),
+            self.tags(),
+            self.calendars(),
+        )
+        .handle(RenameTagCommand::new(from, to))
+        .await?;
+        Ok(ApiResponse::ok(&RetagOutcomeDto::from(outcome)))
+    }
+
+    async fn merge_tag(&self, dto: RetagDto) -> ApiResult {
+        let (from, into) = dto.tags()?;
+        let outcome = MergeTagHandler::new(
+            self.events(),
+            self.recurring(),
+            self.tags(),
+            self.calendars(),
+        )
+        .handle(MergeTagCommand::new(from, into))
+        .await?;
+        Ok(ApiResponse::ok(&RetagOutcomeDto::from(outcome)))
+    }
+
+
+    // ==================================================
+    // Trash
+    // ==================================================
+
+    async fn list_trash(&self) -> ApiResult {
+        let mut dtos: Vec<TrashedItemDto> = Vec::new();
+        dtos.extend(self.calendars().find_trashed().await?.iter().map(TrashedItemDto::from));
+        dtos.extend(self.events().find_trashed().await?.iter().map(TrashedItemDto::from));
+        dtos.extend(self.recurring().find_trashed().await?.iter().map(TrashedItemDto::from));
+        dtos.sort_by(|a, b| b.deleted_at.cmp(&a.deleted_at).then_with(|| a.title.cmp(&b.title)));
+        Ok(ApiResponse::ok(&dtos))
+    }
+
+    /// Ids are unique across kinds, so the first kind that has the id in
+    /// its trash is the one restored.
+    async fn restore_trashed(&self, id: Uuid) -> ApiResult {
+        let restored = RestoreTrashedCalendarHandler::new(self.calendars())
+            .handle(RestoreTrashedCalendarCommand::new(CalendarId::from_uuid(id)))
+            .await;
+        let restored = match restored {
+            Err(ApplicationError::NotInTrash) => {
+                RestoreTrashedEventHandler::new(self.events(), self.calendars())
+                    .handle(RestoreTrashedEventCommand::new(EventId::from_uuid(id)))
+                    .await
+            }
+            other => other,
+        };
+        let restored = match restored {
+            Err(ApplicationError::NotInTrash) => {
+                RestoreTrashedRecurringEventHandler::new(self.recurring(), self.calendars())
+                    .handle(RestoreTrashedRecurringEventCommand::new(EventId::from_uuid(id)))
+                    .await
+            }
+            other => other,
+        };
+
+        restored?;
+        Ok(ApiResponse::no_content())
+    }
+
+    /// Purges what was trashed before `before`, or everything when unset.
+    async fn purge_trash(&self, query: &HashMap<String, String>) -> ApiResult {
+        let cutoff = match query.get("before") {
+            Some(before) => parse_datetime(before)?,
+            None => Utc::now(),
+        };
+
+        let outcome = PurgeTrashHandler::new(
+            self.scope.unrecorded(self.uow.calendars()),
+            self.scope.unrecorded(self.uow.events()),
+            self.scope.unrecorded(self.uow.recurring()),
+        )
+        .handle(PurgeTrashCommand::new(cutoff))
+        .await?;
+        Ok(ApiResponse::ok(&PurgeOutcomeDto::from(outcome)))
+    }
+
+
+    // ==================================================
+    // History
+    // ==================================================
+
+    async fn list_history(&self, query: &HashMap<String, String>) -> ApiResult {
+        let limit = query_limit(query)?.unwrap_or(DEFAULT_HISTORY_LIMIT);
+        let entries = self.history().find_applied(limit).await?;
+        let dtos: Vec<HistoryEntryDto> = entries.iter().map(HistoryEntryDto::from).collect();
+        Ok(ApiResponse::ok(&dtos))
+    }
+
+    // Undo and redo replay through repositories that are not recorded, so
+    // they move the history cursor instead of adding entries; what they
+    // change is still published
+
+    async fn undo(&self) -> ApiResult {
+        let entry = UndoHandler::new(
+            self.scope.unrecorded(self.uow.calendars()),
+            self.scope.unrecorded(self.uow.events()),
+            self.scope.unrecorded(self.uow.recurring()),
+            self.uow.tasks(),
+            self.uow.journal(),
+            self.history(),
+        )
+        .handle(UndoCommand)
+        .await?;
+        Ok(ApiResponse::ok(&HistoryEntryDto::from(&entry)))
+    }
+
+    async fn redo(&self) -> ApiResult {
+        let entry = RedoHandler::new(
+            self.scope.unrecorded(self.uow.calendars()),
+            self.scope.unrecorded(self.uow.events()),
+            self.scope.unrecorded(self.uow.recurring()),
+            self.uow.tasks(),
+            self.uow.journal(),
+            self.history(),
+        )
+        .handle(RedoCommand)
+        .await?;
+        Ok(ApiResponse::ok(&HistoryEntryDto::from(&entry)))
+    }
+
+
+    // ==================================================
+    // Audit log
+    // ==================================================
+
+    // Entries outlive what they describe, so an unknown or deleted id
+    // is not an error
+
+    async fn event_audit(&self, id: Uuid, query: &HashMap<String, String>) -> ApiResult {
+        let limit = query_limit(query)?.unwrap_or(DEFAULT_AUDIT_LIMIT);
+        let entries = self.audit().find_by_event(&EventId::from_uuid(id), limit).await?;
+        let dtos: Vec<AuditEntryDto> = entries.iter().map(AuditEntryDto::from).collect();
+        Ok(ApiResponse::ok(&dtos))
+    }
+
+    async fn calendar_audit(&self, id: Uuid, query: &HashMap<String, String>) -> ApiResult {
+        let limit = query_limit(query)?.unwrap_or(DEFAULT_AUDIT_LIMIT);
+        let entries = self.audit().find_by_calendar(&CalendarId::from_uuid(id), limit).await?;
+        let dtos: Vec<AuditEntryDto> = entries.iter().map(AuditEntryDto::from).collect();
+        Ok(ApiResponse::ok(&dtos))
+    }
+
+
+    // ==================================================
+    // Scheduling
+    // ==================================================
+
+    async fn itip_message(&self, id: Uuid) -> ApiResult {
+        let object = self.find_object(EventId::from_uuid(id)).await?;
+        Ok(ApiResponse::ok(&ItipMessageDto::from(&ItipMessage::for_object(&object)?)))
+    }
+
+    async fn apply_reply(&self, dto: ItipReplyDto) -> ApiResult {
+        let reply = ItipReply::parse(&dto.ics)?;
+        let command = ApplyReplyCommand::new(
+            *reply.event_id(),
+            reply.attendee().clone(),
+            *reply.status(),
+            *reply.sequence(),
+        );
+
+        let outcome = ApplyReplyHandler::new(self.events(), self.recurring(), self.calendars())
+            .handle(command)
+            .await?;
+        Ok(ApiResponse::ok(&ReplyOutcomeDto::from(outcome)))
+    }
+
+    async fn find_object(&self, id: EventId) -> Result<CalendarObject, ApiError> {
+        if let Some(event) = self.events().find_by_id(&id).await? {
+            return Ok(CalendarObject::Event(event));
+        }
+
+        match self.recurring().find_by_id(&id).await {
+            Ok(series) => Ok(CalendarObject::Recurring(series)),
+            Err(RepositoryError::NotFound) => Err(ApplicationError::EventNotFound.into()),
+            Err(e) => Err(e.into()),
+        }
+    }
+}
+
+
+// ======================================================
+// Helpers
+// ======================================================
+
+/// The methods a known path accepts, for the `Allow` header of a 405.
+/// Kept in step with `route`.
+fn allowed_methods(segments: &[&str]) -> Option<&'static str> {
+    let allow = match segments {
+        ["calendars"] => "GET, POST",
+        ["calendars", _] => "GET, PATCH, DELETE",
+        ["calendars", _, "archive" | "unarchive"] => "POST",
+        ["calendars", _, "freebusy" | "audit"] => "GET",
+        ["subscriptions"] => "POST",
+        ["calendars", _, "events" | "recurring" | "tasks" | "journal"] => "GET, POST",
+        ["calendars", _, "tasks" | "journal", "import"] => "POST",
+        ["calendars", _, "tasks" | "journal", "export"] => "GET",
+
+        ["events" | "recurring" | "tasks", _] => "GET, PATCH, DELETE",
+        ["events" | "recurring", _, "cancel" | "restore" | "reminders" | "attendees" | "tags"] => "POST",
+        ["recurring", _, "exceptions" | "locations"] => "POST",
+        ["events" | "recurring", _, "organizer"] => "PUT",
+        ["events" | "recurring", _, "journal" | "audit" | "itip"] => "GET",
+        ["recurring", _, "occurrences"] => "GET",
+        ["events" | "recurring", _, "reminders" | "tags", _] => "DELETE",
+        ["recurring", _, "exceptions", _] => "DELETE",
+        ["events" | "recurring", _, "attendees", _] => "PATCH, DELETE",
+        ["events" | "recurring", _, "reminders", _, "snooze" | "dismiss"] => "POST",
+        ["tasks", _, "complete" | "reopen"] => "POST",
+
+        ["journal", "search"] => "GET",
+        ["journal", _] => "GET, PATCH, DELETE",
+        ["search" | "tags" | "history"] => "GET",
+        ["tags", "rename" | "merge"] => "POST",
+        ["trash"] => "GET, DELETE",
+        ["trash", _, "restore"] => "POST",
+        ["history", "undo" | "redo"] => "POST",
+        ["itip", "replies"] => "POST",
+        _ => return None,
+    };
+
+    Some(allow)
+}
+
+fn parse_body<T: DeserializeOwned>(body: &str) -> Result<T, ApiError> {
+    serde_json::from_str(body).map_err(|e| ApiError::BadRequest(e.to_string()))
+}
+
+fn parse_id(segment: &str) -> Result<Uuid, ApiError> {
+    Uuid::parse_str(segment).map_err(|_| ApiError::BadRequest(format!("invalid id: {segment}")))
+}
+
+fn parse_datetime(value: &str) -> Result<DateTime<Utc>, ApiError> {
+    DateTime::parse_from_rfc3339(&percent_decode(value))
+        .map(|dt| dt.with_timezone(&Utc))
+        .map_err(|_| ApiError::BadRequest(format!("invalid RFC 3339 date-time: {value}")))
+}
+
+/// Reads the optional `from`/`to` window; both or neither must be given.
+fn query_range(query: &HashMap<String, String>) -> Result<Option<TimeRange>, ApiError> {
+    match (query.get("from"), query.get("to")) {
+        (None, None) => Ok(None),
+        (Some(from), Some(to)) => Ok(Some(TimeRange::new(
+            parse_datetime(from)?,
+            parse_datetime(to)?,
+        )?)),
+        _ => Err(ApiError::BadRequest("`from` and `to` must be given together".to_string())),
+    }
+}
+
+fn query_date(query: &HashMap<String, String>, key: &str) -> Result<Option<NaiveDate>, ApiError> {
+    query
+        .get(key)
+        .map(|value| {
+            NaiveDate::parse_from_str(value, "%Y-%m-%d")
+                .map_err(|_| ApiError::BadRequest(format!("invalid date for `{key}`: {value}")))
+        })
+        .transpose()
+}
+
+fn query_limit(query: &HashMap<String, String>) -> Result<Option<u32>, ApiError> {
+    query
+        .get("limit")
+        .map(|limit| {
+            limit
+                .parse()
+                .map_err(|_| ApiError::BadRequest(format!("invalid limit: {limit}")))
+        })
+        .transpose()
+}
+
+/// A decoded, non-empty text parameter; `+` stands for a space.
+fn query_text(query: &HashMap<String, String>, key: &str) -> Option<String> {
+    query
+        .get(key)
+        .map(|value| percent_decode(&value.replace('+', " ")))
+        .filter(|value| !value.is_empty())
+}
+
+fn parse_tag(segment: &str) -> Result<Tag, ApiError> {
+    Ok(Tag::new(percent_decode(segment))?)
+}
+
+/// Reads `tags_all`, `tags_any` and `tags_none`, each a comma-separated list.
+fn query_tag_filter(query: &HashMap<String, String>) -> Result<TagFilter, ApiError> {
+    let list = |key: &str| -> Result<Vec<Tag>, ApiError> {
+        let names: Vec<String> = query_text(query, key)
+            .map(|value| value.split(',').map(str::to_string).collect())
+            .unwrap_or_default();
+        Ok(parse_tags(&names)?)
+    };
+
+    Ok(TagFilter::new(list("tags_all")?, list("tags_any")?, list("tags_none")?))
+}
+
+fn parse_query(query: &str) -> HashMap<String, String> {
+    query
+        .split('&')
+        .filter_map(|pair| pair.split_once('='))
+        .map(|(k, v)| (percent_decode(k), v.to_string()))
+        .collect()
+}
+
+fn percent_decode(value: &str) -> String {
+    let bytes = value.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+
+    while i < bytes.len() {
+        match bytes[i] {
+            b'%' => {
+                if let Some(byte) = value
+                    .get(i + 1..i + 3)
+                    .and_then(|hex| u8::from_str_radix(hex, 16).ok())
+                {
+                    out.push(byte);
+                    i += 3;
+                    continue;
+                }
+                out.push(b'%');
+            }
+            byte => out.push(byte),
+        }
+        i += 1;
+    }
+
+    String::from_utf8_lossy(&out).into_owned()
+}
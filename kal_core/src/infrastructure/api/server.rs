@@ -0,0 +1,158 @@
+use std::{convert::Infallible, sync::Arc};
+
+use http_body_util::{BodyExt, Full, LengthLimitError, Limited};
+use hyper::{
+    body::{Bytes, Incoming},
+    header::{ALLOW, CONTENT_TYPE, HeaderValue},
+    server::conn::http1,
+    service::service_fn,
+    Request,
+    Response,
+    StatusCode,
+};
+use hyper_util::rt::TokioIo;
+use sqlx::SqlitePool;
+use tokio::net::TcpListener;
+
+use crate::application::publisher::Publisher;
+
+use super::{
+    error::ApiError,
+    routes::{ApiResponse, ApiRoutes},
+};
+
+/// Largest request body accepted unless configured otherwise.
+pub const DEFAULT_MAX_BODY_BYTES: usize = 1024 * 1024;
+
+/// Names the user a request's changes are attributed to, once the server
+/// is told to trust it. The API does not authenticate anyone itself, so
+/// only a proxy that does, and sets or strips this header, can vouch for
+/// it; otherwise requests are the publisher's actor's.
+pub const ACTOR_HEADER: &str = "x-kal-user";
+
+/// Serves the REST API over HTTP/1.1.
+pub struct ApiServer {
+    routes: ApiRoutes,
+    max_body_bytes: usize,
+    trust_actor_header: bool,
+}
+
+impl ApiServer {
+    pub fn new(pool: SqlitePool) -> Self {
+        Self {
+            routes: ApiRoutes::new(pool),
+            max_body_bytes: DEFAULT_MAX_BODY_BYTES,
+            trust_actor_header: false,
+        }
+    }
+
+    /// Refuses request bodies larger than `bytes` with 413.
+    pub fn with_max_body_bytes(mut self, bytes: usize) -> Self {
+        self.max_body_bytes = bytes;
+        self
+    }
+
+    /// Attributes requests to the user their `ACTOR_HEADER` names. Only
+    /// for a server every request reaches through an authenticating
+    /// proxy, as anyone else could name whoever they like.
+    pub fn trust_actor_header(mut self) -> Self {
+        self.trust_actor_header = true;
+        self
+    }
+
+    /// Publishes the domain events requests raise to `publisher`'s subscribers.
+    pub fn with_publisher(mut self, publisher: Publisher) -> Self {
+        self.routes = self.routes.with_publisher(publisher);
+        self
+    }
+
+    /// Accepts connections until the listener fails.
+    pub async fn serve(self, listener: TcpListener) -> std::io::Result<()> {
+        let server = Arc::new(self);
+
+        loop {
+            let (stream, _) = listener.accept().await?;
+            let server = Arc::clone(&server);
+
+            tokio::spawn(async move {
+                let service = service_fn(move |request| {
+                    let server = Arc::clone(&server);
+                    async move { Ok::<_, Infallible>(server.respond(request).await) }
+                });
+
+                // A client dropping its connection is not a server error
+                let _ = http1::Builder::new()
+                    .serve_connection(TokioIo::new(stream), service)
+                    .await;
+            });
+        }
+    }
+
+    async fn respond(&self, request: Request<Incoming>) -> Response<Full<Bytes>> {
+        match self.dispatch(request).await {
+            Ok(response) => into_http(response),
+            Err(error) => error_into_http(&error),
+        }
+    }
+
+    async fn dispatch(&self, request: Request<Incoming>) -> Result<ApiResponse, ApiError> {
+        let method = request.method().as_str().to_string();
+        let path = request.uri().path().to_string();
+        let query = request.uri().query().unwrap_or_default().to_string();
+        let actor = request
+            .headers()
+            .get(ACTOR_HEADER)
+            .filter(|_| self.trust_actor_header)
+            .and_then(|value| value.to_str().ok())
+            .map(str::trim)
+            .filter(|actor| !actor.is_empty())
+            .map(str::to_string);
+
+        let limit = self.max_body_bytes;
+        let body = Limited::new(request.into_body(), limit)
+            .collect()
+            .await
+            .map_err(|e| match e.downcast_ref::<LengthLimitError>() {
+                Some(_) => ApiError::PayloadTooLarge { limit },
+                None => ApiError::BadRequest(e.to_string()),
+            })?
+            .to_bytes();
+        let body = std::str::from_utf8(&body)
+            .map_err(|_| ApiError::BadRequest("body is not UTF-8".to_string()))?;
+
+        match actor {
+            Some(actor) => {
+                let routes = self.routes.clone().with_actor(actor);
+                routes.handle(&method, &path, &query, body).await
+            }
+            None => self.routes.handle(&method, &path, &query, body).await,
+        }
+    }
+}
+
+fn into_http(response: ApiResponse) -> Response<Full<Bytes>> {
+    let has_body = response.body.is_some();
+    let mut http = Response::new(Full::new(Bytes::from(response.body.unwrap_or_default())));
+    *http.status_mut() = StatusCode::from_u16(response.status)
+        .unwrap_or(StatusCode::INTERNAL_SERVER_ERROR);
+
+    if has_body {
+        http.headers_mut()
+            .insert(CONTENT_TYPE, HeaderValue::from_static("application/json"));
+    }
+
+    http
+}
+
+fn error_into_http(error: &ApiError) -> Response<Full<Bytes>> {
+    let mut http = into_http(ApiResponse {
+        status: error.status(),
+        body: Some(serde_json::to_string(&error.to_dto()).expect("error DTOs always serialize")),
+    });
+
+    if let ApiError::MethodNotAllowed { allow } = error {
+        http.headers_mut().insert(ALLOW, HeaderValue::from_static(allow));
+    }
+
+    http
+}
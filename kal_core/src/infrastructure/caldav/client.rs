@@ -0,0 +1,322 @@
+use reqwest::{header, Client, Method, RequestBuilder, StatusCode, Url};
+
+use super::{
+    error::CalDavError,
+    xml::{self, DavResponse, MultiStatus},
+};
+
+type CalDavResult<T> = Result<T, CalDavError>;
+
+/// A calendar collection found on the server.
+#[derive(Debug, Clone)]
+pub struct RemoteCalendar {
+    pub href: String,
+    pub display_name: Option<String>,
+    pub description: Option<String>,
+    pub ctag: Option<String>,
+    pub sync_token: Option<String>,
+}
+
+/// A calendar object resource as stored on the server.
+#[derive(Debug, Clone)]
+pub struct RemoteObject {
+    pub href: String,
+    pub etag: Option<String>,
+    pub data: String,
+}
+
+/// Result of a `sync-collection` REPORT.
+#[derive(Debug, Clone, Default)]
+pub struct SyncDelta {
+    pub sync_token: Option<String>,
+    pub changed: Vec<(String, Option<String>)>,
+    pub removed: Vec<String>,
+}
+
+#[derive(Debug, Clone)]
+struct Credentials {
+    username: String,
+    password: String,
+}
+
+#[derive(Debug, Clone)]
+pub struct CalDavClient {
+    http: Client,
+    base_url: Url,
+    credentials: Option<Credentials>,
+}
+
+impl CalDavClient {
+    pub fn new(base_url: &str) -> CalDavResult<Self> {
+        let base_url = Url::parse(base_url)
+            .map_err(|e| CalDavError::InvalidUrl(e.to_string()))?;
+
+        Ok(Self {
+            http: Client::new(),
+            base_url,
+            credentials: None,
+        })
+    }
+
+    pub fn with_credentials(
+        mut self,
+        username: impl Into<String>,
+        password: impl Into<String>,
+    ) -> Self {
+        self.credentials = Some(Credentials {
+            username: username.into(),
+            password: password.into(),
+        });
+        self
+    }
+
+    pub fn base_url(&self) -> &Url {
+        &self.base_url
+    }
+
+    /// Finds every calendar collection that can hold VEVENTs, following
+    /// `current-user-principal` and `calendar-home-set` (RFC 6764, 4791).
+    pub async fn discover_calendars(&self) -> CalDavResult<Vec<RemoteCalendar>> {
+        let root = self.base_url.path().to_string();
+
+        let principal = self
+            .propfind(&root, "0", xml::PROPFIND_PRINCIPAL)
+            .await?
+            .responses
+            .into_iter()
+            .find_map(|r| r.current_user_principal)
+            .unwrap_or(root.clone());
+
+        let home = self
+            .propfind(&principal, "0", xml::PROPFIND_HOME_SET)
+            .await?
+            .responses
+            .into_iter()
+            .find_map(|r| r.calendar_home_set)
+            .unwrap_or(principal);
+
+        let calendars = self
+            .propfind(&home, "1", xml::PROPFIND_CALENDARS)
+            .await?
+            .responses
+            .into_iter()
+            .filter(|r| r.is_calendar())
+            .filter(|r| {
+                r.supported_components.is_empty()
+                    || r.supported_components.iter().any(|c| c == "VEVENT")
+            })
+            .map(|r| RemoteCalendar {
+                href: r.href,
+                display_name: r.display_name,
+                description: r.description,
+                ctag: r.ctag,
+                sync_token: r.sync_token,
+            })
+            .collect();
+
+        Ok(calendars)
+    }
+
+    /// Runs a `sync-collection` REPORT (RFC 6578). Passing `None` performs
+    /// an initial sync that lists every member of the collection.
+    pub async fn sync_collection(
+        &self,
+        calendar_href: &str,
+        sync_token: Option<&str>,
+    ) -> CalDavResult<SyncDelta> {
+        let response = self
+            .request(Method::from_bytes(b"REPORT").expect("valid method"), calendar_href)?
+            .header("Depth", "1")
+            .header(header::CONTENT_TYPE, "application/xml; charset=utf-8")
+            .body(xml::sync_collection(sync_token))
+            .send()
+            .await?;
+
+        let status = response.status();
+        if sync_token.is_some()
+            && (status == StatusCode::FORBIDDEN || status == StatusCode::CONFLICT)
+        {
+            return Err(CalDavError::InvalidSyncToken);
+        }
+
+        let multistatus = self.multistatus(calendar_href, response).await?;
+
+        let mut delta = SyncDelta {
+            sync_token: multistatus.sync_token,
+            ..SyncDelta::default()
+        };
+
+        for r in multistatus.responses {
+            if r.href.trim_end_matches('/') == calendar_href.trim_end_matches('/') {
+                continue;
+            }
+            if r.status == Some(404) {
+                delta.removed.push(r.href);
+            } else {
+                delta.changed.push((r.href, r.etag));
+            }
+        }
+
+        Ok(delta)
+    }
+
+    /// Fetches the given members with a `calendar-multiget` REPORT.
+    pub async fn multiget(
+        &self,
+        calendar_href: &str,
+        hrefs: &[String],
+    ) -> CalDavResult<Vec<RemoteObject>> {
+        if hrefs.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let response = self
+            .request(Method::from_bytes(b"REPORT").expect("valid method"), calendar_href)?
+            .header("Depth", "1")
+            .header(header::CONTENT_TYPE, "application/xml; charset=utf-8")
+            .body(xml::calendar_multiget(hrefs.iter().map(String::as_str)))
+            .send()
+            .await?;
+
+        let objects = self
+            .multistatus(calendar_href, response)
+            .await?
+            .responses
+            .into_iter()
+            .filter_map(|r: DavResponse| {
+                r.calendar_data.map(|data| RemoteObject {
+                    href: r.href,
+                    etag: r.etag,
+                    data,
+                })
+            })
+            .collect();
+
+        Ok(objects)
+    }
+
+    pub async fn get(&self, href: &str) -> CalDavResult<RemoteObject> {
+        let response = self.request(Method::GET, href)?.send().await?;
+        let response = check_status(href, response)?;
+        let etag = etag_header(&response);
+
+        Ok(RemoteObject {
+            href: href.to_string(),
+            etag,
+            data: response.text().await?,
+        })
+    }
+
+    /// Uploads an object. With `etag` the write only succeeds if the
+    /// server copy is unchanged; without it the write only succeeds if
+    /// the resource does not exist yet. Returns the new ETag if the
+    /// server reported one.
+    pub async fn put(
+        &self,
+        href: &str,
+        data: String,
+        etag: Option<&str>,
+    ) -> CalDavResult<Option<String>> {
+        let mut request = self
+            .request(Method::PUT, href)?
+            .header(header::CONTENT_TYPE, "text/calendar; charset=utf-8")
+            .body(data);
+
+        request = match etag {
+            Some(etag) => request.header(header::IF_MATCH, etag),
+            None => request.header(header::IF_NONE_MATCH, "*"),
+        };
+
+        let response = request.send().await?;
+        let response = check_status(href, response)?;
+
+        Ok(etag_header(&response))
+    }
+
+    pub async fn delete(&self, href: &str, etag: Option<&str>) -> CalDavResult<()> {
+        let mut request = self.request(Method::DELETE, href)?;
+
+        if let Some(etag) = etag {
+            request = request.header(header::IF_MATCH, etag);
+        }
+
+        let response = request.send().await?;
+        if response.status() == StatusCode::NOT_FOUND {
+            return Ok(());
+        }
+        check_status(href, response)?;
+
+        Ok(())
+    }
+
+    pub fn resolve(&self, href: &str) -> CalDavResult<Url> {
+        self.base_url
+            .join(href)
+            .map_err(|e| CalDavError::InvalidUrl(e.to_string()))
+    }
+
+    async fn propfind(
+        &self,
+        href: &str,
+        depth: &str,
+        body: &'static str,
+    ) -> CalDavResult<MultiStatus> {
+        let response = self
+            .request(Method::from_bytes(b"PROPFIND").expect("valid method"), href)?
+            .header("Depth", depth)
+            .header(header::CONTENT_TYPE, "application/xml; charset=utf-8")
+            .body(body)
+            .send()
+            .await?;
+
+        self.multistatus(href, response).await
+    }
+
+    async fn multistatus(
+        &self,
+        href: &str,
+        response: reqwest::Response,
+    ) -> CalDavResult<MultiStatus> {
+        let response = check_status(href, response)?;
+        let body = response.text().await?;
+        xml::parse_multistatus(&body)
+    }
+
+    fn request(&self, method: Method, href: &str) -> CalDavResult<RequestBuilder> {
+        let url = self.resolve(href)?;
+        let request = self.http.request(method, url);
+
+        Ok(match &self.credentials {
+            Some(c) => request.basic_auth(&c.username, Some(&c.password)),
+            None => request,
+        })
+    }
+}
+
+fn check_status(
+    href: &str,
+    response: reqwest::Response,
+) -> CalDavResult<reqwest::Response> {
+    let status = response.status();
+
+    if status == StatusCode::PRECONDITION_FAILED {
+        return Err(CalDavError::PreconditionFailed(href.to_string()));
+    }
+
+    if !status.is_success() {
+        return Err(CalDavError::Status {
+            status: status.as_u16(),
+            href: href.to_string(),
+        });
+    }
+
+    Ok(response)
+}
+
+fn etag_header(response: &reqwest::Response) -> Option<String> {
+    response
+        .headers()
+        .get(header::ETAG)
+        .and_then(|v| v.to_str().ok())
+        .map(str::to_string)
+}
@@ -0,0 +1,48 @@
+use thiserror::Error;
+
+use crate::{
+    domain::{error::DomainError, repository::RepositoryError},
+    infrastructure::ical::IcalError,
+};
+
+#[derive(Debug, Error)]
+pub enum CalDavError {
+    #[error("HTTP error: {0}")]
+    Http(String),
+
+    #[error("Unexpected status {status} for {href}")]
+    Status { status: u16, href: String },
+
+    #[error("Precondition failed for {0}: resource changed on the server")]
+    PreconditionFailed(String),
+
+    #[error("Sync token is no longer valid")]
+    InvalidSyncToken,
+
+    #[error("Invalid URL: {0}")]
+    InvalidUrl(String),
+
+    #[error("XML error: {0}")]
+    Xml(String),
+
+    #[error("Discovery failed: {0}")]
+    Discovery(String),
+
+    #[error("Calendar {0} is not linked to a remote collection")]
+    NotLinked(String),
+
+    #[error(transparent)]
+    Domain(#[from] DomainError),
+
+    #[error(transparent)]
+    Ical(#[from] IcalError),
+
+    #[error(transparent)]
+    Repository(#[from] RepositoryError),
+}
+
+impl From<reqwest::Error> for CalDavError {
+    fn from(error: reqwest::Error) -> Self {
+        CalDavError::Http(error.to_string())
+    }
+}
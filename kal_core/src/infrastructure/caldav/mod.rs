@@ -0,0 +1,8 @@
+pub mod client;
+pub mod sync;
+pub mod xml;
+pub mod error;
+
+pub use client::{CalDavClient, RemoteCalendar, RemoteObject, SyncDelta};
+pub use sync::{CalDavSynchronizer, SyncReport};
+pub use error::CalDavError;
@@ -0,0 +1,576 @@
+use std::collections::{HashMap, HashSet};
+
+use uuid::Uuid;
+
+use crate::{
+    domain::{
+        calendar::Calendar,
+        calendar_object::CalendarObject,
+        repository::{
+            CalendarRepository,
+            EventRepository,
+            RecurringEventRepository,
+            RepositoryError,
+            SyncStateRepository,
+        },
+        sync::{
+            Conflict,
+            ConflictReport,
+            ConflictResolver,
+            Resolution,
+            SyncCollection,
+            SyncItem,
+            SyncItemKind,
+            Tombstone,
+        },
+        value_objects::{CalendarId, EventId},
+    },
+    infrastructure::ical::{mappers::text_value, Component, IcalMapper},
+};
+
+use super::{
+    client::{CalDavClient, RemoteCalendar, RemoteObject},
+    error::CalDavError,
+};
+
+type CalDavResult<T> = Result<T, CalDavError>;
+
+#[derive(Debug, Clone, Default)]
+pub struct SyncReport {
+    pub pulled: usize,
+    pub pushed: usize,
+    pub deleted_local: usize,
+    pub deleted_remote: usize,
+    pub skipped: Vec<(String, String)>,
+    pub conflicts: ConflictReport,
+}
+
+/// A parsed remote resource.
+struct Fetched {
+    href: String,
+    etag: Option<String>,
+    uid: Option<String>,
+    object: CalendarObject,
+}
+
+pub struct CalDavSynchronizer<C, E, R, S>
+where
+    C: CalendarRepository,
+    E: EventRepository,
+    R: RecurringEventRepository,
+    S: SyncStateRepository,
+{
+    client: CalDavClient,
+    calendars: C,
+    events: E,
+    recurring: R,
+    state: S,
+}
+
+impl<C, E, R, S> CalDavSynchronizer<C, E, R, S>
+where
+    C: CalendarRepository,
+    E: EventRepository,
+    R: RecurringEventRepository,
+    S: SyncStateRepository,
+{
+    pub fn new(client: CalDavClient, calendars: C, events: E, recurring: R, state: S) -> Self {
+        Self {
+            client,
+            calendars,
+            events,
+            recurring,
+            state,
+        }
+    }
+
+    pub fn client(&self) -> &CalDavClient {
+        &self.client
+    }
+
+    /// Local calendars are keyed by a name-based UUID of the collection
+    /// URL so that repeated discovery maps onto the same `Calendar`.
+    pub fn calendar_id_for(&self, remote: &RemoteCalendar) -> CalDavResult<CalendarId> {
+        let url = self.client.resolve(&remote.href)?;
+        Ok(CalendarId::from_uuid(Uuid::new_v5(
+            &Uuid::NAMESPACE_URL,
+            url.as_str().as_bytes(),
+        )))
+    }
+
+    /// Creates the local calendar for a remote collection if missing and
+    /// links the two.
+    pub async fn import_calendar(&self, remote: &RemoteCalendar) -> CalDavResult<CalendarId> {
+        let calendar_id = self.calendar_id_for(remote)?;
+
+        if self.calendars.find_by_id(&calendar_id).await?.is_none() {
+            let name = remote
+                .display_name
+                .clone()
+                .filter(|n| !n.is_empty())
+                .unwrap_or_else(|| collection_name(&remote.href));
+            let now = chrono::Utc::now();
+
+            let calendar = Calendar::with_id(
+                calendar_id,
+                name,
+                remote.description.clone(),
+                false,
+                None,
+                now,
+                now,
+            )?;
+
+            self.calendars.save(&calendar).await?;
+        }
+
+        self.link(calendar_id, &remote.href).await?;
+
+        Ok(calendar_id)
+    }
+
+    /// Links an existing local calendar to a remote collection. Everything
+    /// already in the calendar is pushed on the next sync.
+    pub async fn link(&self, calendar_id: CalendarId, remote_href: &str) -> CalDavResult<()> {
+        if self.state.find_collection(&calendar_id).await?.is_none() {
+            let collection = SyncCollection::new(calendar_id, remote_href.to_string());
+            self.state.save_collection(&collection).await?;
+        }
+        Ok(())
+    }
+
+    /// Two-way sync of one linked calendar: pulls remote changes since the
+    /// last sync token, pushes dirty items and tombstones, and settles
+    /// items changed on both sides with `resolver`.
+    pub async fn sync(
+        &self,
+        calendar_id: &CalendarId,
+        resolver: &ConflictResolver,
+    ) -> CalDavResult<SyncReport> {
+        let mut collection = self
+            .state
+            .find_collection(calendar_id)
+            .await?
+            .ok_or_else(|| CalDavError::NotLinked(calendar_id.to_string()))?;
+
+        // A trashed calendar keeps its link but is left out of sync, so
+        // nothing in it is deleted remotely
+        if self.calendars.find_by_id(calendar_id).await?.is_none() {
+            return Err(CalDavError::NotLinked(calendar_id.to_string()));
+        }
+
+        let mut report = SyncReport::default();
+        let mut handled: HashSet<EventId> = HashSet::new();
+
+        let tombstones: HashMap<EventId, Tombstone> = self
+            .state
+            .find_tombstones(calendar_id)
+            .await?
+            .into_iter()
+            .map(|t| (*t.item_id(), t))
+            .collect();
+
+        // Pull
+
+        let href = collection.remote_href().clone();
+        let delta = match self
+            .client
+            .sync_collection(&href, collection.sync_token().as_deref())
+            .await
+        {
+            Err(CalDavError::InvalidSyncToken) => {
+                collection.reset_token();
+                self.client.sync_collection(&href, None).await?
+            }
+            other => other?,
+        };
+
+        let mut stale = Vec::new();
+        for (member, etag) in &delta.changed {
+            let known = self.state.find_item_by_href(calendar_id, member).await?;
+            if etag.is_none() || known.and_then(|i| i.etag().clone()) != *etag {
+                stale.push(member.clone());
+            }
+        }
+
+        for remote in self.client.multiget(&href, &stale).await? {
+            let fetched = match self.parse(*calendar_id, remote) {
+                Ok(fetched) => fetched,
+                Err((member, reason)) => {
+                    report.skipped.push((member, reason));
+                    continue;
+                }
+            };
+
+            let item_id = *fetched.object.event_id();
+            handled.insert(item_id);
+
+            let item = self.item_for(calendar_id, &fetched).await?;
+            let local = self.load_local(&item_id).await?;
+
+            let conflict = if let Some(tombstone) = tombstones.get(&item_id) {
+                Some(Conflict {
+                    item_id,
+                    local: None,
+                    local_deleted_at: Some(*tombstone.deleted_at()),
+                    remote: Some(fetched.object.clone()),
+                })
+            } else {
+                match &local {
+                    Some(l) if item.has_local_changes(&IcalMapper::content_hash(l)) => {
+                        Some(Conflict {
+                            item_id,
+                            local: local.clone(),
+                            local_deleted_at: None,
+                            remote: Some(fetched.object.clone()),
+                        })
+                    }
+                    _ => None,
+                }
+            };
+
+            match conflict {
+                Some(conflict) => {
+                    let resolved = resolver.resolve(&conflict);
+                    self.apply_resolution(
+                        &resolved.resolution,
+                        item,
+                        local,
+                        Some(&fetched),
+                        &mut report,
+                    )
+                    .await?;
+                    report.conflicts.push(resolved);
+                }
+                None => {
+                    self.apply_remote(item, &fetched).await?;
+                    report.pulled += 1;
+                }
+            }
+        }
+
+        for member in &delta.removed {
+            let Some(item) = self.state.find_item_by_href(calendar_id, member).await? else {
+                continue;
+            };
+            let item_id = *item.item_id();
+            handled.insert(item_id);
+
+            let local = self.load_local(&item_id).await?;
+            let changed = local
+                .as_ref()
+                .is_some_and(|l| item.has_local_changes(&IcalMapper::content_hash(l)));
+
+            if changed {
+                let conflict = Conflict {
+                    item_id,
+                    local: local.clone(),
+                    local_deleted_at: None,
+                    remote: None,
+                };
+                let resolved = resolver.resolve(&conflict);
+                self.apply_resolution(&resolved.resolution, item, local, None, &mut report)
+                    .await?;
+                report.conflicts.push(resolved);
+            } else {
+                self.forget(&item_id).await?;
+                report.deleted_local += 1;
+            }
+        }
+
+        // Push
+
+        for item in self.state.find_items(calendar_id).await? {
+            let item_id = *item.item_id();
+            if !item.is_dirty() || handled.contains(&item_id) {
+                continue;
+            }
+
+            let Some(local) = self.load_local(&item_id).await? else {
+                continue;
+            };
+
+            match self.upload(&collection, item.clone(), &local).await {
+                Ok(()) => report.pushed += 1,
+                Err(CalDavError::PreconditionFailed(member)) => {
+                    let fetched = self.fetch(*calendar_id, &member).await?;
+                    let conflict = Conflict {
+                        item_id,
+                        local: Some(local.clone()),
+                        local_deleted_at: None,
+                        remote: fetched.as_ref().map(|f| f.object.clone()),
+                    };
+                    let resolved = resolver.resolve(&conflict);
+                    self.apply_resolution(
+                        &resolved.resolution,
+                        item,
+                        Some(local),
+                        fetched.as_ref(),
+                        &mut report,
+                    )
+                    .await?;
+                    report.conflicts.push(resolved);
+                }
+                Err(e) => return Err(e),
+            }
+        }
+
+        for (item_id, tombstone) in &tombstones {
+            if handled.contains(item_id) {
+                continue;
+            }
+
+            match self
+                .client
+                .delete(tombstone.remote_href(), tombstone.etag().as_deref())
+                .await
+            {
+                Ok(()) => {
+                    self.state.delete_tombstone(item_id).await?;
+                    report.deleted_remote += 1;
+                }
+                Err(CalDavError::PreconditionFailed(member)) => {
+                    let fetched = self.fetch(*calendar_id, &member).await?;
+                    let conflict = Conflict {
+                        item_id: *item_id,
+                        local: None,
+                        local_deleted_at: Some(*tombstone.deleted_at()),
+                        remote: fetched.as_ref().map(|f| f.object.clone()),
+                    };
+                    let resolved = resolver.resolve(&conflict);
+                    let item = match &fetched {
+                        Some(f) => self.item_for(calendar_id, f).await?,
+                        None => SyncItem::new(*item_id, *calendar_id, *tombstone.kind()),
+                    };
+                    self.apply_resolution(
+                        &resolved.resolution,
+                        item,
+                        None,
+                        fetched.as_ref(),
+                        &mut report,
+                    )
+                    .await?;
+                    report.conflicts.push(resolved);
+                }
+                Err(e) => return Err(e),
+            }
+        }
+
+        collection.mark_synced(delta.sync_token);
+        self.state.save_collection(&collection).await?;
+
+        Ok(report)
+    }
+
+    async fn apply_resolution(
+        &self,
+        resolution: &Resolution,
+        item: SyncItem,
+        local: Option<CalendarObject>,
+        remote: Option<&Fetched>,
+        report: &mut SyncReport,
+    ) -> CalDavResult<()> {
+        let item_id = *item.item_id();
+
+        match resolution {
+            Resolution::KeepLocal => {
+                if let Some(local) = local {
+                    let mut item = item;
+                    match remote {
+                        Some(remote) => {
+                            item.link(remote.href.clone(), remote.uid.clone());
+                            item.set_etag(remote.etag.clone());
+                        }
+                        // The server copy is gone; upload as a new resource.
+                        None => item.set_etag(None),
+                    }
+                    let collection = self
+                        .state
+                        .find_collection(item.calendar_id())
+                        .await?
+                        .ok_or_else(|| CalDavError::NotLinked(item.calendar_id().to_string()))?;
+                    self.upload(&collection, item, &local).await?;
+                    report.pushed += 1;
+                }
+            }
+            Resolution::KeepRemote => {
+                if let Some(remote) = remote {
+                    self.state.delete_tombstone(&item_id).await?;
+                    self.apply_remote(item, remote).await?;
+                    report.pulled += 1;
+                }
+            }
+            Resolution::KeepBoth { copy_id } => {
+                if let Some(local) = &local {
+                    // Saving the copy marks it dirty; it is pushed as new.
+                    self.save_local(&local.duplicate(*copy_id)?).await?;
+                }
+                if let Some(remote) = remote {
+                    self.apply_remote(item, remote).await?;
+                    report.pulled += 1;
+                }
+            }
+            Resolution::DeleteLocal => {
+                self.forget(&item_id).await?;
+                report.deleted_local += 1;
+            }
+            Resolution::DeleteRemote => {
+                if let Some(remote) = remote {
+                    self.client.delete(&remote.href, remote.etag.as_deref()).await?;
+                }
+                self.state.delete_tombstone(&item_id).await?;
+                self.state.delete_item(&item_id).await?;
+                report.deleted_remote += 1;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Writes a remote object locally and records it as in sync.
+    async fn apply_remote(&self, mut item: SyncItem, fetched: &Fetched) -> CalDavResult<()> {
+        let kind = SyncItemKind::of(&fetched.object);
+
+        if *item.kind() != kind {
+            self.delete_local(item.item_id()).await?;
+        }
+
+        // The remote copy wins, so it replaces whatever version is stored
+        let object = match self.load_local(fetched.object.event_id()).await? {
+            Some(local) => fetched.object.clone().with_version(local.version()),
+            None => fetched.object.clone(),
+        };
+        self.save_local(&object).await?;
+
+        item.link(fetched.href.clone(), fetched.uid.clone());
+        item.mark_synced(kind, fetched.etag.clone(), IcalMapper::content_hash(&fetched.object));
+        self.state.save_item(&item).await?;
+
+        Ok(())
+    }
+
+    async fn upload(
+        &self,
+        collection: &SyncCollection,
+        mut item: SyncItem,
+        local: &CalendarObject,
+    ) -> CalDavResult<()> {
+        let member = item
+            .remote_href()
+            .clone()
+            .unwrap_or_else(|| object_href(collection.remote_href(), item.item_id()));
+        let data = IcalMapper::to_ics_with_uid(local, item.remote_uid().as_deref());
+
+        let etag = self
+            .client
+            .put(&member, data, item.etag().as_deref())
+            .await?;
+
+        item.link(member, None);
+        item.mark_synced(SyncItemKind::of(local), etag, IcalMapper::content_hash(local));
+        self.state.save_item(&item).await?;
+
+        Ok(())
+    }
+
+    async fn item_for(
+        &self,
+        calendar_id: &CalendarId,
+        fetched: &Fetched,
+    ) -> CalDavResult<SyncItem> {
+        let item_id = fetched.object.event_id();
+
+        Ok(match self.state.find_item(item_id).await? {
+            Some(item) => item,
+            None => SyncItem::new(*item_id, *calendar_id, SyncItemKind::of(&fetched.object)),
+        })
+    }
+
+    async fn fetch(&self, calendar_id: CalendarId, member: &str) -> CalDavResult<Option<Fetched>> {
+        match self.client.get(member).await {
+            Ok(remote) => Ok(self.parse(calendar_id, remote).ok()),
+            Err(CalDavError::Status { status: 404, .. }) => Ok(None),
+            Err(e) => Err(e),
+        }
+    }
+
+    fn parse(
+        &self,
+        calendar_id: CalendarId,
+        remote: RemoteObject,
+    ) -> Result<Fetched, (String, String)> {
+        let component = Component::parse(&remote.data)
+            .map_err(|e| (remote.href.clone(), e.to_string()))?;
+
+        let uid = component
+            .components_named("VEVENT")
+            .next()
+            .and_then(|vevent| text_value(vevent, "UID"));
+
+        let object = IcalMapper::to_domain(&component, calendar_id)
+            .map_err(|e| (remote.href.clone(), e.to_string()))?
+            .into_iter()
+            .next()
+            .ok_or_else(|| (remote.href.clone(), "no VEVENT in resource".to_string()))?;
+
+        Ok(Fetched {
+            href: remote.href,
+            etag: remote.etag,
+            uid,
+            object,
+        })
+    }
+
+    async fn load_local(&self, item_id: &EventId) -> CalDavResult<Option<CalendarObject>> {
+        if let Some(event) = self.events.find_by_id(item_id).await? {
+            return Ok(Some(CalendarObject::Event(event)));
+        }
+
+        match self.recurring.find_by_id(item_id).await {
+            Ok(event) => Ok(Some(CalendarObject::Recurring(event))),
+            Err(RepositoryError::NotFound) => Ok(None),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    async fn save_local(&self, object: &CalendarObject) -> CalDavResult<()> {
+        match object {
+            CalendarObject::Event(event) => self.events.save(event).await?,
+            CalendarObject::Recurring(event) => self.recurring.save(event).await?,
+        };
+        Ok(())
+    }
+
+    /// Deletes the local copy along with its sync bookkeeping, so the
+    /// deletion is not pushed back.
+    async fn forget(&self, item_id: &EventId) -> CalDavResult<()> {
+        self.delete_local(item_id).await?;
+        self.state.delete_tombstone(item_id).await?;
+        self.state.delete_item(item_id).await?;
+        Ok(())
+    }
+
+    async fn delete_local(&self, event_id: &EventId) -> CalDavResult<()> {
+        match self.events.delete(event_id).await {
+            Ok(()) => Ok(()),
+            Err(RepositoryError::NotFound) => match self.recurring.delete(event_id).await {
+                Ok(()) | Err(RepositoryError::NotFound) => Ok(()),
+                Err(e) => Err(e.into()),
+            },
+            Err(e) => Err(e.into()),
+        }
+    }
+}
+
+pub fn object_href(calendar_href: &str, event_id: &EventId) -> String {
+    format!("{}/{}.ics", calendar_href.trim_end_matches('/'), event_id)
+}
+
+fn collection_name(href: &str) -> String {
+    href.trim_end_matches('/')
+        .rsplit('/')
+        .next()
+        .filter(|s| !s.is_empty())
+        .unwrap_or("CalDAV")
+        .to_string()
+}
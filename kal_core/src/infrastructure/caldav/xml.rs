@@ -0,0 +1,264 @@
+use quick_xml::{events::Event as XmlEvent, Reader};
+
+use super::error::CalDavError;
+
+pub const PROPFIND_PRINCIPAL: &str = r#"<?xml version="1.0" encoding="utf-8"?>
+<d:propfind xmlns:d="DAV:">
+  <d:prop>
+    <d:current-user-principal/>
+  </d:prop>
+</d:propfind>"#;
+
+pub const PROPFIND_HOME_SET: &str = r#"<?xml version="1.0" encoding="utf-8"?>
+<d:propfind xmlns:d="DAV:" xmlns:c="urn:ietf:params:xml:ns:caldav">
+  <d:prop>
+    <c:calendar-home-set/>
+  </d:prop>
+</d:propfind>"#;
+
+pub const PROPFIND_CALENDARS: &str = r#"<?xml version="1.0" encoding="utf-8"?>
+<d:propfind xmlns:d="DAV:" xmlns:c="urn:ietf:params:xml:ns:caldav" xmlns:cs="http://calendarserver.org/ns/">
+  <d:prop>
+    <d:resourcetype/>
+    <d:displayname/>
+    <c:calendar-description/>
+    <c:supported-calendar-component-set/>
+    <cs:getctag/>
+    <d:sync-token/>
+  </d:prop>
+</d:propfind>"#;
+
+pub fn sync_collection(sync_token: Option<&str>) -> String {
+    format!(
+        r#"<?xml version="1.0" encoding="utf-8"?>
+<d:sync-collection xmlns:d="DAV:">
+  <d:sync-token>{}</d:sync-token>
+  <d:sync-level>1</d:sync-level>
+  <d:prop>
+    <d:getetag/>
+  </d:prop>
+</d:sync-collection>"#,
+        escape(sync_token.unwrap_or_default()),
+    )
+}
+
+pub fn calendar_multiget<'a>(hrefs: impl IntoIterator<Item = &'a str>) -> String {
+    let hrefs: String = hrefs
+        .into_iter()
+        .map(|href| format!("  <d:href>{}</d:href>\n", escape(href)))
+        .collect();
+
+    format!(
+        r#"<?xml version="1.0" encoding="utf-8"?>
+<c:calendar-multiget xmlns:d="DAV:" xmlns:c="urn:ietf:params:xml:ns:caldav">
+  <d:prop>
+    <d:getetag/>
+    <c:calendar-data/>
+  </d:prop>
+{hrefs}</c:calendar-multiget>"#
+    )
+}
+
+/// One `<d:response>` of a multistatus body. Only properties reported
+/// with a 2xx propstat status are filled in.
+#[derive(Debug, Clone, Default)]
+pub struct DavResponse {
+    pub href: String,
+    pub status: Option<u16>,
+    pub etag: Option<String>,
+    pub display_name: Option<String>,
+    pub description: Option<String>,
+    pub resource_types: Vec<String>,
+    pub supported_components: Vec<String>,
+    pub calendar_data: Option<String>,
+    pub ctag: Option<String>,
+    pub sync_token: Option<String>,
+    pub current_user_principal: Option<String>,
+    pub calendar_home_set: Option<String>,
+}
+
+impl DavResponse {
+    pub fn is_calendar(&self) -> bool {
+        self.resource_types.iter().any(|t| t == "calendar")
+    }
+
+    fn merge_props(&mut self, props: DavResponse) {
+        self.etag = props.etag.or(self.etag.take());
+        self.display_name = props.display_name.or(self.display_name.take());
+        self.description = props.description.or(self.description.take());
+        self.resource_types.extend(props.resource_types);
+        self.supported_components.extend(props.supported_components);
+        self.calendar_data = props.calendar_data.or(self.calendar_data.take());
+        self.ctag = props.ctag.or(self.ctag.take());
+        self.sync_token = props.sync_token.or(self.sync_token.take());
+        self.current_user_principal = props
+            .current_user_principal
+            .or(self.current_user_principal.take());
+        self.calendar_home_set = props
+            .calendar_home_set
+            .or(self.calendar_home_set.take());
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct MultiStatus {
+    pub responses: Vec<DavResponse>,
+    pub sync_token: Option<String>,
+}
+
+pub fn parse_multistatus(body: &str) -> Result<MultiStatus, CalDavError> {
+    let mut reader = Reader::from_str(body);
+    reader.config_mut().trim_text(true);
+
+    let mut result = MultiStatus::default();
+    let mut path: Vec<String> = Vec::new();
+    let mut response: Option<DavResponse> = None;
+    let mut props: Option<DavResponse> = None;
+    let mut propstat_status: Option<u16> = None;
+
+    loop {
+        let event = reader
+            .read_event()
+            .map_err(|e| CalDavError::Xml(e.to_string()))?;
+
+        match event {
+            XmlEvent::Start(e) => {
+                let name = local_name(e.local_name().as_ref());
+                match name.as_str() {
+                    "response" => response = Some(DavResponse::default()),
+                    "propstat" => {
+                        props = Some(DavResponse::default());
+                        propstat_status = None;
+                    }
+                    _ => {}
+                }
+                path.push(name);
+            }
+            XmlEvent::Empty(e) => {
+                let name = local_name(e.local_name().as_ref());
+                let parent = path.last().map(String::as_str);
+
+                if let Some(props) = props.as_mut() {
+                    match parent {
+                        Some("resourcetype") => props.resource_types.push(name),
+                        Some("supported-calendar-component-set") if name == "comp" => {
+                            let comp = e
+                                .try_get_attribute("name")
+                                .ok()
+                                .flatten()
+                                .map(|a| String::from_utf8_lossy(&a.value).to_uppercase());
+                            if let Some(comp) = comp {
+                                props.supported_components.push(comp);
+                            }
+                        }
+                        _ => {}
+                    }
+                }
+            }
+            XmlEvent::Text(t) => {
+                let text = t
+                    .unescape()
+                    .map_err(|e| CalDavError::Xml(e.to_string()))?
+                    .into_owned();
+                assign_text(&path, text, &mut result, &mut response, &mut props, &mut propstat_status);
+            }
+            XmlEvent::CData(c) => {
+                let text = String::from_utf8_lossy(&c.into_inner()).into_owned();
+                assign_text(&path, text, &mut result, &mut response, &mut props, &mut propstat_status);
+            }
+            XmlEvent::End(_) => {
+                match path.pop().as_deref() {
+                    Some("propstat") => {
+                        if let (Some(r), Some(p)) = (response.as_mut(), props.take())
+                            && propstat_status.is_none_or(|s| (200..300).contains(&s))
+                        {
+                            r.merge_props(p);
+                        }
+                    }
+                    Some("response") => {
+                        if let Some(r) = response.take() {
+                            result.responses.push(r);
+                        }
+                    }
+                    _ => {}
+                }
+            }
+            XmlEvent::Eof => break,
+            _ => {}
+        }
+    }
+
+    Ok(result)
+}
+
+fn assign_text(
+    path: &[String],
+    text: String,
+    result: &mut MultiStatus,
+    response: &mut Option<DavResponse>,
+    props: &mut Option<DavResponse>,
+    propstat_status: &mut Option<u16>,
+) {
+    let current = path.last().map(String::as_str);
+    let parent = path
+        .len()
+        .checked_sub(2)
+        .and_then(|i| path.get(i))
+        .map(String::as_str);
+
+    match (parent, current) {
+        (Some("multistatus"), Some("sync-token")) => result.sync_token = Some(text),
+        (Some("response"), Some("href")) => {
+            if let Some(r) = response.as_mut() {
+                r.href = text;
+            }
+        }
+        (Some("response"), Some("status")) => {
+            if let Some(r) = response.as_mut() {
+                r.status = parse_status(&text);
+            }
+        }
+        (Some("propstat"), Some("status")) => *propstat_status = parse_status(&text),
+        (Some("current-user-principal"), Some("href")) => {
+            if let Some(p) = props.as_mut() {
+                p.current_user_principal = Some(text);
+            }
+        }
+        (Some("calendar-home-set"), Some("href")) => {
+            if let Some(p) = props.as_mut() {
+                p.calendar_home_set = Some(text);
+            }
+        }
+        (_, Some(name)) => {
+            if let Some(p) = props.as_mut() {
+                match name {
+                    "getetag" => p.etag = Some(text),
+                    "displayname" => p.display_name = Some(text),
+                    "calendar-description" => p.description = Some(text),
+                    "calendar-data" => p.calendar_data = Some(text),
+                    "getctag" => p.ctag = Some(text),
+                    "sync-token" => p.sync_token = Some(text),
+                    _ => {}
+                }
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Extracts the code from a `HTTP/1.1 200 OK` status line.
+fn parse_status(line: &str) -> Option<u16> {
+    line.split_whitespace().nth(1)?.parse().ok()
+}
+
+fn local_name(name: &[u8]) -> String {
+    String::from_utf8_lossy(name).to_ascii_lowercase()
+}
+
+pub fn escape(value: &str) -> String {
+    value
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
@@ -0,0 +1,87 @@
+use thiserror::Error;
+
+use crate::{
+    application::error::ApplicationError,
+    domain::{error::DomainError, repository::RepositoryError},
+    infrastructure::ical::IcalError,
+};
+
+#[derive(Debug, Error)]
+pub enum DavError {
+    #[error("Not found")]
+    NotFound,
+
+    #[error("Method not allowed")]
+    MethodNotAllowed,
+
+    #[error("Authentication required")]
+    Unauthorized,
+
+    #[error("Forbidden: {0}")]
+    Forbidden(String),
+
+    #[error("Conflict: {0}")]
+    Conflict(String),
+
+    #[error("Precondition failed")]
+    PreconditionFailed,
+
+    #[error("Bad request: {0}")]
+    BadRequest(String),
+
+    #[error("Unsupported calendar data: {0}")]
+    UnsupportedData(String),
+
+    #[error(transparent)]
+    Application(#[from] ApplicationError),
+
+    #[error(transparent)]
+    Repository(#[from] RepositoryError),
+}
+
+impl DavError {
+    pub fn status(&self) -> u16 {
+        match self {
+            DavError::NotFound => 404,
+            DavError::MethodNotAllowed => 405,
+            DavError::Unauthorized => 401,
+            DavError::Forbidden(_) => 403,
+            DavError::Conflict(_) => 409,
+            DavError::PreconditionFailed => 412,
+            DavError::BadRequest(_) => 400,
+            DavError::UnsupportedData(_) => 415,
+            DavError::Application(e) => match e {
+                ApplicationError::CalendarNotFound => 409,
+                ApplicationError::EventNotFound
+                | ApplicationError::RecurringEventNotFound
+                | ApplicationError::TaskNotFound
+                | ApplicationError::JournalEntryNotFound
+                | ApplicationError::NotInTrash => 404,
+                ApplicationError::Domain(
+                    DomainError::SubscriptionReadOnly | DomainError::CalendarArchived,
+                ) => 403,
+                ApplicationError::Domain(_) | ApplicationError::Validation(_) => 400,
+                ApplicationError::Conflict
+                | ApplicationError::NothingToUndo
+                | ApplicationError::NothingToRedo
+                | ApplicationError::CalendarPurged(..) => 409,
+                ApplicationError::Repository(_) => 500,
+            },
+            DavError::Repository(RepositoryError::NotFound) => 404,
+            DavError::Repository(RepositoryError::Conflict) => 409,
+            DavError::Repository(_) => 500,
+        }
+    }
+}
+
+impl From<IcalError> for DavError {
+    fn from(error: IcalError) -> Self {
+        DavError::UnsupportedData(error.to_string())
+    }
+}
+
+impl From<DomainError> for DavError {
+    fn from(error: DomainError) -> Self {
+        DavError::Application(ApplicationError::Domain(error))
+    }
+}
@@ -0,0 +1,476 @@
+use chrono::{DateTime, Utc};
+
+use crate::{
+    application::publisher::Publisher,
+    domain::{
+        calendar::Calendar,
+        calendar_object::CalendarObject,
+        value_objects::{CalendarId, TimeRange},
+    },
+    infrastructure::ical::{Component, IcalMapper},
+};
+
+use super::{
+    error::DavError,
+    path::{DavPath, CALENDAR_HOME, PRINCIPAL},
+    store::{ctag, etag, DavStore},
+    xml::{self, MultiStatusWriter, PropName, PropRequest, Report, CALDAV, CALENDARSERVER, DAV},
+};
+
+const ICS_CONTENT_TYPE: &str = "text/calendar; charset=utf-8";
+const XML_CONTENT_TYPE: &str = "application/xml; charset=utf-8";
+
+/// A transport-independent response.
+#[derive(Debug, Clone)]
+pub struct DavResponse {
+    pub status: u16,
+    pub headers: Vec<(&'static str, String)>,
+    pub body: String,
+}
+
+impl DavResponse {
+    fn new(status: u16) -> Self {
+        Self {
+            status,
+            headers: Vec::new(),
+            body: String::new(),
+        }
+    }
+
+    fn header(mut self, name: &'static str, value: impl Into<String>) -> Self {
+        self.headers.push((name, value.into()));
+        self
+    }
+
+    fn body(mut self, content_type: &str, body: String) -> Self {
+        self.headers.push(("Content-Type", content_type.to_string()));
+        self.body = body;
+        self
+    }
+
+    fn multistatus(body: String) -> Self {
+        Self::new(207).body(XML_CONTENT_TYPE, body)
+    }
+}
+
+/// Request headers the methods care about.
+#[derive(Debug, Clone, Default)]
+pub struct Conditions {
+    pub depth: Option<String>,
+    pub if_match: Option<String>,
+    pub if_none_match: Option<String>,
+}
+
+pub struct DavMethods {
+    store: DavStore,
+}
+
+impl DavMethods {
+    pub fn new(store: DavStore) -> Self {
+        Self { store }
+    }
+
+    /// Publishes the domain events writes raise to `publisher`'s subscribers.
+    pub fn with_publisher(mut self, publisher: Publisher) -> Self {
+        self.store = self.store.with_publisher(publisher);
+        self
+    }
+
+    pub async fn handle(
+        &self,
+        method: &str,
+        path: &str,
+        conditions: &Conditions,
+        body: &str,
+    ) -> Result<DavResponse, DavError> {
+        if path.starts_with("/.well-known/caldav") {
+            return Ok(DavResponse::new(301).header("Location", PRINCIPAL));
+        }
+
+        let target = DavPath::parse(path).ok_or(DavError::NotFound)?;
+
+        match method {
+            "OPTIONS" => Ok(Self::options()),
+            "PROPFIND" => self.propfind(target, conditions, body).await,
+            "REPORT" => self.report(target, body).await,
+            "GET" => self.get(target).await,
+            "HEAD" => {
+                let mut response = self.get(target).await?;
+                response.body.clear();
+                Ok(response)
+            }
+            "PUT" => self.put(target, conditions, body).await,
+            "DELETE" => self.delete(target, conditions).await,
+            _ => Err(DavError::MethodNotAllowed),
+        }
+    }
+
+    fn options() -> DavResponse {
+        DavResponse::new(200)
+            .header("DAV", "1, 3, calendar-access")
+            .header("Allow", "OPTIONS, GET, HEAD, PUT, DELETE, PROPFIND, REPORT")
+    }
+
+    async fn propfind(
+        &self,
+        target: DavPath,
+        conditions: &Conditions,
+        body: &str,
+    ) -> Result<DavResponse, DavError> {
+        let request = xml::parse_propfind(body)?;
+        let with_children = conditions.depth.as_deref() != Some("0");
+        let mut writer = MultiStatusWriter::new();
+
+        match target {
+            DavPath::Root | DavPath::Principal => {
+                write_props(&mut writer, &target.href(), &collection_props(target), &request);
+            }
+            DavPath::Home => {
+                write_props(&mut writer, &target.href(), &collection_props(target), &request);
+
+                if with_children {
+                    for calendar in self.store.list_calendars().await? {
+                        let objects = self.store.objects(calendar.calendar_id()).await?;
+                        let path = DavPath::Calendar(*calendar.calendar_id());
+                        write_props(&mut writer, &path.href(), &calendar_props(&calendar, &objects), &request);
+                    }
+                }
+            }
+            DavPath::Calendar(calendar_id) => {
+                let calendar = self.store.find_calendar(&calendar_id).await?;
+                let objects = self.store.objects(&calendar_id).await?;
+                write_props(&mut writer, &target.href(), &calendar_props(&calendar, &objects), &request);
+
+                if with_children {
+                    for object in &objects {
+                        let path = DavPath::Object(calendar_id, *object.event_id());
+                        write_props(&mut writer, &path.href(), &object_props(object), &request);
+                    }
+                }
+            }
+            DavPath::Object(calendar_id, _) => {
+                let object = self.find_object(&target, &calendar_id).await?;
+                write_props(&mut writer, &target.href(), &object_props(&object), &request);
+            }
+        }
+
+        Ok(DavResponse::multistatus(writer.finish()))
+    }
+
+    async fn report(&self, target: DavPath, body: &str) -> Result<DavResponse, DavError> {
+        let DavPath::Calendar(calendar_id) = target else {
+            return Err(DavError::Forbidden("REPORT is only supported on calendars".to_string()));
+        };
+
+        self.store.find_calendar(&calendar_id).await?;
+        let mut writer = MultiStatusWriter::new();
+
+        match xml::parse_report(body)? {
+            Report::CalendarQuery { props, start, end } => {
+                let window = window(start, end)?;
+
+                for object in self.store.objects(&calendar_id).await? {
+                    if matches_window(&object, start, window.as_ref()) {
+                        let path = DavPath::Object(calendar_id, *object.event_id());
+                        write_props(&mut writer, &path.href(), &object_props(&object), &props);
+                    }
+                }
+            }
+            Report::Multiget { props, hrefs } => {
+                for href in hrefs {
+                    let object = match DavPath::parse(&href) {
+                        Some(path @ DavPath::Object(owner, _)) if owner == calendar_id => {
+                            self.find_object(&path, &calendar_id).await.ok()
+                        }
+                        _ => None,
+                    };
+
+                    match object {
+                        Some(object) => write_props(&mut writer, &href, &object_props(&object), &props),
+                        None => writer.status_response(&href, "404 Not Found"),
+                    }
+                }
+            }
+            Report::Unsupported(report) => {
+                return Err(DavError::Forbidden(format!("unsupported report {}", report.name)));
+            }
+        }
+
+        Ok(DavResponse::multistatus(writer.finish()))
+    }
+
+    async fn get(&self, target: DavPath) -> Result<DavResponse, DavError> {
+        match target {
+            DavPath::Object(calendar_id, _) => {
+                let object = self.find_object(&target, &calendar_id).await?;
+
+                Ok(DavResponse::new(200)
+                    .header("ETag", etag(&object))
+                    .body(ICS_CONTENT_TYPE, IcalMapper::to_ics(&object)))
+            }
+            // A whole calendar can be fetched as one feed
+            DavPath::Calendar(calendar_id) => {
+                self.store.find_calendar(&calendar_id).await?;
+                let objects = self.store.objects(&calendar_id).await?;
+                let components = objects
+                    .iter()
+                    .flat_map(IcalMapper::object_to_components)
+                    .collect();
+
+                Ok(DavResponse::new(200)
+                    .header("ETag", format!("\"{}\"", ctag(&objects)))
+                    .body(ICS_CONTENT_TYPE, IcalMapper::wrap(components).to_ics()))
+            }
+            _ => Err(DavError::MethodNotAllowed),
+        }
+    }
+
+    async fn put(
+        &self,
+        target: DavPath,
+        conditions: &Conditions,
+        body: &str,
+    ) -> Result<DavResponse, DavError> {
+        let DavPath::Object(calendar_id, event_id) = target else {
+            return Err(DavError::MethodNotAllowed);
+        };
+
+        self.store
+            .find_calendar(&calendar_id)
+            .await
+            .map_err(|_| DavError::Conflict("calendar does not exist".to_string()))?;
+
+        let vcalendar = Component::parse_all(body)?
+            .into_iter()
+            .next()
+            .ok_or_else(|| DavError::UnsupportedData("no VCALENDAR".to_string()))?;
+        let object = IcalMapper::to_domain_single(&vcalendar, calendar_id, event_id)?;
+
+        let current = self.store.find_object(&event_id).await?;
+
+        if let Some(current) = &current
+            && *current.calendar_id() != calendar_id
+        {
+            return Err(DavError::Conflict("UID already used in another calendar".to_string()));
+        }
+
+        check_preconditions(current.as_ref(), conditions)?;
+
+        let created = current.is_none();
+        self.store.put(object, current).await?;
+
+        let stored = self.find_object(&target, &calendar_id).await?;
+
+        Ok(DavResponse::new(if created { 201 } else { 204 })
+            .header("ETag", etag(&stored)))
+    }
+
+    async fn delete(&self, target: DavPath, conditions: &Conditions) -> Result<DavResponse, DavError> {
+        let DavPath::Object(calendar_id, _) = target else {
+            return Err(DavError::Forbidden("only calendar objects can be deleted".to_string()));
+        };
+
+        let object = self.find_object(&target, &calendar_id).await?;
+        check_preconditions(Some(&object), conditions)?;
+
+        self.store.delete(&object).await?;
+
+        Ok(DavResponse::new(204))
+    }
+
+    async fn find_object(
+        &self,
+        target: &DavPath,
+        calendar_id: &CalendarId,
+    ) -> Result<CalendarObject, DavError> {
+        let DavPath::Object(_, event_id) = target else {
+            return Err(DavError::NotFound);
+        };
+
+        self.store
+            .find_object(event_id)
+            .await?
+            .filter(|object| object.calendar_id() == calendar_id)
+            .ok_or(DavError::NotFound)
+    }
+}
+
+
+// ======================================================
+// Properties
+// ======================================================
+
+fn collection_props(target: DavPath) -> Vec<(PropName, String)> {
+    let mut props = vec![
+        (PropName::new(DAV, "current-user-principal"), xml::href(PRINCIPAL)),
+        (PropName::new(DAV, "displayname"), "kal".to_string()),
+    ];
+
+    match target {
+        DavPath::Principal => {
+            props.push((PropName::new(DAV, "resourcetype"), "<d:principal/>".to_string()));
+            props.push((PropName::new(DAV, "principal-URL"), xml::href(PRINCIPAL)));
+            props.push((PropName::new(CALDAV, "calendar-home-set"), xml::href(CALENDAR_HOME)));
+        }
+        _ => {
+            props.push((PropName::new(DAV, "resourcetype"), "<d:collection/>".to_string()));
+        }
+    }
+
+    props
+}
+
+fn calendar_props(calendar: &Calendar, objects: &[CalendarObject]) -> Vec<(PropName, String)> {
+    let privileges = if calendar.is_subscription() {
+        vec!["read"]
+    } else {
+        vec!["read", "write", "write-content", "bind", "unbind"]
+    };
+
+    let mut props = vec![
+        (PropName::new(DAV, "current-user-principal"), xml::href(PRINCIPAL)),
+        (PropName::new(DAV, "owner"), xml::href(PRINCIPAL)),
+        (
+            PropName::new(DAV, "resourcetype"),
+            "<d:collection/><c:calendar/>".to_string(),
+        ),
+        (PropName::new(DAV, "displayname"), xml::escape(calendar.name())),
+        (
+            PropName::new(CALDAV, "supported-calendar-component-set"),
+            "<c:comp name=\"VEVENT\"/>".to_string(),
+        ),
+        (
+            PropName::new(DAV, "supported-report-set"),
+            ["calendar-query", "calendar-multiget"]
+                .iter()
+                .map(|r| format!("<d:supported-report><d:report><c:{r}/></d:report></d:supported-report>"))
+                .collect(),
+        ),
+        (
+            PropName::new(DAV, "current-user-privilege-set"),
+            privileges
+                .iter()
+                .map(|p| format!("<d:privilege><d:{p}/></d:privilege>"))
+                .collect(),
+        ),
+        (PropName::new(CALENDARSERVER, "getctag"), ctag(objects)),
+    ];
+
+    if let Some(description) = calendar.description() {
+        props.push((PropName::new(CALDAV, "calendar-description"), xml::escape(description)));
+    }
+
+    props
+}
+
+fn object_props(object: &CalendarObject) -> Vec<(PropName, String)> {
+    vec![
+        (PropName::new(DAV, "resourcetype"), String::new()),
+        (PropName::new(DAV, "getetag"), xml::escape(&etag(object))),
+        (
+            PropName::new(DAV, "getcontenttype"),
+            format!("{ICS_CONTENT_TYPE}; component=vevent"),
+        ),
+        (
+            PropName::new(CALDAV, "calendar-data"),
+            xml::escape(&IcalMapper::to_ics(object)),
+        ),
+    ]
+}
+
+fn write_props(
+    writer: &mut MultiStatusWriter,
+    href: &str,
+    available: &[(PropName, String)],
+    request: &PropRequest,
+) {
+    match request {
+        PropRequest::AllProp => {
+            let found: Vec<_> = available
+                .iter()
+                .filter(|(name, _)| !name.is(CALDAV, "calendar-data"))
+                .cloned()
+                .collect();
+            writer.response(href, &found, &[]);
+        }
+        PropRequest::PropName => {
+            let names: Vec<_> = available
+                .iter()
+                .map(|(name, _)| (name.clone(), String::new()))
+                .collect();
+            writer.response(href, &names, &[]);
+        }
+        PropRequest::Props(requested) => {
+            let mut found = Vec::new();
+            let mut missing = Vec::new();
+
+            for name in requested {
+                match available.iter().find(|(n, _)| n == name) {
+                    Some(prop) => found.push(prop.clone()),
+                    None => missing.push(name.clone()),
+                }
+            }
+
+            writer.response(href, &found, &missing);
+        }
+    }
+}
+
+
+// ======================================================
+// Helpers
+// ======================================================
+
+fn window(
+    start: Option<DateTime<Utc>>,
+    end: Option<DateTime<Utc>>,
+) -> Result<Option<TimeRange>, DavError> {
+    match end {
+        Some(end) => TimeRange::new(start.unwrap_or(DateTime::<Utc>::MIN_UTC), end)
+            .map(Some)
+            .map_err(|_| DavError::BadRequest("empty time-range".to_string())),
+        None => Ok(None),
+    }
+}
+
+/// Open-ended queries only check the start, so unbounded series are
+/// never expanded to infinity.
+fn matches_window(
+    object: &CalendarObject,
+    start: Option<DateTime<Utc>>,
+    window: Option<&TimeRange>,
+) -> bool {
+    match (object, window) {
+        (CalendarObject::Event(event), Some(window)) => event.time_range().overlaps(window),
+        (CalendarObject::Recurring(event), Some(window)) => {
+            !event.occurrences_in(window).is_empty()
+        }
+        (CalendarObject::Event(event), None) => {
+            start.is_none_or(|start| *event.time_range().ends_at() > start)
+        }
+        (CalendarObject::Recurring(event), None) => {
+            start.is_none_or(|start| event.rule().until().is_none_or(|until| until >= start))
+        }
+    }
+}
+
+fn check_preconditions(
+    current: Option<&CalendarObject>,
+    conditions: &Conditions,
+) -> Result<(), DavError> {
+    if conditions.if_none_match.as_deref() == Some("*") && current.is_some() {
+        return Err(DavError::PreconditionFailed);
+    }
+
+    if let Some(expected) = &conditions.if_match {
+        let matches = current.is_some_and(|object| {
+            expected == "*" || expected.split(',').any(|tag| tag.trim() == etag(object))
+        });
+
+        if !matches {
+            return Err(DavError::PreconditionFailed);
+        }
+    }
+
+    Ok(())
+}
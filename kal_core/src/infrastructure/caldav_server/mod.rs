@@ -0,0 +1,11 @@
+pub mod server;
+pub mod methods;
+pub mod store;
+pub mod path;
+pub mod xml;
+pub mod error;
+
+pub use server::CalDavServer;
+pub use methods::{DavMethods, DavResponse};
+pub use store::DavStore;
+pub use error::DavError;
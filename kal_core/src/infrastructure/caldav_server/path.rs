@@ -0,0 +1,106 @@
+use uuid::Uuid;
+
+use crate::{
+    domain::value_objects::{CalendarId, EventId},
+    infrastructure::ical::mappers::event_id_for_uid,
+};
+
+pub const PRINCIPAL: &str = "/principal/";
+pub const CALENDAR_HOME: &str = "/calendars/";
+
+/// A resource addressed by the server's URL layout:
+///
+/// ```text
+/// /                                   root
+/// /principal/                         the single principal
+/// /calendars/                         calendar home
+/// /calendars/{calendar_id}/           calendar collection
+/// /calendars/{calendar_id}/{name}.ics calendar object resource
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DavPath {
+    Root,
+    Principal,
+    Home,
+    Calendar(CalendarId),
+    Object(CalendarId, EventId),
+}
+
+impl DavPath {
+    /// Parses a request path or an absolute href.
+    pub fn parse(href: &str) -> Option<Self> {
+        let path = strip_origin(href);
+        let path = path.split(['?', '#']).next().unwrap_or_default();
+        let segments: Vec<String> = path
+            .split('/')
+            .filter(|s| !s.is_empty())
+            .map(percent_decode)
+            .collect();
+
+        match segments.as_slice() {
+            [] => Some(DavPath::Root),
+            [p] if p == "principal" => Some(DavPath::Principal),
+            [c] if c == "calendars" => Some(DavPath::Home),
+            [c, calendar] if c == "calendars" => {
+                Some(DavPath::Calendar(parse_calendar_id(calendar)?))
+            }
+            [c, calendar, name] if c == "calendars" => {
+                let stem = name.strip_suffix(".ics")?;
+                Some(DavPath::Object(parse_calendar_id(calendar)?, object_id(stem)))
+            }
+            _ => None,
+        }
+    }
+
+    pub fn href(&self) -> String {
+        match self {
+            DavPath::Root => "/".to_string(),
+            DavPath::Principal => PRINCIPAL.to_string(),
+            DavPath::Home => CALENDAR_HOME.to_string(),
+            DavPath::Calendar(calendar_id) => format!("{CALENDAR_HOME}{calendar_id}/"),
+            DavPath::Object(calendar_id, event_id) => {
+                format!("{CALENDAR_HOME}{calendar_id}/{event_id}.ics")
+            }
+        }
+    }
+}
+
+/// Objects are stored under ids derived from their resource name, so a
+/// client that names resources after UUID UIDs sees them at the same
+/// href. Other names resolve consistently but are listed under the id.
+fn object_id(stem: &str) -> EventId {
+    event_id_for_uid(stem)
+}
+
+fn parse_calendar_id(segment: &str) -> Option<CalendarId> {
+    Uuid::parse_str(segment).ok().map(CalendarId::from_uuid)
+}
+
+fn strip_origin(href: &str) -> &str {
+    match href.split_once("://") {
+        Some((_, rest)) => rest.find('/').map_or("/", |i| &rest[i..]),
+        None => href,
+    }
+}
+
+fn percent_decode(segment: &str) -> String {
+    let bytes = segment.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+
+    while i < bytes.len() {
+        if bytes[i] == b'%'
+            && let Some(byte) = segment
+                .get(i + 1..i + 3)
+                .and_then(|hex| u8::from_str_radix(hex, 16).ok())
+        {
+            out.push(byte);
+            i += 3;
+            continue;
+        }
+        out.push(bytes[i]);
+        i += 1;
+    }
+
+    String::from_utf8_lossy(&out).into_owned()
+}
@@ -0,0 +1,173 @@
+use std::{convert::Infallible, sync::Arc};
+
+use http_body_util::{BodyExt, Full};
+use hyper::{
+    body::{Bytes, Incoming},
+    header::{HeaderName, HeaderValue},
+    server::conn::http1,
+    service::service_fn,
+    HeaderMap,
+    Request,
+    Response,
+    StatusCode,
+};
+use hyper_util::rt::TokioIo;
+use sqlx::SqlitePool;
+use tokio::net::TcpListener;
+
+use crate::application::publisher::Publisher;
+
+use super::{
+    error::DavError,
+    methods::{Conditions, DavMethods, DavResponse},
+    store::DavStore,
+};
+
+/// Serves the local database as a minimal CalDAV server.
+pub struct CalDavServer {
+    methods: DavMethods,
+    authorization: Option<String>,
+}
+
+impl CalDavServer {
+    pub fn new(pool: SqlitePool) -> Self {
+        Self {
+            methods: DavMethods::new(DavStore::new(pool)),
+            authorization: None,
+        }
+    }
+
+    /// Publishes the domain events writes raise to `publisher`'s subscribers.
+    pub fn with_publisher(mut self, publisher: Publisher) -> Self {
+        self.methods = self.methods.with_publisher(publisher);
+        self
+    }
+
+    /// Requires HTTP Basic authentication with these credentials.
+    pub fn with_credentials(mut self, username: &str, password: &str) -> Self {
+        let token = base64(format!("{username}:{password}").as_bytes());
+        self.authorization = Some(format!("Basic {token}"));
+        self
+    }
+
+    /// Accepts connections until the listener fails.
+    pub async fn serve(self, listener: TcpListener) -> std::io::Result<()> {
+        let server = Arc::new(self);
+
+        loop {
+            let (stream, _) = listener.accept().await?;
+            let server = Arc::clone(&server);
+
+            tokio::spawn(async move {
+                let service = service_fn(move |request| {
+                    let server = Arc::clone(&server);
+                    async move { Ok::<_, Infallible>(server.respond(request).await) }
+                });
+
+                // A client dropping its connection is not a server error
+                let _ = http1::Builder::new()
+                    .serve_connection(TokioIo::new(stream), service)
+                    .await;
+            });
+        }
+    }
+
+    async fn respond(&self, request: Request<Incoming>) -> Response<Full<Bytes>> {
+        let result = match self.authorize(request.headers()) {
+            Ok(()) => self.dispatch(request).await,
+            Err(e) => Err(e),
+        };
+
+        let response = result.unwrap_or_else(|error| {
+            let mut response = DavResponse {
+                status: error.status(),
+                headers: vec![("Content-Type", "text/plain; charset=utf-8".to_string())],
+                body: format!("{error}\n"),
+            };
+            if matches!(error, DavError::Unauthorized) {
+                response.headers.push(("WWW-Authenticate", "Basic realm=\"kal\"".to_string()));
+            }
+            response
+        });
+
+        into_http(response)
+    }
+
+    fn authorize(&self, headers: &HeaderMap) -> Result<(), DavError> {
+        let Some(expected) = &self.authorization else {
+            return Ok(());
+        };
+
+        match header(headers, "authorization") {
+            Some(given) if given == *expected => Ok(()),
+            _ => Err(DavError::Unauthorized),
+        }
+    }
+
+    async fn dispatch(&self, request: Request<Incoming>) -> Result<DavResponse, DavError> {
+        let method = request.method().as_str().to_string();
+        let path = request.uri().path().to_string();
+        let conditions = Conditions {
+            depth: header(request.headers(), "depth"),
+            if_match: header(request.headers(), "if-match"),
+            if_none_match: header(request.headers(), "if-none-match"),
+        };
+
+        let body = request
+            .into_body()
+            .collect()
+            .await
+            .map_err(|e| DavError::BadRequest(e.to_string()))?
+            .to_bytes();
+        let body = String::from_utf8(body.to_vec())
+            .map_err(|_| DavError::BadRequest("body is not UTF-8".to_string()))?;
+
+        self.methods.handle(&method, &path, &conditions, &body).await
+    }
+}
+
+fn header(headers: &HeaderMap, name: &str) -> Option<String> {
+    headers
+        .get(name)
+        .and_then(|v| v.to_str().ok())
+        .map(str::to_string)
+}
+
+fn into_http(response: DavResponse) -> Response<Full<Bytes>> {
+    let mut http = Response::new(Full::new(Bytes::from(response.body)));
+    *http.status_mut() = StatusCode::from_u16(response.status)
+        .unwrap_or(StatusCode::INTERNAL_SERVER_ERROR);
+
+    for (name, value) in response.headers {
+        if let (Ok(name), Ok(value)) = (
+            HeaderName::from_bytes(name.as_bytes()),
+            HeaderValue::from_str(&value),
+        ) {
+            http.headers_mut().append(name, value);
+        }
+    }
+
+    http
+}
+
+fn base64(input: &[u8]) -> String {
+    const ALPHABET: &[u8; 64] =
+        b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+    let mut out = String::with_capacity(input.len().div_ceil(3) * 4);
+
+    for chunk in input.chunks(3) {
+        let b = [chunk[0], *chunk.get(1).unwrap_or(&0), *chunk.get(2).unwrap_or(&0)];
+        let n = (b[0] as u32) << 16 | (b[1] as u32) << 8 | b[2] as u32;
+
+        for i in 0..4 {
+            if i <= chunk.len() {
+                out.push(ALPHABET[(n >> (18 - 6 * i) & 0x3f) as usize] as char);
+            } else {
+                out.push('=');
+            }
+        }
+    }
+
+    out
+}
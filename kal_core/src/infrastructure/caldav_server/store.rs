@@ -0,0 +1,541 @@
+use sha2::{Digest, Sha256};
+use sqlx::SqlitePool;
+
+use crate::{
+    application::{
+        commands::{
+            events::{
+                AddEventAttendeeCommand, AddEventAttendeeHandler,
+                AddEventReminderCommand, AddEventReminderHandler,
+                AddEventTagCommand, AddEventTagHandler,
+                CreateEventCommand, CreateEventHandler,
+                DeleteEventCommand, DeleteEventHandler,
+                RemoveEventAttendeeCommand, RemoveEventAttendeeHandler,
+                RemoveEventReminderCommand, RemoveEventReminderHandler,
+                RemoveEventTagCommand, RemoveEventTagHandler,
+                SetEventOrganizerCommand, SetEventOrganizerHandler,
+                UpdateEventAttendeeStatusCommand, UpdateEventAttendeeStatusHandler,
+                UpdateEventColorCommand, UpdateEventColorHandler,
+                UpdateEventDescriptionCommand, UpdateEventDescriptionHandler,
+                UpdateEventGeoCommand, UpdateEventGeoHandler,
+                UpdateEventLocationCommand, UpdateEventLocationHandler,
+                UpdateEventStatusCommand, UpdateEventStatusHandler,
+                UpdateEventTimeRangeCommand, UpdateEventTimeRangeHandler,
+                UpdateEventTitleCommand, UpdateEventTitleHandler,
+                UpdateEventTransparencyCommand, UpdateEventTransparencyHandler,
+                UpdateEventUrlCommand, UpdateEventUrlHandler,
+            },
+            recurring::{
+                AddRecurringAttendeeCommand, AddRecurringAttendeeHandler,
+                AddRecurringReminderCommand, AddRecurringReminderHandler,
+                CancelRecurringOccurrenceCommand, CancelRecurringOccurrenceHandler,
+                CreateRecurringEventCommand, CreateRecurringEventHandler,
+                DeleteRecurringEventCommand, DeleteRecurringEventHandler,
+                RescheduleRecurringOccurrenceCommand, RescheduleRecurringOccurrenceHandler,
+                SetRecurringOccurrenceLocationCommand, SetRecurringOccurrenceLocationHandler,
+                SetRecurringOrganizerCommand, SetRecurringOrganizerHandler,
+            },
+        },
+        history::Recorded,
+        publisher::{Published, Publisher},
+        scope::CommandScope,
+    },
+    domain::{
+        attendee::{Attendee, Organizer},
+        calendar::Calendar,
+        calendar_object::CalendarObject,
+        event::Event,
+        recurrence::{ExceptionModification, RecurringEvent},
+        reminder::Reminder,
+        repository::{
+            CalendarRepository,
+            EventRepository,
+            RecurringEventRepository,
+            RepositoryError,
+        },
+        value_objects::{CalendarId, EventId},
+    },
+    infrastructure::{
+        ical::IcalMapper,
+        persistence::{
+            SqliteCalendarRepository,
+            SqliteEventRepository,
+            SqliteRecurringEventRepository,
+            SqliteUnitOfWork,
+        },
+    },
+};
+
+use super::error::DavError;
+
+/// Reads from the SQLite repositories and routes every write through the
+/// application command handlers, so the server enforces the same rules
+/// as the CLI. Each write runs in one transaction together with its undo
+/// history and audit log entries, like CLI commands, and its domain
+/// events are published once it has committed.
+#[derive(Clone)]
+pub struct DavStore {
+    pool: SqlitePool,
+    scope: CommandScope,
+}
+
+impl DavStore {
+    pub fn new(pool: SqlitePool) -> Self {
+        Self { pool, scope: CommandScope::new() }
+    }
+
+    /// Publishes domain events to `publisher`'s subscribers.
+    pub fn with_publisher(mut self, publisher: Publisher) -> Self {
+        self.scope = self.scope.with_publisher(publisher);
+        self
+    }
+
+    fn calendars(&self) -> SqliteCalendarRepository {
+        SqliteCalendarRepository::new(self.pool.clone())
+    }
+
+    fn events(&self) -> SqliteEventRepository {
+        SqliteEventRepository::new(self.pool.clone())
+    }
+
+    fn recurring(&self) -> SqliteRecurringEventRepository {
+        SqliteRecurringEventRepository::new(self.pool.clone())
+    }
+
+    /// A write with a transaction and scope of its own, so concurrent
+    /// requests' changes stay apart.
+    async fn begin(&self) -> Result<Write, DavError> {
+        Ok(Write {
+            uow: SqliteUnitOfWork::begin(&self.pool).await?,
+            scope: self.scope.scoped(),
+        })
+    }
+
+    pub async fn list_calendars(&self) -> Result<Vec<Calendar>, DavError> {
+        Ok(self.calendars().find_all_active().await?)
+    }
+
+    pub async fn find_calendar(&self, calendar_id: &CalendarId) -> Result<Calendar, DavError> {
+        self.calendars()
+            .find_by_id(calendar_id)
+            .await?
+            .ok_or(DavError::NotFound)
+    }
+
+    pub async fn objects(&self, calendar_id: &CalendarId) -> Result<Vec<CalendarObject>, DavError> {
+        let mut objects: Vec<CalendarObject> = self
+            .events()
+            .find_by_calendar(calendar_id)
+            .await?
+            .into_iter()
+            .map(CalendarObject::Event)
+            .collect();
+
+        objects.extend(
+            self.recurring()
+                .find_by_calendar(calendar_id)
+                .await?
+                .into_iter()
+                .map(CalendarObject::Recurring),
+        );
+
+        Ok(objects)
+    }
+
+    /// Finds an object by id, whichever calendar it lives in.
+    pub async fn find_object(&self, event_id: &EventId) -> Result<Option<CalendarObject>, DavError> {
+        if let Some(event) = self.events().find_by_id(event_id).await? {
+            return Ok(Some(CalendarObject::Event(event)));
+        }
+
+        match self.recurring().find_by_id(event_id).await {
+            Ok(event) => Ok(Some(CalendarObject::Recurring(event))),
+            Err(RepositoryError::NotFound) => Ok(None),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    /// Stores `object`, replacing `current` if the resource already exists.
+    pub async fn put(
+        &self,
+        object: CalendarObject,
+        current: Option<CalendarObject>,
+    ) -> Result<(), DavError> {
+        let write = self.begin().await?;
+        write.store(object, current).await?;
+        write.finish("caldav.put").await
+    }
+
+    pub async fn delete(&self, object: &CalendarObject) -> Result<(), DavError> {
+        let write = self.begin().await?;
+        write.remove(object).await?;
+        write.finish("caldav.delete").await
+    }
+}
+
+/// One write in flight: the transaction it runs in, and what it changed
+/// and raised along the way. Dropping it unfinished rolls it back.
+struct Write {
+    uow: SqliteUnitOfWork,
+    scope: CommandScope,
+}
+
+impl Write {
+    fn calendars(&self) -> Recorded<Published<SqliteCalendarRepository>> {
+        self.scope.calendars(self.uow.calendars())
+    }
+
+    fn events(&self) -> Recorded<Published<SqliteEventRepository>> {
+        self.scope.events(self.uow.events())
+    }
+
+    fn recurring(&self) -> Recorded<Published<SqliteRecurringEventRepository>> {
+        self.scope.recurring(self.uow.recurring())
+    }
+
+    /// Adds what the write changed to the undo history and the audit log,
+    /// commits, and publishes its events.
+    async fn finish(self, command: &str) -> Result<(), DavError> {
+        self.scope.record(&self.uow.history(), command).await?;
+        self.scope.audit(&self.uow.audit(), command).await?;
+        self.uow.commit().await?;
+        self.scope.publish(command).await;
+
+        Ok(())
+    }
+
+    async fn store(
+        &self,
+        object: CalendarObject,
+        current: Option<CalendarObject>,
+    ) -> Result<(), DavError> {
+        match (current, object) {
+            (None, object) => self.create(object).await,
+            (Some(CalendarObject::Event(old)), CalendarObject::Event(new))
+                if old.is_all_day() == new.is_all_day() =>
+            {
+                self.update_event(&old, new).await
+            }
+            // No handler changes the shape of an object in place
+            (Some(old), object) => {
+                self.remove(&old).await?;
+                self.create(object).await
+            }
+        }
+    }
+
+    async fn remove(&self, object: &CalendarObject) -> Result<(), DavError> {
+        match object {
+            CalendarObject::Event(event) => {
+                DeleteEventHandler::new(self.events(), self.calendars())
+                    .handle(DeleteEventCommand::new(*event.event_id()))
+                    .await?;
+            }
+            CalendarObject::Recurring(event) => {
+                DeleteRecurringEventHandler::new(self.recurring(), self.calendars())
+                    .handle(DeleteRecurringEventCommand::new(*event.event_id()))
+                    .await?;
+            }
+        }
+
+        Ok(())
+    }
+
+    async fn create(&self, object: CalendarObject) -> Result<(), DavError> {
+        match object {
+            CalendarObject::Event(event) => self.create_event(event).await,
+            CalendarObject::Recurring(event) => self.create_recurring(event).await,
+        }
+    }
+
+    async fn create_event(&self, event: Event) -> Result<(), DavError> {
+        let command = CreateEventCommand::new(
+            *event.calendar_id(),
+            event.title().clone(),
+            event.description().clone(),
+            *event.time_range(),
+            *event.color(),
+            *event.is_all_day(),
+        )
+        .with_event_id(*event.event_id())
+        .with_place(event.location().clone(), event.url().clone(), *event.geo())
+        .with_tags(event.tags().iter().cloned().collect())
+        .with_status(*event.status(), *event.transparency());
+
+        let event_id = CreateEventHandler::new(self.events(), self.calendars())
+            .handle(command)
+            .await?;
+
+        self.sync_event_reminders(event_id, &[], event.reminders()).await?;
+        self.sync_event_participants(event_id, &None, &[], &event).await?;
+
+        Ok(())
+    }
+
+    async fn create_recurring(&self, event: RecurringEvent) -> Result<(), DavError> {
+        let command = CreateRecurringEventCommand::new(
+            *event.calendar_id(),
+            event.title().clone(),
+            event.description().clone(),
+            *event.time_range(),
+            event.rule().clone(),
+            *event.color(),
+            *event.is_all_day(),
+        )
+        .with_event_id(*event.event_id())
+        .with_place(event.location().clone(), event.url().clone(), *event.geo())
+        .with_tags(event.tags().iter().cloned().collect())
+        .with_status(*event.status(), *event.transparency());
+
+        let event_id = CreateRecurringEventHandler::new(self.recurring(), self.calendars())
+            .handle(command)
+            .await?;
+
+        for reminder in event.reminders() {
+            AddRecurringReminderHandler::new(self.recurring(), self.calendars())
+                .handle(
+                    AddRecurringReminderCommand::new(
+                        event_id,
+                        *reminder.trigger(),
+                        reminder.action().clone(),
+                        reminder.description().clone(),
+                        *reminder.repeat(),
+                        *reminder.repeat_interval(),
+                    )
+                    .with_reminder_id(*reminder.reminder_id()),
+                )
+                .await?;
+        }
+
+        if event.organizer().is_some() {
+            SetRecurringOrganizerHandler::new(self.recurring(), self.calendars())
+                .handle(SetRecurringOrganizerCommand::new(event_id, event.organizer().clone()))
+                .await?;
+        }
+
+        for attendee in event.attendees() {
+            AddRecurringAttendeeHandler::new(self.recurring(), self.calendars())
+                .handle(AddRecurringAttendeeCommand::new(event_id, attendee.clone()))
+                .await?;
+        }
+
+        for exception in event.exceptions().values() {
+            let original_starts_at = *exception.original_starts_at();
+
+            match exception.modification() {
+                ExceptionModification::Cancelled => {
+                    CancelRecurringOccurrenceHandler::new(self.recurring(), self.calendars())
+                        .handle(CancelRecurringOccurrenceCommand::new(event_id, original_starts_at))
+                        .await?;
+                }
+                ExceptionModification::Rescheduled { new_time_range } => {
+                    RescheduleRecurringOccurrenceHandler::new(self.recurring(), self.calendars())
+                        .handle(RescheduleRecurringOccurrenceCommand::new(
+                            event_id,
+                            original_starts_at,
+                            *new_time_range,
+                        ))
+                        .await?;
+
+                    if exception.location().is_some() {
+                        SetRecurringOccurrenceLocationHandler::new(self.recurring(), self.calendars())
+                            .handle(SetRecurringOccurrenceLocationCommand::new(
+                                event_id,
+                                original_starts_at,
+                                exception.location().clone(),
+                            ))
+                            .await?;
+                    }
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Applies only the fields that changed, one command each.
+    async fn update_event(&self, old: &Event, new: Event) -> Result<(), DavError> {
+        let id = *old.event_id();
+
+        if old.title() != new.title() {
+            UpdateEventTitleHandler::new(self.events(), self.calendars())
+                .handle(UpdateEventTitleCommand::new(id, new.title().clone()))
+                .await?;
+        }
+
+        if old.description() != new.description() {
+            UpdateEventDescriptionHandler::new(self.events(), self.calendars())
+                .handle(UpdateEventDescriptionCommand::new(id, new.description().clone()))
+                .await?;
+        }
+
+        if old.time_range() != new.time_range() {
+            UpdateEventTimeRangeHandler::new(self.events(), self.calendars())
+                .handle(UpdateEventTimeRangeCommand::new(id, *new.time_range()))
+                .await?;
+        }
+
+        if old.color() != new.color() {
+            UpdateEventColorHandler::new(self.events(), self.calendars())
+                .handle(UpdateEventColorCommand::new(id, *new.color()))
+                .await?;
+        }
+
+        if old.location() != new.location() {
+            UpdateEventLocationHandler::new(self.events(), self.calendars())
+                .handle(UpdateEventLocationCommand::new(id, new.location().clone()))
+                .await?;
+        }
+
+        if old.url() != new.url() {
+            UpdateEventUrlHandler::new(self.events(), self.calendars())
+                .handle(UpdateEventUrlCommand::new(id, new.url().clone()))
+                .await?;
+        }
+
+        if old.geo() != new.geo() {
+            UpdateEventGeoHandler::new(self.events(), self.calendars())
+                .handle(UpdateEventGeoCommand::new(id, *new.geo()))
+                .await?;
+        }
+
+        for tag in old.tags().difference(new.tags()) {
+            RemoveEventTagHandler::new(self.events(), self.calendars())
+                .handle(RemoveEventTagCommand::new(id, tag.clone()))
+                .await?;
+        }
+
+        for tag in new.tags().difference(old.tags()) {
+            AddEventTagHandler::new(self.events(), self.calendars())
+                .handle(AddEventTagCommand::new(id, tag.clone()))
+                .await?;
+        }
+
+        self.sync_event_reminders(id, old.reminders(), new.reminders()).await?;
+        self.sync_event_participants(id, old.organizer(), old.attendees(), &new).await?;
+
+        if old.status() != new.status() {
+            UpdateEventStatusHandler::new(self.events(), self.calendars())
+                .handle(UpdateEventStatusCommand::new(id, *new.status()))
+                .await?;
+        }
+
+        if old.transparency() != new.transparency() {
+            UpdateEventTransparencyHandler::new(self.events(), self.calendars())
+                .handle(UpdateEventTransparencyCommand::new(id, *new.transparency()))
+                .await?;
+        }
+
+        Ok(())
+    }
+
+    /// Removes reminders that are gone or changed, then adds the new
+    /// versions under their original ids.
+    async fn sync_event_reminders(
+        &self,
+        id: EventId,
+        old: &[Reminder],
+        new: &[Reminder],
+    ) -> Result<(), DavError> {
+        for reminder in old.iter().filter(|r| !new.contains(r)) {
+            RemoveEventReminderHandler::new(self.events(), self.calendars())
+                .handle(RemoveEventReminderCommand::new(id, *reminder.reminder_id()))
+                .await?;
+        }
+
+        for reminder in new.iter().filter(|r| !old.contains(r)) {
+            let command = AddEventReminderCommand::new(
+                id,
+                *reminder.trigger(),
+                reminder.action().clone(),
+                reminder.description().clone(),
+                *reminder.repeat(),
+                *reminder.repeat_interval(),
+            )
+            .with_reminder_id(*reminder.reminder_id());
+
+            AddEventReminderHandler::new(self.events(), self.calendars())
+                .handle(command)
+                .await?;
+        }
+
+        Ok(())
+    }
+
+    /// Applies organizer and attendee changes. A changed status is a
+    /// status update; any other change re-invites the attendee.
+    async fn sync_event_participants(
+        &self,
+        id: EventId,
+        old_organizer: &Option<Organizer>,
+        old_attendees: &[Attendee],
+        new: &Event,
+    ) -> Result<(), DavError> {
+        if old_organizer != new.organizer() {
+            SetEventOrganizerHandler::new(self.events(), self.calendars())
+                .handle(SetEventOrganizerCommand::new(id, new.organizer().clone()))
+                .await?;
+        }
+
+        for attendee in old_attendees {
+            match new.attendees().iter().find(|a| same_invitation(a, attendee)) {
+                Some(current) if current.status() != attendee.status() => {
+                    UpdateEventAttendeeStatusHandler::new(self.events(), self.calendars())
+                        .handle(UpdateEventAttendeeStatusCommand::new(
+                            id,
+                            attendee.email().clone(),
+                            *current.status(),
+                        ))
+                        .await?;
+                }
+                Some(_) => {}
+                None => {
+                    RemoveEventAttendeeHandler::new(self.events(), self.calendars())
+                        .handle(RemoveEventAttendeeCommand::new(id, attendee.email().clone()))
+                        .await?;
+                }
+            }
+        }
+
+        for attendee in new.attendees() {
+            if !old_attendees.iter().any(|a| same_invitation(a, attendee)) {
+                AddEventAttendeeHandler::new(self.events(), self.calendars())
+                    .handle(AddEventAttendeeCommand::new(id, attendee.clone()))
+                    .await?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Whether two attendees differ at most in their participation status.
+fn same_invitation(a: &Attendee, b: &Attendee) -> bool {
+    a.email() == b.email() && a.name() == b.name() && a.role() == b.role() && a.rsvp() == b.rsvp()
+}
+
+pub fn etag(object: &CalendarObject) -> String {
+    format!("\"{}\"", IcalMapper::content_hash(object))
+}
+
+/// Collection tag: changes whenever any object is added, removed or
+/// modified.
+pub fn ctag(objects: &[CalendarObject]) -> String {
+    let mut tags: Vec<(String, String)> = objects
+        .iter()
+        .map(|o| (o.event_id().to_string(), IcalMapper::content_hash(o)))
+        .collect();
+    tags.sort();
+
+    let mut hasher = Sha256::new();
+    for (id, hash) in tags {
+        hasher.update(id.as_bytes());
+        hasher.update(hash.as_bytes());
+    }
+
+    hasher
+        .finalize()
+        .iter()
+        .map(|b| format!("{b:02x}"))
+        .collect()
+}
@@ -0,0 +1,305 @@
+use chrono::{DateTime, NaiveDateTime, Utc};
+use quick_xml::{
+    events::{BytesStart, Event as XmlEvent},
+    name::{Namespace, ResolveResult},
+    NsReader,
+};
+
+use super::error::DavError;
+
+pub const DAV: &str = "DAV:";
+pub const CALDAV: &str = "urn:ietf:params:xml:ns:caldav";
+pub const CALENDARSERVER: &str = "http://calendarserver.org/ns/";
+
+/// A namespace-qualified property name.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct PropName {
+    pub namespace: String,
+    pub name: String,
+}
+
+impl PropName {
+    pub fn new(namespace: &str, name: &str) -> Self {
+        Self {
+            namespace: namespace.to_string(),
+            name: name.to_string(),
+        }
+    }
+
+    pub fn is(&self, namespace: &str, name: &str) -> bool {
+        self.namespace == namespace && self.name == name
+    }
+}
+
+/// What a PROPFIND or REPORT asked for.
+#[derive(Debug, Clone)]
+pub enum PropRequest {
+    AllProp,
+    PropName,
+    Props(Vec<PropName>),
+}
+
+#[derive(Debug, Clone)]
+pub enum Report {
+    CalendarQuery {
+        props: PropRequest,
+        start: Option<DateTime<Utc>>,
+        end: Option<DateTime<Utc>>,
+    },
+    Multiget {
+        props: PropRequest,
+        hrefs: Vec<String>,
+    },
+    Unsupported(PropName),
+}
+
+
+// ======================================================
+// Requests
+// ======================================================
+
+/// Parses a PROPFIND body. An empty body means `allprop`.
+pub fn parse_propfind(body: &str) -> Result<PropRequest, DavError> {
+    if body.trim().is_empty() {
+        return Ok(PropRequest::AllProp);
+    }
+
+    let mut request = None;
+    let mut props = Vec::new();
+
+    walk(body, |path, element, _| {
+        let parent = path.last();
+
+        if parent.is_some_and(|p| p.is(DAV, "propfind")) {
+            if element.is(DAV, "allprop") {
+                request = Some(PropRequest::AllProp);
+            } else if element.is(DAV, "propname") {
+                request = Some(PropRequest::PropName);
+            }
+        } else if parent.is_some_and(|p| p.is(DAV, "prop"))
+            && path.len() == 2
+        {
+            props.push(element.clone());
+        }
+    }, |_, _| {})?;
+
+    Ok(request.unwrap_or(PropRequest::Props(props)))
+}
+
+pub fn parse_report(body: &str) -> Result<Report, DavError> {
+    let mut root: Option<PropName> = None;
+    let mut request = None;
+    let mut props = Vec::new();
+    let mut hrefs = Vec::new();
+    let mut start = None;
+    let mut end = None;
+    let mut error = None;
+
+    walk(body, |path, element, start_tag| {
+        if path.is_empty() {
+            root = Some(element.clone());
+            return;
+        }
+
+        let parent = path.last();
+
+        if path.len() == 1 && element.is(DAV, "allprop") {
+            request = Some(PropRequest::AllProp);
+        } else if path.len() == 1 && element.is(DAV, "propname") {
+            request = Some(PropRequest::PropName);
+        } else if path.len() == 2 && parent.is_some_and(|p| p.is(DAV, "prop")) {
+            props.push(element.clone());
+        } else if element.is(CALDAV, "time-range")
+            && parent.is_some_and(|p| p.is(CALDAV, "comp-filter"))
+        {
+            let attr = |name: &str| {
+                start_tag
+                    .try_get_attribute(name)
+                    .ok()
+                    .flatten()
+                    .map(|a| String::from_utf8_lossy(&a.value).into_owned())
+            };
+            for (name, slot) in [("start", &mut start), ("end", &mut end)] {
+                if let Some(value) = attr(name) {
+                    match parse_utc(&value) {
+                        Some(dt) => *slot = Some(dt),
+                        None => error = Some(DavError::BadRequest(
+                            format!("invalid time-range {name}: {value}"),
+                        )),
+                    }
+                }
+            }
+        }
+    }, |path, text| {
+        if path.last().is_some_and(|p| p.is(DAV, "href")) && path.len() == 2 {
+            hrefs.push(text.to_string());
+        }
+    })?;
+
+    if let Some(error) = error {
+        return Err(error);
+    }
+
+    let root = root.ok_or_else(|| DavError::BadRequest("empty REPORT body".to_string()))?;
+    let props = request.unwrap_or(PropRequest::Props(props));
+
+    Ok(if root.is(CALDAV, "calendar-query") {
+        Report::CalendarQuery { props, start, end }
+    } else if root.is(CALDAV, "calendar-multiget") {
+        Report::Multiget { props, hrefs }
+    } else {
+        Report::Unsupported(root)
+    })
+}
+
+/// Walks the document, calling `on_element` for every element with the
+/// path of its ancestors and `on_text` for text with the full path.
+fn walk(
+    body: &str,
+    mut on_element: impl FnMut(&[PropName], &PropName, &BytesStart),
+    mut on_text: impl FnMut(&[PropName], &str),
+) -> Result<(), DavError> {
+    let mut reader = NsReader::from_str(body);
+    reader.config_mut().trim_text(true);
+
+    let mut path: Vec<PropName> = Vec::new();
+
+    loop {
+        let (namespace, event) = reader
+            .read_resolved_event()
+            .map_err(|e| DavError::BadRequest(e.to_string()))?;
+
+        match event {
+            XmlEvent::Start(e) => {
+                let element = qualified(namespace, e.local_name().as_ref());
+                on_element(&path, &element, &e);
+                path.push(element);
+            }
+            XmlEvent::Empty(e) => {
+                let element = qualified(namespace, e.local_name().as_ref());
+                on_element(&path, &element, &e);
+            }
+            XmlEvent::Text(t) => {
+                let text = t
+                    .unescape()
+                    .map_err(|e| DavError::BadRequest(e.to_string()))?;
+                on_text(&path, &text);
+            }
+            XmlEvent::End(_) => {
+                path.pop();
+            }
+            XmlEvent::Eof => break,
+            _ => {}
+        }
+    }
+
+    Ok(())
+}
+
+fn qualified(namespace: ResolveResult, local_name: &[u8]) -> PropName {
+    let namespace = match namespace {
+        ResolveResult::Bound(Namespace(ns)) => String::from_utf8_lossy(ns).into_owned(),
+        _ => String::new(),
+    };
+
+    PropName {
+        namespace,
+        name: String::from_utf8_lossy(local_name).into_owned(),
+    }
+}
+
+fn parse_utc(value: &str) -> Option<DateTime<Utc>> {
+    NaiveDateTime::parse_from_str(value, "%Y%m%dT%H%M%SZ")
+        .ok()
+        .map(|dt| dt.and_utc())
+}
+
+
+// ======================================================
+// Responses
+// ======================================================
+
+pub struct MultiStatusWriter {
+    body: String,
+}
+
+impl Default for MultiStatusWriter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl MultiStatusWriter {
+    pub fn new() -> Self {
+        let body = format!(
+            "<?xml version=\"1.0\" encoding=\"utf-8\"?>\n\
+             <d:multistatus xmlns:d=\"{DAV}\" xmlns:c=\"{CALDAV}\" xmlns:cs=\"{CALENDARSERVER}\">\n"
+        );
+        Self { body }
+    }
+
+    /// Adds a response with one propstat for the properties found and a
+    /// 404 propstat for the rest.
+    pub fn response(&mut self, href: &str, found: &[(PropName, String)], missing: &[PropName]) {
+        self.body.push_str(&format!("<d:response><d:href>{}</d:href>", escape(href)));
+
+        if !found.is_empty() {
+            self.body.push_str("<d:propstat><d:prop>");
+            for (prop, value) in found {
+                self.body.push_str(&element(prop, value));
+            }
+            self.body.push_str("</d:prop><d:status>HTTP/1.1 200 OK</d:status></d:propstat>");
+        }
+
+        if !missing.is_empty() {
+            self.body.push_str("<d:propstat><d:prop>");
+            for prop in missing {
+                self.body.push_str(&element(prop, ""));
+            }
+            self.body.push_str("</d:prop><d:status>HTTP/1.1 404 Not Found</d:status></d:propstat>");
+        }
+
+        self.body.push_str("</d:response>\n");
+    }
+
+    pub fn status_response(&mut self, href: &str, status: &str) {
+        self.body.push_str(&format!(
+            "<d:response><d:href>{}</d:href><d:status>HTTP/1.1 {status}</d:status></d:response>\n",
+            escape(href),
+        ));
+    }
+
+    pub fn finish(mut self) -> String {
+        self.body.push_str("</d:multistatus>\n");
+        self.body
+    }
+}
+
+/// Serializes a property with already-escaped inner XML.
+pub fn element(prop: &PropName, inner: &str) -> String {
+    let (tag, declaration) = match prop.namespace.as_str() {
+        DAV => (format!("d:{}", prop.name), String::new()),
+        CALDAV => (format!("c:{}", prop.name), String::new()),
+        CALENDARSERVER => (format!("cs:{}", prop.name), String::new()),
+        "" => (prop.name.clone(), " xmlns=\"\"".to_string()),
+        other => (format!("x:{}", prop.name), format!(" xmlns:x=\"{}\"", escape(other))),
+    };
+
+    if inner.is_empty() {
+        format!("<{tag}{declaration}/>")
+    } else {
+        format!("<{tag}{declaration}>{inner}</{tag}>")
+    }
+}
+
+pub fn href(value: &str) -> String {
+    format!("<d:href>{}</d:href>", escape(value))
+}
+
+pub fn escape(value: &str) -> String {
+    value
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
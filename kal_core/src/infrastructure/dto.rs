@@ -0,0 +1,1171 @@
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::{
+    application::{
+        commands::{scheduling::ReplyOutcome, tags::RetagOutcome, trash::PurgeOutcome},
+        error::ApplicationError,
+    },
+    domain::{
+        attendee::{Attendee, Organizer},
+        audit::AuditEntry,
+        calendar::Calendar,
+        domain_event::FieldChange,
+        error::DomainError,
+        event::Event,
+        free_busy::BusyPeriod,
+        history::{Change, HistoryEntry},
+        journal::{JournalEntry, JournalLink},
+        recurrence::{ExceptionModification, Occurrence, RecurrenceRule, RecurringEvent},
+        reminder::{Reminder, ReminderAction, ReminderState, ReminderTrigger, MAX_REMINDER_SPAN},
+        search::SearchHit,
+        tag::{Tag, TagUsage},
+        task::Task,
+        value_objects::{EventId, EventStatus, GeoPoint, Transparency},
+    },
+    infrastructure::itip::ItipMessage,
+};
+
+
+// ======================================================
+// Responses
+// ======================================================
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CreatedDto {
+    pub id: Uuid,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CalendarDto {
+    pub id: Uuid,
+    pub name: String,
+    pub description: Option<String>,
+    pub is_archived: bool,
+    pub subscription_url: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl From<&Calendar> for CalendarDto {
+    fn from(calendar: &Calendar) -> Self {
+        Self {
+            id: calendar.calendar_id().as_uuid(),
+            name: calendar.name().clone(),
+            description: calendar.description().clone(),
+            is_archived: *calendar.is_archived(),
+            subscription_url: calendar
+                .subscription()
+                .as_ref()
+                .map(|s| s.source_url().clone()),
+            created_at: *calendar.created_at(),
+            updated_at: *calendar.updated_at(),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct EventDto {
+    pub id: Uuid,
+    pub calendar_id: Uuid,
+    pub title: String,
+    pub description: Option<String>,
+    pub location: Option<String>,
+    pub url: Option<String>,
+    pub geo: Option<GeoDto>,
+    pub starts_at: DateTime<Utc>,
+    pub ends_at: DateTime<Utc>,
+    pub color: u8,
+    pub is_all_day: bool,
+    pub is_cancelled: bool,
+    pub status: String,
+    pub transparency: String,
+    pub sequence: u32,
+    pub reminders: Vec<ReminderDto>,
+    pub organizer: Option<OrganizerDto>,
+    pub attendees: Vec<AttendeeDto>,
+    pub tags: Vec<String>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl From<&Event> for EventDto {
+    fn from(event: &Event) -> Self {
+        Self {
+            id: event.event_id().as_uuid(),
+            calendar_id: event.calendar_id().as_uuid(),
+            title: event.title().clone(),
+            description: event.description().clone(),
+            location: event.location().clone(),
+            url: event.url().clone(),
+            geo: event.geo().as_ref().map(GeoDto::from),
+            starts_at: *event.time_range().starts_at(),
+            ends_at: *event.time_range().ends_at(),
+            color: (*event.color()).into(),
+            is_all_day: *event.is_all_day(),
+            is_cancelled: event.is_cancelled(),
+            status: event.status().to_string(),
+            transparency: event.transparency().to_string(),
+            sequence: *event.sequence(),
+            reminders: event.reminders().iter().map(ReminderDto::from).collect(),
+            organizer: event.organizer().as_ref().map(OrganizerDto::from),
+            attendees: event.attendees().iter().map(AttendeeDto::from).collect(),
+            tags: event.tags().iter().map(Tag::to_string).collect(),
+            created_at: *event.created_at(),
+            updated_at: *event.updated_at(),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct GeoDto {
+    pub latitude: f64,
+    pub longitude: f64,
+}
+
+impl From<&GeoPoint> for GeoDto {
+    fn from(geo: &GeoPoint) -> Self {
+        Self {
+            latitude: *geo.latitude(),
+            longitude: *geo.longitude(),
+        }
+    }
+}
+
+impl GeoDto {
+    pub fn point(&self) -> Result<GeoPoint, DomainError> {
+        GeoPoint::new(self.latitude, self.longitude)
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ReminderDto {
+    pub id: Uuid,
+    /// Seconds relative to the start; set for relative triggers.
+    pub offset_seconds: Option<i64>,
+    /// Set for absolute triggers.
+    pub at: Option<DateTime<Utc>>,
+    pub action: String,
+    pub target: Option<String>,
+    pub description: Option<String>,
+    pub repeat: u32,
+    pub repeat_interval_seconds: Option<i64>,
+}
+
+impl From<&Reminder> for ReminderDto {
+    fn from(reminder: &Reminder) -> Self {
+        let (offset_seconds, at) = match reminder.trigger() {
+            ReminderTrigger::Relative(offset) => (Some(offset.num_seconds()), None),
+            ReminderTrigger::Absolute(at) => (None, Some(*at)),
+        };
+
+        Self {
+            id: reminder.reminder_id().as_uuid(),
+            offset_seconds,
+            at,
+            action: reminder.action().kind().to_lowercase(),
+            target: reminder.action().target().map(str::to_string),
+            description: reminder.description().clone(),
+            repeat: *reminder.repeat(),
+            repeat_interval_seconds: reminder.repeat_interval().map(|d| d.num_seconds()),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct OrganizerDto {
+    pub email: String,
+    pub name: Option<String>,
+}
+
+impl From<&Organizer> for OrganizerDto {
+    fn from(organizer: &Organizer) -> Self {
+        Self {
+            email: organizer.email().clone(),
+            name: organizer.name().clone(),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct AttendeeDto {
+    pub email: String,
+    pub name: Option<String>,
+    pub role: String,
+    pub status: String,
+    pub rsvp: bool,
+}
+
+impl From<&Attendee> for AttendeeDto {
+    fn from(attendee: &Attendee) -> Self {
+        Self {
+            email: attendee.email().clone(),
+            name: attendee.name().clone(),
+            role: attendee.role().to_string().to_lowercase(),
+            status: attendee.status().to_string().to_lowercase(),
+            rsvp: *attendee.rsvp(),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct TagDto {
+    pub name: String,
+    pub count: u32,
+}
+
+impl From<&TagUsage> for TagDto {
+    fn from(usage: &TagUsage) -> Self {
+        Self {
+            name: usage.tag().to_string(),
+            count: *usage.count(),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct RetagOutcomeDto {
+    pub updated: u32,
+    /// Objects in read-only subscriptions, left as they were.
+    pub skipped: u32,
+}
+
+impl From<RetagOutcome> for RetagOutcomeDto {
+    fn from(outcome: RetagOutcome) -> Self {
+        Self {
+            updated: outcome.updated,
+            skipped: outcome.skipped,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ReminderStateDto {
+    pub event_id: Uuid,
+    pub original_starts_at: DateTime<Utc>,
+    pub reminder_id: Uuid,
+    pub fired_count: u32,
+    pub last_fired_at: Option<DateTime<Utc>>,
+    pub snoozed_until: Option<DateTime<Utc>>,
+    pub dismissed_at: Option<DateTime<Utc>>,
+}
+
+impl From<&ReminderState> for ReminderStateDto {
+    fn from(state: &ReminderState) -> Self {
+        Self {
+            event_id: state.key().event_id().as_uuid(),
+            original_starts_at: *state.key().original_starts_at(),
+            reminder_id: state.key().reminder_id().as_uuid(),
+            fired_count: *state.fired_count(),
+            last_fired_at: *state.last_fired_at(),
+            snoozed_until: *state.snoozed_until(),
+            dismissed_at: *state.dismissed_at(),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ExceptionDto {
+    pub original_starts_at: DateTime<Utc>,
+    pub is_cancelled: bool,
+    pub starts_at: Option<DateTime<Utc>>,
+    pub ends_at: Option<DateTime<Utc>>,
+    pub location: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct RecurringEventDto {
+    pub id: Uuid,
+    pub calendar_id: Uuid,
+    pub title: String,
+    pub description: Option<String>,
+    pub location: Option<String>,
+    pub url: Option<String>,
+    pub geo: Option<GeoDto>,
+    pub starts_at: DateTime<Utc>,
+    pub ends_at: DateTime<Utc>,
+    pub frequency: String,
+    pub interval: u32,
+    pub until: Option<DateTime<Utc>>,
+    pub exceptions: Vec<ExceptionDto>,
+    pub reminders: Vec<ReminderDto>,
+    pub organizer: Option<OrganizerDto>,
+    pub attendees: Vec<AttendeeDto>,
+    pub tags: Vec<String>,
+    pub color: u8,
+    pub is_all_day: bool,
+    pub is_cancelled: bool,
+    pub status: String,
+    pub transparency: String,
+    pub sequence: u32,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl From<&RecurringEvent> for RecurringEventDto {
+    fn from(event: &RecurringEvent) -> Self {
+        let mut exceptions: Vec<ExceptionDto> = event
+            .exceptions()
+            .values()
+            .map(|exception| {
+                let new_time_range = match exception.modification() {
+                    ExceptionModification::Cancelled => None,
+                    ExceptionModification::Rescheduled { new_time_range } => Some(new_time_range),
+                };
+
+                ExceptionDto {
+                    original_starts_at: *exception.original_starts_at(),
+                    is_cancelled: new_time_range.is_none(),
+                    starts_at: new_time_range.map(|r| *r.starts_at()),
+                    ends_at: new_time_range.map(|r| *r.ends_at()),
+                    location: exception.location().clone(),
+                }
+            })
+            .collect();
+        exceptions.sort_by_key(|e| e.original_starts_at);
+
+        Self {
+            id: event.event_id().as_uuid(),
+            calendar_id: event.calendar_id().as_uuid(),
+            title: event.title().clone(),
+            description: event.description().clone(),
+            location: event.location().clone(),
+            url: event.url().clone(),
+            geo: event.geo().as_ref().map(GeoDto::from),
+            starts_at: *event.time_range().starts_at(),
+            ends_at: *event.time_range().ends_at(),
+            frequency: event.rule().frequency().to_string(),
+            interval: *event.rule().interval(),
+            until: *event.rule().until(),
+            exceptions,
+            reminders: event.reminders().iter().map(ReminderDto::from).collect(),
+            organizer: event.organizer().as_ref().map(OrganizerDto::from),
+            attendees: event.attendees().iter().map(AttendeeDto::from).collect(),
+            tags: event.tags().iter().map(Tag::to_string).collect(),
+            color: (*event.color()).into(),
+            is_all_day: *event.is_all_day(),
+            is_cancelled: event.is_cancelled(),
+            status: event.status().to_string(),
+            transparency: event.transparency().to_string(),
+            sequence: *event.sequence(),
+            created_at: *event.created_at(),
+            updated_at: *event.updated_at(),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct OccurrenceDto {
+    pub original_starts_at: DateTime<Utc>,
+    pub starts_at: DateTime<Utc>,
+    pub ends_at: DateTime<Utc>,
+    pub location: Option<String>,
+}
+
+impl From<&Occurrence> for OccurrenceDto {
+    fn from(occurrence: &Occurrence) -> Self {
+        Self {
+            original_starts_at: *occurrence.original_starts_at(),
+            starts_at: *occurrence.time_range().starts_at(),
+            ends_at: *occurrence.time_range().ends_at(),
+            location: occurrence.location().clone(),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct BusyPeriodDto {
+    pub event_id: Uuid,
+    pub starts_at: DateTime<Utc>,
+    pub ends_at: DateTime<Utc>,
+    /// BUSY or BUSY-TENTATIVE
+    pub kind: String,
+}
+
+impl From<&BusyPeriod> for BusyPeriodDto {
+    fn from(period: &BusyPeriod) -> Self {
+        Self {
+            event_id: period.event_id().as_uuid(),
+            starts_at: *period.time_range().starts_at(),
+            ends_at: *period.time_range().ends_at(),
+            kind: period.kind().to_string(),
+        }
+    }
+}
+
+/// An event or series matching a search. For a series, the time is its
+/// first occurrence in the searched window.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SearchHitDto {
+    pub event_id: Uuid,
+    pub calendar_id: Uuid,
+    /// EVENT or SERIES
+    pub kind: String,
+    pub title: String,
+    pub starts_at: DateTime<Utc>,
+    pub ends_at: DateTime<Utc>,
+    /// Matched words are wrapped in `<mark>` and `</mark>`.
+    pub snippet: String,
+    /// Lower is a better match.
+    pub score: f64,
+}
+
+impl From<&SearchHit> for SearchHitDto {
+    fn from(hit: &SearchHit) -> Self {
+        Self {
+            event_id: hit.event_id().as_uuid(),
+            calendar_id: hit.calendar_id().as_uuid(),
+            kind: hit.kind().to_string(),
+            title: hit.title().clone(),
+            starts_at: *hit.time_range().starts_at(),
+            ends_at: *hit.time_range().ends_at(),
+            snippet: hit.snippet().clone(),
+            score: *hit.score(),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct TaskDto {
+    pub id: Uuid,
+    pub calendar_id: Uuid,
+    pub title: String,
+    pub description: Option<String>,
+    pub starts_at: Option<DateTime<Utc>>,
+    pub due_at: Option<DateTime<Utc>>,
+    pub priority: Option<u8>,
+    pub percent_complete: u8,
+    pub status: String,
+    pub completed_at: Option<DateTime<Utc>>,
+    pub recurrence: Option<RecurrenceDto>,
+    pub is_overdue: bool,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl From<&Task> for TaskDto {
+    fn from(task: &Task) -> Self {
+        Self {
+            id: task.task_id().as_uuid(),
+            calendar_id: task.calendar_id().as_uuid(),
+            title: task.title().clone(),
+            description: task.description().clone(),
+            starts_at: *task.starts_at(),
+            due_at: *task.due_at(),
+            priority: *task.priority(),
+            percent_complete: *task.percent_complete(),
+            status: task.status().to_string(),
+            completed_at: *task.completed_at(),
+            recurrence: task.rule().as_ref().map(RecurrenceDto::from),
+            is_overdue: task.is_overdue(Utc::now()),
+            created_at: *task.created_at(),
+            updated_at: *task.updated_at(),
+        }
+    }
+}
+
+/// How a task repeats.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct RecurrenceDto {
+    pub frequency: String,
+    #[serde(default = "one")]
+    pub interval: u32,
+    pub until: Option<DateTime<Utc>>,
+}
+
+impl From<&RecurrenceRule> for RecurrenceDto {
+    fn from(rule: &RecurrenceRule) -> Self {
+        Self {
+            frequency: rule.frequency().to_string(),
+            interval: *rule.interval(),
+            until: *rule.until(),
+        }
+    }
+}
+
+impl RecurrenceDto {
+    pub fn rule(&self) -> Result<RecurrenceRule, DomainError> {
+        RecurrenceRule::new(self.frequency.parse()?, self.interval, self.until)
+    }
+}
+
+/// How many objects an `.ics` import saved, and which were skipped and
+/// why.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ImportReportDto {
+    pub imported: usize,
+    pub skipped: Vec<SkippedDto>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SkippedDto {
+    pub uid: String,
+    pub reason: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct JournalEntryDto {
+    pub id: Uuid,
+    pub calendar_id: Uuid,
+    pub date: NaiveDate,
+    pub title: String,
+    pub body: String,
+    pub link: Option<JournalLinkDto>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl From<&JournalEntry> for JournalEntryDto {
+    fn from(entry: &JournalEntry) -> Self {
+        Self {
+            id: entry.journal_id().as_uuid(),
+            calendar_id: entry.calendar_id().as_uuid(),
+            date: *entry.date(),
+            title: entry.title().clone(),
+            body: entry.body().clone(),
+            link: entry.link().as_ref().map(JournalLinkDto::from),
+            created_at: *entry.created_at(),
+            updated_at: *entry.updated_at(),
+        }
+    }
+}
+
+/// The event or series a journal entry is about, narrowed to one
+/// occurrence when `original_starts_at` is set.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct JournalLinkDto {
+    pub event_id: Uuid,
+    pub original_starts_at: Option<DateTime<Utc>>,
+}
+
+impl From<&JournalLink> for JournalLinkDto {
+    fn from(link: &JournalLink) -> Self {
+        Self {
+            event_id: link.event_id().as_uuid(),
+            original_starts_at: link.original_starts_at(),
+        }
+    }
+}
+
+impl JournalLinkDto {
+    pub fn link(&self) -> JournalLink {
+        let event_id = EventId::from_uuid(self.event_id);
+        match self.original_starts_at {
+            Some(original_starts_at) => JournalLink::Occurrence(event_id, original_starts_at),
+            None => JournalLink::Event(event_id),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ItipMessageDto {
+    pub method: String,
+    pub uid: String,
+    pub sequence: u32,
+    pub recipients: Vec<String>,
+    pub file_name: String,
+    pub ics: String,
+}
+
+impl From<&ItipMessage> for ItipMessageDto {
+    fn from(message: &ItipMessage) -> Self {
+        Self {
+            method: message.method().to_string(),
+            uid: message.uid().clone(),
+            sequence: *message.sequence(),
+            recipients: message.recipients().clone(),
+            file_name: message.file_name(),
+            ics: message.to_ics(),
+        }
+    }
+}
+
+/// A calendar, event or series waiting in the trash. `calendar_id` is
+/// the calendar's own id for calendars.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct TrashedItemDto {
+    pub id: Uuid,
+    pub calendar_id: Uuid,
+    /// CALENDAR, EVENT or SERIES
+    pub kind: String,
+    pub title: String,
+    pub deleted_at: DateTime<Utc>,
+}
+
+impl From<&Calendar> for TrashedItemDto {
+    fn from(calendar: &Calendar) -> Self {
+        Self {
+            id: calendar.calendar_id().as_uuid(),
+            calendar_id: calendar.calendar_id().as_uuid(),
+            kind: "CALENDAR".to_string(),
+            title: calendar.name().clone(),
+            deleted_at: calendar.deleted_at().unwrap_or_default(),
+        }
+    }
+}
+
+impl From<&Event> for TrashedItemDto {
+    fn from(event: &Event) -> Self {
+        Self {
+            id: event.event_id().as_uuid(),
+            calendar_id: event.calendar_id().as_uuid(),
+            kind: "EVENT".to_string(),
+            title: event.title().clone(),
+            deleted_at: event.deleted_at().unwrap_or_default(),
+        }
+    }
+}
+
+impl From<&RecurringEvent> for TrashedItemDto {
+    fn from(event: &RecurringEvent) -> Self {
+        Self {
+            id: event.event_id().as_uuid(),
+            calendar_id: event.calendar_id().as_uuid(),
+            kind: "SERIES".to_string(),
+            title: event.title().clone(),
+            deleted_at: event.deleted_at().unwrap_or_default(),
+        }
+    }
+}
+
+/// How many items a purge removed for good.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PurgeOutcomeDto {
+    pub calendars: u64,
+    pub events: u64,
+    pub series: u64,
+}
+
+impl From<PurgeOutcome> for PurgeOutcomeDto {
+    fn from(outcome: PurgeOutcome) -> Self {
+        Self {
+            calendars: outcome.calendars,
+            events: outcome.events,
+            series: outcome.series,
+        }
+    }
+}
+
+/// A recorded command that can be undone or redone.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct HistoryEntryDto {
+    pub id: i64,
+    /// The method that ran, such as `event.delete`
+    pub command: String,
+    pub recorded_at: DateTime<Utc>,
+    pub changes: Vec<HistoryChangeDto>,
+}
+
+impl From<&HistoryEntry> for HistoryEntryDto {
+    fn from(entry: &HistoryEntry) -> Self {
+        Self {
+            id: *entry.id(),
+            command: entry.command().clone(),
+            recorded_at: *entry.recorded_at(),
+            changes: entry.changes().iter().map(HistoryChangeDto::from).collect(),
+        }
+    }
+}
+
+/// One object a recorded command touched.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct HistoryChangeDto {
+    pub id: Uuid,
+    /// CALENDAR, EVENT, SERIES, TASK or JOURNAL
+    pub kind: String,
+    pub title: Option<String>,
+    /// created, updated, trashed, restored or deleted
+    pub action: String,
+}
+
+impl From<&Change> for HistoryChangeDto {
+    fn from(change: &Change) -> Self {
+        let action = match (change.before(), change.after()) {
+            (None, _) => "created",
+            (_, None) => "deleted",
+            (Some(before), Some(after)) => match (before.is_trashed(), after.is_trashed()) {
+                (false, true) => "trashed",
+                (true, false) => "restored",
+                _ => "updated",
+            },
+        };
+
+        Self {
+            id: *change.object_id(),
+            kind: change.kind().to_string(),
+            title: change.title().map(str::to_string),
+            action: action.to_string(),
+        }
+    }
+}
+
+/// One audit log entry: who changed an object, when, and how.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct AuditEntryDto {
+    pub id: i64,
+    pub recorded_at: DateTime<Utc>,
+    pub actor: String,
+    /// The method that ran, such as `event.update`
+    pub command: String,
+    /// The domain event, such as `EventRescheduled`
+    pub event: String,
+    pub calendar_id: Uuid,
+    pub object_id: Uuid,
+    pub occurrence: Option<DateTime<Utc>>,
+    pub changes: Vec<FieldChangeDto>,
+}
+
+impl From<&AuditEntry> for AuditEntryDto {
+    fn from(entry: &AuditEntry) -> Self {
+        Self {
+            id: *entry.id(),
+            recorded_at: *entry.recorded_at(),
+            actor: entry.actor().clone(),
+            command: entry.command().clone(),
+            event: entry.event().clone(),
+            calendar_id: entry.calendar_id().as_uuid(),
+            object_id: *entry.object_id(),
+            occurrence: *entry.occurrence(),
+            changes: entry.changes().iter().map(FieldChangeDto::from).collect(),
+        }
+    }
+}
+
+/// One field an audited event changed; `from` is unset for added values
+/// and `to` for removed ones.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct FieldChangeDto {
+    pub field: String,
+    pub from: Option<String>,
+    pub to: Option<String>,
+}
+
+impl From<&FieldChange> for FieldChangeDto {
+    fn from(change: &FieldChange) -> Self {
+        Self {
+            field: change.field().clone(),
+            from: change.from().clone(),
+            to: change.to().clone(),
+        }
+    }
+}
+
+/// `current` is set when the reply answered an outdated revision and
+/// was ignored.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ReplyOutcomeDto {
+    pub applied: bool,
+    pub current: Option<u32>,
+}
+
+impl From<ReplyOutcome> for ReplyOutcomeDto {
+    fn from(outcome: ReplyOutcome) -> Self {
+        match outcome {
+            ReplyOutcome::Applied => Self { applied: true, current: None },
+            ReplyOutcome::Outdated { current } => Self { applied: false, current: Some(current) },
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct ErrorDto {
+    pub code: &'static str,
+    pub message: String,
+}
+
+/// Stable machine-readable code for an application error.
+pub fn error_code(error: &ApplicationError) -> &'static str {
+    match error {
+        ApplicationError::CalendarNotFound => "calendar_not_found",
+        ApplicationError::EventNotFound => "event_not_found",
+        ApplicationError::TaskNotFound => "task_not_found",
+        ApplicationError::JournalEntryNotFound => "journal_entry_not_found",
+        ApplicationError::RecurringEventNotFound => "recurring_event_not_found",
+        ApplicationError::NotInTrash => "not_in_trash",
+        ApplicationError::NothingToUndo => "nothing_to_undo",
+        ApplicationError::NothingToRedo => "nothing_to_redo",
+        ApplicationError::CalendarPurged(..) => "calendar_purged",
+        ApplicationError::Domain(DomainError::ReminderNotFound(_)) => "reminder_not_found",
+        ApplicationError::Domain(DomainError::AttendeeNotFound(_)) => "attendee_not_found",
+        ApplicationError::Domain(DomainError::TagNotFound(_)) => "tag_not_found",
+        ApplicationError::Domain(
+            DomainError::SubscriptionReadOnly | DomainError::CalendarArchived,
+        ) => "read_only",
+        ApplicationError::Domain(_) => "invalid",
+        ApplicationError::Validation(_) => "validation",
+        ApplicationError::Conflict => "conflict",
+        ApplicationError::Repository(_) => "internal",
+    }
+}
+
+
+// ======================================================
+// Requests
+// ======================================================
+
+#[derive(Debug, Deserialize)]
+pub struct CreateCalendarDto {
+    pub name: String,
+    pub description: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SubscribeCalendarDto {
+    pub name: String,
+    pub description: Option<String>,
+    pub url: String,
+    #[serde(default = "hourly")]
+    pub refresh_minutes: i64,
+}
+
+impl SubscribeCalendarDto {
+    /// More minutes than a duration can hold is as out of range as too few.
+    pub fn refresh_interval(&self) -> Result<chrono::Duration, DomainError> {
+        chrono::Duration::try_minutes(self.refresh_minutes)
+            .ok_or(DomainError::InvalidRefreshInterval)
+    }
+}
+
+/// Only the fields present are changed. `description: null` clears it.
+#[derive(Debug, Deserialize)]
+pub struct UpdateCalendarDto {
+    pub name: Option<String>,
+    #[serde(default, deserialize_with = "present")]
+    pub description: Option<Option<String>>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CreateEventDto {
+    pub title: String,
+    pub description: Option<String>,
+    pub location: Option<String>,
+    pub url: Option<String>,
+    pub geo: Option<GeoDto>,
+    pub starts_at: DateTime<Utc>,
+    pub ends_at: DateTime<Utc>,
+    #[serde(default)]
+    pub color: u8,
+    #[serde(default)]
+    pub is_all_day: bool,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    pub status: Option<String>,
+    pub transparency: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct UpdateEventDto {
+    pub title: Option<String>,
+    #[serde(default, deserialize_with = "present")]
+    pub description: Option<Option<String>>,
+    pub starts_at: Option<DateTime<Utc>>,
+    pub ends_at: Option<DateTime<Utc>>,
+    pub color: Option<u8>,
+    #[serde(default, deserialize_with = "present")]
+    pub location: Option<Option<String>>,
+    #[serde(default, deserialize_with = "present")]
+    pub url: Option<Option<String>>,
+    #[serde(default, deserialize_with = "present")]
+    pub geo: Option<Option<GeoDto>>,
+    pub status: Option<String>,
+    pub transparency: Option<String>,
+}
+
+/// Only the fields present are changed; `null` clears a field.
+#[derive(Debug, Deserialize)]
+pub struct UpdateRecurringDto {
+    #[serde(default, deserialize_with = "present")]
+    pub location: Option<Option<String>>,
+    #[serde(default, deserialize_with = "present")]
+    pub url: Option<Option<String>>,
+    #[serde(default, deserialize_with = "present")]
+    pub geo: Option<Option<GeoDto>>,
+    pub status: Option<String>,
+    pub transparency: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CreateRecurringEventDto {
+    pub title: String,
+    pub description: Option<String>,
+    pub location: Option<String>,
+    pub url: Option<String>,
+    pub geo: Option<GeoDto>,
+    pub starts_at: DateTime<Utc>,
+    pub ends_at: DateTime<Utc>,
+    pub frequency: String,
+    #[serde(default = "one")]
+    pub interval: u32,
+    pub until: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub color: u8,
+    #[serde(default)]
+    pub is_all_day: bool,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    pub status: Option<String>,
+    pub transparency: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CreateTaskDto {
+    pub title: String,
+    pub description: Option<String>,
+    pub starts_at: Option<DateTime<Utc>>,
+    pub due_at: Option<DateTime<Utc>>,
+    pub priority: Option<u8>,
+    pub recurrence: Option<RecurrenceDto>,
+}
+
+/// Only the fields present are changed; `null` clears a field.
+#[derive(Debug, Deserialize)]
+pub struct UpdateTaskDto {
+    pub title: Option<String>,
+    #[serde(default, deserialize_with = "present")]
+    pub description: Option<Option<String>>,
+    #[serde(default, deserialize_with = "present")]
+    pub starts_at: Option<Option<DateTime<Utc>>>,
+    #[serde(default, deserialize_with = "present")]
+    pub due_at: Option<Option<DateTime<Utc>>>,
+    #[serde(default, deserialize_with = "present")]
+    pub priority: Option<Option<u8>>,
+    pub percent_complete: Option<u8>,
+    #[serde(default, deserialize_with = "present")]
+    pub recurrence: Option<Option<RecurrenceDto>>,
+}
+
+/// A `.ics` document holding VTODOs.
+#[derive(Debug, Deserialize)]
+pub struct ImportTasksDto {
+    pub ics: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CreateJournalEntryDto {
+    pub date: NaiveDate,
+    pub title: String,
+    #[serde(default)]
+    pub body: String,
+    pub link: Option<JournalLinkDto>,
+}
+
+/// Only the fields present are changed; `"link": null` unlinks the entry.
+#[derive(Debug, Deserialize)]
+pub struct UpdateJournalEntryDto {
+    pub date: Option<NaiveDate>,
+    pub title: Option<String>,
+    pub body: Option<String>,
+    #[serde(default, deserialize_with = "present")]
+    pub link: Option<Option<JournalLinkDto>>,
+}
+
+/// A `.ics` document holding VJOURNALs.
+#[derive(Debug, Deserialize)]
+pub struct ImportJournalDto {
+    pub ics: String,
+}
+
+/// Cancels an occurrence, or moves it when a new time is given.
+#[derive(Debug, Deserialize)]
+pub struct OccurrenceExceptionDto {
+    pub original_starts_at: DateTime<Utc>,
+    pub starts_at: Option<DateTime<Utc>>,
+    pub ends_at: Option<DateTime<Utc>>,
+}
+
+/// `location: null` puts the occurrence back at the series' location.
+#[derive(Debug, Deserialize)]
+pub struct OccurrenceLocationDto {
+    pub original_starts_at: DateTime<Utc>,
+    pub location: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CreateReminderDto {
+    pub offset_seconds: Option<i64>,
+    pub at: Option<DateTime<Utc>>,
+    #[serde(default = "display")]
+    pub action: String,
+    pub target: Option<String>,
+    pub description: Option<String>,
+    #[serde(default)]
+    pub repeat: u32,
+    pub repeat_interval_seconds: Option<i64>,
+}
+
+impl CreateReminderDto {
+    pub fn trigger(&self) -> Result<ReminderTrigger, ApplicationError> {
+        match (self.offset_seconds, self.at) {
+            (Some(offset), None) => Ok(ReminderTrigger::Relative(seconds("offset_seconds", offset)?)),
+            (None, Some(at)) => Ok(ReminderTrigger::Absolute(at)),
+            _ => Err(DomainError::InvalidReminder(
+                "exactly one of `offset_seconds` and `at` is required".into(),
+            )
+            .into()),
+        }
+    }
+
+    pub fn action(&self) -> Result<ReminderAction, DomainError> {
+        ReminderAction::from_parts(&self.action, self.target.clone())
+    }
+
+    pub fn repeat_interval(&self) -> Result<Option<chrono::Duration>, ApplicationError> {
+        self.repeat_interval_seconds
+            .map(|interval| seconds("repeat_interval_seconds", interval))
+            .transpose()
+    }
+}
+
+/// `field`'s seconds, refused when there are more than a duration can hold.
+fn seconds(field: &str, seconds: i64) -> Result<chrono::Duration, ApplicationError> {
+    chrono::Duration::try_seconds(seconds)
+        .ok_or_else(|| ApplicationError::Validation(format!("`{field}` is out of range")))
+}
+
+/// `email: null` removes the organizer.
+#[derive(Debug, Deserialize)]
+pub struct SetOrganizerDto {
+    pub email: Option<String>,
+    pub name: Option<String>,
+}
+
+impl SetOrganizerDto {
+    pub fn organizer(&self) -> Result<Option<Organizer>, DomainError> {
+        self.email
+            .clone()
+            .map(|email| Organizer::new(email, self.name.clone()))
+            .transpose()
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CreateAttendeeDto {
+    pub email: String,
+    pub name: Option<String>,
+    #[serde(default = "req_participant")]
+    pub role: String,
+    #[serde(default = "needs_action")]
+    pub status: String,
+    #[serde(default)]
+    pub rsvp: bool,
+}
+
+impl CreateAttendeeDto {
+    pub fn attendee(&self) -> Result<Attendee, DomainError> {
+        Attendee::new(
+            self.email.clone(),
+            self.name.clone(),
+            self.role.parse()?,
+            self.status.parse()?,
+            self.rsvp,
+        )
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct TagNameDto {
+    pub tag: String,
+}
+
+impl TagNameDto {
+    pub fn tag(&self) -> Result<Tag, DomainError> {
+        Tag::new(&self.tag)
+    }
+}
+
+/// Renames `from` to `to`, or merges `from` into `to`.
+#[derive(Debug, Deserialize)]
+pub struct RetagDto {
+    pub from: String,
+    pub to: String,
+}
+
+impl RetagDto {
+    pub fn tags(&self) -> Result<(Tag, Tag), DomainError> {
+        Ok((Tag::new(&self.from)?, Tag::new(&self.to)?))
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct AttendeeStatusDto {
+    pub status: String,
+}
+
+/// `original_starts_at` picks the occurrence of a recurring series.
+#[derive(Debug, Deserialize)]
+pub struct SnoozeReminderDto {
+    pub original_starts_at: Option<DateTime<Utc>>,
+    pub minutes: i64,
+}
+
+impl SnoozeReminderDto {
+    /// `minutes` from now, which must be positive and within ten years.
+    pub fn until(&self) -> Result<DateTime<Utc>, ApplicationError> {
+        chrono::Duration::try_minutes(self.minutes)
+            .filter(|snooze| *snooze > chrono::Duration::zero() && *snooze <= MAX_REMINDER_SPAN)
+            .and_then(|snooze| Utc::now().checked_add_signed(snooze))
+            .ok_or_else(|| {
+                ApplicationError::Validation(
+                    "`minutes` must be positive and at most ten years".into(),
+                )
+            })
+    }
+}
+
+/// A received METHOD:REPLY document.
+#[derive(Debug, Deserialize)]
+pub struct ItipReplyDto {
+    pub ics: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct DismissReminderDto {
+    pub original_starts_at: Option<DateTime<Utc>>,
+}
+
+/// Parses tag names, e.g. from a create request or a filter.
+pub fn parse_tags(names: &[String]) -> Result<Vec<Tag>, DomainError> {
+    names.iter().map(Tag::new).collect()
+}
+
+/// Parses the optional status and transparency of a create request;
+/// missing values fall back to confirmed and opaque.
+pub fn parse_availability(
+    status: Option<&str>,
+    transparency: Option<&str>,
+) -> Result<(EventStatus, Transparency), DomainError> {
+    Ok((
+        status.map(str::parse).transpose()?.unwrap_or_default(),
+        transparency.map(str::parse).transpose()?.unwrap_or_default(),
+    ))
+}
+
+fn display() -> String {
+    "display".to_string()
+}
+
+fn req_participant() -> String {
+    "req-participant".to_string()
+}
+
+fn needs_action() -> String {
+    "needs-action".to_string()
+}
+
+fn hourly() -> i64 {
+    60
+}
+
+fn one() -> u32 {
+    1
+}
+
+/// Distinguishes an explicit `null` from a missing field.
+fn present<'de, D, T>(deserializer: D) -> Result<Option<Option<T>>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+    T: Deserialize<'de>,
+{
+    Option::<T>::deserialize(deserializer).map(Some)
+}
@@ -0,0 +1,305 @@
+use super::error::IcalError;
+
+type IcalResult<T> = Result<T, IcalError>;
+
+/// Maximum line length in octets before folding (RFC 5545 section 3.1).
+const FOLD_WIDTH: usize = 75;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Property {
+    pub name: String,
+    pub params: Vec<(String, String)>,
+    pub value: String,
+}
+
+impl Property {
+    pub fn new(name: impl Into<String>, value: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            params: Vec::new(),
+            value: value.into(),
+        }
+    }
+
+    pub fn with_param(
+        mut self,
+        name: impl Into<String>,
+        value: impl Into<String>,
+    ) -> Self {
+        self.params.push((name.into(), value.into()));
+        self
+    }
+
+    pub fn param(&self, name: &str) -> Option<&str> {
+        self.params
+            .iter()
+            .find(|(n, _)| n.eq_ignore_ascii_case(name))
+            .map(|(_, v)| v.as_str())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Component {
+    pub name: String,
+    pub properties: Vec<Property>,
+    pub components: Vec<Component>,
+}
+
+impl Component {
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            properties: Vec::new(),
+            components: Vec::new(),
+        }
+    }
+
+    pub fn push(&mut self, property: Property) {
+        self.properties.push(property);
+    }
+
+    pub fn property(&self, name: &str) -> Option<&Property> {
+        self.properties
+            .iter()
+            .find(|p| p.name.eq_ignore_ascii_case(name))
+    }
+
+    pub fn properties_named<'a>(
+        &'a self,
+        name: &'a str,
+    ) -> impl Iterator<Item = &'a Property> + 'a {
+        self.properties
+            .iter()
+            .filter(move |p| p.name.eq_ignore_ascii_case(name))
+    }
+
+    pub fn components_named<'a>(
+        &'a self,
+        name: &'a str,
+    ) -> impl Iterator<Item = &'a Component> + 'a {
+        self.components
+            .iter()
+            .filter(move |c| c.name.eq_ignore_ascii_case(name))
+    }
+
+    /// Parses every top-level component (usually a single `VCALENDAR`).
+    pub fn parse_all(input: &str) -> IcalResult<Vec<Component>> {
+        let mut stack: Vec<Component> = Vec::new();
+        let mut roots = Vec::new();
+
+        for line in unfold(input) {
+            if line.trim().is_empty() {
+                continue;
+            }
+
+            let property = parse_line(&line)?;
+
+            if property.name.eq_ignore_ascii_case("BEGIN") {
+                stack.push(Component::new(property.value.to_uppercase()));
+            } else if property.name.eq_ignore_ascii_case("END") {
+                let component = stack
+                    .pop()
+                    .ok_or_else(|| IcalError::UnbalancedComponent(property.value.clone()))?;
+
+                if !component.name.eq_ignore_ascii_case(&property.value) {
+                    return Err(IcalError::UnbalancedComponent(property.value));
+                }
+
+                match stack.last_mut() {
+                    Some(parent) => parent.components.push(component),
+                    None => roots.push(component),
+                }
+            } else {
+                match stack.last_mut() {
+                    Some(current) => current.properties.push(property),
+                    None => return Err(IcalError::MalformedLine(line)),
+                }
+            }
+        }
+
+        if let Some(open) = stack.pop() {
+            return Err(IcalError::UnbalancedComponent(open.name));
+        }
+
+        Ok(roots)
+    }
+
+    /// Parses a single top-level component.
+    pub fn parse(input: &str) -> IcalResult<Component> {
+        Self::parse_all(input)?
+            .into_iter()
+            .next()
+            .ok_or_else(|| IcalError::UnbalancedComponent("empty input".into()))
+    }
+
+    /// Serializes the component with CRLF line endings and folding.
+    pub fn to_ics(&self) -> String {
+        let mut out = String::new();
+        self.write_into(&mut out);
+        out
+    }
+
+    fn write_into(&self, out: &mut String) {
+        write_folded(out, &format!("BEGIN:{}", self.name));
+
+        for property in &self.properties {
+            let mut line = property.name.clone();
+            for (name, value) in &property.params {
+                line.push(';');
+                line.push_str(name);
+                line.push('=');
+                if value.contains([':', ';', ',']) {
+                    line.push('"');
+                    line.push_str(value);
+                    line.push('"');
+                } else {
+                    line.push_str(value);
+                }
+            }
+            line.push(':');
+            line.push_str(&property.value);
+            write_folded(out, &line);
+        }
+
+        for component in &self.components {
+            component.write_into(out);
+        }
+
+        write_folded(out, &format!("END:{}", self.name));
+    }
+}
+
+
+// ======================================================
+// Text values
+// ======================================================
+
+pub fn escape_text(value: &str) -> String {
+    let mut out = String::with_capacity(value.len());
+    for c in value.chars() {
+        match c {
+            '\\' => out.push_str("\\\\"),
+            ';' => out.push_str("\\;"),
+            ',' => out.push_str("\\,"),
+            '\n' => out.push_str("\\n"),
+            '\r' => {}
+            _ => out.push(c),
+        }
+    }
+    out
+}
+
+pub fn unescape_text(value: &str) -> String {
+    let mut out = String::with_capacity(value.len());
+    let mut chars = value.chars();
+    while let Some(c) = chars.next() {
+        if c == '\\' {
+            match chars.next() {
+                Some('n') | Some('N') => out.push('\n'),
+                Some(other) => out.push(other),
+                None => out.push('\\'),
+            }
+        } else {
+            out.push(c);
+        }
+    }
+    out
+}
+
+
+// ======================================================
+// Helpers
+// ======================================================
+
+fn unfold(input: &str) -> Vec<String> {
+    let mut lines: Vec<String> = Vec::new();
+
+    for raw in input.split('\n') {
+        let raw = raw.strip_suffix('\r').unwrap_or(raw);
+
+        if let Some(rest) = raw.strip_prefix([' ', '\t'])
+            && let Some(last) = lines.last_mut()
+        {
+            last.push_str(rest);
+            continue;
+        }
+
+        lines.push(raw.to_string());
+    }
+
+    lines
+}
+
+fn parse_line(line: &str) -> IcalResult<Property> {
+    let mut in_quotes = false;
+    let mut colon = None;
+
+    for (i, c) in line.char_indices() {
+        match c {
+            '"' => in_quotes = !in_quotes,
+            ':' if !in_quotes => {
+                colon = Some(i);
+                break;
+            }
+            _ => {}
+        }
+    }
+
+    let colon = colon.ok_or_else(|| IcalError::MalformedLine(line.to_string()))?;
+    let (head, value) = (&line[..colon], &line[colon + 1..]);
+
+    let mut parts = split_unquoted(head, ';').into_iter();
+    let name = parts
+        .next()
+        .filter(|n| !n.is_empty())
+        .ok_or_else(|| IcalError::MalformedLine(line.to_string()))?
+        .to_uppercase();
+
+    let mut params = Vec::new();
+    for part in parts {
+        let (key, val) = part
+            .split_once('=')
+            .ok_or_else(|| IcalError::MalformedLine(line.to_string()))?;
+        params.push((key.to_uppercase(), val.trim_matches('"').to_string()));
+    }
+
+    Ok(Property {
+        name,
+        params,
+        value: value.to_string(),
+    })
+}
+
+fn split_unquoted(input: &str, separator: char) -> Vec<&str> {
+    let mut parts = Vec::new();
+    let mut in_quotes = false;
+    let mut start = 0;
+
+    for (i, c) in input.char_indices() {
+        if c == '"' {
+            in_quotes = !in_quotes;
+        } else if c == separator && !in_quotes {
+            parts.push(&input[start..i]);
+            start = i + c.len_utf8();
+        }
+    }
+    parts.push(&input[start..]);
+
+    parts
+}
+
+fn write_folded(out: &mut String, line: &str) {
+    let mut width = 0;
+
+    for c in line.chars() {
+        let len = c.len_utf8();
+        if width + len > FOLD_WIDTH {
+            out.push_str("\r\n ");
+            width = 1;
+        }
+        out.push(c);
+        width += len;
+    }
+
+    out.push_str("\r\n");
+}
@@ -0,0 +1,24 @@
+use thiserror::Error;
+
+use crate::domain::error::DomainError;
+
+#[derive(Debug, Error)]
+pub enum IcalError {
+    #[error("Malformed content line: {0}")]
+    MalformedLine(String),
+
+    #[error("Unbalanced component: {0}")]
+    UnbalancedComponent(String),
+
+    #[error("Missing property: {0}")]
+    MissingProperty(&'static str),
+
+    #[error("Invalid value for {0}: {1}")]
+    InvalidValue(&'static str, String),
+
+    #[error("Unsupported: {0}")]
+    Unsupported(String),
+
+    #[error(transparent)]
+    Domain(#[from] DomainError),
+}
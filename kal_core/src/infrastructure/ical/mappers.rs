@@ -0,0 +1,1352 @@
+use std::collections::{BTreeSet, HashMap};
+use std::str::FromStr;
+
+use chrono::{DateTime, Datelike, Duration, Local, NaiveDate, NaiveDateTime, Utc};
+use sha2::{Digest, Sha256};
+use uuid::Uuid;
+
+use crate::domain::{
+    attendee::{Attendee, AttendeeRole, Organizer, ParticipationStatus},
+    calendar_object::CalendarObject,
+    event::Event,
+    journal::{JournalEntry, JournalLink},
+    reminder::{Reminder, ReminderAction, ReminderTrigger},
+    tag::Tag,
+    task::Task,
+    recurrence::{
+        ExceptionModification,
+        RecurrenceException,
+        RecurrenceRule,
+        RecurringEvent,
+    },
+    value_objects::{
+        validate_url, CalendarId, EventColor, EventId, EventStatus, Frequency, GeoPoint,
+        JournalId, ReminderId, TaskId, TaskStatus, TimeRange, Transparency,
+    },
+};
+
+use super::{
+    component::{escape_text, unescape_text, Component, Property},
+    error::IcalError,
+    timezone,
+};
+
+type IcalResult<T> = Result<T, IcalError>;
+
+const PRODID: &str = "-//kal//kal calendar//EN";
+const COLOR_PROPERTY: &str = "X-KAL-COLOR";
+/// Command reminders are exported as DISPLAY alarms carrying the command,
+/// so other clients still show them.
+const COMMAND_PROPERTY: &str = "X-KAL-COMMAND";
+/// Journal entries about one occurrence of a series carry its original
+/// start on their RELATED-TO.
+const OCCURRENCE_PARAM: &str = "X-KAL-RECURRENCE-ID";
+
+/// Maps a UID onto an `EventId`. UIDs produced by kal are UUIDs and map
+/// back to themselves; foreign UIDs get a stable name-based UUID.
+pub fn event_id_for_uid(uid: &str) -> EventId {
+    match Uuid::parse_str(uid) {
+        Ok(uuid) => EventId::from_uuid(uuid),
+        Err(_) => EventId::from_uuid(Uuid::new_v5(&Uuid::NAMESPACE_OID, uid.as_bytes())),
+    }
+}
+
+/// Like `event_id_for_uid`, for VTODOs.
+pub fn task_id_for_uid(uid: &str) -> TaskId {
+    TaskId::from_uuid(event_id_for_uid(uid).as_uuid())
+}
+
+/// Like `event_id_for_uid`, for VJOURNALs.
+pub fn journal_id_for_uid(uid: &str) -> JournalId {
+    JournalId::from_uuid(event_id_for_uid(uid).as_uuid())
+}
+
+
+// ======================================================
+// Import
+// ======================================================
+
+pub struct IcalMapper;
+
+impl IcalMapper {
+    /// Maps every VEVENT in a VCALENDAR onto domain objects owned by
+    /// `calendar_id`. Overrides (RECURRENCE-ID) are folded into their
+    /// master as exceptions.
+    pub fn to_domain(
+        calendar: &Component,
+        calendar_id: CalendarId,
+    ) -> IcalResult<Vec<CalendarObject>> {
+        Self::map_objects(calendar, calendar_id, event_id_for_uid)
+            .into_iter()
+            .map(|(_, object)| object)
+            .collect()
+    }
+
+    /// Lenient import for feeds: ids are scoped to `calendar_id` so the
+    /// same feed can be subscribed to twice, and objects that cannot be
+    /// mapped are reported instead of failing the whole import.
+    pub fn to_domain_scoped(
+        calendar: &Component,
+        calendar_id: CalendarId,
+    ) -> (Vec<CalendarObject>, Vec<(String, IcalError)>) {
+        let namespace = calendar_id.as_uuid();
+        let scoped = |uid: &str| {
+            EventId::from_uuid(Uuid::new_v5(&namespace, uid.as_bytes()))
+        };
+
+        let mut objects = Vec::new();
+        let mut skipped = Vec::new();
+
+        for (uid, object) in Self::map_objects(calendar, calendar_id, scoped) {
+            match object {
+                Ok(object) => objects.push(object),
+                Err(e) => skipped.push((uid, e)),
+            }
+        }
+
+        (objects, skipped)
+    }
+
+    /// Maps a single calendar object resource, which must hold exactly
+    /// one UID, onto a domain object with the given id.
+    pub fn to_domain_single(
+        calendar: &Component,
+        calendar_id: CalendarId,
+        event_id: EventId,
+    ) -> IcalResult<CalendarObject> {
+        let mut objects = Self::map_objects(calendar, calendar_id, |_| event_id);
+
+        match objects.len() {
+            1 => objects.remove(0).1,
+            0 => Err(IcalError::MissingProperty("VEVENT")),
+            _ => Err(IcalError::Unsupported(
+                "more than one UID in a calendar object resource".to_string(),
+            )),
+        }
+    }
+
+    fn map_objects(
+        calendar: &Component,
+        calendar_id: CalendarId,
+        id_for_uid: impl Fn(&str) -> EventId,
+    ) -> Vec<(String, IcalResult<CalendarObject>)> {
+        let calendar = timezone::with_known_tzids(calendar);
+        let mut masters: Vec<&Component> = Vec::new();
+        let mut overrides: HashMap<String, Vec<&Component>> = HashMap::new();
+        let mut results = Vec::new();
+
+        for vevent in calendar.components_named("VEVENT") {
+            let Some(uid) = text_value(vevent, "UID") else {
+                results.push((String::new(), Err(IcalError::MissingProperty("UID"))));
+                continue;
+            };
+
+            if vevent.property("RECURRENCE-ID").is_some() {
+                overrides.entry(uid).or_default().push(vevent);
+            } else {
+                masters.push(vevent);
+            }
+        }
+
+        for master in masters {
+            let uid = text_value(master, "UID").unwrap_or_default();
+            let instances = overrides.remove(&uid).unwrap_or_default();
+            let object = Self::object_to_domain(
+                master,
+                &instances,
+                calendar_id,
+                id_for_uid(&uid),
+            );
+            results.push((uid, object));
+        }
+
+        results
+    }
+
+    fn object_to_domain(
+        vevent: &Component,
+        overrides: &[&Component],
+        calendar_id: CalendarId,
+        event_id: EventId,
+    ) -> IcalResult<CalendarObject> {
+        let title = text_value(vevent, "SUMMARY")
+            .filter(|s| !s.is_empty())
+            .unwrap_or_else(|| "(untitled)".to_string());
+        let description = text_value(vevent, "DESCRIPTION")
+            .filter(|s| !s.is_empty());
+
+        let (time_range, is_all_day) = time_range_of(vevent)?;
+
+        let color = match vevent.property(COLOR_PROPERTY) {
+            Some(p) => p
+                .value
+                .parse::<u8>()
+                .map(EventColor::from)
+                .map_err(|_| IcalError::InvalidValue(COLOR_PROPERTY, p.value.clone()))?,
+            None => EventColor::from(0),
+        };
+
+        let status = status_of(vevent);
+        let transparency = transparency_of(vevent);
+
+        let reminders = reminders_of(vevent, &event_id, &time_range)?;
+        let (organizer, attendees) = participants_of(vevent);
+        let sequence = sequence_of(vevent)?;
+        let (location, url, geo) = place_of(vevent);
+        let tags = tags_of(vevent);
+
+        let now = Utc::now();
+        let created_at = date_property(vevent, "CREATED")?.unwrap_or(now);
+        let updated_at = match date_property(vevent, "LAST-MODIFIED")? {
+            Some(dt) => dt,
+            None => date_property(vevent, "DTSTAMP")?.unwrap_or(now),
+        };
+
+        let Some(rrule) = vevent.property("RRULE") else {
+            return Ok(CalendarObject::Event(Event::with_id(
+                event_id,
+                calendar_id,
+                title,
+                description,
+                time_range,
+                color,
+                is_all_day,
+                status,
+                created_at,
+                updated_at,
+            )?
+            .with_reminders(reminders)
+            .with_participants(organizer, attendees)
+            .with_sequence(sequence)
+            .with_transparency(transparency)
+            .with_place(location, url, geo)
+            .with_tags(tags)));
+        };
+
+        let rule = parse_rrule(&rrule.value, time_range.starts_at())?;
+
+        let mut exceptions = HashMap::new();
+
+        for exdate in vevent.properties_named("EXDATE") {
+            for value in exdate.value.split(',') {
+                let original = parse_date_value(exdate, value)?.0;
+                exceptions.insert(original, RecurrenceException::cancelled(original));
+            }
+        }
+
+        for instance in overrides {
+            let recurrence_id = instance
+                .property("RECURRENCE-ID")
+                .ok_or(IcalError::MissingProperty("RECURRENCE-ID"))?;
+            let original = parse_date_value(recurrence_id, &recurrence_id.value)?.0;
+
+            let exception = if has_cancelled_status(instance) {
+                RecurrenceException::cancelled(original)
+            } else {
+                // Only a location that differs from the series is an override
+                let instance_location = text_value(instance, "LOCATION")
+                    .filter(|l| !l.is_empty() && Some(l) != location.as_ref());
+
+                RecurrenceException::rescheduled(original, time_range_of(instance)?.0)
+                    .with_location(instance_location)
+            };
+
+            exceptions.insert(original, exception);
+        }
+
+        Ok(CalendarObject::Recurring(RecurringEvent::with_id(
+            event_id,
+            calendar_id,
+            title,
+            description,
+            time_range,
+            rule,
+            exceptions,
+            color,
+            is_all_day,
+            status,
+            created_at,
+            updated_at,
+        )?
+        .with_reminders(reminders)
+        .with_participants(organizer, attendees)
+        .with_sequence(sequence)
+        .with_transparency(transparency)
+        .with_place(location, url, geo)
+        .with_tags(tags)))
+    }
+
+
+    // ==================================================
+    // Export
+    // ==================================================
+
+    pub fn event_to_component(event: &Event) -> Component {
+        let mut vevent = Component::new("VEVENT");
+
+        vevent.push(Property::new("UID", event.event_id().to_string()));
+        vevent.push(Property::new("DTSTAMP", format_datetime(event.updated_at())));
+        vevent.push(Property::new("SEQUENCE", event.sequence().to_string()));
+        push_common(
+            &mut vevent,
+            event.title(),
+            event.description().as_deref(),
+            event.time_range(),
+            *event.is_all_day(),
+            *event.color(),
+            *event.status(),
+            *event.transparency(),
+            event.created_at(),
+            event.updated_at(),
+        );
+        push_place(&mut vevent, event.location().as_deref(), event.url().as_deref(), *event.geo());
+        push_tags(&mut vevent, event.tags());
+        push_participants(&mut vevent, event.organizer().as_ref(), event.attendees());
+        push_reminders(&mut vevent, event.reminders());
+
+        vevent
+    }
+
+    pub fn recurring_to_components(event: &RecurringEvent) -> Vec<Component> {
+        let mut master = Component::new("VEVENT");
+        let uid = event.event_id().to_string();
+
+        master.push(Property::new("UID", uid.clone()));
+        master.push(Property::new("DTSTAMP", format_datetime(event.updated_at())));
+        master.push(Property::new("SEQUENCE", event.sequence().to_string()));
+        push_common(
+            &mut master,
+            event.title(),
+            event.description().as_deref(),
+            event.time_range(),
+            *event.is_all_day(),
+            *event.color(),
+            *event.status(),
+            *event.transparency(),
+            event.created_at(),
+            event.updated_at(),
+        );
+        master.push(Property::new("RRULE", format_rrule(event.rule(), *event.is_all_day())));
+        push_place(&mut master, event.location().as_deref(), event.url().as_deref(), *event.geo());
+        push_tags(&mut master, event.tags());
+        push_participants(&mut master, event.organizer().as_ref(), event.attendees());
+        push_reminders(&mut master, event.reminders());
+
+        let mut exceptions: Vec<_> = event.exceptions().values().collect();
+        exceptions.sort_by_key(|ex| *ex.original_starts_at());
+
+        let mut components = Vec::new();
+
+        for exception in exceptions {
+            let original = exception.original_starts_at();
+
+            match exception.modification() {
+                ExceptionModification::Cancelled => {
+                    master.push(date_property_for("EXDATE", original, *event.is_all_day()));
+                }
+                ExceptionModification::Rescheduled { new_time_range } => {
+                    let mut instance = Component::new("VEVENT");
+                    instance.push(Property::new("UID", uid.clone()));
+                    instance.push(Property::new("DTSTAMP", format_datetime(event.updated_at())));
+                    instance.push(Property::new("SEQUENCE", event.sequence().to_string()));
+                    instance.push(date_property_for(
+                        "RECURRENCE-ID",
+                        original,
+                        *event.is_all_day(),
+                    ));
+                    // A cancelled override would read back as a cancelled occurrence
+                    let status = if event.is_cancelled() {
+                        EventStatus::Confirmed
+                    } else {
+                        *event.status()
+                    };
+                    push_common(
+                        &mut instance,
+                        event.title(),
+                        event.description().as_deref(),
+                        new_time_range,
+                        *event.is_all_day(),
+                        *event.color(),
+                        status,
+                        *event.transparency(),
+                        event.created_at(),
+                        event.updated_at(),
+                    );
+                    push_place(
+                        &mut instance,
+                        exception.location().as_deref().or(event.location().as_deref()),
+                        event.url().as_deref(),
+                        *event.geo(),
+                    );
+                    push_tags(&mut instance, event.tags());
+                    // Overrides replace the master, so repeat its people and alarms
+                    push_participants(&mut instance, event.organizer().as_ref(), event.attendees());
+                    push_reminders(&mut instance, event.reminders());
+                    components.push(instance);
+                }
+            }
+        }
+
+        components.insert(0, master);
+        components
+    }
+
+    pub fn object_to_components(object: &CalendarObject) -> Vec<Component> {
+        match object {
+            CalendarObject::Event(event) => vec![Self::event_to_component(event)],
+            CalendarObject::Recurring(event) => Self::recurring_to_components(event),
+        }
+    }
+
+    /// Wraps components in a VCALENDAR envelope.
+    pub fn wrap(components: Vec<Component>) -> Component {
+        let mut calendar = Component::new("VCALENDAR");
+        calendar.push(Property::new("VERSION", "2.0"));
+        calendar.push(Property::new("PRODID", PRODID));
+        calendar.components = components;
+        calendar
+    }
+
+    /// Serializes a single object as a complete `.ics` document.
+    pub fn to_ics(object: &CalendarObject) -> String {
+        Self::wrap(Self::object_to_components(object)).to_ics()
+    }
+
+    /// Like `to_ics`, but keeps a foreign UID the object was imported with.
+    pub fn to_ics_with_uid(object: &CalendarObject, uid: Option<&str>) -> String {
+        let mut components = Self::object_to_components(object);
+
+        if let Some(uid) = uid {
+            for component in &mut components {
+                for property in &mut component.properties {
+                    if property.name == "UID" {
+                        property.value = uid.to_string();
+                    }
+                }
+            }
+        }
+
+        Self::wrap(components).to_ics()
+    }
+
+    /// Hash of the object's content, ignoring timestamps that change on
+    /// every save. Used to detect whether a dirty item really changed.
+    pub fn content_hash(object: &CalendarObject) -> String {
+        let mut hasher = Sha256::new();
+
+        for component in Self::object_to_components(object) {
+            hash_component(&mut hasher, &component);
+        }
+
+        hasher
+            .finalize()
+            .iter()
+            .map(|b| format!("{b:02x}"))
+            .collect()
+    }
+
+
+    // ==================================================
+    // Tasks
+    // ==================================================
+
+    /// Maps every VTODO in a VCALENDAR onto tasks owned by `calendar_id`.
+    /// Tasks that cannot be mapped are reported instead of failing the
+    /// whole import.
+    pub fn tasks_to_domain(
+        calendar: &Component,
+        calendar_id: CalendarId,
+    ) -> (Vec<Task>, Vec<(String, IcalError)>) {
+        let calendar = timezone::with_known_tzids(calendar);
+        let mut tasks = Vec::new();
+        let mut skipped = Vec::new();
+
+        for vtodo in calendar.components_named("VTODO") {
+            let Some(uid) = text_value(vtodo, "UID") else {
+                skipped.push((String::new(), IcalError::MissingProperty("UID")));
+                continue;
+            };
+
+            match Self::task_to_domain(vtodo, calendar_id, task_id_for_uid(&uid)) {
+                Ok(task) => tasks.push(task),
+                Err(e) => skipped.push((uid, e)),
+            }
+        }
+
+        (tasks, skipped)
+    }
+
+    pub fn task_to_domain(
+        vtodo: &Component,
+        calendar_id: CalendarId,
+        task_id: TaskId,
+    ) -> IcalResult<Task> {
+        let title = text_value(vtodo, "SUMMARY")
+            .filter(|s| !s.is_empty())
+            .unwrap_or_else(|| "(untitled)".to_string());
+        let description = text_value(vtodo, "DESCRIPTION")
+            .filter(|s| !s.is_empty());
+
+        let starts_at = date_property(vtodo, "DTSTART")?;
+        let due_at = match (date_property(vtodo, "DUE")?, vtodo.property("DURATION")) {
+            (Some(due_at), _) => Some(due_at),
+            (None, Some(duration)) => {
+                let starts_at = starts_at.ok_or(IcalError::MissingProperty("DTSTART"))?;
+                Some(add_duration(starts_at, &duration.value)?)
+            }
+            (None, None) => None,
+        };
+
+        // PRIORITY 0 means undefined
+        let priority = match vtodo.property("PRIORITY") {
+            Some(p) => match p.value.trim().parse::<u8>() {
+                Ok(0) => None,
+                Ok(priority @ 1..=9) => Some(priority),
+                _ => return Err(IcalError::InvalidValue("PRIORITY", p.value.clone())),
+            },
+            None => None,
+        };
+
+        // Unknown statuses read as needing action
+        let status = vtodo
+            .property("STATUS")
+            .and_then(|p| p.value.parse::<TaskStatus>().ok())
+            .unwrap_or_default();
+
+        let percent_complete = match vtodo.property("PERCENT-COMPLETE") {
+            Some(p) => match p.value.trim().parse::<u8>() {
+                Ok(percent @ 0..=100) => percent,
+                _ => return Err(IcalError::InvalidValue("PERCENT-COMPLETE", p.value.clone())),
+            },
+            None if status == TaskStatus::Completed => 100,
+            None => 0,
+        };
+
+        let completed_at = date_property(vtodo, "COMPLETED")?;
+
+        let rule = match vtodo.property("RRULE") {
+            Some(rrule) => {
+                let anchor = due_at
+                    .or(starts_at)
+                    .ok_or(IcalError::MissingProperty("DUE"))?;
+                Some(parse_rrule(&rrule.value, &anchor)?)
+            }
+            None => None,
+        };
+
+        let now = Utc::now();
+        let created_at = date_property(vtodo, "CREATED")?.unwrap_or(now);
+        let updated_at = match date_property(vtodo, "LAST-MODIFIED")? {
+            Some(dt) => dt,
+            None => date_property(vtodo, "DTSTAMP")?.unwrap_or(now),
+        };
+
+        Ok(Task::with_id(
+            task_id,
+            calendar_id,
+            title,
+            description,
+            starts_at,
+            due_at,
+            status,
+            created_at,
+            updated_at,
+        )?
+        .with_priority(priority)
+        .with_progress(percent_complete, completed_at)
+        .with_rule(rule))
+    }
+
+    pub fn task_to_component(task: &Task) -> Component {
+        let mut vtodo = Component::new("VTODO");
+
+        vtodo.push(Property::new("UID", task.task_id().to_string()));
+        vtodo.push(Property::new("DTSTAMP", format_datetime(task.updated_at())));
+
+        if let Some(starts_at) = task.starts_at() {
+            vtodo.push(Property::new("DTSTART", format_datetime(starts_at)));
+        }
+
+        if let Some(due_at) = task.due_at() {
+            vtodo.push(Property::new("DUE", format_datetime(due_at)));
+        }
+
+        vtodo.push(Property::new("SUMMARY", escape_text(task.title())));
+
+        if let Some(description) = task.description() {
+            vtodo.push(Property::new("DESCRIPTION", escape_text(description)));
+        }
+
+        if let Some(priority) = task.priority() {
+            vtodo.push(Property::new("PRIORITY", priority.to_string()));
+        }
+
+        vtodo.push(Property::new("STATUS", task.status().to_string()));
+
+        if *task.percent_complete() > 0 {
+            vtodo.push(Property::new("PERCENT-COMPLETE", task.percent_complete().to_string()));
+        }
+
+        if let Some(completed_at) = task.completed_at() {
+            vtodo.push(Property::new("COMPLETED", format_datetime(completed_at)));
+        }
+
+        if let Some(rule) = task.rule() {
+            vtodo.push(Property::new("RRULE", format_rrule(rule, false)));
+        }
+
+        vtodo.push(Property::new("CREATED", format_datetime(task.created_at())));
+        vtodo.push(Property::new("LAST-MODIFIED", format_datetime(task.updated_at())));
+
+        vtodo
+    }
+
+    /// Serializes tasks as a complete `.ics` document.
+    pub fn tasks_to_ics(tasks: &[Task]) -> String {
+        Self::wrap(tasks.iter().map(Self::task_to_component).collect()).to_ics()
+    }
+
+    // ==================================================
+    // Journal
+    // ==================================================
+
+    /// Maps every VJOURNAL in a VCALENDAR onto journal entries owned by
+    /// `calendar_id`. Entries that cannot be mapped are reported instead
+    /// of failing the whole import.
+    pub fn journal_to_domain(
+        calendar: &Component,
+        calendar_id: CalendarId,
+    ) -> (Vec<JournalEntry>, Vec<(String, IcalError)>) {
+        let calendar = timezone::with_known_tzids(calendar);
+        let mut entries = Vec::new();
+        let mut skipped = Vec::new();
+
+        for vjournal in calendar.components_named("VJOURNAL") {
+            let Some(uid) = text_value(vjournal, "UID") else {
+                skipped.push((String::new(), IcalError::MissingProperty("UID")));
+                continue;
+            };
+
+            match Self::journal_entry_to_domain(vjournal, calendar_id, journal_id_for_uid(&uid)) {
+                Ok(entry) => entries.push(entry),
+                Err(e) => skipped.push((uid, e)),
+            }
+        }
+
+        (entries, skipped)
+    }
+
+    pub fn journal_entry_to_domain(
+        vjournal: &Component,
+        calendar_id: CalendarId,
+        journal_id: JournalId,
+    ) -> IcalResult<JournalEntry> {
+        let title = text_value(vjournal, "SUMMARY")
+            .filter(|s| !s.is_empty())
+            .unwrap_or_else(|| "(untitled)".to_string());
+
+        // A VJOURNAL may carry several DESCRIPTIONs
+        let body = vjournal
+            .properties_named("DESCRIPTION")
+            .map(|p| unescape_text(&p.value))
+            .collect::<Vec<_>>()
+            .join("\n\n");
+
+        let date = date_property(vjournal, "DTSTART")?
+            .ok_or(IcalError::MissingProperty("DTSTART"))?
+            .date_naive();
+
+        let link = match vjournal.property("RELATED-TO") {
+            Some(related) => {
+                let event_id = event_id_for_uid(related.value.trim());
+                Some(match related.param(OCCURRENCE_PARAM) {
+                    Some(original) => {
+                        JournalLink::Occurrence(event_id, parse_date_value(related, original)?.0)
+                    }
+                    None => JournalLink::Event(event_id),
+                })
+            }
+            None => None,
+        };
+
+        let now = Utc::now();
+        let created_at = date_property(vjournal, "CREATED")?.unwrap_or(now);
+        let updated_at = match date_property(vjournal, "LAST-MODIFIED")? {
+            Some(dt) => dt,
+            None => date_property(vjournal, "DTSTAMP")?.unwrap_or(now),
+        };
+
+        Ok(JournalEntry::with_id(
+            journal_id,
+            calendar_id,
+            date,
+            title,
+            body,
+            created_at,
+            updated_at,
+        )?
+        .with_link(link))
+    }
+
+    pub fn journal_entry_to_component(entry: &JournalEntry) -> Component {
+        let mut vjournal = Component::new("VJOURNAL");
+
+        vjournal.push(Property::new("UID", entry.journal_id().to_string()));
+        vjournal.push(Property::new("DTSTAMP", format_datetime(entry.updated_at())));
+        vjournal.push(
+            Property::new("DTSTART", entry.date().format("%Y%m%d").to_string())
+                .with_param("VALUE", "DATE"),
+        );
+        vjournal.push(Property::new("SUMMARY", escape_text(entry.title())));
+
+        if !entry.body().is_empty() {
+            vjournal.push(Property::new("DESCRIPTION", escape_text(entry.body())));
+        }
+
+        if let Some(link) = entry.link() {
+            let mut related = Property::new("RELATED-TO", link.event_id().to_string());
+            if let Some(original) = link.original_starts_at() {
+                related = related.with_param(OCCURRENCE_PARAM, format_datetime(&original));
+            }
+            vjournal.push(related);
+        }
+
+        vjournal.push(Property::new("CREATED", format_datetime(entry.created_at())));
+        vjournal.push(Property::new("LAST-MODIFIED", format_datetime(entry.updated_at())));
+
+        vjournal
+    }
+
+    /// Serializes journal entries as a complete `.ics` document.
+    pub fn journal_to_ics(entries: &[JournalEntry]) -> String {
+        Self::wrap(entries.iter().map(Self::journal_entry_to_component).collect()).to_ics()
+    }
+}
+
+
+// ======================================================
+// Helpers
+// ======================================================
+
+#[allow(clippy::too_many_arguments)]
+fn push_common(
+    vevent: &mut Component,
+    title: &str,
+    description: Option<&str>,
+    time_range: &TimeRange,
+    is_all_day: bool,
+    color: EventColor,
+    status: EventStatus,
+    transparency: Transparency,
+    created_at: &DateTime<Utc>,
+    updated_at: &DateTime<Utc>,
+) {
+    vevent.push(date_property_for("DTSTART", time_range.starts_at(), is_all_day));
+    vevent.push(date_property_for("DTEND", time_range.ends_at(), is_all_day));
+    vevent.push(Property::new("SUMMARY", escape_text(title)));
+
+    if let Some(description) = description {
+        vevent.push(Property::new("DESCRIPTION", escape_text(description)));
+    }
+
+    // Defaults are left out so unchanged objects keep their etags
+    if status != EventStatus::Confirmed {
+        vevent.push(Property::new("STATUS", status.to_string()));
+    }
+
+    if transparency != Transparency::Opaque {
+        vevent.push(Property::new("TRANSP", transparency.to_string()));
+    }
+
+    vevent.push(Property::new(COLOR_PROPERTY, u8::from(color).to_string()));
+    vevent.push(Property::new("CREATED", format_datetime(created_at)));
+    vevent.push(Property::new("LAST-MODIFIED", format_datetime(updated_at)));
+}
+
+fn push_place(vevent: &mut Component, location: Option<&str>, url: Option<&str>, geo: Option<GeoPoint>) {
+    if let Some(location) = location {
+        vevent.push(Property::new("LOCATION", escape_text(location)));
+    }
+
+    if let Some(url) = url {
+        vevent.push(Property::new("URL", url));
+    }
+
+    if let Some(geo) = geo {
+        vevent.push(Property::new("GEO", format!("{};{}", geo.latitude(), geo.longitude())));
+    }
+}
+
+fn push_tags(vevent: &mut Component, tags: &BTreeSet<Tag>) {
+    if tags.is_empty() {
+        return;
+    }
+
+    let value = tags.iter().map(|t| escape_text(t.as_str())).collect::<Vec<_>>().join(",");
+    vevent.push(Property::new("CATEGORIES", value));
+}
+
+fn hash_component(hasher: &mut Sha256, component: &Component) {
+    for property in &component.properties {
+        if matches!(
+            property.name.as_str(),
+            "DTSTAMP" | "CREATED" | "LAST-MODIFIED"
+        ) {
+            continue;
+        }
+        hasher.update(property.name.as_bytes());
+        for (name, value) in &property.params {
+            hasher.update(name.as_bytes());
+            hasher.update(value.as_bytes());
+        }
+        hasher.update(property.value.as_bytes());
+        hasher.update(b"\n");
+    }
+
+    for child in &component.components {
+        hasher.update(child.name.as_bytes());
+        hash_component(hasher, child);
+    }
+}
+
+fn push_participants(vevent: &mut Component, organizer: Option<&Organizer>, attendees: &[Attendee]) {
+    if let Some(organizer) = organizer {
+        let mut property = Property::new("ORGANIZER", format!("mailto:{}", organizer.email()));
+        if let Some(name) = organizer.name() {
+            property = property.with_param("CN", name.replace('"', ""));
+        }
+        vevent.push(property);
+    }
+
+    for attendee in attendees {
+        let mut property = Property::new("ATTENDEE", format!("mailto:{}", attendee.email()));
+        if let Some(name) = attendee.name() {
+            property = property.with_param("CN", name.replace('"', ""));
+        }
+        property = property
+            .with_param("ROLE", attendee.role().to_string())
+            .with_param("PARTSTAT", attendee.status().to_string());
+        if *attendee.rsvp() {
+            property = property.with_param("RSVP", "TRUE");
+        }
+        vevent.push(property);
+    }
+}
+
+fn push_reminders(vevent: &mut Component, reminders: &[Reminder]) {
+    for reminder in reminders {
+        vevent.components.push(reminder_to_component(reminder));
+    }
+}
+
+fn reminder_to_component(reminder: &Reminder) -> Component {
+    let mut valarm = Component::new("VALARM");
+
+    valarm.push(Property::new("UID", reminder.reminder_id().to_string()));
+    valarm.push(match reminder.trigger() {
+        ReminderTrigger::Relative(offset) => Property::new("TRIGGER", format_duration(offset)),
+        ReminderTrigger::Absolute(at) => {
+            Property::new("TRIGGER", format_datetime(at)).with_param("VALUE", "DATE-TIME")
+        }
+    });
+
+    let description = reminder.description().clone().unwrap_or_default();
+
+    match reminder.action() {
+        ReminderAction::Display => {
+            valarm.push(Property::new("ACTION", "DISPLAY"));
+            valarm.push(Property::new("DESCRIPTION", escape_text(&description)));
+        }
+        ReminderAction::Email { recipient } => {
+            valarm.push(Property::new("ACTION", "EMAIL"));
+            valarm.push(Property::new("DESCRIPTION", escape_text(&description)));
+            valarm.push(Property::new("SUMMARY", escape_text(&description)));
+            valarm.push(Property::new("ATTENDEE", format!("mailto:{recipient}")));
+        }
+        ReminderAction::Command { command } => {
+            valarm.push(Property::new("ACTION", "DISPLAY"));
+            valarm.push(Property::new("DESCRIPTION", escape_text(&description)));
+            valarm.push(Property::new(COMMAND_PROPERTY, escape_text(command)));
+        }
+    }
+
+    if let Some(interval) = reminder.repeat_interval() {
+        valarm.push(Property::new("REPEAT", reminder.repeat().to_string()));
+        valarm.push(Property::new("DURATION", format_duration(interval)));
+    }
+
+    valarm
+}
+
+/// Maps the VALARMs of a VEVENT. Triggers relative to the end are
+/// rebased onto the start; AUDIO alarms become DISPLAY ones. Alarms
+/// without a UUID UID get an id derived from the event and position, so
+/// re-importing the same data keeps them stable.
+fn reminders_of(
+    vevent: &Component,
+    event_id: &EventId,
+    time_range: &TimeRange,
+) -> IcalResult<Vec<Reminder>> {
+    let mut reminders = Vec::new();
+
+    for (position, valarm) in vevent.components_named("VALARM").enumerate() {
+        let reminder_id = text_value(valarm, "UID")
+            .and_then(|uid| Uuid::parse_str(&uid).ok())
+            .unwrap_or_else(|| {
+                Uuid::new_v5(&event_id.as_uuid(), position.to_string().as_bytes())
+            });
+
+        let trigger = valarm
+            .property("TRIGGER")
+            .ok_or(IcalError::MissingProperty("TRIGGER"))?;
+
+        let trigger = if trigger
+            .param("VALUE")
+            .is_some_and(|v| v.eq_ignore_ascii_case("DATE-TIME"))
+        {
+            ReminderTrigger::Absolute(parse_date_value(trigger, &trigger.value)?.0)
+        } else {
+            let invalid = || IcalError::InvalidValue("TRIGGER", trigger.value.clone());
+            let offset = match trigger.param("RELATED") {
+                Some(related) if related.eq_ignore_ascii_case("END") => parse_duration(&trigger.value)?
+                    .checked_add(&time_range.duration())
+                    .ok_or_else(invalid)?,
+                _ => parse_duration(&trigger.value)?,
+            };
+            // Firing times are worked out from every occurrence's start
+            time_range.starts_at().checked_add_signed(offset).ok_or_else(invalid)?;
+            ReminderTrigger::Relative(offset)
+        };
+
+        let description = text_value(valarm, "DESCRIPTION").filter(|d| !d.is_empty());
+
+        let action = match (
+            valarm.property("ACTION").map(|p| p.value.to_uppercase()),
+            text_value(valarm, COMMAND_PROPERTY),
+        ) {
+            (_, Some(command)) => ReminderAction::Command { command },
+            (Some(action), None) if action == "EMAIL" => {
+                let recipient = valarm
+                    .property("ATTENDEE")
+                    .map(|p| p.value.trim_start_matches("mailto:").to_string())
+                    .ok_or(IcalError::MissingProperty("ATTENDEE"))?;
+                ReminderAction::Email { recipient }
+            }
+            (Some(_), None) => ReminderAction::Display,
+            (None, None) => return Err(IcalError::MissingProperty("ACTION")),
+        };
+
+        let (repeat, repeat_interval) = match (valarm.property("REPEAT"), valarm.property("DURATION")) {
+            (Some(repeat), Some(duration)) => (
+                repeat
+                    .value
+                    .parse::<u32>()
+                    .map_err(|_| IcalError::InvalidValue("REPEAT", repeat.value.clone()))?,
+                Some(parse_duration(&duration.value)?),
+            ),
+            _ => (0, None),
+        };
+
+        // REPEAT:0 with a DURATION is legal iCalendar but means no repeats
+        let repeat_interval = repeat_interval.filter(|_| repeat > 0);
+
+        reminders.push(Reminder::with_id(
+            ReminderId::from_uuid(reminder_id),
+            trigger,
+            action,
+            description,
+            repeat,
+            repeat_interval,
+        )?);
+    }
+
+    Ok(reminders)
+}
+
+/// Maps ORGANIZER and ATTENDEE. Addresses that are not email addresses
+/// (other URI schemes) and repeated attendees are skipped, as are unknown
+/// roles and statuses, which fall back to the defaults.
+/// LOCATION, URL and GEO. Malformed URL or GEO values from other clients
+/// are dropped rather than failing the whole import.
+fn place_of(vevent: &Component) -> (Option<String>, Option<String>, Option<GeoPoint>) {
+    let location = text_value(vevent, "LOCATION").filter(|l| !l.trim().is_empty());
+
+    let url = vevent
+        .property("URL")
+        .map(|p| p.value.trim().to_string())
+        .filter(|u| validate_url(u).is_ok());
+
+    let geo = vevent.property("GEO").and_then(|p| {
+        let (lat, lon) = p.value.split_once(';')?;
+        GeoPoint::new(lat.trim().parse().ok()?, lon.trim().parse().ok()?).ok()
+    });
+
+    (location, url, geo)
+}
+
+/// Every CATEGORIES value, as tags. Values that are not valid tags are
+/// dropped.
+fn tags_of(vevent: &Component) -> Vec<Tag> {
+    let mut tags = Vec::new();
+
+    for property in vevent.properties_named("CATEGORIES") {
+        let mut value = String::new();
+        let mut escaped = false;
+
+        // Split on commas that are not escaped
+        for c in property.value.chars().chain(std::iter::once(',')) {
+            match c {
+                ',' if !escaped => {
+                    if let Ok(tag) = Tag::new(unescape_text(&value)) {
+                        tags.push(tag);
+                    }
+                    value.clear();
+                }
+                c => {
+                    escaped = c == '\\' && !escaped;
+                    value.push(c);
+                }
+            }
+        }
+    }
+
+    tags
+}
+
+fn participants_of(vevent: &Component) -> (Option<Organizer>, Vec<Attendee>) {
+    let organizer = vevent.property("ORGANIZER").and_then(|p| {
+        Organizer::new(mailto_address(&p.value), p.param("CN").map(str::to_string)).ok()
+    });
+
+    let mut attendees: Vec<Attendee> = Vec::new();
+
+    for property in vevent.properties_named("ATTENDEE") {
+        let attendee = Attendee::new(
+            mailto_address(&property.value),
+            property.param("CN").map(str::to_string),
+            property
+                .param("ROLE")
+                .and_then(|r| r.parse().ok())
+                .unwrap_or(AttendeeRole::default()),
+            property
+                .param("PARTSTAT")
+                .and_then(|s| s.parse().ok())
+                .unwrap_or(ParticipationStatus::default()),
+            property.param("RSVP").is_some_and(|r| r.eq_ignore_ascii_case("TRUE")),
+        );
+
+        if let Ok(attendee) = attendee
+            && !attendees.iter().any(|a| a.has_email(attendee.email()))
+        {
+            attendees.push(attendee);
+        }
+    }
+
+    (organizer, attendees)
+}
+
+pub(crate) fn mailto_address(value: &str) -> String {
+    let value = value.trim();
+    match value.get(..7) {
+        Some(scheme) if scheme.eq_ignore_ascii_case("mailto:") => value[7..].to_string(),
+        _ => value.to_string(),
+    }
+}
+
+pub(crate) fn text_value(component: &Component, name: &str) -> Option<String> {
+    component.property(name).map(|p| unescape_text(&p.value))
+}
+
+/// SEQUENCE, 0 when absent.
+pub(crate) fn sequence_of(component: &Component) -> IcalResult<u32> {
+    match component.property("SEQUENCE") {
+        Some(p) => p
+            .value
+            .trim()
+            .parse()
+            .map_err(|_| IcalError::InvalidValue("SEQUENCE", p.value.clone())),
+        None => Ok(0),
+    }
+}
+
+/// Unknown values, such as VTODO statuses, read as confirmed.
+fn status_of(vevent: &Component) -> EventStatus {
+    vevent
+        .property("STATUS")
+        .and_then(|p| p.value.parse().ok())
+        .unwrap_or_default()
+}
+
+fn transparency_of(vevent: &Component) -> Transparency {
+    vevent
+        .property("TRANSP")
+        .and_then(|p| p.value.parse().ok())
+        .unwrap_or_default()
+}
+
+fn has_cancelled_status(component: &Component) -> bool {
+    component
+        .property("STATUS")
+        .is_some_and(|p| p.value.eq_ignore_ascii_case("CANCELLED"))
+}
+
+pub(crate) fn time_range_of(vevent: &Component) -> IcalResult<(TimeRange, bool)> {
+    let dtstart = vevent
+        .property("DTSTART")
+        .ok_or(IcalError::MissingProperty("DTSTART"))?;
+    let (starts_at, is_all_day) = parse_date_value(dtstart, &dtstart.value)?;
+
+    let ends_at = if let Some(dtend) = vevent.property("DTEND") {
+        parse_date_value(dtend, &dtend.value)?.0
+    } else if let Some(duration) = vevent.property("DURATION") {
+        add_duration(starts_at, &duration.value)?
+    } else if is_all_day {
+        starts_at + Duration::days(1)
+    } else {
+        // A DTSTART without an end is an instant; the domain requires a
+        // non-empty range, so store it as one minute long.
+        starts_at + Duration::minutes(1)
+    };
+
+    Ok((TimeRange::new(starts_at, ends_at)?, is_all_day))
+}
+
+fn date_property(
+    component: &Component,
+    name: &'static str,
+) -> IcalResult<Option<DateTime<Utc>>> {
+    match component.property(name) {
+        Some(p) => Ok(Some(parse_date_value(p, &p.value)?.0)),
+        None => Ok(None),
+    }
+}
+
+fn date_property_for(name: &str, dt: &DateTime<Utc>, is_all_day: bool) -> Property {
+    if is_all_day {
+        Property::new(name, format_date(dt)).with_param("VALUE", "DATE")
+    } else {
+        Property::new(name, format_datetime(dt))
+    }
+}
+
+/// Parses a DATE or DATE-TIME value. Returns whether the value was a DATE.
+///
+/// TZID-qualified times are resolved through the timezone database and
+/// fail to map when the TZID is unknown. Floating times are the same
+/// wall-clock time wherever they are read, so they are taken as local.
+pub(crate) fn parse_date_value(
+    property: &Property,
+    value: &str,
+) -> IcalResult<(DateTime<Utc>, bool)> {
+    let value = value.trim();
+    let is_date = property
+        .param("VALUE")
+        .is_some_and(|v| v.eq_ignore_ascii_case("DATE"))
+        || value.len() == 8;
+
+    if is_date {
+        let date = NaiveDate::parse_from_str(value, "%Y%m%d")
+            .map_err(|_| IcalError::InvalidValue("DATE", value.to_string()))?;
+        let midnight = date
+            .and_hms_opt(0, 0, 0)
+            .ok_or_else(|| IcalError::InvalidValue("DATE", value.to_string()))?;
+        return Ok((midnight.and_utc(), true));
+    }
+
+    let invalid = || IcalError::InvalidValue("DATE-TIME", value.to_string());
+    let parse = |value: &str| {
+        NaiveDateTime::parse_from_str(value, "%Y%m%dT%H%M%S").map_err(|_| invalid())
+    };
+
+    // A TZID on a UTC time is meaningless and ignored
+    if let Some(utc) = value.strip_suffix('Z') {
+        return Ok((parse(utc)?.and_utc(), false));
+    }
+
+    let local = parse(value)?;
+    let resolved = match property.param("TZID") {
+        Some(tzid) => {
+            let tz = timezone::find(tzid)
+                .ok_or_else(|| IcalError::Unsupported(format!("TZID {tzid}")))?;
+            timezone::to_utc(&local, &tz)
+        }
+        None => timezone::to_utc(&local, &Local),
+    };
+
+    Ok((resolved.ok_or_else(invalid)?, false))
+}
+
+/// `starts_at` moved on by a DURATION value.
+fn add_duration(starts_at: DateTime<Utc>, value: &str) -> IcalResult<DateTime<Utc>> {
+    starts_at
+        .checked_add_signed(parse_duration(value)?)
+        .ok_or_else(|| IcalError::InvalidValue("DURATION", value.to_string()))
+}
+
+pub(crate) fn format_datetime(dt: &DateTime<Utc>) -> String {
+    dt.format("%Y%m%dT%H%M%SZ").to_string()
+}
+
+pub(crate) fn format_date(dt: &DateTime<Utc>) -> String {
+    dt.format("%Y%m%d").to_string()
+}
+
+/// Formats a duration as `[-]P[nD][T[nH][nM][nS]]`.
+pub(crate) fn format_duration(duration: &Duration) -> String {
+    let sign = if *duration < Duration::zero() { "-" } else { "" };
+    let mut seconds = duration.num_seconds().abs();
+
+    let days = seconds / 86_400;
+    seconds %= 86_400;
+    let (hours, minutes, seconds) = (seconds / 3600, seconds / 60 % 60, seconds % 60);
+
+    let mut out = format!("{sign}P");
+
+    if days > 0 {
+        out.push_str(&format!("{days}D"));
+    }
+
+    if hours > 0 || minutes > 0 || seconds > 0 || days == 0 {
+        out.push('T');
+        if hours > 0 {
+            out.push_str(&format!("{hours}H"));
+        }
+        if minutes > 0 {
+            out.push_str(&format!("{minutes}M"));
+        }
+        if seconds > 0 || (hours == 0 && minutes == 0) {
+            out.push_str(&format!("{seconds}S"));
+        }
+    }
+
+    out
+}
+
+/// Parses the subset of RFC 5545 durations used in practice
+/// (`P1W`, `P1DT2H`, `PT30M`, `-PT15M`).
+pub(crate) fn parse_duration(value: &str) -> IcalResult<Duration> {
+    let invalid = || IcalError::InvalidValue("DURATION", value.to_string());
+
+    let (sign, rest) = match value.strip_prefix('-') {
+        Some(rest) => (-1, rest),
+        None => (1, value.strip_prefix('+').unwrap_or(value)),
+    };
+    let rest = rest.strip_prefix('P').ok_or_else(invalid)?;
+
+    let mut total = Duration::zero();
+    let mut number = String::new();
+    let mut in_time = false;
+
+    for c in rest.chars() {
+        match c {
+            'T' => in_time = true,
+            '0'..='9' => number.push(c),
+            unit => {
+                let n: i64 = number.parse().map_err(|_| invalid())?;
+                number.clear();
+                let part = match (unit, in_time) {
+                    ('W', false) => Duration::try_weeks(n),
+                    ('D', false) => Duration::try_days(n),
+                    ('H', true) => Duration::try_hours(n),
+                    ('M', true) => Duration::try_minutes(n),
+                    ('S', true) => Duration::try_seconds(n),
+                    _ => return Err(invalid()),
+                };
+                total = part
+                    .and_then(|part| total.checked_add(&part))
+                    .ok_or_else(invalid)?;
+            }
+        }
+    }
+
+    if !number.is_empty() {
+        return Err(invalid());
+    }
+
+    Ok(total * sign)
+}
+
+/// Parses an RRULE into the supported `RecurrenceRule` subset. `COUNT` is
+/// converted to an `UNTIL`; BY* parts other than a BYDAY matching the
+/// series' own weekday cannot be represented and are rejected.
+pub(crate) fn parse_rrule(
+    value: &str,
+    starts_at: &DateTime<Utc>,
+) -> IcalResult<RecurrenceRule> {
+    let mut frequency = None;
+    let mut interval = 1u32;
+    let mut until = None;
+    let mut count = None;
+
+    for part in value.split(';').filter(|p| !p.is_empty()) {
+        let (key, val) = part
+            .split_once('=')
+            .ok_or_else(|| IcalError::InvalidValue("RRULE", value.to_string()))?;
+
+        match key.to_uppercase().as_str() {
+            "FREQ" => frequency = Some(Frequency::from_str(val)?),
+            "INTERVAL" => {
+                interval = val
+                    .parse()
+                    .map_err(|_| IcalError::InvalidValue("RRULE", value.to_string()))?;
+            }
+            "UNTIL" => {
+                let property = Property::new("UNTIL", val);
+                until = Some(parse_date_value(&property, val)?.0);
+            }
+            "COUNT" => {
+                count = Some(
+                    val.parse::<u32>()
+                        .map_err(|_| IcalError::InvalidValue("RRULE", value.to_string()))?,
+                );
+            }
+            "WKST" => {}
+            "BYDAY" if val.eq_ignore_ascii_case(weekday_code(starts_at)) => {}
+            _ => return Err(IcalError::Unsupported(format!("RRULE part {part}"))),
+        }
+    }
+
+    let frequency = frequency.ok_or(IcalError::MissingProperty("FREQ"))?;
+    let rule = RecurrenceRule::new(frequency, interval, until)?;
+
+    match count {
+        Some(count) => {
+            let last = rule
+                .advance(*starts_at, count.saturating_sub(1))
+                .ok_or_else(|| IcalError::InvalidValue("RRULE", value.to_string()))?;
+            Ok(RecurrenceRule::new(frequency, interval, Some(last))?)
+        }
+        None => Ok(rule),
+    }
+}
+
+fn format_rrule(rule: &RecurrenceRule, is_all_day: bool) -> String {
+    let mut out = format!("FREQ={}", rule.frequency());
+
+    if *rule.interval() != 1 {
+        out.push_str(&format!(";INTERVAL={}", rule.interval()));
+    }
+
+    if let Some(until) = rule.until() {
+        let until = if is_all_day {
+            format_date(until)
+        } else {
+            format_datetime(until)
+        };
+        out.push_str(&format!(";UNTIL={until}"));
+    }
+
+    out
+}
+
+fn weekday_code(dt: &DateTime<Utc>) -> &'static str {
+    match dt.weekday() {
+        chrono::Weekday::Mon => "MO",
+        chrono::Weekday::Tue => "TU",
+        chrono::Weekday::Wed => "WE",
+        chrono::Weekday::Thu => "TH",
+        chrono::Weekday::Fri => "FR",
+        chrono::Weekday::Sat => "SA",
+        chrono::Weekday::Sun => "SU",
+    }
+}
@@ -0,0 +1,8 @@
+pub mod component;
+pub mod mappers;
+pub mod timezone;
+pub mod error;
+
+pub use component::{Component, Property};
+pub use mappers::IcalMapper;
+pub use error::IcalError;
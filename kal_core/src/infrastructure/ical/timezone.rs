@@ -0,0 +1,77 @@
+use std::{borrow::Cow, collections::HashMap};
+
+use chrono::{DateTime, Duration, LocalResult, NaiveDateTime, Offset, TimeZone, Utc};
+use chrono_tz::Tz;
+
+use super::component::Component;
+
+/// Resolves a TZID to an IANA timezone. Besides plain names such as
+/// `Europe/Berlin`, accepts the prefixed ones some clients generate,
+/// such as `/citadel.org/20190914_1/Europe/Berlin`.
+pub(crate) fn find(tzid: &str) -> Option<Tz> {
+    let tzid = tzid.trim();
+
+    tzid.parse::<Tz>().ok().or_else(|| {
+        tzid.match_indices('/')
+            .find_map(|(i, _)| tzid[i + 1..].parse::<Tz>().ok())
+    })
+}
+
+/// Rewrites TZIDs that are not IANA names to the IANA name their
+/// VTIMEZONE gives as its `X-LIC-LOCATION`. TZIDs left unknown fail to
+/// map later rather than being read as UTC.
+pub(crate) fn with_known_tzids(calendar: &Component) -> Cow<'_, Component> {
+    let aliases: HashMap<String, String> = calendar
+        .components_named("VTIMEZONE")
+        .filter_map(|vtimezone| {
+            let tzid = vtimezone.property("TZID")?.value.trim();
+            if find(tzid).is_some() {
+                return None;
+            }
+
+            let location = find(&vtimezone.property("X-LIC-LOCATION")?.value)?;
+            Some((tzid.to_string(), location.name().to_string()))
+        })
+        .collect();
+
+    if aliases.is_empty() {
+        return Cow::Borrowed(calendar);
+    }
+
+    let mut calendar = calendar.clone();
+    rename_tzids(&mut calendar, &aliases);
+    Cow::Owned(calendar)
+}
+
+fn rename_tzids(component: &mut Component, aliases: &HashMap<String, String>) {
+    for property in &mut component.properties {
+        for (name, value) in &mut property.params {
+            if name.eq_ignore_ascii_case("TZID")
+                && let Some(alias) = aliases.get(value.trim())
+            {
+                *value = alias.clone();
+            }
+        }
+    }
+
+    for child in &mut component.components {
+        rename_tzids(child, aliases);
+    }
+}
+
+/// The instant a wall-clock time in `tz` stands for. A time repeated
+/// when clocks go back is the earlier instant; one skipped when they go
+/// forward takes the offset from before the gap (RFC 5545 section 3.3.5).
+pub(crate) fn to_utc<Z: TimeZone>(local: &NaiveDateTime, tz: &Z) -> Option<DateTime<Utc>> {
+    match tz.from_local_datetime(local) {
+        LocalResult::Single(dt) | LocalResult::Ambiguous(dt, _) => Some(dt.with_timezone(&Utc)),
+        LocalResult::None => {
+            // No zone moves its clocks twice within a day
+            let before = tz
+                .offset_from_local_datetime(&local.checked_sub_signed(Duration::days(1))?)
+                .earliest()?;
+            let offset = Duration::seconds(before.fix().local_minus_utc().into());
+            Some(local.checked_sub_signed(offset)?.and_utc())
+        }
+    }
+}
@@ -0,0 +1,30 @@
+use thiserror::Error;
+
+use crate::{domain::repository::RepositoryError, infrastructure::ical::IcalError};
+
+#[derive(Debug, Error)]
+pub enum ItipError {
+    #[error("Not an iTIP message: {0}")]
+    Invalid(String),
+
+    #[error("Unsupported iTIP method: {0}")]
+    UnsupportedMethod(String),
+
+    #[error("Unsupported: {0}")]
+    Unsupported(String),
+
+    #[error("The event has no organizer")]
+    NoOrganizer,
+
+    #[error("The event has no attendees")]
+    NoAttendees,
+
+    #[error(transparent)]
+    Ical(#[from] IcalError),
+
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+
+    #[error(transparent)]
+    Repository(#[from] RepositoryError),
+}
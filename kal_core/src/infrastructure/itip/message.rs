@@ -0,0 +1,258 @@
+use std::fmt;
+
+use getset::Getters;
+
+use crate::{
+    domain::{
+        attendee::ParticipationStatus,
+        calendar_object::CalendarObject,
+        value_objects::EventId,
+    },
+    infrastructure::ical::{
+        mappers::{event_id_for_uid, mailto_address, sequence_of},
+        Component, IcalError, IcalMapper, Property,
+    },
+};
+
+use super::error::ItipError;
+
+type ItipResult<T> = Result<T, ItipError>;
+
+/// The iTIP methods kal sends and understands (RFC 5546 section 1.4).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ItipMethod {
+    Request,
+    Reply,
+    Cancel,
+}
+
+impl fmt::Display for ItipMethod {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ItipMethod::Request => write!(f, "REQUEST"),
+            ItipMethod::Reply => write!(f, "REPLY"),
+            ItipMethod::Cancel => write!(f, "CANCEL"),
+        }
+    }
+}
+
+impl std::str::FromStr for ItipMethod {
+    type Err = ItipError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_uppercase().as_str() {
+            "REQUEST" => Ok(ItipMethod::Request),
+            "REPLY" => Ok(ItipMethod::Reply),
+            "CANCEL" => Ok(ItipMethod::Cancel),
+            other => Err(ItipError::UnsupportedMethod(other.to_string())),
+        }
+    }
+}
+
+/// A scheduling message: a VCALENDAR with a METHOD, and the addresses it
+/// goes to. Requests and cancellations go from the organizer to the
+/// attendees; replies go back to the organizer.
+#[derive(Debug, Clone, Getters)]
+pub struct ItipMessage {
+    #[getset(get = "pub")]
+    method: ItipMethod,
+    #[getset(get = "pub")]
+    uid: String,
+    #[getset(get = "pub")]
+    sequence: u32,
+    #[getset(get = "pub")]
+    recipients: Vec<String>,
+    #[getset(get = "pub")]
+    calendar: Component,
+}
+
+impl ItipMessage {
+    /// What the organizer sends after a change: a CANCEL once the object
+    /// is cancelled, a REQUEST otherwise.
+    pub fn for_object(object: &CalendarObject) -> ItipResult<Self> {
+        if object.is_cancelled() {
+            Self::cancel(object)
+        } else {
+            Self::request(object)
+        }
+    }
+
+    /// Invites every attendee, or updates their copy when SEQUENCE has
+    /// moved on. Alarms are the organizer's own and are left out.
+    pub fn request(object: &CalendarObject) -> ItipResult<Self> {
+        Self::ensure_schedulable(object)?;
+
+        let mut components = IcalMapper::object_to_components(object);
+        for component in &mut components {
+            component.components.retain(|c| !c.name.eq_ignore_ascii_case("VALARM"));
+        }
+
+        Self::from_calendar(with_method(components, ItipMethod::Request))
+    }
+
+    /// Cancels the whole object for every attendee.
+    pub fn cancel(object: &CalendarObject) -> ItipResult<Self> {
+        Self::ensure_schedulable(object)?;
+
+        // Only the master is needed, marked cancelled even if the object
+        // itself is not (as when the organizer deletes it)
+        let mut master = IcalMapper::object_to_components(object).swap_remove(0);
+        master.components.clear();
+        master.properties.retain(|p| !p.name.eq_ignore_ascii_case("STATUS"));
+        master.push(Property::new("STATUS", "CANCELLED"));
+
+        Self::from_calendar(with_method(vec![master], ItipMethod::Cancel))
+    }
+
+    /// Reads a message received from elsewhere.
+    pub fn parse(ics: &str) -> ItipResult<Self> {
+        Self::from_calendar(Component::parse(ics)?)
+    }
+
+    pub fn to_ics(&self) -> String {
+        self.calendar.to_ics()
+    }
+
+    /// A file name unique per object, revision and method.
+    pub fn file_name(&self) -> String {
+        let uid: String = self
+            .uid
+            .chars()
+            .map(|c| if c.is_ascii_alphanumeric() || c == '-' { c } else { '_' })
+            .collect();
+
+        format!(
+            "{uid}-{}-{}.ics",
+            self.sequence,
+            self.method.to_string().to_lowercase()
+        )
+    }
+
+    /// The first VEVENT, which carries the scheduling properties.
+    pub fn vevent(&self) -> &Component {
+        self.calendar
+            .components_named("VEVENT")
+            .next()
+            .expect("checked when the message was built")
+    }
+
+    fn ensure_schedulable(object: &CalendarObject) -> ItipResult<()> {
+        if object.organizer().is_none() {
+            return Err(ItipError::NoOrganizer);
+        }
+        if object.attendees().is_empty() {
+            return Err(ItipError::NoAttendees);
+        }
+        Ok(())
+    }
+
+    fn from_calendar(calendar: Component) -> ItipResult<Self> {
+        if !calendar.name.eq_ignore_ascii_case("VCALENDAR") {
+            return Err(ItipError::Invalid(format!("expected VCALENDAR, got {}", calendar.name)));
+        }
+
+        let method = calendar
+            .property("METHOD")
+            .ok_or_else(|| ItipError::Invalid("missing METHOD".into()))?
+            .value
+            .parse::<ItipMethod>()?;
+
+        let vevent = calendar
+            .components_named("VEVENT")
+            .next()
+            .ok_or_else(|| ItipError::Invalid("no VEVENT".into()))?;
+
+        let uid = vevent
+            .property("UID")
+            .ok_or(IcalError::MissingProperty("UID"))?
+            .value
+            .clone();
+        let sequence = sequence_of(vevent)?;
+
+        let organizer = vevent.property("ORGANIZER").map(|p| mailto_address(&p.value));
+
+        let recipients = match method {
+            ItipMethod::Reply => organizer.into_iter().collect(),
+            ItipMethod::Request | ItipMethod::Cancel => vevent
+                .properties_named("ATTENDEE")
+                .map(|p| mailto_address(&p.value))
+                .filter(|email| {
+                    !organizer
+                        .as_ref()
+                        .is_some_and(|o| o.eq_ignore_ascii_case(email))
+                })
+                .collect(),
+        };
+
+        Ok(Self {
+            method,
+            uid,
+            sequence,
+            recipients,
+            calendar,
+        })
+    }
+}
+
+/// An attendee's answer to a request, as carried by a METHOD:REPLY.
+#[derive(Debug, Clone, Getters)]
+pub struct ItipReply {
+    #[getset(get = "pub")]
+    event_id: EventId,
+    #[getset(get = "pub")]
+    attendee: String,
+    #[getset(get = "pub")]
+    status: ParticipationStatus,
+    /// The revision of the request being answered.
+    #[getset(get = "pub")]
+    sequence: u32,
+}
+
+impl ItipReply {
+    pub fn parse(ics: &str) -> ItipResult<Self> {
+        Self::from_message(&ItipMessage::parse(ics)?)
+    }
+
+    pub fn from_message(message: &ItipMessage) -> ItipResult<Self> {
+        if *message.method() != ItipMethod::Reply {
+            return Err(ItipError::Invalid(format!(
+                "expected a REPLY, got a {}",
+                message.method()
+            )));
+        }
+
+        let vevent = message.vevent();
+
+        // Attendance is tracked per series, not per occurrence
+        if vevent.property("RECURRENCE-ID").is_some() {
+            return Err(ItipError::Unsupported("replies to a single occurrence".into()));
+        }
+
+        // A reply carries exactly the attendee who is answering
+        let mut attendees = vevent.properties_named("ATTENDEE");
+        let attendee = attendees
+            .next()
+            .ok_or(IcalError::MissingProperty("ATTENDEE"))?;
+        if attendees.next().is_some() {
+            return Err(ItipError::Invalid("a reply names more than one attendee".into()));
+        }
+
+        let partstat = attendee.param("PARTSTAT").unwrap_or("NEEDS-ACTION");
+        let status = partstat
+            .parse::<ParticipationStatus>()
+            .map_err(|_| IcalError::InvalidValue("PARTSTAT", partstat.to_string()))?;
+
+        Ok(Self {
+            event_id: event_id_for_uid(message.uid()),
+            attendee: mailto_address(&attendee.value),
+            status,
+            sequence: *message.sequence(),
+        })
+    }
+}
+
+fn with_method(components: Vec<Component>, method: ItipMethod) -> Component {
+    let mut calendar = IcalMapper::wrap(components);
+    calendar.push(Property::new("METHOD", method.to_string()));
+    calendar
+}
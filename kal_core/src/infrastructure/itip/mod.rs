@@ -0,0 +1,9 @@
+pub mod error;
+pub mod message;
+pub mod subscriber;
+pub mod transport;
+
+pub use error::ItipError;
+pub use message::{ItipMessage, ItipMethod, ItipReply};
+pub use subscriber::ItipSubscriber;
+pub use transport::{DirectoryTransport, StdoutTransport, Transport};
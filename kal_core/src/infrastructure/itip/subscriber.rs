@@ -0,0 +1,131 @@
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+};
+
+use async_trait::async_trait;
+
+use crate::{
+    application::history::{find_event, find_series},
+    domain::{
+        calendar_object::CalendarObject,
+        domain_event::{DomainEvent, EventContext, EventSubscriber},
+        repository::{EventRepository, RecurringEventRepository},
+        value_objects::EventId,
+    },
+};
+
+use super::{error::ItipError, message::ItipMessage, transport::Transport};
+
+/// Sends the organizer's messages as scheduled objects change: a REQUEST
+/// when an event or series with an organizer and attendees is created,
+/// changed or brought back, and a CANCEL when it is cancelled or trashed.
+/// Objects deleted for good are gone before they can be cancelled. Each
+/// stored version is sent once, however many events its change raised.
+pub struct ItipSubscriber<E, R> {
+    events: E,
+    recurring: R,
+    transport: Arc<dyn Transport>,
+    on_error: Box<dyn Fn(&ItipError) + Send + Sync>,
+    sent: Mutex<HashMap<EventId, u32>>,
+}
+
+impl<E, R> ItipSubscriber<E, R>
+where
+    E: EventRepository,
+    R: RecurringEventRepository,
+{
+    /// Failures are dropped until `on_error` is set.
+    pub fn new(events: E, recurring: R, transport: Arc<dyn Transport>) -> Self {
+        Self {
+            events,
+            recurring,
+            transport,
+            on_error: Box::new(|_| {}),
+            sent: Mutex::default(),
+        }
+    }
+
+    /// Hands every message that could not be built or sent to `on_error`;
+    /// the change it was about stands either way.
+    pub fn on_error(mut self, on_error: impl Fn(&ItipError) + Send + Sync + 'static) -> Self {
+        self.on_error = Box::new(on_error);
+        self
+    }
+
+    async fn send(&self, event: &DomainEvent) -> Result<(), ItipError> {
+        let Some(id) = event.event_id() else {
+            return Ok(());
+        };
+
+        let object = match event {
+            DomainEvent::EventCreated { .. }
+            | DomainEvent::EventChanged { .. }
+            | DomainEvent::EventRescheduled { .. }
+            | DomainEvent::EventCancelled { .. }
+            | DomainEvent::EventRestored { .. }
+            | DomainEvent::EventTrashed { .. }
+            | DomainEvent::EventRestoredFromTrash { .. } => {
+                find_event(&self.events, &id).await?.map(CalendarObject::Event)
+            }
+            DomainEvent::SeriesCreated { .. }
+            | DomainEvent::SeriesChanged { .. }
+            | DomainEvent::SeriesCancelled { .. }
+            | DomainEvent::SeriesRestored { .. }
+            | DomainEvent::SeriesTrashed { .. }
+            | DomainEvent::SeriesRestoredFromTrash { .. }
+            | DomainEvent::OccurrenceCancelled { .. }
+            | DomainEvent::OccurrenceRescheduled { .. }
+            | DomainEvent::OccurrenceRestored { .. }
+            | DomainEvent::OccurrenceChanged { .. } => {
+                find_series(&self.recurring, &id).await?.map(CalendarObject::Recurring)
+            }
+            _ => None,
+        };
+
+        let Some(object) = object.filter(is_scheduled) else {
+            return Ok(());
+        };
+        if !self.first_of(&object) {
+            return Ok(());
+        }
+
+        // A trashed object is cancelled for its attendees, and invited
+        // again when it comes back
+        let message = match event {
+            DomainEvent::EventTrashed { .. } | DomainEvent::SeriesTrashed { .. } => {
+                ItipMessage::cancel(&object)?
+            }
+            _ => ItipMessage::for_object(&object)?,
+        };
+
+        self.transport.send(&message).await
+    }
+
+    /// Notes the object's stored version as sent; false if it already was.
+    fn first_of(&self, object: &CalendarObject) -> bool {
+        let mut sent = match self.sent.lock() {
+            Ok(sent) => sent,
+            Err(poisoned) => poisoned.into_inner(),
+        };
+        let version = object.version();
+        sent.insert(*object.event_id(), version) != Some(version)
+    }
+}
+
+#[async_trait]
+impl<E, R> EventSubscriber for ItipSubscriber<E, R>
+where
+    E: EventRepository,
+    R: RecurringEventRepository,
+{
+    async fn notify(&self, event: &DomainEvent, _context: &EventContext) {
+        if let Err(error) = self.send(event).await {
+            (self.on_error)(&error);
+        }
+    }
+}
+
+fn is_scheduled(object: &CalendarObject) -> bool {
+    object.organizer().is_some() && !object.attendees().is_empty()
+}
@@ -0,0 +1,48 @@
+use std::path::PathBuf;
+
+use async_trait::async_trait;
+
+use super::{error::ItipError, message::ItipMessage};
+
+/// Delivers outgoing scheduling messages. There is no mail transport;
+/// messages are written out for something else to send.
+#[async_trait]
+pub trait Transport: Send + Sync {
+    async fn send(&self, message: &ItipMessage) -> Result<(), ItipError>;
+}
+
+/// Prints each message as an `.ics` document.
+pub struct StdoutTransport;
+
+#[async_trait]
+impl Transport for StdoutTransport {
+    async fn send(&self, message: &ItipMessage) -> Result<(), ItipError> {
+        print!("{}", message.to_ics());
+        Ok(())
+    }
+}
+
+/// Writes each message to its own file in a directory, named after the
+/// object, its sequence and the method.
+pub struct DirectoryTransport {
+    dir: PathBuf,
+}
+
+impl DirectoryTransport {
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self { dir: dir.into() }
+    }
+
+    pub fn path_for(&self, message: &ItipMessage) -> PathBuf {
+        self.dir.join(message.file_name())
+    }
+}
+
+#[async_trait]
+impl Transport for DirectoryTransport {
+    async fn send(&self, message: &ItipMessage) -> Result<(), ItipError> {
+        tokio::fs::create_dir_all(&self.dir).await?;
+        tokio::fs::write(self.path_for(message), message.to_ics()).await?;
+        Ok(())
+    }
+}
@@ -0,0 +1,150 @@
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use crate::domain::{
+    calendar::Calendar,
+    repository::{CalendarRepository, RepositoryError},
+    value_objects::CalendarId,
+};
+use super::{next_version, MemoryStore, Trashed};
+
+pub struct MemoryCalendarRepository {
+    store: MemoryStore,
+}
+
+impl MemoryCalendarRepository {
+    pub fn new(store: MemoryStore) -> Self {
+        Self { store }
+    }
+}
+
+#[async_trait]
+impl CalendarRepository for MemoryCalendarRepository {
+    async fn save(&self, calendar: &Calendar) -> Result<u32, RepositoryError> {
+        let mut state = self.store.write()?;
+
+        let id = *calendar.calendar_id();
+        let stored = state.calendars.get(&id);
+        // A trashed calendar keeps its version, so a new one reusing its id conflicts
+        let version = next_version(stored.map(|c| *c.version()), *calendar.version())?;
+        let deleted_at = stored.and_then(|c| *c.deleted_at());
+        state.calendars.insert(
+            id,
+            calendar
+                .clone()
+                .with_version(version)
+                .with_deleted_at(deleted_at)
+                .without_pending_events(),
+        );
+        Ok(version)
+    }
+
+    async fn find_by_id(&self, id: &CalendarId) -> Result<Option<Calendar>, RepositoryError> {
+        let state = self.store.read()?;
+        Ok(state.calendars.get(id).filter(|c| !c.is_trashed()).cloned())
+    }
+
+    async fn find_all_active(&self) -> Result<Vec<Calendar>, RepositoryError> {
+        let state = self.store.read()?;
+
+        let mut calendars: Vec<Calendar> = state
+            .calendars
+            .values()
+            .filter(|calendar| !calendar.is_archived() && !calendar.is_trashed())
+            .cloned()
+            .collect();
+        calendars.sort_by(|a, b| a.name().cmp(b.name()));
+
+        Ok(calendars)
+    }
+
+    async fn trash(&self, id: &CalendarId, deleted_at: DateTime<Utc>) -> Result<(), RepositoryError> {
+        let mut state = self.store.write()?;
+
+        let calendar = state
+            .calendars
+            .get_mut(id)
+            .filter(|c| !c.is_trashed())
+            .ok_or(RepositoryError::NotFound)?;
+        *calendar = calendar.clone().trashed(Some(deleted_at));
+
+        for event in state.events.values_mut() {
+            if event.calendar_id() == id && !event.is_trashed() {
+                *event = event.clone().trashed(Some(deleted_at));
+            }
+        }
+        for event in state.recurring.values_mut() {
+            if event.calendar_id() == id && !event.is_trashed() {
+                *event = event.clone().trashed(Some(deleted_at));
+            }
+        }
+
+        Ok(())
+    }
+
+    async fn restore(&self, id: &CalendarId) -> Result<(), RepositoryError> {
+        let mut state = self.store.write()?;
+
+        let calendar = state
+            .calendars
+            .get_mut(id)
+            .filter(|c| c.is_trashed())
+            .ok_or(RepositoryError::NotFound)?;
+        let deleted_at = *calendar.deleted_at();
+        *calendar = calendar.clone().trashed(None);
+
+        // Only what was trashed with the calendar shares its deletion time
+        for event in state.events.values_mut() {
+            if event.calendar_id() == id && *event.deleted_at() == deleted_at {
+                *event = event.clone().trashed(None);
+            }
+        }
+        for event in state.recurring.values_mut() {
+            if event.calendar_id() == id && *event.deleted_at() == deleted_at {
+                *event = event.clone().trashed(None);
+            }
+        }
+
+        Ok(())
+    }
+
+    async fn find_trashed(&self) -> Result<Vec<Calendar>, RepositoryError> {
+        let state = self.store.read()?;
+
+        let mut calendars: Vec<Calendar> = state
+            .calendars
+            .values()
+            .filter(|calendar| calendar.is_trashed())
+            .cloned()
+            .collect();
+        calendars.sort_by(|a, b| b.deleted_at().cmp(a.deleted_at()).then(a.name().cmp(b.name())));
+
+        Ok(calendars)
+    }
+
+    async fn purge_trashed(&self, cutoff: DateTime<Utc>) -> Result<u64, RepositoryError> {
+        let mut state = self.store.write()?;
+
+        let expired: Vec<CalendarId> = state
+            .calendars
+            .values()
+            .filter(|calendar| calendar.deleted_at().is_some_and(|at| at < cutoff))
+            .map(|calendar| *calendar.calendar_id())
+            .collect();
+
+        for id in &expired {
+            state.remove_calendar(id);
+        }
+
+        Ok(expired.len() as u64)
+    }
+
+    async fn delete(&self, id: &CalendarId) -> Result<(), RepositoryError> {
+        let mut state = self.store.write()?;
+
+        if !state.remove_calendar(id) {
+            return Err(RepositoryError::NotFound);
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,165 @@
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use crate::domain::{
+    event::Event,
+    repository::{EventRepository, RepositoryError},
+    tag::Tag,
+    value_objects::{CalendarId, EventId, TimeRange},
+};
+use super::{location_matches, next_version, MemoryStore, Trashed};
+
+pub struct MemoryEventRepository {
+    store: MemoryStore,
+}
+
+impl MemoryEventRepository {
+    pub fn new(store: MemoryStore) -> Self {
+        Self { store }
+    }
+
+    /// The stored events matching `filter`, by start.
+    fn collect(&self, filter: impl Fn(&Event) -> bool) -> Result<Vec<Event>, RepositoryError> {
+        let state = self.store.read()?;
+
+        let mut events: Vec<Event> = state
+            .events
+            .values()
+            .filter(|event| !event.is_trashed() && filter(event))
+            .cloned()
+            .collect();
+        events.sort_by_key(|event| *event.time_range().starts_at());
+
+        Ok(events)
+    }
+}
+
+#[async_trait]
+impl EventRepository for MemoryEventRepository {
+    async fn save(&self, event: &Event) -> Result<u32, RepositoryError> {
+        let mut state = self.store.write()?;
+        state.ensure_calendar(event.calendar_id())?;
+
+        let id = *event.event_id();
+        let stored = state.events.get(&id);
+        // A trashed event keeps its version, so a new one reusing its id conflicts
+        let version = next_version(stored.map(|e| *e.version()), *event.version())?;
+        let deleted_at = stored.and_then(|e| *e.deleted_at());
+        let event = event
+            .clone()
+            .with_version(version)
+            .with_deleted_at(deleted_at)
+            .without_pending_events();
+        state.events.insert(id, event);
+        Ok(version)
+    }
+
+    async fn find_by_id(&self, id: &EventId) -> Result<Option<Event>, RepositoryError> {
+        let state = self.store.read()?;
+        Ok(state.events.get(id).filter(|event| !event.is_trashed()).cloned())
+    }
+
+    async fn find_by_calendar(&self, calendar_id: &CalendarId) -> Result<Vec<Event>, RepositoryError> {
+        self.collect(|event| event.calendar_id() == calendar_id)
+    }
+
+    async fn find_in_range(
+        &self,
+        calendar_id: &CalendarId,
+        range: &TimeRange,
+    ) -> Result<Vec<Event>, RepositoryError> {
+        self.collect(|event| {
+            event.calendar_id() == calendar_id
+                && !event.is_cancelled()
+                && event.time_range().overlaps(range)
+        })
+    }
+
+    async fn find_by_location(
+        &self,
+        calendar_id: &CalendarId,
+        needle: &str,
+    ) -> Result<Vec<Event>, RepositoryError> {
+        self.collect(|event| {
+            event.calendar_id() == calendar_id
+                && location_matches(event.location().as_ref(), needle)
+        })
+    }
+
+    async fn find_by_tag(&self, tag: &Tag) -> Result<Vec<Event>, RepositoryError> {
+        self.collect(|event| event.tags().contains(tag))
+    }
+
+    async fn find_with_reminders_in(&self, window: &TimeRange) -> Result<Vec<Event>, RepositoryError> {
+        self.collect(|event| {
+            event
+                .reminder_instances()
+                .iter()
+                .any(|instance| window.contains(instance.fires_at()))
+        })
+    }
+
+    async fn trash(&self, id: &EventId, deleted_at: DateTime<Utc>) -> Result<(), RepositoryError> {
+        let mut state = self.store.write()?;
+
+        let event = state
+            .events
+            .get_mut(id)
+            .filter(|e| !e.is_trashed())
+            .ok_or(RepositoryError::NotFound)?;
+        *event = event.clone().trashed(Some(deleted_at));
+
+        Ok(())
+    }
+
+    async fn restore(&self, id: &EventId) -> Result<(), RepositoryError> {
+        let mut state = self.store.write()?;
+
+        let event = state
+            .events
+            .get_mut(id)
+            .filter(|e| e.is_trashed())
+            .ok_or(RepositoryError::NotFound)?;
+        *event = event.clone().trashed(None);
+
+        Ok(())
+    }
+
+    async fn find_trashed(&self) -> Result<Vec<Event>, RepositoryError> {
+        let state = self.store.read()?;
+
+        let mut events: Vec<Event> = state
+            .events
+            .values()
+            .filter(|event| event.is_trashed())
+            .cloned()
+            .collect();
+        events.sort_by(|a, b| {
+            b.deleted_at()
+                .cmp(a.deleted_at())
+                .then(a.time_range().starts_at().cmp(b.time_range().starts_at()))
+        });
+
+        Ok(events)
+    }
+
+    async fn purge_trashed(&self, cutoff: DateTime<Utc>) -> Result<u64, RepositoryError> {
+        let mut state = self.store.write()?;
+
+        let before = state.events.len();
+        state
+            .events
+            .retain(|_, event| !event.deleted_at().is_some_and(|at| at < cutoff));
+
+        Ok((before - state.events.len()) as u64)
+    }
+
+    async fn delete(&self, id: &EventId) -> Result<(), RepositoryError> {
+        let mut state = self.store.write()?;
+
+        if state.events.remove(id).is_none() {
+            return Err(RepositoryError::NotFound);
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,129 @@
+//! Repositories that keep everything in process memory, for tests and
+//! for embedding the core without a database. They follow the SQLite
+//! repositories' semantics, orderings and errors.
+
+pub mod calendar_repository;
+pub mod event_repository;
+pub mod recurring_event_repository;
+
+pub use calendar_repository::MemoryCalendarRepository;
+pub use event_repository::MemoryEventRepository;
+pub use recurring_event_repository::MemoryRecurringEventRepository;
+
+use std::{
+    collections::HashMap,
+    sync::{Arc, RwLock, RwLockReadGuard, RwLockWriteGuard},
+};
+
+use chrono::{DateTime, Utc};
+
+use crate::domain::{
+    calendar::Calendar,
+    event::Event,
+    recurrence::RecurringEvent,
+    repository::RepositoryError,
+    value_objects::{CalendarId, EventId},
+};
+
+/// The shared state behind the in-memory repositories; clones share it,
+/// the way the SQLite repositories share a pool.
+#[derive(Debug, Clone, Default)]
+pub struct MemoryStore {
+    state: Arc<RwLock<State>>,
+}
+
+#[derive(Debug, Default)]
+pub(crate) struct State {
+    pub(crate) calendars: HashMap<CalendarId, Calendar>,
+    pub(crate) events: HashMap<EventId, Event>,
+    pub(crate) recurring: HashMap<EventId, RecurringEvent>,
+}
+
+impl MemoryStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub(crate) fn read(&self) -> Result<RwLockReadGuard<'_, State>, RepositoryError> {
+        self.state
+            .read()
+            .map_err(|e| RepositoryError::DatabaseError(e.to_string()))
+    }
+
+    pub(crate) fn write(&self) -> Result<RwLockWriteGuard<'_, State>, RepositoryError> {
+        self.state
+            .write()
+            .map_err(|e| RepositoryError::DatabaseError(e.to_string()))
+    }
+}
+
+impl State {
+    /// Mirrors the foreign key from events and series to their calendar.
+    pub(crate) fn ensure_calendar(&self, calendar_id: &CalendarId) -> Result<(), RepositoryError> {
+        if self.calendars.contains_key(calendar_id) {
+            Ok(())
+        } else {
+            Err(RepositoryError::DatabaseError(format!(
+                "FOREIGN KEY constraint failed: calendar {calendar_id} does not exist"
+            )))
+        }
+    }
+
+    /// Removes a calendar with its events and series, as ON DELETE
+    /// CASCADE does. Returns whether it existed.
+    pub(crate) fn remove_calendar(&mut self, calendar_id: &CalendarId) -> bool {
+        if self.calendars.remove(calendar_id).is_none() {
+            return false;
+        }
+
+        self.events.retain(|_, event| event.calendar_id() != calendar_id);
+        self.recurring.retain(|_, event| event.calendar_id() != calendar_id);
+        true
+    }
+}
+
+/// Moves a stored object in or out of the trash, bumping its version
+/// like the SQLite `UPDATE` does.
+pub(crate) trait Trashed: Sized {
+    fn trashed(self, deleted_at: Option<DateTime<Utc>>) -> Self;
+}
+
+impl Trashed for Calendar {
+    fn trashed(self, deleted_at: Option<DateTime<Utc>>) -> Self {
+        let version = *self.version() + 1;
+        self.with_version(version).with_deleted_at(deleted_at)
+    }
+}
+
+impl Trashed for Event {
+    fn trashed(self, deleted_at: Option<DateTime<Utc>>) -> Self {
+        let version = *self.version() + 1;
+        self.with_version(version).with_deleted_at(deleted_at)
+    }
+}
+
+impl Trashed for RecurringEvent {
+    fn trashed(self, deleted_at: Option<DateTime<Utc>>) -> Self {
+        let version = *self.version() + 1;
+        self.with_version(version).with_deleted_at(deleted_at)
+    }
+}
+
+/// The version a save stores: one past the loaded version, unless the
+/// stored row has moved on since it was loaded.
+pub(crate) fn next_version(stored: Option<u32>, loaded: u32) -> Result<u32, RepositoryError> {
+    match stored {
+        Some(stored) if stored != loaded => Err(RepositoryError::Conflict),
+        _ => Ok(loaded + 1),
+    }
+}
+
+/// Whether `location` contains `needle`, ignoring ASCII case like
+/// SQLite's `LIKE`.
+pub(crate) fn location_matches(location: Option<&String>, needle: &str) -> bool {
+    location.is_some_and(|location| {
+        location
+            .to_ascii_lowercase()
+            .contains(&needle.to_ascii_lowercase())
+    })
+}
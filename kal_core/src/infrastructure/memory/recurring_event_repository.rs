@@ -0,0 +1,167 @@
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use crate::domain::{
+    recurrence::{ExceptionModification, RecurringEvent},
+    repository::{RecurringEventRepository, RepositoryError},
+    tag::Tag,
+    value_objects::{CalendarId, EventId, TimeRange},
+};
+use super::{location_matches, next_version, MemoryStore, Trashed};
+
+pub struct MemoryRecurringEventRepository {
+    store: MemoryStore,
+}
+
+impl MemoryRecurringEventRepository {
+    pub fn new(store: MemoryStore) -> Self {
+        Self { store }
+    }
+
+    /// The stored series matching `filter`, by first start.
+    fn collect(
+        &self,
+        filter: impl Fn(&RecurringEvent) -> bool,
+    ) -> Result<Vec<RecurringEvent>, RepositoryError> {
+        let state = self.store.read()?;
+
+        let mut events: Vec<RecurringEvent> = state
+            .recurring
+            .values()
+            .filter(|event| !event.is_trashed() && filter(event))
+            .cloned()
+            .collect();
+        events.sort_by_key(|event| *event.time_range().starts_at());
+
+        Ok(events)
+    }
+}
+
+#[async_trait]
+impl RecurringEventRepository for MemoryRecurringEventRepository {
+    async fn save(&self, event: &RecurringEvent) -> Result<u32, RepositoryError> {
+        let mut state = self.store.write()?;
+        state.ensure_calendar(event.calendar_id())?;
+
+        let id = *event.event_id();
+        let stored = state.recurring.get(&id);
+        // A trashed series keeps its version, so a new one reusing its id conflicts
+        let version = next_version(stored.map(|e| *e.version()), *event.version())?;
+        let deleted_at = stored.and_then(|e| *e.deleted_at());
+        let event = event
+            .clone()
+            .with_version(version)
+            .with_deleted_at(deleted_at)
+            .without_pending_events();
+        state.recurring.insert(id, event);
+        Ok(version)
+    }
+
+    async fn find_by_calendar(
+        &self,
+        calendar_id: &CalendarId,
+    ) -> Result<Vec<RecurringEvent>, RepositoryError> {
+        self.collect(|event| event.calendar_id() == calendar_id)
+    }
+
+    async fn find_by_id(&self, event_id: &EventId) -> Result<RecurringEvent, RepositoryError> {
+        let state = self.store.read()?;
+
+        state
+            .recurring
+            .get(event_id)
+            .filter(|event| !event.is_trashed())
+            .cloned()
+            .ok_or(RepositoryError::NotFound)
+    }
+
+    async fn find_by_location(
+        &self,
+        calendar_id: &CalendarId,
+        needle: &str,
+    ) -> Result<Vec<RecurringEvent>, RepositoryError> {
+        self.collect(|event| {
+            event.calendar_id() == calendar_id
+                && (location_matches(event.location().as_ref(), needle)
+                    || event.exceptions().values().any(|ex| {
+                        !matches!(ex.modification(), ExceptionModification::Cancelled)
+                            && location_matches(ex.location().as_ref(), needle)
+                    }))
+        })
+    }
+
+    async fn find_by_tag(&self, tag: &Tag) -> Result<Vec<RecurringEvent>, RepositoryError> {
+        self.collect(|event| event.tags().contains(tag))
+    }
+
+    async fn find_with_reminders_in(
+        &self,
+        window: &TimeRange,
+    ) -> Result<Vec<RecurringEvent>, RepositoryError> {
+        self.collect(|event| !event.reminder_instances(window).is_empty())
+    }
+
+    async fn trash(&self, id: &EventId, deleted_at: DateTime<Utc>) -> Result<(), RepositoryError> {
+        let mut state = self.store.write()?;
+
+        let event = state
+            .recurring
+            .get_mut(id)
+            .filter(|e| !e.is_trashed())
+            .ok_or(RepositoryError::NotFound)?;
+        *event = event.clone().trashed(Some(deleted_at));
+
+        Ok(())
+    }
+
+    async fn restore(&self, id: &EventId) -> Result<(), RepositoryError> {
+        let mut state = self.store.write()?;
+
+        let event = state
+            .recurring
+            .get_mut(id)
+            .filter(|e| e.is_trashed())
+            .ok_or(RepositoryError::NotFound)?;
+        *event = event.clone().trashed(None);
+
+        Ok(())
+    }
+
+    async fn find_trashed(&self) -> Result<Vec<RecurringEvent>, RepositoryError> {
+        let state = self.store.read()?;
+
+        let mut events: Vec<RecurringEvent> = state
+            .recurring
+            .values()
+            .filter(|event| event.is_trashed())
+            .cloned()
+            .collect();
+        events.sort_by(|a, b| {
+            b.deleted_at()
+                .cmp(a.deleted_at())
+                .then(a.time_range().starts_at().cmp(b.time_range().starts_at()))
+        });
+
+        Ok(events)
+    }
+
+    async fn purge_trashed(&self, cutoff: DateTime<Utc>) -> Result<u64, RepositoryError> {
+        let mut state = self.store.write()?;
+
+        let before = state.recurring.len();
+        state
+            .recurring
+            .retain(|_, event| !event.deleted_at().is_some_and(|at| at < cutoff));
+
+        Ok((before - state.recurring.len()) as u64)
+    }
+
+    async fn delete(&self, id: &EventId) -> Result<(), RepositoryError> {
+        let mut state = self.store.write()?;
+
+        if state.recurring.remove(id).is_none() {
+            return Err(RepositoryError::NotFound);
+        }
+
+        Ok(())
+    }
+}
@@ -1 +1,18 @@
 pub mod persistence;
+pub mod ical;
+pub mod reminders;
+pub mod itip;
+#[cfg(feature = "memory")]
+pub mod memory;
+#[cfg(feature = "caldav")]
+pub mod caldav;
+#[cfg(feature = "webcal")]
+pub mod webcal;
+#[cfg(feature = "caldav-server")]
+pub mod caldav_server;
+#[cfg(any(feature = "api", feature = "daemon"))]
+pub mod dto;
+#[cfg(feature = "api")]
+pub mod api;
+#[cfg(feature = "daemon")]
+pub mod rpc;
@@ -0,0 +1,122 @@
+use sqlx::{SqliteConnection, SqliteExecutor};
+
+use crate::domain::{
+    attendee::{Attendee, Organizer},
+    repository::RepositoryError,
+    value_objects::EventId,
+};
+
+use super::{
+    mappers::ParticipantMapper,
+    models::{AttendeeModel, OrganizerModel},
+};
+
+/// Loads the attendee rows of an event or recurring series, in the order
+/// they were invited.
+pub(crate) async fn fetch_attendees<'e>(
+    executor: impl SqliteExecutor<'e>,
+    event_id: &str,
+) -> Result<Vec<AttendeeModel>, RepositoryError> {
+    sqlx::query_as::<_, AttendeeModel>(
+        r#"
+            SELECT event_id, email, position, name, role, status, rsvp
+            FROM attendees
+            WHERE event_id = ?1
+            ORDER BY position
+        "#
+    )
+    .bind(event_id)
+    .fetch_all(executor)
+    .await
+    .map_err(|e| RepositoryError::DatabaseError(e.to_string()))
+}
+
+pub(crate) async fn fetch_organizer<'e>(
+    executor: impl SqliteExecutor<'e>,
+    event_id: &str,
+) -> Result<Option<OrganizerModel>, RepositoryError> {
+    sqlx::query_as::<_, OrganizerModel>(
+        r#"
+            SELECT event_id, email, name
+            FROM organizers
+            WHERE event_id = ?1
+        "#
+    )
+    .bind(event_id)
+    .fetch_optional(executor)
+    .await
+    .map_err(|e| RepositoryError::DatabaseError(e.to_string()))
+}
+
+/// Replaces the stored organizer and attendees of an event or series.
+/// The caller owns the transaction.
+pub(crate) async fn replace_participants(
+    conn: &mut SqliteConnection,
+    event_id: &EventId,
+    organizer: Option<&Organizer>,
+    attendees: &[Attendee],
+) -> Result<(), RepositoryError> {
+    let id_str = event_id.to_string();
+
+    sqlx::query!(
+        r#"
+            DELETE FROM organizers WHERE event_id = ?1
+        "#,
+        id_str,
+    )
+    .execute(&mut *conn)
+    .await
+    .map_err(|e| RepositoryError::DatabaseError(e.to_string()))?;
+
+    sqlx::query!(
+        r#"
+            DELETE FROM attendees WHERE event_id = ?1
+        "#,
+        id_str,
+    )
+    .execute(&mut *conn)
+    .await
+    .map_err(|e| RepositoryError::DatabaseError(e.to_string()))?;
+
+    if let Some(organizer) = organizer {
+        let model = ParticipantMapper::organizer_to_model(organizer, event_id);
+
+        sqlx::query!(
+            r#"
+                INSERT INTO organizers (event_id, email, name)
+                VALUES (?1, ?2, ?3)
+            "#,
+            model.event_id,
+            model.email,
+            model.name,
+        )
+        .execute(&mut *conn)
+        .await
+        .map_err(|e| RepositoryError::DatabaseError(e.to_string()))?;
+    }
+
+    for (position, attendee) in attendees.iter().enumerate() {
+        let model = ParticipantMapper::attendee_to_model(attendee, event_id, position);
+
+        sqlx::query!(
+            r#"
+                INSERT INTO attendees (
+                    event_id, email, position, name, role, status, rsvp
+                )
+                VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)
+            "#,
+            model.event_id,
+            model.email,
+            model.position,
+            model.name,
+            model.role,
+            model.status,
+            model.rsvp,
+        )
+        .execute(&mut *conn)
+        .await
+        .map_err(|e| RepositoryError::DatabaseError(e.to_string()))?;
+    }
+
+    Ok(())
+}
@@ -0,0 +1,165 @@
+use async_trait::async_trait;
+use sqlx::{Connection, SqliteConnection, SqlitePool};
+use crate::domain::{
+    audit::AuditEntry,
+    domain_event::{DomainEvent, EventContext},
+    repository::{AuditRepository, RepositoryError},
+    value_objects::{CalendarId, EventId},
+};
+use super::{
+    handle::Handle,
+    mappers::AuditMapper,
+    models::{AuditChangeModel, AuditEntryModel},
+};
+
+pub struct SqliteAuditRepository {
+    handle: Handle,
+}
+
+impl SqliteAuditRepository {
+    pub fn new(pool: SqlitePool) -> Self {
+        Self { handle: Handle::Pool(pool) }
+    }
+
+    pub(crate) fn with_handle(handle: Handle) -> Self {
+        Self { handle }
+    }
+
+    /// Loads each entry's changes, in the order the event listed them.
+    async fn hydrate(
+        conn: &mut SqliteConnection,
+        models: Vec<AuditEntryModel>,
+    ) -> Result<Vec<AuditEntry>, RepositoryError> {
+        let mut entries = Vec::with_capacity(models.len());
+
+        for model in models {
+            let changes = sqlx::query_as::<_, AuditChangeModel>(
+                r#"
+                SELECT entry_id, position, field, from_value, to_value
+                FROM audit_changes
+                WHERE entry_id = ?1
+                ORDER BY position
+                "#
+            )
+            .bind(model.id)
+            .fetch_all(&mut *conn)
+            .await
+            .map_err(|e| RepositoryError::DatabaseError(e.to_string()))?;
+
+            entries.push(
+                AuditMapper::to_domain(model, changes)
+                    .map_err(|e| RepositoryError::DatabaseError(e.to_string()))?,
+            );
+        }
+
+        Ok(entries)
+    }
+}
+
+#[async_trait]
+impl AuditRepository for SqliteAuditRepository {
+    async fn append(&self, event: &DomainEvent, context: &EventContext) -> Result<i64, RepositoryError> {
+        let mut conn = self.handle.acquire().await?;
+        let mut tx = conn.begin()
+            .await
+            .map_err(|e| RepositoryError::DatabaseError(e.to_string()))?;
+
+        let recorded_at = context.occurred_at().to_rfc3339();
+        let actor = context.actor();
+        let command = context.command();
+        let name = event.name();
+        let calendar_id = event.calendar_id().to_string();
+        let object_id = event.object_id().to_string();
+        let occurrence = event.occurrence().map(|dt| dt.to_rfc3339());
+
+        let entry_id = sqlx::query!(
+            r#"
+                INSERT INTO audit_entries (
+                    recorded_at, actor, command, event, calendar_id, object_id, occurrence
+                )
+                VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)
+            "#,
+            recorded_at,
+            actor,
+            command,
+            name,
+            calendar_id,
+            object_id,
+            occurrence,
+        )
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| RepositoryError::DatabaseError(e.to_string()))?
+        .last_insert_rowid();
+
+        for (position, change) in event.changes().iter().enumerate() {
+            let position = position as i64;
+            let field = change.field();
+            let from = change.from();
+            let to = change.to();
+
+            sqlx::query!(
+                r#"
+                    INSERT INTO audit_changes (entry_id, position, field, from_value, to_value)
+                    VALUES (?1, ?2, ?3, ?4, ?5)
+                "#,
+                entry_id,
+                position,
+                field,
+                from,
+                to,
+            )
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| RepositoryError::DatabaseError(e.to_string()))?;
+        }
+
+        tx.commit()
+            .await
+            .map_err(|e| RepositoryError::DatabaseError(e.to_string()))?;
+
+        Ok(entry_id)
+    }
+
+    async fn find_by_event(&self, event_id: &EventId, limit: u32) -> Result<Vec<AuditEntry>, RepositoryError> {
+        let mut conn = self.handle.acquire().await?;
+
+        let models = sqlx::query_as::<_, AuditEntryModel>(
+            r#"
+            SELECT id, recorded_at, actor, command, event, calendar_id, object_id, occurrence
+            FROM audit_entries
+            WHERE object_id = ?1
+            ORDER BY id DESC
+            LIMIT ?2
+            "#
+        )
+        .bind(event_id.to_string())
+        .bind(limit)
+        .fetch_all(&mut *conn)
+        .await
+        .map_err(|e| RepositoryError::DatabaseError(e.to_string()))?;
+
+        Self::hydrate(&mut conn, models).await
+    }
+
+    async fn find_by_calendar(&self, calendar_id: &CalendarId, limit: u32) -> Result<Vec<AuditEntry>, RepositoryError> {
+        let mut conn = self.handle.acquire().await?;
+
+        let models = sqlx::query_as::<_, AuditEntryModel>(
+            r#"
+            SELECT id, recorded_at, actor, command, event, calendar_id, object_id, occurrence
+            FROM audit_entries
+            WHERE calendar_id = ?1
+            ORDER BY id DESC
+            LIMIT ?2
+            "#
+        )
+        .bind(calendar_id.to_string())
+        .bind(limit)
+        .fetch_all(&mut *conn)
+        .await
+        .map_err(|e| RepositoryError::DatabaseError(e.to_string()))?;
+
+        Self::hydrate(&mut conn, models).await
+    }
+}
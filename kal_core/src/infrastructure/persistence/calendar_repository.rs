@@ -1,5 +1,6 @@
 use async_trait::async_trait;
-use sqlx::SqlitePool;
+use chrono::{DateTime, Utc};
+use sqlx::{Connection, SqlitePool};
 use crate::{
     domain::{
         calendar::Calendar,
@@ -8,65 +9,103 @@ use crate::{
     },
     infrastructure::persistence::models::CalendarModel
 };
-use super::mappers::CalendarMapper;
+use super::{handle::Handle, mappers::CalendarMapper};
 
 pub struct SqliteCalendarRepository {
-    pool: SqlitePool,
+    handle: Handle,
 }
 
 impl SqliteCalendarRepository {
     pub fn new(pool: SqlitePool) -> Self {
-        Self { pool }
+        Self { handle: Handle::Pool(pool) }
+    }
+
+    pub(crate) fn with_handle(handle: Handle) -> Self {
+        Self { handle }
     }
 }
 
 #[async_trait]
 impl CalendarRepository for SqliteCalendarRepository {
-    async fn save(&self, calendar: &Calendar) -> Result<(), RepositoryError> {
+    async fn save(&self, calendar: &Calendar) -> Result<u32, RepositoryError> {
+        let mut conn = self.handle.acquire().await?;
+
         let model = CalendarMapper::to_model(calendar);
 
-        sqlx::query!(
+        let mut tx = conn.begin()
+            .await
+            .map_err(|e| RepositoryError::DatabaseError(e.to_string()))?;
+
+        let result = sqlx::query!(
             r#"
                 INSERT INTO calendars (
                     id, name, description, is_archived,
-                    created_at, updated_at
+                    subscription_url, refresh_interval, last_fetched_at,
+                    fetch_etag, fetch_last_modified, created_at, updated_at, version
                 )
-                VALUES (?1, ?2, ?3, ?4, ?5, ?6)
+                VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?12, ?9, ?10, ?11 + 1)
                 ON CONFLICT(id) DO UPDATE SET
                     name = excluded.name,
                     description = excluded.description,
                     is_archived = excluded.is_archived,
-                    updated_at = excluded.updated_at
+                    subscription_url = excluded.subscription_url,
+                    refresh_interval = excluded.refresh_interval,
+                    last_fetched_at = excluded.last_fetched_at,
+                    fetch_etag = excluded.fetch_etag,
+                    fetch_last_modified = excluded.fetch_last_modified,
+                    updated_at = excluded.updated_at,
+                    version = calendars.version + 1
+                WHERE calendars.version = ?11
             "#,
             model.id,
             model.name,
             model.description,
             model.is_archived,
+            model.subscription_url,
+            model.refresh_interval,
+            model.last_fetched_at,
+            model.fetch_etag,
             model.created_at,
             model.updated_at,
+            model.version,
+            model.fetch_last_modified,
         )
-        .execute(&self.pool)
+        .execute(&mut *tx)
         .await
         .map_err(|e| RepositoryError::DatabaseError(e.to_string()))?;
 
-        Ok(())
+        // The upsert skips the update when the stored version moved on,
+        // which covers a new calendar reusing the id of a trashed one
+        if result.rows_affected() == 0 {
+            return Err(RepositoryError::Conflict);
+        }
+
+        tx.commit()
+            .await
+            .map_err(|e| RepositoryError::DatabaseError(e.to_string()))?;
+
+        Ok(*calendar.version() + 1)
     }
 
     async fn find_by_id(
         &self,
         id: &CalendarId
     ) -> Result<Option<Calendar>, RepositoryError> {
+        let mut conn = self.handle.acquire().await?;
+
         let id_str = id.to_string();
 
         let model = sqlx::query_as::<_, CalendarModel>(
             r#"
-            SELECT id, name, description, is_archived, created_at, updated_at
+            SELECT id, name, description, is_archived, subscription_url,
+                   refresh_interval, last_fetched_at, fetch_etag,
+                   fetch_last_modified, version, deleted_at, created_at, updated_at
             FROM calendars
-            WHERE id = ?1
+            WHERE id = ?1 AND deleted_at IS NULL
             "#
         )
         .bind(&id_str)
-        .fetch_optional(&self.pool)
+        .fetch_optional(&mut *conn)
         .await
         .map_err(|e| RepositoryError::DatabaseError(e.to_string()))?;
 
@@ -81,15 +120,19 @@ impl CalendarRepository for SqliteCalendarRepository {
     }
 
     async fn find_all_active(&self) -> Result<Vec<Calendar>, RepositoryError> {
+        let mut conn = self.handle.acquire().await?;
+
         let models = sqlx::query_as::<_, CalendarModel>(
             r#"
-            SELECT id, name, description, is_archived, created_at, updated_at
+            SELECT id, name, description, is_archived, subscription_url,
+                   refresh_interval, last_fetched_at, fetch_etag,
+                   fetch_last_modified, version, deleted_at, created_at, updated_at
             FROM calendars
-            WHERE is_archived = 0
+            WHERE is_archived = 0 AND deleted_at IS NULL
             ORDER BY name
             "#
         )
-        .fetch_all(&self.pool)
+        .fetch_all(&mut *conn)
         .await
         .map_err(|e| RepositoryError::DatabaseError(e.to_string()))?;
 
@@ -100,7 +143,165 @@ impl CalendarRepository for SqliteCalendarRepository {
             .collect()
     }
 
+    async fn trash(&self, id: &CalendarId, deleted_at: DateTime<Utc>) -> Result<(), RepositoryError> {
+        let mut conn = self.handle.acquire().await?;
+        let mut tx = conn.begin()
+            .await
+            .map_err(|e| RepositoryError::DatabaseError(e.to_string()))?;
+
+        let id_str = id.to_string();
+        let deleted_at = deleted_at.to_rfc3339();
+
+        // The calendar goes first so its contents are not synced as deleted
+        let result = sqlx::query!(
+            r#"
+                UPDATE calendars SET deleted_at = ?2, version = version + 1
+                WHERE id = ?1 AND deleted_at IS NULL
+            "#,
+            id_str,
+            deleted_at,
+        )
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| RepositoryError::DatabaseError(e.to_string()))?;
+
+        if result.rows_affected() == 0 {
+            return Err(RepositoryError::NotFound);
+        }
+
+        sqlx::query!(
+            r#"
+                UPDATE events SET deleted_at = ?2, version = version + 1
+                WHERE calendar_id = ?1 AND deleted_at IS NULL
+            "#,
+            id_str,
+            deleted_at,
+        )
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| RepositoryError::DatabaseError(e.to_string()))?;
+
+        sqlx::query!(
+            r#"
+                UPDATE recurrences SET deleted_at = ?2, version = version + 1
+                WHERE calendar_id = ?1 AND deleted_at IS NULL
+            "#,
+            id_str,
+            deleted_at,
+        )
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| RepositoryError::DatabaseError(e.to_string()))?;
+
+        tx.commit()
+            .await
+            .map_err(|e| RepositoryError::DatabaseError(e.to_string()))?;
+
+        Ok(())
+    }
+
+    async fn restore(&self, id: &CalendarId) -> Result<(), RepositoryError> {
+        let mut conn = self.handle.acquire().await?;
+        let mut tx = conn.begin()
+            .await
+            .map_err(|e| RepositoryError::DatabaseError(e.to_string()))?;
+
+        let id_str = id.to_string();
+
+        // Only what was trashed with the calendar shares its deletion time
+        sqlx::query!(
+            r#"
+                UPDATE events SET deleted_at = NULL, version = version + 1
+                WHERE calendar_id = ?1
+                  AND deleted_at = (SELECT deleted_at FROM calendars WHERE id = ?1)
+            "#,
+            id_str,
+        )
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| RepositoryError::DatabaseError(e.to_string()))?;
+
+        sqlx::query!(
+            r#"
+                UPDATE recurrences SET deleted_at = NULL, version = version + 1
+                WHERE calendar_id = ?1
+                  AND deleted_at = (SELECT deleted_at FROM calendars WHERE id = ?1)
+            "#,
+            id_str,
+        )
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| RepositoryError::DatabaseError(e.to_string()))?;
+
+        let result = sqlx::query!(
+            r#"
+                UPDATE calendars SET deleted_at = NULL, version = version + 1
+                WHERE id = ?1 AND deleted_at IS NOT NULL
+            "#,
+            id_str,
+        )
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| RepositoryError::DatabaseError(e.to_string()))?;
+
+        if result.rows_affected() == 0 {
+            return Err(RepositoryError::NotFound);
+        }
+
+        tx.commit()
+            .await
+            .map_err(|e| RepositoryError::DatabaseError(e.to_string()))?;
+
+        Ok(())
+    }
+
+    async fn find_trashed(&self) -> Result<Vec<Calendar>, RepositoryError> {
+        let mut conn = self.handle.acquire().await?;
+
+        let models = sqlx::query_as::<_, CalendarModel>(
+            r#"
+            SELECT id, name, description, is_archived, subscription_url,
+                   refresh_interval, last_fetched_at, fetch_etag,
+                   fetch_last_modified, version, deleted_at, created_at, updated_at
+            FROM calendars
+            WHERE deleted_at IS NOT NULL
+            ORDER BY deleted_at DESC, name
+            "#
+        )
+        .fetch_all(&mut *conn)
+        .await
+        .map_err(|e| RepositoryError::DatabaseError(e.to_string()))?;
+
+        models
+            .into_iter()
+            .map(|m| CalendarMapper::to_domain(m)
+                .map_err(|e| RepositoryError::DatabaseError(e.to_string())))
+            .collect()
+    }
+
+    async fn purge_trashed(&self, cutoff: DateTime<Utc>) -> Result<u64, RepositoryError> {
+        let mut conn = self.handle.acquire().await?;
+
+        // Compared as instants, as stored timestamps need not share the
+        // cutoff's offset or precision
+        let cutoff = cutoff.to_rfc3339();
+
+        let result = sqlx::query!(
+            r#"
+                DELETE FROM calendars WHERE julianday(deleted_at) < julianday(?1)
+            "#,
+            cutoff,
+        )
+        .execute(&mut *conn)
+        .await
+        .map_err(|e| RepositoryError::DatabaseError(e.to_string()))?;
+
+        Ok(result.rows_affected())
+    }
+
     async fn delete(&self, id: &CalendarId) -> Result<(), RepositoryError> {
+        let mut conn = self.handle.acquire().await?;
+
         let id_str = id.to_string();
 
         let result = sqlx::query!(
@@ -109,7 +310,7 @@ impl CalendarRepository for SqliteCalendarRepository {
             "#,
             id_str,
         )
-        .execute(&self.pool)
+        .execute(&mut *conn)
         .await
         .map_err(|e| RepositoryError::DatabaseError(e.to_string()))?;
 
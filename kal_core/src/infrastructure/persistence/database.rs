@@ -0,0 +1,148 @@
+use std::{path::Path, str::FromStr};
+
+use sqlx::{
+    migrate::Migrator,
+    sqlite::{SqliteConnectOptions, SqliteJournalMode, SqlitePoolOptions},
+    SqlitePool,
+};
+
+use crate::domain::repository::RepositoryError;
+
+use super::{
+    error::DatabaseError,
+    SqliteAuditRepository, SqliteCalendarRepository, SqliteEventRepository, SqliteEventSearchRepository,
+    SqliteHistoryRepository, SqliteJournalRepository, SqliteRecurringEventRepository,
+    SqliteReminderStateRepository,
+    SqliteSubscriptionRepository, SqliteSyncStateRepository, SqliteTagRepository,
+    SqliteTaskRepository, SqliteUnitOfWork,
+};
+
+/// The schema migrations, embedded at build time.
+pub static MIGRATOR: Migrator = sqlx::migrate!("../migrations");
+
+/// An open kal database with an up-to-date schema, handing out the
+/// repositories that work on it.
+#[derive(Debug, Clone)]
+pub struct Database {
+    pool: SqlitePool,
+}
+
+impl Database {
+    /// Opens the database at `path`, creating it and its directory if
+    /// missing, and applies any pending migrations.
+    pub async fn open(path: impl AsRef<Path>) -> Result<Self, DatabaseError> {
+        let path = path.as_ref();
+
+        if let Some(dir) = path.parent().filter(|dir| !dir.as_os_str().is_empty()) {
+            std::fs::create_dir_all(dir)?;
+        }
+
+        let options = SqliteConnectOptions::new()
+            .filename(path)
+            .create_if_missing(true)
+            .foreign_keys(true)
+            .journal_mode(SqliteJournalMode::Wal);
+
+        Self::connect(options, SqlitePoolOptions::new()).await
+    }
+
+    /// A private database that lives as long as this handle, for tests
+    /// and throwaway use. It has a single connection, so while a unit of
+    /// work is open only that unit's repositories can reach it.
+    pub async fn open_in_memory() -> Result<Self, DatabaseError> {
+        let options = SqliteConnectOptions::from_str("sqlite::memory:")?.foreign_keys(true);
+
+        // Every connection to `:memory:` opens its own database, so keep
+        // exactly one alive
+        let pool_options = SqlitePoolOptions::new()
+            .max_connections(1)
+            .idle_timeout(None)
+            .max_lifetime(None);
+
+        Self::connect(options, pool_options).await
+    }
+
+    async fn connect(
+        options: SqliteConnectOptions,
+        pool_options: SqlitePoolOptions,
+    ) -> Result<Self, DatabaseError> {
+        let pool = pool_options.connect_with(options).await?;
+        MIGRATOR.run(&pool).await?;
+
+        Ok(Self { pool })
+    }
+
+    /// The newest migration applied to the schema.
+    pub async fn schema_version(&self) -> Result<i64, DatabaseError> {
+        let version = sqlx::query_scalar::<_, Option<i64>>(
+            r#"
+                SELECT MAX(version) FROM _sqlx_migrations WHERE success = 1
+            "#
+        )
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(version.unwrap_or(0))
+    }
+
+    pub fn pool(&self) -> &SqlitePool {
+        &self.pool
+    }
+
+    pub fn into_pool(self) -> SqlitePool {
+        self.pool
+    }
+
+    /// Starts a unit of work whose repositories share one transaction.
+    pub async fn begin(&self) -> Result<SqliteUnitOfWork, RepositoryError> {
+        SqliteUnitOfWork::begin(&self.pool).await
+    }
+
+    pub fn calendars(&self) -> SqliteCalendarRepository {
+        SqliteCalendarRepository::new(self.pool.clone())
+    }
+
+    pub fn events(&self) -> SqliteEventRepository {
+        SqliteEventRepository::new(self.pool.clone())
+    }
+
+    pub fn recurring(&self) -> SqliteRecurringEventRepository {
+        SqliteRecurringEventRepository::new(self.pool.clone())
+    }
+
+    pub fn tasks(&self) -> SqliteTaskRepository {
+        SqliteTaskRepository::new(self.pool.clone())
+    }
+
+    pub fn journal(&self) -> SqliteJournalRepository {
+        SqliteJournalRepository::new(self.pool.clone())
+    }
+
+    pub fn search(&self) -> SqliteEventSearchRepository {
+        SqliteEventSearchRepository::new(self.pool.clone())
+    }
+
+    pub fn tags(&self) -> SqliteTagRepository {
+        SqliteTagRepository::new(self.pool.clone())
+    }
+
+    pub fn reminder_states(&self) -> SqliteReminderStateRepository {
+        SqliteReminderStateRepository::new(self.pool.clone())
+    }
+
+    pub fn subscriptions(&self) -> SqliteSubscriptionRepository {
+        SqliteSubscriptionRepository::new(self.pool.clone())
+    }
+
+    pub fn sync_state(&self) -> SqliteSyncStateRepository {
+        SqliteSyncStateRepository::new(self.pool.clone())
+    }
+
+    pub fn history(&self) -> SqliteHistoryRepository {
+        SqliteHistoryRepository::new(self.pool.clone())
+    }
+
+    pub fn audit(&self) -> SqliteAuditRepository {
+        SqliteAuditRepository::new(self.pool.clone())
+    }
+}
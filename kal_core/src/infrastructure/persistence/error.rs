@@ -16,3 +16,15 @@ pub enum MapperError {
     #[error(transparent)]
     Domain(#[from] DomainError),
 }
+
+#[derive(Debug, Error)]
+pub enum DatabaseError {
+    #[error("Cannot create database directory: {0}")]
+    Io(#[from] std::io::Error),
+
+    #[error("Database error: {0}")]
+    Sqlx(#[from] sqlx::Error),
+
+    #[error("Migration failed: {0}")]
+    Migration(#[from] sqlx::migrate::MigrateError),
+}
@@ -1,88 +1,89 @@
 use async_trait::async_trait;
-use sqlx::SqlitePool;
+use chrono::{DateTime, Utc};
+use sqlx::{Connection, SqliteConnection, SqlitePool};
 use crate::domain::{
     event::Event,
     repository::{EventRepository, RepositoryError},
+    tag::Tag,
     value_objects::{CalendarId, EventId, TimeRange},
 };
-use super::{models::EventModel, mappers::EventMapper};
+use super::{
+    handle::Handle,
+    models::EventModel,
+    mappers::EventMapper,
+    reminders::{fetch_reminders, replace_reminders},
+    attendees::{fetch_attendees, fetch_organizer, replace_participants},
+    tags::{fetch_tags, find_tagged_ids, replace_tags},
+    like_pattern,
+};
 
 pub struct SqliteEventRepository {
-    pool: SqlitePool,
+    handle: Handle,
 }
 
 impl SqliteEventRepository {
     pub fn new(pool: SqlitePool) -> Self {
-        Self { pool }
+        Self { handle: Handle::Pool(pool) }
+    }
+
+    pub(crate) fn with_handle(handle: Handle) -> Self {
+        Self { handle }
+    }
+
+    async fn hydrate(conn: &mut SqliteConnection, model: EventModel) -> Result<Event, RepositoryError> {
+        let reminders = fetch_reminders(&mut *conn, &model.id).await?;
+        let organizer = fetch_organizer(&mut *conn, &model.id).await?;
+        let attendees = fetch_attendees(&mut *conn, &model.id).await?;
+        let tags = fetch_tags(&mut *conn, &model.id).await?;
+
+        EventMapper::to_domain(model, reminders, organizer, attendees)
+            .map(|event| event.with_tags(tags))
+            .map_err(|e| RepositoryError::DatabaseError(e.to_string()))
     }
 }
 
 #[async_trait]
 impl EventRepository for SqliteEventRepository {
-    async fn save(&self, event: &Event) -> Result<(), RepositoryError> {
-        let model = EventMapper::to_model(event);
+    async fn save(&self, event: &Event) -> Result<u32, RepositoryError> {
+        let mut conn = self.handle.acquire().await?;
+        let mut tx = conn.begin()
+            .await
+            .map_err(|e| RepositoryError::DatabaseError(e.to_string()))?;
 
-        sqlx::query!(
-            r#"
-                INSERT INTO events (
-                    id, calendar_id, title, description, starts_at, ends_at,
-                    color, is_all_day, is_cancelled, created_at, updated_at
-                )
-                VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11)
-                ON CONFLICT(id) DO UPDATE SET
-                    title = excluded.title,
-                    description = excluded.description,
-                    starts_at = excluded.starts_at,
-                    ends_at = excluded.ends_at,
-                    color = excluded.color,
-                    is_all_day = excluded.is_all_day,
-                    is_cancelled = excluded.is_cancelled,
-                    updated_at = excluded.updated_at
-            "#,
-            model.id,
-            model.calendar_id,
-            model.title,
-            model.description,
-            model.starts_at,
-            model.ends_at,
-            model.color,
-            model.is_all_day,
-            model.is_cancelled,
-            model.created_at,
-            model.updated_at,
-        )
-        .execute(&self.pool)
-        .await
-        .map_err(|e| RepositoryError::DatabaseError(e.to_string()))?;
+        let version = upsert_event(&mut tx, event).await?;
 
-        Ok(())
+        tx.commit()
+            .await
+            .map_err(|e| RepositoryError::DatabaseError(e.to_string()))?;
+
+        Ok(version)
     }
 
     async fn find_by_id(
         &self,
         id: &EventId
     ) -> Result<Option<Event>, RepositoryError> {
+        let mut conn = self.handle.acquire().await?;
+
         let id_str = id.to_string();
 
         let model = sqlx::query_as::<_, EventModel>(
             r#"
-            SELECT id, calendar_id, title, description, starts_at, ends_at,
-                   color, is_all_day, is_cancelled, created_at, updated_at
+            SELECT id, calendar_id, title, description, location, url,
+                   geo_latitude, geo_longitude, starts_at, ends_at,
+                   color, is_all_day, status, transparency, sequence, version, deleted_at,
+                   created_at, updated_at
             FROM events
-            WHERE id = ?1
+            WHERE id = ?1 AND deleted_at IS NULL
             "#
         )
         .bind(&id_str)
-        .fetch_optional(&self.pool)
+        .fetch_optional(&mut *conn)
         .await
         .map_err(|e| RepositoryError::DatabaseError(e.to_string()))?;
 
         match model {
-            Some(m) => {
-                let event = EventMapper::to_domain(m)
-                    .map_err(|e| RepositoryError::DatabaseError(e.to_string()))?;
-                Ok(Some(event))
-            }
+            Some(m) => Ok(Some(Self::hydrate(&mut conn, m).await?)),
             None => Ok(None),
         }
     }
@@ -91,27 +92,33 @@ impl EventRepository for SqliteEventRepository {
         &self,
         calendar_id: &CalendarId
     ) -> Result<Vec<Event>, RepositoryError> {
+        let mut conn = self.handle.acquire().await?;
+
         let calendar_id_str = calendar_id.to_string();
 
         let models = sqlx::query_as::<_, EventModel>(
             r#"
-            SELECT id, calendar_id, title, description, starts_at, ends_at,
-                   color, is_all_day, is_cancelled, created_at, updated_at
+            SELECT id, calendar_id, title, description, location, url,
+                   geo_latitude, geo_longitude, starts_at, ends_at,
+                   color, is_all_day, status, transparency, sequence, version, deleted_at,
+                   created_at, updated_at
             FROM events
-            WHERE calendar_id = ?1
+            WHERE calendar_id = ?1 AND deleted_at IS NULL
             ORDER BY starts_at
             "#
         )
         .bind(&calendar_id_str)
-        .fetch_all(&self.pool)
+        .fetch_all(&mut *conn)
         .await
         .map_err(|e| RepositoryError::DatabaseError(e.to_string()))?;
 
-        models
-            .into_iter()
-            .map(|m| EventMapper::to_domain(m)
-                .map_err(|e| RepositoryError::DatabaseError(e.to_string())))
-            .collect()
+        let mut result = Vec::new();
+
+        for model in models {
+            result.push(Self::hydrate(&mut conn, model).await?);
+        }
+
+        Ok(result)
     }
 
     async fn find_in_range(
@@ -119,17 +126,21 @@ impl EventRepository for SqliteEventRepository {
         calendar_id: &CalendarId,
         range: &TimeRange,
     ) -> Result<Vec<Event>, RepositoryError> {
+        let mut conn = self.handle.acquire().await?;
+
         let calendar_id_str = calendar_id.to_string();
         let range_start = range.starts_at().to_rfc3339();
         let range_end = range.ends_at().to_rfc3339();
 
         let models = sqlx::query_as::<_, EventModel>(
             r#"
-            SELECT id, calendar_id, title, description, starts_at, ends_at,
-                   color, is_all_day, is_cancelled, created_at, updated_at
+            SELECT id, calendar_id, title, description, location, url,
+                   geo_latitude, geo_longitude, starts_at, ends_at,
+                   color, is_all_day, status, transparency, sequence, version, deleted_at,
+                   created_at, updated_at
             FROM events
-            WHERE calendar_id = ?1
-              AND is_cancelled = 0
+            WHERE calendar_id = ?1 AND deleted_at IS NULL
+              AND status != 'CANCELLED'
               AND starts_at < ?3
               AND ends_at > ?2
             ORDER BY starts_at
@@ -138,18 +149,216 @@ impl EventRepository for SqliteEventRepository {
         .bind(&calendar_id_str)
         .bind(&range_start)
         .bind(&range_end)
-        .fetch_all(&self.pool)
+        .fetch_all(&mut *conn)
+        .await
+        .map_err(|e| RepositoryError::DatabaseError(e.to_string()))?;
+
+        let mut result = Vec::new();
+
+        for model in models {
+            result.push(Self::hydrate(&mut conn, model).await?);
+        }
+
+        Ok(result)
+    }
+
+    async fn find_by_location(
+        &self,
+        calendar_id: &CalendarId,
+        needle: &str,
+    ) -> Result<Vec<Event>, RepositoryError> {
+        let mut conn = self.handle.acquire().await?;
+
+        let calendar_id_str = calendar_id.to_string();
+        let pattern = like_pattern(needle);
+
+        let models = sqlx::query_as::<_, EventModel>(
+            r#"
+            SELECT id, calendar_id, title, description, location, url,
+                   geo_latitude, geo_longitude, starts_at, ends_at,
+                   color, is_all_day, status, transparency, sequence, version, deleted_at,
+                   created_at, updated_at
+            FROM events
+            WHERE calendar_id = ?1 AND deleted_at IS NULL
+              AND location LIKE ?2 ESCAPE '\'
+            ORDER BY starts_at
+            "#
+        )
+        .bind(&calendar_id_str)
+        .bind(&pattern)
+        .fetch_all(&mut *conn)
+        .await
+        .map_err(|e| RepositoryError::DatabaseError(e.to_string()))?;
+
+        let mut result = Vec::new();
+
+        for model in models {
+            result.push(Self::hydrate(&mut conn, model).await?);
+        }
+
+        Ok(result)
+    }
+
+    async fn find_by_tag(&self, tag: &Tag) -> Result<Vec<Event>, RepositoryError> {
+        let ids = find_tagged_ids(&mut *self.handle.acquire().await?, tag).await?;
+        let mut result = Vec::new();
+
+        for id in ids {
+            if let Some(event) = self.find_by_id(&id).await? {
+                result.push(event);
+            }
+        }
+
+        Ok(result)
+    }
+
+    async fn find_with_reminders_in(
+        &self,
+        window: &TimeRange,
+    ) -> Result<Vec<Event>, RepositoryError> {
+        let mut conn = self.handle.acquire().await?;
+
+        // Whole seconds, widened by one; the exact bounds are checked below
+        let window_start = window.starts_at().timestamp() - 1;
+        let window_end = window.ends_at().timestamp() + 1;
+
+        let models = sqlx::query_as::<_, EventModel>(
+            r#"
+            SELECT id, calendar_id, title, description, location, url,
+                   geo_latitude, geo_longitude, starts_at, ends_at,
+                   color, is_all_day, status, transparency, sequence, version, deleted_at,
+                   created_at, updated_at
+            FROM events e
+            WHERE deleted_at IS NULL
+              AND status != 'CANCELLED'
+              AND EXISTS (
+                  SELECT 1 FROM reminders r
+                  WHERE r.event_id = e.id
+                    AND COALESCE(
+                            CAST(strftime('%s', r.trigger_at) AS INTEGER),
+                            CAST(strftime('%s', e.starts_at) AS INTEGER) + r.trigger_offset
+                        ) BETWEEN ?1 AND ?2
+              )
+            ORDER BY starts_at
+            "#
+        )
+        .bind(window_start)
+        .bind(window_end)
+        .fetch_all(&mut *conn)
+        .await
+        .map_err(|e| RepositoryError::DatabaseError(e.to_string()))?;
+
+        let mut result = Vec::new();
+
+        for model in models {
+            let event = Self::hydrate(&mut conn, model).await?;
+
+            if event.reminder_instances().iter().any(|i| window.contains(i.fires_at())) {
+                result.push(event);
+            }
+        }
+
+        Ok(result)
+    }
+
+    async fn trash(&self, id: &EventId, deleted_at: DateTime<Utc>) -> Result<(), RepositoryError> {
+        let mut conn = self.handle.acquire().await?;
+
+        let id_str = id.to_string();
+        let deleted_at = deleted_at.to_rfc3339();
+
+        let result = sqlx::query!(
+            r#"
+                UPDATE events SET deleted_at = ?2, version = version + 1
+                WHERE id = ?1 AND deleted_at IS NULL
+            "#,
+            id_str,
+            deleted_at,
+        )
+        .execute(&mut *conn)
         .await
         .map_err(|e| RepositoryError::DatabaseError(e.to_string()))?;
 
-        models
-            .into_iter()
-            .map(|m| EventMapper::to_domain(m)
-                .map_err(|e| RepositoryError::DatabaseError(e.to_string())))
-            .collect()
+        if result.rows_affected() == 0 {
+            return Err(RepositoryError::NotFound);
+        }
+
+        Ok(())
+    }
+
+    async fn restore(&self, id: &EventId) -> Result<(), RepositoryError> {
+        let mut conn = self.handle.acquire().await?;
+
+        let id_str = id.to_string();
+
+        let result = sqlx::query!(
+            r#"
+                UPDATE events SET deleted_at = NULL, version = version + 1
+                WHERE id = ?1 AND deleted_at IS NOT NULL
+            "#,
+            id_str,
+        )
+        .execute(&mut *conn)
+        .await
+        .map_err(|e| RepositoryError::DatabaseError(e.to_string()))?;
+
+        if result.rows_affected() == 0 {
+            return Err(RepositoryError::NotFound);
+        }
+
+        Ok(())
+    }
+
+    async fn find_trashed(&self) -> Result<Vec<Event>, RepositoryError> {
+        let mut conn = self.handle.acquire().await?;
+
+        let models = sqlx::query_as::<_, EventModel>(
+            r#"
+            SELECT id, calendar_id, title, description, location, url,
+                   geo_latitude, geo_longitude, starts_at, ends_at,
+                   color, is_all_day, status, transparency, sequence, version, deleted_at,
+                   created_at, updated_at
+            FROM events
+            WHERE deleted_at IS NOT NULL
+            ORDER BY deleted_at DESC, starts_at
+            "#
+        )
+        .fetch_all(&mut *conn)
+        .await
+        .map_err(|e| RepositoryError::DatabaseError(e.to_string()))?;
+
+        let mut result = Vec::new();
+
+        for model in models {
+            result.push(Self::hydrate(&mut conn, model).await?);
+        }
+
+        Ok(result)
+    }
+
+    async fn purge_trashed(&self, cutoff: DateTime<Utc>) -> Result<u64, RepositoryError> {
+        let mut conn = self.handle.acquire().await?;
+
+        // Compared as instants, as stored timestamps need not share the
+        // cutoff's offset or precision
+        let cutoff = cutoff.to_rfc3339();
+
+        let result = sqlx::query!(
+            r#"
+                DELETE FROM events WHERE julianday(deleted_at) < julianday(?1)
+            "#,
+            cutoff,
+        )
+        .execute(&mut *conn)
+        .await
+        .map_err(|e| RepositoryError::DatabaseError(e.to_string()))?;
+
+        Ok(result.rows_affected())
     }
 
     async fn delete(&self, id: &EventId) -> Result<(), RepositoryError> {
+        let mut conn = self.handle.acquire().await?;
+
         let id_str = id.to_string();
         
         let result = sqlx::query!(
@@ -158,7 +367,7 @@ impl EventRepository for SqliteEventRepository {
             "#,
             id_str,
         )
-        .execute(&self.pool)
+        .execute(&mut *conn)
         .await
         .map_err(|e| RepositoryError::DatabaseError(e.to_string()))?;
 
@@ -169,3 +378,77 @@ impl EventRepository for SqliteEventRepository {
         Ok(())
     }
 }
+
+/// Writes the event and replaces its reminders and participants,
+/// returning the new version. The caller owns the transaction so they
+/// stay consistent.
+pub(crate) async fn upsert_event(
+    conn: &mut SqliteConnection,
+    event: &Event,
+) -> Result<u32, RepositoryError> {
+    let model = EventMapper::to_model(event);
+
+    let result = sqlx::query!(
+        r#"
+            INSERT INTO events (
+                id, calendar_id, title, description, location, url,
+                geo_latitude, geo_longitude, starts_at, ends_at,
+                color, is_all_day, status, transparency, sequence, version, created_at, updated_at
+            )
+            VALUES (
+                ?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8,
+                ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?18 + 1, ?16, ?17
+            )
+            ON CONFLICT(id) DO UPDATE SET
+                title = excluded.title,
+                description = excluded.description,
+                location = excluded.location,
+                url = excluded.url,
+                geo_latitude = excluded.geo_latitude,
+                geo_longitude = excluded.geo_longitude,
+                starts_at = excluded.starts_at,
+                ends_at = excluded.ends_at,
+                color = excluded.color,
+                is_all_day = excluded.is_all_day,
+                status = excluded.status,
+                transparency = excluded.transparency,
+                sequence = excluded.sequence,
+                updated_at = excluded.updated_at,
+                version = events.version + 1
+            WHERE events.version = ?18
+        "#,
+        model.id,
+        model.calendar_id,
+        model.title,
+        model.description,
+        model.location,
+        model.url,
+        model.geo_latitude,
+        model.geo_longitude,
+        model.starts_at,
+        model.ends_at,
+        model.color,
+        model.is_all_day,
+        model.status,
+        model.transparency,
+        model.sequence,
+        model.created_at,
+        model.updated_at,
+        model.version,
+    )
+    .execute(&mut *conn)
+    .await
+    .map_err(|e| RepositoryError::DatabaseError(e.to_string()))?;
+
+    // The upsert skips the update when the stored version moved on,
+    // which covers a new event reusing the id of a trashed one
+    if result.rows_affected() == 0 {
+        return Err(RepositoryError::Conflict);
+    }
+
+    replace_reminders(conn, event.event_id(), event.reminders()).await?;
+    replace_participants(conn, event.event_id(), event.organizer().as_ref(), event.attendees()).await?;
+    replace_tags(conn, event.event_id(), event.tags()).await?;
+
+    Ok(*event.version() + 1)
+}
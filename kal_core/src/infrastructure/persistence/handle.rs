@@ -0,0 +1,66 @@
+use std::{
+    ops::{Deref, DerefMut},
+    sync::Arc,
+};
+
+use sqlx::{pool::PoolConnection, Sqlite, SqliteConnection, SqlitePool, Transaction};
+use tokio::sync::{MappedMutexGuard, Mutex, MutexGuard};
+
+use crate::domain::repository::RepositoryError;
+
+/// The transaction behind a unit of work, shared by its repositories.
+/// `None` once committed or rolled back.
+pub(crate) type SharedTransaction = Arc<Mutex<Option<Transaction<'static, Sqlite>>>>;
+
+/// Where a repository runs its statements: on a pooled connection of
+/// its own, or inside a unit of work's transaction.
+#[derive(Debug, Clone)]
+pub(crate) enum Handle {
+    Pool(SqlitePool),
+    Transaction(SharedTransaction),
+}
+
+/// A connection borrowed from a `Handle` for the length of one
+/// repository call. Repositories drop it before calling their own
+/// methods again; the transaction is locked while it is held.
+pub(crate) enum Conn<'a> {
+    Pool(PoolConnection<Sqlite>),
+    Transaction(MappedMutexGuard<'a, Transaction<'static, Sqlite>>),
+}
+
+impl Handle {
+    pub(crate) async fn acquire(&self) -> Result<Conn<'_>, RepositoryError> {
+        match self {
+            Handle::Pool(pool) => pool
+                .acquire()
+                .await
+                .map(Conn::Pool)
+                .map_err(|e| RepositoryError::DatabaseError(e.to_string())),
+            Handle::Transaction(tx) => MutexGuard::try_map(tx.lock().await, Option::as_mut)
+                .map(Conn::Transaction)
+                .map_err(|_| {
+                    RepositoryError::DatabaseError("unit of work already finished".into())
+                }),
+        }
+    }
+}
+
+impl Deref for Conn<'_> {
+    type Target = SqliteConnection;
+
+    fn deref(&self) -> &SqliteConnection {
+        match self {
+            Conn::Pool(conn) => conn,
+            Conn::Transaction(tx) => tx,
+        }
+    }
+}
+
+impl DerefMut for Conn<'_> {
+    fn deref_mut(&mut self) -> &mut SqliteConnection {
+        match self {
+            Conn::Pool(conn) => conn,
+            Conn::Transaction(tx) => tx,
+        }
+    }
+}
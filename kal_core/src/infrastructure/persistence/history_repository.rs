@@ -0,0 +1,211 @@
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use sqlx::{Connection, SqliteConnection, SqlitePool};
+use crate::domain::{
+    history::{Change, HistoryEntry},
+    repository::{HistoryRepository, RepositoryError},
+};
+use super::{
+    handle::Handle,
+    mappers::HistoryMapper,
+    models::{HistoryChangeModel, HistoryEntryModel},
+};
+
+pub struct SqliteHistoryRepository {
+    handle: Handle,
+}
+
+impl SqliteHistoryRepository {
+    pub fn new(pool: SqlitePool) -> Self {
+        Self { handle: Handle::Pool(pool) }
+    }
+
+    pub(crate) fn with_handle(handle: Handle) -> Self {
+        Self { handle }
+    }
+
+    /// Loads an entry's changes, in the order they were made.
+    async fn hydrate(
+        conn: &mut SqliteConnection,
+        model: HistoryEntryModel,
+    ) -> Result<HistoryEntry, RepositoryError> {
+        let changes = sqlx::query_as::<_, HistoryChangeModel>(
+            r#"
+            SELECT entry_id, position, kind, object_id, calendar_id, before_json, after_json
+            FROM history_changes
+            WHERE entry_id = ?1
+            ORDER BY position
+            "#
+        )
+        .bind(model.id)
+        .fetch_all(&mut *conn)
+        .await
+        .map_err(|e| RepositoryError::DatabaseError(e.to_string()))?;
+
+        HistoryMapper::to_domain(model, changes)
+            .map_err(|e| RepositoryError::DatabaseError(e.to_string()))
+    }
+}
+
+#[async_trait]
+impl HistoryRepository for SqliteHistoryRepository {
+    async fn record(
+        &self,
+        command: &str,
+        recorded_at: DateTime<Utc>,
+        changes: &[Change],
+    ) -> Result<i64, RepositoryError> {
+        let mut conn = self.handle.acquire().await?;
+        let mut tx = conn.begin()
+            .await
+            .map_err(|e| RepositoryError::DatabaseError(e.to_string()))?;
+
+        let recorded_at = recorded_at.to_rfc3339();
+
+        let entry_id = sqlx::query!(
+            r#"
+                INSERT INTO history_entries (parent_id, command, recorded_at)
+                VALUES ((SELECT entry_id FROM history_cursor WHERE id = 1), ?1, ?2)
+            "#,
+            command,
+            recorded_at,
+        )
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| RepositoryError::DatabaseError(e.to_string()))?
+        .last_insert_rowid();
+
+        for (position, change) in changes.iter().enumerate() {
+            let model = HistoryMapper::change_to_model(entry_id, position, change)
+                .map_err(|e| RepositoryError::DatabaseError(e.to_string()))?;
+
+            sqlx::query!(
+                r#"
+                    INSERT INTO history_changes (
+                        entry_id, position, kind, object_id, calendar_id,
+                        before_json, after_json
+                    )
+                    VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)
+                "#,
+                model.entry_id,
+                model.position,
+                model.kind,
+                model.object_id,
+                model.calendar_id,
+                model.before_json,
+                model.after_json,
+            )
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| RepositoryError::DatabaseError(e.to_string()))?;
+        }
+
+        sqlx::query!(
+            r#"
+                UPDATE history_cursor SET entry_id = ?1 WHERE id = 1
+            "#,
+            entry_id,
+        )
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| RepositoryError::DatabaseError(e.to_string()))?;
+
+        tx.commit()
+            .await
+            .map_err(|e| RepositoryError::DatabaseError(e.to_string()))?;
+
+        Ok(entry_id)
+    }
+
+    async fn find_current(&self) -> Result<Option<HistoryEntry>, RepositoryError> {
+        let mut conn = self.handle.acquire().await?;
+
+        let model = sqlx::query_as::<_, HistoryEntryModel>(
+            r#"
+            SELECT e.id, e.parent_id, e.command, e.recorded_at
+            FROM history_entries e
+            JOIN history_cursor c ON c.entry_id = e.id
+            "#
+        )
+        .fetch_optional(&mut *conn)
+        .await
+        .map_err(|e| RepositoryError::DatabaseError(e.to_string()))?;
+
+        match model {
+            Some(model) => Ok(Some(Self::hydrate(&mut conn, model).await?)),
+            None => Ok(None),
+        }
+    }
+
+    async fn find_next(&self) -> Result<Option<HistoryEntry>, RepositoryError> {
+        let mut conn = self.handle.acquire().await?;
+
+        let model = sqlx::query_as::<_, HistoryEntryModel>(
+            r#"
+            SELECT id, parent_id, command, recorded_at
+            FROM history_entries
+            WHERE parent_id IS (SELECT entry_id FROM history_cursor WHERE id = 1)
+            ORDER BY id DESC
+            LIMIT 1
+            "#
+        )
+        .fetch_optional(&mut *conn)
+        .await
+        .map_err(|e| RepositoryError::DatabaseError(e.to_string()))?;
+
+        match model {
+            Some(model) => Ok(Some(Self::hydrate(&mut conn, model).await?)),
+            None => Ok(None),
+        }
+    }
+
+    async fn find_applied(&self, limit: u32) -> Result<Vec<HistoryEntry>, RepositoryError> {
+        let mut conn = self.handle.acquire().await?;
+
+        // Walks the parent links back from the cursor
+        let models = sqlx::query_as::<_, HistoryEntryModel>(
+            r#"
+            WITH RECURSIVE applied (id, depth) AS (
+                SELECT entry_id, 0 FROM history_cursor
+                WHERE id = 1 AND entry_id IS NOT NULL
+                UNION ALL
+                SELECT e.parent_id, a.depth + 1
+                FROM history_entries e
+                JOIN applied a ON e.id = a.id
+                WHERE e.parent_id IS NOT NULL AND a.depth + 1 < ?1
+            )
+            SELECT e.id, e.parent_id, e.command, e.recorded_at
+            FROM history_entries e
+            JOIN applied a ON a.id = e.id
+            ORDER BY a.depth
+            "#
+        )
+        .bind(limit)
+        .fetch_all(&mut *conn)
+        .await
+        .map_err(|e| RepositoryError::DatabaseError(e.to_string()))?;
+
+        let mut entries = Vec::with_capacity(models.len());
+        for model in models {
+            entries.push(Self::hydrate(&mut conn, model).await?);
+        }
+
+        Ok(entries)
+    }
+
+    async fn set_current(&self, entry_id: Option<i64>) -> Result<(), RepositoryError> {
+        let mut conn = self.handle.acquire().await?;
+
+        sqlx::query!(
+            r#"
+                UPDATE history_cursor SET entry_id = ?1 WHERE id = 1
+            "#,
+            entry_id,
+        )
+        .execute(&mut *conn)
+        .await
+        .map_err(|e| RepositoryError::DatabaseError(e.to_string()))?;
+
+        Ok(())
+    }
+}
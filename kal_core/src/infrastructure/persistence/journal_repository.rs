@@ -0,0 +1,207 @@
+use async_trait::async_trait;
+use chrono::NaiveDate;
+use sqlx::SqlitePool;
+use crate::domain::{
+    journal::JournalEntry,
+    repository::{JournalRepository, RepositoryError},
+    value_objects::{CalendarId, EventId, JournalId},
+};
+use super::{
+    fts_query,
+    handle::Handle,
+    models::JournalModel,
+    mappers::JournalMapper,
+};
+
+/// Journal entries have no trash of their own; they are hidden while
+/// their calendar is in the trash and come back when it is restored.
+pub struct SqliteJournalRepository {
+    handle: Handle,
+}
+
+impl SqliteJournalRepository {
+    pub fn new(pool: SqlitePool) -> Self {
+        Self { handle: Handle::Pool(pool) }
+    }
+
+    pub(crate) fn with_handle(handle: Handle) -> Self {
+        Self { handle }
+    }
+
+    fn to_domain(models: Vec<JournalModel>) -> Result<Vec<JournalEntry>, RepositoryError> {
+        models
+            .into_iter()
+            .map(JournalMapper::to_domain)
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|e| RepositoryError::DatabaseError(e.to_string()))
+    }
+}
+
+#[async_trait]
+impl JournalRepository for SqliteJournalRepository {
+    async fn save(&self, entry: &JournalEntry) -> Result<(), RepositoryError> {
+        let mut conn = self.handle.acquire().await?;
+
+        let model = JournalMapper::to_model(entry);
+
+        sqlx::query!(
+            r#"
+                INSERT INTO journals (
+                    id, calendar_id, entry_date, title, body,
+                    event_id, original_starts_at, created_at, updated_at
+                )
+                VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)
+                ON CONFLICT(id) DO UPDATE SET
+                    entry_date = excluded.entry_date,
+                    title = excluded.title,
+                    body = excluded.body,
+                    event_id = excluded.event_id,
+                    original_starts_at = excluded.original_starts_at,
+                    updated_at = excluded.updated_at
+            "#,
+            model.id,
+            model.calendar_id,
+            model.entry_date,
+            model.title,
+            model.body,
+            model.event_id,
+            model.original_starts_at,
+            model.created_at,
+            model.updated_at,
+        )
+        .execute(&mut *conn)
+        .await
+        .map_err(|e| RepositoryError::DatabaseError(e.to_string()))?;
+
+        Ok(())
+    }
+
+    async fn find_by_id(&self, id: &JournalId) -> Result<Option<JournalEntry>, RepositoryError> {
+        let mut conn = self.handle.acquire().await?;
+
+        let model = sqlx::query_as::<_, JournalModel>(
+            r#"
+                SELECT id, calendar_id, entry_date, title, body,
+                       event_id, original_starts_at, created_at, updated_at
+                FROM journals
+                WHERE id = ?1
+                  AND calendar_id IN (SELECT id FROM calendars WHERE deleted_at IS NULL)
+            "#
+        )
+        .bind(id.to_string())
+        .fetch_optional(&mut *conn)
+        .await
+        .map_err(|e| RepositoryError::DatabaseError(e.to_string()))?;
+
+        model
+            .map(JournalMapper::to_domain)
+            .transpose()
+            .map_err(|e| RepositoryError::DatabaseError(e.to_string()))
+    }
+
+    async fn find_by_calendar(
+        &self,
+        calendar_id: &CalendarId,
+        from: Option<NaiveDate>,
+        to: Option<NaiveDate>,
+    ) -> Result<Vec<JournalEntry>, RepositoryError> {
+        let mut conn = self.handle.acquire().await?;
+
+        let models = sqlx::query_as::<_, JournalModel>(
+            r#"
+                SELECT id, calendar_id, entry_date, title, body,
+                       event_id, original_starts_at, created_at, updated_at
+                FROM journals
+                WHERE calendar_id = ?1
+                  AND calendar_id IN (SELECT id FROM calendars WHERE deleted_at IS NULL)
+                  AND (?2 IS NULL OR entry_date >= ?2)
+                  AND (?3 IS NULL OR entry_date <= ?3)
+                ORDER BY entry_date, created_at
+            "#
+        )
+        .bind(calendar_id.to_string())
+        .bind(from.map(|d| d.to_string()))
+        .bind(to.map(|d| d.to_string()))
+        .fetch_all(&mut *conn)
+        .await
+        .map_err(|e| RepositoryError::DatabaseError(e.to_string()))?;
+
+        Self::to_domain(models)
+    }
+
+    async fn find_by_event(&self, event_id: &EventId) -> Result<Vec<JournalEntry>, RepositoryError> {
+        let mut conn = self.handle.acquire().await?;
+
+        let models = sqlx::query_as::<_, JournalModel>(
+            r#"
+                SELECT id, calendar_id, entry_date, title, body,
+                       event_id, original_starts_at, created_at, updated_at
+                FROM journals
+                WHERE event_id = ?1
+                  AND calendar_id IN (SELECT id FROM calendars WHERE deleted_at IS NULL)
+                ORDER BY entry_date, created_at
+            "#
+        )
+        .bind(event_id.to_string())
+        .fetch_all(&mut *conn)
+        .await
+        .map_err(|e| RepositoryError::DatabaseError(e.to_string()))?;
+
+        Self::to_domain(models)
+    }
+
+    async fn search(
+        &self,
+        query: &str,
+        calendar_id: Option<&CalendarId>,
+    ) -> Result<Vec<JournalEntry>, RepositoryError> {
+        let Some(query) = fts_query(query) else {
+            return Ok(Vec::new());
+        };
+
+        let mut conn = self.handle.acquire().await?;
+
+        // Title hits weigh double; bm25 scores lower for better matches.
+        let models = sqlx::query_as::<_, JournalModel>(
+            r#"
+                SELECT j.id, j.calendar_id, j.entry_date, j.title, j.body,
+                       j.event_id, j.original_starts_at, j.created_at, j.updated_at
+                FROM journals_fts
+                JOIN journals j ON j.rowid = journals_fts.rowid
+                WHERE journals_fts MATCH ?1
+                  AND (?2 IS NULL OR j.calendar_id = ?2)
+                  AND j.calendar_id IN (SELECT id FROM calendars WHERE deleted_at IS NULL)
+                ORDER BY bm25(journals_fts, 2.0, 1.0), j.entry_date DESC
+            "#
+        )
+        .bind(query)
+        .bind(calendar_id.map(|id| id.to_string()))
+        .fetch_all(&mut *conn)
+        .await
+        .map_err(|e| RepositoryError::DatabaseError(e.to_string()))?;
+
+        Self::to_domain(models)
+    }
+
+    async fn delete(&self, id: &JournalId) -> Result<(), RepositoryError> {
+        let mut conn = self.handle.acquire().await?;
+
+        let id_str = id.to_string();
+
+        let result = sqlx::query!(
+            r#"
+                DELETE FROM journals WHERE id = ?1
+            "#,
+            id_str,
+        )
+        .execute(&mut *conn)
+        .await
+        .map_err(|e| RepositoryError::DatabaseError(e.to_string()))?;
+
+        if result.rows_affected() == 0 {
+            return Err(RepositoryError::NotFound);
+        }
+
+        Ok(())
+    }
+}
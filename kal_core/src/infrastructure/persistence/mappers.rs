@@ -1,10 +1,20 @@
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, NaiveDate, Utc};
+use uuid::Uuid;
 
 use std::{collections::HashMap, str::FromStr};
 
 use crate::domain::{
+    attendee::{Attendee, Organizer},
+    audit::AuditEntry,
     calendar::Calendar,
+    domain_event::FieldChange,
     event::Event,
+    history::{Change, HistoryEntry, ObjectKind, Snapshot},
+    journal::{JournalEntry, JournalLink},
+    reminder::{Reminder, ReminderAction, ReminderKey, ReminderState, ReminderTrigger},
+    search::{SearchHit, SearchHitKind},
+    sync::{SyncCollection, SyncItem, SyncItemKind, Tombstone},
+    task::Task,
     recurrence::{
         ExceptionModification,
         RecurrenceException,
@@ -15,16 +25,38 @@ use crate::domain::{
         CalendarId,
         EventColor,
         EventId,
+        EventStatus,
         Frequency,
+        GeoPoint,
+        JournalId,
+        ReminderId,
+        Subscription,
+        TaskId,
+        TaskStatus,
         TimeRange,
+        Transparency,
     },
 };
 
 use super::models::{
+    AttendeeModel,
+    AuditChangeModel,
+    AuditEntryModel,
     CalendarModel,
     EventModel,
+    HistoryChangeModel,
+    HistoryEntryModel,
+    JournalModel,
+    OrganizerModel,
     RecurrenceModel,
     RecurrenceExceptionModel,
+    ReminderModel,
+    ReminderStateModel,
+    SearchHitModel,
+    SyncCollectionModel,
+    SyncItemModel,
+    TaskModel,
+    TombstoneModel,
 };
 
 use crate::infrastructure::persistence::error::MapperError;
@@ -45,15 +77,41 @@ impl CalendarMapper {
 
         let created_at = parse_date(&model.created_at)?;
         let updated_at = parse_date(&model.updated_at)?;
+        let deleted_at = model.deleted_at.as_deref().map(parse_date).transpose()?;
+
+        let subscription = match (model.subscription_url, model.refresh_interval) {
+            (Some(url), Some(interval)) => {
+                let last_fetched_at = match model.last_fetched_at {
+                    Some(d) => Some(parse_date(&d)?),
+                    None => None,
+                };
+                Some(Subscription::with_state(
+                    url,
+                    chrono::Duration::seconds(interval),
+                    last_fetched_at,
+                    model.fetch_etag,
+                )?
+                .with_last_modified(model.fetch_last_modified))
+            }
+            (None, None) => None,
+            _ => {
+                return Err(MapperError::InvalidData(
+                    "Subscription requires both URL and refresh interval".into(),
+                ))
+            }
+        };
 
         Ok(Calendar::with_id(
             id,
             model.name,
             model.description,
             model.is_archived != 0,
+            subscription,
             created_at,
             updated_at,
-        )?)
+        )?
+        .with_version(model.version as u32)
+        .with_deleted_at(deleted_at))
     }
 
     pub fn to_model(calendar: &Calendar) -> CalendarModel {
@@ -62,6 +120,28 @@ impl CalendarMapper {
             name: calendar.name().to_string(),
             description: calendar.description().clone(),
             is_archived: if *calendar.is_archived() { 1 } else { 0 },
+            subscription_url: calendar
+                .subscription()
+                .as_ref()
+                .map(|s| s.source_url().clone()),
+            refresh_interval: calendar
+                .subscription()
+                .as_ref()
+                .map(|s| s.refresh_interval().num_seconds()),
+            last_fetched_at: calendar
+                .subscription()
+                .as_ref()
+                .and_then(|s| s.last_fetched_at().map(|dt| dt.to_rfc3339())),
+            fetch_etag: calendar
+                .subscription()
+                .as_ref()
+                .and_then(|s| s.etag().clone()),
+            fetch_last_modified: calendar
+                .subscription()
+                .as_ref()
+                .and_then(|s| s.last_modified().clone()),
+            version: *calendar.version() as i64,
+            deleted_at: calendar.deleted_at().map(|dt| dt.to_rfc3339()),
             created_at: calendar.created_at().to_rfc3339(),
             updated_at: calendar.updated_at().to_rfc3339(),
         }
@@ -76,7 +156,12 @@ impl CalendarMapper {
 pub struct EventMapper;
 
 impl EventMapper {
-    pub fn to_domain(model: EventModel) -> MapperResult<Event> {
+    pub fn to_domain(
+        model: EventModel,
+        reminders: Vec<ReminderModel>,
+        organizer: Option<OrganizerModel>,
+        attendees: Vec<AttendeeModel>,
+    ) -> MapperResult<Event> {
         let _id = CalendarId::from_str(&model.id)
             .map_err(|e| MapperError::InvalidId(e.to_string()))?;
 
@@ -91,11 +176,21 @@ impl EventMapper {
 
         let created_at = parse_date(&model.created_at)?;
         let updated_at = parse_date(&model.updated_at)?;
+        let deleted_at = model.deleted_at.as_deref().map(parse_date).transpose()?;
 
         let time_range = TimeRange::new(starts_at, ends_at)?;
 
         let color = EventColor::from(model.color as u8);
 
+        let geo = geo_to_domain(model.geo_latitude, model.geo_longitude)?;
+
+        let reminders = reminders
+            .into_iter()
+            .map(ReminderMapper::to_domain)
+            .collect::<MapperResult<Vec<_>>>()?;
+
+        let (organizer, attendees) = ParticipantMapper::to_domain(organizer, attendees)?;
+
         Ok(Event::with_id(
             event_id,
             calendar_id,
@@ -104,10 +199,17 @@ impl EventMapper {
             time_range,
             color,
             model.is_all_day != 0,
-            model.is_cancelled != 0,
+            EventStatus::from_str(&model.status)?,
             created_at,
             updated_at,
-        )?)
+        )?
+        .with_reminders(reminders)
+        .with_participants(organizer, attendees)
+        .with_sequence(model.sequence as u32)
+        .with_version(model.version as u32)
+        .with_deleted_at(deleted_at)
+        .with_transparency(Transparency::from_str(&model.transparency)?)
+        .with_place(model.location, model.url, geo))
     }
 
     pub fn to_model(event: &Event) -> EventModel {
@@ -116,11 +218,19 @@ impl EventMapper {
             calendar_id: event.calendar_id().to_string(),
             title: event.title().to_string(),
             description: event.description().clone(),
+            location: event.location().clone(),
+            url: event.url().clone(),
+            geo_latitude: event.geo().map(|g| *g.latitude()),
+            geo_longitude: event.geo().map(|g| *g.longitude()),
             starts_at: event.time_range().starts_at().to_rfc3339(),
             ends_at: event.time_range().ends_at().to_rfc3339(),
             color: u8::from(*event.color()) as i64,
             is_all_day: if *event.is_all_day() { 1 } else { 0 },
-            is_cancelled: if *event.is_cancelled() { 1 } else { 0 },
+            status: event.status().to_string(),
+            transparency: event.transparency().to_string(),
+            sequence: *event.sequence() as i64,
+            version: *event.version() as i64,
+            deleted_at: event.deleted_at().map(|dt| dt.to_rfc3339()),
             created_at: event.created_at().to_rfc3339(),
             updated_at: event.updated_at().to_rfc3339(),
         }
@@ -138,6 +248,9 @@ impl RecurrenceMapper {
     pub fn to_domain(
         model: RecurrenceModel,
         exceptions: Vec<RecurrenceExceptionModel>,
+        reminders: Vec<ReminderModel>,
+        organizer: Option<OrganizerModel>,
+        attendees: Vec<AttendeeModel>,
     ) -> MapperResult<RecurringEvent> {
 
         let event_id = EventId::from_str(&model.id)
@@ -151,11 +264,14 @@ impl RecurrenceMapper {
 
         let created_at = parse_date(&model.created_at)?;
         let updated_at = parse_date(&model.updated_at)?;
+        let deleted_at = model.deleted_at.as_deref().map(parse_date).transpose()?;
 
         let base_time_range = TimeRange::new(starts_at, ends_at)?;
 
         let color = EventColor::from(model.color as u8);
 
+        let geo = geo_to_domain(model.geo_latitude, model.geo_longitude)?;
+
         let frequency = Frequency::from_str(&model.frequency)?;
 
         let until = match model.until {
@@ -177,6 +293,13 @@ impl RecurrenceMapper {
             .map(|ex| (*ex.original_starts_at(), ex))
             .collect::<HashMap<_, _>>();
 
+        let reminders = reminders
+            .into_iter()
+            .map(ReminderMapper::to_domain)
+            .collect::<MapperResult<Vec<_>>>()?;
+
+        let (organizer, attendees) = ParticipantMapper::to_domain(organizer, attendees)?;
+
         Ok(RecurringEvent::with_id(
             event_id,
             calendar_id,
@@ -187,10 +310,17 @@ impl RecurrenceMapper {
             exception_map,
             color,
             model.is_all_day != 0,
-            model.is_cancelled != 0,
+            EventStatus::from_str(&model.status)?,
             created_at,
             updated_at,
-        )?)
+        )?
+        .with_reminders(reminders)
+        .with_participants(organizer, attendees)
+        .with_sequence(model.sequence as u32)
+        .with_version(model.version as u32)
+        .with_deleted_at(deleted_at)
+        .with_transparency(Transparency::from_str(&model.transparency)?)
+        .with_place(model.location, model.url, geo))
     }
 
 
@@ -215,7 +345,7 @@ impl RecurrenceMapper {
 
                 let range = TimeRange::new(starts, ends)?;
 
-                Ok(RecurrenceException::rescheduled(original, range))
+                Ok(RecurrenceException::rescheduled(original, range).with_location(model.location))
             }
 
             _ => Err(MapperError::InvalidData(
@@ -247,6 +377,7 @@ impl RecurrenceMapper {
             new_starts_at,
             new_ends_at,
             is_cancelled,
+            location: exception.location().clone(),
         }
     }
 
@@ -257,6 +388,10 @@ impl RecurrenceMapper {
             calendar_id: event.calendar_id().to_string(),
             title: event.title().to_string(),
             description: event.description().clone(),
+            location: event.location().clone(),
+            url: event.url().clone(),
+            geo_latitude: event.geo().map(|g| *g.latitude()),
+            geo_longitude: event.geo().map(|g| *g.longitude()),
             starts_at: event.time_range().starts_at().to_rfc3339(),
             ends_at: event.time_range().ends_at().to_rfc3339(),
             frequency: event.rule().frequency().to_string(),
@@ -264,7 +399,11 @@ impl RecurrenceMapper {
             until: event.rule().until().map(|dt| dt.to_rfc3339()),
             color: u8::from(*event.color()) as i64,
             is_all_day: if *event.is_all_day() { 1 } else { 0 },
-            is_cancelled: if *event.is_cancelled() { 1 } else { 0 },
+            status: event.status().to_string(),
+            transparency: event.transparency().to_string(),
+            sequence: *event.sequence() as i64,
+            version: *event.version() as i64,
+            deleted_at: event.deleted_at().map(|dt| dt.to_rfc3339()),
             created_at: event.created_at().to_rfc3339(),
             updated_at: event.updated_at().to_rfc3339(),
         }
@@ -272,6 +411,551 @@ impl RecurrenceMapper {
 }
 
 
+// ======================================================
+// Tasks
+// ======================================================
+
+pub struct TaskMapper;
+
+impl TaskMapper {
+    pub fn to_domain(model: TaskModel) -> MapperResult<Task> {
+        let task_id = TaskId::from_str(&model.id)
+            .map_err(|e| MapperError::InvalidId(e.to_string()))?;
+
+        let calendar_id = CalendarId::from_str(&model.calendar_id)
+            .map_err(|e| MapperError::InvalidId(e.to_string()))?;
+
+        let starts_at = model.starts_at.as_deref().map(parse_date).transpose()?;
+        let due_at = model.due_at.as_deref().map(parse_date).transpose()?;
+        let completed_at = model.completed_at.as_deref().map(parse_date).transpose()?;
+
+        let rule = match (model.frequency, model.interval) {
+            (Some(frequency), Some(interval)) => Some(RecurrenceRule::new(
+                Frequency::from_str(&frequency)?,
+                interval as u32,
+                model.until.as_deref().map(parse_date).transpose()?,
+            )?),
+            (None, _) => None,
+            (Some(_), None) => {
+                return Err(MapperError::InvalidData(
+                    "Recurring task requires an interval".into(),
+                ))
+            }
+        };
+
+        Ok(Task::with_id(
+            task_id,
+            calendar_id,
+            model.title,
+            model.description,
+            starts_at,
+            due_at,
+            TaskStatus::from_str(&model.status)?,
+            parse_date(&model.created_at)?,
+            parse_date(&model.updated_at)?,
+        )?
+        .with_priority(model.priority.map(|p| p as u8))
+        .with_progress(model.percent_complete as u8, completed_at)
+        .with_rule(rule))
+    }
+
+    pub fn to_model(task: &Task) -> TaskModel {
+        let rule = task.rule().as_ref();
+
+        TaskModel {
+            id: task.task_id().to_string(),
+            calendar_id: task.calendar_id().to_string(),
+            title: task.title().to_string(),
+            description: task.description().clone(),
+            starts_at: task.starts_at().map(|dt| dt.to_rfc3339()),
+            due_at: task.due_at().map(|dt| dt.to_rfc3339()),
+            priority: task.priority().map(|p| p as i64),
+            percent_complete: *task.percent_complete() as i64,
+            status: task.status().to_string(),
+            completed_at: task.completed_at().map(|dt| dt.to_rfc3339()),
+            frequency: rule.map(|r| r.frequency().to_string()),
+            interval: rule.map(|r| *r.interval() as i64),
+            until: rule.and_then(|r| r.until().map(|dt| dt.to_rfc3339())),
+            created_at: task.created_at().to_rfc3339(),
+            updated_at: task.updated_at().to_rfc3339(),
+        }
+    }
+}
+
+
+// ======================================================
+// Journal
+// ======================================================
+
+pub struct JournalMapper;
+
+impl JournalMapper {
+    pub fn to_domain(model: JournalModel) -> MapperResult<JournalEntry> {
+        let journal_id = JournalId::from_str(&model.id)
+            .map_err(|e| MapperError::InvalidId(e.to_string()))?;
+
+        let calendar_id = CalendarId::from_str(&model.calendar_id)
+            .map_err(|e| MapperError::InvalidId(e.to_string()))?;
+
+        let date = NaiveDate::from_str(&model.entry_date)
+            .map_err(|e| MapperError::InvalidDate(e.to_string()))?;
+
+        let link = match model.event_id {
+            Some(event_id) => {
+                let event_id = EventId::from_str(&event_id)
+                    .map_err(|e| MapperError::InvalidId(e.to_string()))?;
+                Some(match model.original_starts_at.as_deref() {
+                    Some(original) => JournalLink::Occurrence(event_id, parse_date(original)?),
+                    None => JournalLink::Event(event_id),
+                })
+            }
+            None => None,
+        };
+
+        Ok(JournalEntry::with_id(
+            journal_id,
+            calendar_id,
+            date,
+            model.title,
+            model.body,
+            parse_date(&model.created_at)?,
+            parse_date(&model.updated_at)?,
+        )?
+        .with_link(link))
+    }
+
+    pub fn to_model(entry: &JournalEntry) -> JournalModel {
+        let link = entry.link().as_ref();
+
+        JournalModel {
+            id: entry.journal_id().to_string(),
+            calendar_id: entry.calendar_id().to_string(),
+            entry_date: entry.date().to_string(),
+            title: entry.title().to_string(),
+            body: entry.body().to_string(),
+            event_id: link.map(|l| l.event_id().to_string()),
+            original_starts_at: link
+                .and_then(|l| l.original_starts_at())
+                .map(|dt| dt.to_rfc3339()),
+            created_at: entry.created_at().to_rfc3339(),
+            updated_at: entry.updated_at().to_rfc3339(),
+        }
+    }
+}
+
+
+
+// ======================================================
+// Search
+// ======================================================
+
+pub struct SearchHitMapper;
+
+impl SearchHitMapper {
+    pub fn to_domain(model: SearchHitModel) -> MapperResult<SearchHit> {
+        let event_id = EventId::from_str(&model.event_id)
+            .map_err(|e| MapperError::InvalidId(e.to_string()))?;
+
+        let calendar_id = CalendarId::from_str(&model.calendar_id)
+            .map_err(|e| MapperError::InvalidId(e.to_string()))?;
+
+        let kind = match model.kind.as_str() {
+            "EVENT" => SearchHitKind::Event,
+            "SERIES" => SearchHitKind::Series,
+            other => return Err(MapperError::InvalidData(format!("unknown search hit kind: {other}"))),
+        };
+
+        Ok(SearchHit::new(
+            event_id,
+            calendar_id,
+            kind,
+            model.title,
+            TimeRange::new(parse_date(&model.starts_at)?, parse_date(&model.ends_at)?)?,
+            model.snippet,
+            model.score,
+        ))
+    }
+}
+
+
+// ======================================================
+// Reminders
+// ======================================================
+
+pub struct ReminderMapper;
+
+impl ReminderMapper {
+    pub fn to_domain(model: ReminderModel) -> MapperResult<Reminder> {
+        let reminder_id = ReminderId::from_str(&model.id)
+            .map_err(|e| MapperError::InvalidId(e.to_string()))?;
+
+        let trigger = match (model.trigger_offset, model.trigger_at) {
+            (Some(offset), None) => ReminderTrigger::Relative(chrono::Duration::seconds(offset)),
+            (None, Some(at)) => ReminderTrigger::Absolute(parse_date(&at)?),
+            _ => {
+                return Err(MapperError::InvalidData(
+                    "Reminder needs exactly one of offset or time".into(),
+                ))
+            }
+        };
+
+        let action = ReminderAction::from_parts(&model.action, model.action_target)?;
+
+        Ok(Reminder::with_id(
+            reminder_id,
+            trigger,
+            action,
+            model.description,
+            model.repeat_count as u32,
+            model.repeat_interval.map(chrono::Duration::seconds),
+        )?)
+    }
+
+    pub fn to_model(
+        reminder: &Reminder,
+        event_id: &EventId,
+        position: usize,
+    ) -> ReminderModel {
+        let (trigger_offset, trigger_at) = match reminder.trigger() {
+            ReminderTrigger::Relative(offset) => (Some(offset.num_seconds()), None),
+            ReminderTrigger::Absolute(at) => (None, Some(at.to_rfc3339())),
+        };
+
+        ReminderModel {
+            event_id: event_id.to_string(),
+            id: reminder.reminder_id().to_string(),
+            position: position as i64,
+            trigger_offset,
+            trigger_at,
+            action: reminder.action().kind().to_string(),
+            action_target: reminder.action().target().map(str::to_string),
+            description: reminder.description().clone(),
+            repeat_count: *reminder.repeat() as i64,
+            repeat_interval: reminder.repeat_interval().map(|d| d.num_seconds()),
+        }
+    }
+}
+
+pub struct ParticipantMapper;
+
+impl ParticipantMapper {
+    pub fn to_domain(
+        organizer: Option<OrganizerModel>,
+        attendees: Vec<AttendeeModel>,
+    ) -> MapperResult<(Option<Organizer>, Vec<Attendee>)> {
+        let organizer = organizer
+            .map(|m| Organizer::new(m.email, m.name))
+            .transpose()?;
+
+        let attendees = attendees
+            .into_iter()
+            .map(|m| {
+                Attendee::new(
+                    m.email,
+                    m.name,
+                    m.role.parse()?,
+                    m.status.parse()?,
+                    m.rsvp != 0,
+                )
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok((organizer, attendees))
+    }
+
+    pub fn organizer_to_model(organizer: &Organizer, event_id: &EventId) -> OrganizerModel {
+        OrganizerModel {
+            event_id: event_id.to_string(),
+            email: organizer.email().clone(),
+            name: organizer.name().clone(),
+        }
+    }
+
+    pub fn attendee_to_model(
+        attendee: &Attendee,
+        event_id: &EventId,
+        position: usize,
+    ) -> AttendeeModel {
+        AttendeeModel {
+            event_id: event_id.to_string(),
+            email: attendee.email().clone(),
+            position: position as i64,
+            name: attendee.name().clone(),
+            role: attendee.role().to_string(),
+            status: attendee.status().to_string(),
+            rsvp: if *attendee.rsvp() { 1 } else { 0 },
+        }
+    }
+}
+
+pub struct ReminderStateMapper;
+
+impl ReminderStateMapper {
+    pub fn to_domain(model: ReminderStateModel) -> MapperResult<ReminderState> {
+        let event_id = EventId::from_str(&model.event_id)
+            .map_err(|e| MapperError::InvalidId(e.to_string()))?;
+        let reminder_id = ReminderId::from_str(&model.reminder_id)
+            .map_err(|e| MapperError::InvalidId(e.to_string()))?;
+
+        let last_fired_at = match model.last_fired_at {
+            Some(d) => Some(parse_date(&d)?),
+            None => None,
+        };
+        let snoozed_until = match model.snoozed_until {
+            Some(d) => Some(parse_date(&d)?),
+            None => None,
+        };
+        let dismissed_at = match model.dismissed_at {
+            Some(d) => Some(parse_date(&d)?),
+            None => None,
+        };
+
+        Ok(ReminderState::with_state(
+            ReminderKey::new(event_id, parse_date(&model.original_starts_at)?, reminder_id),
+            model.fired_count as u32,
+            last_fired_at,
+            snoozed_until,
+            dismissed_at,
+        ))
+    }
+
+    pub fn to_model(state: &ReminderState) -> ReminderStateModel {
+        let key = state.key();
+
+        ReminderStateModel {
+            event_id: key.event_id().to_string(),
+            original_starts_at: key.original_starts_at().to_rfc3339(),
+            reminder_id: key.reminder_id().to_string(),
+            fired_count: *state.fired_count() as i64,
+            last_fired_at: state.last_fired_at().map(|dt| dt.to_rfc3339()),
+            snoozed_until: state.snoozed_until().map(|dt| dt.to_rfc3339()),
+            dismissed_at: state.dismissed_at().map(|dt| dt.to_rfc3339()),
+        }
+    }
+}
+
+
+// ======================================================
+// Sync state
+// ======================================================
+
+pub struct SyncStateMapper;
+
+impl SyncStateMapper {
+    pub fn collection_to_domain(
+        model: SyncCollectionModel,
+    ) -> MapperResult<SyncCollection> {
+        let calendar_id = CalendarId::from_str(&model.calendar_id)
+            .map_err(|e| MapperError::InvalidId(e.to_string()))?;
+
+        let last_synced_at = match model.last_synced_at {
+            Some(d) => Some(parse_date(&d)?),
+            None => None,
+        };
+
+        Ok(SyncCollection::with_state(
+            calendar_id,
+            model.remote_href,
+            model.sync_token,
+            last_synced_at,
+        ))
+    }
+
+    pub fn collection_to_model(collection: &SyncCollection) -> SyncCollectionModel {
+        SyncCollectionModel {
+            calendar_id: collection.calendar_id().to_string(),
+            remote_href: collection.remote_href().clone(),
+            sync_token: collection.sync_token().clone(),
+            last_synced_at: collection.last_synced_at().map(|dt| dt.to_rfc3339()),
+        }
+    }
+
+    pub fn item_to_domain(model: SyncItemModel) -> MapperResult<SyncItem> {
+        let item_id = EventId::from_str(&model.item_id)
+            .map_err(|e| MapperError::InvalidId(e.to_string()))?;
+
+        let calendar_id = CalendarId::from_str(&model.calendar_id)
+            .map_err(|e| MapperError::InvalidId(e.to_string()))?;
+
+        let kind = SyncItemKind::from_str(&model.kind)?;
+
+        let last_synced_at = match model.last_synced_at {
+            Some(d) => Some(parse_date(&d)?),
+            None => None,
+        };
+
+        Ok(SyncItem::with_state(
+            item_id,
+            calendar_id,
+            kind,
+            model.remote_href,
+            model.remote_uid,
+            model.etag,
+            model.last_synced_hash,
+            model.is_dirty != 0,
+            last_synced_at,
+        ))
+    }
+
+    pub fn item_to_model(item: &SyncItem) -> SyncItemModel {
+        SyncItemModel {
+            item_id: item.item_id().to_string(),
+            calendar_id: item.calendar_id().to_string(),
+            kind: item.kind().to_string(),
+            remote_href: item.remote_href().clone(),
+            remote_uid: item.remote_uid().clone(),
+            etag: item.etag().clone(),
+            last_synced_hash: item.last_synced_hash().clone(),
+            is_dirty: if *item.is_dirty() { 1 } else { 0 },
+            last_synced_at: item.last_synced_at().map(|dt| dt.to_rfc3339()),
+        }
+    }
+
+    pub fn tombstone_to_domain(model: TombstoneModel) -> MapperResult<Tombstone> {
+        let item_id = EventId::from_str(&model.item_id)
+            .map_err(|e| MapperError::InvalidId(e.to_string()))?;
+
+        let calendar_id = CalendarId::from_str(&model.calendar_id)
+            .map_err(|e| MapperError::InvalidId(e.to_string()))?;
+
+        Ok(Tombstone::new(
+            item_id,
+            calendar_id,
+            SyncItemKind::from_str(&model.kind)?,
+            model.remote_href,
+            model.etag,
+            parse_date(&model.deleted_at)?,
+        ))
+    }
+}
+
+
+// ======================================================
+// History
+// ======================================================
+
+/// Snapshots are kept as the objects' own JSON, so undo puts back exactly
+/// what was there. Changes recorded before that are iCalendar text with
+/// the trash state alongside, and are still read.
+pub struct HistoryMapper;
+
+impl HistoryMapper {
+    pub fn to_domain(
+        entry: HistoryEntryModel,
+        changes: Vec<HistoryChangeModel>,
+    ) -> MapperResult<HistoryEntry> {
+        let changes = changes
+            .into_iter()
+            .map(Self::change_to_domain)
+            .collect::<MapperResult<Vec<_>>>()?;
+
+        Ok(HistoryEntry::new(
+            entry.id,
+            entry.parent_id,
+            entry.command,
+            parse_date(&entry.recorded_at)?,
+            changes,
+        ))
+    }
+
+    pub fn change_to_model(
+        entry_id: i64,
+        position: usize,
+        change: &Change,
+    ) -> MapperResult<HistoryChangeModel> {
+        let calendar_id = change
+            .after()
+            .as_ref()
+            .or(change.before().as_ref())
+            .map(|snapshot| snapshot.calendar_id().to_string())
+            .unwrap_or_default();
+
+        Ok(HistoryChangeModel {
+            entry_id,
+            position: position as i64,
+            kind: change.kind().to_string(),
+            object_id: change.object_id().to_string(),
+            calendar_id,
+            before_json: change.before().as_ref().map(Self::snapshot_to_json).transpose()?,
+            after_json: change.after().as_ref().map(Self::snapshot_to_json).transpose()?,
+        })
+    }
+
+    fn change_to_domain(model: HistoryChangeModel) -> MapperResult<Change> {
+        let kind = ObjectKind::from_str(&model.kind)?;
+        let object_id = Uuid::from_str(&model.object_id)
+            .map_err(|e| MapperError::InvalidId(e.to_string()))?;
+
+        let snapshot = |json: Option<String>| {
+            json.map(|json| Self::snapshot_from_json(kind, &json)).transpose()
+        };
+
+        Ok(Change::new(
+            kind,
+            object_id,
+            snapshot(model.before_json)?,
+            snapshot(model.after_json)?,
+        ))
+    }
+
+    fn snapshot_to_json(snapshot: &Snapshot) -> MapperResult<String> {
+        let json = match snapshot {
+            Snapshot::Calendar(calendar) => serde_json::to_string(calendar),
+            Snapshot::Event(event) => serde_json::to_string(event),
+            Snapshot::Recurring(event) => serde_json::to_string(event),
+            Snapshot::Task(task) => serde_json::to_string(task),
+            Snapshot::Journal(entry) => serde_json::to_string(entry),
+        };
+
+        json.map_err(|e| MapperError::InvalidData(e.to_string()))
+    }
+
+    fn snapshot_from_json(kind: ObjectKind, json: &str) -> MapperResult<Snapshot> {
+        let snapshot = match kind {
+            ObjectKind::Calendar => serde_json::from_str(json).map(Snapshot::Calendar),
+            ObjectKind::Event => serde_json::from_str(json).map(Snapshot::Event),
+            ObjectKind::Series => serde_json::from_str(json).map(Snapshot::Recurring),
+            ObjectKind::Task => serde_json::from_str(json).map(Snapshot::Task),
+            ObjectKind::Journal => serde_json::from_str(json).map(Snapshot::Journal),
+        };
+
+        snapshot.map_err(|e| MapperError::InvalidData(e.to_string()))
+    }
+}
+
+
+// ======================================================
+// Audit log
+// ======================================================
+
+pub struct AuditMapper;
+
+impl AuditMapper {
+    pub fn to_domain(entry: AuditEntryModel, changes: Vec<AuditChangeModel>) -> MapperResult<AuditEntry> {
+        let calendar_id = CalendarId::from_str(&entry.calendar_id)
+            .map_err(|e| MapperError::InvalidId(e.to_string()))?;
+        let object_id = Uuid::from_str(&entry.object_id)
+            .map_err(|e| MapperError::InvalidId(e.to_string()))?;
+        let changes = changes
+            .into_iter()
+            .map(|change| FieldChange::new(change.field, change.from_value, change.to_value))
+            .collect();
+
+        Ok(AuditEntry::new(
+            entry.id,
+            parse_date(&entry.recorded_at)?,
+            entry.actor,
+            entry.command,
+            entry.event,
+            calendar_id,
+            object_id,
+            entry.occurrence.as_deref().map(parse_date).transpose()?,
+            changes,
+        ))
+    }
+}
+
+
 // ======================================================
 // Helpers
 // ======================================================
@@ -283,3 +967,13 @@ fn parse_date(s: &str) -> MapperResult<DateTime<Utc>> {
             .with_timezone(&Utc)
     )
 }
+
+fn geo_to_domain(latitude: Option<f64>, longitude: Option<f64>) -> MapperResult<Option<GeoPoint>> {
+    match (latitude, longitude) {
+        (Some(latitude), Some(longitude)) => Ok(Some(GeoPoint::new(latitude, longitude)?)),
+        (None, None) => Ok(None),
+        _ => Err(MapperError::InvalidData(
+            "coordinates need both a latitude and a longitude".into(),
+        )),
+    }
+}
@@ -3,8 +3,62 @@ pub mod mappers;
 pub mod calendar_repository;
 pub mod event_repository;
 pub mod recurring_event_repository;
+pub mod task_repository;
+pub mod journal_repository;
+pub mod reminders;
+pub mod attendees;
+pub mod tags;
+pub mod search;
+pub mod reminder_state_repository;
+pub mod sync_state_repository;
+pub mod subscription_repository;
+pub mod history_repository;
+pub mod audit_repository;
+pub mod database;
+pub mod unit_of_work;
+mod handle;
 pub mod error;
 
+pub use database::Database;
+pub use unit_of_work::SqliteUnitOfWork;
 pub use calendar_repository::SqliteCalendarRepository;
 pub use event_repository::SqliteEventRepository;
 pub use recurring_event_repository::SqliteRecurringEventRepository;
+pub use task_repository::SqliteTaskRepository;
+pub use journal_repository::SqliteJournalRepository;
+pub use reminder_state_repository::SqliteReminderStateRepository;
+pub use sync_state_repository::SqliteSyncStateRepository;
+pub use subscription_repository::SqliteSubscriptionRepository;
+pub use history_repository::SqliteHistoryRepository;
+pub use audit_repository::SqliteAuditRepository;
+pub use tags::SqliteTagRepository;
+pub use search::SqliteEventSearchRepository;
+
+/// Builds a `LIKE ... ESCAPE '\'` pattern matching `needle` anywhere,
+/// treating `%` and `_` in the input literally.
+pub(crate) fn like_pattern(needle: &str) -> String {
+    let mut pattern = String::with_capacity(needle.len() + 2);
+    pattern.push('%');
+
+    for c in needle.chars() {
+        if matches!(c, '%' | '_' | '\\') {
+            pattern.push('\\');
+        }
+        pattern.push(c);
+    }
+
+    pattern.push('%');
+    pattern
+}
+
+/// Turns free text into an FTS5 query matching rows that contain every
+/// word, each as a prefix. Quoting keeps FTS5 operators and punctuation
+/// in the input literal. `None` when the text has no words.
+pub(crate) fn fts_query(text: &str) -> Option<String> {
+    let terms: Vec<String> = text
+        .split_whitespace()
+        .map(|word| format!("\"{}\"*", word.replace('"', "\"\"")))
+        .collect();
+
+    (!terms.is_empty()).then(|| terms.join(" "))
+}
@@ -6,6 +6,13 @@ pub struct CalendarModel {
     pub name: String,
     pub description: Option<String>,
     pub is_archived: i64,
+    pub subscription_url: Option<String>,
+    pub refresh_interval: Option<i64>,
+    pub last_fetched_at: Option<String>,
+    pub fetch_etag: Option<String>,
+    pub fetch_last_modified: Option<String>,
+    pub version: i64,
+    pub deleted_at: Option<String>,
     pub created_at: String,
     pub updated_at: String,
 }
@@ -16,11 +23,19 @@ pub struct EventModel {
     pub calendar_id: String,
     pub title: String,
     pub description: Option<String>,
+    pub location: Option<String>,
+    pub url: Option<String>,
+    pub geo_latitude: Option<f64>,
+    pub geo_longitude: Option<f64>,
     pub starts_at: String,
     pub ends_at: String,
     pub color: i64,
     pub is_all_day: i64,
-    pub is_cancelled: i64,
+    pub status: String,
+    pub transparency: String,
+    pub sequence: i64,
+    pub version: i64,
+    pub deleted_at: Option<String>,
     pub created_at: String,
     pub updated_at: String,
 }
@@ -31,6 +46,10 @@ pub struct RecurrenceModel {
     pub calendar_id: String,
     pub title: String,
     pub description: Option<String>,
+    pub location: Option<String>,
+    pub url: Option<String>,
+    pub geo_latitude: Option<f64>,
+    pub geo_longitude: Option<f64>,
     pub starts_at: String,
     pub ends_at: String,
     pub frequency: String,
@@ -38,11 +57,59 @@ pub struct RecurrenceModel {
     pub until: Option<String>,
     pub color: i64,
     pub is_all_day: i64,
-    pub is_cancelled: i64,
+    pub status: String,
+    pub transparency: String,
+    pub sequence: i64,
+    pub version: i64,
+    pub deleted_at: Option<String>,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+#[derive(Debug, FromRow)]
+pub struct TaskModel {
+    pub id: String,
+    pub calendar_id: String,
+    pub title: String,
+    pub description: Option<String>,
+    pub starts_at: Option<String>,
+    pub due_at: Option<String>,
+    pub priority: Option<i64>,
+    pub percent_complete: i64,
+    pub status: String,
+    pub completed_at: Option<String>,
+    pub frequency: Option<String>,
+    pub interval: Option<i64>,
+    pub until: Option<String>,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+#[derive(Debug, FromRow)]
+pub struct JournalModel {
+    pub id: String,
+    pub calendar_id: String,
+    pub entry_date: String,
+    pub title: String,
+    pub body: String,
+    pub event_id: Option<String>,
+    pub original_starts_at: Option<String>,
     pub created_at: String,
     pub updated_at: String,
 }
 
+#[derive(Debug, FromRow)]
+pub struct SearchHitModel {
+    pub event_id: String,
+    pub kind: String,
+    pub calendar_id: String,
+    pub title: String,
+    pub starts_at: String,
+    pub ends_at: String,
+    pub snippet: String,
+    pub score: f64,
+}
+
 #[derive(Debug, FromRow)]
 pub struct RecurrenceExceptionModel {
     pub recurrence_id: String,
@@ -50,4 +117,119 @@ pub struct RecurrenceExceptionModel {
     pub new_starts_at: Option<String>,
     pub new_ends_at: Option<String>,
     pub is_cancelled: i64,
+    pub location: Option<String>,
+}
+
+#[derive(Debug, FromRow)]
+pub struct ReminderModel {
+    pub event_id: String,
+    pub id: String,
+    pub position: i64,
+    pub trigger_offset: Option<i64>,
+    pub trigger_at: Option<String>,
+    pub action: String,
+    pub action_target: Option<String>,
+    pub description: Option<String>,
+    pub repeat_count: i64,
+    pub repeat_interval: Option<i64>,
+}
+
+#[derive(Debug, FromRow)]
+pub struct AttendeeModel {
+    pub event_id: String,
+    pub email: String,
+    pub position: i64,
+    pub name: Option<String>,
+    pub role: String,
+    pub status: String,
+    pub rsvp: i64,
+}
+
+#[derive(Debug, FromRow)]
+pub struct OrganizerModel {
+    pub event_id: String,
+    pub email: String,
+    pub name: Option<String>,
+}
+
+#[derive(Debug, FromRow)]
+pub struct ReminderStateModel {
+    pub event_id: String,
+    pub original_starts_at: String,
+    pub reminder_id: String,
+    pub fired_count: i64,
+    pub last_fired_at: Option<String>,
+    pub snoozed_until: Option<String>,
+    pub dismissed_at: Option<String>,
+}
+
+#[derive(Debug, FromRow)]
+pub struct SyncCollectionModel {
+    pub calendar_id: String,
+    pub remote_href: String,
+    pub sync_token: Option<String>,
+    pub last_synced_at: Option<String>,
+}
+
+#[derive(Debug, FromRow)]
+pub struct SyncItemModel {
+    pub item_id: String,
+    pub calendar_id: String,
+    pub kind: String,
+    pub remote_href: Option<String>,
+    pub remote_uid: Option<String>,
+    pub etag: Option<String>,
+    pub last_synced_hash: Option<String>,
+    pub is_dirty: i64,
+    pub last_synced_at: Option<String>,
+}
+
+#[derive(Debug, FromRow)]
+pub struct TombstoneModel {
+    pub item_id: String,
+    pub calendar_id: String,
+    pub kind: String,
+    pub remote_href: String,
+    pub etag: Option<String>,
+    pub deleted_at: String,
+}
+
+#[derive(Debug, FromRow)]
+pub struct HistoryEntryModel {
+    pub id: i64,
+    pub parent_id: Option<i64>,
+    pub command: String,
+    pub recorded_at: String,
+}
+
+#[derive(Debug, FromRow)]
+pub struct HistoryChangeModel {
+    pub entry_id: i64,
+    pub position: i64,
+    pub kind: String,
+    pub object_id: String,
+    pub calendar_id: String,
+    pub before_json: Option<String>,
+    pub after_json: Option<String>,
+}
+
+#[derive(Debug, FromRow)]
+pub struct AuditEntryModel {
+    pub id: i64,
+    pub recorded_at: String,
+    pub actor: String,
+    pub command: String,
+    pub event: String,
+    pub calendar_id: String,
+    pub object_id: String,
+    pub occurrence: Option<String>,
+}
+
+#[derive(Debug, FromRow)]
+pub struct AuditChangeModel {
+    pub entry_id: i64,
+    pub position: i64,
+    pub field: String,
+    pub from_value: Option<String>,
+    pub to_value: Option<String>,
 }
@@ -1,158 +1,107 @@
 use async_trait::async_trait;
-use sqlx::SqlitePool;
+use chrono::{DateTime, Utc};
+use sqlx::{Connection, SqliteConnection, SqlitePool};
 use crate::domain::{
     recurrence::RecurringEvent,
     repository::{RecurringEventRepository, RepositoryError},
-    value_objects::{CalendarId, EventId},
+    tag::Tag,
+    value_objects::{CalendarId, EventId, TimeRange},
 };
 use super::{
+    handle::Handle,
     models::{RecurrenceModel, RecurrenceExceptionModel},
     mappers::RecurrenceMapper,
+    reminders::{fetch_reminders, replace_reminders},
+    attendees::{fetch_attendees, fetch_organizer, replace_participants},
+    tags::{fetch_tags, find_tagged_ids, replace_tags},
+    like_pattern,
 };
 
 pub struct SqliteRecurringEventRepository {
-    pool: SqlitePool,
+    handle: Handle,
 }
 
 impl SqliteRecurringEventRepository {
     pub fn new(pool: SqlitePool) -> Self {
-        Self { pool }
+        Self { handle: Handle::Pool(pool) }
     }
-}
 
-#[async_trait]
-impl RecurringEventRepository for SqliteRecurringEventRepository {
-    async fn save(&self, event: &RecurringEvent) -> Result<(), RepositoryError> {
-        let mut tx = self.pool.begin()
-            .await
-            .map_err(|e| RepositoryError::DatabaseError(e.to_string()))?;
-        
-        let model = RecurrenceMapper::to_model(event);
+    pub(crate) fn with_handle(handle: Handle) -> Self {
+        Self { handle }
+    }
 
-        sqlx::query!(
+    async fn hydrate(
+        conn: &mut SqliteConnection,
+        model: RecurrenceModel,
+    ) -> Result<RecurringEvent, RepositoryError> {
+        let exceptions = sqlx::query_as::<_, RecurrenceExceptionModel>(
             r#"
-                INSERT INTO recurrences (
-                    id, calendar_id, title, description, starts_at, ends_at,
-                    frequency, interval, until, color, is_all_day, is_cancelled,
-                    created_at, updated_at
-                )
-                VALUES (
-                    ?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8,
-                    ?9, ?10, ?11, ?12, ?13, ?14
-                )
-                ON CONFLICT(id) DO UPDATE SET
-                    title = excluded.title,
-                    description = excluded.description,
-                    starts_at = excluded.starts_at,
-                    ends_at = excluded.ends_at,
-                    frequency = excluded.frequency,
-                    interval = excluded.interval,
-                    until = excluded.until,
-                    color = excluded.color,
-                    is_all_day = excluded.is_all_day,
-                    is_cancelled = excluded.is_cancelled,
-                    updated_at = excluded.updated_at
-            "#,
-            model.id,
-            model.calendar_id,
-            model.title,
-            model.description,
-            model.starts_at,
-            model.ends_at,
-            model.frequency,
-            model.interval,
-            model.until,
-            model.color,
-            model.is_all_day,
-            model.is_cancelled,
-            model.created_at,
-            model.updated_at,
+                SELECT recurrence_id, original_starts_at, new_starts_at,
+                       new_ends_at, is_cancelled, location
+                FROM recurrence_exceptions
+                WHERE recurrence_id = ?1
+            "#
         )
-        .execute(&mut *tx)
-        .await
-        .map_err(|e| RepositoryError::DatabaseError(e.to_string()))?;
+            .bind(&model.id)
+            .fetch_all(&mut *conn)
+            .await
+            .map_err(|e| RepositoryError::DatabaseError(e.to_string()))?;
 
-        sqlx::query!(
-            r#"
-                DELETE FROM recurrence_exceptions WHERE recurrence_id = ?1
-            "#,
-            model.id,
-        )
-        .execute(&mut *tx)
-        .await
-        .map_err(|e| RepositoryError::DatabaseError(e.to_string()))?;
+        let reminders = fetch_reminders(&mut *conn, &model.id).await?;
+        let organizer = fetch_organizer(&mut *conn, &model.id).await?;
+        let attendees = fetch_attendees(&mut *conn, &model.id).await?;
+        let tags = fetch_tags(&mut *conn, &model.id).await?;
 
-        for exception in event.exceptions().values() {
-            let ex_model = RecurrenceMapper::exception_to_model(
-                exception,
-                event.event_id(),
-            );
-
-            sqlx::query!(
-                r#"
-                    INSERT INTO recurrence_exceptions (
-                        recurrence_id, original_starts_at, new_starts_at,
-                        new_ends_at, is_cancelled
-                    )
-                    VALUES (?1, ?2, ?3, ?4, ?5)
-                "#,
-                ex_model.recurrence_id,
-                ex_model.original_starts_at,
-                ex_model.new_starts_at,
-                ex_model.new_ends_at,
-                ex_model.is_cancelled,
-            )
-            .execute(&mut *tx)
+        RecurrenceMapper::to_domain(model, exceptions, reminders, organizer, attendees)
+            .map(|event| event.with_tags(tags))
+            .map_err(|e| RepositoryError::DatabaseError(e.to_string()))
+    }
+}
+
+#[async_trait]
+impl RecurringEventRepository for SqliteRecurringEventRepository {
+    async fn save(&self, event: &RecurringEvent) -> Result<u32, RepositoryError> {
+        let mut conn = self.handle.acquire().await?;
+        let mut tx = conn.begin()
             .await
             .map_err(|e| RepositoryError::DatabaseError(e.to_string()))?;
-        }
+
+        let version = upsert_recurring_event(&mut tx, event).await?;
 
         tx.commit()
             .await
             .map_err(|e| RepositoryError::DatabaseError(e.to_string()))?;
 
-        Ok(())
+        Ok(version)
     }
 
     async fn find_by_calendar(
         &self,
         id: &CalendarId
     ) -> Result<Vec<RecurringEvent>, RepositoryError> {
+        let mut conn = self.handle.acquire().await?;
+
         let models = sqlx::query_as::<_, RecurrenceModel>(
             r#"
-                SELECT id, calendar_id, title, description, starts_at, ends_at,
+                SELECT id, calendar_id, title, description, location, url,
+                       geo_latitude, geo_longitude, starts_at, ends_at,
                        frequency, interval, until, color, is_all_day,
-                       is_cancelled, created_at, updated_at
+                       status, transparency, sequence, version, deleted_at,
+                       created_at, updated_at
                 FROM recurrences
-                WHERE calendar_id = ?1
+                WHERE calendar_id = ?1 AND deleted_at IS NULL
                 ORDER BY starts_at
             "#
         )
         .bind(&id.to_string())
-        .fetch_all(&self.pool)
+        .fetch_all(&mut *conn)
         .await
         .map_err(|e| RepositoryError::DatabaseError(e.to_string()))?;
 
         let mut result = Vec::new();
 
         for model in models {
-            let exceptions = sqlx::query_as::<_, RecurrenceExceptionModel>(
-                r#"
-                    SELECT id, recurrence_id, original_starts_at, new_starts_at,
-                           new_ends_at, is_cancelled
-                    FROM recurrence_exceptions
-                    WHERE recurrence_id = ?1
-                "#
-            )
-            .bind(&model.id)
-            .fetch_all(&self.pool)
-            .await
-            .map_err(|e| RepositoryError::DatabaseError(e.to_string()))?;
-
-            let event = RecurrenceMapper::to_domain(model, exceptions)
-                .map_err(|e| RepositoryError::DatabaseError(e.to_string()))?;
-
-            result.push(event);
+            result.push(Self::hydrate(&mut conn, model).await?);
         }
 
         Ok(result)
@@ -162,46 +111,256 @@ impl RecurringEventRepository for SqliteRecurringEventRepository {
         &self,
         id: &EventId
     ) -> Result<RecurringEvent, RepositoryError> {
+        let mut conn = self.handle.acquire().await?;
+
         let model = sqlx::query_as::<_, RecurrenceModel>(
             r#"
-                SELECT id, calendar_id, title, description, starts_at, ends_at,
+                SELECT id, calendar_id, title, description, location, url,
+                       geo_latitude, geo_longitude, starts_at, ends_at,
                        frequency, interval, until, color, is_all_day,
-                       is_cancelled,
+                       status, transparency, sequence, version, deleted_at,
                        created_at, updated_at
                 FROM recurrences
-                WHERE id = ?1
+                WHERE id = ?1 AND deleted_at IS NULL
             "#
         )
             .bind(id.to_string())
-            .fetch_optional(&self.pool)
+            .fetch_optional(&mut *conn)
             .await
             .map_err(|e| RepositoryError::DatabaseError(e.to_string()))?;
 
-        let model = match model {
-            Some(m) => m,
-            None => return Err(RepositoryError::NotFound),
-        };
+        match model {
+            Some(m) => Self::hydrate(&mut conn, m).await,
+            None => Err(RepositoryError::NotFound),
+        }
+    }
 
-        let exceptions = sqlx::query_as::<_, RecurrenceExceptionModel>(
+    async fn find_by_location(
+        &self,
+        calendar_id: &CalendarId,
+        needle: &str,
+    ) -> Result<Vec<RecurringEvent>, RepositoryError> {
+        let ids = sqlx::query_scalar::<_, String>(
             r#"
-                SELECT recurrence_id, original_starts_at, new_starts_at,
-                       new_ends_at, is_cancelled
-                FROM recurrence_exceptions
-                WHERE recurrence_id = ?1
+                SELECT r.id
+                FROM recurrences r
+                WHERE r.calendar_id = ?1
+                  AND r.deleted_at IS NULL
+                  AND (
+                      r.location LIKE ?2 ESCAPE '\'
+                      OR EXISTS (
+                          SELECT 1 FROM recurrence_exceptions x
+                          WHERE x.recurrence_id = r.id
+                            AND x.is_cancelled = 0
+                            AND x.location LIKE ?2 ESCAPE '\'
+                      )
+                  )
+                ORDER BY r.starts_at
             "#
         )
-            .bind(&model.id)
-            .fetch_all(&self.pool)
+        .bind(calendar_id.to_string())
+        .bind(like_pattern(needle))
+        .fetch_all(&mut *self.handle.acquire().await?)
+        .await
+        .map_err(|e| RepositoryError::DatabaseError(e.to_string()))?;
+
+        let mut result = Vec::with_capacity(ids.len());
+
+        for id in ids {
+            let id = id.parse::<EventId>()
+                .map_err(|e| RepositoryError::DatabaseError(e.to_string()))?;
+            result.push(self.find_by_id(&id).await?);
+        }
+
+        Ok(result)
+    }
+
+    async fn find_by_tag(&self, tag: &Tag) -> Result<Vec<RecurringEvent>, RepositoryError> {
+        let mut result = Vec::new();
+
+        // Tag links are shared with single events; skip those ids
+        let ids = find_tagged_ids(&mut *self.handle.acquire().await?, tag).await?;
+
+        for id in ids {
+            match self.find_by_id(&id).await {
+                Ok(event) => result.push(event),
+                Err(RepositoryError::NotFound) => {}
+                Err(e) => return Err(e),
+            }
+        }
+
+        Ok(result)
+    }
+
+    async fn find_with_reminders_in(
+        &self,
+        window: &TimeRange,
+    ) -> Result<Vec<RecurringEvent>, RepositoryError> {
+        let mut conn = self.handle.acquire().await?;
+
+        // Whole seconds, widened by one; the exact bounds are checked below
+        let window_start = window.starts_at().timestamp() - 1;
+        let window_end = window.ends_at().timestamp() + 1;
+
+        // Series whose absolute reminders fire in the window, or whose
+        // relative ones can: the series has started and not yet ended by
+        // then, or has moved occurrences that could land anywhere
+        let models = sqlx::query_as::<_, RecurrenceModel>(
+            r#"
+                SELECT id, calendar_id, title, description, location, url,
+                       geo_latitude, geo_longitude, starts_at, ends_at,
+                       frequency, interval, until, color, is_all_day,
+                       status, transparency, sequence, version, deleted_at,
+                       created_at, updated_at
+                FROM recurrences s
+                WHERE deleted_at IS NULL
+                  AND status != 'CANCELLED'
+                  AND EXISTS (
+                      SELECT 1 FROM reminders r
+                      WHERE r.event_id = s.id
+                        AND (
+                            CAST(strftime('%s', r.trigger_at) AS INTEGER) BETWEEN ?1 AND ?2
+                            OR (
+                                r.trigger_offset IS NOT NULL
+                                AND CAST(strftime('%s', s.starts_at) AS INTEGER)
+                                    + r.trigger_offset <= ?2
+                                AND (
+                                    s.until IS NULL
+                                    OR CAST(strftime('%s', s.until) AS INTEGER)
+                                        + r.trigger_offset >= ?1
+                                )
+                            )
+                            OR (
+                                r.trigger_offset IS NOT NULL
+                                AND EXISTS (
+                                    SELECT 1 FROM recurrence_exceptions x
+                                    WHERE x.recurrence_id = s.id
+                                      AND x.new_starts_at IS NOT NULL
+                                )
+                            )
+                        )
+                  )
+                ORDER BY starts_at
+            "#
+        )
+        .bind(window_start)
+        .bind(window_end)
+        .fetch_all(&mut *conn)
+        .await
+        .map_err(|e| RepositoryError::DatabaseError(e.to_string()))?;
+
+        let mut result = Vec::new();
+
+        for model in models {
+            let event = Self::hydrate(&mut conn, model).await?;
+
+            if !event.reminder_instances(window).is_empty() {
+                result.push(event);
+            }
+        }
+
+        Ok(result)
+    }
+
+    async fn trash(&self, id: &EventId, deleted_at: DateTime<Utc>) -> Result<(), RepositoryError> {
+        let mut conn = self.handle.acquire().await?;
+
+        let id_str = id.to_string();
+        let deleted_at = deleted_at.to_rfc3339();
+
+        let result = sqlx::query!(
+            r#"
+                UPDATE recurrences SET deleted_at = ?2, version = version + 1
+                WHERE id = ?1 AND deleted_at IS NULL
+            "#,
+            id_str,
+            deleted_at,
+        )
+            .execute(&mut *conn)
+            .await
+            .map_err(|e| RepositoryError::DatabaseError(e.to_string()))?;
+
+        if result.rows_affected() == 0 {
+            return Err(RepositoryError::NotFound);
+        }
+
+        Ok(())
+    }
+
+    async fn restore(&self, id: &EventId) -> Result<(), RepositoryError> {
+        let mut conn = self.handle.acquire().await?;
+
+        let id_str = id.to_string();
+
+        let result = sqlx::query!(
+            r#"
+                UPDATE recurrences SET deleted_at = NULL, version = version + 1
+                WHERE id = ?1 AND deleted_at IS NOT NULL
+            "#,
+            id_str,
+        )
+            .execute(&mut *conn)
             .await
             .map_err(|e| RepositoryError::DatabaseError(e.to_string()))?;
 
-        let event = RecurrenceMapper::to_domain(model, exceptions)
+        if result.rows_affected() == 0 {
+            return Err(RepositoryError::NotFound);
+        }
+
+        Ok(())
+    }
+
+    async fn find_trashed(&self) -> Result<Vec<RecurringEvent>, RepositoryError> {
+        let mut conn = self.handle.acquire().await?;
+
+        let models = sqlx::query_as::<_, RecurrenceModel>(
+            r#"
+                SELECT id, calendar_id, title, description, location, url,
+                       geo_latitude, geo_longitude, starts_at, ends_at,
+                       frequency, interval, until, color, is_all_day,
+                       status, transparency, sequence, version, deleted_at,
+                       created_at, updated_at
+                FROM recurrences
+                WHERE deleted_at IS NOT NULL
+                ORDER BY deleted_at DESC, starts_at
+            "#
+        )
+        .fetch_all(&mut *conn)
+        .await
+        .map_err(|e| RepositoryError::DatabaseError(e.to_string()))?;
+
+        let mut result = Vec::new();
+
+        for model in models {
+            result.push(Self::hydrate(&mut conn, model).await?);
+        }
+
+        Ok(result)
+    }
+
+    async fn purge_trashed(&self, cutoff: DateTime<Utc>) -> Result<u64, RepositoryError> {
+        let mut conn = self.handle.acquire().await?;
+
+        // Compared as instants, as stored timestamps need not share the
+        // cutoff's offset or precision
+        let cutoff = cutoff.to_rfc3339();
+
+        let result = sqlx::query!(
+            r#"
+                DELETE FROM recurrences WHERE julianday(deleted_at) < julianday(?1)
+            "#,
+            cutoff,
+        )
+            .execute(&mut *conn)
+            .await
             .map_err(|e| RepositoryError::DatabaseError(e.to_string()))?;
 
-        Ok(event)
+        Ok(result.rows_affected())
     }
 
     async fn delete(&self, id: &EventId) -> Result<(), RepositoryError> {
+        let mut conn = self.handle.acquire().await?;
+
         let id_str = id.to_string();
 
         let result = sqlx::query!(
@@ -210,7 +369,7 @@ impl RecurringEventRepository for SqliteRecurringEventRepository {
             "#,
             id_str,
         )
-            .execute(&self.pool)
+            .execute(&mut *conn)
             .await
             .map_err(|e| RepositoryError::DatabaseError(e.to_string()))?;
 
@@ -221,3 +380,120 @@ impl RecurringEventRepository for SqliteRecurringEventRepository {
         Ok(())
     }
 }
+
+/// Writes the series and replaces its exceptions, reminders and
+/// participants, returning the new version. The caller owns the
+/// transaction so they stay consistent.
+pub(crate) async fn upsert_recurring_event(
+    conn: &mut SqliteConnection,
+    event: &RecurringEvent,
+) -> Result<u32, RepositoryError> {
+    let model = RecurrenceMapper::to_model(event);
+
+    let result = sqlx::query!(
+        r#"
+            INSERT INTO recurrences (
+                id, calendar_id, title, description, location, url,
+                geo_latitude, geo_longitude, starts_at, ends_at,
+                frequency, interval, until, color, is_all_day, status,
+                transparency, sequence, version, created_at, updated_at
+            )
+            VALUES (
+                ?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10,
+                ?11, ?12, ?13, ?14, ?15, ?16, ?17, ?18, ?21 + 1, ?19, ?20
+            )
+            ON CONFLICT(id) DO UPDATE SET
+                title = excluded.title,
+                description = excluded.description,
+                location = excluded.location,
+                url = excluded.url,
+                geo_latitude = excluded.geo_latitude,
+                geo_longitude = excluded.geo_longitude,
+                starts_at = excluded.starts_at,
+                ends_at = excluded.ends_at,
+                frequency = excluded.frequency,
+                interval = excluded.interval,
+                until = excluded.until,
+                color = excluded.color,
+                is_all_day = excluded.is_all_day,
+                status = excluded.status,
+                transparency = excluded.transparency,
+                sequence = excluded.sequence,
+                updated_at = excluded.updated_at,
+                version = recurrences.version + 1
+            WHERE recurrences.version = ?21
+        "#,
+        model.id,
+        model.calendar_id,
+        model.title,
+        model.description,
+        model.location,
+        model.url,
+        model.geo_latitude,
+        model.geo_longitude,
+        model.starts_at,
+        model.ends_at,
+        model.frequency,
+        model.interval,
+        model.until,
+        model.color,
+        model.is_all_day,
+        model.status,
+        model.transparency,
+        model.sequence,
+        model.created_at,
+        model.updated_at,
+        model.version,
+    )
+    .execute(&mut *conn)
+    .await
+    .map_err(|e| RepositoryError::DatabaseError(e.to_string()))?;
+
+    // The upsert skips the update when the stored version moved on,
+    // which covers a new series reusing the id of a trashed one
+    if result.rows_affected() == 0 {
+        return Err(RepositoryError::Conflict);
+    }
+
+    sqlx::query!(
+        r#"
+            DELETE FROM recurrence_exceptions WHERE recurrence_id = ?1
+        "#,
+        model.id,
+    )
+    .execute(&mut *conn)
+    .await
+    .map_err(|e| RepositoryError::DatabaseError(e.to_string()))?;
+
+    for exception in event.exceptions().values() {
+        let ex_model = RecurrenceMapper::exception_to_model(
+            exception,
+            event.event_id(),
+        );
+
+        sqlx::query!(
+            r#"
+                INSERT INTO recurrence_exceptions (
+                    recurrence_id, original_starts_at, new_starts_at,
+                    new_ends_at, is_cancelled, location
+                )
+                VALUES (?1, ?2, ?3, ?4, ?5, ?6)
+            "#,
+            ex_model.recurrence_id,
+            ex_model.original_starts_at,
+            ex_model.new_starts_at,
+            ex_model.new_ends_at,
+            ex_model.is_cancelled,
+            ex_model.location,
+        )
+        .execute(&mut *conn)
+        .await
+        .map_err(|e| RepositoryError::DatabaseError(e.to_string()))?;
+    }
+
+    replace_reminders(conn, event.event_id(), event.reminders()).await?;
+    replace_participants(conn, event.event_id(), event.organizer().as_ref(), event.attendees()).await?;
+    replace_tags(conn, event.event_id(), event.tags()).await?;
+
+    Ok(*event.version() + 1)
+}
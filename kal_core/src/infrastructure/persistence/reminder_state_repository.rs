@@ -0,0 +1,136 @@
+use std::collections::{BTreeSet, HashSet};
+
+use async_trait::async_trait;
+use sqlx::SqlitePool;
+use crate::domain::{
+    reminder::{ReminderKey, ReminderState},
+    repository::{RepositoryError, ReminderStateRepository},
+};
+use super::{
+    handle::Handle,
+    models::ReminderStateModel,
+    mappers::ReminderStateMapper,
+};
+
+pub struct SqliteReminderStateRepository {
+    handle: Handle,
+}
+
+impl SqliteReminderStateRepository {
+    pub fn new(pool: SqlitePool) -> Self {
+        Self { handle: Handle::Pool(pool) }
+    }
+
+    pub(crate) fn with_handle(handle: Handle) -> Self {
+        Self { handle }
+    }
+}
+
+#[async_trait]
+impl ReminderStateRepository for SqliteReminderStateRepository {
+    async fn save(&self, state: &ReminderState) -> Result<(), RepositoryError> {
+        let mut conn = self.handle.acquire().await?;
+
+        let model = ReminderStateMapper::to_model(state);
+
+        sqlx::query!(
+            r#"
+                INSERT INTO reminder_states (
+                    event_id, original_starts_at, reminder_id,
+                    fired_count, last_fired_at, snoozed_until, dismissed_at
+                )
+                VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)
+                ON CONFLICT(event_id, original_starts_at, reminder_id) DO UPDATE SET
+                    fired_count = excluded.fired_count,
+                    last_fired_at = excluded.last_fired_at,
+                    snoozed_until = excluded.snoozed_until,
+                    dismissed_at = excluded.dismissed_at
+            "#,
+            model.event_id,
+            model.original_starts_at,
+            model.reminder_id,
+            model.fired_count,
+            model.last_fired_at,
+            model.snoozed_until,
+            model.dismissed_at,
+        )
+        .execute(&mut *conn)
+        .await
+        .map_err(|e| RepositoryError::DatabaseError(e.to_string()))?;
+
+        Ok(())
+    }
+
+    async fn find(&self, key: &ReminderKey) -> Result<Option<ReminderState>, RepositoryError> {
+        let mut conn = self.handle.acquire().await?;
+
+        let model = sqlx::query_as::<_, ReminderStateModel>(
+            r#"
+            SELECT event_id, original_starts_at, reminder_id,
+                   fired_count, last_fired_at, snoozed_until, dismissed_at
+            FROM reminder_states
+            WHERE event_id = ?1 AND original_starts_at = ?2 AND reminder_id = ?3
+            "#
+        )
+        .bind(key.event_id().to_string())
+        .bind(key.original_starts_at().to_rfc3339())
+        .bind(key.reminder_id().to_string())
+        .fetch_optional(&mut *conn)
+        .await
+        .map_err(|e| RepositoryError::DatabaseError(e.to_string()))?;
+
+        match model {
+            Some(m) => {
+                let state = ReminderStateMapper::to_domain(m)
+                    .map_err(|e| RepositoryError::DatabaseError(e.to_string()))?;
+                Ok(Some(state))
+            }
+            None => Ok(None),
+        }
+    }
+
+    async fn find_all(&self, keys: &[ReminderKey]) -> Result<Vec<ReminderState>, RepositoryError> {
+        if keys.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let mut conn = self.handle.acquire().await?;
+
+        // One query for every event involved; rows for other instances
+        // of those events are dropped below
+        let event_ids: BTreeSet<String> = keys.iter().map(|k| k.event_id().to_string()).collect();
+        let placeholders = vec!["?"; event_ids.len()].join(", ");
+        let sql = format!(
+            r#"
+            SELECT event_id, original_starts_at, reminder_id,
+                   fired_count, last_fired_at, snoozed_until, dismissed_at
+            FROM reminder_states
+            WHERE event_id IN ({placeholders})
+            "#
+        );
+
+        let mut query = sqlx::query_as::<_, ReminderStateModel>(&sql);
+        for event_id in &event_ids {
+            query = query.bind(event_id);
+        }
+
+        let models = query
+            .fetch_all(&mut *conn)
+            .await
+            .map_err(|e| RepositoryError::DatabaseError(e.to_string()))?;
+
+        let wanted: HashSet<&ReminderKey> = keys.iter().collect();
+        let mut states = Vec::new();
+
+        for model in models {
+            let state = ReminderStateMapper::to_domain(model)
+                .map_err(|e| RepositoryError::DatabaseError(e.to_string()))?;
+
+            if wanted.contains(state.key()) {
+                states.push(state);
+            }
+        }
+
+        Ok(states)
+    }
+}
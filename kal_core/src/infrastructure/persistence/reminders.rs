@@ -0,0 +1,77 @@
+use sqlx::{SqliteConnection, SqliteExecutor};
+
+use crate::domain::{reminder::Reminder, repository::RepositoryError, value_objects::EventId};
+
+use super::{mappers::ReminderMapper, models::ReminderModel};
+
+/// Loads the reminder rows of an event or recurring series, in the
+/// order they were added.
+pub(crate) async fn fetch_reminders<'e>(
+    executor: impl SqliteExecutor<'e>,
+    event_id: &str,
+) -> Result<Vec<ReminderModel>, RepositoryError> {
+    sqlx::query_as::<_, ReminderModel>(
+        r#"
+            SELECT event_id, id, position, trigger_offset, trigger_at,
+                   action, action_target, description, repeat_count,
+                   repeat_interval
+            FROM reminders
+            WHERE event_id = ?1
+            ORDER BY position
+        "#
+    )
+    .bind(event_id)
+    .fetch_all(executor)
+    .await
+    .map_err(|e| RepositoryError::DatabaseError(e.to_string()))
+}
+
+/// Replaces the stored reminders of an event or series. The caller owns
+/// the transaction.
+pub(crate) async fn replace_reminders(
+    conn: &mut SqliteConnection,
+    event_id: &EventId,
+    reminders: &[Reminder],
+) -> Result<(), RepositoryError> {
+    let id_str = event_id.to_string();
+
+    sqlx::query!(
+        r#"
+            DELETE FROM reminders WHERE event_id = ?1
+        "#,
+        id_str,
+    )
+    .execute(&mut *conn)
+    .await
+    .map_err(|e| RepositoryError::DatabaseError(e.to_string()))?;
+
+    for (position, reminder) in reminders.iter().enumerate() {
+        let model = ReminderMapper::to_model(reminder, event_id, position);
+
+        sqlx::query!(
+            r#"
+                INSERT INTO reminders (
+                    event_id, id, position, trigger_offset, trigger_at,
+                    action, action_target, description, repeat_count,
+                    repeat_interval
+                )
+                VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)
+            "#,
+            model.event_id,
+            model.id,
+            model.position,
+            model.trigger_offset,
+            model.trigger_at,
+            model.action,
+            model.action_target,
+            model.description,
+            model.repeat_count,
+            model.repeat_interval,
+        )
+        .execute(&mut *conn)
+        .await
+        .map_err(|e| RepositoryError::DatabaseError(e.to_string()))?;
+    }
+
+    Ok(())
+}
@@ -0,0 +1,92 @@
+use async_trait::async_trait;
+use sqlx::SqlitePool;
+
+use crate::domain::{
+    repository::{EventSearchRepository, RepositoryError},
+    search::{SearchHit, HIGHLIGHT_END, HIGHLIGHT_START},
+    value_objects::{CalendarId, TimeRange},
+};
+use super::{
+    handle::Handle,
+    fts_query,
+    mappers::SearchHitMapper,
+    models::SearchHitModel,
+};
+
+pub struct SqliteEventSearchRepository {
+    handle: Handle,
+}
+
+impl SqliteEventSearchRepository {
+    pub fn new(pool: SqlitePool) -> Self {
+        Self { handle: Handle::Pool(pool) }
+    }
+
+    pub(crate) fn with_handle(handle: Handle) -> Self {
+        Self { handle }
+    }
+}
+
+#[async_trait]
+impl EventSearchRepository for SqliteEventSearchRepository {
+    async fn search(
+        &self,
+        text: &str,
+        calendar_id: Option<&CalendarId>,
+        window: Option<&TimeRange>,
+    ) -> Result<Vec<SearchHit>, RepositoryError> {
+        let Some(query) = fts_query(text) else {
+            return Ok(Vec::new());
+        };
+
+        let mut conn = self.handle.acquire().await?;
+
+        // bm25 scores lower for better matches. Titles weigh most, then
+        // where the event is and who comes; the id column is not text.
+        let models = sqlx::query_as::<_, SearchHitModel>(
+            r#"
+                SELECT event_id, kind, calendar_id, title, starts_at, ends_at, snippet, score
+                FROM (
+                    SELECT e.id AS event_id, 'EVENT' AS kind, e.calendar_id, e.title,
+                           e.starts_at, e.ends_at,
+                           snippet(event_search, -1, ?5, ?6, '…', 12) AS snippet,
+                           bm25(event_search, 0.0, 4.0, 1.0, 2.0, 2.0) AS score
+                    FROM event_search
+                    JOIN events e ON e.id = event_search.event_id
+                    WHERE event_search MATCH ?1
+                      AND e.deleted_at IS NULL
+                      AND (?2 IS NULL OR e.calendar_id = ?2)
+                      AND (?3 IS NULL OR (e.starts_at < ?4 AND e.ends_at > ?3))
+
+                    UNION ALL
+
+                    SELECT r.id, 'SERIES', r.calendar_id, r.title,
+                           r.starts_at, r.ends_at,
+                           snippet(event_search, -1, ?5, ?6, '…', 12),
+                           bm25(event_search, 0.0, 4.0, 1.0, 2.0, 2.0)
+                    FROM event_search
+                    JOIN recurrences r ON r.id = event_search.event_id
+                    WHERE event_search MATCH ?1
+                      AND r.deleted_at IS NULL
+                      AND (?2 IS NULL OR r.calendar_id = ?2)
+                )
+                ORDER BY score, starts_at
+            "#
+        )
+        .bind(query)
+        .bind(calendar_id.map(|id| id.to_string()))
+        .bind(window.map(|w| w.starts_at().to_rfc3339()))
+        .bind(window.map(|w| w.ends_at().to_rfc3339()))
+        .bind(HIGHLIGHT_START)
+        .bind(HIGHLIGHT_END)
+        .fetch_all(&mut *conn)
+        .await
+        .map_err(|e| RepositoryError::DatabaseError(e.to_string()))?;
+
+        models
+            .into_iter()
+            .map(SearchHitMapper::to_domain)
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|e| RepositoryError::DatabaseError(e.to_string()))
+    }
+}
@@ -0,0 +1,140 @@
+use async_trait::async_trait;
+use sqlx::SqlitePool;
+use crate::{
+    domain::{
+        calendar::Calendar,
+        calendar_object::CalendarObject,
+        repository::{RepositoryError, SubscriptionRepository},
+    },
+    infrastructure::persistence::models::CalendarModel,
+};
+use super::{
+    event_repository::upsert_event,
+    mappers::CalendarMapper,
+    recurring_event_repository::upsert_recurring_event,
+};
+
+pub struct SqliteSubscriptionRepository {
+    pool: SqlitePool,
+}
+
+impl SqliteSubscriptionRepository {
+    pub fn new(pool: SqlitePool) -> Self {
+        Self { pool }
+    }
+}
+
+#[async_trait]
+impl SubscriptionRepository for SqliteSubscriptionRepository {
+    async fn find_all(&self) -> Result<Vec<Calendar>, RepositoryError> {
+        let models = sqlx::query_as::<_, CalendarModel>(
+            r#"
+            SELECT id, name, description, is_archived, subscription_url,
+                   refresh_interval, last_fetched_at, fetch_etag,
+                   fetch_last_modified, version, deleted_at, created_at, updated_at
+            FROM calendars
+            WHERE subscription_url IS NOT NULL AND deleted_at IS NULL
+            ORDER BY name
+            "#
+        )
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| RepositoryError::DatabaseError(e.to_string()))?;
+
+        models
+            .into_iter()
+            .map(|m| CalendarMapper::to_domain(m)
+                .map_err(|e| RepositoryError::DatabaseError(e.to_string())))
+            .collect()
+    }
+
+    /// Swaps the whole content of a subscribed calendar in one transaction,
+    /// so readers never see a half-refreshed feed.
+    async fn replace_contents(
+        &self,
+        calendar: &Calendar,
+        objects: &[CalendarObject],
+    ) -> Result<(), RepositoryError> {
+        let model = CalendarMapper::to_model(calendar);
+
+        let mut tx = self.pool.begin()
+            .await
+            .map_err(|e| RepositoryError::DatabaseError(e.to_string()))?;
+
+        let result = sqlx::query!(
+            r#"
+                UPDATE calendars SET
+                    last_fetched_at = ?2,
+                    fetch_etag = ?3,
+                    fetch_last_modified = ?5,
+                    updated_at = ?4,
+                    version = version + 1
+                WHERE id = ?1 AND subscription_url IS NOT NULL AND deleted_at IS NULL
+            "#,
+            model.id,
+            model.last_fetched_at,
+            model.fetch_etag,
+            model.updated_at,
+            model.fetch_last_modified,
+        )
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| RepositoryError::DatabaseError(e.to_string()))?;
+
+        if result.rows_affected() == 0 {
+            return Err(RepositoryError::NotFound);
+        }
+
+        sqlx::query!(
+            r#"
+                DELETE FROM recurrence_exceptions
+                WHERE recurrence_id IN (
+                    SELECT id FROM recurrences WHERE calendar_id = ?1
+                )
+            "#,
+            model.id,
+        )
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| RepositoryError::DatabaseError(e.to_string()))?;
+
+        sqlx::query!(
+            r#"
+                DELETE FROM recurrences WHERE calendar_id = ?1
+            "#,
+            model.id,
+        )
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| RepositoryError::DatabaseError(e.to_string()))?;
+
+        sqlx::query!(
+            r#"
+                DELETE FROM events WHERE calendar_id = ?1
+            "#,
+            model.id,
+        )
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| RepositoryError::DatabaseError(e.to_string()))?;
+
+        for object in objects {
+            if object.calendar_id() != calendar.calendar_id() {
+                return Err(RepositoryError::ConstraintViolation(
+                    "object belongs to another calendar".into(),
+                ));
+            }
+
+            match object {
+                CalendarObject::Event(event) => upsert_event(&mut tx, event).await?,
+                CalendarObject::Recurring(event) => upsert_recurring_event(&mut tx, event).await?,
+            };
+        }
+
+        tx.commit()
+            .await
+            .map_err(|e| RepositoryError::DatabaseError(e.to_string()))?;
+
+        Ok(())
+    }
+}
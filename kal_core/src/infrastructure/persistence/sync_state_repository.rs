@@ -0,0 +1,318 @@
+use async_trait::async_trait;
+use sqlx::SqlitePool;
+use crate::domain::{
+    repository::{RepositoryError, SyncStateRepository},
+    sync::{SyncCollection, SyncItem, Tombstone},
+    value_objects::{CalendarId, EventId},
+};
+use super::{
+    models::{SyncCollectionModel, SyncItemModel, TombstoneModel},
+    mappers::SyncStateMapper,
+};
+
+pub struct SqliteSyncStateRepository {
+    pool: SqlitePool,
+}
+
+impl SqliteSyncStateRepository {
+    pub fn new(pool: SqlitePool) -> Self {
+        Self { pool }
+    }
+}
+
+#[async_trait]
+impl SyncStateRepository for SqliteSyncStateRepository {
+    async fn save_collection(
+        &self,
+        collection: &SyncCollection
+    ) -> Result<(), RepositoryError> {
+        let mut tx = self.pool.begin()
+            .await
+            .map_err(|e| RepositoryError::DatabaseError(e.to_string()))?;
+
+        let model = SyncStateMapper::collection_to_model(collection);
+
+        sqlx::query!(
+            r#"
+                INSERT INTO sync_collections (
+                    calendar_id, remote_href, sync_token, last_synced_at
+                )
+                VALUES (?1, ?2, ?3, ?4)
+                ON CONFLICT(calendar_id) DO UPDATE SET
+                    remote_href = excluded.remote_href,
+                    sync_token = excluded.sync_token,
+                    last_synced_at = excluded.last_synced_at
+            "#,
+            model.calendar_id,
+            model.remote_href,
+            model.sync_token,
+            model.last_synced_at,
+        )
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| RepositoryError::DatabaseError(e.to_string()))?;
+
+        // Items written before the calendar was linked are enrolled as dirty
+        sqlx::query!(
+            r#"
+                INSERT OR IGNORE INTO sync_items (item_id, calendar_id, kind, is_dirty)
+                SELECT id, calendar_id, 'EVENT', 1 FROM events
+                WHERE calendar_id = ?1 AND deleted_at IS NULL
+                UNION ALL
+                SELECT id, calendar_id, 'RECURRENCE', 1 FROM recurrences
+                WHERE calendar_id = ?1 AND deleted_at IS NULL
+            "#,
+            model.calendar_id,
+        )
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| RepositoryError::DatabaseError(e.to_string()))?;
+
+        tx.commit()
+            .await
+            .map_err(|e| RepositoryError::DatabaseError(e.to_string()))?;
+
+        Ok(())
+    }
+
+    async fn find_collection(
+        &self,
+        calendar_id: &CalendarId
+    ) -> Result<Option<SyncCollection>, RepositoryError> {
+        let model = sqlx::query_as::<_, SyncCollectionModel>(
+            r#"
+            SELECT calendar_id, remote_href, sync_token, last_synced_at
+            FROM sync_collections
+            WHERE calendar_id = ?1
+            "#
+        )
+        .bind(calendar_id.to_string())
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e| RepositoryError::DatabaseError(e.to_string()))?;
+
+        match model {
+            Some(m) => {
+                let collection = SyncStateMapper::collection_to_domain(m)
+                    .map_err(|e| RepositoryError::DatabaseError(e.to_string()))?;
+                Ok(Some(collection))
+            }
+            None => Ok(None),
+        }
+    }
+
+    async fn find_all_collections(&self) -> Result<Vec<SyncCollection>, RepositoryError> {
+        let models = sqlx::query_as::<_, SyncCollectionModel>(
+            r#"
+            SELECT calendar_id, remote_href, sync_token, last_synced_at
+            FROM sync_collections
+            ORDER BY remote_href
+            "#
+        )
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| RepositoryError::DatabaseError(e.to_string()))?;
+
+        models
+            .into_iter()
+            .map(|m| SyncStateMapper::collection_to_domain(m)
+                .map_err(|e| RepositoryError::DatabaseError(e.to_string())))
+            .collect()
+    }
+
+    async fn delete_collection(
+        &self,
+        calendar_id: &CalendarId
+    ) -> Result<(), RepositoryError> {
+        let id_str = calendar_id.to_string();
+
+        let result = sqlx::query!(
+            r#"
+                DELETE FROM sync_collections WHERE calendar_id = ?1
+            "#,
+            id_str,
+        )
+        .execute(&self.pool)
+        .await
+        .map_err(|e| RepositoryError::DatabaseError(e.to_string()))?;
+
+        if result.rows_affected() == 0 {
+            return Err(RepositoryError::NotFound);
+        }
+
+        Ok(())
+    }
+
+    async fn save_item(&self, item: &SyncItem) -> Result<(), RepositoryError> {
+        let model = SyncStateMapper::item_to_model(item);
+
+        sqlx::query!(
+            r#"
+                INSERT INTO sync_items (
+                    item_id, calendar_id, kind, remote_href, remote_uid,
+                    etag, last_synced_hash, is_dirty, last_synced_at
+                )
+                VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)
+                ON CONFLICT(item_id) DO UPDATE SET
+                    calendar_id = excluded.calendar_id,
+                    kind = excluded.kind,
+                    remote_href = excluded.remote_href,
+                    remote_uid = excluded.remote_uid,
+                    etag = excluded.etag,
+                    last_synced_hash = excluded.last_synced_hash,
+                    is_dirty = excluded.is_dirty,
+                    last_synced_at = excluded.last_synced_at
+            "#,
+            model.item_id,
+            model.calendar_id,
+            model.kind,
+            model.remote_href,
+            model.remote_uid,
+            model.etag,
+            model.last_synced_hash,
+            model.is_dirty,
+            model.last_synced_at,
+        )
+        .execute(&self.pool)
+        .await
+        .map_err(|e| RepositoryError::DatabaseError(e.to_string()))?;
+
+        Ok(())
+    }
+
+    async fn find_item(
+        &self,
+        item_id: &EventId
+    ) -> Result<Option<SyncItem>, RepositoryError> {
+        let model = sqlx::query_as::<_, SyncItemModel>(
+            r#"
+            SELECT item_id, calendar_id, kind, remote_href, remote_uid,
+                   etag, last_synced_hash, is_dirty, last_synced_at
+            FROM sync_items
+            WHERE item_id = ?1
+            "#
+        )
+        .bind(item_id.to_string())
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e| RepositoryError::DatabaseError(e.to_string()))?;
+
+        match model {
+            Some(m) => {
+                let item = SyncStateMapper::item_to_domain(m)
+                    .map_err(|e| RepositoryError::DatabaseError(e.to_string()))?;
+                Ok(Some(item))
+            }
+            None => Ok(None),
+        }
+    }
+
+    async fn find_item_by_href(
+        &self,
+        calendar_id: &CalendarId,
+        href: &str,
+    ) -> Result<Option<SyncItem>, RepositoryError> {
+        let model = sqlx::query_as::<_, SyncItemModel>(
+            r#"
+            SELECT item_id, calendar_id, kind, remote_href, remote_uid,
+                   etag, last_synced_hash, is_dirty, last_synced_at
+            FROM sync_items
+            WHERE calendar_id = ?1 AND remote_href = ?2
+            "#
+        )
+        .bind(calendar_id.to_string())
+        .bind(href)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e| RepositoryError::DatabaseError(e.to_string()))?;
+
+        match model {
+            Some(m) => {
+                let item = SyncStateMapper::item_to_domain(m)
+                    .map_err(|e| RepositoryError::DatabaseError(e.to_string()))?;
+                Ok(Some(item))
+            }
+            None => Ok(None),
+        }
+    }
+
+    async fn find_items(
+        &self,
+        calendar_id: &CalendarId
+    ) -> Result<Vec<SyncItem>, RepositoryError> {
+        let models = sqlx::query_as::<_, SyncItemModel>(
+            r#"
+            SELECT item_id, calendar_id, kind, remote_href, remote_uid,
+                   etag, last_synced_hash, is_dirty, last_synced_at
+            FROM sync_items
+            WHERE calendar_id = ?1
+            "#
+        )
+        .bind(calendar_id.to_string())
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| RepositoryError::DatabaseError(e.to_string()))?;
+
+        models
+            .into_iter()
+            .map(|m| SyncStateMapper::item_to_domain(m)
+                .map_err(|e| RepositoryError::DatabaseError(e.to_string())))
+            .collect()
+    }
+
+    async fn delete_item(&self, item_id: &EventId) -> Result<(), RepositoryError> {
+        let id_str = item_id.to_string();
+
+        sqlx::query!(
+            r#"
+                DELETE FROM sync_items WHERE item_id = ?1
+            "#,
+            id_str,
+        )
+        .execute(&self.pool)
+        .await
+        .map_err(|e| RepositoryError::DatabaseError(e.to_string()))?;
+
+        Ok(())
+    }
+
+    async fn find_tombstones(
+        &self,
+        calendar_id: &CalendarId
+    ) -> Result<Vec<Tombstone>, RepositoryError> {
+        let models = sqlx::query_as::<_, TombstoneModel>(
+            r#"
+            SELECT item_id, calendar_id, kind, remote_href, etag, deleted_at
+            FROM sync_tombstones
+            WHERE calendar_id = ?1
+            ORDER BY deleted_at
+            "#
+        )
+        .bind(calendar_id.to_string())
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| RepositoryError::DatabaseError(e.to_string()))?;
+
+        models
+            .into_iter()
+            .map(|m| SyncStateMapper::tombstone_to_domain(m)
+                .map_err(|e| RepositoryError::DatabaseError(e.to_string())))
+            .collect()
+    }
+
+    async fn delete_tombstone(&self, item_id: &EventId) -> Result<(), RepositoryError> {
+        let id_str = item_id.to_string();
+
+        sqlx::query!(
+            r#"
+                DELETE FROM sync_tombstones WHERE item_id = ?1
+            "#,
+            id_str,
+        )
+        .execute(&self.pool)
+        .await
+        .map_err(|e| RepositoryError::DatabaseError(e.to_string()))?;
+
+        Ok(())
+    }
+}
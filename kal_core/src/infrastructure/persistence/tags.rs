@@ -0,0 +1,186 @@
+use std::collections::BTreeSet;
+
+use async_trait::async_trait;
+use sqlx::{SqliteConnection, SqliteExecutor, SqlitePool};
+
+use crate::domain::{
+    repository::{RepositoryError, TagRepository},
+    tag::{Tag, TagUsage},
+    value_objects::EventId,
+};
+
+use super::handle::Handle;
+
+pub struct SqliteTagRepository {
+    handle: Handle,
+}
+
+impl SqliteTagRepository {
+    pub fn new(pool: SqlitePool) -> Self {
+        Self { handle: Handle::Pool(pool) }
+    }
+
+    pub(crate) fn with_handle(handle: Handle) -> Self {
+        Self { handle }
+    }
+}
+
+#[async_trait]
+impl TagRepository for SqliteTagRepository {
+    async fn find_all(&self) -> Result<Vec<TagUsage>, RepositoryError> {
+        let mut conn = self.handle.acquire().await?;
+
+        let rows = sqlx::query_as::<_, (String, i64)>(
+            r#"
+                SELECT t.name, COUNT(et.event_id)
+                FROM tags t
+                JOIN event_tags et ON et.tag_id = t.id
+                WHERE et.event_id NOT IN (SELECT id FROM trashed_objects)
+                GROUP BY t.id
+                ORDER BY t.name
+            "#
+        )
+        .fetch_all(&mut *conn)
+        .await
+        .map_err(|e| RepositoryError::DatabaseError(e.to_string()))?;
+
+        rows.into_iter()
+            .map(|(name, count)| Ok(TagUsage::new(to_tag(name)?, count as u32)))
+            .collect()
+    }
+
+    async fn find(&self, tag: &Tag) -> Result<Option<TagUsage>, RepositoryError> {
+        let mut conn = self.handle.acquire().await?;
+
+        let count = sqlx::query_scalar::<_, i64>(
+            r#"
+                SELECT COUNT(et.event_id)
+                FROM tags t
+                JOIN event_tags et ON et.tag_id = t.id
+                WHERE t.name = ?1
+                  AND et.event_id NOT IN (SELECT id FROM trashed_objects)
+            "#
+        )
+        .bind(tag.as_str())
+        .fetch_one(&mut *conn)
+        .await
+        .map_err(|e| RepositoryError::DatabaseError(e.to_string()))?;
+
+        Ok((count > 0).then(|| TagUsage::new(tag.clone(), count as u32)))
+    }
+}
+
+/// Loads the tags of an event or recurring series.
+pub(crate) async fn fetch_tags<'e>(
+    executor: impl SqliteExecutor<'e>,
+    event_id: &str,
+) -> Result<Vec<Tag>, RepositoryError> {
+    fetch_tag_names(executor, event_id)
+        .await?
+        .into_iter()
+        .map(to_tag)
+        .collect()
+}
+
+/// Ids of the events and series carrying `tag`, whichever table they
+/// live in.
+pub(crate) async fn find_tagged_ids<'e>(
+    executor: impl SqliteExecutor<'e>,
+    tag: &Tag,
+) -> Result<Vec<EventId>, RepositoryError> {
+    let ids = sqlx::query_scalar::<_, String>(
+        r#"
+            SELECT et.event_id
+            FROM event_tags et
+            JOIN tags t ON t.id = et.tag_id
+            WHERE t.name = ?1
+        "#
+    )
+    .bind(tag.as_str())
+    .fetch_all(executor)
+    .await
+    .map_err(|e| RepositoryError::DatabaseError(e.to_string()))?;
+
+    ids.into_iter()
+        .map(|id| id.parse::<EventId>().map_err(|e| RepositoryError::DatabaseError(e.to_string())))
+        .collect()
+}
+
+/// Brings the stored tags of an event or series in line with `tags`,
+/// touching only the links that changed. The caller owns the
+/// transaction.
+pub(crate) async fn replace_tags(
+    conn: &mut SqliteConnection,
+    event_id: &EventId,
+    tags: &BTreeSet<Tag>,
+) -> Result<(), RepositoryError> {
+    let id_str = event_id.to_string();
+    let current: BTreeSet<String> = fetch_tag_names(&mut *conn, &id_str).await?.into_iter().collect();
+
+    for name in current.iter().filter(|name| !tags.iter().any(|t| t.as_str() == *name)) {
+        sqlx::query!(
+            r#"
+                DELETE FROM event_tags
+                WHERE event_id = ?1
+                  AND tag_id = (SELECT id FROM tags WHERE name = ?2)
+            "#,
+            id_str,
+            name,
+        )
+        .execute(&mut *conn)
+        .await
+        .map_err(|e| RepositoryError::DatabaseError(e.to_string()))?;
+    }
+
+    for tag in tags.iter().filter(|t| !current.contains(t.as_str())) {
+        let name = tag.as_str();
+
+        sqlx::query!(
+            r#"
+                INSERT INTO tags (name) VALUES (?1)
+                ON CONFLICT(name) DO NOTHING
+            "#,
+            name,
+        )
+        .execute(&mut *conn)
+        .await
+        .map_err(|e| RepositoryError::DatabaseError(e.to_string()))?;
+
+        sqlx::query!(
+            r#"
+                INSERT INTO event_tags (event_id, tag_id)
+                SELECT ?1, id FROM tags WHERE name = ?2
+            "#,
+            id_str,
+            name,
+        )
+        .execute(&mut *conn)
+        .await
+        .map_err(|e| RepositoryError::DatabaseError(e.to_string()))?;
+    }
+
+    Ok(())
+}
+
+async fn fetch_tag_names<'e>(
+    executor: impl SqliteExecutor<'e>,
+    event_id: &str,
+) -> Result<Vec<String>, RepositoryError> {
+    sqlx::query_scalar::<_, String>(
+        r#"
+            SELECT t.name
+            FROM event_tags et
+            JOIN tags t ON t.id = et.tag_id
+            WHERE et.event_id = ?1
+            ORDER BY t.name
+        "#
+    )
+    .bind(event_id)
+    .fetch_all(executor)
+    .await
+    .map_err(|e| RepositoryError::DatabaseError(e.to_string()))
+}
+
+fn to_tag(name: String) -> Result<Tag, RepositoryError> {
+    Tag::new(name).map_err(|e| RepositoryError::DatabaseError(e.to_string()))
+}
@@ -0,0 +1,159 @@
+use async_trait::async_trait;
+use sqlx::SqlitePool;
+use crate::domain::{
+    repository::{RepositoryError, TaskRepository},
+    task::Task,
+    value_objects::{CalendarId, TaskId},
+};
+use super::{
+    handle::Handle,
+    models::TaskModel,
+    mappers::TaskMapper,
+};
+
+/// Tasks have no trash of their own; they are hidden while their
+/// calendar is in the trash and come back when it is restored.
+pub struct SqliteTaskRepository {
+    handle: Handle,
+}
+
+impl SqliteTaskRepository {
+    pub fn new(pool: SqlitePool) -> Self {
+        Self { handle: Handle::Pool(pool) }
+    }
+
+    pub(crate) fn with_handle(handle: Handle) -> Self {
+        Self { handle }
+    }
+}
+
+#[async_trait]
+impl TaskRepository for SqliteTaskRepository {
+    async fn save(&self, task: &Task) -> Result<(), RepositoryError> {
+        let mut conn = self.handle.acquire().await?;
+
+        let model = TaskMapper::to_model(task);
+
+        sqlx::query!(
+            r#"
+                INSERT INTO tasks (
+                    id, calendar_id, title, description, starts_at, due_at,
+                    priority, percent_complete, status, completed_at,
+                    frequency, interval, until, created_at, updated_at
+                )
+                VALUES (
+                    ?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10,
+                    ?11, ?12, ?13, ?14, ?15
+                )
+                ON CONFLICT(id) DO UPDATE SET
+                    title = excluded.title,
+                    description = excluded.description,
+                    starts_at = excluded.starts_at,
+                    due_at = excluded.due_at,
+                    priority = excluded.priority,
+                    percent_complete = excluded.percent_complete,
+                    status = excluded.status,
+                    completed_at = excluded.completed_at,
+                    frequency = excluded.frequency,
+                    interval = excluded.interval,
+                    until = excluded.until,
+                    updated_at = excluded.updated_at
+            "#,
+            model.id,
+            model.calendar_id,
+            model.title,
+            model.description,
+            model.starts_at,
+            model.due_at,
+            model.priority,
+            model.percent_complete,
+            model.status,
+            model.completed_at,
+            model.frequency,
+            model.interval,
+            model.until,
+            model.created_at,
+            model.updated_at,
+        )
+        .execute(&mut *conn)
+        .await
+        .map_err(|e| RepositoryError::DatabaseError(e.to_string()))?;
+
+        Ok(())
+    }
+
+    async fn find_by_id(&self, id: &TaskId) -> Result<Option<Task>, RepositoryError> {
+        let mut conn = self.handle.acquire().await?;
+
+        let model = sqlx::query_as::<_, TaskModel>(
+            r#"
+                SELECT id, calendar_id, title, description, starts_at, due_at,
+                       priority, percent_complete, status, completed_at,
+                       frequency, interval, until, created_at, updated_at
+                FROM tasks
+                WHERE id = ?1
+                  AND calendar_id IN (SELECT id FROM calendars WHERE deleted_at IS NULL)
+            "#
+        )
+        .bind(id.to_string())
+        .fetch_optional(&mut *conn)
+        .await
+        .map_err(|e| RepositoryError::DatabaseError(e.to_string()))?;
+
+        model
+            .map(TaskMapper::to_domain)
+            .transpose()
+            .map_err(|e| RepositoryError::DatabaseError(e.to_string()))
+    }
+
+    async fn find_by_calendar(
+        &self,
+        calendar_id: &CalendarId,
+    ) -> Result<Vec<Task>, RepositoryError> {
+        let mut conn = self.handle.acquire().await?;
+
+        let models = sqlx::query_as::<_, TaskModel>(
+            r#"
+                SELECT id, calendar_id, title, description, starts_at, due_at,
+                       priority, percent_complete, status, completed_at,
+                       frequency, interval, until, created_at, updated_at
+                FROM tasks
+                WHERE calendar_id = ?1
+                  AND calendar_id IN (SELECT id FROM calendars WHERE deleted_at IS NULL)
+                ORDER BY due_at IS NULL, due_at, created_at
+            "#
+        )
+        .bind(calendar_id.to_string())
+        .fetch_all(&mut *conn)
+        .await
+        .map_err(|e| RepositoryError::DatabaseError(e.to_string()))?;
+
+        models
+            .into_iter()
+            .map(TaskMapper::to_domain)
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|e| RepositoryError::DatabaseError(e.to_string()))
+    }
+
+    async fn delete(&self, id: &TaskId) -> Result<(), RepositoryError> {
+        let mut conn = self.handle.acquire().await?;
+
+        let id_str = id.to_string();
+
+        let result = sqlx::query!(
+            r#"
+                DELETE FROM tasks WHERE id = ?1
+            "#,
+            id_str,
+        )
+        .execute(&mut *conn)
+        .await
+        .map_err(|e| RepositoryError::DatabaseError(e.to_string()))?;
+
+        if result.rows_affected() == 0 {
+            return Err(RepositoryError::NotFound);
+        }
+
+        Ok(())
+    }
+}
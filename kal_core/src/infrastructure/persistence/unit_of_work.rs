@@ -0,0 +1,118 @@
+use std::sync::Arc;
+
+use sqlx::SqlitePool;
+use tokio::sync::Mutex;
+
+use crate::domain::repository::RepositoryError;
+
+use super::{
+    handle::{Handle, SharedTransaction},
+    SqliteAuditRepository, SqliteCalendarRepository, SqliteEventRepository,
+    SqliteEventSearchRepository, SqliteHistoryRepository, SqliteJournalRepository,
+    SqliteRecurringEventRepository, SqliteReminderStateRepository, SqliteTagRepository,
+    SqliteTaskRepository,
+};
+
+/// Repositories bound to one SQLite transaction, so a command that
+/// writes several aggregates either lands completely or not at all.
+/// Their writes are only visible to each other until `commit`; dropping
+/// the unit of work without committing rolls it back.
+pub struct SqliteUnitOfWork {
+    tx: SharedTransaction,
+}
+
+impl SqliteUnitOfWork {
+    /// Takes the write lock up front, so a concurrent unit waits out the
+    /// busy timeout rather than failing when its first write needs it.
+    pub async fn begin(pool: &SqlitePool) -> Result<Self, RepositoryError> {
+        let tx = pool
+            .begin_with("BEGIN IMMEDIATE")
+            .await
+            .map_err(|e| RepositoryError::DatabaseError(e.to_string()))?;
+
+        Ok(Self { tx: Arc::new(Mutex::new(Some(tx))) })
+    }
+
+    /// Defers locking until the first statement, for work that only
+    /// reads: it sees one consistent snapshot without queueing behind
+    /// writers for the write lock.
+    pub async fn begin_read(pool: &SqlitePool) -> Result<Self, RepositoryError> {
+        let tx = pool
+            .begin()
+            .await
+            .map_err(|e| RepositoryError::DatabaseError(e.to_string()))?;
+
+        Ok(Self { tx: Arc::new(Mutex::new(Some(tx))) })
+    }
+
+    pub(crate) fn handle(&self) -> Handle {
+        Handle::Transaction(self.tx.clone())
+    }
+
+    pub fn calendars(&self) -> SqliteCalendarRepository {
+        SqliteCalendarRepository::with_handle(self.handle())
+    }
+
+    pub fn events(&self) -> SqliteEventRepository {
+        SqliteEventRepository::with_handle(self.handle())
+    }
+
+    pub fn recurring(&self) -> SqliteRecurringEventRepository {
+        SqliteRecurringEventRepository::with_handle(self.handle())
+    }
+
+    pub fn tasks(&self) -> SqliteTaskRepository {
+        SqliteTaskRepository::with_handle(self.handle())
+    }
+
+    pub fn journal(&self) -> SqliteJournalRepository {
+        SqliteJournalRepository::with_handle(self.handle())
+    }
+
+    pub fn tags(&self) -> SqliteTagRepository {
+        SqliteTagRepository::with_handle(self.handle())
+    }
+
+    pub fn search(&self) -> SqliteEventSearchRepository {
+        SqliteEventSearchRepository::with_handle(self.handle())
+    }
+
+    pub fn reminder_states(&self) -> SqliteReminderStateRepository {
+        SqliteReminderStateRepository::with_handle(self.handle())
+    }
+
+    pub fn history(&self) -> SqliteHistoryRepository {
+        SqliteHistoryRepository::with_handle(self.handle())
+    }
+
+    pub fn audit(&self) -> SqliteAuditRepository {
+        SqliteAuditRepository::with_handle(self.handle())
+    }
+
+    /// Makes every write through this unit's repositories permanent.
+    /// Repositories still held afterwards fail on use.
+    pub async fn commit(self) -> Result<(), RepositoryError> {
+        self.finish()
+            .await?
+            .commit()
+            .await
+            .map_err(|e| RepositoryError::DatabaseError(e.to_string()))
+    }
+
+    /// Discards every write through this unit's repositories.
+    pub async fn rollback(self) -> Result<(), RepositoryError> {
+        self.finish()
+            .await?
+            .rollback()
+            .await
+            .map_err(|e| RepositoryError::DatabaseError(e.to_string()))
+    }
+
+    async fn finish(self) -> Result<sqlx::Transaction<'static, sqlx::Sqlite>, RepositoryError> {
+        self.tx
+            .lock()
+            .await
+            .take()
+            .ok_or_else(|| RepositoryError::DatabaseError("unit of work already finished".into()))
+    }
+}
@@ -0,0 +1,67 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use chrono::{DateTime, Duration, Utc};
+use tokio::sync::watch;
+
+/// Where the scheduler gets the time from and how it waits for it.
+#[async_trait]
+pub trait Clock: Send + Sync {
+    fn now(&self) -> DateTime<Utc>;
+    async fn sleep_until(&self, deadline: DateTime<Utc>);
+}
+
+/// Wall-clock time, waiting on tokio timers.
+pub struct SystemClock;
+
+#[async_trait]
+impl Clock for SystemClock {
+    fn now(&self) -> DateTime<Utc> {
+        Utc::now()
+    }
+
+    async fn sleep_until(&self, deadline: DateTime<Utc>) {
+        let remaining = (deadline - Utc::now()).to_std().unwrap_or_default();
+        tokio::time::sleep(remaining).await;
+    }
+}
+
+/// A clock that only moves when told to. Sleepers wake as soon as the
+/// time is set at or past their deadline.
+#[derive(Clone)]
+pub struct MockClock {
+    now: Arc<watch::Sender<DateTime<Utc>>>,
+}
+
+impl MockClock {
+    pub fn new(start: DateTime<Utc>) -> Self {
+        let (now, _) = watch::channel(start);
+        Self { now: Arc::new(now) }
+    }
+
+    pub fn set(&self, at: DateTime<Utc>) {
+        self.now.send_replace(at);
+    }
+
+    pub fn advance(&self, by: Duration) {
+        self.now.send_modify(|now| *now += by);
+    }
+
+    /// How many tasks are currently waiting in `sleep_until`.
+    pub fn sleepers(&self) -> usize {
+        self.now.receiver_count()
+    }
+}
+
+#[async_trait]
+impl Clock for MockClock {
+    fn now(&self) -> DateTime<Utc> {
+        *self.now.borrow()
+    }
+
+    async fn sleep_until(&self, deadline: DateTime<Utc>) {
+        let mut now = self.now.subscribe();
+        // The sender lives as long as `self`, so this only returns once due
+        let _ = now.wait_for(|now| *now >= deadline).await;
+    }
+}
@@ -0,0 +1,18 @@
+use thiserror::Error;
+
+use crate::domain::{error::DomainError, repository::RepositoryError};
+
+#[derive(Debug, Error)]
+pub enum ReminderError {
+    #[error("Notification failed: {0}")]
+    Notify(String),
+
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+
+    #[error(transparent)]
+    Domain(#[from] DomainError),
+
+    #[error(transparent)]
+    Repository(#[from] RepositoryError),
+}
@@ -0,0 +1,9 @@
+pub mod clock;
+pub mod error;
+pub mod notifier;
+pub mod scheduler;
+
+pub use clock::{Clock, MockClock, SystemClock};
+pub use error::ReminderError;
+pub use notifier::{CommandNotifier, FileNotifier, Notification, Notifier, StdoutNotifier};
+pub use scheduler::{Delivery, ReminderScheduler};
@@ -0,0 +1,146 @@
+use std::path::PathBuf;
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use getset::Getters;
+use tokio::{fs::OpenOptions, io::AsyncWriteExt, process::Command};
+
+use crate::domain::reminder::{ReminderAction, ReminderInstance};
+
+use super::error::ReminderError;
+
+/// A reminder instance that has come due.
+#[derive(Debug, Clone, Getters)]
+pub struct Notification {
+    #[getset(get = "pub")]
+    instance: ReminderInstance,
+    #[getset(get = "pub")]
+    title: String,
+    #[getset(get = "pub")]
+    fired_at: DateTime<Utc>,
+}
+
+impl Notification {
+    pub fn new(instance: ReminderInstance, title: String, fired_at: DateTime<Utc>) -> Self {
+        Self {
+            instance,
+            title,
+            fired_at,
+        }
+    }
+
+    /// The reminder's description, or the event title if it has none.
+    pub fn message(&self) -> &str {
+        self.instance
+            .reminder()
+            .description()
+            .as_deref()
+            .unwrap_or(&self.title)
+    }
+
+    /// One line summary, as written by the stdout and file notifiers.
+    pub fn line(&self) -> String {
+        let action = self.instance.reminder().action();
+        let target = action
+            .target()
+            .map(|target| format!(" ({target})"))
+            .unwrap_or_default();
+
+        format!(
+            "{} {}{} {} [{}]",
+            self.fired_at.to_rfc3339(),
+            action.kind(),
+            target,
+            self.message(),
+            self.instance.starts_at().to_rfc3339(),
+        )
+    }
+}
+
+#[async_trait]
+pub trait Notifier: Send + Sync {
+    async fn notify(&self, notification: &Notification) -> Result<(), ReminderError>;
+}
+
+pub struct StdoutNotifier;
+
+#[async_trait]
+impl Notifier for StdoutNotifier {
+    async fn notify(&self, notification: &Notification) -> Result<(), ReminderError> {
+        println!("{}", notification.line());
+        Ok(())
+    }
+}
+
+/// Appends one line per notification to a file.
+pub struct FileNotifier {
+    path: PathBuf,
+}
+
+impl FileNotifier {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self { path: path.into() }
+    }
+}
+
+#[async_trait]
+impl Notifier for FileNotifier {
+    async fn notify(&self, notification: &Notification) -> Result<(), ReminderError> {
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)
+            .await?;
+
+        file.write_all(format!("{}\n", notification.line()).as_bytes())
+            .await?;
+
+        Ok(())
+    }
+}
+
+/// Runs a shell command per notification, describing it in `KAL_*`
+/// environment variables. Reminders with a command action run their own
+/// command instead.
+pub struct CommandNotifier {
+    command: String,
+}
+
+impl CommandNotifier {
+    pub fn new(command: String) -> Self {
+        Self { command }
+    }
+}
+
+#[async_trait]
+impl Notifier for CommandNotifier {
+    async fn notify(&self, notification: &Notification) -> Result<(), ReminderError> {
+        let instance = notification.instance();
+        let action = instance.reminder().action();
+
+        let command = match action {
+            ReminderAction::Command { command } => command,
+            _ => &self.command,
+        };
+
+        let status = Command::new("sh")
+            .arg("-c")
+            .arg(command)
+            .env("KAL_EVENT_ID", instance.event_id().to_string())
+            .env("KAL_REMINDER_ID", instance.reminder().reminder_id().to_string())
+            .env("KAL_TITLE", notification.title())
+            .env("KAL_MESSAGE", notification.message())
+            .env("KAL_STARTS_AT", instance.starts_at().to_rfc3339())
+            .env("KAL_FIRED_AT", notification.fired_at().to_rfc3339())
+            .env("KAL_ACTION", action.kind())
+            .env("KAL_TARGET", action.target().unwrap_or_default())
+            .status()
+            .await?;
+
+        if !status.success() {
+            return Err(ReminderError::Notify(format!("`{command}` {status}")));
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,224 @@
+use std::{
+    collections::{HashMap, HashSet},
+    sync::Arc,
+};
+
+use chrono::{DateTime, Duration, Utc};
+use getset::Getters;
+
+use crate::domain::{
+    reminder::{ReminderInstance, ReminderKey, ReminderState},
+    repository::{
+        CalendarRepository,
+        EventRepository,
+        RecurringEventRepository,
+        ReminderStateRepository,
+    },
+    value_objects::{CalendarId, TimeRange},
+};
+
+use super::{
+    clock::Clock,
+    error::ReminderError,
+    notifier::{Notification, Notifier},
+};
+
+/// One notification and how handing it to the notifier went.
+#[derive(Debug, Getters)]
+pub struct Delivery {
+    #[getset(get = "pub")]
+    notification: Notification,
+    #[getset(get = "pub")]
+    result: Result<(), ReminderError>,
+}
+
+/// A reminder instance that still has a firing ahead of it.
+struct Pending {
+    instance: ReminderInstance,
+    title: String,
+    state: ReminderState,
+    next_fire_at: DateTime<Utc>,
+}
+
+pub struct ReminderScheduler<C, E, R, S>
+where
+    C: CalendarRepository,
+    E: EventRepository,
+    R: RecurringEventRepository,
+    S: ReminderStateRepository,
+{
+    calendars: C,
+    events: E,
+    recurring: R,
+    states: S,
+    clock: Arc<dyn Clock>,
+    notifier: Box<dyn Notifier>,
+    lookback: Duration,
+    rescan_interval: Duration,
+}
+
+impl<C, E, R, S> ReminderScheduler<C, E, R, S>
+where
+    C: CalendarRepository,
+    E: EventRepository,
+    R: RecurringEventRepository,
+    S: ReminderStateRepository,
+{
+    pub fn new(
+        calendars: C,
+        events: E,
+        recurring: R,
+        states: S,
+        clock: Arc<dyn Clock>,
+        notifier: Box<dyn Notifier>,
+    ) -> Self {
+        Self {
+            calendars,
+            events,
+            recurring,
+            states,
+            clock,
+            notifier,
+            lookback: Duration::days(1),
+            rescan_interval: Duration::minutes(5),
+        }
+    }
+
+    /// How long after its first firing a missed reminder is still
+    /// delivered. Older ones are dropped.
+    pub fn with_lookback(mut self, lookback: Duration) -> Self {
+        self.lookback = lookback;
+        self
+    }
+
+    /// The longest the scheduler sleeps before looking for reminders
+    /// that were added or changed in the meantime.
+    pub fn with_rescan_interval(mut self, rescan_interval: Duration) -> Self {
+        self.rescan_interval = rescan_interval;
+        self
+    }
+
+    /// Delivers every reminder instance that is due, recording each one
+    /// as fired even if its notifier failed so it is not retried forever.
+    pub async fn tick(&self) -> Result<Vec<Delivery>, ReminderError> {
+        let now = self.clock.now();
+        let mut due: Vec<Pending> = self
+            .pending(now)
+            .await?
+            .into_iter()
+            .filter(|p| p.next_fire_at <= now)
+            .collect();
+        due.sort_by_key(|p| p.next_fire_at);
+
+        let mut deliveries = Vec::with_capacity(due.len());
+        for Pending { instance, title, mut state, .. } in due {
+            let notification = Notification::new(instance, title, now);
+            let result = self.notifier.notify(&notification).await;
+
+            state.record_fired(notification.instance(), now);
+            self.states.save(&state).await?;
+
+            deliveries.push(Delivery { notification, result });
+        }
+
+        Ok(deliveries)
+    }
+
+    /// The earliest firing still ahead, within the rescan horizon.
+    pub async fn next_due(&self) -> Result<Option<DateTime<Utc>>, ReminderError> {
+        let now = self.clock.now();
+
+        Ok(self
+            .pending(now)
+            .await?
+            .into_iter()
+            .map(|p| p.next_fire_at)
+            .filter(|at| *at > now)
+            .min())
+    }
+
+    /// Delivers reminders until an error occurs, sleeping until the next
+    /// one is due in between.
+    pub async fn run<F>(&self, mut on_delivery: F) -> Result<(), ReminderError>
+    where
+        F: FnMut(&Delivery) + Send,
+    {
+        loop {
+            for delivery in self.tick().await? {
+                on_delivery(&delivery);
+            }
+
+            let rescan_at = self.clock.now() + self.rescan_interval;
+            let wake_at = self
+                .next_due()
+                .await?
+                .map_or(rescan_at, |due| due.min(rescan_at));
+
+            self.clock.sleep_until(wake_at).await;
+        }
+    }
+
+    /// Reminder instances with a firing still ahead whose first firing
+    /// falls inside the lookback or the next rescan interval. Only the
+    /// events and series with such reminders are loaded.
+    async fn pending(&self, now: DateTime<Utc>) -> Result<Vec<Pending>, ReminderError> {
+        let window = TimeRange::new(now - self.lookback, now + self.rescan_interval)?;
+        let active: HashSet<CalendarId> = self
+            .calendars
+            .find_all_active()
+            .await?
+            .iter()
+            .map(|calendar| *calendar.calendar_id())
+            .collect();
+        let mut instances = Vec::new();
+
+        for event in self.events.find_with_reminders_in(&window).await? {
+            if active.contains(event.calendar_id()) {
+                instances.extend(
+                    event
+                        .reminder_instances()
+                        .into_iter()
+                        .filter(|i| window.contains(i.fires_at()))
+                        .map(|i| (i, event.title().clone())),
+                );
+            }
+        }
+
+        for series in self.recurring.find_with_reminders_in(&window).await? {
+            if active.contains(series.calendar_id()) {
+                instances.extend(
+                    series
+                        .reminder_instances(&window)
+                        .into_iter()
+                        .map(|i| (i, series.title().clone())),
+                );
+            }
+        }
+
+        let keys: Vec<ReminderKey> = instances.iter().map(|(i, _)| i.key()).collect();
+        let mut states: HashMap<ReminderKey, ReminderState> = self
+            .states
+            .find_all(&keys)
+            .await?
+            .into_iter()
+            .map(|state| (*state.key(), state))
+            .collect();
+
+        let mut pending = Vec::new();
+        for (instance, title) in instances {
+            let key = instance.key();
+            let state = states.remove(&key).unwrap_or_else(|| ReminderState::new(key));
+
+            if let Some(next_fire_at) = state.next_fire_at(&instance) {
+                pending.push(Pending {
+                    instance,
+                    title,
+                    state,
+                    next_fire_at,
+                });
+            }
+        }
+
+        Ok(pending)
+    }
+}
@@ -0,0 +1,66 @@
+use std::path::Path;
+
+use serde_json::Value;
+use tokio::{
+    io::{AsyncBufReadExt, AsyncWriteExt, BufReader, Lines},
+    net::{unix::{OwnedReadHalf, OwnedWriteHalf}, UnixStream},
+};
+
+use super::{
+    error::RpcError,
+    protocol::{RpcRequest, RpcResponse},
+};
+
+/// A connection to a running daemon.
+pub struct RpcClient {
+    reader: Lines<BufReader<OwnedReadHalf>>,
+    writer: OwnedWriteHalf,
+    next_id: u64,
+}
+
+impl RpcClient {
+    pub async fn connect(path: impl AsRef<Path>) -> Result<Self, RpcError> {
+        let (reader, writer) = UnixStream::connect(path).await?.into_split();
+
+        Ok(Self {
+            reader: BufReader::new(reader).lines(),
+            writer,
+            next_id: 1,
+        })
+    }
+
+    /// Sends one request and waits for its response. Error responses
+    /// come back as `RpcError::Remote`.
+    pub async fn call(&mut self, method: &str, params: Value) -> Result<Value, RpcError> {
+        let id = self.next_id;
+        self.next_id += 1;
+
+        let request = serde_json::to_string(&RpcRequest::new(id, method, params))
+            .expect("requests always serialize");
+        self.writer.write_all(request.as_bytes()).await?;
+        self.writer.write_all(b"\n").await?;
+        self.writer.flush().await?;
+
+        let line = self
+            .reader
+            .next_line()
+            .await?
+            .ok_or_else(|| RpcError::Transport("daemon closed the connection".to_string()))?;
+
+        let response: RpcResponse =
+            serde_json::from_str(&line).map_err(|e| RpcError::Parse(e.to_string()))?;
+
+        if response.id != id {
+            return Err(RpcError::Transport(format!(
+                "response id {} does not match request id {id}",
+                response.id
+            )));
+        }
+
+        match (response.result, response.error) {
+            (_, Some(error)) => Err(RpcError::Remote(error)),
+            (Some(result), None) => Ok(result),
+            (None, None) => Ok(Value::Null),
+        }
+    }
+}
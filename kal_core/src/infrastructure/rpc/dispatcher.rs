@@ -0,0 +1,1556 @@
+use serde::{de::DeserializeOwned, Serialize};
+use serde_json::Value;
+use sqlx::SqlitePool;
+
+use crate::{
+    application::{
+        commands::{
+            calendars::{
+                ArchiveCalendarCommand, ArchiveCalendarHandler,
+                CreateCalendarCommand, CreateCalendarHandler,
+                DeleteCalendarCommand, DeleteCalendarHandler,
+                RenameCalendarCommand, RenameCalendarHandler,
+                RestoreTrashedCalendarCommand, RestoreTrashedCalendarHandler,
+                SubscribeCalendarCommand, SubscribeCalendarHandler,
+                UnarchiveCalendarCommand, UnarchiveCalendarHandler,
+                UpdateCalendarDescriptionCommand, UpdateCalendarDescriptionHandler,
+            },
+            events::{
+                AddEventAttendeeCommand, AddEventAttendeeHandler,
+                AddEventReminderCommand, AddEventReminderHandler,
+                AddEventTagCommand, AddEventTagHandler,
+                CancelEventCommand, CancelEventHandler,
+                CreateEventCommand, CreateEventHandler,
+                DeleteEventCommand, DeleteEventHandler,
+                RemoveEventAttendeeCommand, RemoveEventAttendeeHandler,
+                RemoveEventReminderCommand, RemoveEventReminderHandler,
+                RemoveEventTagCommand, RemoveEventTagHandler,
+                RestoreEventCommand, RestoreEventHandler,
+                RestoreTrashedEventCommand, RestoreTrashedEventHandler,
+                SetEventOrganizerCommand, SetEventOrganizerHandler,
+                UpdateEventAttendeeStatusCommand, UpdateEventAttendeeStatusHandler,
+                UpdateEventColorCommand, UpdateEventColorHandler,
+                UpdateEventDescriptionCommand, UpdateEventDescriptionHandler,
+                UpdateEventGeoCommand, UpdateEventGeoHandler,
+                UpdateEventLocationCommand, UpdateEventLocationHandler,
+                UpdateEventStatusCommand, UpdateEventStatusHandler,
+                UpdateEventTimeRangeCommand, UpdateEventTimeRangeHandler,
+                UpdateEventTitleCommand, UpdateEventTitleHandler,
+                UpdateEventTransparencyCommand, UpdateEventTransparencyHandler,
+                UpdateEventUrlCommand, UpdateEventUrlHandler,
+            },
+            recurring::{
+                AddRecurringAttendeeCommand, AddRecurringAttendeeHandler,
+                AddRecurringReminderCommand, AddRecurringReminderHandler,
+                AddRecurringTagCommand, AddRecurringTagHandler,
+                CancelRecurringEventCommand, CancelRecurringEventHandler,
+                CancelRecurringOccurrenceCommand, CancelRecurringOccurrenceHandler,
+                CreateRecurringEventCommand, CreateRecurringEventHandler,
+                DeleteRecurringEventCommand, DeleteRecurringEventHandler,
+                RemoveRecurringAttendeeCommand, RemoveRecurringAttendeeHandler,
+                RemoveRecurringReminderCommand, RemoveRecurringReminderHandler,
+                RemoveRecurringTagCommand, RemoveRecurringTagHandler,
+                RescheduleRecurringOccurrenceCommand, RescheduleRecurringOccurrenceHandler,
+                RestoreRecurringEventCommand, RestoreRecurringEventHandler,
+                RestoreRecurringOccurrenceCommand, RestoreRecurringOccurrenceHandler,
+                RestoreTrashedRecurringEventCommand, RestoreTrashedRecurringEventHandler,
+                SetRecurringOccurrenceLocationCommand, SetRecurringOccurrenceLocationHandler,
+                SetRecurringOrganizerCommand, SetRecurringOrganizerHandler,
+                UpdateRecurringAttendeeStatusCommand, UpdateRecurringAttendeeStatusHandler,
+                UpdateRecurringGeoCommand, UpdateRecurringGeoHandler,
+                UpdateRecurringLocationCommand, UpdateRecurringLocationHandler,
+                UpdateRecurringStatusCommand, UpdateRecurringStatusHandler,
+                UpdateRecurringTransparencyCommand, UpdateRecurringTransparencyHandler,
+                UpdateRecurringUrlCommand, UpdateRecurringUrlHandler,
+            },
+            reminders::{
+                DismissReminderCommand, DismissReminderHandler,
+                SnoozeReminderCommand, SnoozeReminderHandler,
+            },
+            journal::{
+                CreateJournalEntryCommand, CreateJournalEntryHandler,
+                DeleteJournalEntryCommand, DeleteJournalEntryHandler,
+                ImportJournalEntriesCommand, ImportJournalEntriesHandler,
+                UpdateJournalEntryCommand, UpdateJournalEntryHandler,
+            },
+            scheduling::{ApplyReplyCommand, ApplyReplyHandler},
+            tags::{MergeTagCommand, MergeTagHandler, RenameTagCommand, RenameTagHandler},
+            tasks::{
+                CompleteTaskCommand, CompleteTaskHandler,
+                CreateTaskCommand, CreateTaskHandler,
+                DeleteTaskCommand, DeleteTaskHandler,
+                ImportTasksCommand, ImportTasksHandler,
+                ReopenTaskCommand, ReopenTaskHandler,
+                UpdateTaskCommand, UpdateTaskHandler,
+            },
+            history::{RedoCommand, RedoHandler, UndoCommand, UndoHandler},
+            trash::{PurgeTrashCommand, PurgeTrashHandler},
+        },
+        error::ApplicationError,
+        history::Recorded,
+        publisher::{Published, Publisher},
+        scope::CommandScope,
+    },
+    domain::{
+        calendar_object::CalendarObject,
+        free_busy::busy_periods,
+        journal::JournalEntry,
+        recurrence::RecurrenceRule,
+        search::{within_window, SearchHit, SearchHitKind},
+        task::Task,
+        repository::{
+            AuditRepository, CalendarRepository, EventRepository, EventSearchRepository,
+            HistoryRepository,
+            JournalRepository, RecurringEventRepository, RepositoryError, TagRepository,
+            TaskRepository,
+        },
+        value_objects::{
+            CalendarId, EventColor, EventId, EventStatus, Frequency, JournalId, ReminderId,
+            TaskId, TimeRange, Transparency,
+        },
+    },
+    infrastructure::{
+        dto::*,
+        ical::{Component, IcalMapper},
+        itip::{ItipMessage, ItipReply},
+        persistence::{
+            SqliteAuditRepository,
+            SqliteCalendarRepository,
+            SqliteEventRepository,
+            SqliteEventSearchRepository,
+            SqliteHistoryRepository,
+            SqliteJournalRepository,
+            SqliteRecurringEventRepository,
+            SqliteReminderStateRepository,
+            SqliteTagRepository,
+            SqliteTaskRepository,
+            SqliteUnitOfWork,
+        },
+    },
+};
+
+use super::{
+    error::RpcError,
+    params::*,
+    protocol::{RpcRequest, RpcResponse, VERSION},
+};
+
+type RpcResult = Result<Value, RpcError>;
+
+/// How many entries `history.list` returns unless asked otherwise.
+const DEFAULT_HISTORY_LIMIT: u32 = 20;
+
+/// How many entries `event.audit` and `calendar.audit` return unless
+/// asked otherwise.
+const DEFAULT_AUDIT_LIMIT: u32 = 20;
+
+/// Methods that never write. Any other method may, including ones not
+/// known at all.
+const READ_METHODS: &[&str] = &[
+    "daemon.ping",
+    "calendar.list",
+    "calendar.get",
+    "calendar.free_busy",
+    "calendar.audit",
+    "event.list",
+    "event.get",
+    "event.search",
+    "event.audit",
+    "recurring.list",
+    "recurring.get",
+    "recurring.occurrences",
+    "task.list",
+    "task.get",
+    "task.export",
+    "journal.list",
+    "journal.get",
+    "journal.for_event",
+    "journal.search",
+    "journal.export",
+    "tag.list",
+    "trash.list",
+    "history.list",
+    "itip.message",
+];
+
+/// Maps JSON-RPC methods onto the command handlers and repositories.
+/// Used by the daemon, and in-process by the CLI when no daemon runs.
+///
+/// Each call runs in one transaction. Writes go through recording
+/// repositories, and every call that changed something is added to the
+/// undo history and the audit log under its method name, in that same
+/// transaction. The domain events a call raised are published once it
+/// has committed.
+#[derive(Clone)]
+pub struct RpcDispatcher {
+    pool: SqlitePool,
+    scope: CommandScope,
+}
+
+impl RpcDispatcher {
+    pub fn new(pool: SqlitePool) -> Self {
+        Self { pool, scope: CommandScope::new() }
+    }
+
+    /// Publishes domain events to `publisher`'s subscribers.
+    pub fn with_publisher(mut self, publisher: Publisher) -> Self {
+        self.scope = self.scope.with_publisher(publisher);
+        self
+    }
+
+    /// Attributes calls to `actor` rather than the publisher's, such as the
+    /// user on the other end of the socket.
+    pub fn with_actor(mut self, actor: impl Into<String>) -> Self {
+        self.scope = self.scope.with_actor(actor);
+        self
+    }
+
+    /// Handles one line of the wire protocol. Returns `None` for
+    /// notifications.
+    pub async fn handle_line(&self, line: &str) -> Option<String> {
+        let response = match serde_json::from_str::<RpcRequest>(line) {
+            Err(e) => Some(RpcResponse::error(Value::Null, RpcError::Parse(e.to_string()).to_object())),
+            Ok(request) if request.jsonrpc != VERSION => Some(RpcResponse::error(
+                request.id.unwrap_or(Value::Null),
+                RpcError::InvalidRequest("jsonrpc must be \"2.0\"".to_string()).to_object(),
+            )),
+            Ok(request) => {
+                let result = self.call(&request.method, request.params).await;
+                request.id.map(|id| match result {
+                    Ok(value) => RpcResponse::result(id, value),
+                    Err(e) => RpcResponse::error(id, e.to_object()),
+                })
+            }
+        };
+
+        response.map(|r| serde_json::to_string(&r).expect("responses always serialize"))
+    }
+
+    pub async fn call(&self, method: &str, params: Value) -> RpcResult {
+        // Only calls that may write take the write lock
+        let uow = if READ_METHODS.contains(&method) {
+            SqliteUnitOfWork::begin_read(&self.pool).await?
+        } else {
+            SqliteUnitOfWork::begin(&self.pool).await?
+        };
+
+        // A scope of its own per call keeps concurrent calls' changes apart
+        let call = Call { uow, scope: self.scope.scoped() };
+
+        // A failed call drops its unit of work, which rolls it back, except
+        // that undo and redo keep stepping past an entry they cannot replay
+        let value = match call.dispatch(method, params).await {
+            Err(RpcError::Application(e)) if e.skipped_history_entry() => {
+                call.uow.commit().await?;
+                return Err(e.into());
+            }
+            value => value?,
+        };
+
+        call.scope.record(&call.uow.history(), method).await?;
+        call.scope.audit(&call.uow.audit(), method).await?;
+        call.uow.commit().await?;
+        call.scope.publish(method).await;
+
+        Ok(value)
+    }
+}
+
+/// One call in flight: the transaction it runs in, and what it changed
+/// and raised along the way.
+struct Call {
+    uow: SqliteUnitOfWork,
+    scope: CommandScope,
+}
+
+impl Call {
+    fn calendars(&self) -> Recorded<Published<SqliteCalendarRepository>> {
+        self.scope.calendars(self.uow.calendars())
+    }
+
+    fn events(&self) -> Recorded<Published<SqliteEventRepository>> {
+        self.scope.events(self.uow.events())
+    }
+
+    fn recurring(&self) -> Recorded<Published<SqliteRecurringEventRepository>> {
+        self.scope.recurring(self.uow.recurring())
+    }
+
+    fn search(&self) -> SqliteEventSearchRepository {
+        self.uow.search()
+    }
+
+    fn reminder_states(&self) -> SqliteReminderStateRepository {
+        self.uow.reminder_states()
+    }
+
+    fn tags(&self) -> SqliteTagRepository {
+        self.uow.tags()
+    }
+
+    fn tasks(&self) -> Recorded<SqliteTaskRepository> {
+        self.scope.tasks(self.uow.tasks())
+    }
+
+    fn journal(&self) -> Recorded<SqliteJournalRepository> {
+        self.scope.journal(self.uow.journal())
+    }
+
+    fn history(&self) -> SqliteHistoryRepository {
+        self.uow.history()
+    }
+
+    fn audit(&self) -> SqliteAuditRepository {
+        self.uow.audit()
+    }
+
+    async fn dispatch(&self, method: &str, params: Value) -> RpcResult {
+        match method {
+            "daemon.ping" => Ok(Value::from("pong")),
+
+            "calendar.list" => self.list_calendars().await,
+            "calendar.get" => self.get_calendar(parse(params)?).await,
+            "calendar.create" => self.create_calendar(parse(params)?).await,
+            "calendar.subscribe" => self.subscribe_calendar(parse(params)?).await,
+            "calendar.update" => self.update_calendar(parse(params)?).await,
+            "calendar.archive" => self.archive_calendar(parse(params)?, true).await,
+            "calendar.unarchive" => self.archive_calendar(parse(params)?, false).await,
+            "calendar.delete" => self.delete_calendar(parse(params)?).await,
+            "calendar.free_busy" => self.free_busy(parse(params)?).await,
+            "calendar.audit" => self.calendar_audit(parse(params)?).await,
+
+            "event.list" => self.list_events(parse(params)?).await,
+            "event.get" => self.get_event(parse(params)?).await,
+            "event.create" => self.create_event(parse(params)?).await,
+            "event.update" => self.update_event(parse(params)?).await,
+            "event.cancel" => self.cancel_event(parse(params)?, true).await,
+            "event.restore" => self.cancel_event(parse(params)?, false).await,
+            "event.delete" => self.delete_event(parse(params)?).await,
+            "event.add_reminder" => self.add_event_reminder(parse(params)?).await,
+            "event.remove_reminder" => self.remove_event_reminder(parse(params)?).await,
+            "event.set_organizer" => self.set_event_organizer(parse(params)?).await,
+            "event.add_attendee" => self.add_event_attendee(parse(params)?).await,
+            "event.remove_attendee" => self.remove_event_attendee(parse(params)?).await,
+            "event.update_attendee_status" => self.update_event_attendee_status(parse(params)?).await,
+            "event.add_tag" => self.add_event_tag(parse(params)?).await,
+            "event.remove_tag" => self.remove_event_tag(parse(params)?).await,
+            "event.search" => self.search_events(parse(params)?).await,
+            "event.audit" => self.event_audit(parse(params)?).await,
+
+            "recurring.list" => self.list_recurring(parse(params)?).await,
+            "recurring.get" => self.get_recurring(parse(params)?).await,
+            "recurring.create" => self.create_recurring(parse(params)?).await,
+            "recurring.update" => self.update_recurring(parse(params)?).await,
+            "recurring.cancel" => self.cancel_recurring(parse(params)?, true).await,
+            "recurring.restore" => self.cancel_recurring(parse(params)?, false).await,
+            "recurring.delete" => self.delete_recurring(parse(params)?).await,
+            "recurring.occurrences" => self.list_occurrences(parse(params)?).await,
+            "recurring.cancel_occurrence" => self.cancel_occurrence(parse(params)?).await,
+            "recurring.reschedule_occurrence" => self.reschedule_occurrence(parse(params)?).await,
+            "recurring.restore_occurrence" => self.restore_occurrence(parse(params)?).await,
+            "recurring.set_occurrence_location" => self.set_occurrence_location(parse(params)?).await,
+            "recurring.add_reminder" => self.add_recurring_reminder(parse(params)?).await,
+            "recurring.remove_reminder" => self.remove_recurring_reminder(parse(params)?).await,
+            "recurring.set_organizer" => self.set_recurring_organizer(parse(params)?).await,
+            "recurring.add_attendee" => self.add_recurring_attendee(parse(params)?).await,
+            "recurring.remove_attendee" => self.remove_recurring_attendee(parse(params)?).await,
+            "recurring.update_attendee_status" => self.update_recurring_attendee_status(parse(params)?).await,
+            "recurring.add_tag" => self.add_recurring_tag(parse(params)?).await,
+            "recurring.remove_tag" => self.remove_recurring_tag(parse(params)?).await,
+
+            "task.list" => self.list_tasks(parse(params)?).await,
+            "task.get" => self.get_task(parse(params)?).await,
+            "task.create" => self.create_task(parse(params)?).await,
+            "task.update" => self.update_task(parse(params)?).await,
+            "task.complete" => self.complete_task(parse(params)?).await,
+            "task.reopen" => self.reopen_task(parse(params)?).await,
+            "task.delete" => self.delete_task(parse(params)?).await,
+            "task.import" => self.import_tasks(parse(params)?).await,
+            "task.export" => self.export_tasks(parse(params)?).await,
+
+            "journal.list" => self.list_journal(parse(params)?).await,
+            "journal.get" => self.get_journal_entry(parse(params)?).await,
+            "journal.create" => self.create_journal_entry(parse(params)?).await,
+            "journal.update" => self.update_journal_entry(parse(params)?).await,
+            "journal.delete" => self.delete_journal_entry(parse(params)?).await,
+            "journal.for_event" => self.journal_for_event(parse(params)?).await,
+            "journal.search" => self.search_journal(parse(params)?).await,
+            "journal.import" => self.import_journal(parse(params)?).await,
+            "journal.export" => self.export_journal(parse(params)?).await,
+
+            "tag.list" => self.list_tags().await,
+            "tag.rename" => self.rename_tag(parse(params)?).await,
+            "tag.merge" => self.merge_tag(parse(params)?).await,
+
+            "trash.list" => self.list_trash().await,
+            "trash.restore" => self.restore_trashed(parse(params)?).await,
+            "trash.purge" => self.purge_trash(parse(params)?).await,
+
+            "history.list" => self.list_history(parse(params)?).await,
+            "history.undo" => self.undo().await,
+            "history.redo" => self.redo().await,
+
+            "reminder.snooze" => self.snooze_reminder(parse(params)?).await,
+            "reminder.dismiss" => self.dismiss_reminder(parse(params)?).await,
+
+            "itip.message" => self.itip_message(parse(params)?).await,
+            "itip.reply" => self.apply_reply(parse(params)?).await,
+
+            other => Err(RpcError::MethodNotFound(other.to_string())),
+        }
+    }
+
+
+    // ==================================================
+    // Calendars
+    // ==================================================
+
+    async fn list_calendars(&self) -> RpcResult {
+        let calendars = self.calendars().find_all_active().await?;
+        to_value(calendars.iter().map(CalendarDto::from).collect::<Vec<_>>())
+    }
+
+    async fn get_calendar(&self, params: IdParams) -> RpcResult {
+        let calendar = self
+            .calendars()
+            .find_by_id(&CalendarId::from_uuid(params.id))
+            .await?
+            .ok_or(ApplicationError::CalendarNotFound)?;
+        to_value(CalendarDto::from(&calendar))
+    }
+
+    async fn create_calendar(&self, params: CreateCalendarDto) -> RpcResult {
+        let id = CreateCalendarHandler::new(self.calendars())
+            .handle(CreateCalendarCommand::new(params.name, params.description))
+            .await?;
+        to_value(CreatedDto { id: id.as_uuid() })
+    }
+
+    async fn subscribe_calendar(&self, params: SubscribeCalendarDto) -> RpcResult {
+        let refresh_interval = params.refresh_interval()?;
+        let command = SubscribeCalendarCommand::new(
+            params.name,
+            params.description,
+            params.url,
+            refresh_interval,
+        );
+
+        let id = SubscribeCalendarHandler::new(self.calendars())
+            .handle(command)
+            .await?;
+        to_value(CreatedDto { id: id.as_uuid() })
+    }
+
+    async fn update_calendar(&self, params: WithId<UpdateCalendarDto>) -> RpcResult {
+        let id = CalendarId::from_uuid(params.id);
+
+        if let Some(name) = params.body.name {
+            RenameCalendarHandler::new(self.calendars())
+                .handle(RenameCalendarCommand::new(id, name))
+                .await?;
+        }
+
+        if let Some(description) = params.body.description {
+            UpdateCalendarDescriptionHandler::new(self.calendars())
+                .handle(UpdateCalendarDescriptionCommand::new(id, description))
+                .await?;
+        }
+
+        self.get_calendar(IdParams { id: params.id }).await
+    }
+
+    async fn archive_calendar(&self, params: IdParams, archive: bool) -> RpcResult {
+        let id = CalendarId::from_uuid(params.id);
+
+        if archive {
+            ArchiveCalendarHandler::new(self.calendars())
+                .handle(ArchiveCalendarCommand::new(id))
+                .await?;
+        } else {
+            UnarchiveCalendarHandler::new(self.calendars())
+                .handle(UnarchiveCalendarCommand::new(id))
+                .await?;
+        }
+
+        Ok(Value::Null)
+    }
+
+    async fn delete_calendar(&self, params: IdParams) -> RpcResult {
+        DeleteCalendarHandler::new(self.calendars())
+            .handle(DeleteCalendarCommand::new(CalendarId::from_uuid(params.id)))
+            .await?;
+        Ok(Value::Null)
+    }
+
+    async fn free_busy(&self, params: FreeBusyParams) -> RpcResult {
+        let calendar_id = CalendarId::from_uuid(params.id);
+        self.ensure_calendar(&calendar_id).await?;
+        let window = TimeRange::new(params.from, params.to)?;
+
+        let events = self.events().find_in_range(&calendar_id, &window).await?;
+        let series = self.recurring().find_by_calendar(&calendar_id).await?;
+        let periods = busy_periods(&events, &series, &window);
+
+        to_value(periods.iter().map(BusyPeriodDto::from).collect::<Vec<_>>())
+    }
+
+
+    // ==================================================
+    // Events
+    // ==================================================
+
+    async fn list_events(&self, params: InCalendar<RangeParams>) -> RpcResult {
+        let calendar_id = CalendarId::from_uuid(params.calendar_id);
+        self.ensure_calendar(&calendar_id).await?;
+        let filter = params.body.tag_filter()?;
+
+        let mut events = match (params.body.location, range(params.body.from, params.body.to)?) {
+            (None, Some(range)) => self.events().find_in_range(&calendar_id, &range).await?,
+            (None, None) => self.events().find_by_calendar(&calendar_id).await?,
+            (Some(location), range) => {
+                let mut events = self.events().find_by_location(&calendar_id, &location).await?;
+
+                if let Some(range) = range {
+                    events.retain(|event| !event.is_cancelled() && event.time_range().overlaps(&range));
+                }
+
+                events
+            }
+        };
+        events.retain(|event| filter.matches(event.tags()));
+
+        to_value(events.iter().map(EventDto::from).collect::<Vec<_>>())
+    }
+
+    /// Events and series matching the query, best match first.
+    async fn search_events(&self, params: EventSearchParams) -> RpcResult {
+        let calendar_id = params.calendar_id.map(CalendarId::from_uuid);
+        if let Some(calendar_id) = &calendar_id {
+            self.ensure_calendar(calendar_id).await?;
+        }
+        let window = range(params.from, params.to)?;
+
+        let mut hits = self
+            .search()
+            .search(&params.query, calendar_id.as_ref(), window.as_ref())
+            .await?;
+        if let Some(window) = &window {
+            hits = self.hits_within(hits, window).await?;
+        }
+        hits.truncate(params.limit);
+
+        to_value(hits.iter().map(SearchHitDto::from).collect::<Vec<_>>())
+    }
+
+    /// Keeps the series hits with an occurrence in `window`.
+    async fn hits_within(&self, hits: Vec<SearchHit>, window: &TimeRange) -> Result<Vec<SearchHit>, RpcError> {
+        let mut series = Vec::new();
+        for hit in hits.iter().filter(|hit| *hit.kind() == SearchHitKind::Series) {
+            series.push(self.recurring().find_by_id(hit.event_id()).await?);
+        }
+
+        Ok(within_window(hits, &series, window))
+    }
+
+    async fn get_event(&self, params: IdParams) -> RpcResult {
+        let event = self
+            .events()
+            .find_by_id(&EventId::from_uuid(params.id))
+            .await?
+            .ok_or(ApplicationError::EventNotFound)?;
+        to_value(EventDto::from(&event))
+    }
+
+    async fn create_event(&self, params: InCalendar<CreateEventDto>) -> RpcResult {
+        let dto = params.body;
+        let (status, transparency) =
+            parse_availability(dto.status.as_deref(), dto.transparency.as_deref())?;
+        let command = CreateEventCommand::new(
+            CalendarId::from_uuid(params.calendar_id),
+            dto.title,
+            dto.description,
+            TimeRange::new(dto.starts_at, dto.ends_at)?,
+            EventColor::from(dto.color),
+            dto.is_all_day,
+        )
+        .with_place(dto.location, dto.url, dto.geo.map(|g| g.point()).transpose()?)
+        .with_tags(parse_tags(&dto.tags)?)
+        .with_status(status, transparency);
+
+        let id = CreateEventHandler::new(self.events(), self.calendars())
+            .handle(command)
+            .await?;
+        to_value(CreatedDto { id: id.as_uuid() })
+    }
+
+    async fn update_event(&self, params: WithId<UpdateEventDto>) -> RpcResult {
+        let id = EventId::from_uuid(params.id);
+        let dto = params.body;
+
+        // Validate the whole patch before applying any part of it
+        let time_range = match (dto.starts_at, dto.ends_at) {
+            (None, None) => None,
+            (starts_at, ends_at) => {
+                let current = self
+                    .events()
+                    .find_by_id(&id)
+                    .await?
+                    .ok_or(ApplicationError::EventNotFound)?;
+                let starts_at = starts_at.unwrap_or(*current.time_range().starts_at());
+                let ends_at = ends_at.unwrap_or(*current.time_range().ends_at());
+                Some(TimeRange::new(starts_at, ends_at)?)
+            }
+        };
+        let geo = dto.geo.map(|geo| geo.map(|g| g.point()).transpose()).transpose()?;
+        let status = dto.status.as_deref().map(str::parse::<EventStatus>).transpose()?;
+        let transparency = dto.transparency.as_deref().map(str::parse::<Transparency>).transpose()?;
+
+        if let Some(title) = dto.title {
+            UpdateEventTitleHandler::new(self.events(), self.calendars())
+                .handle(UpdateEventTitleCommand::new(id, title))
+                .await?;
+        }
+
+        if let Some(description) = dto.description {
+            UpdateEventDescriptionHandler::new(self.events(), self.calendars())
+                .handle(UpdateEventDescriptionCommand::new(id, description))
+                .await?;
+        }
+
+        if let Some(time_range) = time_range {
+            UpdateEventTimeRangeHandler::new(self.events(), self.calendars())
+                .handle(UpdateEventTimeRangeCommand::new(id, time_range))
+                .await?;
+        }
+
+        if let Some(color) = dto.color {
+            UpdateEventColorHandler::new(self.events(), self.calendars())
+                .handle(UpdateEventColorCommand::new(id, EventColor::from(color)))
+                .await?;
+        }
+
+        if let Some(location) = dto.location {
+            UpdateEventLocationHandler::new(self.events(), self.calendars())
+                .handle(UpdateEventLocationCommand::new(id, location))
+                .await?;
+        }
+
+        if let Some(url) = dto.url {
+            UpdateEventUrlHandler::new(self.events(), self.calendars())
+                .handle(UpdateEventUrlCommand::new(id, url))
+                .await?;
+        }
+
+        if let Some(geo) = geo {
+            UpdateEventGeoHandler::new(self.events(), self.calendars())
+                .handle(UpdateEventGeoCommand::new(id, geo))
+                .await?;
+        }
+
+        if let Some(status) = status {
+            UpdateEventStatusHandler::new(self.events(), self.calendars())
+                .handle(UpdateEventStatusCommand::new(id, status))
+                .await?;
+        }
+
+        if let Some(transparency) = transparency {
+            UpdateEventTransparencyHandler::new(self.events(), self.calendars())
+                .handle(UpdateEventTransparencyCommand::new(id, transparency))
+                .await?;
+        }
+
+        self.get_event(IdParams { id: params.id }).await
+    }
+
+    async fn cancel_event(&self, params: IdParams, cancel: bool) -> RpcResult {
+        let id = EventId::from_uuid(params.id);
+
+        if cancel {
+            CancelEventHandler::new(self.events(), self.calendars())
+                .handle(CancelEventCommand::new(id))
+                .await?;
+        } else {
+            RestoreEventHandler::new(self.events(), self.calendars())
+                .handle(RestoreEventCommand::new(id))
+                .await?;
+        }
+
+        Ok(Value::Null)
+    }
+
+    async fn add_event_reminder(&self, params: WithId<CreateReminderDto>) -> RpcResult {
+        let dto = params.body;
+        let command = AddEventReminderCommand::new(
+            EventId::from_uuid(params.id),
+            dto.trigger()?,
+            dto.action()?,
+            dto.description.clone(),
+            dto.repeat,
+            dto.repeat_interval()?,
+        );
+
+        let reminder_id = AddEventReminderHandler::new(self.events(), self.calendars())
+            .handle(command)
+            .await?;
+        to_value(CreatedDto { id: reminder_id.as_uuid() })
+    }
+
+    async fn remove_event_reminder(&self, params: ReminderParams) -> RpcResult {
+        RemoveEventReminderHandler::new(self.events(), self.calendars())
+            .handle(RemoveEventReminderCommand::new(
+                EventId::from_uuid(params.id),
+                ReminderId::from_uuid(params.reminder_id),
+            ))
+            .await?;
+        Ok(Value::Null)
+    }
+
+    async fn set_event_organizer(&self, params: WithId<SetOrganizerDto>) -> RpcResult {
+        let command = SetEventOrganizerCommand::new(
+            EventId::from_uuid(params.id),
+            params.body.organizer()?,
+        );
+
+        SetEventOrganizerHandler::new(self.events(), self.calendars())
+            .handle(command)
+            .await?;
+        Ok(Value::Null)
+    }
+
+    async fn add_event_attendee(&self, params: WithId<CreateAttendeeDto>) -> RpcResult {
+        let command = AddEventAttendeeCommand::new(
+            EventId::from_uuid(params.id),
+            params.body.attendee()?,
+        );
+
+        AddEventAttendeeHandler::new(self.events(), self.calendars())
+            .handle(command)
+            .await?;
+        Ok(Value::Null)
+    }
+
+    async fn remove_event_attendee(&self, params: AttendeeParams) -> RpcResult {
+        RemoveEventAttendeeHandler::new(self.events(), self.calendars())
+            .handle(RemoveEventAttendeeCommand::new(
+                EventId::from_uuid(params.id),
+                params.email,
+            ))
+            .await?;
+        Ok(Value::Null)
+    }
+
+    async fn update_event_attendee_status(&self, params: WithAttendee<AttendeeStatusDto>) -> RpcResult {
+        let command = UpdateEventAttendeeStatusCommand::new(
+            EventId::from_uuid(params.id),
+            params.email,
+            params.body.status.parse()?,
+        );
+
+        UpdateEventAttendeeStatusHandler::new(self.events(), self.calendars())
+            .handle(command)
+            .await?;
+        Ok(Value::Null)
+    }
+
+    async fn add_event_tag(&self, params: WithId<TagNameDto>) -> RpcResult {
+        AddEventTagHandler::new(self.events(), self.calendars())
+            .handle(AddEventTagCommand::new(EventId::from_uuid(params.id), params.body.tag()?))
+            .await?;
+        Ok(Value::Null)
+    }
+
+    async fn remove_event_tag(&self, params: WithId<TagNameDto>) -> RpcResult {
+        RemoveEventTagHandler::new(self.events(), self.calendars())
+            .handle(RemoveEventTagCommand::new(EventId::from_uuid(params.id), params.body.tag()?))
+            .await?;
+        Ok(Value::Null)
+    }
+
+    async fn delete_event(&self, params: IdParams) -> RpcResult {
+        DeleteEventHandler::new(self.events(), self.calendars())
+            .handle(DeleteEventCommand::new(EventId::from_uuid(params.id)))
+            .await?;
+        Ok(Value::Null)
+    }
+
+
+    // ==================================================
+    // Recurring series
+    // ==================================================
+
+    async fn list_recurring(&self, params: InCalendar<RangeParams>) -> RpcResult {
+        let calendar_id = CalendarId::from_uuid(params.calendar_id);
+        self.ensure_calendar(&calendar_id).await?;
+
+        let mut events = match &params.body.location {
+            Some(location) => self.recurring().find_by_location(&calendar_id, location).await?,
+            None => self.recurring().find_by_calendar(&calendar_id).await?,
+        };
+
+        if let Some(range) = range(params.body.from, params.body.to)? {
+            events.retain(|event| !event.occurrences_in(&range).is_empty());
+        }
+
+        let filter = params.body.tag_filter()?;
+        events.retain(|event| filter.matches(event.tags()));
+
+        to_value(events.iter().map(RecurringEventDto::from).collect::<Vec<_>>())
+    }
+
+    async fn get_recurring(&self, params: IdParams) -> RpcResult {
+        let event = self
+            .recurring()
+            .find_by_id(&EventId::from_uuid(params.id))
+            .await
+            .map_err(ApplicationError::from_recurring_lookup)?;
+        to_value(RecurringEventDto::from(&event))
+    }
+
+    async fn create_recurring(&self, params: InCalendar<CreateRecurringEventDto>) -> RpcResult {
+        let dto = params.body;
+        let (status, transparency) =
+            parse_availability(dto.status.as_deref(), dto.transparency.as_deref())?;
+        let frequency = dto.frequency.parse::<Frequency>()?;
+
+        let command = CreateRecurringEventCommand::new(
+            CalendarId::from_uuid(params.calendar_id),
+            dto.title,
+            dto.description,
+            TimeRange::new(dto.starts_at, dto.ends_at)?,
+            RecurrenceRule::new(frequency, dto.interval, dto.until)?,
+            EventColor::from(dto.color),
+            dto.is_all_day,
+        )
+        .with_place(dto.location, dto.url, dto.geo.map(|g| g.point()).transpose()?)
+        .with_tags(parse_tags(&dto.tags)?)
+        .with_status(status, transparency);
+
+        let id = CreateRecurringEventHandler::new(self.recurring(), self.calendars())
+            .handle(command)
+            .await?;
+        to_value(CreatedDto { id: id.as_uuid() })
+    }
+
+    async fn update_recurring(&self, params: WithId<UpdateRecurringDto>) -> RpcResult {
+        let id = EventId::from_uuid(params.id);
+        let dto = params.body;
+        let geo = dto.geo.map(|geo| geo.map(|g| g.point()).transpose()).transpose()?;
+        let status = dto.status.as_deref().map(str::parse::<EventStatus>).transpose()?;
+        let transparency = dto.transparency.as_deref().map(str::parse::<Transparency>).transpose()?;
+
+        if let Some(location) = dto.location {
+            UpdateRecurringLocationHandler::new(self.recurring(), self.calendars())
+                .handle(UpdateRecurringLocationCommand::new(id, location))
+                .await?;
+        }
+
+        if let Some(url) = dto.url {
+            UpdateRecurringUrlHandler::new(self.recurring(), self.calendars())
+                .handle(UpdateRecurringUrlCommand::new(id, url))
+                .await?;
+        }
+
+        if let Some(geo) = geo {
+            UpdateRecurringGeoHandler::new(self.recurring(), self.calendars())
+                .handle(UpdateRecurringGeoCommand::new(id, geo))
+                .await?;
+        }
+
+        if let Some(status) = status {
+            UpdateRecurringStatusHandler::new(self.recurring(), self.calendars())
+                .handle(UpdateRecurringStatusCommand::new(id, status))
+                .await?;
+        }
+
+        if let Some(transparency) = transparency {
+            UpdateRecurringTransparencyHandler::new(self.recurring(), self.calendars())
+                .handle(UpdateRecurringTransparencyCommand::new(id, transparency))
+                .await?;
+        }
+
+        self.get_recurring(IdParams { id: params.id }).await
+    }
+
+    async fn cancel_recurring(&self, params: IdParams, cancel: bool) -> RpcResult {
+        let id = EventId::from_uuid(params.id);
+
+        if cancel {
+            CancelRecurringEventHandler::new(self.recurring(), self.calendars())
+                .handle(CancelRecurringEventCommand::new(id))
+                .await?;
+        } else {
+            RestoreRecurringEventHandler::new(self.recurring(), self.calendars())
+                .handle(RestoreRecurringEventCommand::new(id))
+                .await?;
+        }
+
+        Ok(Value::Null)
+    }
+
+    async fn add_recurring_tag(&self, params: WithId<TagNameDto>) -> RpcResult {
+        AddRecurringTagHandler::new(self.recurring(), self.calendars())
+            .handle(AddRecurringTagCommand::new(EventId::from_uuid(params.id), params.body.tag()?))
+            .await?;
+        Ok(Value::Null)
+    }
+
+    async fn remove_recurring_tag(&self, params: WithId<TagNameDto>) -> RpcResult {
+        RemoveRecurringTagHandler::new(self.recurring(), self.calendars())
+            .handle(RemoveRecurringTagCommand::new(EventId::from_uuid(params.id), params.body.tag()?))
+            .await?;
+        Ok(Value::Null)
+    }
+
+    async fn delete_recurring(&self, params: IdParams) -> RpcResult {
+        DeleteRecurringEventHandler::new(self.recurring(), self.calendars())
+            .handle(DeleteRecurringEventCommand::new(EventId::from_uuid(params.id)))
+            .await?;
+        Ok(Value::Null)
+    }
+
+    async fn list_occurrences(&self, params: WithId<RangeParams>) -> RpcResult {
+        let range = range(params.body.from, params.body.to)?
+            .ok_or_else(|| RpcError::InvalidParams("`from` and `to` are required".to_string()))?;
+
+        let event = self
+            .recurring()
+            .find_by_id(&EventId::from_uuid(params.id))
+            .await
+            .map_err(ApplicationError::from_recurring_lookup)?;
+
+        to_value(
+            event
+                .occurrences_in(&range)
+                .iter()
+                .map(OccurrenceDto::from)
+                .collect::<Vec<_>>(),
+        )
+    }
+
+    async fn cancel_occurrence(&self, params: OccurrenceParams) -> RpcResult {
+        CancelRecurringOccurrenceHandler::new(self.recurring(), self.calendars())
+            .handle(CancelRecurringOccurrenceCommand::new(
+                EventId::from_uuid(params.id),
+                params.original_starts_at,
+            ))
+            .await?;
+        Ok(Value::Null)
+    }
+
+    async fn reschedule_occurrence(&self, params: WithId<OccurrenceExceptionDto>) -> RpcResult {
+        let dto = params.body;
+        let (Some(starts_at), Some(ends_at)) = (dto.starts_at, dto.ends_at) else {
+            return Err(RpcError::InvalidParams(
+                "`starts_at` and `ends_at` are required".to_string(),
+            ));
+        };
+
+        let command = RescheduleRecurringOccurrenceCommand::new(
+            EventId::from_uuid(params.id),
+            dto.original_starts_at,
+            TimeRange::new(starts_at, ends_at)?,
+        );
+
+        RescheduleRecurringOccurrenceHandler::new(self.recurring(), self.calendars())
+            .handle(command)
+            .await?;
+        Ok(Value::Null)
+    }
+
+    async fn restore_occurrence(&self, params: OccurrenceParams) -> RpcResult {
+        RestoreRecurringOccurrenceHandler::new(self.recurring(), self.calendars())
+            .handle(RestoreRecurringOccurrenceCommand::new(
+                EventId::from_uuid(params.id),
+                params.original_starts_at,
+            ))
+            .await?;
+        Ok(Value::Null)
+    }
+
+    async fn set_occurrence_location(&self, params: WithId<OccurrenceLocationDto>) -> RpcResult {
+        SetRecurringOccurrenceLocationHandler::new(self.recurring(), self.calendars())
+            .handle(SetRecurringOccurrenceLocationCommand::new(
+                EventId::from_uuid(params.id),
+                params.body.original_starts_at,
+                params.body.location,
+            ))
+            .await?;
+        Ok(Value::Null)
+    }
+
+    async fn add_recurring_reminder(&self, params: WithId<CreateReminderDto>) -> RpcResult {
+        let dto = params.body;
+        let command = AddRecurringReminderCommand::new(
+            EventId::from_uuid(params.id),
+            dto.trigger()?,
+            dto.action()?,
+            dto.description.clone(),
+            dto.repeat,
+            dto.repeat_interval()?,
+        );
+
+        let reminder_id = AddRecurringReminderHandler::new(self.recurring(), self.calendars())
+            .handle(command)
+            .await?;
+        to_value(CreatedDto { id: reminder_id.as_uuid() })
+    }
+
+    async fn remove_recurring_reminder(&self, params: ReminderParams) -> RpcResult {
+        RemoveRecurringReminderHandler::new(self.recurring(), self.calendars())
+            .handle(RemoveRecurringReminderCommand::new(
+                EventId::from_uuid(params.id),
+                ReminderId::from_uuid(params.reminder_id),
+            ))
+            .await?;
+        Ok(Value::Null)
+    }
+
+    async fn set_recurring_organizer(&self, params: WithId<SetOrganizerDto>) -> RpcResult {
+        let command = SetRecurringOrganizerCommand::new(
+            EventId::from_uuid(params.id),
+            params.body.organizer()?,
+        );
+
+        SetRecurringOrganizerHandler::new(self.recurring(), self.calendars())
+            .handle(command)
+            .await?;
+        Ok(Value::Null)
+    }
+
+    async fn add_recurring_attendee(&self, params: WithId<CreateAttendeeDto>) -> RpcResult {
+        let command = AddRecurringAttendeeCommand::new(
+            EventId::from_uuid(params.id),
+            params.body.attendee()?,
+        );
+
+        AddRecurringAttendeeHandler::new(self.recurring(), self.calendars())
+            .handle(command)
+            .await?;
+        Ok(Value::Null)
+    }
+
+    async fn remove_recurring_attendee(&self, params: AttendeeParams) -> RpcResult {
+        RemoveRecurringAttendeeHandler::new(self.recurring(), self.calendars())
+            .handle(RemoveRecurringAttendeeCommand::new(
+                EventId::from_uuid(params.id),
+                params.email,
+            ))
+            .await?;
+        Ok(Value::Null)
+    }
+
+    async fn update_recurring_attendee_status(&self, params: WithAttendee<AttendeeStatusDto>) -> RpcResult {
+        let command = UpdateRecurringAttendeeStatusCommand::new(
+            EventId::from_uuid(params.id),
+            params.email,
+            params.body.status.parse()?,
+        );
+
+        UpdateRecurringAttendeeStatusHandler::new(self.recurring(), self.calendars())
+            .handle(command)
+            .await?;
+        Ok(Value::Null)
+    }
+
+
+    // ==================================================
+    // Reminder instances
+    // ==================================================
+
+    async fn snooze_reminder(&self, params: WithReminder<SnoozeReminderDto>) -> RpcResult {
+        let command = SnoozeReminderCommand::new(
+            EventId::from_uuid(params.id),
+            ReminderId::from_uuid(params.reminder_id),
+            params.body.until()?,
+        )
+        .with_occurrence(params.body.original_starts_at);
+
+        let state = SnoozeReminderHandler::new(self.events(), self.recurring(), self.reminder_states())
+            .handle(command)
+            .await?;
+        to_value(ReminderStateDto::from(&state))
+    }
+
+    async fn dismiss_reminder(&self, params: WithReminder<DismissReminderDto>) -> RpcResult {
+        let command = DismissReminderCommand::new(
+            EventId::from_uuid(params.id),
+            ReminderId::from_uuid(params.reminder_id),
+        )
+        .with_occurrence(params.body.original_starts_at);
+
+        let state = DismissReminderHandler::new(self.events(), self.recurring(), self.reminder_states())
+            .handle(command)
+            .await?;
+        to_value(ReminderStateDto::from(&state))
+    }
+
+
+    // ==================================================
+    // Tasks
+    // ==================================================
+
+    async fn list_tasks(&self, params: TaskListParams) -> RpcResult {
+        let tasks = self.find_tasks(&params).await?;
+        to_value(tasks.iter().map(TaskDto::from).collect::<Vec<_>>())
+    }
+
+    async fn get_task(&self, params: IdParams) -> RpcResult {
+        let task = self
+            .tasks()
+            .find_by_id(&TaskId::from_uuid(params.id))
+            .await?
+            .ok_or(ApplicationError::TaskNotFound)?;
+        to_value(TaskDto::from(&task))
+    }
+
+    async fn create_task(&self, params: InCalendar<CreateTaskDto>) -> RpcResult {
+        let dto = params.body;
+        let rule = dto.recurrence.as_ref().map(RecurrenceDto::rule).transpose()?;
+        let command = CreateTaskCommand::new(
+            CalendarId::from_uuid(params.calendar_id),
+            dto.title,
+            dto.description,
+            dto.starts_at,
+            dto.due_at,
+        )
+        .with_priority(dto.priority)
+        .with_rule(rule);
+
+        let id = CreateTaskHandler::new(self.tasks(), self.calendars())
+            .handle(command)
+            .await?;
+        to_value(CreatedDto { id: id.as_uuid() })
+    }
+
+    async fn update_task(&self, params: WithId<UpdateTaskDto>) -> RpcResult {
+        let dto = params.body;
+        let mut command = UpdateTaskCommand::new(TaskId::from_uuid(params.id));
+
+        if let Some(title) = dto.title {
+            command = command.with_title(title);
+        }
+        if let Some(description) = dto.description {
+            command = command.with_description(description);
+        }
+        if let Some(starts_at) = dto.starts_at {
+            command = command.with_starts_at(starts_at);
+        }
+        if let Some(due_at) = dto.due_at {
+            command = command.with_due_at(due_at);
+        }
+        if let Some(priority) = dto.priority {
+            command = command.with_priority(priority);
+        }
+        if let Some(percent_complete) = dto.percent_complete {
+            command = command.with_percent_complete(percent_complete);
+        }
+        if let Some(recurrence) = dto.recurrence {
+            command = command.with_rule(recurrence.as_ref().map(RecurrenceDto::rule).transpose()?);
+        }
+
+        UpdateTaskHandler::new(self.tasks(), self.calendars())
+            .handle(command)
+            .await?;
+
+        self.get_task(IdParams { id: params.id }).await
+    }
+
+    async fn complete_task(&self, params: IdParams) -> RpcResult {
+        let task = CompleteTaskHandler::new(self.tasks(), self.calendars())
+            .handle(CompleteTaskCommand::new(TaskId::from_uuid(params.id)))
+            .await?;
+        to_value(TaskDto::from(&task))
+    }
+
+    async fn reopen_task(&self, params: IdParams) -> RpcResult {
+        ReopenTaskHandler::new(self.tasks(), self.calendars())
+            .handle(ReopenTaskCommand::new(TaskId::from_uuid(params.id)))
+            .await?;
+
+        self.get_task(params).await
+    }
+
+    async fn delete_task(&self, params: IdParams) -> RpcResult {
+        DeleteTaskHandler::new(self.tasks(), self.calendars())
+            .handle(DeleteTaskCommand::new(TaskId::from_uuid(params.id)))
+            .await?;
+
+        Ok(Value::Null)
+    }
+
+    async fn import_tasks(&self, params: InCalendar<ImportTasksDto>) -> RpcResult {
+        let calendar_id = CalendarId::from_uuid(params.calendar_id);
+        let calendar = Component::parse(&params.body.ics)?;
+        let (tasks, skipped) = IcalMapper::tasks_to_domain(&calendar, calendar_id);
+
+        let imported = ImportTasksHandler::new(self.tasks(), self.calendars())
+            .handle(ImportTasksCommand::new(calendar_id, tasks))
+            .await?;
+
+        to_value(ImportReportDto {
+            imported,
+            skipped: skipped
+                .into_iter()
+                .map(|(uid, e)| SkippedDto { uid, reason: e.to_string() })
+                .collect(),
+        })
+    }
+
+    /// The calendar's tasks as a `.ics` document.
+    async fn export_tasks(&self, params: TaskListParams) -> RpcResult {
+        let tasks = self.find_tasks(&params).await?;
+        to_value(IcalMapper::tasks_to_ics(&tasks))
+    }
+
+    async fn find_tasks(&self, params: &TaskListParams) -> Result<Vec<Task>, RpcError> {
+        let calendar_id = CalendarId::from_uuid(params.calendar_id);
+        self.ensure_calendar(&calendar_id).await?;
+
+        let mut tasks = self.tasks().find_by_calendar(&calendar_id).await?;
+        if params.open {
+            tasks.retain(Task::is_open);
+        }
+
+        Ok(tasks)
+    }
+
+
+    // ==================================================
+    // Journal
+    // ==================================================
+
+    async fn list_journal(&self, params: JournalListParams) -> RpcResult {
+        let entries = self.find_journal(&params).await?;
+        to_value(entries.iter().map(JournalEntryDto::from).collect::<Vec<_>>())
+    }
+
+    async fn get_journal_entry(&self, params: IdParams) -> RpcResult {
+        let entry = self
+            .journal()
+            .find_by_id(&JournalId::from_uuid(params.id))
+            .await?
+            .ok_or(ApplicationError::JournalEntryNotFound)?;
+        to_value(JournalEntryDto::from(&entry))
+    }
+
+    async fn create_journal_entry(&self, params: InCalendar<CreateJournalEntryDto>) -> RpcResult {
+        let dto = params.body;
+        let command = CreateJournalEntryCommand::new(
+            CalendarId::from_uuid(params.calendar_id),
+            dto.date,
+            dto.title,
+            dto.body,
+        )
+        .with_link(dto.link.as_ref().map(JournalLinkDto::link));
+
+        let id = CreateJournalEntryHandler::new(self.journal(), self.events(), self.recurring(), self.calendars())
+            .handle(command)
+            .await?;
+        to_value(CreatedDto { id: id.as_uuid() })
+    }
+
+    async fn update_journal_entry(&self, params: WithId<UpdateJournalEntryDto>) -> RpcResult {
+        let dto = params.body;
+        let mut command = UpdateJournalEntryCommand::new(JournalId::from_uuid(params.id));
+
+        if let Some(date) = dto.date {
+            command = command.with_date(date);
+        }
+        if let Some(title) = dto.title {
+            command = command.with_title(title);
+        }
+        if let Some(body) = dto.body {
+            command = command.with_body(body);
+        }
+        if let Some(link) = dto.link {
+            command = command.with_link(link.as_ref().map(JournalLinkDto::link));
+        }
+
+        UpdateJournalEntryHandler::new(self.journal(), self.events(), self.recurring(), self.calendars())
+            .handle(command)
+            .await?;
+
+        self.get_journal_entry(IdParams { id: params.id }).await
+    }
+
+    async fn delete_journal_entry(&self, params: IdParams) -> RpcResult {
+        DeleteJournalEntryHandler::new(self.journal(), self.calendars())
+            .handle(DeleteJournalEntryCommand::new(JournalId::from_uuid(params.id)))
+            .await?;
+
+        Ok(Value::Null)
+    }
+
+    /// Entries about an event or series, or one of its occurrences.
+    async fn journal_for_event(&self, params: IdParams) -> RpcResult {
+        let entries = self.journal().find_by_event(&EventId::from_uuid(params.id)).await?;
+        to_value(entries.iter().map(JournalEntryDto::from).collect::<Vec<_>>())
+    }
+
+    async fn search_journal(&self, params: JournalSearchParams) -> RpcResult {
+        let calendar_id = params.calendar_id.map(CalendarId::from_uuid);
+        if let Some(calendar_id) = &calendar_id {
+            self.ensure_calendar(calendar_id).await?;
+        }
+
+        let entries = self.journal().search(&params.query, calendar_id.as_ref()).await?;
+        to_value(entries.iter().map(JournalEntryDto::from).collect::<Vec<_>>())
+    }
+
+    async fn import_journal(&self, params: InCalendar<ImportJournalDto>) -> RpcResult {
+        let calendar_id = CalendarId::from_uuid(params.calendar_id);
+        let calendar = Component::parse(&params.body.ics)?;
+        let (entries, skipped) = IcalMapper::journal_to_domain(&calendar, calendar_id);
+
+        let imported = ImportJournalEntriesHandler::new(
+            self.journal(),
+            self.events(),
+            self.recurring(),
+            self.calendars(),
+        )
+        .handle(ImportJournalEntriesCommand::new(calendar_id, entries))
+        .await?;
+
+        to_value(ImportReportDto {
+            imported,
+            skipped: skipped
+                .into_iter()
+                .map(|(uid, e)| SkippedDto { uid, reason: e.to_string() })
+                .collect(),
+        })
+    }
+
+    /// The calendar's journal entries as a `.ics` document.
+    async fn export_journal(&self, params: JournalListParams) -> RpcResult {
+        let entries = self.find_journal(&params).await?;
+        to_value(IcalMapper::journal_to_ics(&entries))
+    }
+
+    async fn find_journal(&self, params: &JournalListParams) -> Result<Vec<JournalEntry>, RpcError> {
+        let calendar_id = CalendarId::from_uuid(params.calendar_id);
+        self.ensure_calendar(&calendar_id).await?;
+
+        Ok(self.journal().find_by_calendar(&calendar_id, params.from, params.to).await?)
+    }
+
+    // ==================================================
+    // Tags
+    // ==================================================
+
+    async fn list_tags(&self) -> RpcResult {
+        let tags = self.tags().find_all().await?;
+        to_value(tags.iter().map(TagDto::from).collect::<Vec<_>>())
+    }
+
+    async fn rename_tag(&self, params: RetagDto) -> RpcResult {
+        let (from, to) = params.tags()?;
+        let outcome = RenameTagHandler::new(
+            self.events(),
+            self.recurring(),
+            self.tags(),
+            self.calendars(),
+        )
+        .handle(RenameTagCommand::new(from, to))
+        .await?;
+        to_value(RetagOutcomeDto::from(outcome))
+    }
+
+    async fn merge_tag(&self, params: RetagDto) -> RpcResult {
+        let (from, into) = params.tags()?;
+        let outcome = MergeTagHandler::new(
+            self.events(),
+            self.recurring(),
+            self.tags(),
+            self.calendars(),
+        )
+        .handle(MergeTagCommand::new(from, into))
+        .await?;
+        to_value(RetagOutcomeDto::from(outcome))
+    }
+
+
+    // ==================================================
+    // Trash
+    // ==================================================
+
+    async fn list_trash(&self) -> RpcResult {
+        let mut items: Vec<TrashedItemDto> = Vec::new();
+        items.extend(self.calendars().find_trashed().await?.iter().map(TrashedItemDto::from));
+        items.extend(self.events().find_trashed().await?.iter().map(TrashedItemDto::from));
+        items.extend(self.recurring().find_trashed().await?.iter().map(TrashedItemDto::from));
+        items.sort_by(|a, b| b.deleted_at.cmp(&a.deleted_at).then_with(|| a.title.cmp(&b.title)));
+        to_value(items)
+    }
+
+    /// Ids are unique across kinds, so the first kind that has the id in
+    /// its trash is the one restored.
+    async fn restore_trashed(&self, params: IdParams) -> RpcResult {
+        let restored = RestoreTrashedCalendarHandler::new(self.calendars())
+            .handle(RestoreTrashedCalendarCommand::new(CalendarId::from_uuid(params.id)))
+            .await;
+        let restored = match restored {
+            Err(ApplicationError::NotInTrash) => {
+                RestoreTrashedEventHandler::new(self.events(), self.calendars())
+                    .handle(RestoreTrashedEventCommand::new(EventId::from_uuid(params.id)))
+                    .await
+            }
+            other => other,
+        };
+        let restored = match restored {
+            Err(ApplicationError::NotInTrash) => {
+                RestoreTrashedRecurringEventHandler::new(self.recurring(), self.calendars())
+                    .handle(RestoreTrashedRecurringEventCommand::new(EventId::from_uuid(params.id)))
+                    .await
+            }
+            other => other,
+        };
+
+        restored?;
+        Ok(Value::Null)
+    }
+
+    async fn purge_trash(&self, params: PurgeTrashParams) -> RpcResult {
+        let cutoff = params.before.unwrap_or_else(chrono::Utc::now);
+        let outcome = PurgeTrashHandler::new(
+            self.scope.unrecorded(self.uow.calendars()),
+            self.scope.unrecorded(self.uow.events()),
+            self.scope.unrecorded(self.uow.recurring()),
+        )
+        .handle(PurgeTrashCommand::new(cutoff))
+        .await?;
+        to_value(PurgeOutcomeDto::from(outcome))
+    }
+
+
+    // ==================================================
+    // History
+    // ==================================================
+
+    async fn list_history(&self, params: HistoryListParams) -> RpcResult {
+        let entries = self
+            .history()
+            .find_applied(params.limit.unwrap_or(DEFAULT_HISTORY_LIMIT))
+            .await?;
+        to_value(entries.iter().map(HistoryEntryDto::from).collect::<Vec<_>>())
+    }
+
+    // Undo and redo replay through repositories that are not recorded, so
+    // they move the history cursor instead of adding entries; what they
+    // change is still published
+
+    async fn undo(&self) -> RpcResult {
+        let entry = UndoHandler::new(
+            self.scope.unrecorded(self.uow.calendars()),
+            self.scope.unrecorded(self.uow.events()),
+            self.scope.unrecorded(self.uow.recurring()),
+            self.uow.tasks(),
+            self.uow.journal(),
+            self.history(),
+        )
+        .handle(UndoCommand)
+        .await?;
+        to_value(HistoryEntryDto::from(&entry))
+    }
+
+    async fn redo(&self) -> RpcResult {
+        let entry = RedoHandler::new(
+            self.scope.unrecorded(self.uow.calendars()),
+            self.scope.unrecorded(self.uow.events()),
+            self.scope.unrecorded(self.uow.recurring()),
+            self.uow.tasks(),
+            self.uow.journal(),
+            self.history(),
+        )
+        .handle(RedoCommand)
+        .await?;
+        to_value(HistoryEntryDto::from(&entry))
+    }
+
+
+    // ==================================================
+    // Audit log
+    // ==================================================
+
+    // Entries outlive what they describe, so an unknown or deleted id
+    // is not an error
+
+    async fn event_audit(&self, params: AuditParams) -> RpcResult {
+        let entries = self
+            .audit()
+            .find_by_event(&EventId::from_uuid(params.id), params.limit.unwrap_or(DEFAULT_AUDIT_LIMIT))
+            .await?;
+        to_value(entries.iter().map(AuditEntryDto::from).collect::<Vec<_>>())
+    }
+
+    async fn calendar_audit(&self, params: AuditParams) -> RpcResult {
+        let entries = self
+            .audit()
+            .find_by_calendar(&CalendarId::from_uuid(params.id), params.limit.unwrap_or(DEFAULT_AUDIT_LIMIT))
+            .await?;
+        to_value(entries.iter().map(AuditEntryDto::from).collect::<Vec<_>>())
+    }
+
+
+    // ==================================================
+    // Scheduling
+    // ==================================================
+
+    async fn itip_message(&self, params: IdParams) -> RpcResult {
+        let object = self.find_object(EventId::from_uuid(params.id)).await?;
+        to_value(ItipMessageDto::from(&ItipMessage::for_object(&object)?))
+    }
+
+    async fn apply_reply(&self, params: ItipReplyDto) -> RpcResult {
+        let reply = ItipReply::parse(&params.ics)?;
+        let command = ApplyReplyCommand::new(
+            *reply.event_id(),
+            reply.attendee().clone(),
+            *reply.status(),
+            *reply.sequence(),
+        );
+
+        let outcome = ApplyReplyHandler::new(self.events(), self.recurring(), self.calendars())
+            .handle(command)
+            .await?;
+        to_value(ReplyOutcomeDto::from(outcome))
+    }
+
+    async fn find_object(&self, id: EventId) -> Result<CalendarObject, RpcError> {
+        if let Some(event) = self.events().find_by_id(&id).await? {
+            return Ok(CalendarObject::Event(event));
+        }
+
+        match self.recurring().find_by_id(&id).await {
+            Ok(series) => Ok(CalendarObject::Recurring(series)),
+            Err(RepositoryError::NotFound) => Err(ApplicationError::EventNotFound.into()),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    async fn ensure_calendar(&self, calendar_id: &CalendarId) -> Result<(), RpcError> {
+        self.calendars()
+            .find_by_id(calendar_id)
+            .await?
+            .ok_or(ApplicationError::CalendarNotFound)?;
+        Ok(())
+    }
+}
+
+fn parse<T: DeserializeOwned>(params: Value) -> Result<T, RpcError> {
+    serde_json::from_value(params).map_err(|e| RpcError::InvalidParams(e.to_string()))
+}
+
+fn to_value(value: impl Serialize) -> RpcResult {
+    Ok(serde_json::to_value(value).expect("DTOs always serialize"))
+}
+
+fn range(
+    from: Option<chrono::DateTime<chrono::Utc>>,
+    to: Option<chrono::DateTime<chrono::Utc>>,
+) -> Result<Option<TimeRange>, RpcError> {
+    match (from, to) {
+        (None, None) => Ok(None),
+        (Some(from), Some(to)) => Ok(Some(TimeRange::new(from, to)?)),
+        _ => Err(RpcError::InvalidParams("`from` and `to` must be given together".to_string())),
+    }
+}
@@ -0,0 +1,99 @@
+use serde_json::json;
+use thiserror::Error;
+
+use crate::{
+    application::error::ApplicationError,
+    domain::{error::DomainError, repository::RepositoryError},
+    infrastructure::{dto::error_code, ical::IcalError, itip::ItipError},
+};
+
+use super::protocol::RpcErrorObject;
+
+pub const PARSE_ERROR: i64 = -32700;
+pub const INVALID_REQUEST: i64 = -32600;
+pub const METHOD_NOT_FOUND: i64 = -32601;
+pub const INVALID_PARAMS: i64 = -32602;
+pub const APPLICATION_ERROR: i64 = -32000;
+
+#[derive(Debug, Error)]
+pub enum RpcError {
+    #[error("Parse error: {0}")]
+    Parse(String),
+
+    #[error("Invalid request: {0}")]
+    InvalidRequest(String),
+
+    #[error("Method not found: {0}")]
+    MethodNotFound(String),
+
+    #[error("Invalid params: {0}")]
+    InvalidParams(String),
+
+    #[error(transparent)]
+    Application(#[from] ApplicationError),
+
+    /// An error object returned by the daemon.
+    #[error("{}", .0.message)]
+    Remote(RpcErrorObject),
+
+    #[error("Transport error: {0}")]
+    Transport(String),
+}
+
+impl RpcError {
+    /// Application errors share one JSON-RPC code; `data.code` tells
+    /// them apart.
+    pub fn to_object(&self) -> RpcErrorObject {
+        match self {
+            RpcError::Remote(object) => object.clone(),
+            RpcError::Application(e) => RpcErrorObject {
+                code: APPLICATION_ERROR,
+                message: self.to_string(),
+                data: Some(json!({ "code": error_code(e) })),
+            },
+            other => RpcErrorObject {
+                code: match other {
+                    RpcError::Parse(_) => PARSE_ERROR,
+                    RpcError::InvalidRequest(_) => INVALID_REQUEST,
+                    RpcError::MethodNotFound(_) => METHOD_NOT_FOUND,
+                    RpcError::InvalidParams(_) => INVALID_PARAMS,
+                    _ => APPLICATION_ERROR,
+                },
+                message: self.to_string(),
+                data: None,
+            },
+        }
+    }
+}
+
+impl From<DomainError> for RpcError {
+    fn from(error: DomainError) -> Self {
+        RpcError::Application(ApplicationError::Domain(error))
+    }
+}
+
+impl From<RepositoryError> for RpcError {
+    fn from(error: RepositoryError) -> Self {
+        RpcError::Application(error.into())
+    }
+}
+
+impl From<std::io::Error> for RpcError {
+    fn from(error: std::io::Error) -> Self {
+        RpcError::Transport(error.to_string())
+    }
+}
+
+/// Unschedulable objects and malformed messages are the caller's problem.
+impl From<ItipError> for RpcError {
+    fn from(error: ItipError) -> Self {
+        RpcError::Application(ApplicationError::Validation(error.to_string()))
+    }
+}
+
+/// Documents that cannot be parsed are the caller's problem.
+impl From<IcalError> for RpcError {
+    fn from(error: IcalError) -> Self {
+        RpcError::Application(ApplicationError::Validation(error.to_string()))
+    }
+}
@@ -0,0 +1,16 @@
+pub mod protocol;
+pub mod params;
+pub mod dispatcher;
+pub mod error;
+#[cfg(unix)]
+pub mod server;
+#[cfg(unix)]
+pub mod client;
+
+pub use protocol::{RpcErrorObject, RpcRequest, RpcResponse};
+pub use dispatcher::RpcDispatcher;
+pub use error::RpcError;
+#[cfg(unix)]
+pub use server::RpcServer;
+#[cfg(unix)]
+pub use client::RpcClient;
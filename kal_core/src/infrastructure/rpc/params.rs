@@ -0,0 +1,160 @@
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::Deserialize;
+use uuid::Uuid;
+
+use crate::{
+    domain::{error::DomainError, tag::TagFilter},
+    infrastructure::dto::parse_tags,
+};
+
+#[derive(Debug, Deserialize)]
+pub struct IdParams {
+    pub id: Uuid,
+}
+
+/// Method params that add an `id` to a request DTO.
+#[derive(Debug, Deserialize)]
+pub struct WithId<T> {
+    pub id: Uuid,
+    #[serde(flatten)]
+    pub body: T,
+}
+
+/// Method params that add a `calendar_id` to a request DTO.
+#[derive(Debug, Deserialize)]
+pub struct InCalendar<T> {
+    pub calendar_id: Uuid,
+    #[serde(flatten)]
+    pub body: T,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct RangeParams {
+    pub from: Option<DateTime<Utc>>,
+    pub to: Option<DateTime<Utc>>,
+    /// Keeps only objects whose location contains this text.
+    pub location: Option<String>,
+    /// Keeps only objects carrying all of these tags.
+    #[serde(default)]
+    pub tags_all: Vec<String>,
+    /// Keeps only objects carrying at least one of these tags.
+    #[serde(default)]
+    pub tags_any: Vec<String>,
+    /// Drops objects carrying any of these tags.
+    #[serde(default)]
+    pub tags_none: Vec<String>,
+}
+
+impl RangeParams {
+    pub fn tag_filter(&self) -> Result<TagFilter, DomainError> {
+        Ok(TagFilter::new(
+            parse_tags(&self.tags_all)?,
+            parse_tags(&self.tags_any)?,
+            parse_tags(&self.tags_none)?,
+        ))
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct TaskListParams {
+    pub calendar_id: Uuid,
+    /// Keeps only tasks that still need doing.
+    #[serde(default)]
+    pub open: bool,
+}
+
+/// A calendar's journal entries, optionally only those dated within
+/// `from..=to`.
+#[derive(Debug, Deserialize)]
+pub struct JournalListParams {
+    pub calendar_id: Uuid,
+    pub from: Option<NaiveDate>,
+    pub to: Option<NaiveDate>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct JournalSearchParams {
+    pub query: String,
+    pub calendar_id: Option<Uuid>,
+}
+
+/// A full-text search over events and series, optionally within one
+/// calendar and a `from`/`to` window (both or neither).
+#[derive(Debug, Deserialize)]
+pub struct EventSearchParams {
+    pub query: String,
+    pub calendar_id: Option<Uuid>,
+    pub from: Option<DateTime<Utc>>,
+    pub to: Option<DateTime<Utc>>,
+    #[serde(default = "default_search_limit")]
+    pub limit: usize,
+}
+
+fn default_search_limit() -> usize {
+    50
+}
+
+/// A calendar and the window to look up busy time in.
+#[derive(Debug, Deserialize)]
+pub struct FreeBusyParams {
+    pub id: Uuid,
+    pub from: DateTime<Utc>,
+    pub to: DateTime<Utc>,
+}
+
+/// Purges what was trashed before `before`, or everything when unset.
+#[derive(Debug, Deserialize)]
+pub struct PurgeTrashParams {
+    pub before: Option<DateTime<Utc>>,
+}
+
+/// Lists the last `limit` commands that can be undone, most recent first.
+#[derive(Debug, Deserialize)]
+pub struct HistoryListParams {
+    pub limit: Option<u32>,
+}
+
+/// Lists the last `limit` audit entries for an event, series or
+/// calendar, most recent first.
+#[derive(Debug, Deserialize)]
+pub struct AuditParams {
+    pub id: Uuid,
+    pub limit: Option<u32>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct OccurrenceParams {
+    pub id: Uuid,
+    pub original_starts_at: DateTime<Utc>,
+}
+
+
+#[derive(Debug, Deserialize)]
+pub struct ReminderParams {
+    pub id: Uuid,
+    pub reminder_id: Uuid,
+}
+
+/// `ReminderParams` plus a request DTO.
+#[derive(Debug, Deserialize)]
+pub struct WithReminder<T> {
+    pub id: Uuid,
+    pub reminder_id: Uuid,
+    #[serde(flatten)]
+    pub body: T,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct AttendeeParams {
+    pub id: Uuid,
+    pub email: String,
+}
+
+/// `AttendeeParams` plus a request DTO.
+#[derive(Debug, Deserialize)]
+pub struct WithAttendee<T> {
+    pub id: Uuid,
+    pub email: String,
+    #[serde(flatten)]
+    pub body: T,
+}
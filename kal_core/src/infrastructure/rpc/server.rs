@@ -0,0 +1,108 @@
+use std::sync::Arc;
+
+use sqlx::SqlitePool;
+use tokio::{
+    io::{AsyncBufReadExt, AsyncWriteExt, BufReader},
+    net::{UnixListener, UnixStream},
+};
+
+use crate::application::publisher::Publisher;
+
+use super::dispatcher::RpcDispatcher;
+
+/// Serves newline-delimited JSON-RPC 2.0 over a Unix socket. Each
+/// request is one line; each response is written back as one line.
+/// Calls are attributed to the user connected, going by the socket's peer
+/// credentials.
+pub struct RpcServer {
+    dispatcher: RpcDispatcher,
+}
+
+impl RpcServer {
+    pub fn new(pool: SqlitePool) -> Self {
+        Self {
+            dispatcher: RpcDispatcher::new(pool),
+        }
+    }
+
+    /// Publishes the domain events calls raise to `publisher`'s subscribers.
+    pub fn with_publisher(mut self, publisher: Publisher) -> Self {
+        self.dispatcher = self.dispatcher.with_publisher(publisher);
+        self
+    }
+
+    /// Accepts connections until the listener fails.
+    pub async fn serve(self, listener: UnixListener) -> std::io::Result<()> {
+        let server = Arc::new(self);
+
+        loop {
+            let (stream, _) = listener.accept().await?;
+            let server = Arc::clone(&server);
+
+            tokio::spawn(async move {
+                // A client dropping its connection is not a server error
+                let _ = server.serve_connection(stream).await;
+            });
+        }
+    }
+
+    async fn serve_connection(&self, stream: UnixStream) -> std::io::Result<()> {
+        // Calls are made by whoever is on the other end of the socket
+        let dispatcher = match peer_user(&stream) {
+            Some(user) => self.dispatcher.clone().with_actor(user),
+            None => self.dispatcher.clone(),
+        };
+        let (reader, mut writer) = stream.into_split();
+        let mut lines = BufReader::new(reader).lines();
+
+        while let Some(line) = lines.next_line().await? {
+            if line.trim().is_empty() {
+                continue;
+            }
+
+            if let Some(response) = dispatcher.handle_line(&line).await {
+                writer.write_all(response.as_bytes()).await?;
+                writer.write_all(b"\n").await?;
+                writer.flush().await?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// The name of the user connected through `stream`, from the credentials
+/// the kernel keeps for the socket; their uid when the system has no name
+/// for it.
+fn peer_user(stream: &UnixStream) -> Option<String> {
+    let uid = stream.peer_cred().ok()?.uid();
+    Some(user_name(uid).unwrap_or_else(|| uid.to_string()))
+}
+
+/// Looks `uid` up the way the rest of the system does, through whatever
+/// user databases the C library is configured with.
+fn user_name(uid: u32) -> Option<String> {
+    let mut buffer = vec![0 as libc::c_char; 1024];
+    loop {
+        // SAFETY: every pointer is to memory owned here and outlives the
+        // call, and `buffer.len()` is the size of the buffer passed
+        let mut entry: libc::passwd = unsafe { std::mem::zeroed() };
+        let mut found = std::ptr::null_mut();
+        let status = unsafe {
+            libc::getpwuid_r(uid, &mut entry, buffer.as_mut_ptr(), buffer.len(), &mut found)
+        };
+
+        if status == libc::ERANGE && buffer.len() < 1 << 20 {
+            buffer.resize(buffer.len() * 2, 0);
+            continue;
+        }
+        if status != 0 || found.is_null() || entry.pw_name.is_null() {
+            return None;
+        }
+
+        // SAFETY: on success `pw_name` points to a NUL-terminated string
+        // inside `buffer`
+        let name = unsafe { std::ffi::CStr::from_ptr(entry.pw_name) };
+        return name.to_str().ok().map(str::to_string);
+    }
+}
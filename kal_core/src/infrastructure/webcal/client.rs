@@ -0,0 +1,77 @@
+use reqwest::{header, Client, StatusCode};
+
+use super::error::WebcalError;
+
+#[derive(Debug, Clone)]
+pub enum FetchResult {
+    NotModified,
+    Fetched {
+        body: String,
+        etag: Option<String>,
+        last_modified: Option<String>,
+    },
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct WebcalClient {
+    http: Client,
+}
+
+impl WebcalClient {
+    pub fn new() -> Self {
+        Self { http: Client::new() }
+    }
+
+    /// Fetches a feed, using `etag` and `last_modified` from the previous
+    /// fetch for a conditional request.
+    pub async fn fetch(
+        &self,
+        url: &str,
+        etag: Option<&str>,
+        last_modified: Option<&str>,
+    ) -> Result<FetchResult, WebcalError> {
+        let mut request = self
+            .http
+            .get(url)
+            .header(header::ACCEPT, "text/calendar");
+
+        if let Some(etag) = etag {
+            request = request.header(header::IF_NONE_MATCH, etag);
+        }
+
+        if let Some(last_modified) = last_modified {
+            request = request.header(header::IF_MODIFIED_SINCE, last_modified);
+        }
+
+        let response = request.send().await?;
+        let status = response.status();
+
+        if status == StatusCode::NOT_MODIFIED {
+            return Ok(FetchResult::NotModified);
+        }
+
+        if !status.is_success() {
+            return Err(WebcalError::Status {
+                status: status.as_u16(),
+                url: url.to_string(),
+            });
+        }
+
+        let etag = header_value(&response, header::ETAG);
+        let last_modified = header_value(&response, header::LAST_MODIFIED);
+
+        Ok(FetchResult::Fetched {
+            body: response.text().await?,
+            etag,
+            last_modified,
+        })
+    }
+}
+
+fn header_value(response: &reqwest::Response, name: header::HeaderName) -> Option<String> {
+    response
+        .headers()
+        .get(name)
+        .and_then(|v| v.to_str().ok())
+        .map(str::to_string)
+}
@@ -0,0 +1,36 @@
+use thiserror::Error;
+
+use crate::{
+    domain::{error::DomainError, repository::RepositoryError},
+    infrastructure::ical::IcalError,
+};
+
+#[derive(Debug, Error)]
+pub enum WebcalError {
+    #[error("HTTP error: {0}")]
+    Http(String),
+
+    #[error("Unexpected status {status} fetching {url}")]
+    Status { status: u16, url: String },
+
+    #[error("Calendar not found")]
+    CalendarNotFound,
+
+    #[error("Calendar is not a subscription")]
+    NotSubscribed,
+
+    #[error(transparent)]
+    Domain(#[from] DomainError),
+
+    #[error(transparent)]
+    Ical(#[from] IcalError),
+
+    #[error(transparent)]
+    Repository(#[from] RepositoryError),
+}
+
+impl From<reqwest::Error> for WebcalError {
+    fn from(error: reqwest::Error) -> Self {
+        WebcalError::Http(error.to_string())
+    }
+}
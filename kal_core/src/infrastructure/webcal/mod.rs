@@ -0,0 +1,7 @@
+pub mod client;
+pub mod refresher;
+pub mod error;
+
+pub use client::{FetchResult, WebcalClient};
+pub use refresher::{RefreshOutcome, SubscriptionRefresher};
+pub use error::WebcalError;
@@ -0,0 +1,128 @@
+use chrono::{DateTime, Utc};
+
+use crate::{
+    domain::{
+        repository::{CalendarRepository, SubscriptionRepository},
+        value_objects::CalendarId,
+    },
+    infrastructure::ical::{Component, IcalMapper},
+};
+
+use super::{
+    client::{FetchResult, WebcalClient},
+    error::WebcalError,
+};
+
+#[derive(Debug, Clone)]
+pub enum RefreshOutcome {
+    NotModified,
+    Replaced {
+        objects: usize,
+        skipped: Vec<(String, String)>,
+    },
+}
+
+pub struct SubscriptionRefresher<C, S>
+where
+    C: CalendarRepository,
+    S: SubscriptionRepository,
+{
+    client: WebcalClient,
+    calendars: C,
+    subscriptions: S,
+}
+
+impl<C, S> SubscriptionRefresher<C, S>
+where
+    C: CalendarRepository,
+    S: SubscriptionRepository,
+{
+    pub fn new(client: WebcalClient, calendars: C, subscriptions: S) -> Self {
+        Self {
+            client,
+            calendars,
+            subscriptions,
+        }
+    }
+
+    /// Fetches the feed of one subscribed calendar and, if it changed,
+    /// replaces the calendar's content with it.
+    pub async fn refresh(
+        &self,
+        calendar_id: &CalendarId,
+        now: DateTime<Utc>,
+    ) -> Result<RefreshOutcome, WebcalError> {
+        let mut calendar = self
+            .calendars
+            .find_by_id(calendar_id)
+            .await?
+            .ok_or(WebcalError::CalendarNotFound)?;
+
+        let subscription = calendar
+            .subscription()
+            .clone()
+            .ok_or(WebcalError::NotSubscribed)?;
+
+        let fetched = self
+            .client
+            .fetch(
+                &subscription.fetch_url(),
+                subscription.etag().as_deref(),
+                subscription.last_modified().as_deref(),
+            )
+            .await?;
+
+        let (body, etag, last_modified) = match fetched {
+            FetchResult::NotModified => {
+                calendar.record_fetch(
+                    now,
+                    subscription.etag().clone(),
+                    subscription.last_modified().clone(),
+                );
+                self.calendars.save(&calendar).await?;
+                return Ok(RefreshOutcome::NotModified);
+            }
+            FetchResult::Fetched { body, etag, last_modified } => (body, etag, last_modified),
+        };
+
+        let mut objects = Vec::new();
+        let mut skipped = Vec::new();
+
+        for vcalendar in Component::parse_all(&body)? {
+            let (mapped, failed) = IcalMapper::to_domain_scoped(&vcalendar, *calendar_id);
+            objects.extend(mapped);
+            skipped.extend(failed.into_iter().map(|(uid, e)| (uid, e.to_string())));
+        }
+
+        calendar.record_fetch(now, etag, last_modified);
+        self.subscriptions.replace_contents(&calendar, &objects).await?;
+
+        Ok(RefreshOutcome::Replaced {
+            objects: objects.len(),
+            skipped,
+        })
+    }
+
+    /// Refreshes every subscription whose refresh interval has elapsed.
+    /// A failing feed does not stop the others.
+    pub async fn refresh_due(
+        &self,
+        now: DateTime<Utc>,
+    ) -> Result<Vec<(CalendarId, Result<RefreshOutcome, WebcalError>)>, WebcalError> {
+        let mut results = Vec::new();
+
+        for calendar in self.subscriptions.find_all().await? {
+            let due = calendar
+                .subscription()
+                .as_ref()
+                .is_some_and(|s| s.is_due(now));
+
+            if due && !calendar.is_archived() {
+                let outcome = self.refresh(calendar.calendar_id(), now).await;
+                results.push((*calendar.calendar_id(), outcome));
+            }
+        }
+
+        Ok(results)
+    }
+}
@@ -0,0 +1,221 @@
+//! The REST API: how application errors map onto HTTP statuses, and
+//! what the server answers to requests it refuses.
+
+use chrono::Duration;
+use reqwest::{header, Method, StatusCode};
+use tokio::net::TcpListener;
+use uuid::Uuid;
+
+use kal_core::{
+    application::publisher::Publisher,
+    domain::{
+        calendar::Calendar,
+        repository::{AuditRepository, CalendarRepository},
+        value_objects::{CalendarId, Subscription},
+    },
+    infrastructure::{
+        api::{ApiRoutes, ApiServer, ACTOR_HEADER},
+        persistence::Database,
+    },
+};
+
+/// Runs one request through the routes and returns status and body.
+async fn call(
+    routes: &ApiRoutes,
+    method: &str,
+    path: &str,
+    body: &str,
+) -> (u16, serde_json::Value) {
+    let (status, body) = match routes.handle(method, path, "", body).await {
+        Ok(response) => (response.status, response.body),
+        Err(error) => (error.status(), Some(serde_json::to_string(&error.to_dto()).unwrap())),
+    };
+    let body = body.map(|b| serde_json::from_str(&b).unwrap()).unwrap_or_default();
+    (status, body)
+}
+
+fn event(title: &str) -> String {
+    serde_json::json!({
+        "title": title,
+        "starts_at": "2025-03-10T09:00:00Z",
+        "ends_at": "2025-03-10T10:00:00Z",
+    })
+    .to_string()
+}
+
+#[tokio::test]
+async fn application_errors_map_onto_rest_statuses() {
+    let database = Database::open_in_memory().await.unwrap();
+    let routes = ApiRoutes::new(database.pool().clone());
+
+    let (status, body) = call(&routes, "POST", "/calendars", r#"{"name":"Work"}"#).await;
+    assert_eq!(status, 201);
+    let calendar_id = body["id"].as_str().unwrap().to_string();
+
+    let (status, body) = call(&routes, "GET", &format!("/calendars/{calendar_id}"), "").await;
+    assert_eq!((status, body["name"].as_str()), (200, Some("Work")));
+
+    let subscription =
+        Subscription::new("https://example.com/holidays.ics".into(), Duration::hours(6)).unwrap();
+    let holidays = Calendar::subscribed("Holidays".into(), None, subscription).unwrap();
+    database.calendars().save(&holidays).await.unwrap();
+
+    let never_refreshed = format!(
+        r#"{{"name":"Feed","url":"https://example.com/a.ics","refresh_minutes":{}}}"#,
+        i64::MAX
+    );
+
+    let cases = [
+        ("GET", format!("/calendars/{}", Uuid::new_v4()), String::new(), 404, "calendar_not_found"),
+        ("GET", format!("/events/{}", Uuid::new_v4()), String::new(), 404, "event_not_found"),
+        ("GET", "/nowhere".to_string(), String::new(), 404, "route_not_found"),
+        ("GET", "/calendars/not-a-uuid".to_string(), String::new(), 400, "bad_request"),
+        ("POST", "/calendars".to_string(), "{".to_string(), 400, "bad_request"),
+        // Rejected by the domain
+        ("POST", "/calendars".to_string(), r#"{"name":""}"#.to_string(), 422, "invalid"),
+        (
+            "POST",
+            format!("/calendars/{calendar_id}/events"),
+            event(""),
+            422,
+            "invalid",
+        ),
+        (
+            "POST",
+            format!("/calendars/{}/events", holidays.calendar_id()),
+            event("Dinner"),
+            403,
+            "read_only",
+        ),
+        (
+            "POST",
+            "/subscriptions".to_string(),
+            never_refreshed.clone(),
+            422,
+            "invalid",
+        ),
+        ("POST", "/history/redo".to_string(), String::new(), 409, "nothing_to_redo"),
+        ("DELETE", "/calendars".to_string(), String::new(), 405, "method_not_allowed"),
+    ];
+
+    for (method, path, body, expected_status, expected_code) in cases {
+        let (status, error) = call(&routes, method, &path, &body).await;
+        assert_eq!(status, expected_status, "{method} {path}: {error}");
+        assert_eq!(error["code"], expected_code, "{method} {path}: {error}");
+        assert!(error["message"].is_string());
+    }
+}
+
+async fn serve(server: ApiServer) -> String {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url = format!("http://{}", listener.local_addr().unwrap());
+    tokio::spawn(server.serve(listener));
+    url
+}
+
+#[tokio::test]
+async fn refused_methods_name_the_allowed_ones() {
+    let database = Database::open_in_memory().await.unwrap();
+    let url = serve(ApiServer::new(database.pool().clone())).await;
+    let client = reqwest::Client::new();
+    let id = Uuid::new_v4();
+
+    for (method, path, allow) in [
+        (Method::DELETE, "/calendars".to_string(), "GET, POST"),
+        (Method::POST, format!("/calendars/{id}"), "GET, PATCH, DELETE"),
+        (Method::GET, "/history/undo".to_string(), "POST"),
+        (Method::POST, format!("/events/{id}/organizer"), "PUT"),
+        (Method::GET, format!("/recurring/{id}/attendees/a@example.com"), "PATCH, DELETE"),
+        (Method::PUT, "/journal/search".to_string(), "GET"),
+    ] {
+        let response =
+            client.request(method.clone(), format!("{url}{path}")).send().await.unwrap();
+        assert_eq!(response.status(), StatusCode::METHOD_NOT_ALLOWED, "{method} {path}");
+        assert_eq!(
+            response.headers().get(header::ALLOW).and_then(|v| v.to_str().ok()),
+            Some(allow),
+            "{method} {path}"
+        );
+    }
+
+    let response = client.get(format!("{url}/nowhere")).send().await.unwrap();
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    assert!(response.headers().get(header::ALLOW).is_none());
+}
+
+#[tokio::test]
+async fn oversized_bodies_are_refused() {
+    let database = Database::open_in_memory().await.unwrap();
+    let url = serve(ApiServer::new(database.pool().clone()).with_max_body_bytes(64)).await;
+    let client = reqwest::Client::new();
+
+    let response = client
+        .post(format!("{url}/calendars"))
+        .body(format!(r#"{{"name":"{}"}}"#, "x".repeat(100)))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::PAYLOAD_TOO_LARGE);
+    let error: serde_json::Value = serde_json::from_str(&response.text().await.unwrap()).unwrap();
+    assert_eq!(error["code"], "payload_too_large");
+    assert!(database.calendars().find_all_active().await.unwrap().is_empty());
+
+    // Bodies within the limit still go through
+    let response = client
+        .post(format!("{url}/calendars"))
+        .body(r#"{"name":"Work"}"#)
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::CREATED);
+}
+
+#[tokio::test]
+async fn requests_are_attributed_to_the_user_they_name_when_trusted() {
+    let database = Database::open_in_memory().await.unwrap();
+    let server = ApiServer::new(database.pool().clone())
+        .with_publisher(Publisher::new().with_actor("api"))
+        .trust_actor_header();
+    let url = serve(server).await;
+    let client = reqwest::Client::new();
+
+    let response = client
+        .post(format!("{url}/calendars"))
+        .header(ACTOR_HEADER, "alice")
+        .body(r#"{"name":"Work"}"#)
+        .send()
+        .await
+        .unwrap();
+    let created: serde_json::Value = serde_json::from_str(&response.text().await.unwrap()).unwrap();
+    let calendar_id = created["id"].as_str().unwrap().parse::<CalendarId>().unwrap();
+
+    // Without the header, the change is the server's own
+    let path = format!("{url}/calendars/{calendar_id}");
+    client.patch(&path).body(r#"{"name":"Team"}"#).send().await.unwrap();
+
+    let entries = database.audit().find_by_calendar(&calendar_id, 10).await.unwrap();
+    let actors: Vec<&str> = entries.iter().map(|entry| entry.actor().as_str()).collect();
+    assert_eq!(actors, ["api", "alice"]);
+}
+
+#[tokio::test]
+async fn the_user_header_is_ignored_unless_trusted() {
+    let database = Database::open_in_memory().await.unwrap();
+    let server = ApiServer::new(database.pool().clone())
+        .with_publisher(Publisher::new().with_actor("api"));
+    let url = serve(server).await;
+
+    let response = reqwest::Client::new()
+        .post(format!("{url}/calendars"))
+        .header(ACTOR_HEADER, "alice")
+        .body(r#"{"name":"Work"}"#)
+        .send()
+        .await
+        .unwrap();
+    let created: serde_json::Value = serde_json::from_str(&response.text().await.unwrap()).unwrap();
+    let calendar_id = created["id"].as_str().unwrap().parse::<CalendarId>().unwrap();
+
+    let entries = database.audit().find_by_calendar(&calendar_id, 10).await.unwrap();
+    let actors: Vec<&str> = entries.iter().map(|entry| entry.actor().as_str()).collect();
+    assert_eq!(actors, ["api"]);
+}
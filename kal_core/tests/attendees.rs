@@ -0,0 +1,228 @@
+//! Organizers and attendees: the commands that manage them, storage, and
+//! ORGANIZER/ATTENDEE mapping.
+
+mod support;
+
+use kal_core::{
+    application::{
+        commands::{
+            events::{
+                AddEventAttendeeCommand, AddEventAttendeeHandler, RemoveEventAttendeeCommand,
+                RemoveEventAttendeeHandler, SetEventOrganizerCommand, SetEventOrganizerHandler,
+                UpdateEventAttendeeStatusCommand, UpdateEventAttendeeStatusHandler,
+            },
+            recurring::{
+                AddRecurringAttendeeCommand, AddRecurringAttendeeHandler,
+                SetRecurringOrganizerCommand, SetRecurringOrganizerHandler,
+                UpdateRecurringAttendeeStatusCommand, UpdateRecurringAttendeeStatusHandler,
+            },
+        },
+        error::ApplicationError,
+    },
+    domain::{
+        attendee::{Attendee, AttendeeRole, Organizer, ParticipationStatus},
+        calendar_object::CalendarObject,
+        error::DomainError,
+        recurrence::{RecurrenceRule, RecurringEvent},
+        repository::{EventRepository, RecurringEventRepository},
+        value_objects::{CalendarId, EventColor, Frequency},
+    },
+    infrastructure::{
+        ical::{Component, IcalMapper},
+        persistence::Database,
+    },
+};
+
+use support::{calendar, event, range};
+
+fn attendee(email: &str, role: AttendeeRole) -> Attendee {
+    Attendee::new(email.into(), None, role, ParticipationStatus::NeedsAction, true).unwrap()
+}
+
+#[test]
+fn addresses_must_look_like_email() {
+    for email in ["", "alice", "@example.com", "alice@", "alice smith@example.com"] {
+        let result = Attendee::new(
+            email.into(),
+            None,
+            AttendeeRole::Required,
+            ParticipationStatus::NeedsAction,
+            false,
+        );
+        assert!(matches!(result, Err(DomainError::InvalidAttendee(_))), "{email:?}");
+    }
+
+    let organizer = Organizer::new(" alice@example.com ".into(), Some("  ".into())).unwrap();
+    assert_eq!(organizer.email(), "alice@example.com");
+    assert_eq!(*organizer.name(), None);
+}
+
+#[tokio::test]
+async fn commands_manage_the_people_on_an_event() {
+    let database = Database::open_in_memory().await.unwrap();
+    let calendar_id = calendar(&database).await;
+    let event = event(calendar_id, "Review", range(10, 9, 10));
+    let id = *event.event_id();
+    database.events().save(&event).await.unwrap();
+
+    let organizer = Organizer::new("alice@example.com".into(), Some("Alice".into())).unwrap();
+    SetEventOrganizerHandler::new(database.events(), database.calendars())
+        .handle(SetEventOrganizerCommand::new(id, Some(organizer.clone())))
+        .await
+        .unwrap();
+
+    let add = AddEventAttendeeHandler::new(database.events(), database.calendars());
+    add.handle(AddEventAttendeeCommand::new(id, attendee("bob@example.com", AttendeeRole::Chair)))
+        .await
+        .unwrap();
+    add.handle(AddEventAttendeeCommand::new(
+        id,
+        attendee("carol@example.com", AttendeeRole::Optional),
+    ))
+    .await
+    .unwrap();
+
+    // Addresses are compared without regard to case
+    let twice = add
+        .handle(AddEventAttendeeCommand::new(
+            id,
+            attendee("BOB@example.com", AttendeeRole::Required),
+        ))
+        .await;
+    assert!(matches!(twice, Err(ApplicationError::Domain(DomainError::InvalidAttendee(_)))));
+
+    UpdateEventAttendeeStatusHandler::new(database.events(), database.calendars())
+        .handle(UpdateEventAttendeeStatusCommand::new(
+            id,
+            "Carol@Example.com".into(),
+            ParticipationStatus::Tentative,
+        ))
+        .await
+        .unwrap();
+
+    let stored = database.events().find_by_id(&id).await.unwrap().unwrap();
+    assert_eq!(*stored.organizer(), Some(organizer));
+    let people: Vec<(&str, AttendeeRole, ParticipationStatus, bool)> = stored
+        .attendees()
+        .iter()
+        .map(|a| (a.email().as_str(), *a.role(), *a.status(), *a.rsvp()))
+        .collect();
+    assert_eq!(
+        people,
+        [
+            ("bob@example.com", AttendeeRole::Chair, ParticipationStatus::NeedsAction, true),
+            ("carol@example.com", AttendeeRole::Optional, ParticipationStatus::Tentative, true),
+        ]
+    );
+
+    let remove = RemoveEventAttendeeHandler::new(database.events(), database.calendars());
+    remove.handle(RemoveEventAttendeeCommand::new(id, "bob@example.com".into())).await.unwrap();
+    let gone = remove.handle(RemoveEventAttendeeCommand::new(id, "bob@example.com".into())).await;
+    assert!(matches!(gone, Err(ApplicationError::Domain(DomainError::AttendeeNotFound(_)))));
+
+    SetEventOrganizerHandler::new(database.events(), database.calendars())
+        .handle(SetEventOrganizerCommand::new(id, None))
+        .await
+        .unwrap();
+    let stored = database.events().find_by_id(&id).await.unwrap().unwrap();
+    assert_eq!(*stored.organizer(), None);
+    assert_eq!(stored.attendees().len(), 1);
+}
+
+#[tokio::test]
+async fn series_keep_their_people_too() {
+    let database = Database::open_in_memory().await.unwrap();
+    let calendar_id = calendar(&database).await;
+    let series = RecurringEvent::new(
+        calendar_id,
+        "Standup".into(),
+        None,
+        range(10, 9, 10),
+        RecurrenceRule::new(Frequency::Weekly, 1, None).unwrap(),
+        EventColor::from(0),
+        false,
+    )
+    .unwrap();
+    let id = *series.event_id();
+    database.recurring().save(&series).await.unwrap();
+
+    let organizer = Organizer::new("alice@example.com".into(), None).unwrap();
+    SetRecurringOrganizerHandler::new(database.recurring(), database.calendars())
+        .handle(SetRecurringOrganizerCommand::new(id, Some(organizer.clone())))
+        .await
+        .unwrap();
+    AddRecurringAttendeeHandler::new(database.recurring(), database.calendars())
+        .handle(AddRecurringAttendeeCommand::new(
+            id,
+            attendee("bob@example.com", AttendeeRole::Required),
+        ))
+        .await
+        .unwrap();
+    UpdateRecurringAttendeeStatusHandler::new(database.recurring(), database.calendars())
+        .handle(UpdateRecurringAttendeeStatusCommand::new(
+            id,
+            "bob@example.com".into(),
+            ParticipationStatus::Declined,
+        ))
+        .await
+        .unwrap();
+
+    let stored = database.recurring().find_by_id(&id).await.unwrap();
+    assert_eq!(*stored.organizer(), Some(organizer));
+    assert_eq!(stored.attendees().len(), 1);
+    assert_eq!(*stored.attendees()[0].status(), ParticipationStatus::Declined);
+
+    // Deleting the series takes its attendees with it
+    database.recurring().delete(&id).await.unwrap();
+    let rows: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM attendees")
+        .fetch_one(database.pool())
+        .await
+        .unwrap();
+    assert_eq!(rows, 0);
+}
+
+#[test]
+fn organizer_and_attendees_map_both_ways() {
+    let ics = "BEGIN:VCALENDAR\r\nVERSION:2.0\r\nPRODID:-//test//EN\r\nBEGIN:VEVENT\r\n\
+               UID:review@example.com\r\nSUMMARY:Review\r\n\
+               DTSTART:20250310T090000Z\r\nDTEND:20250310T100000Z\r\n\
+               ORGANIZER;CN=Alice:mailto:alice@example.com\r\n\
+               ATTENDEE;CN=Bob;ROLE=CHAIR;PARTSTAT=ACCEPTED;RSVP=TRUE:MAILTO:bob@example.com\r\n\
+               ATTENDEE;ROLE=X-OBSERVER;PARTSTAT=X-MAYBE:mailto:carol@example.com\r\n\
+               ATTENDEE:tel:+15555550100\r\n\
+               ATTENDEE;PARTSTAT=DECLINED:mailto:Bob@Example.com\r\n\
+               END:VEVENT\r\nEND:VCALENDAR\r\n";
+    let calendar_id = CalendarId::new();
+    let mut objects =
+        IcalMapper::to_domain(&Component::parse(ics).unwrap(), calendar_id).unwrap();
+    let CalendarObject::Event(event) = objects.remove(0) else {
+        panic!("expected a single event");
+    };
+
+    let organizer = event.organizer().clone().unwrap();
+    assert_eq!(organizer.email(), "alice@example.com");
+    assert_eq!(organizer.name().as_deref(), Some("Alice"));
+
+    // Unknown roles and statuses fall back to the defaults; addresses that
+    // are not email and repeats are skipped
+    let bob = &event.attendees()[0];
+    assert_eq!(event.attendees().len(), 2);
+    assert_eq!(
+        (bob.email().as_str(), bob.name().as_deref(), *bob.role(), *bob.status(), *bob.rsvp()),
+        ("bob@example.com", Some("Bob"), AttendeeRole::Chair, ParticipationStatus::Accepted, true)
+    );
+    let carol = &event.attendees()[1];
+    assert_eq!(
+        (*carol.role(), *carol.status(), *carol.rsvp()),
+        (AttendeeRole::Required, ParticipationStatus::NeedsAction, false)
+    );
+
+    let exported = IcalMapper::to_ics(&CalendarObject::Event(event.clone()));
+    let mut objects =
+        IcalMapper::to_domain(&Component::parse(&exported).unwrap(), calendar_id).unwrap();
+    let CalendarObject::Event(reimported) = objects.remove(0) else {
+        panic!("expected a single event");
+    };
+    assert_eq!(reimported.organizer(), event.organizer());
+    assert_eq!(reimported.attendees(), event.attendees());
+}
@@ -0,0 +1,222 @@
+//! A command's domain events are appended to the audit log in its
+//! transaction, which answers who changed an event or calendar, when, and
+//! how.
+
+mod support;
+
+use chrono::{Duration, TimeZone, Utc};
+use serde_json::{json, Value};
+
+use kal_core::{
+    application::{
+        commands::{
+            calendars::{RenameCalendarCommand, RenameCalendarHandler},
+            events::{
+                CreateEventCommand, CreateEventHandler, DeleteEventCommand, DeleteEventHandler,
+                UpdateEventTimeRangeCommand, UpdateEventTimeRangeHandler,
+            },
+        },
+        publisher::{Publisher, DEFAULT_ACTOR},
+        scope::CommandScope,
+    },
+    domain::{
+        domain_event::{DomainEvent, EventContext, FieldChange},
+        repository::{AuditRepository, CalendarRepository, EventRepository, HistoryRepository},
+        value_objects::{CalendarId, EventColor, EventId, TimeRange},
+    },
+    infrastructure::{persistence::Database, rpc::RpcDispatcher},
+};
+
+use support::{calendar, range};
+
+fn scope(actor: &str) -> CommandScope {
+    CommandScope::new().with_publisher(Publisher::new().with_actor(actor))
+}
+
+async fn create_event(database: &Database, scope: &CommandScope, calendar_id: CalendarId) -> EventId {
+    let scope = scope.scoped();
+    let id = CreateEventHandler::new(scope.events(database.events()), database.calendars())
+        .handle(CreateEventCommand::new(
+            calendar_id,
+            "Meeting".into(),
+            None,
+            range(10, 9, 10),
+            EventColor::from(0),
+            false,
+        ))
+        .await
+        .unwrap();
+    scope.audit(&database.audit(), "event.create").await.unwrap();
+    id
+}
+
+async fn reschedule(database: &Database, scope: &CommandScope, id: EventId, to: TimeRange) {
+    let scope = scope.scoped();
+    UpdateEventTimeRangeHandler::new(scope.events(database.events()), database.calendars())
+        .handle(UpdateEventTimeRangeCommand::new(id, to))
+        .await
+        .unwrap();
+    scope.audit(&database.audit(), "event.update").await.unwrap();
+}
+
+#[tokio::test]
+async fn appended_entries_keep_the_context_and_changes() {
+    let database = Database::open_in_memory().await.unwrap();
+    let calendar_id = CalendarId::new();
+    let event_id = EventId::new();
+    let occurred_at = Utc.with_ymd_and_hms(2025, 3, 1, 12, 0, 0).unwrap();
+
+    let event = DomainEvent::EventChanged {
+        calendar_id,
+        event_id,
+        changes: vec![
+            FieldChange::new("title", Some("Meeting".into()), Some("Review".into())),
+            FieldChange::new("location", None, Some("Room 1".into())),
+        ],
+    };
+    let id = database
+        .audit()
+        .append(&event, &EventContext::new("event.update", "alice", occurred_at))
+        .await
+        .unwrap();
+
+    let entries = database.audit().find_by_event(&event_id, 10).await.unwrap();
+    assert_eq!(entries.len(), 1);
+
+    let entry = &entries[0];
+    assert_eq!(*entry.id(), id);
+    assert_eq!(*entry.recorded_at(), occurred_at);
+    assert_eq!(entry.actor(), "alice");
+    assert_eq!(entry.command(), "event.update");
+    assert_eq!(entry.event(), "EventChanged");
+    assert_eq!(*entry.calendar_id(), calendar_id);
+    assert_eq!(*entry.object_id(), event_id.as_uuid());
+    assert_eq!(*entry.occurrence(), None);
+    assert_eq!(
+        *entry.changes(),
+        vec![
+            FieldChange::new("title", Some("Meeting".into()), Some("Review".into())),
+            FieldChange::new("location", None, Some("Room 1".into())),
+        ]
+    );
+
+    // Nothing was recorded for anything else
+    assert!(database.audit().find_by_event(&EventId::new(), 10).await.unwrap().is_empty());
+    assert!(database.audit().find_by_calendar(&CalendarId::new(), 10).await.unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn published_changes_show_who_moved_an_event() {
+    let database = Database::open_in_memory().await.unwrap();
+    let calendar_id = calendar(&database).await;
+
+    let id = create_event(&database, &scope("alice"), calendar_id).await;
+    reschedule(&database, &scope("bob"), id, range(11, 14, 15)).await;
+
+    let entries = database.audit().find_by_event(&id, 10).await.unwrap();
+    let summary: Vec<(&str, &str, &str)> = entries
+        .iter()
+        .map(|entry| (entry.actor().as_str(), entry.command().as_str(), entry.event().as_str()))
+        .collect();
+    assert_eq!(
+        summary,
+        [("bob", "event.update", "EventRescheduled"), ("alice", "event.create", "EventCreated")]
+    );
+    assert_eq!(
+        *entries[0].changes(),
+        vec![FieldChange::new(
+            "time_range",
+            Some(range(10, 9, 10).to_string()),
+            Some(range(11, 14, 15).to_string()),
+        )]
+    );
+    assert!(entries[1].changes().is_empty());
+
+    // Only the newest entries are returned when limited
+    let latest = database.audit().find_by_event(&id, 1).await.unwrap();
+    assert_eq!(latest.len(), 1);
+    assert_eq!(latest[0].id(), entries[0].id());
+}
+
+#[tokio::test]
+async fn calendar_history_covers_everything_in_it() {
+    let database = Database::open_in_memory().await.unwrap();
+    let scope = scope(DEFAULT_ACTOR);
+    let calendar_id = calendar(&database).await;
+
+    let id = create_event(&database, &scope, calendar_id).await;
+    RenameCalendarHandler::new(scope.calendars(database.calendars()))
+        .handle(RenameCalendarCommand::new(calendar_id, "Team".into()))
+        .await
+        .unwrap();
+    scope.audit(&database.audit(), "calendar.update").await.unwrap();
+
+    let entries = database.audit().find_by_calendar(&calendar_id, 10).await.unwrap();
+    let objects: Vec<_> = entries.iter().map(|entry| *entry.object_id()).collect();
+    assert_eq!(objects, [calendar_id.as_uuid(), id.as_uuid()]);
+    assert!(entries.iter().all(|entry| entry.actor() == DEFAULT_ACTOR));
+    assert_eq!(
+        *entries[0].changes(),
+        vec![FieldChange::new("name", Some("Work".into()), Some("Team".into()))]
+    );
+}
+
+#[tokio::test]
+async fn entries_outlive_the_event_and_cannot_be_changed() {
+    let database = Database::open_in_memory().await.unwrap();
+    let scope = scope("alice");
+    let calendar_id = calendar(&database).await;
+
+    let id = create_event(&database, &scope, calendar_id).await;
+    reschedule(&database, &scope, id, range(11, 9, 10)).await;
+    DeleteEventHandler::new(scope.events(database.events()), database.calendars())
+        .handle(DeleteEventCommand::new(id))
+        .await
+        .unwrap();
+    scope.audit(&database.audit(), "event.delete").await.unwrap();
+    database.events().purge_trashed(Utc::now() + Duration::days(1)).await.unwrap();
+    assert!(database.events().find_by_id(&id).await.unwrap().is_none());
+
+    let entries = database.audit().find_by_event(&id, 10).await.unwrap();
+    let names: Vec<&str> = entries.iter().map(|entry| entry.event().as_str()).collect();
+    assert_eq!(names, ["EventTrashed", "EventRescheduled", "EventCreated"]);
+
+    for statement in [
+        "UPDATE audit_entries SET actor = 'mallory'",
+        "DELETE FROM audit_entries",
+        "UPDATE audit_changes SET to_value = NULL",
+        "DELETE FROM audit_changes",
+    ] {
+        let result = sqlx::query(statement).execute(database.pool()).await;
+        assert!(result.is_err(), "{statement} should be refused");
+    }
+    let unchanged = database.audit().find_by_event(&id, 10).await.unwrap();
+    assert_eq!(unchanged.len(), 3);
+    assert_eq!(unchanged[1].changes(), entries[1].changes());
+}
+
+#[tokio::test]
+async fn a_change_whose_entry_cannot_be_written_is_not_made() {
+    let database = Database::open_in_memory().await.unwrap();
+    let dispatcher = RpcDispatcher::new(database.pool().clone());
+    sqlx::query(
+        "CREATE TRIGGER no_audit BEFORE INSERT ON audit_entries \
+         BEGIN SELECT RAISE(ABORT, 'audit log is full'); END",
+    )
+    .execute(database.pool())
+    .await
+    .unwrap();
+
+    let call = json!({
+        "jsonrpc": "2.0",
+        "id": 1,
+        "method": "calendar.create",
+        "params": { "name": "Work" },
+    });
+    let response = dispatcher.handle_line(&call.to_string()).await.unwrap();
+    let response: Value = serde_json::from_str(&response).unwrap();
+    assert!(response["error"].is_object());
+
+    assert!(database.calendars().find_all_active().await.unwrap().is_empty());
+    assert!(database.history().find_applied(10).await.unwrap().is_empty());
+}
@@ -0,0 +1,262 @@
+//! The CalDAV client and synchronizer against an in-process server.
+
+mod support;
+
+use chrono::{TimeZone, Utc};
+
+use kal_core::{
+    domain::{
+        event::Event,
+        repository::{EventRepository, SyncStateRepository},
+        sync::{ConflictResolver, ConflictStrategy},
+        value_objects::{CalendarId, EventColor, TimeRange},
+    },
+    infrastructure::{
+        caldav::{sync::object_href, CalDavClient, CalDavError, CalDavSynchronizer},
+        ical::mappers::event_id_for_uid,
+        persistence::Database,
+    },
+};
+
+use support::caldav::{CalDavStub, COLLECTION, HOME, PRINCIPAL};
+
+fn ics(uid: &str, summary: &str) -> String {
+    format!(
+        "BEGIN:VCALENDAR\r\nVERSION:2.0\r\nPRODID:-//test//EN\r\nBEGIN:VEVENT\r\n\
+         UID:{uid}\r\nSUMMARY:{summary}\r\n\
+         DTSTART:20250310T090000Z\r\nDTEND:20250310T093000Z\r\n\
+         END:VEVENT\r\nEND:VCALENDAR\r\n"
+    )
+}
+
+fn member(name: &str) -> String {
+    format!("{COLLECTION}{name}.ics")
+}
+
+async fn start() -> (CalDavStub, CalDavClient) {
+    let (stub, url) = CalDavStub::start().await;
+    let client = CalDavClient::new(&format!("{url}/")).unwrap();
+    (stub, client)
+}
+
+#[tokio::test]
+async fn discovery_follows_the_principal_to_event_calendars() {
+    let (stub, client) = start().await;
+
+    let calendars = client.discover_calendars().await.unwrap();
+
+    // The task list does not hold VEVENTs and is left out
+    assert_eq!(calendars.len(), 1);
+    let calendar = &calendars[0];
+    assert_eq!(calendar.href, COLLECTION);
+    assert_eq!(calendar.display_name.as_deref(), Some("Work"));
+    assert_eq!(calendar.description.as_deref(), Some("Shared work calendar"));
+    assert!(calendar.sync_token.is_some());
+
+    let steps: Vec<(String, String, Option<String>)> = stub
+        .received()
+        .into_iter()
+        .map(|r| (r.method, r.path, r.depth))
+        .collect();
+    assert_eq!(
+        steps,
+        [
+            ("PROPFIND".into(), "/".into(), Some("0".into())),
+            ("PROPFIND".into(), PRINCIPAL.into(), Some("0".into())),
+            ("PROPFIND".into(), HOME.into(), Some("1".into())),
+        ]
+    );
+}
+
+#[tokio::test]
+async fn multiget_returns_the_requested_members_with_their_etags() {
+    let (stub, client) = start().await;
+    let standup = stub.store(&member("standup"), &ics("standup@example.com", "Standup & coffee"));
+    stub.store(&member("review"), &ics("review@example.com", "Review"));
+
+    let objects = client
+        .multiget(COLLECTION, &[member("standup"), member("missing")])
+        .await
+        .unwrap();
+
+    // Members the server does not have are left out
+    assert_eq!(objects.len(), 1);
+    assert_eq!(objects[0].href, member("standup"));
+    assert_eq!(objects[0].etag.as_deref(), Some(standup.as_str()));
+    assert_eq!(
+        objects[0].data.trim_end(),
+        ics("standup@example.com", "Standup & coffee").trim_end()
+    );
+
+    // Nothing is requested for no members
+    let before = stub.received().len();
+    assert!(client.multiget(COLLECTION, &[]).await.unwrap().is_empty());
+    assert_eq!(stub.received().len(), before);
+}
+
+#[tokio::test]
+async fn put_only_overwrites_the_copy_it_last_saw() {
+    let (stub, client) = start().await;
+    let href = member("standup");
+
+    // Creating requires the resource to be absent
+    let created = client.put(&href, ics("standup@example.com", "Standup"), None).await.unwrap();
+    assert_eq!(created, stub.etag(&href));
+    let again = client.put(&href, ics("standup@example.com", "Other"), None).await;
+    assert!(matches!(again, Err(CalDavError::PreconditionFailed(h)) if h == href));
+
+    // Another client changes it; the old ETag no longer matches
+    let theirs = stub.store(&href, &ics("standup@example.com", "Moved standup"));
+    let stale = client
+        .put(&href, ics("standup@example.com", "Mine"), created.as_deref())
+        .await;
+    assert!(matches!(stale, Err(CalDavError::PreconditionFailed(_))));
+    assert_eq!(stub.data(&href).unwrap(), ics("standup@example.com", "Moved standup"));
+
+    let updated = client
+        .put(&href, ics("standup@example.com", "Mine"), Some(&theirs))
+        .await
+        .unwrap();
+    assert_ne!(updated.as_deref(), Some(theirs.as_str()));
+
+    let fetched = client.get(&href).await.unwrap();
+    assert_eq!(fetched.data, ics("standup@example.com", "Mine"));
+    assert_eq!(fetched.etag, updated);
+
+    let puts: Vec<(Option<String>, Option<String>)> = stub
+        .received()
+        .into_iter()
+        .filter(|r| r.method == "PUT")
+        .map(|r| (r.if_match, r.if_none_match))
+        .collect();
+    assert_eq!(
+        puts,
+        [
+            (None, Some("*".into())),
+            (None, Some("*".into())),
+            (created.clone(), None),
+            (Some(theirs), None),
+        ]
+    );
+}
+
+#[tokio::test]
+async fn delete_checks_the_etag_and_tolerates_missing_resources() {
+    let (stub, client) = start().await;
+    let href = member("standup");
+    let first = stub.store(&href, &ics("standup@example.com", "Standup"));
+    let second = stub.store(&href, &ics("standup@example.com", "Moved standup"));
+
+    let stale = client.delete(&href, Some(&first)).await;
+    assert!(matches!(stale, Err(CalDavError::PreconditionFailed(_))));
+    assert!(stub.data(&href).is_some());
+
+    client.delete(&href, Some(&second)).await.unwrap();
+    assert!(stub.hrefs().is_empty());
+
+    // Already gone counts as deleted
+    client.delete(&href, Some(&second)).await.unwrap();
+    let missing = client.get(&href).await;
+    assert!(matches!(missing, Err(CalDavError::Status { status: 404, .. })));
+}
+
+#[tokio::test]
+async fn sync_collection_reports_changes_since_the_token() {
+    let (stub, client) = start().await;
+    stub.store(&member("standup"), &ics("standup@example.com", "Standup"));
+    stub.store(&member("review"), &ics("review@example.com", "Review"));
+
+    let initial = client.sync_collection(COLLECTION, None).await.unwrap();
+    let mut changed: Vec<&str> = initial.changed.iter().map(|(href, _)| href.as_str()).collect();
+    changed.sort();
+    assert_eq!(changed, [member("review"), member("standup")]);
+    assert!(initial.removed.is_empty());
+
+    stub.remove(&member("review"));
+    let etag = stub.store(&member("standup"), &ics("standup@example.com", "Moved standup"));
+
+    let delta = client
+        .sync_collection(COLLECTION, initial.sync_token.as_deref())
+        .await
+        .unwrap();
+    assert_eq!(delta.changed, [(member("standup"), Some(etag))]);
+    assert_eq!(delta.removed, [member("review")]);
+    assert_ne!(delta.sync_token, initial.sync_token);
+
+    let expired = client
+        .sync_collection(COLLECTION, Some("http://stub.invalid/sync/999"))
+        .await;
+    assert!(matches!(expired, Err(CalDavError::InvalidSyncToken)));
+}
+
+#[tokio::test]
+async fn sync_round_trips_changes_in_both_directions() {
+    let (stub, client) = start().await;
+    let database = Database::open_in_memory().await.unwrap();
+    let resolver = ConflictResolver::new(ConflictStrategy::LocalWins);
+    stub.store(&member("standup"), &ics("standup@example.com", "Standup"));
+
+    let remote = client.discover_calendars().await.unwrap().remove(0);
+    let synchronizer = CalDavSynchronizer::new(
+        client,
+        database.calendars(),
+        database.events(),
+        database.recurring(),
+        database.sync_state(),
+    );
+    let calendar_id: CalendarId = synchronizer.import_calendar(&remote).await.unwrap();
+
+    // Pull what the server has
+    let report = synchronizer.sync(&calendar_id, &resolver).await.unwrap();
+    assert_eq!((report.pulled, report.pushed), (1, 0));
+    let standup_id = event_id_for_uid("standup@example.com");
+    let standup = database.events().find_by_id(&standup_id).await.unwrap().unwrap();
+    assert_eq!(standup.title(), "Standup");
+
+    // Push an event created locally
+    let review = Event::new(
+        calendar_id,
+        "Review".into(),
+        None,
+        TimeRange::new(
+            Utc.with_ymd_and_hms(2025, 3, 11, 14, 0, 0).unwrap(),
+            Utc.with_ymd_and_hms(2025, 3, 11, 15, 0, 0).unwrap(),
+        )
+        .unwrap(),
+        EventColor::from(0),
+        false,
+    )
+    .unwrap();
+    database.events().save(&review).await.unwrap();
+
+    let report = synchronizer.sync(&calendar_id, &resolver).await.unwrap();
+    assert_eq!((report.pulled, report.pushed), (0, 1));
+    let review_href = object_href(COLLECTION, review.event_id());
+    assert!(stub.data(&review_href).unwrap().contains("SUMMARY:Review"));
+
+    // Nothing is left to do once both sides agree
+    let report = synchronizer.sync(&calendar_id, &resolver).await.unwrap();
+    assert_eq!((report.pulled, report.pushed), (0, 0));
+    let items = database.sync_state().find_items(&calendar_id).await.unwrap();
+    assert_eq!(items.len(), 2);
+    assert!(items.iter().all(|item| !item.is_dirty()));
+
+    // Pull an edit made by another client
+    stub.store(&member("standup"), &ics("standup@example.com", "Moved standup"));
+    let report = synchronizer.sync(&calendar_id, &resolver).await.unwrap();
+    assert_eq!((report.pulled, report.pushed), (1, 0));
+    assert!(report.conflicts.is_empty());
+    let standup = database.events().find_by_id(&standup_id).await.unwrap().unwrap();
+    assert_eq!(standup.title(), "Moved standup");
+
+    // Push a local deletion, and apply a remote one
+    database.events().delete(review.event_id()).await.unwrap();
+    stub.remove(&member("standup"));
+    let report = synchronizer.sync(&calendar_id, &resolver).await.unwrap();
+    assert_eq!((report.deleted_local, report.deleted_remote), (1, 1));
+
+    assert!(stub.hrefs().is_empty());
+    assert!(database.events().find_by_calendar(&calendar_id).await.unwrap().is_empty());
+    assert!(database.sync_state().find_items(&calendar_id).await.unwrap().is_empty());
+    assert!(database.sync_state().find_tombstones(&calendar_id).await.unwrap().is_empty());
+}
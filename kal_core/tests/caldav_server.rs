@@ -0,0 +1,101 @@
+//! The local CalDAV server, driven by kal's own CalDAV client.
+
+mod support;
+
+use chrono::{TimeZone, Utc};
+use tokio::net::TcpListener;
+
+use kal_core::{
+    domain::repository::EventRepository,
+    infrastructure::{
+        caldav::{sync::object_href, CalDavClient, CalDavError},
+        caldav_server::CalDavServer,
+        ical::mappers::event_id_for_uid,
+        persistence::Database,
+    },
+};
+
+use support::calendar_with;
+
+fn ics(uid: &str, summary: &str) -> String {
+    format!(
+        "BEGIN:VCALENDAR\r\nVERSION:2.0\r\nPRODID:-//test//EN\r\nBEGIN:VEVENT\r\n\
+         UID:{uid}\r\nSUMMARY:{summary}\r\n\
+         DTSTART:20250310T090000Z\r\nDTEND:20250310T093000Z\r\n\
+         END:VEVENT\r\nEND:VCALENDAR\r\n"
+    )
+}
+
+/// Serves `database` with credentials alice/secret; returns its base URL.
+async fn serve(database: &Database) -> String {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url = format!("http://{}/", listener.local_addr().unwrap());
+    let server = CalDavServer::new(database.pool().clone()).with_credentials("alice", "secret");
+    tokio::spawn(server.serve(listener));
+    url
+}
+
+#[tokio::test]
+async fn clients_discover_and_edit_local_calendars() {
+    let database = Database::open_in_memory().await.unwrap();
+    let calendar_id = calendar_with(&database, "Work", Some("Team meetings")).await;
+    let client = CalDavClient::new(&serve(&database).await)
+        .unwrap()
+        .with_credentials("alice", "secret");
+
+    let calendars = client.discover_calendars().await.unwrap();
+    assert_eq!(calendars.len(), 1);
+    assert_eq!(calendars[0].href, format!("/calendars/{calendar_id}/"));
+    assert_eq!(calendars[0].display_name.as_deref(), Some("Work"));
+    assert_eq!(calendars[0].description.as_deref(), Some("Team meetings"));
+    let collection = calendars[0].href.clone();
+
+    // A PUT creates the event through the application layer
+    let event_id = event_id_for_uid("standup@example.com");
+    let href = object_href(&collection, &event_id);
+    let created = client.put(&href, ics("standup@example.com", "Standup"), None).await.unwrap();
+    let event = database.events().find_by_id(&event_id).await.unwrap().unwrap();
+    assert_eq!(event.title(), "Standup");
+    assert_eq!(*event.calendar_id(), calendar_id);
+    assert_eq!(
+        *event.time_range().starts_at(),
+        Utc.with_ymd_and_hms(2025, 3, 10, 9, 0, 0).unwrap()
+    );
+
+    let objects = client.multiget(&collection, std::slice::from_ref(&href)).await.unwrap();
+    assert_eq!(objects.len(), 1);
+    assert_eq!(objects[0].etag, created);
+    assert!(objects[0].data.contains("SUMMARY:Standup"));
+
+    // Updates must name the current ETag
+    let updated = client
+        .put(&href, ics("standup@example.com", "Moved standup"), created.as_deref())
+        .await
+        .unwrap();
+    let stale = client
+        .put(&href, ics("standup@example.com", "Lost update"), created.as_deref())
+        .await;
+    assert!(matches!(stale, Err(CalDavError::PreconditionFailed(_))));
+    let event = database.events().find_by_id(&event_id).await.unwrap().unwrap();
+    assert_eq!(event.title(), "Moved standup");
+
+    client.delete(&href, updated.as_deref()).await.unwrap();
+    assert!(database.events().find_by_id(&event_id).await.unwrap().is_none());
+    let gone = client.get(&href).await;
+    assert!(matches!(gone, Err(CalDavError::Status { status: 404, .. })));
+}
+
+#[tokio::test]
+async fn requests_without_the_credentials_are_refused() {
+    let database = Database::open_in_memory().await.unwrap();
+    calendar_with(&database, "Work", Some("Team meetings")).await;
+    let url = serve(&database).await;
+
+    let anonymous = CalDavClient::new(&url).unwrap();
+    let result = anonymous.discover_calendars().await;
+    assert!(matches!(result, Err(CalDavError::Status { status: 401, .. })), "{result:?}");
+
+    let wrong = CalDavClient::new(&url).unwrap().with_credentials("alice", "guess");
+    let result = wrong.discover_calendars().await;
+    assert!(matches!(result, Err(CalDavError::Status { status: 401, .. })), "{result:?}");
+}
@@ -0,0 +1,368 @@
+//! How items changed on both sides of a CalDAV sync are settled, first
+//! by the resolver alone and then through a sync against a server.
+
+mod support;
+
+use chrono::{DateTime, Duration, TimeZone, Utc};
+
+use kal_core::{
+    domain::{
+        calendar_object::CalendarObject,
+        event::Event,
+        repository::{
+            CalendarRepository, EventRepository, RecurringEventRepository, SyncStateRepository,
+        },
+        sync::{Conflict, ConflictResolver, ConflictStrategy, Resolution},
+        value_objects::{CalendarId, EventColor, EventId, EventStatus, TimeRange},
+    },
+    infrastructure::{
+        caldav::{CalDavClient, CalDavSynchronizer, SyncReport},
+        ical::mappers::event_id_for_uid,
+        persistence::Database,
+    },
+};
+
+use support::caldav::{CalDavStub, COLLECTION};
+
+const UID: &str = "standup@example.com";
+
+// ======================================================
+// Resolver
+// ======================================================
+
+fn at(hour: u32) -> DateTime<Utc> {
+    Utc.with_ymd_and_hms(2025, 3, 10, hour, 0, 0).unwrap()
+}
+
+fn object(id: EventId, title: &str, updated_at: DateTime<Utc>) -> CalendarObject {
+    let range = TimeRange::new(at(9), at(10)).unwrap();
+    CalendarObject::Event(
+        Event::with_id(
+            id,
+            CalendarId::new(),
+            title.into(),
+            None,
+            range,
+            EventColor::from(0),
+            false,
+            EventStatus::default(),
+            at(0),
+            updated_at,
+        )
+        .unwrap(),
+    )
+}
+
+/// Resolves a conflict between a local side edited at `local` (or
+/// deleted at `deleted`) and a remote side edited at `remote`.
+fn resolve(
+    strategy: ConflictStrategy,
+    local: Option<u32>,
+    deleted: Option<u32>,
+    remote: Option<u32>,
+) -> Resolution {
+    let id = EventId::new();
+    let conflict = Conflict {
+        item_id: id,
+        local: local.map(|hour| object(id, "Mine", at(hour))),
+        local_deleted_at: deleted.map(at),
+        remote: remote.map(|hour| object(id, "Theirs", at(hour))),
+    };
+
+    let resolved = ConflictResolver::new(strategy).resolve(&conflict);
+    assert_eq!(resolved.item_id, id);
+    assert_eq!(resolved.strategy, strategy);
+    resolved.resolution
+}
+
+#[test]
+fn both_sides_edited_follow_the_strategy() {
+    use ConflictStrategy::*;
+
+    assert_eq!(resolve(LocalWins, Some(11), None, Some(12)), Resolution::KeepLocal);
+    assert_eq!(resolve(RemoteWins, Some(12), None, Some(11)), Resolution::KeepRemote);
+
+    assert_eq!(resolve(NewestWins, Some(12), None, Some(11)), Resolution::KeepLocal);
+    assert_eq!(resolve(NewestWins, Some(11), None, Some(12)), Resolution::KeepRemote);
+    // A tie keeps the local copy
+    assert_eq!(resolve(NewestWins, Some(11), None, Some(11)), Resolution::KeepLocal);
+
+    // Each kept copy gets a fresh id
+    let Resolution::KeepBoth { copy_id: first } = resolve(KeepBoth, Some(11), None, Some(12)) else {
+        panic!("expected both copies kept");
+    };
+    let Resolution::KeepBoth { copy_id: second } = resolve(KeepBoth, Some(11), None, Some(12)) else {
+        panic!("expected both copies kept");
+    };
+    assert_ne!(first, second);
+}
+
+#[test]
+fn local_deletion_against_a_remote_edit() {
+    use ConflictStrategy::*;
+
+    assert_eq!(resolve(LocalWins, None, Some(11), Some(12)), Resolution::DeleteRemote);
+    assert_eq!(resolve(RemoteWins, None, Some(12), Some(11)), Resolution::KeepRemote);
+    // Keeping both cannot keep a deletion, so the edit survives
+    assert_eq!(resolve(KeepBoth, None, Some(12), Some(11)), Resolution::KeepRemote);
+
+    assert_eq!(resolve(NewestWins, None, Some(12), Some(11)), Resolution::DeleteRemote);
+    assert_eq!(resolve(NewestWins, None, Some(11), Some(12)), Resolution::KeepRemote);
+}
+
+#[test]
+fn remote_deletion_against_a_local_edit() {
+    use ConflictStrategy::*;
+
+    assert_eq!(resolve(LocalWins, Some(11), None, None), Resolution::KeepLocal);
+    assert_eq!(resolve(RemoteWins, Some(11), None, None), Resolution::DeleteLocal);
+    // A remote deletion has no time, so the edit is newer
+    assert_eq!(resolve(NewestWins, Some(11), None, None), Resolution::KeepLocal);
+    assert_eq!(resolve(KeepBoth, Some(11), None, None), Resolution::KeepLocal);
+
+    // Deleted on both sides
+    assert_eq!(resolve(LocalWins, None, Some(11), None), Resolution::DeleteLocal);
+}
+
+// ======================================================
+// Sync
+// ======================================================
+
+fn ics(summary: &str) -> String {
+    format!(
+        "BEGIN:VCALENDAR\r\nVERSION:2.0\r\nPRODID:-//test//EN\r\nBEGIN:VEVENT\r\n\
+         UID:{UID}\r\nSUMMARY:{summary}\r\n\
+         DTSTART:20250310T090000Z\r\nDTEND:20250310T093000Z\r\n\
+         END:VEVENT\r\nEND:VCALENDAR\r\n"
+    )
+}
+
+fn href() -> String {
+    format!("{COLLECTION}standup.ics")
+}
+
+struct Linked {
+    stub: CalDavStub,
+    client: CalDavClient,
+    database: Database,
+    calendar_id: CalendarId,
+    event_id: EventId,
+}
+
+impl Linked {
+    /// A calendar linked to the server and synced once, holding one event.
+    async fn new() -> Self {
+        let (stub, url) = CalDavStub::start().await;
+        let client = CalDavClient::new(&format!("{url}/")).unwrap();
+        let database = Database::open_in_memory().await.unwrap();
+        stub.store(&href(), &ics("Standup"));
+
+        let remote = client.discover_calendars().await.unwrap().remove(0);
+        let calendar_id = synchronizer(&client, &database).import_calendar(&remote).await.unwrap();
+
+        let linked = Self {
+            stub,
+            client,
+            database,
+            calendar_id,
+            event_id: event_id_for_uid(UID),
+        };
+        assert_eq!(linked.sync(ConflictStrategy::LocalWins).await.pulled, 1);
+        linked
+    }
+
+    async fn sync(&self, strategy: ConflictStrategy) -> SyncReport {
+        synchronizer(&self.client, &self.database)
+            .sync(&self.calendar_id, &ConflictResolver::new(strategy))
+            .await
+            .unwrap()
+    }
+
+    async fn edit_locally(&self, title: &str) {
+        let events = self.database.events();
+        let mut event = events.find_by_id(&self.event_id).await.unwrap().unwrap();
+        event.update_title(title.into());
+        events.save(&event).await.unwrap();
+    }
+
+    async fn delete_locally(&self) {
+        self.database.events().delete(&self.event_id).await.unwrap();
+    }
+
+    async fn local_titles(&self) -> Vec<String> {
+        let mut titles: Vec<String> = self
+            .database
+            .events()
+            .find_by_calendar(&self.calendar_id)
+            .await
+            .unwrap()
+            .iter()
+            .map(|event| event.title().clone())
+            .collect();
+        titles.sort();
+        titles
+    }
+
+    fn remote_summaries(&self) -> Vec<String> {
+        let mut summaries: Vec<String> = self
+            .stub
+            .hrefs()
+            .iter()
+            .filter_map(|href| self.stub.data(href))
+            .filter_map(|data| {
+                data.lines()
+                    .find_map(|line| line.strip_prefix("SUMMARY:").map(str::to_string))
+            })
+            .collect();
+        summaries.sort();
+        summaries
+    }
+
+    /// The `is_dirty` flag migration 002 keeps per item.
+    async fn is_dirty(&self, item_id: &EventId) -> bool {
+        sqlx::query_scalar::<_, bool>("SELECT is_dirty FROM sync_items WHERE item_id = ?")
+            .bind(item_id.to_string())
+            .fetch_one(self.database.pool())
+            .await
+            .unwrap()
+    }
+}
+
+fn synchronizer(
+    client: &CalDavClient,
+    database: &Database,
+) -> CalDavSynchronizer<
+    impl CalendarRepository,
+    impl EventRepository,
+    impl RecurringEventRepository,
+    impl SyncStateRepository,
+> {
+    CalDavSynchronizer::new(
+        client.clone(),
+        database.calendars(),
+        database.events(),
+        database.recurring(),
+        database.sync_state(),
+    )
+}
+
+fn resolutions(report: &SyncReport) -> Vec<Resolution> {
+    report.conflicts.resolved.iter().map(|r| r.resolution.clone()).collect()
+}
+
+#[tokio::test]
+async fn pushing_clears_the_dirty_flag() {
+    let linked = Linked::new().await;
+    assert!(!linked.is_dirty(&linked.event_id).await);
+
+    linked.edit_locally("Moved standup").await;
+    assert!(linked.is_dirty(&linked.event_id).await);
+
+    let report = linked.sync(ConflictStrategy::LocalWins).await;
+    assert_eq!(report.pushed, 1);
+    assert!(!linked.is_dirty(&linked.event_id).await);
+    let item = linked.database.sync_state().find_item(&linked.event_id).await.unwrap().unwrap();
+    assert_eq!(*item.etag(), linked.stub.etag(&href()));
+    assert!(item.last_synced_hash().is_some());
+
+    // A clean item is not pushed again
+    assert_eq!(linked.sync(ConflictStrategy::LocalWins).await.pushed, 0);
+}
+
+#[tokio::test]
+async fn edits_on_both_sides_follow_the_strategy() {
+    let linked = Linked::new().await;
+    linked.edit_locally("Mine").await;
+    linked.stub.store(&href(), &ics("Theirs"));
+    let report = linked.sync(ConflictStrategy::LocalWins).await;
+    assert_eq!(resolutions(&report), [Resolution::KeepLocal]);
+    assert_eq!(linked.local_titles().await, ["Mine"]);
+    assert_eq!(linked.remote_summaries(), ["Mine"]);
+    assert!(!linked.is_dirty(&linked.event_id).await);
+
+    let linked = Linked::new().await;
+    linked.edit_locally("Mine").await;
+    linked.stub.store(&href(), &ics("Theirs"));
+    let report = linked.sync(ConflictStrategy::RemoteWins).await;
+    assert_eq!(resolutions(&report), [Resolution::KeepRemote]);
+    assert_eq!(linked.local_titles().await, ["Theirs"]);
+    assert_eq!(linked.remote_summaries(), ["Theirs"]);
+    assert!(!linked.is_dirty(&linked.event_id).await);
+
+    // The local edit is pushed as a copy next to the remote one
+    let linked = Linked::new().await;
+    linked.edit_locally("Mine").await;
+    linked.stub.store(&href(), &ics("Theirs"));
+    let report = linked.sync(ConflictStrategy::KeepBoth).await;
+    assert!(matches!(resolutions(&report)[..], [Resolution::KeepBoth { .. }]));
+    assert_eq!(linked.local_titles().await, ["Mine", "Theirs"]);
+    assert_eq!(linked.remote_summaries(), ["Mine", "Theirs"]);
+}
+
+#[tokio::test]
+async fn local_deletion_against_a_remote_edit_follows_the_strategy() {
+    let linked = Linked::new().await;
+    linked.delete_locally().await;
+    linked.stub.store(&href(), &ics("Theirs"));
+    let report = linked.sync(ConflictStrategy::LocalWins).await;
+    assert_eq!(resolutions(&report), [Resolution::DeleteRemote]);
+    assert!(linked.stub.hrefs().is_empty());
+    assert!(linked.local_titles().await.is_empty());
+    let tombstones = linked.database.sync_state().find_tombstones(&linked.calendar_id).await.unwrap();
+    assert!(tombstones.is_empty());
+
+    let linked = Linked::new().await;
+    linked.delete_locally().await;
+    linked.stub.store(&href(), &ics("Theirs"));
+    let report = linked.sync(ConflictStrategy::RemoteWins).await;
+    assert_eq!(resolutions(&report), [Resolution::KeepRemote]);
+    assert_eq!(linked.local_titles().await, ["Theirs"]);
+    assert_eq!(linked.remote_summaries(), ["Theirs"]);
+    let tombstones = linked.database.sync_state().find_tombstones(&linked.calendar_id).await.unwrap();
+    assert!(tombstones.is_empty());
+}
+
+#[tokio::test]
+async fn remote_deletion_against_a_local_edit_follows_the_strategy() {
+    // The edit is uploaded again as a new resource
+    let linked = Linked::new().await;
+    linked.edit_locally("Mine").await;
+    linked.stub.remove(&href());
+    let report = linked.sync(ConflictStrategy::LocalWins).await;
+    assert_eq!(resolutions(&report), [Resolution::KeepLocal]);
+    assert_eq!(linked.local_titles().await, ["Mine"]);
+    assert_eq!(linked.remote_summaries(), ["Mine"]);
+    assert!(!linked.is_dirty(&linked.event_id).await);
+
+    let linked = Linked::new().await;
+    linked.edit_locally("Mine").await;
+    linked.stub.remove(&href());
+    let report = linked.sync(ConflictStrategy::RemoteWins).await;
+    assert_eq!(resolutions(&report), [Resolution::DeleteLocal]);
+    assert!(linked.local_titles().await.is_empty());
+    assert!(linked.database.sync_state().find_item(&linked.event_id).await.unwrap().is_none());
+
+    // Without a local edit the remote deletion is simply applied
+    let linked = Linked::new().await;
+    linked.stub.remove(&href());
+    let report = linked.sync(ConflictStrategy::LocalWins).await;
+    assert!(report.conflicts.is_empty());
+    assert_eq!(report.deleted_local, 1);
+    assert!(linked.local_titles().await.is_empty());
+}
+
+#[tokio::test]
+async fn newest_wins_compares_edit_times_across_a_sync() {
+    let linked = Linked::new().await;
+    // The server copy was last modified well after the local edit
+    let later = (Utc::now() + Duration::hours(1)).format("%Y%m%dT%H%M%SZ");
+    linked.edit_locally("Mine").await;
+    linked.stub.store(
+        &href(),
+        &ics("Theirs").replace("SUMMARY:", &format!("LAST-MODIFIED:{later}\r\nSUMMARY:")),
+    );
+
+    let report = linked.sync(ConflictStrategy::NewestWins).await;
+    assert_eq!(resolutions(&report), [Resolution::KeepRemote]);
+    assert_eq!(linked.local_titles().await, ["Theirs"]);
+}
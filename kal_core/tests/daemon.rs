@@ -0,0 +1,168 @@
+//! The JSON-RPC daemon, driven over a Unix socket by its client.
+
+use std::os::unix::fs::MetadataExt;
+
+use serde_json::{json, Value};
+use tokio::net::UnixListener;
+
+use kal_core::{
+    application::publisher::Publisher,
+    domain::{
+        repository::{AuditRepository, CalendarRepository},
+        value_objects::CalendarId,
+    },
+    infrastructure::{
+        persistence::Database,
+        rpc::{RpcClient, RpcDispatcher, RpcError, RpcServer},
+    },
+};
+
+/// Serves `database` on a socket in a fresh directory; returns a client.
+async fn connect(database: &Database) -> RpcClient {
+    serve(RpcServer::new(database.pool().clone())).await
+}
+
+async fn serve(server: RpcServer) -> RpcClient {
+    let dir = std::env::temp_dir().join(format!("kal-{}", uuid::Uuid::new_v4()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let path = dir.join("kal.sock");
+
+    let listener = UnixListener::bind(&path).unwrap();
+    tokio::spawn(server.serve(listener));
+    RpcClient::connect(&path).await.unwrap()
+}
+
+/// The JSON-RPC code and, for application errors, the kal error code.
+fn error_codes(result: Result<Value, RpcError>) -> (i64, Option<String>) {
+    match result {
+        Err(RpcError::Remote(error)) => {
+            let code = error.data.and_then(|d| d["code"].as_str().map(str::to_string));
+            (error.code, code)
+        }
+        other => panic!("expected an error response, got {other:?}"),
+    }
+}
+
+#[tokio::test]
+async fn clients_run_commands_and_queries_over_the_socket() {
+    let database = Database::open_in_memory().await.unwrap();
+    let mut client = connect(&database).await;
+
+    assert_eq!(client.call("daemon.ping", Value::Null).await.unwrap(), "pong");
+
+    let created = client.call("calendar.create", json!({ "name": "Work" })).await.unwrap();
+    let calendar_id = created["id"].as_str().unwrap().to_string();
+
+    let created = client
+        .call(
+            "event.create",
+            json!({
+                "calendar_id": calendar_id,
+                "title": "Standup",
+                "starts_at": "2025-03-10T09:00:00Z",
+                "ends_at": "2025-03-10T09:15:00Z",
+            }),
+        )
+        .await
+        .unwrap();
+    let event_id = created["id"].as_str().unwrap().to_string();
+
+    // Several calls share one connection
+    let event = client.call("event.get", json!({ "id": event_id })).await.unwrap();
+    assert_eq!(event["title"], "Standup");
+    assert_eq!(event["calendar_id"], calendar_id.as_str());
+
+    let events = client
+        .call("event.list", json!({ "calendar_id": calendar_id }))
+        .await
+        .unwrap();
+    assert_eq!(events.as_array().unwrap().len(), 1);
+
+    // What the daemon wrote is in the database it owns
+    let calendars = client.call("calendar.list", Value::Null).await.unwrap();
+    assert_eq!(calendars[0]["name"], "Work");
+    let stored: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM events")
+        .fetch_one(database.pool())
+        .await
+        .unwrap();
+    assert_eq!(stored, 1);
+}
+
+#[tokio::test]
+async fn failures_come_back_as_json_rpc_errors() {
+    let database = Database::open_in_memory().await.unwrap();
+    let mut client = connect(&database).await;
+
+    let missing = client.call("calendar.frobnicate", Value::Null).await;
+    assert_eq!(error_codes(missing), (-32601, None));
+
+    let malformed = client.call("calendar.get", json!({ "id": "not-a-uuid" })).await;
+    assert_eq!(error_codes(malformed), (-32602, None));
+
+    let unknown = client.call("calendar.get", json!({ "id": uuid::Uuid::new_v4() })).await;
+    assert_eq!(error_codes(unknown), (-32000, Some("calendar_not_found".into())));
+
+    let invalid = client.call("calendar.create", json!({ "name": "" })).await;
+    assert_eq!(error_codes(invalid).0, -32000);
+
+    for minutes in [i64::MAX, 0, -5, 60 * 24 * 367] {
+        let params = json!({
+            "name": "Holidays",
+            "url": "https://example.com/holidays.ics",
+            "refresh_minutes": minutes,
+        });
+        let refused = client.call("calendar.subscribe", params).await;
+        assert_eq!(error_codes(refused), (-32000, Some("invalid".into())), "{minutes}");
+    }
+
+    // The connection survives failed calls
+    assert_eq!(client.call("daemon.ping", Value::Null).await.unwrap(), "pong");
+}
+
+#[tokio::test]
+async fn malformed_lines_and_notifications() {
+    let database = Database::open_in_memory().await.unwrap();
+    let dispatcher = RpcDispatcher::new(database.pool().clone());
+
+    let response: Value =
+        serde_json::from_str(&dispatcher.handle_line("{not json").await.unwrap()).unwrap();
+    assert_eq!(response["error"]["code"], -32700);
+    assert_eq!(response["id"], Value::Null);
+
+    let line = r#"{"jsonrpc":"1.0","method":"daemon.ping","id":7}"#;
+    let response: Value =
+        serde_json::from_str(&dispatcher.handle_line(line).await.unwrap()).unwrap();
+    assert_eq!(response["error"]["code"], -32600);
+    assert_eq!(response["id"], 7);
+
+    // Notifications are carried out but not answered
+    let line = r#"{"jsonrpc":"2.0","method":"calendar.create","params":{"name":"Work"}}"#;
+    assert!(dispatcher.handle_line(line).await.is_none());
+    assert_eq!(database.calendars().find_all_active().await.unwrap().len(), 1);
+}
+
+#[tokio::test]
+async fn calls_are_attributed_to_the_user_who_connected() {
+    let database = Database::open_in_memory().await.unwrap();
+    let server = RpcServer::new(database.pool().clone())
+        .with_publisher(Publisher::new().with_actor("daemon"));
+    let mut client = serve(server).await;
+
+    let created = client.call("calendar.create", json!({ "name": "Work" })).await.unwrap();
+    let calendar_id = created["id"].as_str().unwrap().parse::<CalendarId>().unwrap();
+
+    // This process is the peer, and owns what it creates
+    let dir = std::env::temp_dir().join(format!("kal-{}", uuid::Uuid::new_v4()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let uid = std::fs::metadata(&dir).unwrap().uid().to_string();
+    let user = std::process::Command::new("id")
+        .arg("-un")
+        .output()
+        .ok()
+        .filter(|output| output.status.success())
+        .map_or(uid, |output| String::from_utf8_lossy(&output.stdout).trim().to_string());
+
+    let entries = database.audit().find_by_calendar(&calendar_id, 10).await.unwrap();
+    let actors: Vec<&str> = entries.iter().map(|entry| entry.actor().as_str()).collect();
+    assert_eq!(actors, [user.as_str()]);
+}
@@ -0,0 +1,45 @@
+//! Opening a database file: creation, pragmas and migrations.
+
+use kal_core::infrastructure::persistence::{database::MIGRATOR, Database};
+
+fn latest_version() -> i64 {
+    MIGRATOR.iter().map(|migration| migration.version).max().unwrap()
+}
+
+#[tokio::test]
+async fn open_creates_and_migrates() {
+    let dir = std::env::temp_dir().join(format!("kal-{}", uuid::Uuid::new_v4()));
+    let path = dir.join("nested").join("kal.db");
+
+    let database = Database::open(&path).await.unwrap();
+    assert!(path.exists());
+    assert_eq!(database.schema_version().await.unwrap(), latest_version());
+
+    let journal_mode: String = sqlx::query_scalar("PRAGMA journal_mode")
+        .fetch_one(database.pool())
+        .await
+        .unwrap();
+    assert_eq!(journal_mode, "wal");
+
+    let foreign_keys: i64 = sqlx::query_scalar("PRAGMA foreign_keys")
+        .fetch_one(database.pool())
+        .await
+        .unwrap();
+    assert_eq!(foreign_keys, 1);
+
+    database.pool().close().await;
+
+    // Opening again finds nothing left to apply
+    let database = Database::open(&path).await.unwrap();
+    assert_eq!(database.schema_version().await.unwrap(), latest_version());
+    database.pool().close().await;
+
+    std::fs::remove_dir_all(dir).unwrap();
+}
+
+#[tokio::test]
+async fn in_memory_is_migrated() {
+    let database = Database::open_in_memory().await.unwrap();
+
+    assert_eq!(database.schema_version().await.unwrap(), latest_version());
+}
@@ -0,0 +1,288 @@
+//! Mutators raise domain events on the aggregates, and a `Publisher`
+//! hands them to its subscribers once the changes are stored.
+
+mod support;
+
+use std::sync::{Arc, Mutex};
+
+use async_trait::async_trait;
+use chrono::{TimeZone, Utc};
+
+use kal_core::{
+    application::{
+        commands::{
+            calendars::{ArchiveCalendarCommand, ArchiveCalendarHandler},
+            events::{
+                CreateEventCommand, CreateEventHandler, DeleteEventCommand, DeleteEventHandler,
+                UpdateEventTimeRangeCommand, UpdateEventTimeRangeHandler, UpdateEventTitleCommand,
+                UpdateEventTitleHandler,
+            },
+            history::{UndoCommand, UndoHandler},
+            trash::{PurgeTrashCommand, PurgeTrashHandler},
+            recurring::{
+                CancelRecurringOccurrenceCommand, CancelRecurringOccurrenceHandler,
+                CreateRecurringEventCommand, CreateRecurringEventHandler,
+            },
+        },
+        history::Recorder,
+        publisher::Publisher,
+    },
+    domain::{
+        domain_event::{DomainEvent, EventContext, EventSubscriber, FieldChange},
+        recurrence::RecurrenceRule,
+        repository::{CalendarRepository, EventRepository, HistoryRepository, RepositoryError},
+        value_objects::{CalendarId, EventColor, EventId, EventStatus, Frequency},
+    },
+    infrastructure::persistence::Database,
+};
+
+use support::{calendar, event, range};
+
+/// Keeps every event it is told about.
+#[derive(Default)]
+struct Collector {
+    events: Mutex<Vec<DomainEvent>>,
+}
+
+impl Collector {
+    fn names(&self) -> Vec<&'static str> {
+        self.events.lock().unwrap().iter().map(DomainEvent::name).collect()
+    }
+}
+
+#[async_trait]
+impl EventSubscriber for Collector {
+    async fn notify(&self, event: &DomainEvent, _context: &EventContext) {
+        self.events.lock().unwrap().push(event.clone());
+    }
+}
+
+fn publisher() -> (Publisher, Arc<Collector>) {
+    let collector = Arc::new(Collector::default());
+    (Publisher::new().subscribe(collector.clone()), collector)
+}
+
+async fn create_event(database: &Database, publisher: &Publisher, calendar_id: CalendarId) -> EventId {
+    CreateEventHandler::new(publisher.track(database.events()), database.calendars())
+        .handle(CreateEventCommand::new(
+            calendar_id,
+            "Meeting".into(),
+            None,
+            range(10, 9, 10),
+            EventColor::from(0),
+            false,
+        ))
+        .await
+        .unwrap()
+}
+
+#[test]
+fn mutators_raise_events_for_what_changed() {
+    let calendar_id = CalendarId::new();
+    let mut event = event(calendar_id, "Meeting", range(10, 9, 10));
+    let event_id = *event.event_id();
+    assert!(event.pending_events().is_empty());
+
+    event.update_title("Meeting".into());
+    event.update_title("Review".into());
+    event.update_time_range(range(11, 9, 10));
+    event.cancel();
+    event.restore();
+    event.set_status(EventStatus::Tentative);
+
+    assert_eq!(
+        event.take_pending_events(),
+        vec![
+            DomainEvent::EventChanged {
+                calendar_id,
+                event_id,
+                changes: vec![FieldChange::new(
+                    "title",
+                    Some("Meeting".into()),
+                    Some("Review".into())
+                )],
+            },
+            DomainEvent::EventRescheduled {
+                calendar_id,
+                event_id,
+                from: range(10, 9, 10),
+                to: range(11, 9, 10),
+            },
+            DomainEvent::EventCancelled { calendar_id, event_id },
+            DomainEvent::EventRestored { calendar_id, event_id },
+            DomainEvent::EventChanged {
+                calendar_id,
+                event_id,
+                changes: vec![FieldChange::new(
+                    "status",
+                    Some("CONFIRMED".into()),
+                    Some("TENTATIVE".into())
+                )],
+            },
+        ]
+    );
+    assert!(event.pending_events().is_empty());
+}
+
+#[tokio::test]
+async fn subscribers_hear_of_changes_once_published() {
+    let database = Database::open_in_memory().await.unwrap();
+    let (publisher, collector) = publisher();
+    let calendar_id = calendar(&database).await;
+
+    let id = create_event(&database, &publisher, calendar_id).await;
+    UpdateEventTimeRangeHandler::new(publisher.track(database.events()), database.calendars())
+        .handle(UpdateEventTimeRangeCommand::new(id, range(11, 9, 10)))
+        .await
+        .unwrap();
+    DeleteEventHandler::new(publisher.track(database.events()), database.calendars())
+        .handle(DeleteEventCommand::new(id))
+        .await
+        .unwrap();
+    assert!(collector.names().is_empty());
+
+    publisher.publish("event.update").await;
+    assert_eq!(collector.names(), ["EventCreated", "EventRescheduled", "EventTrashed"]);
+    assert!(collector.events.lock().unwrap().iter().all(|e| e.calendar_id() == calendar_id));
+
+    // The queue was drained
+    publisher.publish("event.update").await;
+    assert_eq!(collector.names().len(), 3);
+}
+
+#[tokio::test]
+async fn a_failed_save_raises_nothing() {
+    let database = Database::open_in_memory().await.unwrap();
+    let (publisher, _) = publisher();
+    let calendar_id = calendar(&database).await;
+    let id = create_event(&database, &publisher, calendar_id).await;
+    publisher.take();
+
+    // Someone else saves first, so this copy is stale
+    let mut stale = database.events().find_by_id(&id).await.unwrap().unwrap();
+    UpdateEventTitleHandler::new(database.events(), database.calendars())
+        .handle(UpdateEventTitleCommand::new(id, "Review".into()))
+        .await
+        .unwrap();
+
+    stale.update_title("Planning".into());
+    let result = publisher.track(database.events()).save(&stale).await;
+    assert!(matches!(result, Err(RepositoryError::Conflict)));
+    assert!(publisher.take().is_empty());
+}
+
+#[tokio::test]
+async fn calendars_and_series_raise_their_own_events() {
+    let database = Database::open_in_memory().await.unwrap();
+    let (publisher, collector) = publisher();
+    let calendar_id = calendar(&database).await;
+
+    let series = CreateRecurringEventHandler::new(publisher.track(database.recurring()), database.calendars())
+        .handle(CreateRecurringEventCommand::new(
+            calendar_id,
+            "Standup".into(),
+            None,
+            range(10, 9, 10),
+            RecurrenceRule::new(Frequency::Daily, 1, None).unwrap(),
+            EventColor::from(0),
+            false,
+        ))
+        .await
+        .unwrap();
+    let occurrence = *range(12, 9, 10).starts_at();
+    CancelRecurringOccurrenceHandler::new(publisher.track(database.recurring()), database.calendars())
+        .handle(CancelRecurringOccurrenceCommand::new(series, occurrence))
+        .await
+        .unwrap();
+    ArchiveCalendarHandler::new(publisher.track(database.calendars()))
+        .handle(ArchiveCalendarCommand::new(calendar_id))
+        .await
+        .unwrap();
+    publisher.publish("calendar.archive").await;
+
+    let events = collector.events.lock().unwrap().clone();
+    assert_eq!(
+        events,
+        vec![
+            DomainEvent::SeriesCreated { calendar_id, event_id: series },
+            DomainEvent::OccurrenceCancelled { calendar_id, event_id: series, occurrence },
+            DomainEvent::CalendarArchived { calendar_id },
+        ]
+    );
+    assert_eq!(events[1].object_id(), series.as_uuid());
+    assert_eq!(events[2].object_id(), calendar_id.as_uuid());
+}
+
+#[tokio::test]
+async fn undo_publishes_what_it_puts_back() {
+    let database = Database::open_in_memory().await.unwrap();
+    let (publisher, collector) = publisher();
+    let recorder = Recorder::new();
+    let calendar_id = calendar(&database).await;
+    let id = create_event(&database, &publisher, calendar_id).await;
+
+    UpdateEventTitleHandler::new(recorder.record(database.events()), database.calendars())
+        .handle(UpdateEventTitleCommand::new(id, "Review".into()))
+        .await
+        .unwrap();
+    database
+        .history()
+        .record("event.update", Utc::now(), &recorder.take())
+        .await
+        .unwrap();
+    publisher.take();
+
+    UndoHandler::new(
+        publisher.track(database.calendars()),
+        publisher.track(database.events()),
+        publisher.track(database.recurring()),
+        database.tasks(),
+        database.journal(),
+        database.history(),
+    )
+    .handle(UndoCommand)
+    .await
+    .unwrap();
+    publisher.publish("history.undo").await;
+
+    // Undo replaces the event wholesale, so no fields are named
+    assert_eq!(
+        *collector.events.lock().unwrap(),
+        vec![DomainEvent::EventChanged { calendar_id, event_id: id, changes: Vec::new() }]
+    );
+}
+
+#[tokio::test]
+async fn purging_the_trash_publishes_what_went() {
+    let database = Database::open_in_memory().await.unwrap();
+    let (publisher, collector) = publisher();
+    let calendar_id = calendar(&database).await;
+    let purged = create_event(&database, &publisher, calendar_id).await;
+    let kept = create_event(&database, &publisher, calendar_id).await;
+    let gone = calendar(&database).await;
+    publisher.take();
+
+    let cutoff = Utc.with_ymd_and_hms(2025, 3, 1, 0, 0, 0).unwrap();
+    let before = cutoff - chrono::Duration::days(1);
+    database.events().trash(&purged, before).await.unwrap();
+    database.events().trash(&kept, cutoff).await.unwrap();
+    database.calendars().trash(&gone, before).await.unwrap();
+
+    PurgeTrashHandler::new(
+        publisher.track(database.calendars()),
+        publisher.track(database.events()),
+        publisher.track(database.recurring()),
+    )
+    .handle(PurgeTrashCommand::new(cutoff))
+    .await
+    .unwrap();
+    publisher.publish("trash.purge").await;
+
+    assert_eq!(
+        *collector.events.lock().unwrap(),
+        vec![
+            DomainEvent::CalendarDeleted { calendar_id: gone },
+            DomainEvent::EventDeleted { calendar_id, event_id: purged },
+        ]
+    );
+}
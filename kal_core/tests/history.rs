@@ -0,0 +1,411 @@
+//! Commands recorded through a `Recorder` can be undone and redone from
+//! the history stored alongside the calendars.
+
+mod support;
+
+use chrono::{Duration, Utc};
+use serde_json::{json, Value};
+
+use kal_core::{
+    application::{
+        commands::{
+            calendars::{DeleteCalendarCommand, DeleteCalendarHandler},
+            events::{
+                CreateEventCommand, CreateEventHandler, DeleteEventCommand, DeleteEventHandler,
+                UpdateEventTitleCommand, UpdateEventTitleHandler,
+            },
+            history::{RedoCommand, RedoHandler, UndoCommand, UndoHandler},
+        },
+        error::ApplicationError,
+        history::Recorder,
+    },
+    domain::{
+        repository::{CalendarRepository, EventRepository, HistoryRepository},
+        value_objects::{CalendarId, EventColor, EventId},
+    },
+    infrastructure::{
+        persistence::{
+            Database, SqliteCalendarRepository, SqliteEventRepository, SqliteHistoryRepository,
+            SqliteJournalRepository, SqliteRecurringEventRepository, SqliteTaskRepository,
+        },
+        rpc::RpcDispatcher,
+    },
+};
+
+use support::{calendar, range};
+
+type Undo = UndoHandler<
+    SqliteCalendarRepository,
+    SqliteEventRepository,
+    SqliteRecurringEventRepository,
+    SqliteTaskRepository,
+    SqliteJournalRepository,
+    SqliteHistoryRepository,
+>;
+
+type Redo = RedoHandler<
+    SqliteCalendarRepository,
+    SqliteEventRepository,
+    SqliteRecurringEventRepository,
+    SqliteTaskRepository,
+    SqliteJournalRepository,
+    SqliteHistoryRepository,
+>;
+
+fn undo(database: &Database) -> Undo {
+    UndoHandler::new(
+        database.calendars(),
+        database.events(),
+        database.recurring(),
+        database.tasks(),
+        database.journal(),
+        database.history(),
+    )
+}
+
+fn redo(database: &Database) -> Redo {
+    RedoHandler::new(
+        database.calendars(),
+        database.events(),
+        database.recurring(),
+        database.tasks(),
+        database.journal(),
+        database.history(),
+    )
+}
+
+/// Adds what the recorder collected to the history, as the dispatcher
+/// does after each call.
+async fn commit(database: &Database, recorder: &Recorder, command: &str) {
+    database
+        .history()
+        .record(command, Utc::now(), &recorder.take())
+        .await
+        .unwrap();
+}
+
+async fn create_event(database: &Database, recorder: &Recorder, calendar_id: CalendarId) -> EventId {
+    let id = CreateEventHandler::new(recorder.record(database.events()), database.calendars())
+        .handle(CreateEventCommand::new(
+            calendar_id,
+            "Meeting".into(),
+            None,
+            range(10, 9, 10),
+            EventColor::from(0),
+            false,
+        ))
+        .await
+        .unwrap();
+    commit(database, recorder, "event.create").await;
+
+    id
+}
+
+/// Renames an event recording it, or outside the history without.
+async fn rename(database: &Database, recorder: Option<&Recorder>, id: EventId, title: &str) {
+    let command = UpdateEventTitleCommand::new(id, title.into());
+    match recorder {
+        Some(recorder) => {
+            UpdateEventTitleHandler::new(recorder.record(database.events()), database.calendars())
+                .handle(command)
+                .await
+                .unwrap();
+            commit(database, recorder, "event.update").await;
+        }
+        None => {
+            UpdateEventTitleHandler::new(database.events(), database.calendars())
+                .handle(command)
+                .await
+                .unwrap();
+        }
+    }
+}
+
+#[tokio::test]
+async fn undo_and_redo_walk_back_and_forth() {
+    let database = Database::open_in_memory().await.unwrap();
+    let recorder = Recorder::new();
+    let calendar_id = calendar(&database).await;
+
+    let id = create_event(&database, &recorder, calendar_id).await;
+    DeleteEventHandler::new(recorder.record(database.events()), database.calendars())
+        .handle(DeleteEventCommand::new(id))
+        .await
+        .unwrap();
+    commit(&database, &recorder, "event.delete").await;
+
+    let undone = undo(&database).handle(UndoCommand).await.unwrap();
+    assert_eq!(undone.command(), "event.delete");
+    assert!(database.events().find_by_id(&id).await.unwrap().is_some());
+
+    undo(&database).handle(UndoCommand).await.unwrap();
+    assert!(database.events().find_by_id(&id).await.unwrap().is_none());
+    assert!(database.events().find_trashed().await.unwrap().is_empty());
+    assert!(matches!(
+        undo(&database).handle(UndoCommand).await,
+        Err(ApplicationError::NothingToUndo)
+    ));
+
+    let redone = redo(&database).handle(RedoCommand).await.unwrap();
+    assert_eq!(redone.command(), "event.create");
+    assert!(database.events().find_by_id(&id).await.unwrap().is_some());
+
+    redo(&database).handle(RedoCommand).await.unwrap();
+    assert!(database.events().find_by_id(&id).await.unwrap().is_none());
+    assert_eq!(database.events().find_trashed().await.unwrap().len(), 1);
+    assert!(matches!(
+        redo(&database).handle(RedoCommand).await,
+        Err(ApplicationError::NothingToRedo)
+    ));
+}
+
+#[tokio::test]
+async fn undo_puts_back_the_previous_state() {
+    let database = Database::open_in_memory().await.unwrap();
+    let recorder = Recorder::new();
+    let calendar_id = calendar(&database).await;
+    let id = create_event(&database, &recorder, calendar_id).await;
+
+    UpdateEventTitleHandler::new(recorder.record(database.events()), database.calendars())
+        .handle(UpdateEventTitleCommand::new(id, "Review".into()))
+        .await
+        .unwrap();
+    commit(&database, &recorder, "event.update").await;
+
+    undo(&database).handle(UndoCommand).await.unwrap();
+    let event = database.events().find_by_id(&id).await.unwrap().unwrap();
+    assert_eq!(event.title(), "Meeting");
+
+    // Replays save against the stored version, so later edits still work
+    UpdateEventTitleHandler::new(database.events(), database.calendars())
+        .handle(UpdateEventTitleCommand::new(id, "Planning".into()))
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+async fn undo_and_redo_refuse_to_clobber_edits_made_outside_the_history() {
+    let database = Database::open_in_memory().await.unwrap();
+    let recorder = Recorder::new();
+    let calendar_id = calendar(&database).await;
+
+    let edited = create_event(&database, &recorder, calendar_id).await;
+    rename(&database, Some(&recorder), edited, "Review").await;
+    rename(&database, None, edited, "Planning").await;
+    assert!(matches!(
+        undo(&database).handle(UndoCommand).await,
+        Err(ApplicationError::Conflict)
+    ));
+    let event = database.events().find_by_id(&edited).await.unwrap().unwrap();
+    assert_eq!(event.title(), "Planning");
+
+    // Trashing and restoring the calendar moves the version on but leaves
+    // the event as it was
+    let id = create_event(&database, &recorder, calendar_id).await;
+    rename(&database, Some(&recorder), id, "Review").await;
+    database.calendars().trash(&calendar_id, Utc::now()).await.unwrap();
+    database.calendars().restore(&calendar_id).await.unwrap();
+    undo(&database).handle(UndoCommand).await.unwrap();
+
+    rename(&database, None, id, "Retro").await;
+    assert!(matches!(
+        redo(&database).handle(RedoCommand).await,
+        Err(ApplicationError::Conflict)
+    ));
+    let event = database.events().find_by_id(&id).await.unwrap().unwrap();
+    assert_eq!(event.title(), "Retro");
+}
+
+#[tokio::test]
+async fn undoing_a_calendar_delete_brings_back_its_events() {
+    let database = Database::open_in_memory().await.unwrap();
+    let recorder = Recorder::new();
+    let calendar_id = calendar(&database).await;
+    let id = create_event(&database, &recorder, calendar_id).await;
+
+    DeleteCalendarHandler::new(recorder.record(database.calendars()))
+        .handle(DeleteCalendarCommand::new(calendar_id))
+        .await
+        .unwrap();
+    commit(&database, &recorder, "calendar.delete").await;
+    assert!(database.events().find_by_id(&id).await.unwrap().is_none());
+
+    undo(&database).handle(UndoCommand).await.unwrap();
+    assert!(database.calendars().find_by_id(&calendar_id).await.unwrap().is_some());
+    assert!(database.events().find_by_id(&id).await.unwrap().is_some());
+}
+
+#[tokio::test]
+async fn a_new_command_after_undo_leaves_nothing_to_redo() {
+    let database = Database::open_in_memory().await.unwrap();
+    let recorder = Recorder::new();
+    let calendar_id = calendar(&database).await;
+    let first = create_event(&database, &recorder, calendar_id).await;
+
+    undo(&database).handle(UndoCommand).await.unwrap();
+    let second = create_event(&database, &recorder, calendar_id).await;
+
+    assert!(matches!(
+        redo(&database).handle(RedoCommand).await,
+        Err(ApplicationError::NothingToRedo)
+    ));
+    assert!(database.events().find_by_id(&first).await.unwrap().is_none());
+
+    // The undone command is off the current branch
+    let applied = database.history().find_applied(10).await.unwrap();
+    assert_eq!(applied.len(), 1);
+    assert_eq!(*applied[0].changes()[0].object_id(), second.as_uuid());
+}
+
+#[tokio::test]
+async fn history_outlives_the_process_that_recorded_it() {
+    let dir = std::env::temp_dir().join(format!("kal-{}", uuid::Uuid::new_v4()));
+    let path = dir.join("kal.db");
+
+    let database = Database::open(&path).await.unwrap();
+    let recorder = Recorder::new();
+    let calendar_id = calendar(&database).await;
+    let id = create_event(&database, &recorder, calendar_id).await;
+    database.pool().close().await;
+
+    let database = Database::open(&path).await.unwrap();
+    let undone = undo(&database).handle(UndoCommand).await.unwrap();
+    assert_eq!(undone.command(), "event.create");
+    assert!(database.events().find_by_id(&id).await.unwrap().is_none());
+    database.pool().close().await;
+
+    let database = Database::open(&path).await.unwrap();
+    redo(&database).handle(RedoCommand).await.unwrap();
+    assert!(database.events().find_by_id(&id).await.unwrap().is_some());
+    database.pool().close().await;
+
+    std::fs::remove_dir_all(dir).unwrap();
+}
+
+#[tokio::test]
+async fn history_is_append_only() {
+    let database = Database::open_in_memory().await.unwrap();
+    let recorder = Recorder::new();
+    let calendar_id = calendar(&database).await;
+    create_event(&database, &recorder, calendar_id).await;
+
+    for statement in [
+        "DELETE FROM history_entries",
+        "UPDATE history_entries SET command = 'x'",
+        "DELETE FROM history_changes",
+        "UPDATE history_changes SET after_json = NULL",
+    ] {
+        let result = sqlx::query(statement).execute(database.pool()).await;
+        assert!(result.is_err(), "{statement} should be refused");
+    }
+}
+
+#[tokio::test]
+async fn a_call_and_its_history_entry_land_together() {
+    let database = Database::open_in_memory().await.unwrap();
+    let dispatcher = RpcDispatcher::new(database.pool().clone());
+    let call = |name: &str| {
+        json!({
+            "jsonrpc": "2.0",
+            "id": 1,
+            "method": "calendar.create",
+            "params": { "name": name },
+        })
+        .to_string()
+    };
+
+    dispatcher.handle_line(&call("Work")).await.unwrap();
+    assert_eq!(database.history().find_applied(10).await.unwrap().len(), 1);
+
+    // When the entry cannot be written, the change it records goes too
+    sqlx::query(
+        "CREATE TRIGGER no_history BEFORE INSERT ON history_entries \
+         BEGIN SELECT RAISE(ABORT, 'history is full'); END",
+    )
+    .execute(database.pool())
+    .await
+    .unwrap();
+    let response = dispatcher.handle_line(&call("Home")).await.unwrap();
+    let response: Value = serde_json::from_str(&response).unwrap();
+    assert!(response["error"].is_object());
+
+    let names: Vec<String> = database
+        .calendars()
+        .find_all_active()
+        .await
+        .unwrap()
+        .iter()
+        .map(|c| c.name().clone())
+        .collect();
+    assert_eq!(names, ["Work"]);
+    assert_eq!(database.history().find_applied(10).await.unwrap().len(), 1);
+}
+
+#[tokio::test]
+async fn undo_puts_back_exactly_what_was_there() {
+    let database = Database::open_in_memory().await.unwrap();
+    let recorder = Recorder::new();
+    let calendar_id = calendar(&database).await;
+    let id = create_event(&database, &recorder, calendar_id).await;
+    let created = database.events().find_by_id(&id).await.unwrap().unwrap();
+
+    UpdateEventTitleHandler::new(recorder.record(database.events()), database.calendars())
+        .handle(UpdateEventTitleCommand::new(id, "Review".into()))
+        .await
+        .unwrap();
+    commit(&database, &recorder, "event.update").await;
+    undo(&database).handle(UndoCommand).await.unwrap();
+
+    // Down to the sub-second creation time iCalendar would round away;
+    // only the stored revision has moved on
+    let restored = database.events().find_by_id(&id).await.unwrap().unwrap();
+    let mut expected = serde_json::to_value(&created).unwrap();
+    let mut actual = serde_json::to_value(&restored).unwrap();
+    expected.as_object_mut().unwrap().remove("version");
+    actual.as_object_mut().unwrap().remove("version");
+    assert_eq!(actual, expected);
+}
+
+#[tokio::test]
+async fn undo_and_redo_step_past_entries_of_a_purged_calendar() {
+    let database = Database::open_in_memory().await.unwrap();
+    let recorder = Recorder::new();
+    let calendar_id = calendar(&database).await;
+    let id = create_event(&database, &recorder, calendar_id).await;
+    UpdateEventTitleHandler::new(recorder.record(database.events()), database.calendars())
+        .handle(UpdateEventTitleCommand::new(id, "Review".into()))
+        .await
+        .unwrap();
+    commit(&database, &recorder, "event.update").await;
+    let applied = database.history().find_applied(10).await.unwrap();
+    let (updated, created) = (*applied[0].id(), *applied[1].id());
+
+    database.calendars().trash(&calendar_id, Utc::now()).await.unwrap();
+    database.calendars().purge_trashed(Utc::now() + Duration::days(1)).await.unwrap();
+
+    // Through the daemon, so the cursor has to survive the failed call
+    let dispatcher = RpcDispatcher::new(database.pool().clone());
+    let request = json!({ "jsonrpc": "2.0", "id": 1, "method": "history.undo" });
+    let response = dispatcher.handle_line(&request.to_string()).await.unwrap();
+    let response: Value = serde_json::from_str(&response).unwrap();
+    assert_eq!(response["error"]["data"]["code"], "calendar_purged");
+    let current = database.history().find_current().await.unwrap().unwrap();
+    assert_eq!(*current.id(), created);
+
+    // Undoing the create only removes what is already gone
+    undo(&database).handle(UndoCommand).await.unwrap();
+
+    for entry in [created, updated] {
+        match redo(&database).handle(RedoCommand).await {
+            Err(ApplicationError::CalendarPurged(skipped, calendar)) => {
+                assert_eq!((skipped, calendar), (entry, calendar_id));
+            }
+            other => panic!("expected a skipped entry, got {other:?}"),
+        }
+    }
+    assert!(database.events().find_by_id(&id).await.unwrap().is_none());
+    assert!(matches!(
+        redo(&database).handle(RedoCommand).await,
+        Err(ApplicationError::NothingToRedo)
+    ));
+}
@@ -0,0 +1,140 @@
+//! Times and recurrence read from other clients' iCalendar data.
+
+use chrono::{DateTime, Local, NaiveDate, TimeZone, Utc};
+
+use kal_core::{
+    domain::{calendar_object::CalendarObject, value_objects::CalendarId},
+    infrastructure::ical::{Component, IcalError, IcalMapper},
+};
+
+fn vcalendar(body: &str) -> Component {
+    let ics = format!(
+        "BEGIN:VCALENDAR\r\nVERSION:2.0\r\nPRODID:-//test//EN\r\n{body}END:VCALENDAR\r\n"
+    );
+    Component::parse(&ics).unwrap()
+}
+
+fn vevent(properties: &str) -> String {
+    format!("BEGIN:VEVENT\r\nUID:meeting@example.com\r\nSUMMARY:Meeting\r\n{properties}END:VEVENT\r\n")
+}
+
+fn import(body: &str) -> Result<CalendarObject, IcalError> {
+    let mut objects = IcalMapper::to_domain(&vcalendar(body), CalendarId::new())?;
+    assert_eq!(objects.len(), 1);
+    Ok(objects.remove(0))
+}
+
+fn starts_at(object: &CalendarObject) -> DateTime<Utc> {
+    match object {
+        CalendarObject::Event(event) => *event.time_range().starts_at(),
+        CalendarObject::Recurring(series) => *series.time_range().starts_at(),
+    }
+}
+
+fn utc(y: i32, m: u32, d: u32, h: u32, min: u32) -> DateTime<Utc> {
+    Utc.with_ymd_and_hms(y, m, d, h, min, 0).unwrap()
+}
+
+#[test]
+fn tzid_times_are_resolved_through_the_timezone_database() {
+    let object = import(&vevent(
+        "DTSTART;TZID=Europe/Berlin:20251020T090000\r\nDTEND;TZID=Europe/Berlin:20251020T100000\r\n",
+    ))
+    .unwrap();
+    assert_eq!(starts_at(&object), utc(2025, 10, 20, 7, 0));
+
+    // Winter time is an hour further from UTC
+    let object = import(&vevent(
+        "DTSTART;TZID=Europe/Berlin:20251120T090000\r\nDURATION:PT1H\r\n",
+    ))
+    .unwrap();
+    assert_eq!(starts_at(&object), utc(2025, 11, 20, 8, 0));
+
+    // Exported back as the same instant
+    let ics = IcalMapper::to_ics(&object);
+    assert!(ics.contains("DTSTART:20251120T080000Z"), "{ics}");
+}
+
+#[test]
+fn prefixed_and_vtimezone_tzids_resolve_to_their_location() {
+    let object = import(&vevent(
+        "DTSTART;TZID=/citadel.org/20190914_1/America/New_York:20250710T090000\r\nDURATION:PT1H\r\n",
+    ))
+    .unwrap();
+    assert_eq!(starts_at(&object), utc(2025, 7, 10, 13, 0));
+
+    let body = format!(
+        "BEGIN:VTIMEZONE\r\nTZID:W. Europe Standard Time\r\nX-LIC-LOCATION:Europe/Berlin\r\nEND:VTIMEZONE\r\n{}",
+        vevent("DTSTART;TZID=W. Europe Standard Time:20250710T090000\r\nDURATION:PT1H\r\n"),
+    );
+    assert_eq!(starts_at(&import(&body).unwrap()), utc(2025, 7, 10, 7, 0));
+}
+
+#[test]
+fn unknown_tzids_fail_to_map_instead_of_reading_as_utc() {
+    let result = import(&vevent(
+        "DTSTART;TZID=Olympus Mons Time:20250710T090000\r\nDURATION:PT1H\r\n",
+    ));
+    assert!(matches!(result, Err(IcalError::Unsupported(_))), "{result:?}");
+}
+
+#[test]
+fn times_skipped_by_a_clock_change_take_the_offset_before_it() {
+    // Clocks in Berlin jumped from 02:00 to 03:00 that night
+    let object = import(&vevent(
+        "DTSTART;TZID=Europe/Berlin:20250330T023000\r\nDURATION:PT1H\r\n",
+    ))
+    .unwrap();
+    assert_eq!(starts_at(&object), utc(2025, 3, 30, 1, 30));
+}
+
+#[test]
+fn floating_times_are_local() {
+    let object = import(&vevent("DTSTART:20250710T090000\r\nDURATION:PT1H\r\n")).unwrap();
+
+    let local = NaiveDate::from_ymd_opt(2025, 7, 10).unwrap().and_hms_opt(9, 0, 0).unwrap();
+    let expected = Local.from_local_datetime(&local).earliest().unwrap();
+    assert_eq!(starts_at(&object), expected.with_timezone(&Utc));
+}
+
+#[test]
+fn huge_counts_and_intervals_are_rejected_rather_than_overflowing() {
+    for rrule in [
+        "FREQ=DAILY;INTERVAL=2;COUNT=4294967295",
+        "FREQ=YEARLY;COUNT=100000000",
+        "FREQ=WEEKLY;INTERVAL=4000000000;COUNT=3",
+        "FREQ=MONTHLY;INTERVAL=4000000000;COUNT=2",
+    ] {
+        let result = import(&vevent(&format!(
+            "DTSTART:20250710T090000Z\r\nDURATION:PT1H\r\nRRULE:{rrule}\r\n"
+        )));
+        assert!(matches!(result, Err(IcalError::InvalidValue("RRULE", _))), "{rrule}: {result:?}");
+    }
+
+    // A count within range still ends the series
+    let object = import(&vevent(
+        "DTSTART:20250710T090000Z\r\nDURATION:PT1H\r\nRRULE:FREQ=WEEKLY;INTERVAL=2;COUNT=3\r\n",
+    ))
+    .unwrap();
+    let CalendarObject::Recurring(series) = object else {
+        panic!("expected a series");
+    };
+    assert_eq!(*series.rule().until(), Some(utc(2025, 8, 7, 9, 0)));
+}
+
+#[test]
+fn huge_durations_are_rejected_rather_than_overflowing() {
+    for properties in [
+        "DTSTART:20250710T090000Z\r\nDURATION:P99999999999999W\r\n",
+        "DTSTART:20250710T090000Z\r\nDURATION:P9999999999D\r\n",
+        "DTSTART:20250710T090000Z\r\nDURATION:P1000000DT9223372036854775807S\r\n",
+    ] {
+        let result = import(&vevent(properties));
+        assert!(matches!(result, Err(IcalError::InvalidValue(_, _))), "{properties}: {result:?}");
+    }
+
+    let alarm = "DTSTART:20250710T090000Z\r\nDURATION:PT1H\r\n\
+                 BEGIN:VALARM\r\nACTION:DISPLAY\r\nTRIGGER:-P9999999999D\r\nEND:VALARM\r\n";
+    let result = import(&vevent(alarm));
+    assert!(matches!(result, Err(IcalError::InvalidValue(_, _))), "{result:?}");
+}
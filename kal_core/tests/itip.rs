@@ -0,0 +1,349 @@
+//! iTIP scheduling: requests and cancellations sent to attendees, as
+//! the organizer's copy changes, and replies applied back to it.
+
+mod support;
+
+use std::sync::{Arc, Mutex};
+
+use async_trait::async_trait;
+use chrono::{Duration, Utc};
+
+use kal_core::{
+    application::{
+        commands::scheduling::{ApplyReplyCommand, ApplyReplyHandler, ReplyOutcome},
+        publisher::Publisher,
+    },
+    domain::{
+        attendee::{Attendee, AttendeeRole, Organizer, ParticipationStatus},
+        calendar::Calendar,
+        calendar_object::CalendarObject,
+        event::Event,
+        recurrence::{RecurrenceRule, RecurringEvent},
+        reminder::{Reminder, ReminderAction, ReminderTrigger},
+        repository::{CalendarRepository, EventRepository, RecurringEventRepository},
+        value_objects::{CalendarId, EventColor, Frequency},
+    },
+    infrastructure::{
+        itip::{
+            DirectoryTransport, ItipError, ItipMessage, ItipMethod, ItipReply, ItipSubscriber,
+            Transport,
+        },
+        persistence::Database,
+    },
+};
+
+use support::range;
+
+fn attendee(email: &str) -> Attendee {
+    Attendee::new(
+        email.into(),
+        None,
+        AttendeeRole::Required,
+        ParticipationStatus::NeedsAction,
+        true,
+    )
+    .unwrap()
+}
+
+/// A meeting organized by alice, with alice herself, bob and carol invited.
+fn meeting(calendar_id: CalendarId) -> Event {
+    let mut event = support::event(calendar_id, "Review", range(10, 9, 10));
+    event.set_organizer(Some(Organizer::new("alice@example.com".into(), None).unwrap()));
+    for email in ["Alice@example.com", "bob@example.com", "carol@example.com"] {
+        event.add_attendee(attendee(email)).unwrap();
+    }
+    event
+}
+
+/// Keeps every message sent, as method and sequence.
+#[derive(Default)]
+struct Outbox {
+    sent: Mutex<Vec<(ItipMethod, u32)>>,
+}
+
+#[async_trait]
+impl Transport for Outbox {
+    async fn send(&self, message: &ItipMessage) -> Result<(), ItipError> {
+        self.sent.lock().unwrap().push((*message.method(), *message.sequence()));
+        Ok(())
+    }
+}
+
+fn reply(uid: &str, sequence: u32, attendees: &[&str], extra: &str) -> String {
+    let attendees: String = attendees.iter().map(|a| format!("ATTENDEE;{a}\r\n")).collect();
+    format!(
+        "BEGIN:VCALENDAR\r\nVERSION:2.0\r\nPRODID:-//test//EN\r\nMETHOD:REPLY\r\n\
+         BEGIN:VEVENT\r\nUID:{uid}\r\nSEQUENCE:{sequence}\r\n\
+         DTSTART:20250310T090000Z\r\nDTSTAMP:20250301T120000Z\r\n\
+         ORGANIZER:mailto:alice@example.com\r\n{attendees}{extra}END:VEVENT\r\nEND:VCALENDAR\r\n"
+    )
+}
+
+#[test]
+fn requests_go_to_every_attendee_but_the_organizer() {
+    let mut event = meeting(CalendarId::new());
+    event.add_reminder(
+        Reminder::new(
+            ReminderTrigger::Relative(-Duration::minutes(10)),
+            ReminderAction::Display,
+            None,
+            0,
+            None,
+        )
+        .unwrap(),
+    );
+    let object = CalendarObject::Event(event.clone());
+
+    let message = ItipMessage::for_object(&object).unwrap();
+    assert_eq!(*message.method(), ItipMethod::Request);
+    assert_eq!(message.uid(), &event.event_id().to_string());
+    assert_eq!(*message.sequence(), 0);
+    assert_eq!(message.recipients(), &["bob@example.com", "carol@example.com"]);
+
+    // The organizer's alarms stay with the organizer
+    let ics = message.to_ics();
+    assert!(ics.contains("METHOD:REQUEST"));
+    assert!(!ics.contains("BEGIN:VALARM"));
+
+    // Read back, the message is the same
+    let parsed = ItipMessage::parse(&ics).unwrap();
+    assert_eq!(parsed.recipients(), message.recipients());
+    assert_eq!(parsed.file_name(), format!("{}-0-request.ics", event.event_id()));
+
+    // Without an organizer or anyone to invite there is nothing to send
+    let mut alone = event.clone();
+    alone.set_organizer(None);
+    let result = ItipMessage::request(&CalendarObject::Event(alone));
+    assert!(matches!(result, Err(ItipError::NoOrganizer)));
+
+    let nobody = support::event(CalendarId::new(), "Focus", range(10, 9, 10)).with_participants(
+        Some(Organizer::new("alice@example.com".into(), None).unwrap()),
+        vec![],
+    );
+    let result = ItipMessage::request(&CalendarObject::Event(nobody));
+    assert!(matches!(result, Err(ItipError::NoAttendees)));
+}
+
+#[tokio::test]
+async fn cancelled_events_send_a_cancel_through_the_transport() {
+    let mut event = meeting(CalendarId::new());
+    event.cancel();
+
+    let message = ItipMessage::for_object(&CalendarObject::Event(event.clone())).unwrap();
+    assert_eq!(*message.method(), ItipMethod::Cancel);
+    assert_eq!(message.vevent().property("STATUS").unwrap().value, "CANCELLED");
+
+    // Deleting a confirmed event cancels it too
+    let confirmed = meeting(CalendarId::new());
+    let message = ItipMessage::cancel(&CalendarObject::Event(confirmed)).unwrap();
+    assert_eq!(message.vevent().properties_named("STATUS").count(), 1);
+    assert_eq!(message.vevent().property("STATUS").unwrap().value, "CANCELLED");
+
+    let dir = std::env::temp_dir().join(format!("kal-{}", uuid::Uuid::new_v4()));
+    let transport = DirectoryTransport::new(dir.join("outbox"));
+    transport.send(&message).await.unwrap();
+
+    let written = std::fs::read_to_string(transport.path_for(&message)).unwrap();
+    assert_eq!(written, message.to_ics());
+    assert!(transport.path_for(&message).ends_with(message.file_name()));
+}
+
+#[tokio::test]
+async fn scheduled_events_send_their_messages_as_they_change() {
+    let database = Database::open_in_memory().await.unwrap();
+    let calendar = Calendar::new("Work".into(), None).unwrap();
+    database.calendars().save(&calendar).await.unwrap();
+    let outbox = Arc::new(Outbox::default());
+    let subscriber = ItipSubscriber::new(database.events(), database.recurring(), outbox.clone());
+    let publisher = Publisher::new().subscribe(Arc::new(subscriber));
+    let events = publisher.track(database.events());
+
+    // Once per change, though inviting each attendee raised an event
+    let mut event = meeting(*calendar.calendar_id());
+    let version = events.save(&event).await.unwrap();
+    publisher.publish("event.create").await;
+
+    event = event.with_version(version);
+    event.update_time_range(range(10, 14, 15));
+    let version = events.save(&event).await.unwrap();
+    publisher.publish("event.update").await;
+    let rescheduled = *event.sequence();
+
+    event = event.with_version(version);
+    event.cancel();
+    events.save(&event).await.unwrap();
+    publisher.publish("event.cancel").await;
+
+    assert_eq!(
+        *outbox.sent.lock().unwrap(),
+        vec![
+            (ItipMethod::Request, 0),
+            (ItipMethod::Request, rescheduled),
+            (ItipMethod::Cancel, *event.sequence()),
+        ]
+    );
+
+    // A trashed series is cancelled too
+    outbox.sent.lock().unwrap().clear();
+    let rule = RecurrenceRule::new(Frequency::Weekly, 1, None).unwrap();
+    let mut series = RecurringEvent::new(
+        *calendar.calendar_id(),
+        "Standup".into(),
+        None,
+        range(10, 9, 10),
+        rule,
+        EventColor::from(0),
+        false,
+    )
+    .unwrap();
+    series.set_organizer(Some(Organizer::new("alice@example.com".into(), None).unwrap()));
+    series.add_attendee(attendee("bob@example.com")).unwrap();
+    let recurring = publisher.track(database.recurring());
+    recurring.save(&series).await.unwrap();
+    publisher.publish("recurring.create").await;
+    recurring.trash(series.event_id(), Utc::now()).await.unwrap();
+    publisher.publish("recurring.delete").await;
+    assert_eq!(
+        *outbox.sent.lock().unwrap(),
+        vec![(ItipMethod::Request, 0), (ItipMethod::Cancel, 0)]
+    );
+
+    // Nobody to tell about an event without attendees
+    outbox.sent.lock().unwrap().clear();
+    let solo = support::event(*calendar.calendar_id(), "Focus", range(10, 9, 10));
+    events.save(&solo).await.unwrap();
+    events.trash(solo.event_id(), Utc::now()).await.unwrap();
+    publisher.publish("event.create").await;
+    assert!(outbox.sent.lock().unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn messages_that_cannot_be_sent_are_reported() {
+    let database = Database::open_in_memory().await.unwrap();
+    let calendar = Calendar::new("Work".into(), None).unwrap();
+    database.calendars().save(&calendar).await.unwrap();
+
+    // A file stands where the outbox directory should be
+    let dir = std::env::temp_dir().join(format!("kal-{}", uuid::Uuid::new_v4()));
+    std::fs::create_dir_all(&dir).unwrap();
+    std::fs::write(dir.join("outbox"), "").unwrap();
+
+    let failures = Arc::new(Mutex::new(Vec::new()));
+    let reported = failures.clone();
+    let transport = Arc::new(DirectoryTransport::new(dir.join("outbox")));
+    let subscriber = ItipSubscriber::new(database.events(), database.recurring(), transport)
+        .on_error(move |error| reported.lock().unwrap().push(error.to_string()));
+    let publisher = Publisher::new().subscribe(Arc::new(subscriber));
+
+    let event = meeting(*calendar.calendar_id());
+    publisher.track(database.events()).save(&event).await.unwrap();
+    publisher.publish("event.create").await;
+
+    // The event is kept all the same
+    assert_eq!(failures.lock().unwrap().len(), 1);
+    assert!(database.events().find_by_id(event.event_id()).await.unwrap().is_some());
+    std::fs::remove_dir_all(dir).unwrap();
+}
+
+#[tokio::test]
+async fn replies_update_the_attendee_unless_outdated() {
+    let database = Database::open_in_memory().await.unwrap();
+    let calendar = Calendar::new("Work".into(), None).unwrap();
+    database.calendars().save(&calendar).await.unwrap();
+    let event = meeting(*calendar.calendar_id());
+    let id = *event.event_id();
+    database.events().save(&event).await.unwrap();
+
+    let uid = id.to_string();
+    let handler =
+        ApplyReplyHandler::new(database.events(), database.recurring(), database.calendars());
+
+    let accepted = ItipReply::parse(&reply(
+        &uid,
+        0,
+        &["PARTSTAT=ACCEPTED:mailto:bob@example.com"],
+        "",
+    ))
+    .unwrap();
+    assert_eq!(*accepted.event_id(), id);
+    assert_eq!(accepted.attendee(), "bob@example.com");
+    assert_eq!(*accepted.status(), ParticipationStatus::Accepted);
+
+    let outcome = handler
+        .handle(ApplyReplyCommand::new(
+            *accepted.event_id(),
+            accepted.attendee().clone(),
+            *accepted.status(),
+            *accepted.sequence(),
+        ))
+        .await
+        .unwrap();
+    assert_eq!(outcome, ReplyOutcome::Applied);
+
+    let stored = database.events().find_by_id(&id).await.unwrap().unwrap();
+    let bob = stored.attendees().iter().find(|a| a.email() == "bob@example.com").unwrap();
+    assert_eq!(*bob.status(), ParticipationStatus::Accepted);
+
+    // Moving the meeting is a new revision; answers to the old one are ignored
+    let mut moved = stored;
+    moved.update_time_range(range(10, 14, 15));
+    assert_eq!(*moved.sequence(), 1);
+    database.events().save(&moved).await.unwrap();
+
+    let outcome = handler
+        .handle(ApplyReplyCommand::new(
+            id,
+            "carol@example.com".into(),
+            ParticipationStatus::Declined,
+            0,
+        ))
+        .await
+        .unwrap();
+    assert_eq!(outcome, ReplyOutcome::Outdated { current: 1 });
+
+    let stored = database.events().find_by_id(&id).await.unwrap().unwrap();
+    let carol = stored.attendees().iter().find(|a| a.email() == "carol@example.com").unwrap();
+    assert_eq!(*carol.status(), ParticipationStatus::NeedsAction);
+}
+
+#[test]
+fn replies_must_answer_for_one_attendee_and_the_whole_event() {
+    let uid = "review@example.com";
+
+    let two = reply(
+        uid,
+        0,
+        &[
+            "PARTSTAT=ACCEPTED:mailto:bob@example.com",
+            "PARTSTAT=DECLINED:mailto:carol@example.com",
+        ],
+        "",
+    );
+    assert!(matches!(ItipReply::parse(&two), Err(ItipError::Invalid(_))));
+
+    let occurrence = reply(
+        uid,
+        0,
+        &["PARTSTAT=ACCEPTED:mailto:bob@example.com"],
+        "RECURRENCE-ID:20250317T090000Z\r\n",
+    );
+    assert!(matches!(ItipReply::parse(&occurrence), Err(ItipError::Unsupported(_))));
+
+    let request = reply(uid, 0, &["PARTSTAT=ACCEPTED:mailto:bob@example.com"], "")
+        .replace("METHOD:REPLY", "METHOD:REQUEST");
+    assert!(matches!(ItipReply::parse(&request), Err(ItipError::Invalid(_))));
+
+    let publish = request.replace("METHOD:REQUEST", "METHOD:PUBLISH");
+    assert!(matches!(ItipReply::parse(&publish), Err(ItipError::UnsupportedMethod(_))));
+
+    // A UID that is not one of ours still maps to a stable id
+    let tentative =
+        ItipReply::parse(&reply(uid, 3, &["PARTSTAT=TENTATIVE:mailto:bob@example.com"], ""))
+            .unwrap();
+    assert_eq!(*tentative.sequence(), 3);
+    assert_eq!(*tentative.status(), ParticipationStatus::Tentative);
+    let unanswered =
+        ItipReply::parse(&reply(uid, 0, &["RSVP=TRUE:mailto:bob@example.com"], "")).unwrap();
+    assert_eq!(*unanswered.status(), ParticipationStatus::NeedsAction);
+    assert_eq!(tentative.event_id(), unanswered.event_id());
+}
@@ -0,0 +1,249 @@
+//! Journal entries (VJOURNAL): dated notes, their links to events and
+//! occurrences, full-text search, and importing and exporting them.
+
+mod support;
+
+use chrono::NaiveDate;
+
+use kal_core::{
+    application::{
+        commands::journal::{
+            CreateJournalEntryCommand, CreateJournalEntryHandler, DeleteJournalEntryCommand,
+            DeleteJournalEntryHandler, ImportJournalEntriesCommand, ImportJournalEntriesHandler,
+            UpdateJournalEntryCommand, UpdateJournalEntryHandler,
+        },
+        error::ApplicationError,
+    },
+    domain::{
+        journal::{JournalEntry, JournalLink},
+        recurrence::{RecurrenceRule, RecurringEvent},
+        repository::{
+            EventRepository, JournalRepository, RecurringEventRepository,
+        },
+        value_objects::{CalendarId, EventColor, EventId, Frequency, JournalId},
+    },
+    infrastructure::{
+        ical::{Component, IcalMapper},
+        persistence::Database,
+    },
+};
+
+use support::{calendar, event, range, utc};
+
+fn day(d: u32) -> NaiveDate {
+    NaiveDate::from_ymd_opt(2025, 3, d).unwrap()
+}
+
+async fn write(
+    database: &Database,
+    calendar_id: CalendarId,
+    date: NaiveDate,
+    title: &str,
+    body: &str,
+    link: Option<JournalLink>,
+) -> Result<JournalId, ApplicationError> {
+    CreateJournalEntryHandler::new(
+        database.journal(),
+        database.events(),
+        database.recurring(),
+        database.calendars(),
+    )
+    .handle(
+        CreateJournalEntryCommand::new(calendar_id, date, title.into(), body.into())
+            .with_link(link),
+    )
+    .await
+}
+
+fn titles(entries: &[JournalEntry]) -> Vec<&str> {
+    entries.iter().map(|e| e.title().as_str()).collect()
+}
+
+#[tokio::test]
+async fn entries_belong_to_days_and_link_to_events() {
+    let database = Database::open_in_memory().await.unwrap();
+    let calendar_id = calendar(&database).await;
+    let review = event(calendar_id, "Review", range(10, 9, 10));
+    database.events().save(&review).await.unwrap();
+    let standup = RecurringEvent::new(
+        calendar_id,
+        "Standup".into(),
+        None,
+        range(10, 9, 10),
+        RecurrenceRule::new(Frequency::Daily, 1, None).unwrap(),
+        EventColor::from(0),
+        false,
+    )
+    .unwrap();
+    database.recurring().save(&standup).await.unwrap();
+    let review_id = *review.event_id();
+    let standup_id = *standup.event_id();
+
+    write(&database, calendar_id, day(9), "Plan", "", None).await.unwrap();
+    let review_link = Some(JournalLink::Event(review_id));
+    write(&database, calendar_id, day(10), "Review notes", "", review_link).await.unwrap();
+    let monday = JournalLink::Occurrence(standup_id, utc(10, 9));
+    let notes = write(&database, calendar_id, day(10), "Standup", "", Some(monday)).await.unwrap();
+    write(&database, calendar_id, day(11), "Series", "", Some(JournalLink::Event(standup_id)))
+        .await
+        .unwrap();
+
+    // Links must point at something, and occurrences only exist on series
+    let missing = write(
+        &database,
+        calendar_id,
+        day(10),
+        "Lost",
+        "",
+        Some(JournalLink::Event(EventId::new())),
+    )
+    .await;
+    assert!(matches!(missing, Err(ApplicationError::EventNotFound)));
+    let one_off = JournalLink::Occurrence(review_id, utc(10, 9));
+    let missing = write(&database, calendar_id, day(10), "Lost", "", Some(one_off)).await;
+    assert!(matches!(missing, Err(ApplicationError::RecurringEventNotFound)));
+
+    let journal = database.journal();
+    let all = journal.find_by_calendar(&calendar_id, None, None).await.unwrap();
+    assert_eq!(titles(&all), ["Plan", "Review notes", "Standup", "Series"]);
+    let tenth = journal.find_by_calendar(&calendar_id, Some(day(10)), Some(day(10)));
+    assert_eq!(titles(&tenth.await.unwrap()), ["Review notes", "Standup"]);
+    let from = journal.find_by_calendar(&calendar_id, Some(day(10)), None).await.unwrap();
+    assert_eq!(from.len(), 3);
+
+    // Notes on an occurrence count as notes on its series
+    let series = journal.find_by_event(&standup_id).await.unwrap();
+    assert_eq!(titles(&series), ["Standup", "Series"]);
+    assert_eq!(*series[0].link(), Some(monday));
+
+    UpdateJournalEntryHandler::new(
+        database.journal(),
+        database.events(),
+        database.recurring(),
+        database.calendars(),
+    )
+    .handle(UpdateJournalEntryCommand::new(notes).with_date(day(12)).with_link(None))
+    .await
+    .unwrap();
+    let moved = journal.find_by_id(&notes).await.unwrap().unwrap();
+    assert_eq!((*moved.date(), *moved.link()), (day(12), None));
+
+    // Deleting the event keeps the notes taken for it
+    database.events().delete(&review_id).await.unwrap();
+    assert!(journal.find_by_event(&review_id).await.unwrap().is_empty());
+    let all = journal.find_by_calendar(&calendar_id, None, None).await.unwrap();
+    assert_eq!(all.len(), 4);
+    assert_eq!(*all[1].link(), None);
+}
+
+#[tokio::test]
+async fn search_finds_words_in_titles_and_bodies() {
+    let database = Database::open_in_memory().await.unwrap();
+    let work = calendar(&database).await;
+    let home = calendar(&database).await;
+
+    let budget = write(&database, work, day(10), "Budget review", "Numbers look fine", None)
+        .await
+        .unwrap();
+    write(&database, work, day(11), "Retro", "We should review the budget process", None)
+        .await
+        .unwrap();
+    write(&database, home, day(11), "Groceries", "Budget for the week", None).await.unwrap();
+
+    let journal = database.journal();
+
+    // Every word must match, prefixes included; title hits rank first
+    let found = journal.search("budget review", None).await.unwrap();
+    assert_eq!(titles(&found), ["Budget review", "Retro"]);
+    let found = journal.search("num", None).await.unwrap();
+    assert_eq!(titles(&found), ["Budget review"]);
+    let found = journal.search("budget", Some(&home)).await.unwrap();
+    assert_eq!(titles(&found), ["Groceries"]);
+    assert!(journal.search("   ", None).await.unwrap().is_empty());
+    // Quotes and operators are taken as plain text
+    assert!(journal.search("\"budget OR", None).await.unwrap().is_empty());
+
+    // The index follows edits and deletions
+    UpdateJournalEntryHandler::new(
+        database.journal(),
+        database.events(),
+        database.recurring(),
+        database.calendars(),
+    )
+    .handle(UpdateJournalEntryCommand::new(budget).with_title("Forecast".into()))
+    .await
+    .unwrap();
+    let found = journal.search("forecast", Some(&work)).await.unwrap();
+    assert_eq!(titles(&found), ["Forecast"]);
+
+    DeleteJournalEntryHandler::new(database.journal(), database.calendars())
+        .handle(DeleteJournalEntryCommand::new(budget))
+        .await
+        .unwrap();
+    assert!(journal.search("forecast", None).await.unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn vjournals_import_and_export() {
+    let database = Database::open_in_memory().await.unwrap();
+    let calendar_id = calendar(&database).await;
+    let standup = RecurringEvent::new(
+        calendar_id,
+        "Standup".into(),
+        None,
+        range(10, 9, 10),
+        RecurrenceRule::new(Frequency::Daily, 1, None).unwrap(),
+        EventColor::from(0),
+        false,
+    )
+    .unwrap();
+    database.recurring().save(&standup).await.unwrap();
+
+    let ics = format!(
+        "BEGIN:VCALENDAR\r\nVERSION:2.0\r\nPRODID:-//test//EN\r\n\
+         BEGIN:VJOURNAL\r\nUID:notes@example.com\r\nSUMMARY:Standup notes\r\n\
+         DTSTART;VALUE=DATE:20250310\r\nDESCRIPTION:First point\r\n\
+         DESCRIPTION:Second point\\, with a comma\r\n\
+         RELATED-TO;X-KAL-RECURRENCE-ID=20250310T090000Z:{}\r\nEND:VJOURNAL\r\n\
+         BEGIN:VJOURNAL\r\nUID:orphan@example.com\r\nSUMMARY:Orphan\r\n\
+         DTSTART:20250311T120000Z\r\nRELATED-TO:gone@example.com\r\nEND:VJOURNAL\r\n\
+         BEGIN:VJOURNAL\r\nUID:undated@example.com\r\nSUMMARY:Undated\r\nEND:VJOURNAL\r\n\
+         END:VCALENDAR\r\n",
+        standup.event_id()
+    );
+    let (entries, skipped) =
+        IcalMapper::journal_to_domain(&Component::parse(&ics).unwrap(), calendar_id);
+    assert_eq!(skipped.len(), 1);
+    assert_eq!(skipped[0].0, "undated@example.com");
+
+    assert_eq!(*entries[0].date(), day(10));
+    assert_eq!(entries[0].body(), "First point\n\nSecond point, with a comma");
+    let occurrence = JournalLink::Occurrence(*standup.event_id(), utc(10, 9));
+    assert_eq!(*entries[0].link(), Some(occurrence));
+    assert_eq!(*entries[1].date(), day(11));
+
+    let import = ImportJournalEntriesHandler::new(
+        database.journal(),
+        database.events(),
+        database.recurring(),
+        database.calendars(),
+    );
+    let saved = import.handle(ImportJournalEntriesCommand::new(calendar_id, entries)).await;
+    assert_eq!(saved.unwrap(), 2);
+
+    // Links to events this calendar does not have are dropped
+    let stored = database.journal().find_by_calendar(&calendar_id, None, None).await.unwrap();
+    assert_eq!(titles(&stored), ["Standup notes", "Orphan"]);
+    assert!(stored[0].link().is_some());
+    assert_eq!(*stored[1].link(), None);
+
+    let exported = IcalMapper::journal_to_ics(&stored);
+    let (reimported, skipped) =
+        IcalMapper::journal_to_domain(&Component::parse(&exported).unwrap(), calendar_id);
+    assert!(skipped.is_empty());
+    for (entry, again) in stored.iter().zip(&reimported) {
+        assert_eq!(again.journal_id(), entry.journal_id());
+        assert_eq!((again.date(), again.title()), (entry.date(), entry.title()));
+        assert_eq!((again.body(), again.link()), (entry.body(), entry.link()));
+    }
+}
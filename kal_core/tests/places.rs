@@ -0,0 +1,206 @@
+//! Where events happen: location, URL and GEO on events and series,
+//! per-occurrence locations, lookup by location and iCalendar mapping.
+
+mod support;
+
+use kal_core::{
+    application::{
+        commands::{
+            events::{
+                UpdateEventGeoCommand, UpdateEventGeoHandler, UpdateEventLocationCommand,
+                UpdateEventLocationHandler, UpdateEventUrlCommand, UpdateEventUrlHandler,
+            },
+            recurring::{
+                SetRecurringOccurrenceLocationCommand, SetRecurringOccurrenceLocationHandler,
+                UpdateRecurringLocationCommand, UpdateRecurringLocationHandler,
+            },
+        },
+        error::ApplicationError,
+    },
+    domain::{
+        calendar_object::CalendarObject,
+        error::DomainError,
+        recurrence::{RecurrenceRule, RecurringEvent},
+        repository::{EventRepository, RecurringEventRepository},
+        value_objects::{CalendarId, EventColor, Frequency, GeoPoint, TimeRange},
+    },
+    infrastructure::{
+        ical::{Component, IcalMapper},
+        persistence::Database,
+    },
+};
+
+use support::{calendar, event, range, utc};
+
+#[test]
+fn coordinates_and_urls_are_validated() {
+    assert!(GeoPoint::new(90.0, -180.0).is_ok());
+    for (lat, lon) in [(90.5, 0.0), (-91.0, 0.0), (0.0, 180.1), (0.0, -200.0)] {
+        assert!(matches!(GeoPoint::new(lat, lon), Err(DomainError::InvalidGeo(_))), "{lat},{lon}");
+    }
+    assert_eq!(GeoPoint::new(52.52, 13.405).unwrap().to_string(), "52.52,13.405");
+
+    let mut event = event(CalendarId::new(), "Review", range(10, 9, 10));
+    for url in ["", "example.com/meet", "://example.com", "1http://example.com"] {
+        let result = event.update_url(Some(url.into()));
+        assert!(matches!(result, Err(DomainError::InvalidUrl(_))), "{url}");
+    }
+    event.update_url(Some("tel:+15555550100".into())).unwrap();
+    assert_eq!(event.url().as_deref(), Some("tel:+15555550100"));
+}
+
+#[tokio::test]
+async fn commands_set_the_place_of_an_event() {
+    let database = Database::open_in_memory().await.unwrap();
+    let calendar_id = calendar(&database).await;
+    let review = event(calendar_id, "Review", range(10, 9, 10));
+    let id = *review.event_id();
+    database.events().save(&review).await.unwrap();
+    let lunch = event(calendar_id, "Lunch", range(10, 12, 13))
+        .with_place(Some("Canteen".into()), None, None);
+    database.events().save(&lunch).await.unwrap();
+
+    let geo = GeoPoint::new(52.52, 13.405).unwrap();
+    UpdateEventLocationHandler::new(database.events(), database.calendars())
+        .handle(UpdateEventLocationCommand::new(id, Some("Room 4.2, Main Office".into())))
+        .await
+        .unwrap();
+    UpdateEventUrlHandler::new(database.events(), database.calendars())
+        .handle(UpdateEventUrlCommand::new(id, Some("https://meet.example.com/review".into())))
+        .await
+        .unwrap();
+    UpdateEventGeoHandler::new(database.events(), database.calendars())
+        .handle(UpdateEventGeoCommand::new(id, Some(geo)))
+        .await
+        .unwrap();
+
+    let stored = database.events().find_by_id(&id).await.unwrap().unwrap();
+    assert_eq!(stored.location().as_deref(), Some("Room 4.2, Main Office"));
+    assert_eq!(stored.url().as_deref(), Some("https://meet.example.com/review"));
+    assert_eq!(*stored.geo(), Some(geo));
+    // A new place invalidates invitations already sent
+    assert_eq!(*stored.sequence(), 1);
+
+    let bad = UpdateEventUrlHandler::new(database.events(), database.calendars())
+        .handle(UpdateEventUrlCommand::new(id, Some("not a url".into())))
+        .await;
+    assert!(matches!(bad, Err(ApplicationError::Domain(DomainError::InvalidUrl(_)))));
+
+    // Matching ignores case, and only looks at the location
+    let found = database.events().find_by_location(&calendar_id, "main office").await.unwrap();
+    let titles: Vec<&str> = found.iter().map(|e| e.title().as_str()).collect();
+    assert_eq!(titles, ["Review"]);
+    let found = database.events().find_by_location(&calendar_id, "CANTEEN").await.unwrap();
+    assert_eq!(found.len(), 1);
+    assert!(database.events().find_by_location(&calendar_id, "meet").await.unwrap().is_empty());
+
+    // Blank locations clear the field
+    UpdateEventLocationHandler::new(database.events(), database.calendars())
+        .handle(UpdateEventLocationCommand::new(id, Some("   ".into())))
+        .await
+        .unwrap();
+    UpdateEventGeoHandler::new(database.events(), database.calendars())
+        .handle(UpdateEventGeoCommand::new(id, None))
+        .await
+        .unwrap();
+    let stored = database.events().find_by_id(&id).await.unwrap().unwrap();
+    assert_eq!(*stored.location(), None);
+    assert_eq!(*stored.geo(), None);
+}
+
+#[tokio::test]
+async fn occurrences_can_meet_somewhere_else() {
+    let database = Database::open_in_memory().await.unwrap();
+    let calendar_id = calendar(&database).await;
+    let series = RecurringEvent::new(
+        calendar_id,
+        "Standup".into(),
+        None,
+        range(10, 9, 10),
+        RecurrenceRule::new(Frequency::Daily, 1, None).unwrap(),
+        EventColor::from(0),
+        false,
+    )
+    .unwrap();
+    let id = *series.event_id();
+    database.recurring().save(&series).await.unwrap();
+
+    UpdateRecurringLocationHandler::new(database.recurring(), database.calendars())
+        .handle(UpdateRecurringLocationCommand::new(id, Some("Room 1".into())))
+        .await
+        .unwrap();
+    let set =
+        SetRecurringOccurrenceLocationHandler::new(database.recurring(), database.calendars());
+    set.handle(SetRecurringOccurrenceLocationCommand::new(id, utc(11, 9), Some("Rooftop".into())))
+        .await
+        .unwrap();
+
+    let stored = database.recurring().find_by_id(&id).await.unwrap();
+    let window = TimeRange::new(utc(10, 0), utc(13, 0)).unwrap();
+    let occurrences = stored.occurrences_in(&window);
+    let places: Vec<Option<&str>> = occurrences.iter().map(|o| o.location().as_deref()).collect();
+    assert_eq!(places, [Some("Room 1"), Some("Rooftop"), Some("Room 1")]);
+
+    // Found by the series' location or by any occurrence's
+    let found = database.recurring().find_by_location(&calendar_id, "roof").await.unwrap();
+    assert_eq!(found.len(), 1);
+    let found = database.recurring().find_by_location(&calendar_id, "Room 2").await.unwrap();
+    assert!(found.is_empty());
+
+    // Clearing the override puts the occurrence back with the series
+    set.handle(SetRecurringOccurrenceLocationCommand::new(id, utc(11, 9), None)).await.unwrap();
+    let stored = database.recurring().find_by_id(&id).await.unwrap();
+    assert!(stored.exceptions().is_empty());
+    assert!(database.recurring().find_by_location(&calendar_id, "roof").await.unwrap().is_empty());
+}
+
+#[test]
+fn location_url_and_geo_map_both_ways() {
+    let ics = "BEGIN:VCALENDAR\r\nVERSION:2.0\r\nPRODID:-//test//EN\r\n\
+               BEGIN:VEVENT\r\nUID:standup@example.com\r\nSUMMARY:Standup\r\n\
+               DTSTART:20250310T090000Z\r\nDTEND:20250310T100000Z\r\nRRULE:FREQ=DAILY\r\n\
+               LOCATION:Room 1\\, East Wing\r\nURL:https://meet.example.com/standup\r\n\
+               GEO:52.52;13.405\r\nEND:VEVENT\r\n\
+               BEGIN:VEVENT\r\nUID:standup@example.com\r\nRECURRENCE-ID:20250311T090000Z\r\n\
+               DTSTART:20250311T090000Z\r\nDTEND:20250311T100000Z\r\n\
+               LOCATION:Rooftop\r\nEND:VEVENT\r\n\
+               BEGIN:VEVENT\r\nUID:review@example.com\r\nSUMMARY:Review\r\n\
+               DTSTART:20250310T140000Z\r\nDTEND:20250310T150000Z\r\n\
+               URL:meet.example.com\r\nGEO:95;13\r\nEND:VEVENT\r\n\
+               END:VCALENDAR\r\n";
+    let calendar_id = CalendarId::new();
+    let objects = IcalMapper::to_domain(&Component::parse(ics).unwrap(), calendar_id).unwrap();
+    assert_eq!(objects.len(), 2);
+
+    let Some(CalendarObject::Recurring(series)) =
+        objects.iter().find(|o| matches!(o, CalendarObject::Recurring(_)))
+    else {
+        panic!("expected a series");
+    };
+    assert_eq!(series.location().as_deref(), Some("Room 1, East Wing"));
+    assert_eq!(series.url().as_deref(), Some("https://meet.example.com/standup"));
+    assert_eq!(*series.geo(), Some(GeoPoint::new(52.52, 13.405).unwrap()));
+    let window = TimeRange::new(utc(11, 0), utc(12, 0)).unwrap();
+    assert_eq!(series.occurrences_in(&window)[0].location().as_deref(), Some("Rooftop"));
+
+    // Malformed URL and GEO from other clients are dropped, not fatal
+    let Some(CalendarObject::Event(review)) =
+        objects.iter().find(|o| matches!(o, CalendarObject::Event(_)))
+    else {
+        panic!("expected an event");
+    };
+    assert_eq!(*review.url(), None);
+    assert_eq!(*review.geo(), None);
+
+    let exported = IcalMapper::to_ics(&CalendarObject::Recurring(series.clone()));
+    assert!(exported.contains("GEO:52.52;13.405"));
+    let mut objects =
+        IcalMapper::to_domain(&Component::parse(&exported).unwrap(), calendar_id).unwrap();
+    let CalendarObject::Recurring(reimported) = objects.remove(0) else {
+        panic!("expected a series");
+    };
+    assert_eq!(reimported.location(), series.location());
+    assert_eq!(reimported.url(), series.url());
+    assert_eq!(reimported.geo(), series.geo());
+    assert_eq!(reimported.occurrences_in(&window), series.occurrences_in(&window));
+}
@@ -0,0 +1,313 @@
+//! Reminders on events and recurring series: storage, per-occurrence
+//! inheritance and VALARM mapping.
+
+mod support;
+
+use chrono::{DateTime, Duration, TimeZone, Utc};
+use serde_json::json;
+
+use kal_core::{
+    application::{
+        commands::{
+            events::{
+                AddEventReminderCommand, AddEventReminderHandler, RemoveEventReminderCommand,
+                RemoveEventReminderHandler,
+            },
+            recurring::{
+                AddRecurringReminderCommand, AddRecurringReminderHandler,
+                CancelRecurringOccurrenceCommand, CancelRecurringOccurrenceHandler,
+                RescheduleRecurringOccurrenceCommand, RescheduleRecurringOccurrenceHandler,
+            },
+        },
+        error::ApplicationError,
+    },
+    domain::{
+        calendar_object::CalendarObject,
+        error::DomainError,
+        event::Event,
+        recurrence::{RecurrenceRule, RecurringEvent},
+        reminder::{Reminder, ReminderAction, ReminderTrigger, MAX_REMINDER_REPEAT},
+        repository::{EventRepository, RecurringEventRepository},
+        value_objects::{CalendarId, EventColor, Frequency, TimeRange},
+    },
+    infrastructure::{
+        ical::{Component, IcalMapper},
+        persistence::Database,
+        rpc::{RpcDispatcher, RpcError},
+    },
+};
+
+use support::calendar;
+
+fn at(d: u32, h: u32, min: u32) -> DateTime<Utc> {
+    Utc.with_ymd_and_hms(2025, 3, d, h, min, 0).unwrap()
+}
+
+#[tokio::test]
+async fn event_reminders_are_stored_with_their_actions_and_repeats() {
+    let database = Database::open_in_memory().await.unwrap();
+    let calendar_id = calendar(&database).await;
+    let event = Event::new(
+        calendar_id,
+        "Review".into(),
+        None,
+        TimeRange::new(at(10, 14, 0), at(10, 15, 0)).unwrap(),
+        EventColor::from(0),
+        false,
+    )
+    .unwrap();
+    database.events().save(&event).await.unwrap();
+
+    let add = AddEventReminderHandler::new(database.events(), database.calendars());
+    let display = add
+        .handle(AddEventReminderCommand::new(
+            *event.event_id(),
+            ReminderTrigger::Relative(-Duration::minutes(15)),
+            ReminderAction::Display,
+            Some("Review in 15 minutes".into()),
+            2,
+            Some(Duration::minutes(5)),
+        ))
+        .await
+        .unwrap();
+    add.handle(AddEventReminderCommand::new(
+        *event.event_id(),
+        ReminderTrigger::Absolute(at(9, 18, 0)),
+        ReminderAction::Email { recipient: "alice@example.com".into() },
+        None,
+        0,
+        None,
+    ))
+    .await
+    .unwrap();
+    let command = add
+        .handle(AddEventReminderCommand::new(
+            *event.event_id(),
+            ReminderTrigger::Relative(Duration::zero()),
+            ReminderAction::Command { command: "notify-send Review".into() },
+            None,
+            0,
+            None,
+        ))
+        .await
+        .unwrap();
+
+    let stored = database.events().find_by_id(event.event_id()).await.unwrap().unwrap();
+    assert_eq!(stored.reminders().len(), 3);
+    let first = stored.reminders().iter().find(|r| *r.reminder_id() == display).unwrap();
+    assert_eq!(*first.repeat(), 2);
+    assert_eq!(
+        first.fire_times(at(10, 14, 0)).collect::<Vec<_>>(),
+        [at(10, 13, 45), at(10, 13, 50), at(10, 13, 55)]
+    );
+    let mut fires: Vec<DateTime<Utc>> =
+        stored.reminder_instances().iter().map(|i| i.fires_at()).collect();
+    fires.sort();
+    assert_eq!(fires, [at(9, 18, 0), at(10, 13, 45), at(10, 14, 0)]);
+
+    let remove = RemoveEventReminderHandler::new(database.events(), database.calendars());
+    remove.handle(RemoveEventReminderCommand::new(*event.event_id(), command)).await.unwrap();
+    let again = remove.handle(RemoveEventReminderCommand::new(*event.event_id(), command)).await;
+    assert!(matches!(
+        again,
+        Err(ApplicationError::Domain(DomainError::ReminderNotFound(_)))
+    ));
+
+    let stored = database.events().find_by_id(event.event_id()).await.unwrap().unwrap();
+    let kinds: Vec<&str> = stored.reminders().iter().map(|r| r.action().kind()).collect();
+    assert_eq!(kinds, ["DISPLAY", "EMAIL"]);
+}
+
+#[tokio::test]
+async fn occurrences_inherit_the_series_reminders() {
+    let database = Database::open_in_memory().await.unwrap();
+    let calendar_id = calendar(&database).await;
+    let series = RecurringEvent::new(
+        calendar_id,
+        "Standup".into(),
+        None,
+        TimeRange::new(at(10, 9, 0), at(10, 9, 15)).unwrap(),
+        RecurrenceRule::new(Frequency::Daily, 1, None).unwrap(),
+        EventColor::from(0),
+        false,
+    )
+    .unwrap();
+    let id = *series.event_id();
+    database.recurring().save(&series).await.unwrap();
+
+    AddRecurringReminderHandler::new(database.recurring(), database.calendars())
+        .handle(AddRecurringReminderCommand::new(
+            id,
+            ReminderTrigger::Relative(-Duration::minutes(10)),
+            ReminderAction::Display,
+            None,
+            0,
+            None,
+        ))
+        .await
+        .unwrap();
+    RescheduleRecurringOccurrenceHandler::new(database.recurring(), database.calendars())
+        .handle(RescheduleRecurringOccurrenceCommand::new(
+            id,
+            at(11, 9, 0),
+            TimeRange::new(at(11, 14, 0), at(11, 14, 15)).unwrap(),
+        ))
+        .await
+        .unwrap();
+    CancelRecurringOccurrenceHandler::new(database.recurring(), database.calendars())
+        .handle(CancelRecurringOccurrenceCommand::new(id, at(12, 9, 0)))
+        .await
+        .unwrap();
+
+    let series = database.recurring().find_by_id(&id).await.unwrap();
+    let instances = series.reminder_instances(&TimeRange::new(at(10, 0, 0), at(14, 0, 0)).unwrap());
+
+    // Measured from each occurrence's own start; the cancelled one has none
+    let fired: Vec<(DateTime<Utc>, DateTime<Utc>)> = instances
+        .iter()
+        .map(|i| (*i.original_starts_at(), i.fires_at()))
+        .collect();
+    assert_eq!(
+        fired,
+        [
+            (at(10, 9, 0), at(10, 8, 50)),
+            (at(11, 9, 0), at(11, 13, 50)),
+            (at(13, 9, 0), at(13, 8, 50)),
+        ]
+    );
+
+    // Instances of the same alarm are told apart by occurrence
+    assert_ne!(instances[0].key(), instances[1].key());
+    assert_eq!(instances[0].reminder().reminder_id(), instances[1].reminder().reminder_id());
+}
+
+#[test]
+fn valarms_map_both_ways() {
+    let ics = "BEGIN:VCALENDAR\r\nVERSION:2.0\r\nPRODID:-//test//EN\r\nBEGIN:VEVENT\r\n\
+               UID:review@example.com\r\nSUMMARY:Review\r\n\
+               DTSTART:20250310T140000Z\r\nDTEND:20250310T150000Z\r\n\
+               BEGIN:VALARM\r\nACTION:DISPLAY\r\nDESCRIPTION:Soon\r\nTRIGGER:-PT15M\r\n\
+               REPEAT:2\r\nDURATION:PT5M\r\nEND:VALARM\r\n\
+               BEGIN:VALARM\r\nACTION:EMAIL\r\nSUMMARY:Over\r\nDESCRIPTION:Over\r\n\
+               ATTENDEE:mailto:alice@example.com\r\nTRIGGER;RELATED=END:PT0S\r\nEND:VALARM\r\n\
+               BEGIN:VALARM\r\nACTION:AUDIO\r\n\
+               TRIGGER;VALUE=DATE-TIME:20250309T180000Z\r\nEND:VALARM\r\n\
+               END:VEVENT\r\nEND:VCALENDAR\r\n";
+    let calendar_id = CalendarId::new();
+    let mut objects =
+        IcalMapper::to_domain(&Component::parse(ics).unwrap(), calendar_id).unwrap();
+    let CalendarObject::Event(event) = objects.remove(0) else {
+        panic!("expected a single event");
+    };
+
+    let reminders = event.reminders();
+    assert_eq!(reminders.len(), 3);
+    assert_eq!(*reminders[0].trigger(), ReminderTrigger::Relative(-Duration::minutes(15)));
+    assert_eq!(*reminders[0].repeat(), 2);
+    assert_eq!(*reminders[0].repeat_interval(), Some(Duration::minutes(5)));
+    assert_eq!(reminders[0].description().as_deref(), Some("Soon"));
+    // Related to the end, rebased onto the start
+    assert_eq!(*reminders[1].trigger(), ReminderTrigger::Relative(Duration::hours(1)));
+    assert_eq!(
+        *reminders[1].action(),
+        ReminderAction::Email { recipient: "alice@example.com".into() }
+    );
+    assert_eq!(*reminders[2].trigger(), ReminderTrigger::Absolute(at(9, 18, 0)));
+    assert_eq!(*reminders[2].action(), ReminderAction::Display);
+
+    // Exported and read back unchanged, ids included
+    let exported = IcalMapper::to_ics(&CalendarObject::Event(event.clone()));
+    let mut objects =
+        IcalMapper::to_domain(&Component::parse(&exported).unwrap(), calendar_id).unwrap();
+    let CalendarObject::Event(reimported) = objects.remove(0) else {
+        panic!("expected a single event");
+    };
+    assert_eq!(reimported.reminders(), event.reminders());
+}
+
+#[test]
+fn reminders_stay_within_range_and_never_overflow() {
+    let display = || ReminderAction::Display;
+    let refused = [
+        Reminder::new(
+            ReminderTrigger::Relative(Duration::seconds(10i64.pow(15))),
+            display(),
+            None,
+            0,
+            None,
+        ),
+        Reminder::new(
+            ReminderTrigger::Relative(Duration::zero()),
+            display(),
+            None,
+            1000,
+            Some(Duration::seconds(10i64.pow(12))),
+        ),
+        Reminder::new(
+            ReminderTrigger::Relative(Duration::zero()),
+            display(),
+            None,
+            2_000_000_000,
+            Some(Duration::seconds(1)),
+        ),
+    ];
+    for result in refused {
+        assert!(matches!(result, Err(DomainError::InvalidReminder(_))), "{result:?}");
+    }
+
+    // Firings past the end of the calendar are dropped, not computed
+    let daily = Reminder::new(
+        ReminderTrigger::Relative(Duration::days(1)),
+        display(),
+        None,
+        MAX_REMINDER_REPEAT,
+        Some(Duration::days(1)),
+    )
+    .unwrap();
+    let late = DateTime::<Utc>::MAX_UTC - Duration::hours(60);
+    assert_eq!(daily.fire_times(late).count(), 2);
+    assert_eq!(daily.fire_times(at(10, 9, 0)).count(), MAX_REMINDER_REPEAT as usize + 1);
+
+    let ics = "BEGIN:VCALENDAR\r\nVERSION:2.0\r\nPRODID:-//test//EN\r\nBEGIN:VEVENT\r\n\
+               UID:review@example.com\r\nSUMMARY:Review\r\n\
+               DTSTART:20250310T140000Z\r\nDTEND:20250310T150000Z\r\n\
+               BEGIN:VALARM\r\nACTION:DISPLAY\r\nTRIGGER:-PT15M\r\n\
+               REPEAT:2000000000\r\nDURATION:PT1S\r\nEND:VALARM\r\n\
+               END:VEVENT\r\nEND:VCALENDAR\r\n";
+    assert!(IcalMapper::to_domain(&Component::parse(ics).unwrap(), CalendarId::new()).is_err());
+}
+
+#[tokio::test]
+async fn offsets_too_large_for_a_duration_are_refused() {
+    let database = Database::open_in_memory().await.unwrap();
+    let dispatcher = RpcDispatcher::new(database.pool().clone());
+    let calendar = dispatcher.call("calendar.create", json!({ "name": "Work" })).await.unwrap();
+    let event = dispatcher
+        .call(
+            "event.create",
+            json!({
+                "calendar_id": calendar["id"],
+                "title": "Review",
+                "starts_at": "2025-03-10T14:00:00Z",
+                "ends_at": "2025-03-10T15:00:00Z",
+            }),
+        )
+        .await
+        .unwrap();
+
+    for params in [
+        json!({ "id": event["id"], "offset_seconds": i64::MAX }),
+        json!({
+            "id": event["id"],
+            "offset_seconds": -900,
+            "repeat": 2,
+            "repeat_interval_seconds": i64::MIN,
+        }),
+    ] {
+        let refused = dispatcher.call("event.add_reminder", params).await;
+        assert!(
+            matches!(refused, Err(RpcError::Application(ApplicationError::Validation(_)))),
+            "{refused:?}"
+        );
+    }
+}
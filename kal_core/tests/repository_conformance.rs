@@ -0,0 +1,804 @@
+//! Runs the same scenarios against the SQLite and in-memory repositories
+//! so the two backends cannot drift apart.
+
+use std::collections::BTreeSet;
+
+use chrono::{DateTime, Duration, TimeZone, Utc};
+use kal_core::{
+    domain::{
+        calendar::Calendar,
+        event::Event,
+        recurrence::{RecurrenceRule, RecurringEvent},
+        reminder::{Reminder, ReminderAction, ReminderTrigger},
+        repository::{
+            CalendarRepository, EventRepository, RecurringEventRepository, RepositoryError,
+        },
+        tag::Tag,
+        value_objects::{CalendarId, EventColor, EventId, EventStatus, Frequency, TimeRange},
+    },
+    infrastructure::{
+        memory::{
+            MemoryCalendarRepository, MemoryEventRepository, MemoryRecurringEventRepository,
+            MemoryStore,
+        },
+        persistence::Database,
+    },
+};
+
+// ============================================================================
+// Backends
+// ============================================================================
+
+struct Repos {
+    calendars: Box<dyn CalendarRepository>,
+    events: Box<dyn EventRepository>,
+    recurring: Box<dyn RecurringEventRepository>,
+}
+
+async fn sqlite() -> Repos {
+    let database = Database::open_in_memory().await.unwrap();
+
+    Repos {
+        calendars: Box::new(database.calendars()),
+        events: Box::new(database.events()),
+        recurring: Box::new(database.recurring()),
+    }
+}
+
+async fn memory() -> Repos {
+    let store = MemoryStore::new();
+
+    Repos {
+        calendars: Box::new(MemoryCalendarRepository::new(store.clone())),
+        events: Box::new(MemoryEventRepository::new(store.clone())),
+        recurring: Box::new(MemoryRecurringEventRepository::new(store)),
+    }
+}
+
+/// Declares each scenario once per backend.
+macro_rules! conformance {
+    ($($scenario:ident),* $(,)?) => {
+        mod sqlite_backend {
+            $(
+                #[tokio::test]
+                async fn $scenario() {
+                    super::$scenario(super::sqlite().await).await;
+                }
+            )*
+        }
+
+        mod memory_backend {
+            $(
+                #[tokio::test]
+                async fn $scenario() {
+                    super::$scenario(super::memory().await).await;
+                }
+            )*
+        }
+    };
+}
+
+conformance!(
+    calendar_round_trip,
+    calendar_save_updates,
+    active_calendars_by_name,
+    calendar_delete_missing_is_not_found,
+    calendar_delete_cascades,
+    event_round_trip,
+    event_needs_calendar,
+    events_by_calendar_by_start,
+    events_in_range,
+    events_by_location,
+    events_by_tag,
+    events_with_reminders_in,
+    event_delete,
+    series_round_trip,
+    series_missing_is_not_found,
+    series_by_calendar_by_start,
+    series_by_location,
+    series_by_tag,
+    series_with_reminders_in,
+    series_delete,
+    calendar_stale_save_conflicts,
+    event_stale_save_conflicts,
+    series_stale_save_conflicts,
+    event_trash_and_restore,
+    series_trash_and_restore,
+    calendar_trash_cascades_and_restores,
+    purge_trashed_honours_cutoff,
+    fresh_save_over_trashed_conflicts,
+);
+
+// ============================================================================
+// Fixtures
+// ============================================================================
+
+fn at(hour: i64) -> DateTime<Utc> {
+    Utc.with_ymd_and_hms(2025, 3, 10, 0, 0, 0).unwrap() + Duration::hours(hour)
+}
+
+fn range(from: i64, to: i64) -> TimeRange {
+    TimeRange::new(at(from), at(to)).unwrap()
+}
+
+/// Saves a calendar and returns it as stored, at its current version.
+async fn calendar(repos: &Repos, name: &str) -> Calendar {
+    let calendar = Calendar::new(name.into(), None).unwrap();
+    repos.calendars.save(&calendar).await.unwrap();
+    repos.calendars.find_by_id(calendar.calendar_id()).await.unwrap().unwrap()
+}
+
+fn event(calendar_id: CalendarId, title: &str, from: i64, to: i64) -> Event {
+    Event::new(calendar_id, title.into(), None, range(from, to), EventColor::from(0), false)
+        .unwrap()
+}
+
+fn series(calendar_id: CalendarId, title: &str, from: i64, to: i64) -> RecurringEvent {
+    let rule = RecurrenceRule::new(Frequency::Daily, 1, None).unwrap();
+    RecurringEvent::new(
+        calendar_id,
+        title.into(),
+        None,
+        range(from, to),
+        rule,
+        EventColor::from(0),
+        false,
+    )
+    .unwrap()
+}
+
+fn reminder(trigger: ReminderTrigger) -> Vec<Reminder> {
+    vec![Reminder::new(trigger, ReminderAction::Display, None, 0, None).unwrap()]
+}
+
+fn before(hours: i64) -> ReminderTrigger {
+    ReminderTrigger::Relative(-Duration::hours(hours))
+}
+
+fn titles_of_events(events: &[Event]) -> Vec<&str> {
+    events.iter().map(|event| event.title().as_str()).collect()
+}
+
+fn titles_of_series(events: &[RecurringEvent]) -> Vec<&str> {
+    events.iter().map(|event| event.title().as_str()).collect()
+}
+
+fn ids<'a>(ids: impl IntoIterator<Item = &'a EventId>) -> BTreeSet<String> {
+    ids.into_iter().map(ToString::to_string).collect()
+}
+
+// ============================================================================
+// Calendars
+// ============================================================================
+
+async fn calendar_round_trip(repos: Repos) {
+    let mut saved = Calendar::new("Work".into(), None).unwrap();
+    saved.update_description(Some("Office hours".into()));
+    repos.calendars.save(&saved).await.unwrap();
+
+    let found = repos.calendars.find_by_id(saved.calendar_id()).await.unwrap().unwrap();
+    assert_eq!(found.name(), "Work");
+    assert_eq!(found.description().as_deref(), Some("Office hours"));
+    assert!(!found.is_archived());
+
+    assert!(repos.calendars.find_by_id(&CalendarId::new()).await.unwrap().is_none());
+}
+
+async fn calendar_save_updates(repos: Repos) {
+    let mut saved = calendar(&repos, "Work").await;
+    saved.update_name("Office".into());
+    repos.calendars.save(&saved).await.unwrap();
+
+    let found = repos.calendars.find_by_id(saved.calendar_id()).await.unwrap().unwrap();
+    assert_eq!(found.name(), "Office");
+    assert_eq!(repos.calendars.find_all_active().await.unwrap().len(), 1);
+}
+
+async fn active_calendars_by_name(repos: Repos) {
+    calendar(&repos, "Work").await;
+    calendar(&repos, "Family").await;
+    let mut archived = calendar(&repos, "Archive").await;
+    archived.archive();
+    repos.calendars.save(&archived).await.unwrap();
+
+    let names: Vec<String> = repos
+        .calendars
+        .find_all_active()
+        .await
+        .unwrap()
+        .iter()
+        .map(|calendar| calendar.name().clone())
+        .collect();
+    assert_eq!(names, ["Family", "Work"]);
+}
+
+async fn calendar_delete_missing_is_not_found(repos: Repos) {
+    let saved = calendar(&repos, "Work").await;
+    repos.calendars.delete(saved.calendar_id()).await.unwrap();
+
+    assert!(repos.calendars.find_by_id(saved.calendar_id()).await.unwrap().is_none());
+    assert!(matches!(
+        repos.calendars.delete(saved.calendar_id()).await,
+        Err(RepositoryError::NotFound)
+    ));
+}
+
+async fn calendar_delete_cascades(repos: Repos) {
+    let work = calendar(&repos, "Work").await;
+    let home = calendar(&repos, "Home").await;
+    let meeting = event(*work.calendar_id(), "Meeting", 9, 10);
+    let standup = series(*work.calendar_id(), "Standup", 8, 9);
+    let dinner = event(*home.calendar_id(), "Dinner", 19, 21);
+    repos.events.save(&meeting).await.unwrap();
+    repos.recurring.save(&standup).await.unwrap();
+    repos.events.save(&dinner).await.unwrap();
+
+    repos.calendars.delete(work.calendar_id()).await.unwrap();
+
+    assert!(repos.events.find_by_id(meeting.event_id()).await.unwrap().is_none());
+    assert!(matches!(
+        repos.recurring.find_by_id(standup.event_id()).await,
+        Err(RepositoryError::NotFound)
+    ));
+    assert!(repos.events.find_by_id(dinner.event_id()).await.unwrap().is_some());
+}
+
+// ============================================================================
+// Events
+// ============================================================================
+
+async fn event_round_trip(repos: Repos) {
+    let work = calendar(&repos, "Work").await;
+    let mut saved = event(*work.calendar_id(), "Meeting", 9, 10)
+        .with_place(Some("Room 4".into()), None, None);
+    saved.add_tag(Tag::new("project").unwrap());
+    saved.set_status(EventStatus::Tentative);
+    repos.events.save(&saved).await.unwrap();
+
+    let found = repos.events.find_by_id(saved.event_id()).await.unwrap().unwrap();
+    assert_eq!(found.title(), "Meeting");
+    assert_eq!(found.calendar_id(), work.calendar_id());
+    assert_eq!(found.time_range(), &range(9, 10));
+    assert_eq!(found.location().as_deref(), Some("Room 4"));
+    assert_eq!(found.status(), &EventStatus::Tentative);
+    assert_eq!(found.tags(), saved.tags());
+
+    let mut updated = found;
+    updated.update_title("Review".into());
+    repos.events.save(&updated).await.unwrap();
+    let found = repos.events.find_by_id(saved.event_id()).await.unwrap().unwrap();
+    assert_eq!(found.title(), "Review");
+
+    assert!(repos.events.find_by_id(&EventId::new()).await.unwrap().is_none());
+}
+
+async fn event_needs_calendar(repos: Repos) {
+    let orphan = event(CalendarId::new(), "Meeting", 9, 10);
+
+    assert!(matches!(
+        repos.events.save(&orphan).await,
+        Err(RepositoryError::DatabaseError(_))
+    ));
+    assert!(repos.events.find_by_id(orphan.event_id()).await.unwrap().is_none());
+}
+
+async fn events_by_calendar_by_start(repos: Repos) {
+    let work = calendar(&repos, "Work").await;
+    let home = calendar(&repos, "Home").await;
+    repos.events.save(&event(*work.calendar_id(), "Lunch", 12, 13)).await.unwrap();
+    repos.events.save(&event(*work.calendar_id(), "Meeting", 9, 10)).await.unwrap();
+    repos.events.save(&event(*home.calendar_id(), "Dinner", 19, 21)).await.unwrap();
+
+    let found = repos.events.find_by_calendar(work.calendar_id()).await.unwrap();
+    assert_eq!(titles_of_events(&found), ["Meeting", "Lunch"]);
+}
+
+async fn events_in_range(repos: Repos) {
+    let work = calendar(&repos, "Work").await;
+    let home = calendar(&repos, "Home").await;
+    let calendar_id = *work.calendar_id();
+    repos.events.save(&event(calendar_id, "Before", 6, 8)).await.unwrap();
+    repos.events.save(&event(calendar_id, "Overlapping start", 7, 9)).await.unwrap();
+    repos.events.save(&event(calendar_id, "Inside", 10, 11)).await.unwrap();
+    repos.events.save(&event(calendar_id, "Overlapping end", 11, 13)).await.unwrap();
+    repos.events.save(&event(calendar_id, "Touching end", 12, 14)).await.unwrap();
+    repos.events.save(&event(*home.calendar_id(), "Elsewhere", 10, 11)).await.unwrap();
+    let mut cancelled = event(calendar_id, "Cancelled", 9, 10);
+    cancelled.cancel();
+    repos.events.save(&cancelled).await.unwrap();
+    let mut tentative = event(calendar_id, "Tentative", 9, 10);
+    tentative.set_status(EventStatus::Tentative);
+    repos.events.save(&tentative).await.unwrap();
+
+    let found = repos.events.find_in_range(&calendar_id, &range(8, 12)).await.unwrap();
+    assert_eq!(
+        titles_of_events(&found),
+        ["Overlapping start", "Tentative", "Inside", "Overlapping end"]
+    );
+}
+
+async fn events_by_location(repos: Repos) {
+    let work = calendar(&repos, "Work").await;
+    let home = calendar(&repos, "Home").await;
+    let calendar_id = *work.calendar_id();
+    let placed = |title: &str, from, location: Option<&str>, calendar_id| {
+        event(calendar_id, title, from, from + 1)
+            .with_place(location.map(Into::into), None, None)
+    };
+    repos.events.save(&placed("Board", 11, Some("BOARDROOM 1"), calendar_id)).await.unwrap();
+    repos.events.save(&placed("Sync", 9, Some("Room 4, 2nd floor"), calendar_id)).await.unwrap();
+    repos.events.save(&placed("Remote", 10, None, calendar_id)).await.unwrap();
+    repos.events.save(&placed("Percent", 12, Some("100% room"), calendar_id)).await.unwrap();
+    repos.events.save(&placed("Home", 9, Some("Living room"), *home.calendar_id())).await.unwrap();
+
+    let found = repos.events.find_by_location(&calendar_id, "room").await.unwrap();
+    assert_eq!(titles_of_events(&found), ["Sync", "Board", "Percent"]);
+
+    let found = repos.events.find_by_location(&calendar_id, "0% r").await.unwrap();
+    assert_eq!(titles_of_events(&found), ["Percent"]);
+
+    let found = repos.events.find_by_location(&calendar_id, "_").await.unwrap();
+    assert!(found.is_empty());
+}
+
+async fn events_by_tag(repos: Repos) {
+    let work = calendar(&repos, "Work").await;
+    let home = calendar(&repos, "Home").await;
+    let tag = Tag::new("urgent").unwrap();
+    let mut meeting = event(*work.calendar_id(), "Meeting", 9, 10);
+    meeting.add_tag(tag.clone());
+    let mut plumber = event(*home.calendar_id(), "Plumber", 8, 9);
+    plumber.add_tag(tag.clone());
+    let lunch = event(*work.calendar_id(), "Lunch", 12, 13);
+    let mut standup = series(*work.calendar_id(), "Standup", 8, 9);
+    standup.add_tag(tag.clone());
+    for event in [&meeting, &plumber, &lunch] {
+        repos.events.save(event).await.unwrap();
+    }
+    repos.recurring.save(&standup).await.unwrap();
+
+    let found = repos.events.find_by_tag(&tag).await.unwrap();
+    assert_eq!(
+        ids(found.iter().map(Event::event_id)),
+        ids([meeting.event_id(), plumber.event_id()])
+    );
+
+    let mut meeting = repos.events.find_by_id(meeting.event_id()).await.unwrap().unwrap();
+    meeting.remove_tag(&tag).unwrap();
+    repos.events.save(&meeting).await.unwrap();
+    let found = repos.events.find_by_tag(&tag).await.unwrap();
+    assert_eq!(titles_of_events(&found), ["Plumber"]);
+}
+
+async fn events_with_reminders_in(repos: Repos) {
+    let work = calendar(&repos, "Work").await;
+    let home = calendar(&repos, "Home").await;
+    let calendar_id = *work.calendar_id();
+    let soon = event(calendar_id, "Soon", 30, 31).with_reminders(reminder(before(1)));
+    // Starts after the window, but is reminded inside it
+    let early = event(*home.calendar_id(), "Early", 50, 51).with_reminders(reminder(before(3)));
+    let fixed = event(calendar_id, "Fixed", 100, 101)
+        .with_reminders(reminder(ReminderTrigger::Absolute(at(40))));
+    let late = event(calendar_id, "Late", 30, 31)
+        .with_reminders(reminder(ReminderTrigger::Relative(Duration::hours(20))));
+    let mut cancelled = event(calendar_id, "Cancelled", 30, 31).with_reminders(reminder(before(1)));
+    cancelled.cancel();
+    let trashed = event(calendar_id, "Trashed", 30, 31).with_reminders(reminder(before(1)));
+    let plain = event(calendar_id, "Plain", 30, 31);
+    for event in [&soon, &early, &fixed, &late, &cancelled, &trashed, &plain] {
+        repos.events.save(event).await.unwrap();
+    }
+    repos.events.trash(trashed.event_id(), at(0)).await.unwrap();
+
+    let found = repos.events.find_with_reminders_in(&range(24, 48)).await.unwrap();
+    assert_eq!(titles_of_events(&found), ["Soon", "Early", "Fixed"]);
+    // The end of the window is exclusive
+    let found = repos.events.find_with_reminders_in(&range(24, 29)).await.unwrap();
+    assert!(found.is_empty());
+}
+
+async fn event_delete(repos: Repos) {
+    let work = calendar(&repos, "Work").await;
+    let meeting = event(*work.calendar_id(), "Meeting", 9, 10);
+    repos.events.save(&meeting).await.unwrap();
+
+    repos.events.delete(meeting.event_id()).await.unwrap();
+
+    assert!(repos.events.find_by_id(meeting.event_id()).await.unwrap().is_none());
+    assert!(matches!(
+        repos.events.delete(meeting.event_id()).await,
+        Err(RepositoryError::NotFound)
+    ));
+    assert!(matches!(
+        repos.events.delete(&EventId::new()).await,
+        Err(RepositoryError::NotFound)
+    ));
+}
+
+// ============================================================================
+// Recurring events
+// ============================================================================
+
+async fn series_round_trip(repos: Repos) {
+    let work = calendar(&repos, "Work").await;
+    let mut saved = series(*work.calendar_id(), "Standup", 8, 9)
+        .with_place(Some("Room 4".into()), None, None);
+    saved.cancel_occurrence(at(8 + 24));
+    saved.reschedule_occurrence(at(8 + 48), range(48 + 10, 48 + 11));
+    repos.recurring.save(&saved).await.unwrap();
+
+    let found = repos.recurring.find_by_id(saved.event_id()).await.unwrap();
+    assert_eq!(found.title(), "Standup");
+    assert_eq!(found.time_range(), &range(8, 9));
+    assert_eq!(found.rule().frequency(), &Frequency::Daily);
+    assert_eq!(found.location().as_deref(), Some("Room 4"));
+    assert_eq!(found.exceptions().len(), 2);
+
+    let occurrences = found.occurrences_in(&range(0, 72));
+    let starts: Vec<_> = occurrences
+        .iter()
+        .map(|occurrence| *occurrence.time_range().starts_at())
+        .collect();
+    assert_eq!(starts, [at(8), at(48 + 10)]);
+
+    let mut updated = found;
+    updated.restore_occurrence(at(8 + 24));
+    repos.recurring.save(&updated).await.unwrap();
+    let found = repos.recurring.find_by_id(saved.event_id()).await.unwrap();
+    assert_eq!(found.exceptions().len(), 1);
+}
+
+async fn series_missing_is_not_found(repos: Repos) {
+    assert!(matches!(
+        repos.recurring.find_by_id(&EventId::new()).await,
+        Err(RepositoryError::NotFound)
+    ));
+
+    let orphan = series(CalendarId::new(), "Standup", 8, 9);
+    assert!(matches!(
+        repos.recurring.save(&orphan).await,
+        Err(RepositoryError::DatabaseError(_))
+    ));
+}
+
+async fn series_by_calendar_by_start(repos: Repos) {
+    let work = calendar(&repos, "Work").await;
+    let home = calendar(&repos, "Home").await;
+    repos.recurring.save(&series(*work.calendar_id(), "Review", 15, 16)).await.unwrap();
+    repos.recurring.save(&series(*work.calendar_id(), "Standup", 8, 9)).await.unwrap();
+    repos.recurring.save(&series(*home.calendar_id(), "Walk", 7, 8)).await.unwrap();
+
+    let found = repos.recurring.find_by_calendar(work.calendar_id()).await.unwrap();
+    assert_eq!(titles_of_series(&found), ["Standup", "Review"]);
+}
+
+async fn series_by_location(repos: Repos) {
+    let work = calendar(&repos, "Work").await;
+    let calendar_id = *work.calendar_id();
+    let placed = series(calendar_id, "Placed", 9, 10)
+        .with_place(Some("Main OFFICE".into()), None, None);
+    let mut moved = series(calendar_id, "Moved", 8, 9);
+    moved.set_occurrence_location(at(8 + 24), Some("Branch office".into())).unwrap();
+    let mut cancelled = series(calendar_id, "Cancelled", 7, 8);
+    cancelled.set_occurrence_location(at(7 + 24), Some("Office annex".into())).unwrap();
+    cancelled.cancel_occurrence(at(7 + 24));
+    let remote = series(calendar_id, "Remote", 10, 11);
+    for event in [&placed, &moved, &cancelled, &remote] {
+        repos.recurring.save(event).await.unwrap();
+    }
+
+    let found = repos.recurring.find_by_location(&calendar_id, "office").await.unwrap();
+    assert_eq!(titles_of_series(&found), ["Moved", "Placed"]);
+}
+
+async fn series_by_tag(repos: Repos) {
+    let work = calendar(&repos, "Work").await;
+    let home = calendar(&repos, "Home").await;
+    let tag = Tag::new("team").unwrap();
+    let mut standup = series(*work.calendar_id(), "Standup", 8, 9);
+    standup.add_tag(tag.clone());
+    let mut walk = series(*home.calendar_id(), "Walk", 7, 8);
+    walk.add_tag(tag.clone());
+    let review = series(*work.calendar_id(), "Review", 15, 16);
+    let mut meeting = event(*work.calendar_id(), "Meeting", 9, 10);
+    meeting.add_tag(tag.clone());
+    for event in [&standup, &walk, &review] {
+        repos.recurring.save(event).await.unwrap();
+    }
+    repos.events.save(&meeting).await.unwrap();
+
+    let found = repos.recurring.find_by_tag(&tag).await.unwrap();
+    assert_eq!(
+        ids(found.iter().map(RecurringEvent::event_id)),
+        ids([standup.event_id(), walk.event_id()])
+    );
+}
+
+async fn series_with_reminders_in(repos: Repos) {
+    let work = calendar(&repos, "Work").await;
+    let calendar_id = *work.calendar_id();
+    let once = RecurrenceRule::new(Frequency::Daily, 1, Some(at(10))).unwrap();
+    let daily = series(calendar_id, "Daily", 8, 9).with_reminders(reminder(before(1)));
+    let ended = RecurringEvent::new(
+        calendar_id,
+        "Ended".into(),
+        None,
+        range(7, 8),
+        once.clone(),
+        EventColor::from(0),
+        false,
+    )
+    .unwrap()
+    .with_reminders(reminder(before(1)));
+    // Its only occurrence is moved into the window
+    let mut moved = RecurringEvent::new(
+        calendar_id,
+        "Moved".into(),
+        None,
+        range(9, 10),
+        once,
+        EventColor::from(0),
+        false,
+    )
+    .unwrap()
+    .with_reminders(reminder(before(1)));
+    moved.reschedule_occurrence(at(9), range(60, 61));
+    let fixed = series(calendar_id, "Fixed", 200, 201)
+        .with_reminders(reminder(ReminderTrigger::Absolute(at(50))));
+    let future = series(calendar_id, "Future", 100, 101).with_reminders(reminder(before(1)));
+    let plain = series(calendar_id, "Plain", 6, 7);
+    for event in [&daily, &ended, &moved, &fixed, &future, &plain] {
+        repos.recurring.save(event).await.unwrap();
+    }
+
+    let found = repos.recurring.find_with_reminders_in(&range(48, 72)).await.unwrap();
+    assert_eq!(titles_of_series(&found), ["Daily", "Moved", "Fixed"]);
+}
+
+async fn series_delete(repos: Repos) {
+    let work = calendar(&repos, "Work").await;
+    let standup = series(*work.calendar_id(), "Standup", 8, 9);
+    repos.recurring.save(&standup).await.unwrap();
+
+    repos.recurring.delete(standup.event_id()).await.unwrap();
+
+    assert!(matches!(
+        repos.recurring.find_by_id(standup.event_id()).await,
+        Err(RepositoryError::NotFound)
+    ));
+    assert!(matches!(
+        repos.recurring.delete(standup.event_id()).await,
+        Err(RepositoryError::NotFound)
+    ));
+}
+
+// ============================================================================
+// Optimistic concurrency
+// ============================================================================
+
+async fn calendar_stale_save_conflicts(repos: Repos) {
+    let work = calendar(&repos, "Work").await;
+    assert_eq!(*work.version(), 1);
+
+    let mut first = work.clone();
+    first.update_name("Office".into());
+    assert_eq!(repos.calendars.save(&first).await.unwrap(), 2);
+
+    let mut second = work;
+    second.update_name("Home".into());
+    assert!(matches!(
+        repos.calendars.save(&second).await,
+        Err(RepositoryError::Conflict)
+    ));
+
+    let mut found = repos.calendars.find_by_id(first.calendar_id()).await.unwrap().unwrap();
+    assert_eq!(found.name(), "Office");
+    assert_eq!(*found.version(), 2);
+
+    found.archive();
+    repos.calendars.save(&found).await.unwrap();
+}
+
+async fn event_stale_save_conflicts(repos: Repos) {
+    let work = calendar(&repos, "Work").await;
+    let meeting = event(*work.calendar_id(), "Meeting", 9, 10);
+    repos.events.save(&meeting).await.unwrap();
+    let loaded = repos.events.find_by_id(meeting.event_id()).await.unwrap().unwrap();
+    assert_eq!(*loaded.version(), 1);
+
+    // A second unsaved copy under the same id is as stale as an old load
+    assert!(matches!(repos.events.save(&meeting).await, Err(RepositoryError::Conflict)));
+
+    // Saving again goes through with the version the save returned
+    let mut first = loaded.clone();
+    first.update_title("Stand".into());
+    let version = repos.events.save(&first).await.unwrap();
+    let mut first = first.with_version(version);
+    first.update_title("Standup".into());
+    assert_eq!(repos.events.save(&first).await.unwrap(), 3);
+
+    let mut second = loaded;
+    second.update_title("Review".into());
+    assert!(matches!(repos.events.save(&second).await, Err(RepositoryError::Conflict)));
+
+    let found = repos.events.find_by_id(meeting.event_id()).await.unwrap().unwrap();
+    assert_eq!(found.title(), "Standup");
+    assert_eq!(*found.version(), 3);
+
+    repos.events.delete(meeting.event_id()).await.unwrap();
+    repos.events.save(&second).await.unwrap();
+    let found = repos.events.find_by_id(meeting.event_id()).await.unwrap().unwrap();
+    assert_eq!(found.title(), "Review");
+}
+
+async fn series_stale_save_conflicts(repos: Repos) {
+    let work = calendar(&repos, "Work").await;
+    let standup = series(*work.calendar_id(), "Standup", 8, 9);
+    repos.recurring.save(&standup).await.unwrap();
+    let loaded = repos.recurring.find_by_id(standup.event_id()).await.unwrap();
+    assert_eq!(*loaded.version(), 1);
+
+    assert!(matches!(repos.recurring.save(&standup).await, Err(RepositoryError::Conflict)));
+
+    let mut first = loaded.clone();
+    first.cancel();
+    repos.recurring.save(&first).await.unwrap();
+
+    let mut second = loaded;
+    second.update_location(Some("Room 4".into()));
+    assert!(matches!(repos.recurring.save(&second).await, Err(RepositoryError::Conflict)));
+
+    let found = repos.recurring.find_by_id(standup.event_id()).await.unwrap();
+    assert!(found.is_cancelled());
+    assert_eq!(*found.version(), 2);
+}
+
+// ============================================================================
+// Trash
+// ============================================================================
+
+async fn event_trash_and_restore(repos: Repos) {
+    let work = calendar(&repos, "Work").await;
+    let meeting = event(*work.calendar_id(), "Meeting", 9, 10);
+    let review = event(*work.calendar_id(), "Review", 11, 12);
+    repos.events.save(&meeting).await.unwrap();
+    repos.events.save(&review).await.unwrap();
+
+    repos.events.trash(meeting.event_id(), at(0)).await.unwrap();
+
+    assert!(repos.events.find_by_id(meeting.event_id()).await.unwrap().is_none());
+    let live = repos.events.find_by_calendar(work.calendar_id()).await.unwrap();
+    assert_eq!(titles_of_events(&live), ["Review"]);
+    let trashed = repos.events.find_trashed().await.unwrap();
+    assert_eq!(titles_of_events(&trashed), ["Meeting"]);
+    assert_eq!(*trashed[0].deleted_at(), Some(at(0)));
+    assert!(matches!(
+        repos.events.trash(meeting.event_id(), at(1)).await,
+        Err(RepositoryError::NotFound)
+    ));
+
+    repos.events.restore(meeting.event_id()).await.unwrap();
+
+    let found = repos.events.find_by_id(meeting.event_id()).await.unwrap().unwrap();
+    assert!(!found.is_trashed());
+    assert!(repos.events.find_trashed().await.unwrap().is_empty());
+    assert!(matches!(
+        repos.events.restore(meeting.event_id()).await,
+        Err(RepositoryError::NotFound)
+    ));
+
+    // Trashing and restoring count as writes for concurrency
+    repos.events.save(&found).await.unwrap();
+}
+
+async fn series_trash_and_restore(repos: Repos) {
+    let work = calendar(&repos, "Work").await;
+    let standup = series(*work.calendar_id(), "Standup", 8, 9);
+    repos.recurring.save(&standup).await.unwrap();
+
+    repos.recurring.trash(standup.event_id(), at(0)).await.unwrap();
+
+    assert!(matches!(
+        repos.recurring.find_by_id(standup.event_id()).await,
+        Err(RepositoryError::NotFound)
+    ));
+    assert!(repos.recurring.find_by_calendar(work.calendar_id()).await.unwrap().is_empty());
+    let trashed = repos.recurring.find_trashed().await.unwrap();
+    assert_eq!(titles_of_series(&trashed), ["Standup"]);
+    assert_eq!(*trashed[0].deleted_at(), Some(at(0)));
+
+    repos.recurring.restore(standup.event_id()).await.unwrap();
+
+    let found = repos.recurring.find_by_id(standup.event_id()).await.unwrap();
+    assert!(!found.is_trashed());
+    assert!(matches!(
+        repos.recurring.restore(standup.event_id()).await,
+        Err(RepositoryError::NotFound)
+    ));
+}
+
+async fn calendar_trash_cascades_and_restores(repos: Repos) {
+    let work = calendar(&repos, "Work").await;
+    let meeting = event(*work.calendar_id(), "Meeting", 9, 10);
+    let earlier = event(*work.calendar_id(), "Earlier", 7, 8);
+    let standup = series(*work.calendar_id(), "Standup", 8, 9);
+    repos.events.save(&meeting).await.unwrap();
+    repos.events.save(&earlier).await.unwrap();
+    repos.recurring.save(&standup).await.unwrap();
+    repos.events.trash(earlier.event_id(), at(-1)).await.unwrap();
+
+    repos.calendars.trash(work.calendar_id(), at(0)).await.unwrap();
+
+    assert!(repos.calendars.find_by_id(work.calendar_id()).await.unwrap().is_none());
+    assert!(repos.calendars.find_all_active().await.unwrap().is_empty());
+    assert!(repos.events.find_by_id(meeting.event_id()).await.unwrap().is_none());
+    let trashed = repos.calendars.find_trashed().await.unwrap();
+    assert_eq!(trashed.len(), 1);
+    assert_eq!(*trashed[0].deleted_at(), Some(at(0)));
+    let trashed = repos.events.find_trashed().await.unwrap();
+    assert_eq!(titles_of_events(&trashed), ["Meeting", "Earlier"]);
+    assert_eq!(titles_of_series(&repos.recurring.find_trashed().await.unwrap()), ["Standup"]);
+
+    repos.calendars.restore(work.calendar_id()).await.unwrap();
+
+    assert!(repos.calendars.find_by_id(work.calendar_id()).await.unwrap().is_some());
+    assert!(repos.events.find_by_id(meeting.event_id()).await.unwrap().is_some());
+    assert!(repos.recurring.find_by_id(standup.event_id()).await.is_ok());
+    // What was trashed on its own before stays in the trash
+    let trashed = repos.events.find_trashed().await.unwrap();
+    assert_eq!(titles_of_events(&trashed), ["Earlier"]);
+}
+
+async fn purge_trashed_honours_cutoff(repos: Repos) {
+    let work = calendar(&repos, "Work").await;
+    let home = calendar(&repos, "Home").await;
+    let meeting = event(*work.calendar_id(), "Meeting", 9, 10);
+    let review = event(*work.calendar_id(), "Review", 11, 12);
+    let dinner = event(*home.calendar_id(), "Dinner", 19, 21);
+    repos.events.save(&meeting).await.unwrap();
+    repos.events.save(&review).await.unwrap();
+    repos.events.save(&dinner).await.unwrap();
+    repos.events.trash(meeting.event_id(), at(0)).await.unwrap();
+    repos.events.trash(review.event_id(), at(5)).await.unwrap();
+    repos.calendars.trash(home.calendar_id(), at(1)).await.unwrap();
+
+    assert_eq!(repos.events.purge_trashed(at(3)).await.unwrap(), 2);
+    assert_eq!(titles_of_events(&repos.events.find_trashed().await.unwrap()), ["Review"]);
+
+    assert_eq!(repos.calendars.purge_trashed(at(0)).await.unwrap(), 0);
+    assert_eq!(repos.calendars.purge_trashed(at(3)).await.unwrap(), 1);
+    assert!(repos.calendars.find_trashed().await.unwrap().is_empty());
+
+    assert!(matches!(
+        repos.events.restore(meeting.event_id()).await,
+        Err(RepositoryError::NotFound)
+    ));
+    assert!(repos.calendars.find_by_id(work.calendar_id()).await.unwrap().is_some());
+}
+
+async fn fresh_save_over_trashed_conflicts(repos: Repos) {
+    let work = calendar(&repos, "Work").await;
+    let meeting = event(*work.calendar_id(), "Meeting", 9, 10);
+    let standup = series(*work.calendar_id(), "Standup", 8, 9);
+    repos.events.save(&meeting).await.unwrap();
+    repos.recurring.save(&standup).await.unwrap();
+    repos.events.trash(meeting.event_id(), at(0)).await.unwrap();
+    repos.recurring.trash(standup.event_id(), at(0)).await.unwrap();
+
+    // An unsaved object under a trashed id, as a re-import brings in
+    assert!(matches!(repos.events.save(&meeting).await, Err(RepositoryError::Conflict)));
+    assert!(matches!(repos.recurring.save(&standup).await, Err(RepositoryError::Conflict)));
+    let fresh =
+        Calendar::with_id(*work.calendar_id(), "Work".into(), None, false, None, at(0), at(0))
+            .unwrap();
+    repos.calendars.trash(work.calendar_id(), at(1)).await.unwrap();
+    assert!(matches!(repos.calendars.save(&fresh).await, Err(RepositoryError::Conflict)));
+
+    // The trashed objects are left as they were
+    assert_eq!(titles_of_events(&repos.events.find_trashed().await.unwrap()), ["Meeting"]);
+    assert_eq!(repos.recurring.find_trashed().await.unwrap().len(), 1);
+    repos.calendars.restore(work.calendar_id()).await.unwrap();
+    repos.events.restore(meeting.event_id()).await.unwrap();
+    let found = repos.events.find_by_id(meeting.event_id()).await.unwrap().unwrap();
+    assert_eq!(found.title(), "Meeting");
+}
@@ -0,0 +1,492 @@
+//! The reminder scheduler on a mock clock, and the snoozes and
+//! dismissals it honours.
+
+mod support;
+
+use std::sync::{Arc, Mutex};
+
+use async_trait::async_trait;
+use chrono::{DateTime, Duration, DurationRound, TimeZone, Utc};
+use serde_json::json;
+use tokio::sync::mpsc;
+
+use kal_core::{
+    application::{
+        commands::{
+            calendars::{ArchiveCalendarCommand, ArchiveCalendarHandler},
+            reminders::{
+                DismissReminderCommand, DismissReminderHandler, SnoozeReminderCommand,
+                SnoozeReminderHandler,
+            },
+        },
+        error::ApplicationError,
+    },
+    domain::{
+        event::Event,
+        recurrence::{RecurrenceRule, RecurringEvent},
+        reminder::{Reminder, ReminderAction, ReminderState, ReminderTrigger},
+        repository::{
+            EventRepository, RecurringEventRepository,
+            ReminderStateRepository,
+        },
+        value_objects::{CalendarId, EventColor, Frequency, TimeRange},
+    },
+    infrastructure::{
+        persistence::{
+            Database, SqliteCalendarRepository, SqliteEventRepository,
+            SqliteRecurringEventRepository, SqliteReminderStateRepository,
+        },
+        reminders::{
+            Clock, MockClock, Notification, Notifier, ReminderError, ReminderScheduler,
+        },
+        rpc::{RpcDispatcher, RpcError},
+    },
+};
+
+use support::{calendar, calendar_with};
+
+type Scheduler = ReminderScheduler<
+    SqliteCalendarRepository,
+    SqliteEventRepository,
+    SqliteRecurringEventRepository,
+    SqliteReminderStateRepository,
+>;
+
+type SnoozeHandler = SnoozeReminderHandler<
+    SqliteEventRepository,
+    SqliteRecurringEventRepository,
+    SqliteReminderStateRepository,
+>;
+
+type DismissHandler = DismissReminderHandler<
+    SqliteEventRepository,
+    SqliteRecurringEventRepository,
+    SqliteReminderStateRepository,
+>;
+
+/// Keeps what it is handed; fails instead while `failing` is set.
+#[derive(Clone, Default)]
+struct Inbox {
+    received: Arc<Mutex<Vec<Notification>>>,
+    failing: Arc<Mutex<bool>>,
+}
+
+#[async_trait]
+impl Notifier for Inbox {
+    async fn notify(&self, notification: &Notification) -> Result<(), ReminderError> {
+        if *self.failing.lock().unwrap() {
+            return Err(ReminderError::Notify("unreachable".into()));
+        }
+        self.received.lock().unwrap().push(notification.clone());
+        Ok(())
+    }
+}
+
+impl Inbox {
+    /// (occurrence start, fired at) of everything received so far.
+    fn take(&self) -> Vec<(DateTime<Utc>, DateTime<Utc>)> {
+        self.received
+            .lock()
+            .unwrap()
+            .drain(..)
+            .map(|n| (*n.instance().starts_at(), *n.fired_at()))
+            .collect()
+    }
+}
+
+fn at(d: u32, h: u32, min: u32) -> DateTime<Utc> {
+    Utc.with_ymd_and_hms(2025, 3, d, h, min, 0).unwrap()
+}
+
+fn scheduler(database: &Database, clock: &MockClock, inbox: &Inbox) -> Scheduler {
+    ReminderScheduler::new(
+        database.calendars(),
+        database.events(),
+        database.recurring(),
+        database.reminder_states(),
+        Arc::new(clock.clone()),
+        Box::new(inbox.clone()),
+    )
+}
+
+/// A review at 10:00 reminded at 9:45 and, once more, at 9:50.
+async fn review(database: &Database, calendar_id: CalendarId) -> Event {
+    let event = Event::new(
+        calendar_id,
+        "Review".into(),
+        None,
+        TimeRange::new(at(10, 10, 0), at(10, 11, 0)).unwrap(),
+        EventColor::from(0),
+        false,
+    )
+    .unwrap()
+    .with_reminders(vec![Reminder::new(
+        ReminderTrigger::Relative(-Duration::minutes(15)),
+        ReminderAction::Display,
+        None,
+        1,
+        Some(Duration::minutes(5)),
+    )
+    .unwrap()]);
+    database.events().save(&event).await.unwrap();
+    event
+}
+
+#[tokio::test]
+async fn reminders_fire_on_the_tick_they_come_due() {
+    let database = Database::open_in_memory().await.unwrap();
+    let calendar_id = calendar(&database).await;
+    review(&database, calendar_id).await;
+    let clock = MockClock::new(at(10, 9, 30));
+    let inbox = Inbox::default();
+    let scheduler = scheduler(&database, &clock, &inbox);
+
+    // Beyond the rescan horizon, nothing is known yet
+    assert!(scheduler.tick().await.unwrap().is_empty());
+    assert_eq!(scheduler.next_due().await.unwrap(), None);
+
+    clock.set(at(10, 9, 42));
+    assert!(scheduler.tick().await.unwrap().is_empty());
+    assert_eq!(scheduler.next_due().await.unwrap(), Some(at(10, 9, 45)));
+
+    clock.set(at(10, 9, 45));
+    let deliveries = scheduler.tick().await.unwrap();
+    assert_eq!(deliveries.len(), 1);
+    assert!(deliveries[0].result().is_ok());
+    assert_eq!(deliveries[0].notification().title(), "Review");
+    assert_eq!(inbox.take(), [(at(10, 10, 0), at(10, 9, 45))]);
+
+    // Once per firing, however often the scheduler looks
+    assert!(scheduler.tick().await.unwrap().is_empty());
+    assert_eq!(scheduler.next_due().await.unwrap(), Some(at(10, 9, 50)));
+
+    clock.set(at(10, 9, 51));
+    assert_eq!(scheduler.tick().await.unwrap().len(), 1);
+    assert_eq!(inbox.take(), [(at(10, 10, 0), at(10, 9, 51))]);
+    assert!(scheduler.tick().await.unwrap().is_empty());
+    assert_eq!(scheduler.next_due().await.unwrap(), None);
+}
+
+#[tokio::test]
+async fn archived_calendars_stay_quiet() {
+    let database = Database::open_in_memory().await.unwrap();
+    let archived = calendar_with(&database, "Old", None).await;
+    review(&database, archived).await;
+    ArchiveCalendarHandler::new(database.calendars())
+        .handle(ArchiveCalendarCommand::new(archived))
+        .await
+        .unwrap();
+    let clock = MockClock::new(at(10, 9, 45));
+    let inbox = Inbox::default();
+
+    assert!(scheduler(&database, &clock, &inbox).tick().await.unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn a_restarted_scheduler_does_not_fire_again() {
+    let database = Database::open_in_memory().await.unwrap();
+    let calendar_id = calendar(&database).await;
+    review(&database, calendar_id).await;
+    let clock = MockClock::new(at(10, 9, 45));
+    let inbox = Inbox::default();
+
+    // A failed delivery still counts, so it is not retried forever
+    *inbox.failing.lock().unwrap() = true;
+    let deliveries = scheduler(&database, &clock, &inbox).tick().await.unwrap();
+    assert!(matches!(deliveries[0].result(), Err(ReminderError::Notify(_))));
+    *inbox.failing.lock().unwrap() = false;
+
+    let restarted = scheduler(&database, &clock, &inbox);
+    clock.set(at(10, 9, 47));
+    assert!(restarted.tick().await.unwrap().is_empty());
+    assert_eq!(restarted.next_due().await.unwrap(), Some(at(10, 9, 50)));
+
+    // Firings missed while nothing ran are not delivered in a burst
+    clock.set(at(10, 12, 0));
+    let restarted = scheduler(&database, &clock, &inbox);
+    assert_eq!(restarted.tick().await.unwrap().len(), 1);
+    assert!(scheduler(&database, &clock, &inbox).tick().await.unwrap().is_empty());
+    assert_eq!(inbox.take(), [(at(10, 10, 0), at(10, 12, 0))]);
+}
+
+#[tokio::test]
+async fn snoozed_reminders_fire_once_the_snooze_ends() {
+    let database = Database::open_in_memory().await.unwrap();
+    let calendar_id = calendar(&database).await;
+    let event = review(&database, calendar_id).await;
+    let clock = MockClock::new(at(10, 9, 45));
+    let inbox = Inbox::default();
+    let scheduler = scheduler(&database, &clock, &inbox);
+    scheduler.tick().await.unwrap();
+    inbox.take();
+
+    let instance = event.reminder_instances().remove(0);
+    let mut state = database.reminder_states().find(&instance.key()).await.unwrap().unwrap();
+    state.snooze(at(10, 10, 5), clock.now()).unwrap();
+    database.reminder_states().save(&state).await.unwrap();
+
+    // The snooze replaces the 9:50 repeat
+    clock.set(at(10, 9, 50));
+    assert!(scheduler.tick().await.unwrap().is_empty());
+    assert_eq!(scheduler.next_due().await.unwrap(), Some(at(10, 10, 5)));
+
+    clock.set(at(10, 10, 5));
+    assert_eq!(scheduler.tick().await.unwrap().len(), 1);
+    assert_eq!(inbox.take(), [(at(10, 10, 0), at(10, 10, 5))]);
+    assert!(scheduler.tick().await.unwrap().is_empty());
+    assert_eq!(scheduler.next_due().await.unwrap(), None);
+
+    let state: ReminderState =
+        database.reminder_states().find(&instance.key()).await.unwrap().unwrap();
+    assert_eq!(*state.snoozed_until(), None);
+    assert_eq!(*state.last_fired_at(), Some(at(10, 10, 5)));
+}
+
+#[tokio::test]
+async fn each_occurrence_of_a_series_fires_once() {
+    let database = Database::open_in_memory().await.unwrap();
+    let calendar_id = calendar(&database).await;
+    let mut series = RecurringEvent::new(
+        calendar_id,
+        "Standup".into(),
+        None,
+        TimeRange::new(at(10, 9, 0), at(10, 9, 15)).unwrap(),
+        RecurrenceRule::new(Frequency::Daily, 1, None).unwrap(),
+        EventColor::from(0),
+        false,
+    )
+    .unwrap()
+    .with_reminders(vec![Reminder::new(
+        ReminderTrigger::Relative(-Duration::minutes(10)),
+        ReminderAction::Display,
+        None,
+        0,
+        None,
+    )
+    .unwrap()]);
+    series.cancel_occurrence(at(12, 9, 0));
+    database.recurring().save(&series).await.unwrap();
+
+    let clock = MockClock::new(at(10, 8, 50));
+    let inbox = Inbox::default();
+    let scheduler = scheduler(&database, &clock, &inbox);
+
+    for day in 10..=13 {
+        clock.set(at(day, 8, 50));
+        scheduler.tick().await.unwrap();
+        scheduler.tick().await.unwrap();
+    }
+
+    // Nothing for the cancelled occurrence on the 12th
+    assert_eq!(
+        inbox.take(),
+        [
+            (at(10, 9, 0), at(10, 8, 50)),
+            (at(11, 9, 0), at(11, 8, 50)),
+            (at(13, 9, 0), at(13, 8, 50)),
+        ]
+    );
+}
+
+#[tokio::test]
+async fn run_sleeps_until_the_next_reminder_is_due() {
+    let database = Database::open_in_memory().await.unwrap();
+    let calendar_id = calendar(&database).await;
+    review(&database, calendar_id).await;
+    let clock = MockClock::new(at(10, 9, 44));
+    let inbox = Inbox::default();
+    let scheduler = scheduler(&database, &clock, &inbox);
+
+    let (sender, mut delivered) = mpsc::unbounded_channel();
+    tokio::spawn(async move {
+        scheduler
+            .run(move |delivery| {
+                let _ = sender.send(*delivery.notification().fired_at());
+            })
+            .await
+    });
+
+    // Sleeps instead of firing early, then wakes when the clock gets there
+    wait_for_sleeper(&clock).await;
+    assert!(delivered.try_recv().is_err());
+    clock.set(at(10, 9, 45));
+    assert_eq!(delivered.recv().await, Some(at(10, 9, 45)));
+
+    wait_for_sleeper(&clock).await;
+    clock.set(at(10, 9, 50));
+    assert_eq!(delivered.recv().await, Some(at(10, 9, 50)));
+}
+
+async fn wait_for_sleeper(clock: &MockClock) {
+    tokio::time::timeout(std::time::Duration::from_secs(5), async {
+        while clock.sleepers() == 0 {
+            tokio::task::yield_now().await;
+        }
+    })
+    .await
+    .expect("the scheduler never went to sleep");
+}
+
+// ============================================================================
+// Snooze and dismiss
+// ============================================================================
+
+/// Snoozes end in the future of the wall clock, so these scenarios start
+/// an hour from now rather than on a fixed date.
+fn soon() -> DateTime<Utc> {
+    Utc::now().duration_trunc(Duration::minutes(1)).unwrap() + Duration::hours(1)
+}
+
+fn snooze(database: &Database) -> SnoozeHandler {
+    SnoozeReminderHandler::new(database.events(), database.recurring(), database.reminder_states())
+}
+
+fn dismiss(database: &Database) -> DismissHandler {
+    DismissReminderHandler::new(database.events(), database.recurring(), database.reminder_states())
+}
+
+#[tokio::test]
+async fn dismissed_reminders_stay_quiet_after_a_restart() {
+    let database = Database::open_in_memory().await.unwrap();
+    let calendar_id = calendar(&database).await;
+    let base = soon();
+    let reminder = Reminder::new(
+        ReminderTrigger::Relative(-Duration::minutes(15)),
+        ReminderAction::Display,
+        None,
+        1,
+        Some(Duration::minutes(5)),
+    )
+    .unwrap();
+    let reminder_id = *reminder.reminder_id();
+    let event = Event::new(
+        calendar_id,
+        "Review".into(),
+        None,
+        TimeRange::new(base + Duration::minutes(15), base + Duration::hours(1)).unwrap(),
+        EventColor::from(0),
+        false,
+    )
+    .unwrap()
+    .with_reminders(vec![reminder]);
+    database.events().save(&event).await.unwrap();
+    let clock = MockClock::new(base);
+    let inbox = Inbox::default();
+    assert_eq!(scheduler(&database, &clock, &inbox).tick().await.unwrap().len(), 1);
+
+    let state = dismiss(&database)
+        .handle(DismissReminderCommand::new(*event.event_id(), reminder_id))
+        .await
+        .unwrap();
+    assert!(state.is_dismissed());
+
+    // The repeat five minutes later is dropped, restart or not
+    clock.set(base + Duration::minutes(5));
+    let restarted = scheduler(&database, &clock, &inbox);
+    assert!(restarted.tick().await.unwrap().is_empty());
+    assert_eq!(restarted.next_due().await.unwrap(), None);
+
+    // Snoozing brings it back
+    snooze(&database)
+        .handle(SnoozeReminderCommand::new(
+            *event.event_id(),
+            reminder_id,
+            base + Duration::minutes(10),
+        ))
+        .await
+        .unwrap();
+    clock.set(base + Duration::minutes(10));
+    assert_eq!(restarted.tick().await.unwrap().len(), 1);
+    assert!(restarted.tick().await.unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn snoozes_and_dismissals_apply_to_one_occurrence() {
+    let database = Database::open_in_memory().await.unwrap();
+    let calendar_id = calendar(&database).await;
+    let base = soon();
+    let first = base + Duration::minutes(10);
+    let reminder = Reminder::new(
+        ReminderTrigger::Relative(-Duration::minutes(10)),
+        ReminderAction::Display,
+        None,
+        0,
+        None,
+    )
+    .unwrap();
+    let reminder_id = *reminder.reminder_id();
+    let series = RecurringEvent::new(
+        calendar_id,
+        "Standup".into(),
+        None,
+        TimeRange::new(first, first + Duration::minutes(15)).unwrap(),
+        RecurrenceRule::new(Frequency::Daily, 1, None).unwrap(),
+        EventColor::from(0),
+        false,
+    )
+    .unwrap()
+    .with_reminders(vec![reminder]);
+    let id = *series.event_id();
+    database.recurring().save(&series).await.unwrap();
+
+    // Series need the occurrence
+    let missing = snooze(&database)
+        .handle(SnoozeReminderCommand::new(id, reminder_id, first))
+        .await;
+    assert!(matches!(missing, Err(ApplicationError::Validation(_))), "{missing:?}");
+
+    let clock = MockClock::new(base);
+    let inbox = Inbox::default();
+    let scheduler = scheduler(&database, &clock, &inbox);
+    scheduler.tick().await.unwrap();
+
+    snooze(&database)
+        .handle(
+            SnoozeReminderCommand::new(id, reminder_id, base + Duration::minutes(20))
+                .with_occurrence(Some(first)),
+        )
+        .await
+        .unwrap();
+    dismiss(&database)
+        .handle(
+            DismissReminderCommand::new(id, reminder_id)
+                .with_occurrence(Some(first + Duration::days(1))),
+        )
+        .await
+        .unwrap();
+
+    for minutes in [20, 60 * 24, 60 * 48] {
+        clock.set(base + Duration::minutes(minutes));
+        scheduler.tick().await.unwrap();
+    }
+
+    assert_eq!(
+        inbox.take(),
+        [
+            (first, base),
+            (first, base + Duration::minutes(20)),
+            (first + Duration::days(2), base + Duration::days(2)),
+        ]
+    );
+}
+
+#[tokio::test]
+async fn snoozes_must_end_in_a_reachable_future() {
+    let database = Database::open_in_memory().await.unwrap();
+    let calendar_id = calendar(&database).await;
+    let event = review(&database, calendar_id).await;
+    let dispatcher = RpcDispatcher::new(database.pool().clone());
+
+    for minutes in [1_000_000_000_000, i64::MAX, 0, -5] {
+        let params = json!({
+            "id": event.event_id().as_uuid(),
+            "reminder_id": event.reminders()[0].reminder_id().as_uuid(),
+            "minutes": minutes,
+        });
+        let refused = dispatcher.call("reminder.snooze", params).await;
+        assert!(
+            matches!(refused, Err(RpcError::Application(ApplicationError::Validation(_)))),
+            "{minutes}: {refused:?}"
+        );
+    }
+}
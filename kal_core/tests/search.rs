@@ -0,0 +1,208 @@
+//! Full-text search over events and series: what is indexed, ranking and
+//! snippets, keeping the index in step, and calendar and date filters.
+
+mod support;
+
+use chrono::Utc;
+use serde_json::{json, Value};
+
+use kal_core::{
+    domain::{
+        attendee::{Attendee, AttendeeRole, ParticipationStatus},
+        event::Event,
+        recurrence::{RecurrenceRule, RecurringEvent},
+        repository::{EventRepository, EventSearchRepository, RecurringEventRepository},
+        search::{within_window, SearchHit, SearchHitKind},
+        value_objects::{CalendarId, EventColor, Frequency, TimeRange},
+    },
+    infrastructure::{persistence::Database, rpc::RpcDispatcher},
+};
+
+use support::{calendar, calendar_with, range, utc};
+
+fn event(calendar_id: CalendarId, title: &str, description: Option<&str>, d: u32) -> Event {
+    Event::new(
+        calendar_id,
+        title.into(),
+        description.map(str::to_string),
+        range(d, 9, 10),
+        EventColor::from(0),
+        false,
+    )
+    .unwrap()
+}
+
+fn titles(hits: &[SearchHit]) -> Vec<&str> {
+    hits.iter().map(|h| h.title().as_str()).collect()
+}
+
+#[tokio::test]
+async fn hits_are_ranked_and_highlighted() {
+    let database = Database::open_in_memory().await.unwrap();
+    let calendar_id = calendar(&database).await;
+
+    let retro = event(calendar_id, "Retro", Some("Talk about the budget overrun"), 10);
+    let budget = event(calendar_id, "Budget planning", Some("Quarterly numbers"), 11);
+    let mut lunch = event(calendar_id, "Lunch", None, 12).with_place(
+        Some("Café Müller".into()),
+        None,
+        None,
+    );
+    lunch
+        .add_attendee(
+            Attendee::new(
+                "zoe@example.com".into(),
+                Some("Zoë Quinn".into()),
+                AttendeeRole::Required,
+                ParticipationStatus::NeedsAction,
+                false,
+            )
+            .unwrap(),
+        )
+        .unwrap();
+    for event in [&retro, &budget, &lunch] {
+        database.events().save(event).await.unwrap();
+    }
+
+    let search = database.search();
+
+    // A title match outranks one in the description
+    let hits = search.search("budget", None, None).await.unwrap();
+    assert_eq!(titles(&hits), ["Budget planning", "Retro"]);
+    assert!(hits[0].score() < hits[1].score());
+    assert_eq!(*hits[0].kind(), SearchHitKind::Event);
+    assert_eq!(hits[1].snippet(), "Talk about the <mark>budget</mark> overrun");
+
+    // Locations and attendees are indexed; accents and case are ignored
+    assert_eq!(titles(&search.search("cafe muller", None, None).await.unwrap()), ["Lunch"]);
+    assert_eq!(titles(&search.search("ZOE", None, None).await.unwrap()), ["Lunch"]);
+    assert_eq!(titles(&search.search("zoe@example", None, None).await.unwrap()), ["Lunch"]);
+
+    // Every word must match, each as a prefix
+    let hits = search.search("quart numb", None, None).await.unwrap();
+    assert_eq!(titles(&hits), ["Budget planning"]);
+    assert!(search.search("budget lunch", None, None).await.unwrap().is_empty());
+    assert!(search.search("  ", None, None).await.unwrap().is_empty());
+    assert!(search.search("budget\" OR \"", None, None).await.unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn the_index_follows_changes() {
+    let database = Database::open_in_memory().await.unwrap();
+    let calendar_id = calendar(&database).await;
+    let retro = event(calendar_id, "Retro", None, 10);
+    database.events().save(&retro).await.unwrap();
+    let series = RecurringEvent::new(
+        calendar_id,
+        "Standup".into(),
+        None,
+        range(10, 9, 10),
+        RecurrenceRule::new(Frequency::Daily, 1, None).unwrap(),
+        EventColor::from(0),
+        false,
+    )
+    .unwrap();
+    database.recurring().save(&series).await.unwrap();
+    let search = database.search();
+
+    let mut review = database.events().find_by_id(retro.event_id()).await.unwrap().unwrap();
+    review.update_title("Sprint review".into());
+    review.update_location(Some("Boardroom".into()));
+    database.events().save(&review).await.unwrap();
+    assert!(search.search("retro", None, None).await.unwrap().is_empty());
+    let hits = search.search("boardroom", None, None).await.unwrap();
+    assert_eq!(titles(&hits), ["Sprint review"]);
+
+    let mut standup = database.recurring().find_by_id(series.event_id()).await.unwrap();
+    standup
+        .add_attendee(
+            Attendee::new(
+                "bob@example.com".into(),
+                Some("Bob".into()),
+                AttendeeRole::Required,
+                ParticipationStatus::NeedsAction,
+                false,
+            )
+            .unwrap(),
+        )
+        .unwrap();
+    database.recurring().save(&standup).await.unwrap();
+    let hits = search.search("bob", None, None).await.unwrap();
+    assert_eq!(titles(&hits), ["Standup"]);
+    assert_eq!(*hits[0].kind(), SearchHitKind::Series);
+
+    // Trashed events drop out, deleted ones leave the index
+    database.events().trash(retro.event_id(), Utc::now()).await.unwrap();
+    assert!(search.search("sprint", None, None).await.unwrap().is_empty());
+    database.recurring().delete(series.event_id()).await.unwrap();
+    assert!(search.search("standup", None, None).await.unwrap().is_empty());
+    let rows: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM event_search")
+        .fetch_one(database.pool())
+        .await
+        .unwrap();
+    assert_eq!(rows, 1);
+}
+
+#[tokio::test]
+async fn searches_narrow_to_a_calendar_and_a_window() {
+    let database = Database::open_in_memory().await.unwrap();
+    let work = calendar(&database).await;
+    let home = calendar_with(&database, "Home", None).await;
+
+    database.events().save(&event(work, "Budget review", None, 10)).await.unwrap();
+    database.events().save(&event(work, "Budget close", None, 20)).await.unwrap();
+    database.events().save(&event(home, "Budget for holidays", None, 11)).await.unwrap();
+    let weekly = RecurringEvent::new(
+        work,
+        "Budget sync".into(),
+        None,
+        range(3, 14, 15),
+        RecurrenceRule::new(Frequency::Weekly, 1, Some(utc(17, 23))).unwrap(),
+        EventColor::from(0),
+        false,
+    )
+    .unwrap();
+    database.recurring().save(&weekly).await.unwrap();
+
+    let search = database.search();
+    let hits = search.search("budget", Some(&home), None).await.unwrap();
+    assert_eq!(titles(&hits), ["Budget for holidays"]);
+
+    // Series are matched whatever their dates, then moved to their first
+    // occurrence in the window by the caller
+    let window = TimeRange::new(utc(9, 0), utc(16, 0)).unwrap();
+    let hits = search.search("budget", Some(&work), Some(&window)).await.unwrap();
+    let mut found = titles(&hits);
+    found.sort();
+    assert_eq!(found, ["Budget review", "Budget sync"]);
+    let hits = within_window(hits, std::slice::from_ref(&weekly), &window);
+    let sync = hits.iter().find(|h| h.title() == "Budget sync").unwrap();
+    assert_eq!(*sync.time_range(), range(10, 14, 15));
+
+    // A series with nothing in the window is dropped
+    let late = TimeRange::new(utc(18, 0), utc(25, 0)).unwrap();
+    let hits = search.search("budget", Some(&work), Some(&late)).await.unwrap();
+    let hits = within_window(hits, std::slice::from_ref(&weekly), &late);
+    assert_eq!(titles(&hits), ["Budget close"]);
+
+    // The daemon puts the pieces together and caps the results
+    let dispatcher = RpcDispatcher::new(database.pool().clone());
+    let line = json!({
+        "jsonrpc": "2.0",
+        "id": 1,
+        "method": "event.search",
+        "params": {
+            "query": "budget",
+            "calendar_id": work.to_string(),
+            "from": "2025-03-09T00:00:00Z",
+            "to": "2025-03-16T00:00:00Z",
+            "limit": 5,
+        },
+    });
+    let response = dispatcher.handle_line(&line.to_string()).await.unwrap();
+    let response: Value = serde_json::from_str(&response).unwrap();
+    let hits = response["result"].as_array().unwrap();
+    assert_eq!(hits.len(), 2);
+    let sync = hits.iter().find(|h| h["kind"] == "SERIES").unwrap();
+    assert_eq!(sync["starts_at"], "2025-03-10T14:00:00Z");
+}
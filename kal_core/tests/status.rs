@@ -0,0 +1,222 @@
+//! STATUS and TRANSP: the migration off the cancelled flag, commands,
+//! free/busy and conflicts, and iCalendar mapping.
+
+mod support;
+
+use std::borrow::Cow;
+
+use sqlx::{migrate::Migrator, sqlite::SqliteConnectOptions, SqlitePool};
+
+use kal_core::{
+    application::commands::{
+        events::{
+            UpdateEventStatusCommand, UpdateEventStatusHandler, UpdateEventTransparencyCommand,
+            UpdateEventTransparencyHandler,
+        },
+        recurring::{UpdateRecurringTransparencyCommand, UpdateRecurringTransparencyHandler},
+    },
+    domain::{
+        calendar::Calendar,
+        calendar_object::CalendarObject,
+        event::Event,
+        free_busy::{busy_periods, BusyKind},
+        recurrence::{RecurrenceRule, RecurringEvent},
+        repository::{CalendarRepository, EventRepository, RecurringEventRepository},
+        value_objects::{
+            CalendarId, EventColor, EventId, EventStatus, Frequency, Transparency,
+        },
+    },
+    infrastructure::{
+        ical::{Component, IcalMapper},
+        persistence::{database::MIGRATOR, Database},
+    },
+};
+
+use support::{event, range};
+
+#[tokio::test]
+async fn the_migration_keeps_cancelled_rows_cancelled() {
+    let dir = std::env::temp_dir().join(format!("kal-{}", uuid::Uuid::new_v4()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let path = dir.join("kal.db");
+
+    // A database from before STATUS, migrated up to the tags
+    let before = Migrator {
+        migrations: Cow::Owned(MIGRATOR.migrations[..10].to_vec()),
+        ..MIGRATOR
+    };
+    let options = SqliteConnectOptions::new().filename(&path).create_if_missing(true);
+    let pool = SqlitePool::connect_with(options).await.unwrap();
+    before.run(&pool).await.unwrap();
+
+    let calendar_id = CalendarId::new();
+    let now = "2025-03-01T00:00:00+00:00";
+    sqlx::query(
+        "INSERT INTO calendars (id, name, created_at, updated_at) VALUES (?, 'Work', ?, ?)",
+    )
+    .bind(calendar_id.to_string())
+    .bind(now)
+    .bind(now)
+    .execute(&pool)
+    .await
+    .unwrap();
+    let mut ids = Vec::new();
+    for (title, cancelled) in [("Review", 0), ("Offsite", 1)] {
+        let id = uuid::Uuid::new_v4();
+        sqlx::query(
+            "INSERT INTO events (id, calendar_id, title, starts_at, ends_at, is_cancelled, \
+             created_at, updated_at) VALUES (?, ?, ?, ?, ?, ?, ?, ?)",
+        )
+        .bind(id.to_string())
+        .bind(calendar_id.to_string())
+        .bind(title)
+        .bind("2025-03-10T09:00:00+00:00")
+        .bind("2025-03-10T10:00:00+00:00")
+        .bind(cancelled)
+        .bind(now)
+        .bind(now)
+        .execute(&pool)
+        .await
+        .unwrap();
+        ids.push(id);
+    }
+    pool.close().await;
+
+    let database = Database::open(&path).await.unwrap();
+    let mut statuses = Vec::new();
+    for id in &ids {
+        let id = EventId::from_uuid(*id);
+        let event = database.events().find_by_id(&id).await.unwrap().unwrap();
+        statuses.push((*event.status(), *event.transparency()));
+    }
+    assert_eq!(
+        statuses,
+        [
+            (EventStatus::Confirmed, Transparency::Opaque),
+            (EventStatus::Cancelled, Transparency::Opaque),
+        ]
+    );
+
+    // Cancelled events are still left out of ranges
+    let found = database.events().find_in_range(&calendar_id, &range(10, 0, 23)).await.unwrap();
+    let titles: Vec<&str> = found.iter().map(|e| e.title().as_str()).collect();
+    assert_eq!(titles, ["Review"]);
+}
+
+#[tokio::test]
+async fn free_busy_tells_tentative_from_busy_and_skips_free_time() {
+    let database = Database::open_in_memory().await.unwrap();
+    let calendar = Calendar::new("Work".into(), None).unwrap();
+    database.calendars().save(&calendar).await.unwrap();
+    let calendar_id = *calendar.calendar_id();
+
+    let review = event(calendar_id, "Review", range(10, 9, 10));
+    let hold = event(calendar_id, "Hold", range(10, 11, 12));
+    let home = event(calendar_id, "Working from home", range(10, 8, 17));
+    let dropped = event(calendar_id, "Dropped", range(10, 14, 15));
+    for event in [&review, &hold, &home, &dropped] {
+        database.events().save(event).await.unwrap();
+    }
+    let series = RecurringEvent::new(
+        calendar_id,
+        "Standup".into(),
+        None,
+        range(10, 13, 14),
+        RecurrenceRule::new(Frequency::Daily, 1, None).unwrap(),
+        EventColor::from(0),
+        false,
+    )
+    .unwrap();
+    database.recurring().save(&series).await.unwrap();
+
+    let status = UpdateEventStatusHandler::new(database.events(), database.calendars());
+    status
+        .handle(UpdateEventStatusCommand::new(*hold.event_id(), EventStatus::Tentative))
+        .await
+        .unwrap();
+    status
+        .handle(UpdateEventStatusCommand::new(*dropped.event_id(), EventStatus::Cancelled))
+        .await
+        .unwrap();
+    UpdateEventTransparencyHandler::new(database.events(), database.calendars())
+        .handle(UpdateEventTransparencyCommand::new(
+            *home.event_id(),
+            Transparency::Transparent,
+        ))
+        .await
+        .unwrap();
+
+    let window = range(10, 0, 23);
+    let events = database.events().find_in_range(&calendar_id, &window).await.unwrap();
+    let all_series = database.recurring().find_by_calendar(&calendar_id).await.unwrap();
+    let periods: Vec<(&str, BusyKind)> = busy_periods(&events, &all_series, &window)
+        .iter()
+        .map(|p| {
+            let title = if p.event_id() == series.event_id() {
+                "Standup"
+            } else {
+                events.iter().find(|e| e.event_id() == p.event_id()).unwrap().title().as_str()
+            };
+            (title, *p.kind())
+        })
+        .collect();
+    assert_eq!(
+        periods,
+        [("Review", BusyKind::Busy), ("Hold", BusyKind::Tentative), ("Standup", BusyKind::Busy)]
+    );
+
+    // A transparent series frees its occurrences too
+    UpdateRecurringTransparencyHandler::new(database.recurring(), database.calendars())
+        .handle(UpdateRecurringTransparencyCommand::new(
+            *series.event_id(),
+            Transparency::Transparent,
+        ))
+        .await
+        .unwrap();
+    let all_series = database.recurring().find_by_calendar(&calendar_id).await.unwrap();
+    assert_eq!(busy_periods(&events, &all_series, &window).len(), 2);
+
+    // Conflicts only count time that is taken
+    let stored = |id| events.iter().find(|e| e.event_id() == id).unwrap();
+    let clash = event(calendar_id, "Clash", range(10, 9, 12));
+    assert!(clash.overlaps_with(stored(review.event_id())));
+    assert!(clash.overlaps_with(stored(hold.event_id())));
+    assert!(!clash.overlaps_with(stored(home.event_id())));
+}
+
+#[test]
+fn status_and_transp_map_both_ways() {
+    let ics = "BEGIN:VCALENDAR\r\nVERSION:2.0\r\nPRODID:-//test//EN\r\n\
+               BEGIN:VEVENT\r\nUID:hold@example.com\r\nSUMMARY:Hold\r\n\
+               DTSTART:20250310T110000Z\r\nDTEND:20250310T120000Z\r\n\
+               STATUS:TENTATIVE\r\nTRANSP:TRANSPARENT\r\nEND:VEVENT\r\n\
+               BEGIN:VEVENT\r\nUID:plain@example.com\r\nSUMMARY:Plain\r\n\
+               DTSTART:20250310T130000Z\r\nDTEND:20250310T140000Z\r\nEND:VEVENT\r\n\
+               END:VCALENDAR\r\n";
+    let calendar_id = CalendarId::new();
+    let objects = IcalMapper::to_domain(&Component::parse(ics).unwrap(), calendar_id).unwrap();
+    let events: Vec<&Event> = objects
+        .iter()
+        .map(|o| match o {
+            CalendarObject::Event(event) => event,
+            _ => panic!("expected events"),
+        })
+        .collect();
+
+    assert_eq!(*events[0].status(), EventStatus::Tentative);
+    assert_eq!(*events[0].transparency(), Transparency::Transparent);
+    // Without the properties, events are confirmed and opaque
+    assert_eq!(*events[1].status(), EventStatus::Confirmed);
+    assert_eq!(*events[1].transparency(), Transparency::Opaque);
+
+    let exported = IcalMapper::to_ics(&CalendarObject::Event(events[0].clone()));
+    assert!(exported.contains("STATUS:TENTATIVE"));
+    assert!(exported.contains("TRANSP:TRANSPARENT"));
+    let mut objects =
+        IcalMapper::to_domain(&Component::parse(&exported).unwrap(), calendar_id).unwrap();
+    let CalendarObject::Event(reimported) = objects.remove(0) else {
+        panic!("expected an event");
+    };
+    assert_eq!(reimported.status(), events[0].status());
+    assert_eq!(reimported.transparency(), events[0].transparency());
+}
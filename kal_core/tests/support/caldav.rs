@@ -0,0 +1,303 @@
+//! A CalDAV server holding one calendar collection in memory. It answers
+//! the requests `CalDavClient` makes: discovery PROPFINDs,
+//! `sync-collection` and `calendar-multiget` REPORTs, and conditional
+//! GET, PUT and DELETE on calendar object resources.
+
+use std::{
+    collections::BTreeMap,
+    sync::{Arc, Mutex},
+};
+
+use hyper::{Request, Response};
+
+use super::{header, response, serve};
+
+pub const PRINCIPAL: &str = "/principals/alice/";
+pub const HOME: &str = "/calendars/alice/";
+pub const COLLECTION: &str = "/calendars/alice/work/";
+pub const TASK_LIST: &str = "/calendars/alice/todo/";
+
+const TOKEN_PREFIX: &str = "http://stub.invalid/sync/";
+
+/// A request as the server received it.
+#[derive(Debug, Clone)]
+pub struct Received {
+    pub method: String,
+    pub path: String,
+    pub depth: Option<String>,
+    pub if_match: Option<String>,
+    pub if_none_match: Option<String>,
+}
+
+#[derive(Debug, Clone)]
+struct Stored {
+    etag: String,
+    data: String,
+}
+
+#[derive(Debug, Default)]
+struct State {
+    objects: BTreeMap<String, Stored>,
+    /// Every change to a member, as (revision, href).
+    changes: Vec<(u64, String)>,
+    revision: u64,
+    received: Vec<Received>,
+}
+
+impl State {
+    fn change(&mut self, href: &str) -> u64 {
+        self.revision += 1;
+        self.changes.push((self.revision, href.to_string()));
+        self.revision
+    }
+
+    fn store(&mut self, href: &str, data: String) -> String {
+        let etag = format!("\"{}\"", self.change(href));
+        self.objects.insert(href.to_string(), Stored { etag: etag.clone(), data });
+        etag
+    }
+
+    fn token(&self) -> String {
+        format!("{TOKEN_PREFIX}{}", self.revision)
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct CalDavStub {
+    state: Arc<Mutex<State>>,
+}
+
+impl CalDavStub {
+    /// Starts serving an empty collection; returns the stub and its URL.
+    pub async fn start() -> (Self, String) {
+        let stub = Self::default();
+        let server = stub.clone();
+        let url = serve(move |request| server.respond(request)).await;
+        (stub, url)
+    }
+
+    /// Writes a member the way another client would; returns its ETag.
+    pub fn store(&self, href: &str, data: &str) -> String {
+        self.state.lock().unwrap().store(href, data.to_string())
+    }
+
+    /// Deletes a member the way another client would.
+    pub fn remove(&self, href: &str) {
+        let mut state = self.state.lock().unwrap();
+        if state.objects.remove(href).is_some() {
+            state.change(href);
+        }
+    }
+
+    pub fn data(&self, href: &str) -> Option<String> {
+        self.state.lock().unwrap().objects.get(href).map(|o| o.data.clone())
+    }
+
+    pub fn etag(&self, href: &str) -> Option<String> {
+        self.state.lock().unwrap().objects.get(href).map(|o| o.etag.clone())
+    }
+
+    pub fn hrefs(&self) -> Vec<String> {
+        self.state.lock().unwrap().objects.keys().cloned().collect()
+    }
+
+    /// Every request received so far, oldest first.
+    pub fn received(&self) -> Vec<Received> {
+        self.state.lock().unwrap().received.clone()
+    }
+
+    fn respond(&self, request: Request<String>) -> Response<String> {
+        let mut state = self.state.lock().unwrap();
+        let method = request.method().as_str().to_string();
+        let path = request.uri().path().to_string();
+
+        state.received.push(Received {
+            method: method.clone(),
+            path: path.clone(),
+            depth: header(&request, "depth"),
+            if_match: header(&request, "if-match"),
+            if_none_match: header(&request, "if-none-match"),
+        });
+
+        match method.as_str() {
+            "PROPFIND" => propfind(&state, &path),
+            "REPORT" if path == COLLECTION => report(&state, request.body()),
+            "GET" => match state.objects.get(&path) {
+                Some(object) => response(200, &[("ETag", &object.etag)], object.data.clone()),
+                None => response(404, &[], ""),
+            },
+            "PUT" if path.starts_with(COLLECTION) => {
+                let current = state.objects.get(&path).map(|o| o.etag.clone());
+                if !precondition_holds(&request, current.as_deref()) {
+                    return response(412, &[], "");
+                }
+
+                let etag = state.store(&path, request.body().clone());
+                let status = if current.is_some() { 204 } else { 201 };
+                response(status, &[("ETag", &etag)], "")
+            }
+            "DELETE" => {
+                let Some(current) = state.objects.get(&path).map(|o| o.etag.clone()) else {
+                    return response(404, &[], "");
+                };
+                if !precondition_holds(&request, Some(&current)) {
+                    return response(412, &[], "");
+                }
+
+                state.objects.remove(&path);
+                state.change(&path);
+                response(204, &[], "")
+            }
+            _ => response(405, &[], ""),
+        }
+    }
+}
+
+fn precondition_holds(request: &Request<String>, current: Option<&str>) -> bool {
+    if let Some(expected) = header(request, "if-match") {
+        return current == Some(expected.as_str());
+    }
+    if header(request, "if-none-match").as_deref() == Some("*") {
+        return current.is_none();
+    }
+    true
+}
+
+fn propfind(state: &State, path: &str) -> Response<String> {
+    let responses = match path {
+        "/" => format!(
+            "<d:response><d:href>/</d:href>{}</d:response>",
+            propstat(&format!(
+                "<d:current-user-principal><d:href>{PRINCIPAL}</d:href></d:current-user-principal>"
+            )),
+        ),
+        PRINCIPAL => format!(
+            "<d:response><d:href>{PRINCIPAL}</d:href>{}</d:response>",
+            propstat(&format!("<c:calendar-home-set><d:href>{HOME}</d:href></c:calendar-home-set>")),
+        ),
+        HOME => [
+            format!(
+                "<d:response><d:href>{HOME}</d:href>{}</d:response>",
+                propstat("<d:resourcetype><d:collection/></d:resourcetype>"),
+            ),
+            format!(
+                "<d:response><d:href>{COLLECTION}</d:href>{}</d:response>",
+                propstat(&format!(
+                    "<d:resourcetype><d:collection/><c:calendar/></d:resourcetype>\
+                     <d:displayname>Work</d:displayname>\
+                     <c:calendar-description>Shared work calendar</c:calendar-description>\
+                     <c:supported-calendar-component-set><c:comp name=\"VEVENT\"/></c:supported-calendar-component-set>\
+                     <cs:getctag>{0}</cs:getctag>\
+                     <d:sync-token>{1}</d:sync-token>",
+                    state.revision,
+                    state.token(),
+                )),
+            ),
+            format!(
+                "<d:response><d:href>{TASK_LIST}</d:href>{}</d:response>",
+                propstat(
+                    "<d:resourcetype><d:collection/><c:calendar/></d:resourcetype>\
+                     <d:displayname>To do</d:displayname>\
+                     <c:supported-calendar-component-set><c:comp name=\"VTODO\"/></c:supported-calendar-component-set>",
+                ),
+            ),
+        ]
+        .concat(),
+        _ => return response(404, &[], ""),
+    };
+
+    multistatus(&responses)
+}
+
+fn report(state: &State, body: &str) -> Response<String> {
+    if body.contains("sync-collection") {
+        sync_collection(state, body)
+    } else if body.contains("calendar-multiget") {
+        calendar_multiget(state, body)
+    } else {
+        response(400, &[], "unsupported REPORT")
+    }
+}
+
+fn sync_collection(state: &State, body: &str) -> Response<String> {
+    let token = between(body, "<d:sync-token>", "</d:sync-token>").next().unwrap_or_default();
+
+    let since = if token.is_empty() {
+        0
+    } else {
+        match token.strip_prefix(TOKEN_PREFIX).and_then(|r| r.parse::<u64>().ok()) {
+            Some(revision) if revision <= state.revision => revision,
+            _ => return response(403, &[], "invalid sync token"),
+        }
+    };
+
+    let mut hrefs: Vec<&str> = state
+        .changes
+        .iter()
+        .filter(|(revision, _)| *revision > since)
+        .map(|(_, href)| href.as_str())
+        .collect();
+    hrefs.sort();
+    hrefs.dedup();
+
+    let responses: String = hrefs
+        .into_iter()
+        .filter_map(|href| match state.objects.get(href) {
+            Some(object) => Some(format!(
+                "<d:response><d:href>{href}</d:href>{}</d:response>",
+                propstat(&format!("<d:getetag>{}</d:getetag>", object.etag)),
+            )),
+            // An initial sync lists only current members
+            None if since == 0 => None,
+            None => Some(format!(
+                "<d:response><d:href>{href}</d:href><d:status>HTTP/1.1 404 Not Found</d:status></d:response>"
+            )),
+        })
+        .collect();
+
+    multistatus(&format!("{responses}<d:sync-token>{}</d:sync-token>", state.token()))
+}
+
+fn calendar_multiget(state: &State, body: &str) -> Response<String> {
+    let responses: String = between(body, "<d:href>", "</d:href>")
+        .map(|href| match state.objects.get(href) {
+            Some(object) => format!(
+                "<d:response><d:href>{href}</d:href>{}</d:response>",
+                propstat(&format!(
+                    "<d:getetag>{}</d:getetag><c:calendar-data>{}</c:calendar-data>",
+                    object.etag,
+                    escape(&object.data),
+                )),
+            ),
+            None => format!(
+                "<d:response><d:href>{href}</d:href><d:status>HTTP/1.1 404 Not Found</d:status></d:response>"
+            ),
+        })
+        .collect();
+
+    multistatus(&responses)
+}
+
+fn propstat(props: &str) -> String {
+    format!("<d:propstat><d:prop>{props}</d:prop><d:status>HTTP/1.1 200 OK</d:status></d:propstat>")
+}
+
+fn multistatus(responses: &str) -> Response<String> {
+    let body = format!(
+        "<?xml version=\"1.0\" encoding=\"utf-8\"?>\n\
+         <d:multistatus xmlns:d=\"DAV:\" xmlns:c=\"urn:ietf:params:xml:ns:caldav\" \
+         xmlns:cs=\"http://calendarserver.org/ns/\">{responses}</d:multistatus>"
+    );
+    response(207, &[("Content-Type", "application/xml; charset=utf-8")], body)
+}
+
+/// The text between each `open` and the next `close`.
+fn between<'a>(text: &'a str, open: &'a str, close: &'a str) -> impl Iterator<Item = &'a str> {
+    text.split(open)
+        .skip(1)
+        .filter_map(move |rest| rest.split_once(close).map(|(inner, _)| inner))
+}
+
+fn escape(value: &str) -> String {
+    value.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;")
+}
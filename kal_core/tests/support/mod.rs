@@ -0,0 +1,117 @@
+//! Fixtures the integration tests share and servers they talk to over
+//! HTTP. Each test crate uses only part of this module.
+#![allow(dead_code)]
+
+pub mod caldav;
+
+use std::sync::Arc;
+
+use chrono::{DateTime, TimeZone, Utc};
+use http_body_util::{BodyExt, Full};
+use hyper::{
+    body::{Bytes, Incoming},
+    server::conn::http1,
+    service::service_fn,
+    Request,
+    Response,
+};
+use hyper_util::rt::TokioIo;
+use kal_core::{
+    domain::{
+        calendar::Calendar,
+        event::Event,
+        repository::CalendarRepository,
+        value_objects::{CalendarId, EventColor, TimeRange},
+    },
+    infrastructure::persistence::Database,
+};
+use tokio::net::TcpListener;
+
+/// `hour` o'clock UTC on the `day`th of March 2025, where the tests' events
+/// fall.
+pub fn utc(day: u32, hour: u32) -> DateTime<Utc> {
+    Utc.with_ymd_and_hms(2025, 3, day, hour, 0, 0).unwrap()
+}
+
+/// From `from` to `to` o'clock UTC on the `day`th of March 2025.
+pub fn range(day: u32, from: u32, to: u32) -> TimeRange {
+    TimeRange::new(utc(day, from), utc(day, to)).unwrap()
+}
+
+/// Stores a calendar named "Work" and returns its id.
+pub async fn calendar(database: &Database) -> CalendarId {
+    calendar_with(database, "Work", None).await
+}
+
+/// Stores a calendar with the given name and description and returns
+/// its id.
+pub async fn calendar_with(
+    database: &Database,
+    name: &str,
+    description: Option<&str>,
+) -> CalendarId {
+    let calendar = Calendar::new(name.into(), description.map(str::to_string)).unwrap();
+    database.calendars().save(&calendar).await.unwrap();
+    *calendar.calendar_id()
+}
+
+/// An unsaved event in `calendar_id` with no description, the default
+/// color and a timed `time_range`.
+pub fn event(calendar_id: CalendarId, title: &str, time_range: TimeRange) -> Event {
+    Event::new(calendar_id, title.into(), None, time_range, EventColor::from(0), false).unwrap()
+}
+
+/// Serves `handler` on a free local port until the test ends and returns
+/// the base URL to reach it.
+pub async fn serve<F>(handler: F) -> String
+where
+    F: Fn(Request<String>) -> Response<String> + Send + Sync + 'static,
+{
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let address = listener.local_addr().unwrap();
+    let handler = Arc::new(handler);
+
+    tokio::spawn(async move {
+        while let Ok((stream, _)) = listener.accept().await {
+            let handler = Arc::clone(&handler);
+
+            tokio::spawn(async move {
+                let service = service_fn(move |request: Request<Incoming>| {
+                    let handler = Arc::clone(&handler);
+                    async move {
+                        let (parts, body) = request.into_parts();
+                        let body = body.collect().await?.to_bytes();
+                        let request =
+                            Request::from_parts(parts, String::from_utf8_lossy(&body).into_owned());
+
+                        Ok::<_, hyper::Error>(handler(request).map(|body| Full::new(Bytes::from(body))))
+                    }
+                });
+
+                let _ = http1::Builder::new()
+                    .serve_connection(TokioIo::new(stream), service)
+                    .await;
+            });
+        }
+    });
+
+    format!("http://{address}")
+}
+
+/// A response with `status`, the given headers and `body`.
+pub fn response(status: u16, headers: &[(&str, &str)], body: impl Into<String>) -> Response<String> {
+    let mut builder = Response::builder().status(status);
+    for (name, value) in headers {
+        builder = builder.header(*name, *value);
+    }
+    builder.body(body.into()).unwrap()
+}
+
+/// The value of a request header, if present and readable.
+pub fn header(request: &Request<String>, name: &str) -> Option<String> {
+    request
+        .headers()
+        .get(name)
+        .and_then(|value| value.to_str().ok())
+        .map(str::to_string)
+}
@@ -0,0 +1,230 @@
+//! Tags on events and series: tagging, renaming and merging across
+//! calendars, and filtering listings by tag.
+
+mod support;
+
+use std::collections::BTreeSet;
+
+use chrono::Duration;
+use serde_json::{json, Value};
+
+use kal_core::{
+    application::{
+        commands::{
+            events::{
+                AddEventTagCommand, AddEventTagHandler, RemoveEventTagCommand,
+                RemoveEventTagHandler,
+            },
+            recurring::{AddRecurringTagCommand, AddRecurringTagHandler},
+            tags::{
+                MergeTagCommand, MergeTagHandler, RenameTagCommand, RenameTagHandler,
+                RetagOutcome,
+            },
+        },
+        error::ApplicationError,
+    },
+    domain::{
+        calendar::Calendar,
+        error::DomainError,
+        recurrence::{RecurrenceRule, RecurringEvent},
+        repository::{
+            CalendarRepository, EventRepository, RecurringEventRepository, TagRepository,
+        },
+        tag::{Tag, TagFilter, TagUsage},
+        value_objects::{CalendarId, EventColor, EventId, Frequency, Subscription},
+    },
+    infrastructure::{persistence::Database, rpc::RpcDispatcher},
+};
+
+use support::{event, range};
+
+fn tag(name: &str) -> Tag {
+    Tag::new(name).unwrap()
+}
+
+fn tags(names: &[&str]) -> BTreeSet<Tag> {
+    names.iter().map(|n| tag(n)).collect()
+}
+
+async fn save_event(
+    database: &Database,
+    calendar_id: CalendarId,
+    title: &str,
+    with: &[&str],
+) -> EventId {
+    let event = event(calendar_id, title, range(10, 9, 10)).with_tags(tags(with));
+    database.events().save(&event).await.unwrap();
+    *event.event_id()
+}
+
+async fn tags_of(database: &Database, id: &EventId) -> Vec<String> {
+    let event = database.events().find_by_id(id).await.unwrap().unwrap();
+    event.tags().iter().map(Tag::to_string).collect()
+}
+
+/// Titles of the events `event.list` returns for `filter`, sorted.
+async fn list_titles(
+    dispatcher: &RpcDispatcher,
+    calendar_id: CalendarId,
+    filter: Value,
+) -> Vec<String> {
+    let mut params = json!({ "calendar_id": calendar_id.to_string() });
+    params.as_object_mut().unwrap().extend(filter.as_object().unwrap().clone());
+    let line = json!({ "jsonrpc": "2.0", "id": 1, "method": "event.list", "params": params });
+
+    let response = dispatcher.handle_line(&line.to_string()).await.unwrap();
+    let response: Value = serde_json::from_str(&response).unwrap();
+    let mut titles: Vec<String> = response["result"]
+        .as_array()
+        .unwrap_or_else(|| panic!("{response}"))
+        .iter()
+        .map(|e| e["title"].as_str().unwrap().to_string())
+        .collect();
+    titles.sort();
+    titles
+}
+
+#[test]
+fn tags_are_normalised_and_filtered() {
+    assert_eq!(tag("  Deep-Work "), tag("deep-work"));
+    assert!(matches!(Tag::new(" "), Err(DomainError::InvalidTag(_))));
+    assert!(matches!(Tag::new("a,b"), Err(DomainError::InvalidTag(_))));
+    assert!(Tag::new("1:1").is_ok());
+
+    let carried = tags(&["billable", "deep-work"]);
+    assert!(TagFilter::default().matches(&carried));
+    let all_of = TagFilter::new(vec![tag("billable"), tag("deep-work")], vec![], vec![]);
+    assert!(all_of.matches(&carried));
+    let all_of = TagFilter::new(vec![tag("billable"), tag("1:1")], vec![], vec![]);
+    assert!(!all_of.matches(&carried));
+    assert!(TagFilter::new(vec![], vec![tag("1:1"), tag("billable")], vec![]).matches(&carried));
+    assert!(!TagFilter::new(vec![], vec![tag("1:1")], vec![]).matches(&carried));
+    assert!(!TagFilter::new(vec![], vec![], vec![tag("deep-work")]).matches(&carried));
+    assert!(!TagFilter::new(vec![], vec![tag("1:1")], vec![]).matches(&BTreeSet::new()));
+}
+
+#[tokio::test]
+async fn commands_tag_events_and_series() {
+    let database = Database::open_in_memory().await.unwrap();
+    let calendar = Calendar::new("Work".into(), None).unwrap();
+    database.calendars().save(&calendar).await.unwrap();
+    let calendar_id = *calendar.calendar_id();
+    let id = save_event(&database, calendar_id, "Review", &[]).await;
+    let series = RecurringEvent::new(
+        calendar_id,
+        "Standup".into(),
+        None,
+        range(10, 9, 10),
+        RecurrenceRule::new(Frequency::Daily, 1, None).unwrap(),
+        EventColor::from(0),
+        false,
+    )
+    .unwrap();
+    database.recurring().save(&series).await.unwrap();
+
+    let add = AddEventTagHandler::new(database.events(), database.calendars());
+    add.handle(AddEventTagCommand::new(id, tag("Billable"))).await.unwrap();
+    add.handle(AddEventTagCommand::new(id, tag("deep-work"))).await.unwrap();
+    // Tagging twice changes nothing
+    add.handle(AddEventTagCommand::new(id, tag("billable"))).await.unwrap();
+    AddRecurringTagHandler::new(database.recurring(), database.calendars())
+        .handle(AddRecurringTagCommand::new(*series.event_id(), tag("billable")))
+        .await
+        .unwrap();
+
+    assert_eq!(tags_of(&database, &id).await, ["billable", "deep-work"]);
+    assert_eq!(
+        database.tags().find_all().await.unwrap(),
+        [TagUsage::new(tag("billable"), 2), TagUsage::new(tag("deep-work"), 1)]
+    );
+
+    let remove = RemoveEventTagHandler::new(database.events(), database.calendars());
+    remove.handle(RemoveEventTagCommand::new(id, tag("deep-work"))).await.unwrap();
+    let again = remove.handle(RemoveEventTagCommand::new(id, tag("deep-work"))).await;
+    assert!(matches!(again, Err(ApplicationError::Domain(DomainError::TagNotFound(_)))));
+    assert_eq!(database.tags().find(&tag("deep-work")).await.unwrap(), None);
+}
+
+#[tokio::test]
+async fn renames_and_merges_leave_subscriptions_alone() {
+    let database = Database::open_in_memory().await.unwrap();
+    let work = Calendar::new("Work".into(), None).unwrap();
+    database.calendars().save(&work).await.unwrap();
+    let feed = Calendar::subscribed(
+        "Holidays".into(),
+        None,
+        Subscription::new("https://example.com/holidays.ics".into(), Duration::hours(1))
+            .unwrap(),
+    )
+    .unwrap();
+    database.calendars().save(&feed).await.unwrap();
+
+    let review = save_event(&database, *work.calendar_id(), "Review", &["client-a"]).await;
+    let both = save_event(&database, *work.calendar_id(), "Sync", &["client-a", "acme"]).await;
+    let remote = save_event(&database, *feed.calendar_id(), "Offsite", &["client-a"]).await;
+
+    let rename = RenameTagHandler::new(
+        database.events(),
+        database.recurring(),
+        database.tags(),
+        database.calendars(),
+    );
+    let outcome =
+        rename.handle(RenameTagCommand::new(tag("client-a"), tag("globex"))).await.unwrap();
+    assert_eq!(outcome, RetagOutcome { updated: 2, skipped: 1 });
+    assert_eq!(tags_of(&database, &review).await, ["globex"]);
+    assert_eq!(tags_of(&database, &both).await, ["acme", "globex"]);
+    // The feed keeps its tags until it changes them
+    assert_eq!(tags_of(&database, &remote).await, ["client-a"]);
+
+    // Renaming onto a tag in use is a merge, and unknown tags are refused
+    let taken = rename.handle(RenameTagCommand::new(tag("globex"), tag("acme"))).await;
+    assert!(matches!(taken, Err(ApplicationError::Validation(_))));
+    let unknown = rename.handle(RenameTagCommand::new(tag("initech"), tag("hooli"))).await;
+    assert!(matches!(unknown, Err(ApplicationError::Domain(DomainError::TagNotFound(_)))));
+
+    let outcome = MergeTagHandler::new(
+        database.events(),
+        database.recurring(),
+        database.tags(),
+        database.calendars(),
+    )
+    .handle(MergeTagCommand::new(tag("globex"), tag("acme")))
+    .await
+    .unwrap();
+    assert_eq!(outcome, RetagOutcome { updated: 2, skipped: 0 });
+    assert_eq!(tags_of(&database, &review).await, ["acme"]);
+    assert_eq!(tags_of(&database, &both).await, ["acme"]);
+    assert_eq!(database.tags().find(&tag("globex")).await.unwrap(), None);
+    let acme = database.tags().find(&tag("acme")).await.unwrap();
+    assert_eq!(acme, Some(TagUsage::new(tag("acme"), 2)));
+}
+
+#[tokio::test]
+async fn listings_filter_by_tag() {
+    let database = Database::open_in_memory().await.unwrap();
+    let calendar = Calendar::new("Work".into(), None).unwrap();
+    database.calendars().save(&calendar).await.unwrap();
+    let calendar_id = *calendar.calendar_id();
+    save_event(&database, calendar_id, "Deep", &["billable", "deep-work"]).await;
+    save_event(&database, calendar_id, "Call", &["billable", "1:1"]).await;
+    save_event(&database, calendar_id, "Lunch", &[]).await;
+
+    let dispatcher = RpcDispatcher::new(database.pool().clone());
+    let list = |filter| list_titles(&dispatcher, calendar_id, filter);
+
+    assert_eq!(list(json!({})).await, ["Call", "Deep", "Lunch"]);
+    assert_eq!(list(json!({ "tags_all": ["billable", "deep-work"] })).await, ["Deep"]);
+    assert_eq!(list(json!({ "tags_any": ["1:1", "Deep-Work"] })).await, ["Call", "Deep"]);
+    assert_eq!(list(json!({ "tags_none": ["billable"] })).await, ["Lunch"]);
+    assert_eq!(
+        list(json!({
+            "from": "2025-03-10T00:00:00Z",
+            "to": "2025-03-11T00:00:00Z",
+            "tags_all": ["billable"],
+            "tags_none": ["1:1"],
+        }))
+        .await,
+        ["Deep"]
+    );
+}
@@ -0,0 +1,210 @@
+//! Tasks (VTODO): their lifecycle through the commands, recurring tasks,
+//! and importing and exporting them.
+
+mod support;
+
+use chrono::Duration;
+
+use kal_core::{
+    application::{
+        commands::tasks::{
+            CompleteTaskCommand, CompleteTaskHandler, CreateTaskCommand, CreateTaskHandler,
+            ImportTasksCommand, ImportTasksHandler, ReopenTaskCommand, ReopenTaskHandler,
+            UpdateTaskCommand, UpdateTaskHandler,
+        },
+        error::ApplicationError,
+    },
+    domain::{
+        calendar::Calendar,
+        error::DomainError,
+        recurrence::RecurrenceRule,
+        repository::{CalendarRepository, TaskRepository},
+        value_objects::{Frequency, Subscription, TaskStatus},
+    },
+    infrastructure::{
+        ical::{Component, IcalMapper},
+        persistence::Database,
+    },
+};
+
+use support::{calendar, utc};
+
+#[tokio::test]
+async fn tasks_move_through_their_statuses() {
+    let database = Database::open_in_memory().await.unwrap();
+    let calendar_id = calendar(&database).await;
+
+    let create = CreateTaskHandler::new(database.tasks(), database.calendars());
+    let report = create
+        .handle(
+            CreateTaskCommand::new(calendar_id, "Report".into(), None, None, Some(utc(14, 17)))
+                .with_priority(Some(1)),
+        )
+        .await
+        .unwrap();
+    create
+        .handle(CreateTaskCommand::new(calendar_id, "Someday".into(), None, None, None))
+        .await
+        .unwrap();
+    create
+        .handle(CreateTaskCommand::new(
+            calendar_id,
+            "Invoice".into(),
+            None,
+            None,
+            Some(utc(11, 9)),
+        ))
+        .await
+        .unwrap();
+
+    // Soonest due first, undated last
+    let tasks = database.tasks().find_by_calendar(&calendar_id).await.unwrap();
+    let titles: Vec<&str> = tasks.iter().map(|t| t.title().as_str()).collect();
+    assert_eq!(titles, ["Invoice", "Report", "Someday"]);
+
+    let update = UpdateTaskHandler::new(database.tasks(), database.calendars());
+    update.handle(UpdateTaskCommand::new(report).with_percent_complete(40)).await.unwrap();
+    let task = database.tasks().find_by_id(&report).await.unwrap().unwrap();
+    assert_eq!((*task.status(), *task.percent_complete()), (TaskStatus::InProcess, 40));
+    assert_eq!(*task.priority(), Some(1));
+    assert!(task.is_overdue(utc(15, 0)));
+
+    let invalid = update.handle(UpdateTaskCommand::new(report).with_priority(Some(10))).await;
+    assert!(matches!(invalid, Err(ApplicationError::Domain(DomainError::InvalidTask(_)))));
+    // Starting after it is due
+    let invalid = update
+        .handle(UpdateTaskCommand::new(report).with_starts_at(Some(utc(15, 0))))
+        .await;
+    assert!(matches!(invalid, Err(ApplicationError::Domain(DomainError::InvalidTimeRange))));
+
+    let completed = CompleteTaskHandler::new(database.tasks(), database.calendars())
+        .handle(CompleteTaskCommand::new(report))
+        .await
+        .unwrap();
+    assert_eq!(*completed.status(), TaskStatus::Completed);
+    assert_eq!(*completed.percent_complete(), 100);
+    assert!(completed.completed_at().is_some());
+    assert!(!completed.is_overdue(utc(15, 0)));
+
+    ReopenTaskHandler::new(database.tasks(), database.calendars())
+        .handle(ReopenTaskCommand::new(report))
+        .await
+        .unwrap();
+    let task = database.tasks().find_by_id(&report).await.unwrap().unwrap();
+    assert_eq!((*task.status(), *task.percent_complete()), (TaskStatus::NeedsAction, 0));
+    assert_eq!(*task.completed_at(), None);
+}
+
+#[tokio::test]
+async fn recurring_tasks_move_on_until_their_rule_runs_out() {
+    let database = Database::open_in_memory().await.unwrap();
+    let calendar_id = calendar(&database).await;
+
+    let rule = RecurrenceRule::new(Frequency::Weekly, 1, Some(utc(18, 0))).unwrap();
+    let id = CreateTaskHandler::new(database.tasks(), database.calendars())
+        .handle(
+            CreateTaskCommand::new(
+                calendar_id,
+                "Timesheet".into(),
+                None,
+                Some(utc(7, 9)),
+                Some(utc(7, 17)),
+            )
+            .with_rule(Some(rule)),
+        )
+        .await
+        .unwrap();
+
+    let complete = CompleteTaskHandler::new(database.tasks(), database.calendars());
+    let task = complete.handle(CompleteTaskCommand::new(id)).await.unwrap();
+    assert_eq!((*task.starts_at(), *task.due_at()), (Some(utc(14, 9)), Some(utc(14, 17))));
+    assert_eq!(*task.status(), TaskStatus::NeedsAction);
+
+    // The next repetition would fall after the rule ends
+    let task = complete.handle(CompleteTaskCommand::new(id)).await.unwrap();
+    assert_eq!(*task.due_at(), Some(utc(14, 17)));
+    assert_eq!(*task.status(), TaskStatus::Completed);
+
+    // A recurring task needs a date to repeat
+    let undated = UpdateTaskHandler::new(database.tasks(), database.calendars())
+        .handle(UpdateTaskCommand::new(id).with_starts_at(None).with_due_at(None))
+        .await;
+    assert!(matches!(undated, Err(ApplicationError::Domain(DomainError::InvalidTask(_)))));
+}
+
+#[tokio::test]
+async fn vtodos_import_and_export() {
+    let ics = "BEGIN:VCALENDAR\r\nVERSION:2.0\r\nPRODID:-//test//EN\r\n\
+               BEGIN:VTODO\r\nUID:report@example.com\r\nSUMMARY:Report\r\n\
+               DTSTART:20250310T090000Z\r\nDURATION:PT8H\r\nPRIORITY:0\r\n\
+               STATUS:X-WAITING\r\nEND:VTODO\r\n\
+               BEGIN:VTODO\r\nUID:done@example.com\r\nSUMMARY:Done\r\n\
+               DUE:20250307T170000Z\r\nSTATUS:COMPLETED\r\nCOMPLETED:20250307T120000Z\r\n\
+               PRIORITY:2\r\nRRULE:FREQ=WEEKLY;UNTIL=20250301T000000Z\r\nEND:VTODO\r\n\
+               BEGIN:VTODO\r\nUID:broken@example.com\r\nSUMMARY:Broken\r\n\
+               PRIORITY:12\r\nEND:VTODO\r\n\
+               BEGIN:VTODO\r\nSUMMARY:Anonymous\r\nEND:VTODO\r\n\
+               BEGIN:VEVENT\r\nUID:event@example.com\r\nSUMMARY:Not a task\r\n\
+               DTSTART:20250310T090000Z\r\nDTEND:20250310T100000Z\r\nEND:VEVENT\r\n\
+               END:VCALENDAR\r\n";
+    let database = Database::open_in_memory().await.unwrap();
+    let calendar_id = calendar(&database).await;
+
+    let (tasks, skipped) =
+        IcalMapper::tasks_to_domain(&Component::parse(ics).unwrap(), calendar_id);
+    let skipped: Vec<&str> = skipped.iter().map(|(uid, _)| uid.as_str()).collect();
+    assert_eq!(skipped, ["broken@example.com", ""]);
+    assert_eq!(tasks.len(), 2);
+
+    let report = &tasks[0];
+    assert_eq!(*report.due_at(), Some(utc(10, 17)));
+    assert_eq!(*report.priority(), None);
+    assert_eq!(*report.status(), TaskStatus::NeedsAction);
+    let done = &tasks[1];
+    assert_eq!(*done.status(), TaskStatus::Completed);
+    assert_eq!(*done.percent_complete(), 100);
+    assert_eq!(*done.completed_at(), Some(utc(7, 12)));
+    assert!(done.rule().is_some());
+
+    let import = ImportTasksHandler::new(database.tasks(), database.calendars());
+    let saved = import.handle(ImportTasksCommand::new(calendar_id, tasks.clone())).await.unwrap();
+    assert_eq!(saved, 2);
+    // Ids come from the UIDs, so a second import updates in place
+    import.handle(ImportTasksCommand::new(calendar_id, tasks.clone())).await.unwrap();
+    let stored = database.tasks().find_by_calendar(&calendar_id).await.unwrap();
+    assert_eq!(stored.len(), 2);
+
+    // Exported and read back with the same fields
+    let exported = IcalMapper::tasks_to_ics(&stored);
+    let (reimported, skipped) =
+        IcalMapper::tasks_to_domain(&Component::parse(&exported).unwrap(), calendar_id);
+    assert!(skipped.is_empty());
+    for (task, again) in stored.iter().zip(&reimported) {
+        assert_eq!(again.task_id(), task.task_id());
+        assert_eq!(again.title(), task.title());
+        assert_eq!((again.starts_at(), again.due_at()), (task.starts_at(), task.due_at()));
+        assert_eq!(again.priority(), task.priority());
+        assert_eq!(again.status(), task.status());
+        assert_eq!(again.completed_at(), task.completed_at());
+        assert_eq!(format!("{:?}", again.rule()), format!("{:?}", task.rule()));
+    }
+
+    // Subscribed calendars are read-only
+    let feed = Calendar::subscribed(
+        "Feed".into(),
+        None,
+        Subscription::new("https://example.com/todo.ics".into(), Duration::hours(1)).unwrap(),
+    )
+    .unwrap();
+    database.calendars().save(&feed).await.unwrap();
+    let refused = import.handle(ImportTasksCommand::new(*feed.calendar_id(), vec![])).await;
+    assert!(matches!(
+        refused,
+        Err(ApplicationError::Domain(DomainError::SubscriptionReadOnly))
+    ));
+
+    // Tasks must belong to the calendar they are imported into
+    let other = calendar(&database).await;
+    let misplaced = import.handle(ImportTasksCommand::new(other, tasks)).await;
+    assert!(matches!(misplaced, Err(ApplicationError::Validation(_))));
+}
@@ -0,0 +1,101 @@
+//! Purging the SQLite trash by age, whatever shape the stored deletion
+//! times take, and what a trashed calendar hides.
+
+use chrono::{TimeZone, Utc};
+use serde_json::{json, Value};
+
+use kal_core::{
+    domain::{
+        calendar::Calendar,
+        event::Event,
+        repository::{CalendarRepository, EventRepository},
+        value_objects::{EventColor, TimeRange},
+    },
+    infrastructure::{persistence::Database, rpc::RpcDispatcher},
+};
+
+#[tokio::test]
+async fn purge_compares_deletion_times_as_instants() {
+    let database = Database::open_in_memory().await.unwrap();
+    let calendar = Calendar::new("Work".into(), None).unwrap();
+    database.calendars().save(&calendar).await.unwrap();
+
+    let range = TimeRange::new(
+        Utc.with_ymd_and_hms(2025, 3, 10, 9, 0, 0).unwrap(),
+        Utc.with_ymd_and_hms(2025, 3, 10, 10, 0, 0).unwrap(),
+    )
+    .unwrap();
+    let mut ids = Vec::new();
+    for title in ["Offset", "Fraction", "Recent"] {
+        let calendar_id = *calendar.calendar_id();
+        let event = Event::new(calendar_id, title.into(), None, range, EventColor::from(0), false)
+            .unwrap();
+        database.events().save(&event).await.unwrap();
+        database.events().trash(event.event_id(), Utc::now()).await.unwrap();
+        ids.push(*event.event_id());
+    }
+
+    // Written by another client: 08:00 UTC in a later-looking offset, and
+    // a time a fraction of a second past the cutoff
+    let cutoff = Utc.with_ymd_and_hms(2025, 3, 1, 8, 30, 0).unwrap();
+    for (id, deleted_at) in ids.iter().zip([
+        "2025-03-01T10:00:00+02:00",
+        "2025-03-01T08:30:00.250Z",
+        "2025-03-01T09:00:00+00:00",
+    ]) {
+        sqlx::query("UPDATE events SET deleted_at = ? WHERE id = ?")
+            .bind(deleted_at)
+            .bind(id.to_string())
+            .execute(database.pool())
+            .await
+            .unwrap();
+    }
+
+    assert_eq!(database.events().purge_trashed(cutoff).await.unwrap(), 1);
+    let trashed = database.events().find_trashed().await.unwrap();
+    let mut left: Vec<&str> = trashed.iter().map(|e| e.title().as_str()).collect();
+    left.sort();
+    assert_eq!(left, ["Fraction", "Recent"]);
+}
+
+#[tokio::test]
+async fn tasks_and_journal_entries_follow_their_calendar_into_the_trash() {
+    let database = Database::open_in_memory().await.unwrap();
+    let dispatcher = RpcDispatcher::new(database.pool().clone());
+    let call = |method: &'static str, params: Value| {
+        let dispatcher = dispatcher.clone();
+        async move { dispatcher.call(method, params).await }
+    };
+
+    let calendar = call("calendar.create", json!({ "name": "Work" })).await.unwrap();
+    let calendar_id = calendar["id"].clone();
+    let task = call("task.create", json!({ "calendar_id": calendar_id, "title": "Budget" }))
+        .await
+        .unwrap();
+    let entry = call(
+        "journal.create",
+        json!({ "calendar_id": calendar_id, "date": "2025-03-10", "title": "Budget" }),
+    )
+    .await
+    .unwrap();
+
+    // Listing a trashed calendar fails like listing a missing one
+    let visible = || async {
+        let counts = [
+            call("task.list", json!({ "calendar_id": calendar_id })).await,
+            call("journal.list", json!({ "calendar_id": calendar_id })).await,
+            call("journal.search", json!({ "query": "budget" })).await,
+        ]
+        .map(|listed| listed.map_or(0, |list| list.as_array().unwrap().len()));
+        let task = call("task.get", json!({ "id": task["id"] })).await.is_ok();
+        let entry = call("journal.get", json!({ "id": entry["id"] })).await.is_ok();
+        (counts, task, entry)
+    };
+    assert_eq!(visible().await, ([1, 1, 1], true, true));
+
+    call("calendar.delete", json!({ "id": calendar_id })).await.unwrap();
+    assert_eq!(visible().await, ([0, 0, 0], false, false));
+
+    call("trash.restore", json!({ "id": calendar_id })).await.unwrap();
+    assert_eq!(visible().await, ([1, 1, 1], true, true));
+}
@@ -0,0 +1,179 @@
+//! Repositories from one unit of work share a transaction: their writes
+//! land together on commit and vanish together otherwise. Only work that
+//! may write takes the write lock.
+
+use std::time::Duration;
+
+use chrono::{TimeZone, Utc};
+use serde_json::json;
+use tokio::time::timeout;
+
+use kal_core::{
+    application::{
+        commands::tasks::{ImportTasksCommand, ImportTasksHandler},
+        error::ApplicationError,
+    },
+    domain::{
+        calendar::Calendar,
+        event::Event,
+        recurrence::{RecurrenceRule, RecurringEvent},
+        repository::{
+            CalendarRepository, EventRepository, RecurringEventRepository, RepositoryError,
+            TaskRepository,
+        },
+        tag::Tag,
+        task::Task,
+        value_objects::{CalendarId, EventColor, Frequency, TimeRange},
+    },
+    infrastructure::{api::ApiRoutes, persistence::Database, rpc::RpcDispatcher},
+};
+
+fn time_range() -> TimeRange {
+    TimeRange::new(
+        Utc.with_ymd_and_hms(2025, 3, 10, 9, 0, 0).unwrap(),
+        Utc.with_ymd_and_hms(2025, 3, 10, 10, 0, 0).unwrap(),
+    )
+    .unwrap()
+}
+
+fn event(calendar_id: CalendarId) -> Event {
+    Event::new(calendar_id, "Meeting".into(), None, time_range(), EventColor::from(0), false)
+        .unwrap()
+}
+
+fn series(calendar_id: CalendarId) -> RecurringEvent {
+    let rule = RecurrenceRule::new(Frequency::Daily, 1, None).unwrap();
+    RecurringEvent::new(
+        calendar_id,
+        "Standup".into(),
+        None,
+        time_range(),
+        rule,
+        EventColor::from(0),
+        false,
+    )
+    .unwrap()
+}
+
+#[tokio::test]
+async fn commit_keeps_every_write() {
+    let database = Database::open_in_memory().await.unwrap();
+    let calendar = Calendar::new("Work".into(), None).unwrap();
+    let meeting = event(*calendar.calendar_id());
+    let standup = series(*calendar.calendar_id());
+
+    let uow = database.begin().await.unwrap();
+    uow.calendars().save(&calendar).await.unwrap();
+    uow.events().save(&meeting).await.unwrap();
+    uow.recurring().save(&standup).await.unwrap();
+
+    // The unit's repositories see each other's writes before commit
+    assert!(uow.events().find_by_id(meeting.event_id()).await.unwrap().is_some());
+    uow.commit().await.unwrap();
+
+    assert!(database.calendars().find_by_id(calendar.calendar_id()).await.unwrap().is_some());
+    assert!(database.events().find_by_id(meeting.event_id()).await.unwrap().is_some());
+    assert!(database.recurring().find_by_id(standup.event_id()).await.is_ok());
+}
+
+#[tokio::test]
+async fn rollback_and_drop_discard_every_write() {
+    let database = Database::open_in_memory().await.unwrap();
+    let calendar = Calendar::new("Work".into(), None).unwrap();
+    let meeting = event(*calendar.calendar_id());
+
+    let uow = database.begin().await.unwrap();
+    uow.calendars().save(&calendar).await.unwrap();
+    uow.events().save(&meeting).await.unwrap();
+    uow.rollback().await.unwrap();
+
+    assert!(database.calendars().find_by_id(calendar.calendar_id()).await.unwrap().is_none());
+    assert!(database.events().find_by_id(meeting.event_id()).await.unwrap().is_none());
+
+    {
+        let uow = database.begin().await.unwrap();
+        uow.calendars().save(&calendar).await.unwrap();
+    }
+
+    assert!(database.calendars().find_by_id(calendar.calendar_id()).await.unwrap().is_none());
+}
+
+#[tokio::test]
+async fn nested_saves_roll_back_with_the_unit() {
+    let database = Database::open_in_memory().await.unwrap();
+    let calendar = Calendar::new("Work".into(), None).unwrap();
+    database.calendars().save(&calendar).await.unwrap();
+
+    // Saving an event writes its tags in a transaction of its own, which
+    // becomes a savepoint inside the unit
+    let mut meeting = event(*calendar.calendar_id());
+    meeting.add_tag(Tag::new("project").unwrap());
+
+    let uow = database.begin().await.unwrap();
+    uow.events().save(&meeting).await.unwrap();
+    assert_eq!(uow.events().find_by_tag(&Tag::new("project").unwrap()).await.unwrap().len(), 1);
+    drop(uow);
+
+    assert!(database.events().find_by_tag(&Tag::new("project").unwrap()).await.unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn failed_command_leaves_nothing_behind() {
+    let database = Database::open_in_memory().await.unwrap();
+    let calendar = Calendar::new("Work".into(), None).unwrap();
+    database.calendars().save(&calendar).await.unwrap();
+
+    let calendar_id = *calendar.calendar_id();
+    let tasks = vec![
+        Task::new(calendar_id, "First".into(), None, None, None).unwrap(),
+        Task::new(CalendarId::new(), "Stray".into(), None, None, None).unwrap(),
+    ];
+
+    let uow = database.begin().await.unwrap();
+    let result = ImportTasksHandler::new(uow.tasks(), uow.calendars())
+        .handle(ImportTasksCommand::new(calendar_id, tasks))
+        .await;
+    assert!(matches!(result, Err(ApplicationError::Validation(_))));
+    drop(uow);
+
+    assert!(database.tasks().find_by_calendar(&calendar_id).await.unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn repositories_outliving_the_unit_fail() {
+    let database = Database::open_in_memory().await.unwrap();
+
+    let uow = database.begin().await.unwrap();
+    let calendars = uow.calendars();
+    uow.commit().await.unwrap();
+
+    assert!(matches!(
+        calendars.find_all_active().await,
+        Err(RepositoryError::DatabaseError(_))
+    ));
+}
+
+#[tokio::test]
+async fn reads_do_not_wait_for_the_write_lock() {
+    let dir = std::env::temp_dir().join(format!("kal-{}", uuid::Uuid::new_v4()));
+    let database = Database::open(dir.join("kal.db")).await.unwrap();
+    let dispatcher = RpcDispatcher::new(database.pool().clone());
+    let routes = ApiRoutes::new(database.pool().clone());
+    let writer = database.begin().await.unwrap();
+    let patience = Duration::from_millis(500);
+
+    let listed = timeout(patience, dispatcher.call("calendar.list", json!(null))).await;
+    assert!(listed.unwrap().is_ok());
+    let listed = timeout(patience, routes.handle("GET", "/calendars", "", "")).await;
+    assert!(listed.unwrap().is_ok());
+
+    // Writes queue behind the open unit of work
+    let created = dispatcher.call("calendar.create", json!({ "name": "Work" }));
+    assert!(timeout(patience, created).await.is_err());
+    let created = routes.handle("POST", "/calendars", "", r#"{"name":"Work"}"#);
+    assert!(timeout(patience, created).await.is_err());
+
+    writer.rollback().await.unwrap();
+    database.pool().close().await;
+    std::fs::remove_dir_all(dir).unwrap();
+}
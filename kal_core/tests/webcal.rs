@@ -0,0 +1,256 @@
+//! Subscribed calendars refreshed from a feed served on a local port.
+
+mod support;
+
+use std::sync::{Arc, Mutex};
+
+use chrono::{DateTime, Duration, TimeZone, Utc};
+
+use kal_core::{
+    application::{
+        commands::{
+            calendars::{SubscribeCalendarCommand, SubscribeCalendarHandler},
+            events::{CreateEventCommand, CreateEventHandler},
+        },
+        error::ApplicationError,
+    },
+    domain::{
+        error::DomainError,
+        repository::{CalendarRepository, EventRepository, SubscriptionRepository},
+        value_objects::{CalendarId, EventColor, TimeRange},
+    },
+    infrastructure::{
+        persistence::Database,
+        webcal::{RefreshOutcome, SubscriptionRefresher, WebcalClient},
+    },
+};
+
+use support::{header, response, serve};
+
+/// What the feed serves and the validators it was asked with.
+#[derive(Debug, Default)]
+struct Feed {
+    body: String,
+    etag: Option<String>,
+    last_modified: Option<String>,
+    /// (If-None-Match, If-Modified-Since) of every request.
+    conditions: Vec<(Option<String>, Option<String>)>,
+}
+
+impl Feed {
+    async fn start(body: String) -> (Arc<Mutex<Feed>>, String) {
+        let feed = Arc::new(Mutex::new(Feed { body, ..Feed::default() }));
+        let server = Arc::clone(&feed);
+
+        let url = serve(move |request| {
+            let mut feed = server.lock().unwrap();
+            let if_none_match = header(&request, "if-none-match");
+            let if_modified_since = header(&request, "if-modified-since");
+            feed.conditions.push((if_none_match.clone(), if_modified_since.clone()));
+
+            // An ETag, when the feed has one, takes precedence (RFC 9110 13.2.2)
+            let unchanged = match (&feed.etag, &feed.last_modified) {
+                (Some(etag), _) => if_none_match.as_ref() == Some(etag),
+                (None, Some(modified)) => if_modified_since.as_ref() == Some(modified),
+                (None, None) => false,
+            };
+            if unchanged {
+                return response(304, &[], "");
+            }
+
+            let mut headers = vec![("Content-Type", "text/calendar")];
+            if let Some(etag) = &feed.etag {
+                headers.push(("ETag", etag));
+            }
+            if let Some(modified) = &feed.last_modified {
+                headers.push(("Last-Modified", modified));
+            }
+            response(200, &headers, feed.body.clone())
+        })
+        .await;
+
+        (feed, format!("{url}/holidays.ics"))
+    }
+}
+
+fn vevent(uid: &str, summary: &str, day: u32) -> String {
+    format!(
+        "BEGIN:VEVENT\r\nUID:{uid}\r\nSUMMARY:{summary}\r\n\
+         DTSTART;VALUE=DATE:202512{day:02}\r\nDTEND;VALUE=DATE:202512{:02}\r\nEND:VEVENT\r\n",
+        day + 1,
+    )
+}
+
+fn ics(events: &[String]) -> String {
+    format!(
+        "BEGIN:VCALENDAR\r\nVERSION:2.0\r\nPRODID:-//test//EN\r\n{}END:VCALENDAR\r\n",
+        events.concat()
+    )
+}
+
+fn now() -> DateTime<Utc> {
+    Utc.with_ymd_and_hms(2025, 11, 1, 8, 0, 0).unwrap()
+}
+
+async fn subscribe(database: &Database, url: &str) -> CalendarId {
+    SubscribeCalendarHandler::new(database.calendars())
+        .handle(SubscribeCalendarCommand::new(
+            "Holidays".into(),
+            None,
+            url.into(),
+            Duration::hours(6),
+        ))
+        .await
+        .unwrap()
+}
+
+fn refresher(
+    database: &Database,
+) -> SubscriptionRefresher<impl CalendarRepository, impl SubscriptionRepository> {
+    SubscriptionRefresher::new(WebcalClient::new(), database.calendars(), database.subscriptions())
+}
+
+async fn titles(database: &Database, calendar_id: &CalendarId) -> Vec<String> {
+    let mut titles: Vec<String> = database
+        .events()
+        .find_by_calendar(calendar_id)
+        .await
+        .unwrap()
+        .iter()
+        .map(|event| event.title().clone())
+        .collect();
+    titles.sort();
+    titles
+}
+
+#[tokio::test]
+async fn refresh_replaces_the_calendar_and_drops_vanished_events() {
+    let (feed, url) = Feed::start(ics(&[
+        vevent("christmas@example.com", "Christmas", 25),
+        vevent("boxing@example.com", "Boxing Day", 26),
+    ]))
+    .await;
+    let database = Database::open_in_memory().await.unwrap();
+    let calendar_id = subscribe(&database, &url).await;
+
+    let outcome = refresher(&database).refresh(&calendar_id, now()).await.unwrap();
+    assert!(matches!(
+        outcome,
+        RefreshOutcome::Replaced { objects: 2, ref skipped } if skipped.is_empty()
+    ));
+    assert_eq!(titles(&database, &calendar_id).await, ["Boxing Day", "Christmas"]);
+
+    // The feed drops one event and renames the other
+    feed.lock().unwrap().body = ics(&[vevent("christmas@example.com", "Christmas Day", 25)]);
+    let outcome = refresher(&database).refresh(&calendar_id, now()).await.unwrap();
+    assert!(matches!(outcome, RefreshOutcome::Replaced { objects: 1, .. }));
+    assert_eq!(titles(&database, &calendar_id).await, ["Christmas Day"]);
+
+    // Events the feed cannot describe are reported, the rest kept
+    feed.lock().unwrap().body = ics(&[
+        vevent("christmas@example.com", "Christmas Day", 25),
+        "BEGIN:VEVENT\r\nSUMMARY:No UID\r\nDTSTART:20251231T230000Z\r\nEND:VEVENT\r\n".into(),
+    ]);
+    let outcome = refresher(&database).refresh(&calendar_id, now()).await.unwrap();
+    assert!(matches!(
+        outcome,
+        RefreshOutcome::Replaced { objects: 1, ref skipped } if skipped.len() == 1
+    ));
+    assert_eq!(titles(&database, &calendar_id).await, ["Christmas Day"]);
+}
+
+#[tokio::test]
+async fn unchanged_feeds_answer_304_to_the_stored_etag() {
+    let (feed, url) = Feed::start(ics(&[vevent("christmas@example.com", "Christmas", 25)])).await;
+    feed.lock().unwrap().etag = Some("\"v1\"".into());
+    let database = Database::open_in_memory().await.unwrap();
+    let calendar_id = subscribe(&database, &url).await;
+
+    refresher(&database).refresh(&calendar_id, now()).await.unwrap();
+    let later = now() + Duration::hours(7);
+    let outcome = refresher(&database).refresh(&calendar_id, later).await.unwrap();
+    assert!(matches!(outcome, RefreshOutcome::NotModified));
+    assert_eq!(titles(&database, &calendar_id).await, ["Christmas"]);
+
+    // The fetch still counts, so the feed is not due again yet
+    let calendar = database.calendars().find_by_id(&calendar_id).await.unwrap().unwrap();
+    let subscription = calendar.subscription().clone().unwrap();
+    assert_eq!(*subscription.last_fetched_at(), Some(later));
+    assert_eq!(subscription.etag().as_deref(), Some("\"v1\""));
+    assert!(refresher(&database).refresh_due(later).await.unwrap().is_empty());
+
+    // A new ETag brings the new content
+    {
+        let mut feed = feed.lock().unwrap();
+        feed.body = ics(&[vevent("party@example.com", "Office party", 19)]);
+        feed.etag = Some("\"v2\"".into());
+    }
+    let due = refresher(&database).refresh_due(later + Duration::hours(6)).await.unwrap();
+    assert_eq!(due.len(), 1);
+    assert!(matches!(due[0].1, Ok(RefreshOutcome::Replaced { objects: 1, .. })));
+    assert_eq!(titles(&database, &calendar_id).await, ["Office party"]);
+
+    let sent: Vec<Option<String>> =
+        feed.lock().unwrap().conditions.iter().map(|c| c.0.clone()).collect();
+    assert_eq!(sent, [None, Some("\"v1\"".into()), Some("\"v1\"".into())]);
+}
+
+#[tokio::test]
+async fn feeds_without_an_etag_answer_304_to_the_stored_last_modified() {
+    let (feed, url) = Feed::start(ics(&[vevent("christmas@example.com", "Christmas", 25)])).await;
+    let first = "Sat, 01 Nov 2025 06:00:00 GMT";
+    feed.lock().unwrap().last_modified = Some(first.into());
+    let database = Database::open_in_memory().await.unwrap();
+    let calendar_id = subscribe(&database, &url).await;
+
+    refresher(&database).refresh(&calendar_id, now()).await.unwrap();
+    let outcome = refresher(&database).refresh(&calendar_id, now()).await.unwrap();
+    assert!(matches!(outcome, RefreshOutcome::NotModified));
+
+    // The validator survives a reload of the calendar
+    let calendar = database.calendars().find_by_id(&calendar_id).await.unwrap().unwrap();
+    assert_eq!(calendar.subscription().as_ref().unwrap().last_modified().as_deref(), Some(first));
+
+    {
+        let mut feed = feed.lock().unwrap();
+        feed.body = ics(&[]);
+        feed.last_modified = Some("Sun, 02 Nov 2025 06:00:00 GMT".into());
+    }
+    let outcome = refresher(&database).refresh(&calendar_id, now()).await.unwrap();
+    assert!(matches!(outcome, RefreshOutcome::Replaced { objects: 0, .. }));
+    assert!(titles(&database, &calendar_id).await.is_empty());
+
+    let sent: Vec<Option<String>> =
+        feed.lock().unwrap().conditions.iter().map(|c| c.1.clone()).collect();
+    assert_eq!(sent, [None, Some(first.into()), Some(first.into())]);
+}
+
+#[tokio::test]
+async fn subscribed_calendars_refuse_edits() {
+    let (_feed, url) = Feed::start(ics(&[vevent("christmas@example.com", "Christmas", 25)])).await;
+    let database = Database::open_in_memory().await.unwrap();
+    let calendar_id = subscribe(&database, &url).await;
+    refresher(&database).refresh(&calendar_id, now()).await.unwrap();
+
+    let range = TimeRange::new(
+        Utc.with_ymd_and_hms(2025, 12, 24, 18, 0, 0).unwrap(),
+        Utc.with_ymd_and_hms(2025, 12, 24, 22, 0, 0).unwrap(),
+    )
+    .unwrap();
+    let result = CreateEventHandler::new(database.events(), database.calendars())
+        .handle(CreateEventCommand::new(
+            calendar_id,
+            "Dinner".into(),
+            None,
+            range,
+            EventColor::from(0),
+            false,
+        ))
+        .await;
+
+    assert!(
+        matches!(result, Err(ApplicationError::Domain(DomainError::SubscriptionReadOnly))),
+        "{result:?}"
+    );
+    assert_eq!(titles(&database, &calendar_id).await, ["Christmas"]);
+}
@@ -0,0 +1,113 @@
+CREATE TABLE sync_collections (
+    calendar_id TEXT PRIMARY KEY,
+    remote_href TEXT NOT NULL,
+    sync_token TEXT,
+    last_synced_at TEXT,
+    FOREIGN KEY (calendar_id)
+        REFERENCES calendars(id)
+        ON DELETE CASCADE
+);
+
+/* One row per event or recurring series in a synced calendar */
+CREATE TABLE sync_items (
+    item_id TEXT PRIMARY KEY,
+    calendar_id TEXT NOT NULL,
+    kind TEXT NOT NULL,
+    remote_href TEXT,
+    remote_uid TEXT,
+    etag TEXT,
+    last_synced_hash TEXT,
+    is_dirty INTEGER NOT NULL DEFAULT 1,
+    last_synced_at TEXT,
+    FOREIGN KEY (calendar_id)
+        REFERENCES sync_collections(calendar_id)
+        ON DELETE CASCADE,
+    CHECK (kind IN ('EVENT', 'RECURRENCE'))
+);
+
+/* Local deletions of items the server knows about, until pushed */
+CREATE TABLE sync_tombstones (
+    item_id TEXT PRIMARY KEY,
+    calendar_id TEXT NOT NULL,
+    kind TEXT NOT NULL,
+    remote_href TEXT NOT NULL,
+    etag TEXT,
+    deleted_at TEXT NOT NULL,
+    FOREIGN KEY (calendar_id)
+        REFERENCES sync_collections(calendar_id)
+        ON DELETE CASCADE
+);
+
+CREATE UNIQUE INDEX idx_sync_items_href
+    ON sync_items (calendar_id, remote_href);
+
+CREATE INDEX idx_sync_items_dirty
+    ON sync_items (calendar_id, is_dirty);
+
+CREATE INDEX idx_sync_tombstones_calendar
+    ON sync_tombstones (calendar_id);
+
+/* Dirty tracking: any write to a synced calendar marks its item dirty */
+CREATE TRIGGER trg_events_sync_insert
+AFTER INSERT ON events
+WHEN EXISTS (SELECT 1 FROM sync_collections WHERE calendar_id = NEW.calendar_id)
+BEGIN
+    INSERT INTO sync_items (item_id, calendar_id, kind, is_dirty)
+    VALUES (NEW.id, NEW.calendar_id, 'EVENT', 1)
+    ON CONFLICT(item_id) DO UPDATE SET is_dirty = 1;
+END;
+
+CREATE TRIGGER trg_events_sync_update
+AFTER UPDATE ON events
+WHEN EXISTS (SELECT 1 FROM sync_collections WHERE calendar_id = NEW.calendar_id)
+BEGIN
+    INSERT INTO sync_items (item_id, calendar_id, kind, is_dirty)
+    VALUES (NEW.id, NEW.calendar_id, 'EVENT', 1)
+    ON CONFLICT(item_id) DO UPDATE SET is_dirty = 1;
+END;
+
+CREATE TRIGGER trg_events_sync_delete
+AFTER DELETE ON events
+BEGIN
+    INSERT OR REPLACE INTO sync_tombstones (
+        item_id, calendar_id, kind, remote_href, etag, deleted_at
+    )
+    SELECT item_id, calendar_id, kind, remote_href, etag,
+           strftime('%Y-%m-%dT%H:%M:%fZ', 'now')
+    FROM sync_items
+    WHERE item_id = OLD.id AND remote_href IS NOT NULL;
+
+    DELETE FROM sync_items WHERE item_id = OLD.id;
+END;
+
+CREATE TRIGGER trg_recurrences_sync_insert
+AFTER INSERT ON recurrences
+WHEN EXISTS (SELECT 1 FROM sync_collections WHERE calendar_id = NEW.calendar_id)
+BEGIN
+    INSERT INTO sync_items (item_id, calendar_id, kind, is_dirty)
+    VALUES (NEW.id, NEW.calendar_id, 'RECURRENCE', 1)
+    ON CONFLICT(item_id) DO UPDATE SET is_dirty = 1;
+END;
+
+CREATE TRIGGER trg_recurrences_sync_update
+AFTER UPDATE ON recurrences
+WHEN EXISTS (SELECT 1 FROM sync_collections WHERE calendar_id = NEW.calendar_id)
+BEGIN
+    INSERT INTO sync_items (item_id, calendar_id, kind, is_dirty)
+    VALUES (NEW.id, NEW.calendar_id, 'RECURRENCE', 1)
+    ON CONFLICT(item_id) DO UPDATE SET is_dirty = 1;
+END;
+
+CREATE TRIGGER trg_recurrences_sync_delete
+AFTER DELETE ON recurrences
+BEGIN
+    INSERT OR REPLACE INTO sync_tombstones (
+        item_id, calendar_id, kind, remote_href, etag, deleted_at
+    )
+    SELECT item_id, calendar_id, kind, remote_href, etag,
+           strftime('%Y-%m-%dT%H:%M:%fZ', 'now')
+    FROM sync_items
+    WHERE item_id = OLD.id AND remote_href IS NOT NULL;
+
+    DELETE FROM sync_items WHERE item_id = OLD.id;
+END;
@@ -0,0 +1,9 @@
+/* Read-only calendars mirrored from a webcal/ICS feed */
+ALTER TABLE calendars ADD COLUMN subscription_url TEXT;
+ALTER TABLE calendars ADD COLUMN refresh_interval INTEGER;
+ALTER TABLE calendars ADD COLUMN last_fetched_at TEXT;
+ALTER TABLE calendars ADD COLUMN fetch_etag TEXT;
+
+CREATE INDEX idx_calendars_subscription
+    ON calendars (subscription_url)
+    WHERE subscription_url IS NOT NULL;
@@ -0,0 +1,31 @@
+/* VALARMs of events and recurring series. Ids are unique per owner */
+CREATE TABLE reminders (
+    event_id TEXT NOT NULL,
+    id TEXT NOT NULL,
+    position INTEGER NOT NULL,
+    trigger_offset INTEGER,
+    trigger_at TEXT,
+    action TEXT NOT NULL,
+    action_target TEXT,
+    description TEXT,
+    repeat_count INTEGER NOT NULL DEFAULT 0,
+    repeat_interval INTEGER,
+    PRIMARY KEY (event_id, id),
+    CHECK ((trigger_offset IS NULL) <> (trigger_at IS NULL)),
+    CHECK (action IN ('DISPLAY', 'EMAIL', 'COMMAND')),
+    CHECK (repeat_count >= 0),
+    CHECK (repeat_count = 0 OR repeat_interval > 0)
+);
+
+/* Owners live in two tables, so cascade by hand */
+CREATE TRIGGER trg_events_reminders_delete
+AFTER DELETE ON events
+BEGIN
+    DELETE FROM reminders WHERE event_id = OLD.id;
+END;
+
+CREATE TRIGGER trg_recurrences_reminders_delete
+AFTER DELETE ON recurrences
+BEGIN
+    DELETE FROM reminders WHERE event_id = OLD.id;
+END;
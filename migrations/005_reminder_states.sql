@@ -0,0 +1,24 @@
+/* Delivery state per reminder instance; instances of a series are told
+   apart by the occurrence's original start */
+CREATE TABLE reminder_states (
+    event_id TEXT NOT NULL,
+    original_starts_at TEXT NOT NULL,
+    reminder_id TEXT NOT NULL,
+    fired_count INTEGER NOT NULL DEFAULT 0,
+    last_fired_at TEXT,
+    snoozed_until TEXT,
+    PRIMARY KEY (event_id, original_starts_at, reminder_id),
+    CHECK (fired_count >= 0)
+);
+
+CREATE TRIGGER trg_events_reminder_states_delete
+AFTER DELETE ON events
+BEGIN
+    DELETE FROM reminder_states WHERE event_id = OLD.id;
+END;
+
+CREATE TRIGGER trg_recurrences_reminder_states_delete
+AFTER DELETE ON recurrences
+BEGIN
+    DELETE FROM reminder_states WHERE event_id = OLD.id;
+END;
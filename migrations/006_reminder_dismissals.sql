@@ -0,0 +1,2 @@
+/* A dismissed reminder instance never fires again */
+ALTER TABLE reminder_states ADD COLUMN dismissed_at TEXT;
@@ -0,0 +1,35 @@
+/* Invited people, in the order they were added; emails compare
+   case-insensitively */
+CREATE TABLE attendees (
+    event_id TEXT NOT NULL,
+    email TEXT NOT NULL COLLATE NOCASE,
+    position INTEGER NOT NULL,
+    name TEXT,
+    role TEXT NOT NULL DEFAULT 'REQ-PARTICIPANT',
+    status TEXT NOT NULL DEFAULT 'NEEDS-ACTION',
+    rsvp INTEGER NOT NULL DEFAULT 0,
+    PRIMARY KEY (event_id, email),
+    CHECK (role IN ('CHAIR', 'REQ-PARTICIPANT', 'OPT-PARTICIPANT', 'NON-PARTICIPANT')),
+    CHECK (status IN ('NEEDS-ACTION', 'ACCEPTED', 'DECLINED', 'TENTATIVE', 'DELEGATED')),
+    CHECK (rsvp IN (0, 1))
+);
+
+CREATE TABLE organizers (
+    event_id TEXT PRIMARY KEY,
+    email TEXT NOT NULL,
+    name TEXT
+);
+
+CREATE TRIGGER trg_events_participants_delete
+AFTER DELETE ON events
+BEGIN
+    DELETE FROM attendees WHERE event_id = OLD.id;
+    DELETE FROM organizers WHERE event_id = OLD.id;
+END;
+
+CREATE TRIGGER trg_recurrences_participants_delete
+AFTER DELETE ON recurrences
+BEGIN
+    DELETE FROM attendees WHERE event_id = OLD.id;
+    DELETE FROM organizers WHERE event_id = OLD.id;
+END;
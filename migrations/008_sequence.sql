@@ -0,0 +1,3 @@
+/* iTIP SEQUENCE, bumped when invitations already sent go stale */
+ALTER TABLE events ADD COLUMN sequence INTEGER NOT NULL DEFAULT 0;
+ALTER TABLE recurrences ADD COLUMN sequence INTEGER NOT NULL DEFAULT 0;
@@ -0,0 +1,18 @@
+/* Where an event takes place. Coordinates are stored in decimal
+   degrees and are either both set or both missing */
+ALTER TABLE events ADD COLUMN location TEXT;
+ALTER TABLE events ADD COLUMN url TEXT;
+ALTER TABLE events ADD COLUMN geo_latitude REAL
+    CHECK (geo_latitude BETWEEN -90 AND 90);
+ALTER TABLE events ADD COLUMN geo_longitude REAL
+    CHECK (geo_longitude BETWEEN -180 AND 180);
+
+ALTER TABLE recurrences ADD COLUMN location TEXT;
+ALTER TABLE recurrences ADD COLUMN url TEXT;
+ALTER TABLE recurrences ADD COLUMN geo_latitude REAL
+    CHECK (geo_latitude BETWEEN -90 AND 90);
+ALTER TABLE recurrences ADD COLUMN geo_longitude REAL
+    CHECK (geo_longitude BETWEEN -180 AND 180);
+
+/* Per-occurrence location overrides */
+ALTER TABLE recurrence_exceptions ADD COLUMN location TEXT;
@@ -0,0 +1,38 @@
+/* Tags shared by events and recurring series. Names are stored
+   lowercased; a tag row lives only while something carries it */
+CREATE TABLE tags (
+    id INTEGER PRIMARY KEY,
+    name TEXT NOT NULL UNIQUE
+);
+
+CREATE TABLE event_tags (
+    event_id TEXT NOT NULL,
+    tag_id INTEGER NOT NULL,
+    PRIMARY KEY (event_id, tag_id),
+    FOREIGN KEY (tag_id)
+        REFERENCES tags(id)
+        ON DELETE CASCADE
+);
+
+CREATE INDEX idx_event_tags_tag
+    ON event_tags (tag_id);
+
+CREATE TRIGGER trg_event_tags_prune
+AFTER DELETE ON event_tags
+WHEN NOT EXISTS (SELECT 1 FROM event_tags WHERE tag_id = OLD.tag_id)
+BEGIN
+    DELETE FROM tags WHERE id = OLD.tag_id;
+END;
+
+/* Owners live in two tables, so cascade by hand */
+CREATE TRIGGER trg_events_tags_delete
+AFTER DELETE ON events
+BEGIN
+    DELETE FROM event_tags WHERE event_id = OLD.id;
+END;
+
+CREATE TRIGGER trg_recurrences_tags_delete
+AFTER DELETE ON recurrences
+BEGIN
+    DELETE FROM event_tags WHERE event_id = OLD.id;
+END;
@@ -0,0 +1,38 @@
+/* iCalendar STATUS and TRANSP replace the cancelled flag. Cancelled
+   rows keep their state; everything else starts out confirmed and
+   opaque. Occurrence exceptions keep their own flag, since cancelling
+   one occurrence removes it rather than changing its status */
+ALTER TABLE events ADD COLUMN status TEXT NOT NULL DEFAULT 'CONFIRMED'
+    CHECK (status IN ('TENTATIVE', 'CONFIRMED', 'CANCELLED'));
+ALTER TABLE events ADD COLUMN transparency TEXT NOT NULL DEFAULT 'OPAQUE'
+    CHECK (transparency IN ('OPAQUE', 'TRANSPARENT'));
+
+ALTER TABLE recurrences ADD COLUMN status TEXT NOT NULL DEFAULT 'CONFIRMED'
+    CHECK (status IN ('TENTATIVE', 'CONFIRMED', 'CANCELLED'));
+ALTER TABLE recurrences ADD COLUMN transparency TEXT NOT NULL DEFAULT 'OPAQUE'
+    CHECK (transparency IN ('OPAQUE', 'TRANSPARENT'));
+
+UPDATE events SET status = 'CANCELLED' WHERE is_cancelled != 0;
+UPDATE recurrences SET status = 'CANCELLED' WHERE is_cancelled != 0;
+
+DROP INDEX idx_events_overlap;
+DROP INDEX idx_events_active;
+DROP INDEX idx_events_calendar_dates;
+DROP INDEX idx_recurrences_active;
+
+ALTER TABLE events DROP COLUMN is_cancelled;
+ALTER TABLE recurrences DROP COLUMN is_cancelled;
+
+CREATE INDEX idx_events_overlap
+    ON events (calendar_id, ends_at, starts_at)
+    WHERE status != 'CANCELLED';
+
+CREATE INDEX idx_events_active
+    ON events (calendar_id, status, starts_at);
+
+CREATE INDEX idx_events_calendar_dates
+    ON events (calendar_id, starts_at, ends_at)
+    WHERE status != 'CANCELLED';
+
+CREATE INDEX idx_recurrences_active
+    ON recurrences (status, until);
@@ -0,0 +1,29 @@
+/* To-do items (VTODO). A task recurs when it has a frequency */
+CREATE TABLE tasks (
+    id TEXT PRIMARY KEY,
+    calendar_id TEXT NOT NULL,
+    title TEXT NOT NULL,
+    description TEXT,
+    starts_at TEXT,
+    due_at TEXT,
+    priority INTEGER,
+    percent_complete INTEGER NOT NULL DEFAULT 0,
+    status TEXT NOT NULL DEFAULT 'NEEDS-ACTION',
+    completed_at TEXT,
+    frequency TEXT,
+    interval INTEGER,
+    until TEXT,
+    created_at TEXT NOT NULL,
+    updated_at TEXT NOT NULL,
+    FOREIGN KEY (calendar_id)
+        REFERENCES calendars(id)
+        ON DELETE CASCADE,
+    CHECK (starts_at IS NULL OR due_at IS NULL OR starts_at <= due_at),
+    CHECK (priority IS NULL OR priority BETWEEN 1 AND 9),
+    CHECK (percent_complete BETWEEN 0 AND 100),
+    CHECK (status IN ('NEEDS-ACTION', 'IN-PROCESS', 'COMPLETED', 'CANCELLED')),
+    CHECK (frequency IS NULL OR interval > 0)
+);
+
+CREATE INDEX idx_tasks_calendar_due
+    ON tasks (calendar_id, due_at);
@@ -0,0 +1,73 @@
+/* Notes and logs that belong to a date (VJOURNAL). An entry may point
+   at an event or series, or at one occurrence by its original start */
+CREATE TABLE journals (
+    id TEXT PRIMARY KEY,
+    calendar_id TEXT NOT NULL,
+    entry_date TEXT NOT NULL,
+    title TEXT NOT NULL,
+    body TEXT NOT NULL DEFAULT '',
+    event_id TEXT,
+    original_starts_at TEXT,
+    created_at TEXT NOT NULL,
+    updated_at TEXT NOT NULL,
+    FOREIGN KEY (calendar_id)
+        REFERENCES calendars(id)
+        ON DELETE CASCADE,
+    CHECK (original_starts_at IS NULL OR event_id IS NOT NULL)
+);
+
+CREATE INDEX idx_journals_calendar_date
+    ON journals (calendar_id, entry_date);
+
+CREATE INDEX idx_journals_event
+    ON journals (event_id)
+    WHERE event_id IS NOT NULL;
+
+/* Notes outlive the event they were taken for; only the link goes */
+CREATE TRIGGER trg_events_journals_unlink
+AFTER DELETE ON events
+BEGIN
+    UPDATE journals
+    SET event_id = NULL, original_starts_at = NULL
+    WHERE event_id = OLD.id;
+END;
+
+CREATE TRIGGER trg_recurrences_journals_unlink
+AFTER DELETE ON recurrences
+BEGIN
+    UPDATE journals
+    SET event_id = NULL, original_starts_at = NULL
+    WHERE event_id = OLD.id;
+END;
+
+/* Full-text index over title and body, kept in step by triggers */
+CREATE VIRTUAL TABLE journals_fts USING fts5(
+    title,
+    body,
+    content = 'journals',
+    content_rowid = 'rowid',
+    tokenize = 'unicode61 remove_diacritics 2'
+);
+
+CREATE TRIGGER trg_journals_fts_insert
+AFTER INSERT ON journals
+BEGIN
+    INSERT INTO journals_fts (rowid, title, body)
+    VALUES (NEW.rowid, NEW.title, NEW.body);
+END;
+
+CREATE TRIGGER trg_journals_fts_delete
+AFTER DELETE ON journals
+BEGIN
+    INSERT INTO journals_fts (journals_fts, rowid, title, body)
+    VALUES ('delete', OLD.rowid, OLD.title, OLD.body);
+END;
+
+CREATE TRIGGER trg_journals_fts_update
+AFTER UPDATE OF title, body ON journals
+BEGIN
+    INSERT INTO journals_fts (journals_fts, rowid, title, body)
+    VALUES ('delete', OLD.rowid, OLD.title, OLD.body);
+    INSERT INTO journals_fts (rowid, title, body)
+    VALUES (NEW.rowid, NEW.title, NEW.body);
+END;
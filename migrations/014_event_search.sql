@@ -0,0 +1,106 @@
+/* Full-text index over events and series: title, description, location
+   and attendee names and addresses. One row per event or series, kept
+   in step by triggers on the tables the text comes from */
+CREATE VIRTUAL TABLE event_search USING fts5(
+    event_id UNINDEXED,
+    title,
+    description,
+    location,
+    attendees,
+    tokenize = 'unicode61 remove_diacritics 2'
+);
+
+INSERT INTO event_search (event_id, title, description, location, attendees)
+SELECT e.id, e.title, e.description, e.location,
+       (SELECT group_concat(coalesce(a.name || ' ', '') || a.email, ' ')
+        FROM attendees a WHERE a.event_id = e.id)
+FROM events e
+UNION ALL
+SELECT r.id, r.title, r.description, r.location,
+       (SELECT group_concat(coalesce(a.name || ' ', '') || a.email, ' ')
+        FROM attendees a WHERE a.event_id = r.id)
+FROM recurrences r;
+
+CREATE TRIGGER trg_events_search_insert
+AFTER INSERT ON events
+BEGIN
+    INSERT INTO event_search (event_id, title, description, location, attendees)
+    VALUES (
+        NEW.id, NEW.title, NEW.description, NEW.location,
+        (SELECT group_concat(coalesce(name || ' ', '') || email, ' ')
+         FROM attendees WHERE event_id = NEW.id)
+    );
+END;
+
+CREATE TRIGGER trg_events_search_update
+AFTER UPDATE OF title, description, location ON events
+BEGIN
+    UPDATE event_search
+    SET title = NEW.title, description = NEW.description, location = NEW.location
+    WHERE event_id = NEW.id;
+END;
+
+CREATE TRIGGER trg_events_search_delete
+AFTER DELETE ON events
+BEGIN
+    DELETE FROM event_search WHERE event_id = OLD.id;
+END;
+
+CREATE TRIGGER trg_recurrences_search_insert
+AFTER INSERT ON recurrences
+BEGIN
+    INSERT INTO event_search (event_id, title, description, location, attendees)
+    VALUES (
+        NEW.id, NEW.title, NEW.description, NEW.location,
+        (SELECT group_concat(coalesce(name || ' ', '') || email, ' ')
+         FROM attendees WHERE event_id = NEW.id)
+    );
+END;
+
+CREATE TRIGGER trg_recurrences_search_update
+AFTER UPDATE OF title, description, location ON recurrences
+BEGIN
+    UPDATE event_search
+    SET title = NEW.title, description = NEW.description, location = NEW.location
+    WHERE event_id = NEW.id;
+END;
+
+CREATE TRIGGER trg_recurrences_search_delete
+AFTER DELETE ON recurrences
+BEGIN
+    DELETE FROM event_search WHERE event_id = OLD.id;
+END;
+
+/* Attendees belong to events and series alike */
+CREATE TRIGGER trg_attendees_search_insert
+AFTER INSERT ON attendees
+BEGIN
+    UPDATE event_search
+    SET attendees = (
+        SELECT group_concat(coalesce(name || ' ', '') || email, ' ')
+        FROM attendees WHERE event_id = NEW.event_id
+    )
+    WHERE event_id = NEW.event_id;
+END;
+
+CREATE TRIGGER trg_attendees_search_update
+AFTER UPDATE OF email, name ON attendees
+BEGIN
+    UPDATE event_search
+    SET attendees = (
+        SELECT group_concat(coalesce(name || ' ', '') || email, ' ')
+        FROM attendees WHERE event_id = NEW.event_id
+    )
+    WHERE event_id = NEW.event_id;
+END;
+
+CREATE TRIGGER trg_attendees_search_delete
+AFTER DELETE ON attendees
+BEGIN
+    UPDATE event_search
+    SET attendees = (
+        SELECT group_concat(coalesce(name || ' ', '') || email, ' ')
+        FROM attendees WHERE event_id = OLD.event_id
+    )
+    WHERE event_id = OLD.event_id;
+END;
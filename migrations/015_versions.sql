@@ -0,0 +1,5 @@
+/* Revision counters for optimistic concurrency: a save only lands when
+   the stored version still matches the one the aggregate was loaded at */
+ALTER TABLE calendars ADD COLUMN version INTEGER NOT NULL DEFAULT 1;
+ALTER TABLE events ADD COLUMN version INTEGER NOT NULL DEFAULT 1;
+ALTER TABLE recurrences ADD COLUMN version INTEGER NOT NULL DEFAULT 1;
@@ -0,0 +1,111 @@
+/* Soft delete: a trashed row keeps everything it had until it is
+   restored or purged. Rows trashed along with their calendar share its
+   deletion time */
+ALTER TABLE calendars ADD COLUMN deleted_at TEXT;
+ALTER TABLE events ADD COLUMN deleted_at TEXT;
+ALTER TABLE recurrences ADD COLUMN deleted_at TEXT;
+
+CREATE INDEX idx_calendars_trash ON calendars (deleted_at) WHERE deleted_at IS NOT NULL;
+CREATE INDEX idx_events_trash ON events (deleted_at) WHERE deleted_at IS NOT NULL;
+CREATE INDEX idx_recurrences_trash ON recurrences (deleted_at) WHERE deleted_at IS NOT NULL;
+
+/* Sync sees a trashed item as deleted: writes to it are not tracked, and
+   trashing it on its own leaves a tombstone like a delete would. Items
+   trashed with their calendar are left alone, since the calendar is no
+   longer synced */
+DROP TRIGGER trg_events_sync_update;
+
+CREATE TRIGGER trg_events_sync_update
+AFTER UPDATE ON events
+WHEN NEW.deleted_at IS NULL
+ AND EXISTS (SELECT 1 FROM sync_collections WHERE calendar_id = NEW.calendar_id)
+BEGIN
+    INSERT INTO sync_items (item_id, calendar_id, kind, is_dirty)
+    VALUES (NEW.id, NEW.calendar_id, 'EVENT', 1)
+    ON CONFLICT(item_id) DO UPDATE SET is_dirty = 1;
+END;
+
+CREATE TRIGGER trg_events_sync_trash
+AFTER UPDATE OF deleted_at ON events
+WHEN OLD.deleted_at IS NULL AND NEW.deleted_at IS NOT NULL
+ AND NOT EXISTS (
+    SELECT 1 FROM calendars WHERE id = NEW.calendar_id AND deleted_at IS NOT NULL
+ )
+BEGIN
+    INSERT OR REPLACE INTO sync_tombstones (
+        item_id, calendar_id, kind, remote_href, etag, deleted_at
+    )
+    SELECT item_id, calendar_id, kind, remote_href, etag, NEW.deleted_at
+    FROM sync_items
+    WHERE item_id = OLD.id AND remote_href IS NOT NULL;
+
+    DELETE FROM sync_items WHERE item_id = OLD.id;
+END;
+
+/* Restoring before the deletion was pushed takes the tombstone back */
+CREATE TRIGGER trg_events_sync_restore
+AFTER UPDATE OF deleted_at ON events
+WHEN OLD.deleted_at IS NOT NULL AND NEW.deleted_at IS NULL
+BEGIN
+    INSERT INTO sync_items (item_id, calendar_id, kind, remote_href, etag, is_dirty)
+    SELECT item_id, calendar_id, kind, remote_href, etag, 1
+    FROM sync_tombstones
+    WHERE item_id = NEW.id
+    ON CONFLICT(item_id) DO UPDATE SET
+        remote_href = excluded.remote_href,
+        etag = excluded.etag,
+        is_dirty = 1;
+
+    DELETE FROM sync_tombstones WHERE item_id = NEW.id;
+END;
+
+DROP TRIGGER trg_recurrences_sync_update;
+
+CREATE TRIGGER trg_recurrences_sync_update
+AFTER UPDATE ON recurrences
+WHEN NEW.deleted_at IS NULL
+ AND EXISTS (SELECT 1 FROM sync_collections WHERE calendar_id = NEW.calendar_id)
+BEGIN
+    INSERT INTO sync_items (item_id, calendar_id, kind, is_dirty)
+    VALUES (NEW.id, NEW.calendar_id, 'RECURRENCE', 1)
+    ON CONFLICT(item_id) DO UPDATE SET is_dirty = 1;
+END;
+
+CREATE TRIGGER trg_recurrences_sync_trash
+AFTER UPDATE OF deleted_at ON recurrences
+WHEN OLD.deleted_at IS NULL AND NEW.deleted_at IS NOT NULL
+ AND NOT EXISTS (
+    SELECT 1 FROM calendars WHERE id = NEW.calendar_id AND deleted_at IS NOT NULL
+ )
+BEGIN
+    INSERT OR REPLACE INTO sync_tombstones (
+        item_id, calendar_id, kind, remote_href, etag, deleted_at
+    )
+    SELECT item_id, calendar_id, kind, remote_href, etag, NEW.deleted_at
+    FROM sync_items
+    WHERE item_id = OLD.id AND remote_href IS NOT NULL;
+
+    DELETE FROM sync_items WHERE item_id = OLD.id;
+END;
+
+CREATE TRIGGER trg_recurrences_sync_restore
+AFTER UPDATE OF deleted_at ON recurrences
+WHEN OLD.deleted_at IS NOT NULL AND NEW.deleted_at IS NULL
+BEGIN
+    INSERT INTO sync_items (item_id, calendar_id, kind, remote_href, etag, is_dirty)
+    SELECT item_id, calendar_id, kind, remote_href, etag, 1
+    FROM sync_tombstones
+    WHERE item_id = NEW.id
+    ON CONFLICT(item_id) DO UPDATE SET
+        remote_href = excluded.remote_href,
+        etag = excluded.etag,
+        is_dirty = 1;
+
+    DELETE FROM sync_tombstones WHERE item_id = NEW.id;
+END;
+
+/* Ids of every trashed event and series, for queries that span both */
+CREATE VIEW trashed_objects AS
+SELECT id FROM events WHERE deleted_at IS NOT NULL
+UNION ALL
+SELECT id FROM recurrences WHERE deleted_at IS NOT NULL;
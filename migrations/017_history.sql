@@ -0,0 +1,59 @@
+/* Undo history. Each command that changed something is an entry, a
+   child of the entry that was current when it ran; undo moves the cursor
+   to an entry's parent and redo to the newest child, so a command run
+   after an undo starts a new branch. Entries and changes are never
+   rewritten */
+CREATE TABLE history_entries (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    parent_id INTEGER REFERENCES history_entries(id),
+    command TEXT NOT NULL,
+    recorded_at TEXT NOT NULL
+);
+
+CREATE INDEX idx_history_entries_parent ON history_entries (parent_id);
+
+/* One object's state before and after a command, as the object itself in
+   JSON, or NULL where it did not exist */
+CREATE TABLE history_changes (
+    entry_id INTEGER NOT NULL REFERENCES history_entries(id),
+    position INTEGER NOT NULL,
+    kind TEXT NOT NULL,
+    object_id TEXT NOT NULL,
+    calendar_id TEXT NOT NULL,
+    before_json TEXT,
+    after_json TEXT,
+    PRIMARY KEY (entry_id, position),
+    CHECK (before_json IS NOT NULL OR after_json IS NOT NULL)
+);
+
+CREATE TRIGGER trg_history_entries_no_update
+BEFORE UPDATE ON history_entries
+BEGIN
+    SELECT RAISE(ABORT, 'history entries are append-only');
+END;
+
+CREATE TRIGGER trg_history_entries_no_delete
+BEFORE DELETE ON history_entries
+BEGIN
+    SELECT RAISE(ABORT, 'history entries are append-only');
+END;
+
+CREATE TRIGGER trg_history_changes_no_update
+BEFORE UPDATE ON history_changes
+BEGIN
+    SELECT RAISE(ABORT, 'history changes are append-only');
+END;
+
+CREATE TRIGGER trg_history_changes_no_delete
+BEFORE DELETE ON history_changes
+BEGIN
+    SELECT RAISE(ABORT, 'history changes are append-only');
+END;
+
+/* The last entry applied; NULL before the first */
+CREATE TABLE history_cursor (
+    id INTEGER PRIMARY KEY CHECK (id = 1),
+    entry_id INTEGER REFERENCES history_entries(id)
+);
+
+INSERT INTO history_cursor (id, entry_id) VALUES (1, NULL);
@@ -0,0 +1,50 @@
+/* Audit log. One entry per domain event, naming who set it off and the
+   command they ran. Entries outlive the objects they describe, so ids are
+   not foreign keys, and are never rewritten */
+CREATE TABLE audit_entries (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    recorded_at TEXT NOT NULL,
+    actor TEXT NOT NULL,
+    command TEXT NOT NULL,
+    event TEXT NOT NULL,
+    calendar_id TEXT NOT NULL,
+    object_id TEXT NOT NULL,
+    occurrence TEXT
+);
+
+CREATE INDEX idx_audit_entries_object ON audit_entries (object_id, id);
+CREATE INDEX idx_audit_entries_calendar ON audit_entries (calendar_id, id);
+
+/* The fields an event changed, as text; NULL where a value was empty */
+CREATE TABLE audit_changes (
+    entry_id INTEGER NOT NULL REFERENCES audit_entries(id),
+    position INTEGER NOT NULL,
+    field TEXT NOT NULL,
+    from_value TEXT,
+    to_value TEXT,
+    PRIMARY KEY (entry_id, position)
+);
+
+CREATE TRIGGER trg_audit_entries_no_update
+BEFORE UPDATE ON audit_entries
+BEGIN
+    SELECT RAISE(ABORT, 'audit entries are append-only');
+END;
+
+CREATE TRIGGER trg_audit_entries_no_delete
+BEFORE DELETE ON audit_entries
+BEGIN
+    SELECT RAISE(ABORT, 'audit entries are append-only');
+END;
+
+CREATE TRIGGER trg_audit_changes_no_update
+BEFORE UPDATE ON audit_changes
+BEGIN
+    SELECT RAISE(ABORT, 'audit changes are append-only');
+END;
+
+CREATE TRIGGER trg_audit_changes_no_delete
+BEFORE DELETE ON audit_changes
+BEGIN
+    SELECT RAISE(ABORT, 'audit changes are append-only');
+END;
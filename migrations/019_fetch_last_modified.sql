@@ -0,0 +1,3 @@
+/* Last-Modified of the last subscription fetch, sent back as
+   If-Modified-Since for feeds served without an ETag */
+ALTER TABLE calendars ADD COLUMN fetch_last_modified TEXT;